@@ -5,16 +5,16 @@ use crate::{
         utils::{chain_help, chain_value_parser, SUPPORTED_CHAINS},
         LogArgs,
     },
-    commands::debug_cmd,
-    macros::block_executor,
+    commands::{debug_cmd, follow},
+    macros::{block_executor, evm_config},
     version::{LONG_VERSION, SHORT_VERSION},
 };
 use clap::{value_parser, Parser, Subcommand};
 use reth_chainspec::ChainSpec;
 use reth_cli_commands::{
-    config_cmd, db, dump_genesis, import, init_cmd, init_state,
+    config_cmd, db, dump_genesis, dump_state, import, init_cmd, init_state,
     node::{self, NoArgs},
-    p2p, prune, recover, stage,
+    p2p, prune, recover, stage, static_file,
 };
 use reth_cli_runner::CliRunner;
 use reth_db::DatabaseEnv;
@@ -151,6 +151,7 @@ impl<Ext: clap::Args + fmt::Debug> Cli<Ext> {
             }
             Commands::Init(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::InitState(command) => runner.run_blocking_until_ctrl_c(command.execute()),
+            Commands::DumpState(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Import(command) => runner.run_blocking_until_ctrl_c(
                 command.execute(|chain_spec| block_executor!(chain_spec)),
             ),
@@ -163,13 +164,15 @@ impl<Ext: clap::Args + fmt::Debug> Cli<Ext> {
             Commands::DumpGenesis(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Db(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::Stage(command) => runner.run_command_until_exit(|ctx| {
-                command.execute(ctx, |chain_spec| block_executor!(chain_spec))
+                command.execute(ctx, |chain_spec| block_executor!(chain_spec), evm_config!())
             }),
+            Commands::StaticFile(command) => runner.run_blocking_until_ctrl_c(command.execute()),
             Commands::P2P(command) => runner.run_until_ctrl_c(command.execute()),
             #[cfg(feature = "dev")]
             Commands::TestVectors(command) => runner.run_until_ctrl_c(command.execute()),
             Commands::Config(command) => runner.run_until_ctrl_c(command.execute()),
             Commands::Debug(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
+            Commands::Follow(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
             Commands::Recover(command) => runner.run_command_until_exit(|ctx| command.execute(ctx)),
             Commands::Prune(command) => runner.run_until_ctrl_c(command.execute()),
         }
@@ -197,6 +200,9 @@ pub enum Commands<Ext: clap::Args + fmt::Debug = NoArgs> {
     /// Initialize the database from a state dump file.
     #[command(name = "init-state")]
     InitState(init_state::InitStateCommand),
+    /// Dump the state at a block to a state dump file.
+    #[command(name = "dump-state")]
+    DumpState(dump_state::DumpStateCommand),
     /// This syncs RLP encoded blocks from a file.
     #[command(name = "import")]
     Import(import::ImportCommand),
@@ -216,6 +222,9 @@ pub enum Commands<Ext: clap::Args + fmt::Debug = NoArgs> {
     /// Manipulate individual stages.
     #[command(name = "stage")]
     Stage(stage::Command),
+    /// Static file utilities
+    #[command(name = "static-file")]
+    StaticFile(static_file::Command),
     /// P2P Debugging utilities
     #[command(name = "p2p")]
     P2P(p2p::Command),
@@ -229,6 +238,9 @@ pub enum Commands<Ext: clap::Args + fmt::Debug = NoArgs> {
     /// Various debug routines
     #[command(name = "debug")]
     Debug(debug_cmd::Command),
+    /// Serve RPC from the datadir of a node running in another process
+    #[command(name = "follow")]
+    Follow(follow::Command),
     /// Scripts for node recovery
     #[command(name = "recover")]
     Recover(recover::Command),
@@ -246,6 +246,7 @@ impl Command {
             payload_config,
             Cancelled::default(),
             None,
+            Vec::new(),
         );
 
         #[cfg(feature = "optimism")]
@@ -0,0 +1,120 @@
+//! `reth follow` command. Serves RPC from the datadir of a node running in another process.
+
+use crate::args::RpcServerArgs;
+use clap::Parser;
+use reth_beacon_consensus::EthBeaconConsensus;
+use reth_blockchain_tree::noop::NoopBlockchainTree;
+use reth_cli_commands::common::{AccessRights, Environment, EnvironmentArgs};
+use reth_cli_runner::CliContext;
+use reth_cli_util::parse_duration_from_secs;
+use reth_node_ethereum::{EthEvmConfig, EthExecutorProvider};
+use reth_provider::{providers::BlockchainProvider, ChainSpecProvider};
+use reth_rpc::eth::EthApi;
+use reth_rpc_builder::{
+    config::RethRpcServerConfig, RethRpcModule, RpcModuleBuilder, RpcModuleConfig,
+    RpcModuleSelection, TransportRpcModuleConfig,
+};
+use std::{collections::HashSet, sync::Arc, time::Duration};
+use tokio::sync::broadcast;
+use tracing::*;
+
+/// RPC modules that can't be served without the transaction pool and network of a full node.
+const UNSUPPORTED_MODULES: [RethRpcModule; 2] = [RethRpcModule::Admin, RethRpcModule::Txpool];
+
+/// Capacity of the canonical state notification channel.
+const CANON_STATE_NOTIFICATION_CAPACITY: usize = 256;
+
+/// `reth follow` command
+///
+/// Opens the datadir of a node running in another process as read-only and serves the RPC
+/// namespaces that don't require a transaction pool. The canonical chain is kept in step with the
+/// followed node by polling its database.
+#[derive(Debug, Parser)]
+pub struct Command {
+    #[command(flatten)]
+    env: EnvironmentArgs,
+
+    #[command(flatten)]
+    rpc: RpcServerArgs,
+
+    /// Interval between checks for changes made by the followed node.
+    #[arg(long, value_parser = parse_duration_from_secs, default_value = "1", value_name = "SECONDS")]
+    interval: Duration,
+}
+
+impl Command {
+    /// Execute `follow` command
+    pub async fn execute(self, ctx: CliContext) -> eyre::Result<()> {
+        let Environment { provider_factory, .. } = self.env.init(AccessRights::RO)?;
+        let chain_spec = provider_factory.chain_spec();
+
+        let (canon_state_notification_sender, _) =
+            broadcast::channel(CANON_STATE_NOTIFICATION_CAPACITY);
+        let tree = NoopBlockchainTree::with_canon_state_notifications(
+            canon_state_notification_sender.clone(),
+        );
+        let provider = BlockchainProvider::new(provider_factory, Arc::new(tree))?;
+
+        let mut follower =
+            provider.follower().with_canon_state_notifications(canon_state_notification_sender);
+        let interval = self.interval;
+        ctx.task_executor.spawn_critical_blocking("database follower", async move {
+            loop {
+                match follower.poll() {
+                    Ok(Some(head)) => {
+                        info!(target: "reth::cli", number = head.number, hash = ?head.hash(), "Canonical head updated")
+                    }
+                    Ok(None) => {}
+                    Err(err) => warn!(target: "reth::cli", %err, "Failed to follow the database"),
+                }
+                tokio::time::sleep(interval).await;
+            }
+        });
+
+        let modules = RpcModuleBuilder::default()
+            .with_provider(provider.clone())
+            .with_noop_pool()
+            .with_noop_network()
+            .with_executor(ctx.task_executor.clone())
+            .with_evm_config(EthEvmConfig::default())
+            .with_events(provider)
+            .with_block_executor(EthExecutorProvider::ethereum(chain_spec.clone()))
+            .with_consensus(Arc::new(EthBeaconConsensus::new(chain_spec)))
+            .build(self.module_config(), Box::new(EthApi::with_spawner));
+
+        let handle = self.rpc.rpc_server_config().start(&modules).await?;
+        if let Some(addr) = handle.http_local_addr() {
+            info!(target: "reth::cli", url=%addr, "RPC HTTP server started");
+        }
+        if let Some(addr) = handle.ws_local_addr() {
+            info!(target: "reth::cli", url=%addr, "RPC WS server started");
+        }
+
+        futures::future::pending::<()>().await;
+        Ok(())
+    }
+
+    /// Returns the configured RPC modules without the ones the follower can't serve.
+    fn module_config(&self) -> TransportRpcModuleConfig {
+        let configured = self.rpc.transport_rpc_module_config();
+        let supported = |selection: &RpcModuleSelection| {
+            selection
+                .iter_selection()
+                .filter(|module| !UNSUPPORTED_MODULES.contains(module))
+                .collect::<HashSet<_>>()
+        };
+
+        let mut config = TransportRpcModuleConfig::default()
+            .with_config(RpcModuleConfig::new(self.rpc.eth_config()));
+        if let Some(http) = configured.http() {
+            config = config.with_http(supported(http));
+        }
+        if let Some(ws) = configured.ws() {
+            config = config.with_ws(supported(ws));
+        }
+        if let Some(ipc) = configured.ipc() {
+            config = config.with_ipc(supported(ipc));
+        }
+        config
+    }
+}
@@ -17,4 +17,20 @@ macro_rules! block_executor {
     };
 }
 
+/// Creates the EVM configuration type based on the configured feature.
+#[cfg(not(feature = "optimism"))]
+macro_rules! evm_config {
+    () => {
+        reth_node_ethereum::EthEvmConfig::default()
+    };
+}
+
+#[cfg(feature = "optimism")]
+macro_rules! evm_config {
+    () => {
+        reth_node_optimism::OptimismEvmConfig::default()
+    };
+}
+
 pub(crate) use block_executor;
+pub(crate) use evm_config;
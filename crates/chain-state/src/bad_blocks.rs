@@ -0,0 +1,170 @@
+//! A bounded store of blocks that were rejected as invalid.
+
+use alloy_rlp::{Decodable, Encodable};
+use parking_lot::RwLock;
+use reth_fs_util::FsPathError;
+use reth_primitives::{Block, SealedBlock, B256};
+use std::{collections::VecDeque, path::PathBuf, sync::Arc};
+use tracing::warn;
+
+/// The default maximum number of blocks kept by the [`BadBlockStore`].
+pub const DEFAULT_MAX_BAD_BLOCKS: usize = 10;
+
+/// A bounded store of blocks that were rejected as invalid by the engine.
+///
+/// If the store is backed by a file, the blocks are written to it on every insert, so they are
+/// still available after a restart, e.g. to diagnose a consensus split. Once the store is full,
+/// the oldest block is evicted.
+///
+/// The store is cheap to clone, all clones share the same blocks.
+#[derive(Debug, Clone)]
+pub struct BadBlockStore {
+    inner: Arc<BadBlockStoreInner>,
+}
+
+impl BadBlockStore {
+    /// Creates a new in-memory store that keeps at most `max_blocks` blocks.
+    pub fn new(max_blocks: usize) -> Self {
+        Self::with_blocks(None, max_blocks, VecDeque::new())
+    }
+
+    /// Opens the store that is persisted to the given file, the file is created on the first
+    /// insert.
+    ///
+    /// If the file can't be decoded, it's overwritten on the next insert.
+    pub fn open(path: impl Into<PathBuf>, max_blocks: usize) -> Result<Self, FsPathError> {
+        let path = path.into();
+
+        let mut blocks = VecDeque::new();
+        if path.exists() {
+            let data = reth_fs_util::read(&path)?;
+            match Vec::<Block>::decode(&mut data.as_slice()) {
+                Ok(decoded) => blocks.extend(decoded.into_iter().map(Block::seal_slow)),
+                Err(err) => {
+                    warn!(target: "chain_state::bad_blocks", ?path, %err, "Failed to decode bad blocks")
+                }
+            }
+        }
+
+        Ok(Self::with_blocks(Some(path), max_blocks, blocks))
+    }
+
+    fn with_blocks(
+        path: Option<PathBuf>,
+        max_blocks: usize,
+        mut blocks: VecDeque<SealedBlock>,
+    ) -> Self {
+        // the maximum might have been lowered since the blocks were persisted
+        while blocks.len() > max_blocks {
+            blocks.pop_front();
+        }
+        Self {
+            inner: Arc::new(BadBlockStoreInner { path, max_blocks, blocks: RwLock::new(blocks) }),
+        }
+    }
+
+    /// Inserts a bad block, evicting the oldest block if the store is full.
+    ///
+    /// Does nothing if the block is already in the store.
+    pub fn insert(&self, block: SealedBlock) -> Result<(), FsPathError> {
+        let mut blocks = self.inner.blocks.write();
+        if blocks.iter().any(|existing| existing.hash() == block.hash()) {
+            return Ok(())
+        }
+
+        blocks.push_back(block);
+        while blocks.len() > self.inner.max_blocks {
+            blocks.pop_front();
+        }
+
+        if let Some(path) = &self.inner.path {
+            let mut buf = Vec::new();
+            blocks.iter().map(|block| block.clone().unseal()).collect::<Vec<_>>().encode(&mut buf);
+
+            // write to a temporary file first, so a crash can't leave a partially written file
+            let tmp_path = path.with_extension("tmp");
+            reth_fs_util::write(&tmp_path, buf)?;
+            reth_fs_util::rename(&tmp_path, path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns all bad blocks, the most recently inserted block first.
+    pub fn blocks(&self) -> Vec<SealedBlock> {
+        self.inner.blocks.read().iter().rev().cloned().collect()
+    }
+
+    /// Returns the bad block with the given hash.
+    pub fn get(&self, hash: &B256) -> Option<SealedBlock> {
+        self.inner.blocks.read().iter().find(|block| block.hash() == *hash).cloned()
+    }
+
+    /// Returns the number of bad blocks in the store.
+    pub fn len(&self) -> usize {
+        self.inner.blocks.read().len()
+    }
+
+    /// Returns `true` if the store contains no bad blocks.
+    pub fn is_empty(&self) -> bool {
+        self.inner.blocks.read().is_empty()
+    }
+}
+
+impl Default for BadBlockStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_BAD_BLOCKS)
+    }
+}
+
+#[derive(Debug)]
+struct BadBlockStoreInner {
+    /// The file the blocks are persisted to, if any.
+    path: Option<PathBuf>,
+    /// The maximum number of blocks in the store.
+    max_blocks: usize,
+    /// The blocks, ordered from the oldest to the most recent insert.
+    blocks: RwLock<VecDeque<SealedBlock>>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::Header;
+
+    fn block(number: u64) -> SealedBlock {
+        Block { header: Header { number, ..Default::default() }, ..Default::default() }.seal_slow()
+    }
+
+    #[test]
+    fn evicts_oldest_block() {
+        let store = BadBlockStore::new(2);
+        store.insert(block(1)).unwrap();
+        store.insert(block(2)).unwrap();
+        store.insert(block(2)).unwrap();
+        assert_eq!(store.len(), 2);
+
+        store.insert(block(3)).unwrap();
+        assert_eq!(store.blocks(), vec![block(3), block(2)]);
+        assert!(store.get(&block(1).hash()).is_none());
+    }
+
+    #[test]
+    fn persists_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bad-blocks.rlp");
+
+        let store = BadBlockStore::open(&path, 3).unwrap();
+        assert!(store.is_empty());
+        for number in 1..=3 {
+            store.insert(block(number)).unwrap();
+        }
+
+        let store = BadBlockStore::open(&path, 3).unwrap();
+        assert_eq!(store.blocks(), vec![block(3), block(2), block(1)]);
+
+        // lowering the maximum evicts the oldest blocks
+        let store = BadBlockStore::open(&path, 1).unwrap();
+        assert_eq!(store.blocks(), vec![block(3)]);
+    }
+}
@@ -11,6 +11,9 @@
 mod in_memory;
 pub use in_memory::*;
 
+mod bad_blocks;
+pub use bad_blocks::{BadBlockStore, DEFAULT_MAX_BAD_BLOCKS};
+
 mod chain_info;
 pub use chain_info::ChainInfoTracker;
 
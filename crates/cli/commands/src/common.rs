@@ -1,9 +1,10 @@
 //! Contains common `reth` arguments
 
 use clap::Parser;
+use eyre::WrapErr;
 use reth_beacon_consensus::EthBeaconConsensus;
 use reth_chainspec::ChainSpec;
-use reth_config::{config::EtlConfig, Config};
+use reth_config::{config::EtlConfig, Config, PruneConfig};
 use reth_db::{init_db, open_db_read_only, DatabaseEnv};
 use reth_db_common::init::init_genesis;
 use reth_downloaders::{bodies::noop::NoopBodiesDownloader, headers::noop::NoopHeaderDownloader};
@@ -17,9 +18,13 @@ use reth_node_core::{
 };
 use reth_primitives::B256;
 use reth_provider::{providers::StaticFileProvider, ProviderFactory, StaticFileProviderFactory};
+use reth_prune::PruneModes;
 use reth_stages::{sets::DefaultStages, Pipeline, PipelineTarget};
 use reth_static_file::StaticFileProducer;
-use std::{path::PathBuf, sync::Arc};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use tokio::sync::watch;
 use tracing::{debug, info, warn};
 
@@ -155,6 +160,23 @@ impl EnvironmentArgs {
     }
 }
 
+/// Returns the prune modes configured in the config file at the given path, or the ones of the
+/// given prune config if the file doesn't configure pruning.
+///
+/// Unlike loading the config with `confy`, the file isn't created if it doesn't exist.
+pub fn load_prune_modes(
+    config_path: &Path,
+    default: Option<PruneConfig>,
+) -> eyre::Result<PruneModes> {
+    let config = if config_path.exists() {
+        confy::load_path::<Config>(config_path)
+            .wrap_err_with(|| format!("Could not load config file {config_path:?}"))?
+    } else {
+        Config::default()
+    };
+    Ok(config.prune.or(default).map(|prune| prune.segments).unwrap_or_default())
+}
+
 /// Environment built from [`EnvironmentArgs`].
 #[derive(Debug)]
 pub struct Environment {
@@ -0,0 +1,272 @@
+use crate::common::{AccessRights, Environment, EnvironmentArgs};
+use clap::Parser;
+use reth_chainspec::ChainSpec;
+use reth_config::Config;
+use reth_db::{
+    mdbx::DatabaseArguments, open_db_read_only, tables, version::DB_VERSION_FILE_NAME, DatabaseEnv,
+};
+use reth_db_api::{
+    cursor::DbCursorRO, database::Database, models::ClientVersion, transaction::DbTx,
+};
+use reth_primitives::{BlockNumber, B256};
+use reth_provider::{providers::StaticFileProvider, ProviderFactory, StaticFileProviderFactory};
+use reth_static_file_types::StaticFileSegment;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
+use strum::IntoEnumIterator;
+use tracing::info;
+
+/// Name of the file describing a backup, written once the backup is complete.
+pub(crate) const BACKUP_MANIFEST_FILE_NAME: &str = "backup.json";
+
+/// Name of the MDBX data file in the database directory.
+pub(crate) const MDBX_DATA_FILE_NAME: &str = "mdbx.dat";
+
+/// Name of the reth config file in the datadir.
+pub(crate) const CONFIG_FILE_NAME: &str = "reth.toml";
+
+/// The arguments for the `reth db backup` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The directory to write the backup to. It must not exist or be empty.
+    ///
+    /// The backup has the same layout as a datadir.
+    output: PathBuf,
+
+    /// Omit the free pages of the database and renumber its pages while copying it, which
+    /// produces a smaller backup.
+    #[arg(long)]
+    compact: bool,
+}
+
+impl Command {
+    /// Execute `db backup` command
+    ///
+    /// The database is copied in a single read transaction, so the node can keep running. The
+    /// static files are copied afterwards, and any data the node appended to them since the
+    /// database was copied is then pruned from the backup, so that they end at the same heights as
+    /// the database.
+    pub fn execute(self, env: &EnvironmentArgs) -> eyre::Result<()> {
+        if self.output.exists() {
+            eyre::ensure!(
+                reth_fs_util::read_dir(&self.output)?.next().is_none(),
+                "Backup directory {:?} is not empty",
+                self.output
+            );
+        }
+        let Environment { config, provider_factory, data_dir } = env.init(AccessRights::RO)?;
+        let db_path = self.output.join("db");
+        let static_files_path = self.output.join("static_files");
+        reth_fs_util::create_dir_all(&db_path)?;
+        reth_fs_util::create_dir_all(&static_files_path)?;
+
+        info!(target: "reth::cli", path = ?db_path, compact = self.compact, "Copying database");
+        let start = Instant::now();
+        provider_factory.db_ref().copy(&db_path.join(MDBX_DATA_FILE_NAME), self.compact)?;
+        reth_fs_util::copy(
+            data_dir.db().join(DB_VERSION_FILE_NAME),
+            db_path.join(DB_VERSION_FILE_NAME),
+        )?;
+        info!(target: "reth::cli", elapsed = ?start.elapsed(), "Copied database");
+
+        info!(target: "reth::cli", path = ?static_files_path, "Copying static files");
+        let start = Instant::now();
+        copy_static_files(provider_factory.static_file_provider().directory(), &static_files_path)?;
+        info!(target: "reth::cli", elapsed = ?start.elapsed(), "Copied static files");
+
+        let config_path = env.config.clone().unwrap_or_else(|| data_dir.config());
+        if config_path.exists() {
+            reth_fs_util::copy(config_path, self.output.join(CONFIG_FILE_NAME))?;
+        }
+
+        let manifest = BackupManifest::new(
+            env.chain.genesis_hash(),
+            self.compact,
+            open_consistent(
+                &db_path,
+                &static_files_path,
+                env.chain.clone(),
+                &config,
+                env.db.database_args(),
+            )?,
+        )?;
+        reth_fs_util::write_json_file(&self.output.join(BACKUP_MANIFEST_FILE_NAME), &manifest)?;
+
+        info!(target: "reth::cli", path = ?self.output, highest_static_files = ?manifest.highest_static_files, "Backup complete");
+        Ok(())
+    }
+}
+
+/// Description of a complete backup, used to verify it when it's restored.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct BackupManifest {
+    /// Genesis hash of the chain of the backup.
+    pub(crate) genesis_hash: B256,
+    /// Whether the database was compacted while it was copied.
+    pub(crate) compact: bool,
+    /// Highest block of each static file segment, matching the database.
+    pub(crate) highest_static_files: BTreeMap<StaticFileSegment, BlockNumber>,
+    /// Client versions that opened the database, from [`tables::VersionHistory`].
+    pub(crate) version_history: Vec<ClientVersion>,
+}
+
+impl BackupManifest {
+    fn new(
+        genesis_hash: B256,
+        compact: bool,
+        provider_factory: ProviderFactory<DatabaseEnv>,
+    ) -> eyre::Result<Self> {
+        let version_history = provider_factory
+            .db_ref()
+            .tx()?
+            .cursor_read::<tables::VersionHistory>()?
+            .walk(None)?
+            .map(|entry| entry.map(|(_, version)| version))
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            genesis_hash,
+            compact,
+            highest_static_files: highest_static_files(&provider_factory.static_file_provider()),
+            version_history,
+        })
+    }
+}
+
+/// Returns the highest block of each static file segment that has static files.
+pub(crate) fn highest_static_files(
+    static_file_provider: &StaticFileProvider,
+) -> BTreeMap<StaticFileSegment, BlockNumber> {
+    StaticFileSegment::iter()
+        .filter_map(|segment| {
+            static_file_provider
+                .get_highest_static_file_block(segment)
+                .map(|block| (segment, block))
+        })
+        .collect()
+}
+
+/// Copies the static files from one directory to another.
+///
+/// The static files may be written to while they're copied. A static file writer appends to the
+/// data and offsets files first, and atomically replaces the config file when it commits. The
+/// config files are therefore copied first, so that the copied data and offsets files are never
+/// behind them, and data appended after the commit is truncated when the copy is opened.
+pub(crate) fn copy_static_files(from: &Path, to: &Path) -> eyre::Result<()> {
+    let mut files = Vec::new();
+    for entry in reth_fs_util::read_dir(from)? {
+        let path = entry?.path();
+        let Some(name) = path.file_name().and_then(|name| name.to_str()) else { continue };
+        if !name.starts_with("static_file_") {
+            continue
+        }
+
+        let order = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("conf") => 0,
+            Some("off") => 1,
+            Some("tmp") => continue,
+            _ => 2,
+        };
+        files.push((order, path));
+    }
+    files.sort();
+
+    for (_, path) in files {
+        let name = path.file_name().expect("has a file name");
+        reth_fs_util::copy(&path, to.join(name))?;
+    }
+
+    Ok(())
+}
+
+/// Opens the database read-only and the static files read-write, and prunes the static files that
+/// are ahead of the database.
+///
+/// Fails if the static files are missing data the database expects, since healing it would
+/// require unwinding the database.
+pub(crate) fn open_consistent(
+    db_path: &Path,
+    static_files_path: &Path,
+    chain_spec: Arc<ChainSpec>,
+    config: &Config,
+    db_args: DatabaseArguments,
+) -> eyre::Result<ProviderFactory<DatabaseEnv>> {
+    let db = open_db_read_only(db_path, db_args)?;
+    let static_file_provider = StaticFileProvider::read_write(static_files_path)?;
+    let provider_factory = ProviderFactory::new(db, chain_spec, static_file_provider);
+
+    let has_receipt_pruning = config.prune.as_ref().map_or(false, |a| a.has_receipts_pruning());
+    if let Some(unwind_target) = provider_factory
+        .static_file_provider()
+        .check_consistency(&provider_factory.provider()?, has_receipt_pruning)?
+    {
+        eyre::bail!(
+            "Static files {static_files_path:?} are behind the database, healing them requires an unwind to {unwind_target}"
+        )
+    }
+
+    Ok(provider_factory)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::restore;
+
+    fn env_args(datadir: &Path) -> EnvironmentArgs {
+        EnvironmentArgs::try_parse_from([
+            "reth",
+            "--datadir",
+            datadir.to_str().unwrap(),
+            "--chain",
+            "dev",
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn backup_and_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let node = env_args(&dir.path().join("node"));
+        let Environment { provider_factory, .. } = node.init(AccessRights::RW).unwrap();
+        let expected = highest_static_files(&provider_factory.static_file_provider());
+        assert!(!expected.is_empty());
+        drop(provider_factory);
+
+        let backup_path = dir.path().join("backup");
+        Command::try_parse_from(["backup", backup_path.to_str().unwrap(), "--compact"])
+            .unwrap()
+            .execute(&node)
+            .unwrap();
+        let manifest: BackupManifest =
+            reth_fs_util::read_json_file(&backup_path.join(BACKUP_MANIFEST_FILE_NAME)).unwrap();
+        assert!(manifest.compact);
+        assert_eq!(manifest.highest_static_files, expected);
+        assert!(!manifest.version_history.is_empty());
+
+        // backing up into a non-empty directory fails
+        assert!(Command::try_parse_from(["backup", backup_path.to_str().unwrap()])
+            .unwrap()
+            .execute(&node)
+            .is_err());
+
+        let restored = env_args(&dir.path().join("restored"));
+        restore::Command::try_parse_from(["restore", backup_path.to_str().unwrap()])
+            .unwrap()
+            .execute(&restored)
+            .unwrap();
+        let Environment { provider_factory, .. } = restored.init(AccessRights::RO).unwrap();
+        assert_eq!(highest_static_files(&provider_factory.static_file_provider()), expected);
+
+        // restoring over an existing database fails
+        assert!(restore::Command::try_parse_from(["restore", backup_path.to_str().unwrap()])
+            .unwrap()
+            .execute(&restored)
+            .is_err());
+    }
+}
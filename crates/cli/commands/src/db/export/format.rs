@@ -0,0 +1,313 @@
+//! Output formats of the `reth db export` command.
+
+use super::schema::ExportSchema;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+
+/// File format of the exported data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportFormat {
+    /// Apache Parquet, compressed with zstd.
+    #[cfg(feature = "parquet")]
+    Parquet,
+    /// Comma-separated values with a header row.
+    Csv,
+}
+
+impl Default for ExportFormat {
+    /// Parquet if the `parquet` feature is enabled, CSV otherwise.
+    fn default() -> Self {
+        #[cfg(feature = "parquet")]
+        return Self::Parquet;
+        #[cfg(not(feature = "parquet"))]
+        return Self::Csv;
+    }
+}
+
+impl ExportFormat {
+    /// Returns the file extension of the format.
+    pub(crate) const fn extension(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "parquet")]
+            Self::Parquet => "parquet",
+            Self::Csv => "csv",
+        }
+    }
+
+    /// Writes the rows to the file at the given path, with one column per schema column.
+    ///
+    /// The rows are written to a temporary file first, which is moved to the final path once it's
+    /// complete. This way, an interrupted export never leaves behind a file that looks complete.
+    pub(crate) fn write(&self, path: &Path, columns: &Columns, rows: &[Row]) -> eyre::Result<()> {
+        let tmp_path = path.with_extension(format!("{}.tmp", self.extension()));
+
+        match self {
+            #[cfg(feature = "parquet")]
+            Self::Parquet => parquet_file::write(&tmp_path, columns, rows)?,
+            Self::Csv => write_csv(&tmp_path, columns, rows)?,
+        }
+
+        reth_fs_util::rename(tmp_path, path)?;
+        Ok(())
+    }
+}
+
+/// Type of a column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ColumnKind {
+    Boolean,
+    UInt64,
+    /// Strings, hex encoded bytes and numbers that don't fit into 64 bits, and values without a
+    /// fixed set of fields, such as lists and enums, encoded as JSON.
+    Utf8,
+}
+
+impl ColumnKind {
+    /// Returns the value as a boolean, if it's one.
+    fn boolean(value: &Value) -> Option<bool> {
+        value.as_bool()
+    }
+
+    /// Returns the value as an unsigned integer, if it's a number or a hex encoded quantity.
+    fn uint64(value: &Value) -> Option<u64> {
+        value.as_u64().or_else(|| {
+            value.as_str().and_then(|value| u64::from_str_radix(value.strip_prefix("0x")?, 16).ok())
+        })
+    }
+
+    /// Returns the value as text, without quotes for strings.
+    fn utf8(value: &Value) -> Option<String> {
+        match value {
+            Value::Null => None,
+            Value::String(value) => Some(value.clone()),
+            value => Some(value.to_string()),
+        }
+    }
+
+    /// Returns the text of a CSV cell of the value.
+    fn cell(&self, value: &Value) -> String {
+        match self {
+            Self::Boolean => Self::boolean(value).map(|value| value.to_string()),
+            Self::UInt64 => Self::uint64(value).map(|value| value.to_string()),
+            Self::Utf8 => Self::utf8(value),
+        }
+        .unwrap_or_default()
+    }
+}
+
+/// The columns of exported rows, derived from the types of the exported values.
+#[derive(Debug, Default)]
+pub(crate) struct Columns(Vec<(String, ColumnKind)>);
+
+impl Columns {
+    /// Adds the columns of a value of type `T` with the given name.
+    ///
+    /// Counterpart of [`Row::column`].
+    pub(crate) fn column<T: ExportSchema>(mut self, name: &str) -> Self {
+        T::columns(Some(name), &mut self);
+        self
+    }
+
+    /// Adds one column for every field of type `T`, or a `value` column if it's not an object.
+    ///
+    /// Counterpart of [`Row::fields`].
+    pub(crate) fn fields<T: ExportSchema>(mut self) -> Self {
+        T::columns(None, &mut self);
+        self
+    }
+
+    /// Adds a column with the given path, or a `value` column if there's none.
+    pub(crate) fn push(&mut self, path: Option<&str>, kind: ColumnKind) {
+        self.0.push((path.unwrap_or("value").to_string(), kind));
+    }
+
+    /// Returns the names of the columns.
+    pub(crate) fn names(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|(name, _)| name.as_str())
+    }
+}
+
+/// A row of exported data, as a list of named values.
+///
+/// Values are written to the columns of the [`Columns`] of the exported types. Objects have one
+/// column per field, named after the path of the field, joined by dots.
+#[derive(Debug, Default)]
+pub(crate) struct Row(Vec<(String, Value)>);
+
+impl Row {
+    /// Adds the value with the given name.
+    pub(crate) fn column(mut self, name: &str, value: impl Serialize) -> eyre::Result<Self> {
+        self.0.push((name.to_string(), serde_json::to_value(value)?));
+        Ok(self)
+    }
+
+    /// Adds every field of the value, or the value named `value` if it's not an object.
+    pub(crate) fn fields(mut self, value: impl Serialize) -> eyre::Result<Self> {
+        match serde_json::to_value(value)? {
+            Value::Object(fields) => self.0.extend(fields),
+            value => self.0.push(("value".to_string(), value)),
+        }
+        Ok(self)
+    }
+
+    /// Returns the value of the column with the given path, if it's set.
+    fn get(&self, path: &str) -> Option<&Value> {
+        let mut fields = path.split('.');
+        let name = fields.next()?;
+        let value = self.0.iter().find_map(|(other, value)| (other == name).then_some(value))?;
+        fields.try_fold(value, |value, field| value.get(field)).filter(|value| !value.is_null())
+    }
+}
+
+fn write_csv(path: &Path, columns: &Columns, rows: &[Row]) -> eyre::Result<()> {
+    let mut writer = csv::Writer::from_path(path)?;
+    writer.write_record(columns.names())?;
+    for row in rows {
+        writer.write_record(
+            columns.0.iter().map(|(name, kind)| {
+                row.get(name).map(|value| kind.cell(value)).unwrap_or_default()
+            }),
+        )?;
+    }
+    writer.flush()?;
+
+    Ok(())
+}
+
+#[cfg(feature = "parquet")]
+mod parquet_file {
+    use super::{ColumnKind, Columns, Row};
+    use arrow_array::{ArrayRef, BooleanArray, RecordBatch, StringArray, UInt64Array};
+    use arrow_schema::{DataType, Field, Schema};
+    use parquet::{
+        arrow::ArrowWriter,
+        basic::{Compression, ZstdLevel},
+        file::properties::WriterProperties,
+    };
+    use std::{fs::File, path::Path, sync::Arc};
+
+    impl ColumnKind {
+        const fn data_type(&self) -> DataType {
+            match self {
+                Self::Boolean => DataType::Boolean,
+                Self::UInt64 => DataType::UInt64,
+                Self::Utf8 => DataType::Utf8,
+            }
+        }
+
+        fn to_array(self, name: &str, rows: &[Row]) -> ArrayRef {
+            let values = rows.iter().map(|row| row.get(name));
+            match self {
+                Self::Boolean => Arc::new(
+                    values.map(|value| value.and_then(Self::boolean)).collect::<BooleanArray>(),
+                ),
+                Self::UInt64 => Arc::new(
+                    values.map(|value| value.and_then(Self::uint64)).collect::<UInt64Array>(),
+                ),
+                Self::Utf8 => Arc::new(
+                    values.map(|value| value.and_then(Self::utf8)).collect::<StringArray>(),
+                ),
+            }
+        }
+    }
+
+    /// Writes the rows to a Parquet file with the schema of the columns.
+    pub(super) fn write(path: &Path, columns: &Columns, rows: &[Row]) -> eyre::Result<()> {
+        let schema = Arc::new(Schema::new(
+            columns
+                .0
+                .iter()
+                .map(|(name, kind)| Field::new(name, kind.data_type(), true))
+                .collect::<Vec<_>>(),
+        ));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            columns.0.iter().map(|(name, kind)| kind.to_array(name, rows)).collect::<Vec<_>>(),
+        )?;
+
+        let properties = WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .build();
+        let mut writer = ArrowWriter::try_new(File::create(path)?, schema, Some(properties))?;
+        writer.write(&batch)?;
+        writer.close()?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db_api::models::AccountBeforeTx;
+    use reth_primitives::{Account, Address, B256, U256};
+
+    #[test]
+    fn writes_columns_of_types() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rows.csv");
+
+        let columns = Columns::default().column::<u64>("block_number").fields::<AccountBeforeTx>();
+        let rows = [
+            AccountBeforeTx { address: Address::with_last_byte(1), info: None },
+            AccountBeforeTx {
+                address: Address::with_last_byte(2),
+                info: Some(Account {
+                    nonce: 1,
+                    balance: U256::from(2),
+                    bytecode_hash: Some(B256::with_last_byte(3)),
+                }),
+            },
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(number, account)| {
+            Row::default().column("block_number", number).unwrap().fields(account).unwrap()
+        })
+        .collect::<Vec<_>>();
+        ExportFormat::Csv.write(&path, &columns, &rows).unwrap();
+
+        assert_eq!(
+            reth_fs_util::read_to_string(&path).unwrap(),
+            format!(
+                "block_number,address,info.nonce,info.balance,info.bytecode_hash\n\
+                 0,{},,,\n\
+                 1,{},1,0x2,{}\n",
+                Address::with_last_byte(1),
+                Address::with_last_byte(2),
+                B256::with_last_byte(3),
+            )
+        );
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn writes_parquet_schema_without_rows() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rows.parquet");
+        let columns = Columns::default().column::<u64>("block_number").fields::<AccountBeforeTx>();
+        ExportFormat::Parquet.write(&path, &columns, &[]).unwrap();
+
+        let reader = ::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::try_new(
+            std::fs::File::open(&path).unwrap(),
+        )
+        .unwrap();
+        let schema = reader.schema();
+        let fields = schema
+            .fields()
+            .iter()
+            .map(|field| (field.name().as_str(), field.data_type().clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                ("block_number", arrow_schema::DataType::UInt64),
+                ("address", arrow_schema::DataType::Utf8),
+                ("info.nonce", arrow_schema::DataType::UInt64),
+                ("info.balance", arrow_schema::DataType::Utf8),
+                ("info.bytecode_hash", arrow_schema::DataType::Utf8),
+            ]
+        );
+    }
+}
@@ -0,0 +1,566 @@
+//! `reth db export` command.
+
+use clap::Parser;
+use format::{Columns, ExportFormat, Row};
+use reth_db::{tables, RawKey, RawTable, TableViewer, Tables};
+use reth_db_api::{
+    cursor::DbCursorRO,
+    database::Database,
+    models::{AccountBeforeTx, BlockNumberAddress, StoredBlockBodyIndices},
+    table::Table,
+    transaction::DbTx,
+};
+use reth_db_common::DbTool;
+use reth_primitives::{
+    Address, BlockNumber, Header, Log, Receipt, StorageEntry, TransactionSignedNoHash, TxNumber,
+    B256,
+};
+use reth_provider::{
+    BlockNumReader, HeaderProvider, ReceiptProvider, StaticFileProviderFactory,
+    TransactionsProvider,
+};
+use reth_static_file_types::StaticFileSegment;
+use schema::table_columns;
+use std::{ops::RangeInclusive, path::PathBuf};
+use tracing::info;
+
+mod format;
+mod schema;
+
+/// The arguments for the `reth db export` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    #[command(subcommand)]
+    subcommand: Subcommand,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Subcommand {
+    /// Exports the content of a database table
+    ///
+    /// Tables keyed by block or transaction number are exported over the given range of blocks.
+    /// All other tables can only be exported as a whole.
+    Mdbx {
+        table: Tables,
+
+        #[command(flatten)]
+        args: ExportArgs,
+    },
+    /// Exports the content of a static file segment
+    StaticFile {
+        segment: StaticFileSegment,
+
+        #[command(flatten)]
+        args: ExportArgs,
+    },
+    /// Exports the logs of all receipts, one row per log
+    Logs {
+        #[command(flatten)]
+        args: ExportArgs,
+    },
+}
+
+/// Where and how the data is exported.
+#[derive(Parser, Debug)]
+struct ExportArgs {
+    /// The directory to write the exported files to
+    #[arg(long, short)]
+    output: PathBuf,
+
+    /// The format of the exported files
+    #[arg(long, value_enum, default_value_t = ExportFormat::default())]
+    format: ExportFormat,
+
+    /// The first block to export
+    #[arg(long, default_value_t = 0)]
+    from: BlockNumber,
+
+    /// The last block to export. Defaults to the highest available block.
+    #[arg(long)]
+    to: Option<BlockNumber>,
+
+    /// The number of blocks exported to each file.
+    ///
+    /// Files cover aligned ranges of blocks. An export into a directory that already has files
+    /// resumes after the highest exported block, as long as `--from` is within or right after
+    /// the exported blocks. All rows of a file are held in memory before it's written.
+    #[arg(long, default_value_t = 1_000, value_parser = clap::value_parser!(u64).range(1..))]
+    blocks_per_file: u64,
+
+    /// The number of rows exported to each file, for tables that aren't keyed by block or
+    /// transaction number
+    #[arg(long, default_value_t = 1_000_000, value_parser = clap::value_parser!(u64).range(1..))]
+    rows_per_file: u64,
+}
+
+impl ExportArgs {
+    /// Exports the data of every partition of the block range that wasn't exported yet.
+    ///
+    /// The file of a partition is named after the blocks it covers. If the output directory
+    /// already has files, the export resumes after the highest exported block. Ranges that start
+    /// before the exported blocks or leave a gap after them are rejected.
+    fn export_blocks(
+        &self,
+        name: &str,
+        columns: &Columns,
+        last_block: BlockNumber,
+        mut rows: impl FnMut(RangeInclusive<BlockNumber>) -> eyre::Result<Vec<Row>>,
+    ) -> eyre::Result<()> {
+        let to = self.to.unwrap_or(last_block);
+        eyre::ensure!(self.from <= to, "Nothing to export, the last block is {last_block}");
+        reth_fs_util::create_dir_all(&self.output)?;
+
+        let mut start = self.from;
+        if let Some(exported) = self.exported_blocks(name)? {
+            eyre::ensure!(
+                *exported.start() <= self.from && self.from <= exported.end().saturating_add(1),
+                "Blocks {exported:?} of {name} were already exported to {}, the export must start \
+                 within or right after them",
+                self.output.display()
+            );
+            let Some(next) = exported.end().checked_add(1).filter(|next| *next <= to) else {
+                info!(target: "reth::cli", ?exported, "Blocks are already exported");
+                return Ok(())
+            };
+            start = start.max(next);
+        }
+
+        while start <= to {
+            let end = (start / self.blocks_per_file + 1)
+                .saturating_mul(self.blocks_per_file)
+                .saturating_sub(1)
+                .min(to);
+            let path = self.file(&format!("{name}-{start:010}-{end:010}"));
+            let rows = rows(start..=end)?;
+            self.format.write(&path, columns, &rows)?;
+            info!(target: "reth::cli", ?path, rows = rows.len(), "Exported blocks {start}..={end}");
+
+            let Some(next) = end.checked_add(1) else { break };
+            start = next;
+        }
+
+        Ok(())
+    }
+
+    /// Exports the rows in chunks of [`ExportArgs::rows_per_file`], numbering the files. Files
+    /// that were already exported are skipped.
+    fn export_rows(
+        &self,
+        name: &str,
+        columns: &Columns,
+        mut rows: impl Iterator<Item = eyre::Result<Row>>,
+    ) -> eyre::Result<()> {
+        eyre::ensure!(
+            self.from == 0 && self.to.is_none(),
+            "{name} isn't keyed by block or transaction number, it can only be exported as a whole"
+        );
+        reth_fs_util::create_dir_all(&self.output)?;
+
+        for index in 0.. {
+            let path = self.file(&format!("{name}-{index:06}"));
+            if path.exists() {
+                info!(target: "reth::cli", ?path, "Skipping exported file");
+                if rows.by_ref().take(self.rows_per_file as usize).count() == 0 {
+                    break
+                }
+                continue
+            }
+
+            let chunk = rows
+                .by_ref()
+                .take(self.rows_per_file as usize)
+                .collect::<eyre::Result<Vec<_>>>()?;
+            if chunk.is_empty() {
+                break
+            }
+            self.format.write(&path, columns, &chunk)?;
+            info!(target: "reth::cli", ?path, rows = chunk.len(), "Exported rows");
+        }
+
+        Ok(())
+    }
+
+    /// Returns the path of the exported file with the given name.
+    fn file(&self, name: &str) -> PathBuf {
+        self.output.join(format!("{name}.{}", self.format.extension()))
+    }
+
+    /// Returns the range of blocks covered by the exported files of the given name, removing
+    /// unfinished temporary files.
+    ///
+    /// Returns an error if the files don't cover a contiguous range of blocks.
+    fn exported_blocks(&self, name: &str) -> eyre::Result<Option<RangeInclusive<BlockNumber>>> {
+        let prefix = format!("{name}-");
+        let extension = format!(".{}", self.format.extension());
+        let tmp_extension = format!("{extension}.tmp");
+
+        let mut ranges = Vec::new();
+        for entry in reth_fs_util::read_dir(&self.output)? {
+            let path = entry?.path();
+            let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else { continue };
+            let Some(suffix) = file_name.strip_prefix(&prefix) else { continue };
+
+            if suffix.ends_with(&tmp_extension) {
+                reth_fs_util::remove_file(&path)?;
+            } else if let Some(range) = suffix.strip_suffix(&extension).and_then(parse_block_range)
+            {
+                ranges.push(range);
+            }
+        }
+
+        ranges.sort_unstable_by_key(|range| *range.start());
+        for window in ranges.windows(2) {
+            eyre::ensure!(
+                window[0].end().checked_add(1) == Some(*window[1].start()),
+                "Exported files of {name} in {} don't cover contiguous blocks: {:?} is followed \
+                 by {:?}",
+                self.output.display(),
+                window[0],
+                window[1]
+            );
+        }
+
+        Ok(ranges.first().zip(ranges.last()).map(|(first, last)| *first.start()..=*last.end()))
+    }
+}
+
+/// Parses the `{start:010}-{end:010}` block range of an exported file name.
+fn parse_block_range(range: &str) -> Option<RangeInclusive<BlockNumber>> {
+    let (start, end) = range.split_once('-')?;
+    let parse = |number: &str| {
+        (number.len() >= 10 && number.bytes().all(|byte| byte.is_ascii_digit()))
+            .then(|| number.parse().ok())
+            .flatten()
+    };
+    let (start, end) = (parse(start)?, parse(end)?);
+    (start <= end).then_some(start..=end)
+}
+
+impl Command {
+    /// Execute `db export` command
+    pub fn execute<DB: Database>(self, tool: &DbTool<DB>) -> eyre::Result<()> {
+        match self.subcommand {
+            Subcommand::Mdbx { table, args } => {
+                table.view(&ExportTableViewer { tool, table, args: &args })?
+            }
+            Subcommand::StaticFile { segment, args } => export_segment(tool, segment, &args)?,
+            Subcommand::Logs { args } => export_logs(tool, &args)?,
+        }
+
+        Ok(())
+    }
+}
+
+/// Number the keys of a table start with.
+enum KeyKind {
+    Block,
+    Transaction,
+}
+
+impl KeyKind {
+    const fn of(table: Tables) -> Option<Self> {
+        match table {
+            Tables::CanonicalHeaders |
+            Tables::HeaderTerminalDifficulties |
+            Tables::Headers |
+            Tables::BlockBodyIndices |
+            Tables::BlockOmmers |
+            Tables::BlockWithdrawals |
+            Tables::BlockRequests |
+            Tables::AccountChangeSets |
+            Tables::StorageChangeSets => Some(Self::Block),
+            Tables::Transactions |
+            Tables::TransactionBlocks |
+            Tables::Receipts |
+            Tables::TransactionSenders => Some(Self::Transaction),
+            _ => None,
+        }
+    }
+}
+
+struct ExportTableViewer<'a, DB: Database> {
+    tool: &'a DbTool<DB>,
+    table: Tables,
+    args: &'a ExportArgs,
+}
+
+impl<DB: Database> TableViewer<()> for ExportTableViewer<'_, DB> {
+    type Error = eyre::Report;
+
+    fn view<T: Table>(&self) -> Result<(), Self::Error> {
+        let provider =
+            self.tool.provider_factory.provider()?.disable_long_read_transaction_safety();
+        let tx = provider.tx_ref();
+        let columns = table_columns(self.table);
+
+        match KeyKind::of(self.table) {
+            Some(KeyKind::Block) => self.args.export_blocks(
+                T::NAME,
+                &columns,
+                provider.last_block_number()?,
+                |blocks| table_rows::<T>(tx, blocks),
+            ),
+            Some(KeyKind::Transaction) => self.args.export_blocks(
+                T::NAME,
+                &columns,
+                provider.last_block_number()?,
+                |blocks| {
+                    let bodies = block_bodies(provider.tx_ref(), blocks)?;
+                    match transactions(&bodies) {
+                        Some(transactions) => table_rows::<T>(tx, transactions),
+                        None => Ok(Vec::new()),
+                    }
+                },
+            ),
+            None => {
+                let mut cursor = tx.cursor_read::<RawTable<T>>()?;
+                let rows = cursor.walk(None)?.map(|entry| {
+                    let (key, value) = entry?;
+                    Row::default().column("key", key.key()?)?.fields(value.value()?)
+                });
+                self.args.export_rows(T::NAME, &columns, rows)
+            }
+        }
+    }
+}
+
+/// Returns the rows of the table whose keys start with a number in the range.
+fn table_rows<T: Table>(tx: &impl DbTx, range: RangeInclusive<u64>) -> eyre::Result<Vec<Row>> {
+    let mut cursor = tx.cursor_read::<RawTable<T>>()?;
+    let start = RawKey::from_vec(range.start().to_be_bytes().to_vec());
+
+    let mut rows = Vec::new();
+    for entry in cursor.walk(Some(start))? {
+        let (key, value) = entry?;
+        let number = key.raw_key().first_chunk().copied().map(u64::from_be_bytes);
+        if number.map_or(true, |number| number > *range.end()) {
+            break
+        }
+        rows.push(Row::default().column("key", key.key()?)?.fields(value.value()?)?);
+    }
+
+    Ok(rows)
+}
+
+/// Returns the body indices of the blocks in the range.
+fn block_bodies(
+    tx: &impl DbTx,
+    range: RangeInclusive<BlockNumber>,
+) -> eyre::Result<Vec<(BlockNumber, StoredBlockBodyIndices)>> {
+    Ok(tx
+        .cursor_read::<tables::BlockBodyIndices>()?
+        .walk_range(range)?
+        .collect::<Result<Vec<_>, _>>()?)
+}
+
+/// Returns the range of transactions of the blocks, or `None` if they have no transactions.
+fn transactions(
+    bodies: &[(BlockNumber, StoredBlockBodyIndices)],
+) -> Option<RangeInclusive<TxNumber>> {
+    let first = bodies.first()?.1.first_tx_num();
+    let next = bodies.last()?.1.next_tx_num();
+    (next > first).then(|| first..=next - 1)
+}
+
+/// Pairs each item of a block range's transactions with its block and transaction number.
+fn with_transaction_numbers<'a, T: 'a>(
+    bodies: &'a [(BlockNumber, StoredBlockBodyIndices)],
+    items: Vec<T>,
+) -> impl Iterator<Item = (BlockNumber, TxNumber, T)> + 'a {
+    bodies
+        .iter()
+        .flat_map(|(block, body)| body.tx_num_range().map(move |tx_number| (*block, tx_number)))
+        .zip(items)
+        .map(|((block, tx_number), item)| (block, tx_number, item))
+}
+
+fn export_segment<DB: Database>(
+    tool: &DbTool<DB>,
+    segment: StaticFileSegment,
+    args: &ExportArgs,
+) -> eyre::Result<()> {
+    let provider = tool.provider_factory.provider()?.disable_long_read_transaction_safety();
+    let static_file_provider = tool.provider_factory.static_file_provider();
+    let last_block = static_file_provider
+        .get_highest_static_file_block(segment)
+        .ok_or_else(|| eyre::eyre!("No static files for the {segment} segment"))?;
+
+    let columns = match segment {
+        StaticFileSegment::Headers => Columns::default()
+            .column::<u64>("block_number")
+            .column::<B256>("hash")
+            .fields::<Header>(),
+        StaticFileSegment::Transactions => Columns::default()
+            .column::<u64>("block_number")
+            .column::<u64>("tx_number")
+            .column::<B256>("hash")
+            .fields::<TransactionSignedNoHash>(),
+        StaticFileSegment::Receipts => Columns::default()
+            .column::<u64>("block_number")
+            .column::<u64>("tx_number")
+            .fields::<Receipt>(),
+        StaticFileSegment::AccountChangeSets => {
+            Columns::default().column::<u64>("block_number").fields::<AccountBeforeTx>()
+        }
+        StaticFileSegment::StorageChangeSets => Columns::default()
+            .column::<u64>("block_number")
+            .column::<Address>("address")
+            .fields::<StorageEntry>(),
+    };
+
+    args.export_blocks(segment.as_str(), &columns, last_block, |blocks| match segment {
+        StaticFileSegment::Headers => static_file_provider
+            .sealed_headers_range(blocks)?
+            .into_iter()
+            .map(|header| {
+                Row::default()
+                    .column("block_number", header.number)?
+                    .column("hash", header.hash())?
+                    .fields(header.header())
+            })
+            .collect(),
+        StaticFileSegment::Transactions => {
+            let bodies = block_bodies(provider.tx_ref(), blocks)?;
+            let Some(range) = transactions(&bodies) else { return Ok(Vec::new()) };
+            let transactions = static_file_provider.transactions_by_tx_range(range)?;
+            with_transaction_numbers(&bodies, transactions)
+                .map(|(block, tx_number, transaction)| {
+                    Row::default()
+                        .column("block_number", block)?
+                        .column("tx_number", tx_number)?
+                        .column("hash", transaction.hash())?
+                        .fields(transaction)
+                })
+                .collect()
+        }
+        StaticFileSegment::Receipts => {
+            let bodies = block_bodies(provider.tx_ref(), blocks)?;
+            let Some(range) = transactions(&bodies) else { return Ok(Vec::new()) };
+            let receipts = static_file_provider.receipts_by_tx_range(range)?;
+            with_transaction_numbers(&bodies, receipts)
+                .map(|(block, tx_number, receipt)| {
+                    Row::default()
+                        .column("block_number", block)?
+                        .column("tx_number", tx_number)?
+                        .fields(receipt)
+                })
+                .collect()
+        }
+        StaticFileSegment::AccountChangeSets => static_file_provider
+            .account_changesets_range(blocks)?
+            .into_iter()
+            .map(|(block, account)| Row::default().column("block_number", block)?.fields(account))
+            .collect(),
+        StaticFileSegment::StorageChangeSets => static_file_provider
+            .storage_changesets_range(blocks)?
+            .into_iter()
+            .map(|(BlockNumberAddress((block, address)), entry)| {
+                Row::default()
+                    .column("block_number", block)?
+                    .column("address", address)?
+                    .fields(entry)
+            })
+            .collect(),
+    })
+}
+
+fn export_logs<DB: Database>(tool: &DbTool<DB>, args: &ExportArgs) -> eyre::Result<()> {
+    let provider = tool.provider_factory.provider()?.disable_long_read_transaction_safety();
+
+    let columns = Columns::default()
+        .column::<u64>("block_number")
+        .column::<u64>("tx_number")
+        .column::<u64>("log_index")
+        .fields::<Log>();
+
+    args.export_blocks("logs", &columns, provider.last_block_number()?, |blocks| {
+        let bodies = block_bodies(provider.tx_ref(), blocks)?;
+        let Some(range) = transactions(&bodies) else { return Ok(Vec::new()) };
+        let receipts = provider.receipts_by_tx_range(range)?;
+
+        let mut rows = Vec::new();
+        let mut log_index = 0;
+        let mut current_block = None;
+        for (block, tx_number, receipt) in with_transaction_numbers(&bodies, receipts) {
+            if current_block != Some(block) {
+                current_block = Some(block);
+                log_index = 0;
+            }
+            for log in receipt.logs {
+                rows.push(
+                    Row::default()
+                        .column("block_number", block)?
+                        .column("tx_number", tx_number)?
+                        .column("log_index", log_index)?
+                        .fields(log)?,
+                );
+                log_index += 1;
+            }
+        }
+
+        Ok(rows)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resumes_block_partitions() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut args = ExportArgs::parse_from([
+            "export",
+            "--output",
+            dir.path().to_str().unwrap(),
+            "--format",
+            "csv",
+            "--from",
+            "5",
+            "--blocks-per-file",
+            "10",
+        ]);
+
+        let columns = Columns::default().column::<u64>("block_number");
+        let export = |args: &ExportArgs, last_block| {
+            let mut exported = Vec::new();
+            args.export_blocks("headers", &columns, last_block, |blocks| {
+                exported.push(blocks.clone());
+                blocks.map(|block| Row::default().column("block_number", block)).collect()
+            })
+            .map(|_| exported)
+        };
+
+        assert_eq!(export(&args, 25).unwrap(), vec![5..=9, 10..=19, 20..=25]);
+        assert_eq!(export(&args, 25).unwrap(), vec![]);
+
+        // Once the chain grew, the export resumes after the highest exported block.
+        std::fs::write(dir.path().join("headers-0000000026-0000000029.csv.tmp"), "").unwrap();
+        assert_eq!(export(&args, 32).unwrap(), vec![26..=29, 30..=32]);
+        assert!(!dir.path().join("headers-0000000026-0000000029.csv.tmp").exists());
+        assert_eq!(
+            reth_fs_util::read_to_string(dir.path().join("headers-0000000026-0000000029.csv"))
+                .unwrap(),
+            "block_number\n26\n27\n28\n29\n"
+        );
+
+        // Ranges ending within the exported blocks have nothing left to export.
+        args.to = Some(22);
+        assert_eq!(export(&args, 32).unwrap(), vec![]);
+
+        // Ranges starting before the exported blocks or after the next block are rejected.
+        args.to = None;
+        args.from = 0;
+        assert!(export(&args, 50).is_err());
+        args.from = 40;
+        assert!(export(&args, 50).is_err());
+
+        // Exports can be extended from any block within the exported ones.
+        args.from = 10;
+        assert_eq!(export(&args, 35).unwrap(), vec![33..=35]);
+
+        // Files that don't cover contiguous blocks are rejected.
+        reth_fs_util::remove_file(dir.path().join("headers-0000000010-0000000019.csv")).unwrap();
+        assert!(export(&args, 40).is_err());
+    }
+}
@@ -0,0 +1,271 @@
+//! Columns of the types exported by the `reth db export` command.
+
+use super::format::{ColumnKind, Columns};
+use reth_db::{
+    tables::{self, ChainStateKey},
+    BlockNumberList, Tables,
+};
+use reth_db_api::{
+    models::{
+        storage_sharded_key::StorageShardedKey, AccountBeforeTx, BlockNumberAddress, ClientVersion,
+        CompactU256, ShardedKey, StoredBlockBodyIndices, StoredBlockOmmers, StoredBlockWithdrawals,
+    },
+    table::Table,
+};
+use reth_primitives::{
+    Account, Address, Bloom, Bytecode, Bytes, Header, Log, Receipt, Requests, Signature,
+    StorageEntry, Transaction, TransactionSignedNoHash, TxType, Withdrawals, B256, U256,
+};
+use reth_prune::{PruneCheckpoint, PruneMode, PruneSegment};
+use reth_stages::{StageCheckpoint, StageUnitCheckpoint};
+use reth_trie::{
+    BranchNodeCompact, StorageTrieEntry, StoredNibbles, StoredNibblesSubKey, TrieMask,
+};
+
+/// A type with a fixed set of columns it's exported to.
+///
+/// The columns mirror the serialized representation of the type: objects have one column per
+/// field, named after the path of the field joined by dots, and all other values have a single
+/// column.
+pub(crate) trait ExportSchema {
+    /// Adds the columns of the type to `columns`, named after the given path. A single column
+    /// without a path is named `value`.
+    fn columns(path: Option<&str>, columns: &mut Columns);
+}
+
+impl<T: ExportSchema> ExportSchema for Option<T> {
+    fn columns(path: Option<&str>, columns: &mut Columns) {
+        T::columns(path, columns)
+    }
+}
+
+impl<T> ExportSchema for Vec<T> {
+    fn columns(path: Option<&str>, columns: &mut Columns) {
+        columns.push(path, ColumnKind::Utf8)
+    }
+}
+
+/// Implements [`ExportSchema`] for types exported to a single column of the given kind.
+macro_rules! scalar_schema {
+    ($kind:ident: $($ty:ty),+ $(,)?) => {
+        $(
+            impl ExportSchema for $ty {
+                fn columns(path: Option<&str>, columns: &mut Columns) {
+                    columns.push(path, ColumnKind::$kind)
+                }
+            }
+        )+
+    };
+}
+
+scalar_schema!(Boolean: bool);
+scalar_schema!(UInt64: u64, TrieMask);
+scalar_schema!(
+    Utf8: String,
+    Address,
+    B256,
+    U256,
+    CompactU256,
+    Bloom,
+    Bytes,
+    TxType,
+    ChainStateKey,
+    PruneSegment,
+    PruneMode,
+    StoredNibbles,
+    StoredNibblesSubKey,
+    BlockNumberAddress,
+    BlockNumberList,
+    // Enums and lists, encoded as JSON
+    Transaction,
+    Bytecode,
+    Withdrawals,
+    Requests,
+    StageUnitCheckpoint,
+);
+
+/// Implements [`ExportSchema`] for objects, with the columns of each of their fields.
+macro_rules! object_schema {
+    ($($ty:ty { $($field:ident: $field_ty:ty),+ $(,)? })+) => {
+        $(
+            impl ExportSchema for $ty {
+                fn columns(path: Option<&str>, columns: &mut Columns) {
+                    $(
+                        <$field_ty as ExportSchema>::columns(
+                            Some(&path.map_or_else(
+                                || stringify!($field).to_string(),
+                                |path| format!("{path}.{}", stringify!($field)),
+                            )),
+                            columns,
+                        );
+                    )+
+                }
+            }
+        )+
+    };
+}
+
+object_schema! {
+    Header {
+        parent_hash: B256,
+        ommers_hash: B256,
+        beneficiary: Address,
+        state_root: B256,
+        transactions_root: B256,
+        receipts_root: B256,
+        withdrawals_root: Option<B256>,
+        logs_bloom: Bloom,
+        difficulty: U256,
+        number: u64,
+        gas_limit: u64,
+        gas_used: u64,
+        timestamp: u64,
+        mix_hash: B256,
+        nonce: u64,
+        base_fee_per_gas: Option<u64>,
+        blob_gas_used: Option<u64>,
+        excess_blob_gas: Option<u64>,
+        parent_beacon_block_root: Option<B256>,
+        requests_root: Option<B256>,
+        extra_data: Bytes,
+    }
+    StoredBlockBodyIndices { first_tx_num: u64, tx_count: u64 }
+    StoredBlockOmmers { ommers: Vec<Header> }
+    StoredBlockWithdrawals { withdrawals: Withdrawals }
+    Signature { r: U256, s: U256, odd_y_parity: bool }
+    TransactionSignedNoHash { signature: Signature, transaction: Transaction }
+    Log { address: Address, topics: Vec<B256>, data: Bytes }
+    Receipt { tx_type: TxType, success: bool, cumulative_gas_used: u64, logs: Vec<Log> }
+    Account { nonce: u64, balance: U256, bytecode_hash: Option<B256> }
+    StorageEntry { key: B256, value: U256 }
+    AccountBeforeTx { address: Address, info: Option<Account> }
+    ShardedKey<Address> { key: Address, highest_block_number: u64 }
+    ShardedKey<B256> { key: B256, highest_block_number: u64 }
+    StorageShardedKey { address: Address, sharded_key: ShardedKey<B256> }
+    BranchNodeCompact {
+        state_mask: TrieMask,
+        tree_mask: TrieMask,
+        hash_mask: TrieMask,
+        hashes: Vec<B256>,
+        root_hash: Option<B256>,
+    }
+    StorageTrieEntry { nibbles: StoredNibblesSubKey, node: BranchNodeCompact }
+    StageCheckpoint { block_number: u64, stage_checkpoint: Option<StageUnitCheckpoint> }
+    PruneCheckpoint { block_number: Option<u64>, tx_number: Option<u64>, prune_mode: PruneMode }
+    ClientVersion { version: String, git_sha: String, build_timestamp: String }
+}
+
+/// Returns the columns of the table's key, named `key`, and of the fields of its value.
+fn columns_of<T: Table>() -> Columns
+where
+    T::Key: ExportSchema,
+    T::Value: ExportSchema,
+{
+    Columns::default().column::<T::Key>("key").fields::<T::Value>()
+}
+
+/// Returns the columns of the rows exported from the table.
+pub(crate) fn table_columns(table: Tables) -> Columns {
+    macro_rules! table_columns {
+        ($($name:ident),+ $(,)?) => {
+            match table {
+                $(Tables::$name => columns_of::<tables::$name>(),)+
+            }
+        };
+    }
+
+    table_columns!(
+        CanonicalHeaders,
+        HeaderTerminalDifficulties,
+        HeaderNumbers,
+        Headers,
+        BlockBodyIndices,
+        BlockOmmers,
+        BlockWithdrawals,
+        Transactions,
+        TransactionHashNumbers,
+        TransactionBlocks,
+        Receipts,
+        Bytecodes,
+        PlainAccountState,
+        PlainStorageState,
+        AccountsHistory,
+        StoragesHistory,
+        AccountChangeSets,
+        StorageChangeSets,
+        HashedAccounts,
+        HashedStorages,
+        AccountsTrie,
+        StoragesTrie,
+        TransactionSenders,
+        StageCheckpoints,
+        StageCheckpointProgresses,
+        PruneCheckpoints,
+        VersionHistory,
+        BlockRequests,
+        ChainState,
+        AddressAppearances,
+        TransactionAddressAppearances,
+        MigrationCheckpoints,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+    use serde_json::Value;
+
+    /// Asserts that every column of the type is a field of the serialized value, and that the
+    /// columns cover every serialized field.
+    fn assert_columns<T: ExportSchema + Serialize>(value: T) {
+        let value = serde_json::to_value(value).unwrap();
+        let columns = Columns::default().fields::<T>();
+        let names = columns.names().collect::<Vec<_>>();
+
+        for name in &names {
+            let pointer = format!("/{}", name.replace('.', "/"));
+            let column = if value.is_object() { value.pointer(&pointer) } else { Some(&value) };
+            assert!(column.is_some(), "{name} isn't a field of {value}");
+        }
+
+        let mut fields =
+            names.iter().map(|name| name.split('.').next().unwrap()).collect::<Vec<_>>();
+        fields.dedup();
+        match value {
+            Value::Object(object) => {
+                assert_eq!(fields, object.keys().map(String::as_str).collect::<Vec<_>>())
+            }
+            _ => assert_eq!(fields, ["value"]),
+        }
+    }
+
+    #[test]
+    fn columns_match_serialized_fields() {
+        assert_columns(Header {
+            withdrawals_root: Some(B256::ZERO),
+            base_fee_per_gas: Some(1),
+            blob_gas_used: Some(1),
+            excess_blob_gas: Some(1),
+            parent_beacon_block_root: Some(B256::ZERO),
+            requests_root: Some(B256::ZERO),
+            ..Default::default()
+        });
+        assert_columns(StoredBlockBodyIndices::default());
+        assert_columns(TransactionSignedNoHash::default());
+        assert_columns(Receipt::default());
+        assert_columns(Log::default());
+        assert_columns(AccountBeforeTx { address: Address::ZERO, info: Some(Account::default()) });
+        assert_columns(StorageEntry::default());
+        assert_columns(StorageShardedKey::default());
+        assert_columns(BranchNodeCompact::default());
+        assert_columns(StageCheckpoint::default());
+        assert_columns(PruneCheckpoint {
+            block_number: Some(1),
+            tx_number: Some(1),
+            prune_mode: PruneMode::Full,
+        });
+        assert_columns(ClientVersion::default());
+        assert_columns(B256::ZERO);
+    }
+}
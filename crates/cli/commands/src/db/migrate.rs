@@ -0,0 +1,49 @@
+use crate::common::{load_prune_modes, EnvironmentArgs};
+use clap::Parser;
+use reth_db::{open_db_for_migration, open_db_read_only};
+use reth_db_common::migration::Migrator;
+use reth_provider::{providers::StaticFileProvider, ProviderFactory};
+
+/// The arguments for the `reth db migrate` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// Only list the pending migrations without running them.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+impl Command {
+    /// Execute `db migrate` command
+    ///
+    /// Opens the database without checking its version, creates the tables it's missing and
+    /// upgrades it to the latest version. A dry run opens the database read-only.
+    pub fn execute(self, env: &EnvironmentArgs) -> eyre::Result<()> {
+        let data_dir = env.datadir.clone().resolve_datadir(env.chain.chain);
+        let db_path = data_dir.db();
+        let static_files_path = data_dir.static_files();
+        let prune_modes =
+            load_prune_modes(&env.config.clone().unwrap_or_else(|| data_dir.config()), None)?;
+
+        // The tables added since the database was written are created before migrating. A dry run
+        // doesn't write anything, so it opens the database read-only.
+        if self.dry_run {
+            let provider_factory = ProviderFactory::new(
+                open_db_read_only(&db_path, env.db.database_args())?,
+                env.chain.clone(),
+                StaticFileProvider::read_only(static_files_path)?,
+            )
+            .with_prune_modes(prune_modes);
+            Migrator::new(provider_factory, db_path).run(true)?;
+        } else {
+            let provider_factory = ProviderFactory::new(
+                open_db_for_migration(&db_path, env.db.database_args())?,
+                env.chain.clone(),
+                StaticFileProvider::read_write(static_files_path)?,
+            )
+            .with_prune_modes(prune_modes);
+            Migrator::new(provider_factory, db_path).run(false)?;
+        }
+
+        Ok(())
+    }
+}
@@ -4,14 +4,19 @@ use reth_db::version::{get_db_version, DatabaseVersionError, DB_VERSION};
 use reth_db_common::DbTool;
 use std::io::{self, Write};
 
+mod backup;
 mod checksum;
 mod clear;
 mod diff;
+mod export;
 mod get;
 mod list;
+mod migrate;
+mod restore;
 mod stats;
 /// DB List TUI
 mod tui;
+mod verify_static_files;
 
 /// `reth db` command
 #[derive(Debug, Parser)]
@@ -36,6 +41,14 @@ pub enum Subcommands {
     Diff(diff::Command),
     /// Gets the content of a table for the given key
     Get(get::Command),
+    /// Exports a table or static file segment to Parquet or CSV files
+    Export(export::Command),
+    /// Writes a consistent backup of the database, static files and config while the node runs
+    Backup(backup::Command),
+    /// Restores a backup written by `reth db backup` into an empty datadir
+    Restore(restore::Command),
+    /// Verifies the checksums of the static files, and optionally truncates corrupted ones
+    VerifyStaticFiles(verify_static_files::Command),
     /// Deletes all database entries
     Drop {
         /// Bypasses the interactive confirmation and drops the database directly
@@ -46,6 +59,8 @@ pub enum Subcommands {
     Clear(clear::Command),
     /// Lists current and local database versions
     Version,
+    /// Runs the pending migrations of the database to the current database version
+    Migrate(migrate::Command),
     /// Returns the full database path
     Path,
 }
@@ -63,6 +78,11 @@ macro_rules! db_ro_exec {
 impl Command {
     /// Execute `db` command
     pub async fn execute(self) -> eyre::Result<()> {
+        // restoring a backup creates the datadir
+        if let Subcommands::Restore(command) = self.command {
+            return command.execute(&self.env)
+        }
+
         let data_dir = self.env.datadir.clone().resolve_datadir(self.env.chain.chain);
         let db_path = data_dir.db();
         let static_files_path = data_dir.static_files();
@@ -104,6 +124,18 @@ impl Command {
                     command.execute(&tool)?;
                 });
             }
+            Subcommands::Export(command) => {
+                db_ro_exec!(self.env, tool, {
+                    command.execute(&tool)?;
+                });
+            }
+            Subcommands::Backup(command) => {
+                command.execute(&self.env)?;
+            }
+            Subcommands::Restore(_) => unreachable!("handled above"),
+            Subcommands::VerifyStaticFiles(command) => {
+                command.execute(&self.env)?;
+            }
             Subcommands::Drop { force } => {
                 if !force {
                     // Ask for confirmation
@@ -143,6 +175,9 @@ impl Command {
                     println!("Local database is uninitialized");
                 }
             }
+            Subcommands::Migrate(command) => {
+                command.execute(&self.env)?;
+            }
             Subcommands::Path => {
                 println!("{}", db_path.display());
             }
@@ -0,0 +1,91 @@
+use super::backup::{
+    copy_static_files, highest_static_files, open_consistent, BackupManifest,
+    BACKUP_MANIFEST_FILE_NAME, CONFIG_FILE_NAME, MDBX_DATA_FILE_NAME,
+};
+use crate::common::EnvironmentArgs;
+use clap::Parser;
+use reth_config::Config;
+use reth_db::version::DB_VERSION_FILE_NAME;
+use reth_provider::StaticFileProviderFactory;
+use std::path::PathBuf;
+use tracing::{info, warn};
+
+/// The arguments for the `reth db restore` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The directory of the backup, as written by `reth db backup`.
+    backup: PathBuf,
+}
+
+impl Command {
+    /// Execute `db restore` command
+    ///
+    /// Copies the backup into the datadir, which must not contain a database yet, and verifies
+    /// that its static files end at the heights recorded in the backup.
+    pub fn execute(self, env: &EnvironmentArgs) -> eyre::Result<()> {
+        let manifest_path = self.backup.join(BACKUP_MANIFEST_FILE_NAME);
+        eyre::ensure!(
+            manifest_path.is_file(),
+            "Backup is incomplete, {manifest_path:?} does not exist"
+        );
+        let manifest: BackupManifest = reth_fs_util::read_json_file(&manifest_path)?;
+        eyre::ensure!(
+            manifest.genesis_hash == env.chain.genesis_hash(),
+            "Backup is of a different chain, genesis hash is {} but expected {}",
+            manifest.genesis_hash,
+            env.chain.genesis_hash()
+        );
+
+        let data_dir = env.datadir.clone().resolve_datadir(env.chain.chain);
+        let db_path = data_dir.db();
+        let static_files_path = data_dir.static_files();
+        eyre::ensure!(
+            !db_path.join(MDBX_DATA_FILE_NAME).exists(),
+            "Database already exists: {db_path:?}"
+        );
+        if static_files_path.exists() {
+            eyre::ensure!(
+                reth_fs_util::read_dir(&static_files_path)?.next().is_none(),
+                "Static files directory is not empty: {static_files_path:?}"
+            );
+        }
+        reth_fs_util::create_dir_all(&db_path)?;
+        reth_fs_util::create_dir_all(&static_files_path)?;
+
+        info!(target: "reth::cli", path = ?db_path, "Restoring database");
+        for file in [MDBX_DATA_FILE_NAME, DB_VERSION_FILE_NAME] {
+            reth_fs_util::copy(self.backup.join("db").join(file), db_path.join(file))?;
+        }
+
+        info!(target: "reth::cli", path = ?static_files_path, "Restoring static files");
+        copy_static_files(&self.backup.join("static_files"), &static_files_path)?;
+
+        let config_path = env.config.clone().unwrap_or_else(|| data_dir.config());
+        let backup_config_path = self.backup.join(CONFIG_FILE_NAME);
+        if backup_config_path.exists() {
+            if config_path.exists() {
+                warn!(target: "reth::cli", path = ?config_path, "Config file already exists, not restoring it");
+            } else {
+                reth_fs_util::copy(backup_config_path, &config_path)?;
+            }
+        }
+
+        let config: Config = confy::load_path(&config_path).unwrap_or_default();
+        let provider_factory = open_consistent(
+            &db_path,
+            &static_files_path,
+            env.chain.clone(),
+            &config,
+            env.db.database_args(),
+        )?;
+        let restored = highest_static_files(&provider_factory.static_file_provider());
+        eyre::ensure!(
+            restored == manifest.highest_static_files,
+            "Restored static files end at {restored:?}, but the backup has {:?}",
+            manifest.highest_static_files
+        );
+
+        info!(target: "reth::cli", path = ?data_dir.data_dir(), highest_static_files = ?restored, "Restore complete");
+        Ok(())
+    }
+}
@@ -0,0 +1,407 @@
+use crate::common::{AccessRights, Environment, EnvironmentArgs};
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use itertools::Itertools;
+use reth_db::{static_file::iter_static_files, tables, DatabaseEnv};
+use reth_db_api::{cursor::DbCursorRO, transaction::DbTx};
+use reth_primitives::{static_file::find_fixed_range, BlockNumber, StaticFileSegment};
+use reth_provider::{
+    providers::{StaticFileProvider, StaticFileWriter},
+    BlockReader, ProviderFactory, StaticFileProviderFactory, TransactionsProvider,
+};
+use reth_static_file::segments::{self, Segment};
+use std::{collections::BTreeMap, ops::RangeInclusive, sync::Arc};
+use tracing::info;
+
+/// The arguments for the `reth db verify-static-files` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// Repair every segment with corrupted rows.
+    ///
+    /// The segment is truncated back to its last good block, and the truncated blocks are copied
+    /// again from the database tables if they still have them, e.g. changesets and receipts that
+    /// weren't pruned from the database yet. Otherwise, the database is unwound to match the
+    /// static files and the truncated blocks are synced again on the next start of the node.
+    #[arg(long)]
+    repair: bool,
+}
+
+impl Command {
+    /// Execute `db verify-static-files` command
+    pub fn execute(self, env: &EnvironmentArgs) -> eyre::Result<()> {
+        let access = if self.repair { AccessRights::RW } else { AccessRights::RO };
+        let Environment { provider_factory, .. } = env.init(access)?;
+
+        let verifications = verify_static_files(&provider_factory.static_file_provider())?;
+        println!("{}", verifications_table(&verifications));
+
+        let corrupted = verifications
+            .iter()
+            .filter_map(|(segment, verification)| {
+                verification.corrupted.first().map(|rows| (*segment, *rows.start()))
+            })
+            .collect::<Vec<_>>();
+        if corrupted.is_empty() {
+            println!("No corrupted static files found");
+            return Ok(())
+        }
+        eyre::ensure!(
+            self.repair,
+            "Found corrupted static files, run with --repair to repair them"
+        );
+
+        let mut resync = false;
+        for (segment, first_corrupted) in corrupted {
+            let blocks = truncate_segment(&provider_factory, segment, first_corrupted)?;
+            resync |= !rederive_segment(&provider_factory, segment, blocks)?;
+        }
+        drop(provider_factory);
+
+        // Opening the environment read-write makes the database consistent with the truncated
+        // static files.
+        env.init(AccessRights::RW)?;
+        if resync {
+            println!(
+                "Repaired corrupted static files, start the node to sync the truncated blocks"
+            );
+        } else {
+            println!("Repaired corrupted static files from the database");
+        }
+
+        Ok(())
+    }
+}
+
+/// Result of the checksum verification of all static files of a segment.
+#[derive(Debug, Default)]
+struct SegmentVerification {
+    /// Number of static files of the segment.
+    files: usize,
+    /// Number of rows verified against their checksum.
+    checked: usize,
+    /// Number of rows without a checksum, written before checksums were recorded.
+    unchecked: usize,
+    /// Ranges of corrupted block numbers for block-based segments, and transaction numbers
+    /// otherwise.
+    corrupted: Vec<RangeInclusive<u64>>,
+}
+
+/// Verifies the checksums of the rows of every static file.
+fn verify_static_files(
+    static_file_provider: &StaticFileProvider,
+) -> eyre::Result<BTreeMap<StaticFileSegment, SegmentVerification>> {
+    let mut verifications = BTreeMap::<_, SegmentVerification>::new();
+
+    let static_files = iter_static_files(static_file_provider.directory())?;
+    for (segment, ranges) in static_files.into_iter().sorted_by_key(|(segment, _)| *segment) {
+        let verification = verifications.entry(segment).or_default();
+
+        for (block_range, _) in ranges {
+            let fixed_block_range = find_fixed_range(block_range.start());
+            let jar_provider = static_file_provider
+                .get_segment_provider(segment, || Some(fixed_block_range), None)?
+                .ok_or_else(|| {
+                    eyre::eyre!("Failed to get segment provider for segment: {}", segment)
+                })?;
+
+            info!(target: "reth::cli", ?segment, %block_range, "Verifying static file");
+            let jar_verification = jar_provider.verify_checksums()?;
+            let header = jar_provider.user_header();
+            let first_number =
+                if segment.is_block_based() { header.block_start() } else { header.tx_start() }
+                    .unwrap_or_default();
+
+            verification.files += 1;
+            verification.checked += jar_verification.checked.len();
+            verification.unchecked += jar_provider.rows() - jar_verification.checked.len();
+            for row in jar_verification.corrupted {
+                let number = first_number + row as u64;
+                match verification.corrupted.last_mut() {
+                    Some(rows) if *rows.end() + 1 == number => *rows = *rows.start()..=number,
+                    _ => verification.corrupted.push(number..=number),
+                }
+            }
+        }
+    }
+
+    Ok(verifications)
+}
+
+fn verifications_table(
+    verifications: &BTreeMap<StaticFileSegment, SegmentVerification>,
+) -> ComfyTable {
+    let mut table = ComfyTable::new();
+    table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+    table.set_header(["Segment", "Files", "Verified Rows", "Unverified Rows", "Corrupted"]);
+
+    for (segment, verification) in verifications {
+        let corrupted = if verification.corrupted.is_empty() {
+            "none".to_string()
+        } else {
+            let unit = if segment.is_block_based() { "blocks" } else { "transactions" };
+            format!(
+                "{unit} {}",
+                verification
+                    .corrupted
+                    .iter()
+                    .map(|rows| format!("{}..={}", rows.start(), rows.end()))
+                    .join(", ")
+            )
+        };
+
+        let mut row = Row::new();
+        row.add_cell(Cell::new(segment))
+            .add_cell(Cell::new(verification.files))
+            .add_cell(Cell::new(verification.checked))
+            .add_cell(Cell::new(verification.unchecked))
+            .add_cell(Cell::new(corrupted));
+        table.add_row(row);
+    }
+
+    table
+}
+
+/// Truncates the static files of the segment to the last complete block before the first
+/// corrupted row.
+///
+/// Returns the truncated blocks.
+fn truncate_segment(
+    provider_factory: &ProviderFactory<Arc<DatabaseEnv>>,
+    segment: StaticFileSegment,
+    first_corrupted: u64,
+) -> eyre::Result<RangeInclusive<BlockNumber>> {
+    let static_file_provider = provider_factory.static_file_provider();
+    let mut writer = static_file_provider.latest_writer(segment)?;
+    let highest_block =
+        static_file_provider.get_highest_static_file_block(segment).unwrap_or_default();
+
+    let first_block = if segment.is_block_based() {
+        let to_delete = highest_block + 1 - first_corrupted;
+        if segment.is_headers() {
+            writer.prune_headers(to_delete)?;
+        } else {
+            writer.prune_changesets(to_delete)?;
+        }
+        info!(target: "reth::cli", ?segment, block = first_corrupted.checked_sub(1), "Truncated static files");
+        first_corrupted
+    } else {
+        // Transactions are truncated together with the rest of their block.
+        let provider = provider_factory.provider()?;
+        let block = provider.transaction_block(first_corrupted)?.ok_or_else(|| {
+            eyre::eyre!("Block of transaction {first_corrupted} not found in the database")
+        })?;
+        let first_tx = provider
+            .block_body_indices(block)?
+            .ok_or_else(|| eyre::eyre!("Body indices of block {block} not found in the database"))?
+            .first_tx_num();
+        let highest_tx =
+            static_file_provider.get_highest_static_file_tx(segment).unwrap_or_default();
+        let to_delete = highest_tx + 1 - first_tx;
+        let last_block = block.saturating_sub(1);
+        match segment {
+            StaticFileSegment::Transactions => writer.prune_transactions(to_delete, last_block)?,
+            StaticFileSegment::Receipts => writer.prune_receipts(to_delete, last_block)?,
+            _ => unreachable!("only transactions and receipts are transaction-based"),
+        }
+        info!(target: "reth::cli", ?segment, block = last_block, "Truncated static files");
+        block
+    };
+
+    writer.commit()?;
+    Ok(first_block..=highest_block)
+}
+
+/// Copies the truncated blocks of the segment back into the static files from the database, if
+/// its tables still have all of them.
+///
+/// Returns `false` if the blocks have to be synced again instead.
+fn rederive_segment(
+    provider_factory: &ProviderFactory<Arc<DatabaseEnv>>,
+    segment: StaticFileSegment,
+    blocks: RangeInclusive<BlockNumber>,
+) -> eyre::Result<bool> {
+    let provider = provider_factory.provider()?;
+    let tx = provider.tx_ref();
+    let (start, end) = (*blocks.start(), *blocks.end());
+
+    let available = match segment {
+        StaticFileSegment::Headers => {
+            tx.get::<tables::Headers>(start)?.is_some() && tx.get::<tables::Headers>(end)?.is_some()
+        }
+        StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            match (provider.block_body_indices(start)?, provider.block_body_indices(end)?) {
+                (Some(first), Some(last)) if first.first_tx_num() < last.next_tx_num() => {
+                    let (first_tx, last_tx) = (first.first_tx_num(), last.next_tx_num() - 1);
+                    if segment == StaticFileSegment::Transactions {
+                        tx.get::<tables::Transactions>(first_tx)?.is_some() &&
+                            tx.get::<tables::Transactions>(last_tx)?.is_some()
+                    } else {
+                        tx.get::<tables::Receipts>(first_tx)?.is_some() &&
+                            tx.get::<tables::Receipts>(last_tx)?.is_some()
+                    }
+                }
+                // Blocks without transactions only need their body indices.
+                (Some(_), Some(_)) => true,
+                _ => false,
+            }
+        }
+        // Changesets are deleted from the database in order, and every block gets a row in the
+        // static files even if it has no changes, so they're complete from their first block on.
+        StaticFileSegment::AccountChangeSets => tx
+            .cursor_read::<tables::AccountChangeSets>()?
+            .first()?
+            .is_some_and(|(block, _)| block <= start),
+        StaticFileSegment::StorageChangeSets => tx
+            .cursor_read::<tables::StorageChangeSets>()?
+            .first()?
+            .is_some_and(|(key, _)| key.block_number() <= start),
+    };
+    if !available {
+        info!(target: "reth::cli", ?segment, ?blocks, "Truncated blocks aren't in the database anymore");
+        return Ok(false)
+    }
+
+    let segment_copier: Box<dyn Segment<Arc<DatabaseEnv>>> = match segment {
+        StaticFileSegment::Headers => Box::new(segments::Headers),
+        StaticFileSegment::Transactions => Box::new(segments::Transactions),
+        StaticFileSegment::Receipts => Box::new(segments::Receipts),
+        StaticFileSegment::AccountChangeSets => Box::new(segments::AccountChangeSets),
+        StaticFileSegment::StorageChangeSets => Box::new(segments::StorageChangeSets),
+    };
+    let static_file_provider = provider_factory.static_file_provider();
+    segment_copier.copy_to_static_files(provider, static_file_provider.clone(), blocks.clone())?;
+    static_file_provider.commit()?;
+    info!(target: "reth::cli", ?segment, ?blocks, "Copied truncated blocks from the database");
+
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::open_db;
+    use reth_db_api::{models::AccountBeforeTx, transaction::DbTxMut};
+    use reth_primitives::{Account, Address};
+    use std::path::Path;
+
+    fn env_args(datadir: &Path) -> EnvironmentArgs {
+        EnvironmentArgs::try_parse_from([
+            "reth",
+            "--datadir",
+            datadir.to_str().unwrap(),
+            "--chain",
+            "dev",
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn detects_corrupted_static_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let env = env_args(dir.path());
+        let Environment { provider_factory, .. } = env.init(AccessRights::RW).unwrap();
+        let static_file_provider = provider_factory.static_file_provider();
+
+        let verifications = verify_static_files(&static_file_provider).unwrap();
+        let headers = &verifications[&StaticFileSegment::Headers];
+        assert_eq!((headers.files, headers.checked, headers.unchecked), (1, 1, 0));
+        assert!(verifications.values().all(|verification| verification.corrupted.is_empty()));
+
+        // Flip a byte of the genesis header
+        let jar_provider = static_file_provider
+            .get_segment_provider(StaticFileSegment::Headers, || Some(find_fixed_range(0)), None)
+            .unwrap()
+            .unwrap();
+        let data_path = jar_provider.data_path().to_path_buf();
+        drop(jar_provider);
+        drop(static_file_provider);
+        drop(provider_factory);
+        Command::try_parse_from(["verify-static-files"]).unwrap().execute(&env).unwrap();
+
+        let mut data = std::fs::read(&data_path).unwrap();
+        data[0] ^= 0xff;
+        std::fs::write(&data_path, data).unwrap();
+
+        let Environment { provider_factory, .. } = env.init(AccessRights::RO).unwrap();
+        let verifications = verify_static_files(&provider_factory.static_file_provider()).unwrap();
+        assert_eq!(verifications[&StaticFileSegment::Headers].corrupted, vec![0..=0]);
+        drop(provider_factory);
+        assert!(Command::try_parse_from(["verify-static-files"]).unwrap().execute(&env).is_err());
+    }
+
+    #[test]
+    fn repairs_changesets_from_database() {
+        let dir = tempfile::tempdir().unwrap();
+        let env = env_args(dir.path());
+        drop(env.init(AccessRights::RW).unwrap());
+
+        // The consistency check of the environment would unwind the changesets to the execution
+        // checkpoint, so the static files are opened directly.
+        let data_dir = env.datadir.clone().resolve_datadir(env.chain.chain);
+        let provider_factory = || {
+            ProviderFactory::new(
+                Arc::new(open_db(&data_dir.db(), env.db.database_args()).unwrap()),
+                env.chain.clone(),
+                StaticFileProvider::read_write(data_dir.static_files()).unwrap(),
+            )
+        };
+        let factory = provider_factory();
+        let static_file_provider = factory.static_file_provider();
+
+        // Changesets of blocks 1 and 2, in the database and the static files
+        let account = |block: u64| AccountBeforeTx {
+            address: Address::with_last_byte(block as u8),
+            info: Some(Account { nonce: block, ..Default::default() }),
+        };
+        let provider = factory.provider_rw().unwrap();
+        for block in 1..=2 {
+            provider.tx_ref().put::<tables::AccountChangeSets>(block, account(block)).unwrap();
+        }
+        provider.commit().unwrap();
+
+        let segment = StaticFileSegment::AccountChangeSets;
+        let start =
+            static_file_provider.get_highest_static_file_block(segment).map_or(0, |b| b + 1);
+        Segment::<Arc<DatabaseEnv>>::copy_to_static_files(
+            &segments::AccountChangeSets,
+            factory.provider().unwrap(),
+            static_file_provider.clone(),
+            start..=2,
+        )
+        .unwrap();
+        static_file_provider.commit().unwrap();
+
+        // Flip the last byte of the changeset of block 2
+        let jar_provider = static_file_provider
+            .get_segment_provider(segment, || Some(find_fixed_range(0)), None)
+            .unwrap()
+            .unwrap();
+        let data_path = jar_provider.data_path().to_path_buf();
+        drop(jar_provider);
+        drop(static_file_provider);
+        drop(factory);
+
+        let mut data = std::fs::read(&data_path).unwrap();
+        *data.last_mut().unwrap() ^= 0xff;
+        std::fs::write(&data_path, data).unwrap();
+
+        let factory = provider_factory();
+        let verifications = verify_static_files(&factory.static_file_provider()).unwrap();
+        assert_eq!(verifications[&segment].corrupted, vec![2..=2]);
+
+        let blocks = truncate_segment(&factory, segment, 2).unwrap();
+        assert_eq!(blocks, 2..=2);
+        assert!(rederive_segment(&factory, segment, blocks).unwrap());
+        drop(factory);
+
+        let factory = provider_factory();
+        let static_file_provider = factory.static_file_provider();
+        let verifications = verify_static_files(&static_file_provider).unwrap();
+        assert!(verifications[&segment].corrupted.is_empty());
+        assert_eq!(static_file_provider.get_highest_static_file_block(segment), Some(2));
+        assert_eq!(
+            static_file_provider.account_changesets_range(1..=2).unwrap(),
+            vec![(1, account(1)), (2, account(2))]
+        );
+    }
+}
@@ -0,0 +1,57 @@
+//! Command that dumps the state at a block to a JSONL file.
+
+use crate::common::{AccessRights, Environment, EnvironmentArgs};
+use clap::Parser;
+use reth_db_common::state_dump::dump_state_at;
+use reth_primitives::BlockNumber;
+use reth_provider::StageCheckpointReader;
+use reth_stages::StageId;
+use std::{
+    io::{BufWriter, Write},
+    path::PathBuf,
+};
+use tracing::info;
+
+/// Dumps the state at a block to a JSONL file, in the format consumed by `init-state`.
+#[derive(Debug, Parser)]
+pub struct DumpStateCommand {
+    #[command(flatten)]
+    env: EnvironmentArgs,
+
+    /// The block to dump the state at.
+    ///
+    /// Defaults to the last executed block. The state at older blocks is reconstructed from the
+    /// changesets, so it must not be pruned.
+    #[arg(long, value_name = "BLOCK_NUMBER")]
+    block: Option<BlockNumber>,
+
+    /// JSONL file to write the state dump to.
+    #[arg(value_name = "STATE_DUMP_FILE")]
+    output: PathBuf,
+}
+
+impl DumpStateCommand {
+    /// Execute the `dump-state` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        info!(target: "reth::cli", "Reth dump-state starting");
+
+        let Environment { provider_factory, .. } = self.env.init(AccessRights::RO)?;
+        let provider = provider_factory.provider()?;
+
+        let block = match self.block {
+            Some(block) => block,
+            None => {
+                provider.get_stage_checkpoint(StageId::Execution)?.unwrap_or_default().block_number
+            }
+        };
+
+        info!(target: "reth::cli", block, path = ?self.output, "Dumping state");
+
+        let mut writer = BufWriter::new(reth_fs_util::create_file(&self.output)?);
+        let accounts = dump_state_at(&provider, block, &mut writer)?;
+        writer.flush()?;
+
+        info!(target: "reth::cli", block, accounts, "State dump written");
+        Ok(())
+    }
+}
@@ -1,10 +1,12 @@
 //! Main node command for launching a node
 
+use crate::common::load_prune_modes;
 use clap::{value_parser, Args, Parser};
 use reth_chainspec::ChainSpec;
 use reth_cli_runner::CliContext;
 use reth_cli_util::parse_socket_address;
-use reth_db::{init_db, DatabaseEnv};
+use reth_db::{init_db, open_db_for_migration, DatabaseEnv};
+use reth_db_common::migration::{needs_migration, Migrator};
 use reth_node_builder::{NodeBuilder, WithLaunchContext};
 use reth_node_core::{
     args::{
@@ -15,6 +17,7 @@ use reth_node_core::{
     node_config::NodeConfig,
     version,
 };
+use reth_provider::{providers::StaticFileProvider, ProviderFactory};
 use std::{ffi::OsString, fmt, future::Future, net::SocketAddr, path::PathBuf, sync::Arc};
 
 /// Start the node
@@ -95,6 +98,11 @@ pub struct NodeCommand<Ext: clap::Args + fmt::Debug = NoArgs> {
     #[command(flatten)]
     pub db: DatabaseArgs,
 
+    /// Run the pending database migrations on startup, instead of refusing to start when the
+    /// database version doesn't match the current database version.
+    #[arg(long = "db.auto-migrate", help_heading = "Database")]
+    pub auto_migrate: bool,
+
     /// All dev related arguments with --dev prefix
     #[command(flatten)]
     pub dev: DevArgs,
@@ -149,6 +157,7 @@ impl<Ext: clap::Args + fmt::Debug> NodeCommand<Ext> {
             builder,
             debug,
             db,
+            auto_migrate,
             dev,
             pruning,
             ext,
@@ -178,6 +187,21 @@ impl<Ext: clap::Args + fmt::Debug> NodeCommand<Ext> {
         let data_dir = node_config.datadir();
         let db_path = data_dir.db();
 
+        if auto_migrate && needs_migration(&db_path)? {
+            tracing::info!(target: "reth::cli", path = ?db_path, "Migrating database");
+            let prune_modes = load_prune_modes(
+                &node_config.config.clone().unwrap_or_else(|| data_dir.config()),
+                node_config.prune_config(),
+            )?;
+            let provider_factory = ProviderFactory::new(
+                open_db_for_migration(&db_path, self.db.database_args())?,
+                node_config.chain.clone(),
+                StaticFileProvider::read_write(data_dir.static_files())?,
+            )
+            .with_prune_modes(prune_modes);
+            Migrator::new(provider_factory, &db_path).run(false)?;
+        }
+
         tracing::info!(target: "reth::cli", path = ?db_path, "Opening database");
         let database = Arc::new(init_db(db_path.clone(), self.db.database_args())?.with_metrics());
 
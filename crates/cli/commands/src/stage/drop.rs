@@ -31,26 +31,34 @@ impl Command {
 
         let tool = DbTool::new(provider_factory)?;
 
-        let static_file_segment = match self.stage {
-            StageEnum::Headers => Some(StaticFileSegment::Headers),
-            StageEnum::Bodies => Some(StaticFileSegment::Transactions),
-            StageEnum::Execution => Some(StaticFileSegment::Receipts),
-            _ => None,
+        let static_file_segments: &[StaticFileSegment] = match self.stage {
+            StageEnum::Headers => &[StaticFileSegment::Headers],
+            StageEnum::Bodies => &[StaticFileSegment::Transactions],
+            StageEnum::Execution => &[
+                StaticFileSegment::Receipts,
+                StaticFileSegment::AccountChangeSets,
+                StaticFileSegment::StorageChangeSets,
+            ],
+            _ => &[],
         };
 
         // Delete static file segment data before inserting the genesis header below
-        if let Some(static_file_segment) = static_file_segment {
+        if !static_file_segments.is_empty() {
             let static_file_provider = tool.provider_factory.static_file_provider();
             let static_files = iter_static_files(static_file_provider.directory())?;
-            if let Some(segment_static_files) = static_files.get(&static_file_segment) {
-                // Delete static files from the highest to the lowest block range
-                for (block_range, _) in segment_static_files
-                    .iter()
-                    .sorted_by_key(|(block_range, _)| block_range.start())
-                    .rev()
-                {
-                    static_file_provider
-                        .delete_jar(static_file_segment, find_fixed_range(block_range.start()))?;
+            for static_file_segment in static_file_segments {
+                if let Some(segment_static_files) = static_files.get(static_file_segment) {
+                    // Delete static files from the highest to the lowest block range
+                    for (block_range, _) in segment_static_files
+                        .iter()
+                        .sorted_by_key(|(block_range, _)| block_range.start())
+                        .rev()
+                    {
+                        static_file_provider.delete_jar(
+                            *static_file_segment,
+                            find_fixed_range(block_range.start()),
+                        )?;
+                    }
                 }
             }
         }
@@ -162,6 +170,14 @@ impl Command {
                 )?;
                 insert_genesis_history(&provider_rw, self.env.chain.genesis.alloc.iter())?;
             }
+            StageEnum::AddressAppearances => {
+                tx.clear::<tables::AddressAppearances>()?;
+                tx.clear::<tables::TransactionAddressAppearances>()?;
+                tx.put::<tables::StageCheckpoints>(
+                    StageId::IndexAddressAppearances.to_string(),
+                    Default::default(),
+                )?;
+            }
             StageEnum::TxLookup => {
                 tx.clear::<tables::TransactionHashNumbers>()?;
                 tx.put::<tables::StageCheckpoints>(
@@ -5,7 +5,7 @@ use std::sync::Arc;
 use clap::{Parser, Subcommand};
 use reth_chainspec::ChainSpec;
 use reth_cli_runner::CliContext;
-use reth_evm::execute::BlockExecutorProvider;
+use reth_evm::{execute::BlockExecutorProvider, ConfigureEvm};
 
 pub mod drop;
 pub mod dump;
@@ -39,13 +39,19 @@ pub enum Subcommands {
 
 impl Command {
     /// Execute `stage` command
-    pub async fn execute<E, F>(self, ctx: CliContext, executor: F) -> eyre::Result<()>
+    pub async fn execute<E, F, EvmConfig>(
+        self,
+        ctx: CliContext,
+        executor: F,
+        evm_config: EvmConfig,
+    ) -> eyre::Result<()>
     where
         E: BlockExecutorProvider,
         F: FnOnce(Arc<ChainSpec>) -> E,
+        EvmConfig: ConfigureEvm,
     {
         match self.command {
-            Subcommands::Run(command) => command.execute(ctx, executor).await,
+            Subcommands::Run(command) => command.execute(ctx, executor, evm_config).await,
             Subcommands::Drop(command) => command.execute().await,
             Subcommands::Dump(command) => command.execute(executor).await,
             Subcommands::Unwind(command) => command.execute().await,
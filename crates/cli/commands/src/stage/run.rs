@@ -9,7 +9,7 @@ use reth_cli_runner::CliContext;
 use reth_cli_util::get_secret_key;
 use reth_config::config::{HashingConfig, SenderRecoveryConfig, TransactionLookupConfig};
 use reth_downloaders::bodies::bodies::BodiesDownloaderBuilder;
-use reth_evm::execute::BlockExecutorProvider;
+use reth_evm::{execute::BlockExecutorProvider, ConfigureEvm};
 use reth_exex::ExExManagerHandle;
 use reth_node_core::{
     args::{NetworkArgs, StageEnum},
@@ -22,8 +22,8 @@ use reth_provider::{
 use reth_stages::{
     stages::{
         AccountHashingStage, BodyStage, ExecutionStage, IndexAccountHistoryStage,
-        IndexStorageHistoryStage, MerkleStage, SenderRecoveryStage, StorageHashingStage,
-        TransactionLookupStage,
+        IndexAddressAppearancesStage, IndexStorageHistoryStage, MerkleStage, SenderRecoveryStage,
+        StorageHashingStage, TransactionLookupStage,
     },
     ExecInput, ExecOutput, ExecutionStageThresholds, Stage, StageExt, UnwindInput, UnwindOutput,
 };
@@ -84,10 +84,16 @@ pub struct Command {
 
 impl Command {
     /// Execute `stage` command
-    pub async fn execute<E, F>(self, ctx: CliContext, executor: F) -> eyre::Result<()>
+    pub async fn execute<E, F, EvmConfig>(
+        self,
+        ctx: CliContext,
+        executor: F,
+        evm_config: EvmConfig,
+    ) -> eyre::Result<()>
     where
         E: BlockExecutorProvider,
         F: FnOnce(Arc<ChainSpec>) -> E,
+        EvmConfig: ConfigureEvm,
     {
         // Raise the fd limit of the process.
         // Does not do anything on windows.
@@ -225,6 +231,15 @@ impl Command {
                     )),
                     None,
                 ),
+                StageEnum::AddressAppearances => (
+                    Box::new(IndexAddressAppearancesStage::new(
+                        evm_config,
+                        config.stages.index_address_appearances,
+                        etl_config,
+                        prune_modes.address_appearances,
+                    )),
+                    None,
+                ),
                 _ => return Ok(()),
             };
         if let Some(unwind_stage) = &unwind_stage {
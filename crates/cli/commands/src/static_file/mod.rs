@@ -0,0 +1,32 @@
+//! `reth static-file` command.
+
+use crate::common::EnvironmentArgs;
+use clap::{Parser, Subcommand};
+
+mod recompress;
+
+/// `reth static-file` command
+#[derive(Debug, Parser)]
+pub struct Command {
+    #[command(flatten)]
+    env: EnvironmentArgs,
+
+    #[command(subcommand)]
+    command: Subcommands,
+}
+
+/// `reth static-file` subcommands
+#[derive(Subcommand, Debug)]
+pub enum Subcommands {
+    /// Recompresses the static files of segments with zstd dictionaries trained on their data
+    Recompress(recompress::Command),
+}
+
+impl Command {
+    /// Execute `static-file` command
+    pub async fn execute(self) -> eyre::Result<()> {
+        match self.command {
+            Subcommands::Recompress(command) => command.execute(&self.env),
+        }
+    }
+}
@@ -0,0 +1,364 @@
+use crate::common::{AccessRights, EnvironmentArgs};
+use clap::Parser;
+use comfy_table::{Cell, Row, Table as ComfyTable};
+use human_bytes::human_bytes;
+use reth_db::static_file::iter_static_files;
+use reth_nippy_jar::{
+    compression::{Compression, Compressors, Zstd},
+    ConsistencyFailStrategy, NippyJar, NippyJarCursor, NippyJarWriter,
+};
+use reth_primitives::static_file::{find_fixed_range, SegmentHeader};
+use reth_static_file_types::StaticFileSegment;
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use tracing::info;
+
+/// Directory in the static files directory where recompressed static files are written to.
+const STAGING_DIR: &str = "recompress";
+
+/// Directory the staging directory is renamed to once all of its static files are written. Its
+/// static files then replace the original ones.
+const READY_DIR: &str = "recompress.ready";
+
+/// Default maximum size of a dictionary, the same as the `zstd` CLI.
+const DEFAULT_DICTIONARY_SIZE: usize = 112_640;
+
+/// The arguments for the `reth static-file recompress` command
+#[derive(Parser, Debug)]
+pub struct Command {
+    /// The segments to recompress.
+    #[arg(long, value_delimiter = ',', default_values = ["headers", "receipts"])]
+    segments: Vec<StaticFileSegment>,
+
+    /// Maximum size in bytes of the dictionary trained for each column of a segment.
+    #[arg(long, default_value_t = DEFAULT_DICTIONARY_SIZE)]
+    dictionary_size: usize,
+
+    /// Number of rows sampled evenly from the static files of a segment to train its
+    /// dictionaries.
+    #[arg(long, default_value_t = 100_000)]
+    sample_rows: usize,
+
+    /// Number of rows read from the static files of a segment, before and after recompressing
+    /// them, to measure the lookup latency.
+    #[arg(long, default_value_t = 10_000)]
+    lookup_samples: usize,
+}
+
+impl Command {
+    /// Execute `static-file recompress` command
+    ///
+    /// The node must not be running. Every static file of a segment is rewritten to a staging
+    /// directory first, and the staged files replace the original ones once all of them are
+    /// written. If the replacement is interrupted, running the command again completes it, which
+    /// must be done before starting the node.
+    pub fn execute(self, env: &EnvironmentArgs) -> eyre::Result<()> {
+        let data_dir = env.datadir.clone().resolve_datadir(env.chain.chain);
+        let static_files_path = data_dir.static_files();
+        finish_interrupted(&static_files_path)?;
+
+        // Opening the environment read-write heals the static files, which are not accessed
+        // through the provider afterwards.
+        drop(env.init(AccessRights::RW)?);
+        let static_files = iter_static_files(&static_files_path)?;
+
+        let mut results = Vec::new();
+        for segment in self.segments.iter().copied() {
+            let paths = static_files
+                .get(&segment)
+                .into_iter()
+                .flatten()
+                .map(|(block_range, _)| {
+                    static_files_path.join(segment.filename(&find_fixed_range(block_range.start())))
+                })
+                .collect::<Vec<_>>();
+            results.extend(self.recompress_segment(segment, &static_files_path, &paths)?);
+        }
+
+        println!("{}", results_table(&results));
+        Ok(())
+    }
+
+    /// Trains the dictionaries of the segment, and replaces its static files with ones compressed
+    /// with them.
+    ///
+    /// Returns `None` if the segment has no rows.
+    fn recompress_segment(
+        &self,
+        segment: StaticFileSegment,
+        static_files_path: &Path,
+        paths: &[PathBuf],
+    ) -> eyre::Result<Option<SegmentRecompression>> {
+        let jars = load_jars(paths)?;
+        if jars.iter().all(|jar| jar.rows() == 0) {
+            info!(target: "reth::cli", ?segment, "No static file rows to recompress");
+            return Ok(None)
+        }
+
+        let size_before = jars.iter().map(jar_size).sum::<eyre::Result<u64>>()?;
+        let latency_before = lookup_latency(&jars, self.lookup_samples)?;
+
+        info!(target: "reth::cli", ?segment, files = jars.len(), sample_rows = self.sample_rows, "Training dictionaries");
+        let mut cursors = jars.iter().map(NippyJarCursor::new).collect::<Result<Vec<_>, _>>()?;
+        let mut samples = vec![Vec::new(); segment.columns()];
+        for (jar, row) in sample_rows(&jars, self.sample_rows) {
+            let values = cursors[jar].row_by_number(row)?.ok_or_else(|| {
+                eyre::eyre!("Row {row} not found in static file {:?}", jars[jar].data_path())
+            })?;
+            for (column, value) in values.into_iter().enumerate() {
+                samples[column].push(value.to_vec());
+            }
+        }
+        drop(cursors);
+        let mut zstd = Zstd::new(true, self.dictionary_size, segment.columns());
+        zstd.prepare_compression(samples).map_err(|err| {
+            eyre::eyre!("Failed to train the dictionaries of segment {segment}: {err}")
+        })?;
+        let compressor = Compressors::Zstd(zstd);
+
+        let staging_path = static_files_path.join(STAGING_DIR);
+        reth_fs_util::create_dir_all(&staging_path)?;
+        for jar in &jars {
+            info!(target: "reth::cli", ?segment, path = ?jar.data_path(), "Recompressing static file");
+            let file_name = jar.data_path().file_name().expect("static files have a file name");
+            rewrite_jar(jar, &compressor, &staging_path.join(file_name))?;
+        }
+        drop(jars);
+
+        let ready_path = static_files_path.join(READY_DIR);
+        reth_fs_util::rename(&staging_path, &ready_path)?;
+        sync_dir(static_files_path)?;
+        replace_static_files(&ready_path, static_files_path)?;
+
+        let jars = load_jars(paths)?;
+        Ok(Some(SegmentRecompression {
+            segment,
+            files: jars.len(),
+            size_before,
+            size_after: jars.iter().map(jar_size).sum::<eyre::Result<u64>>()?,
+            latency_before,
+            latency_after: lookup_latency(&jars, self.lookup_samples)?,
+        }))
+    }
+}
+
+/// Size and lookup latency of the static files of a segment before and after recompressing them.
+#[derive(Debug)]
+struct SegmentRecompression {
+    segment: StaticFileSegment,
+    files: usize,
+    size_before: u64,
+    size_after: u64,
+    latency_before: Duration,
+    latency_after: Duration,
+}
+
+fn results_table(results: &[SegmentRecompression]) -> ComfyTable {
+    let mut table = ComfyTable::new();
+    table.load_preset(comfy_table::presets::ASCII_MARKDOWN);
+    table.set_header([
+        "Segment",
+        "Files",
+        "Size Before",
+        "Size After",
+        "Size Change",
+        "Lookup Before",
+        "Lookup After",
+    ]);
+
+    for result in results {
+        let size_change =
+            (result.size_after as f64 - result.size_before as f64) / result.size_before as f64;
+
+        let mut row = Row::new();
+        row.add_cell(Cell::new(result.segment))
+            .add_cell(Cell::new(result.files))
+            .add_cell(Cell::new(human_bytes(result.size_before as f64)))
+            .add_cell(Cell::new(human_bytes(result.size_after as f64)))
+            .add_cell(Cell::new(format!("{:+.1}%", size_change * 100.0)))
+            .add_cell(Cell::new(format!("{:?}", result.latency_before)))
+            .add_cell(Cell::new(format!("{:?}", result.latency_after)));
+        table.add_row(row);
+    }
+
+    table
+}
+
+/// Completes the replacement of static files interrupted in a previous run, or discards the
+/// static files staged before it was interrupted.
+fn finish_interrupted(static_files_path: &Path) -> eyre::Result<()> {
+    let ready_path = static_files_path.join(READY_DIR);
+    if ready_path.exists() {
+        info!(target: "reth::cli", path = ?ready_path, "Completing interrupted recompression");
+        replace_static_files(&ready_path, static_files_path)?;
+    }
+
+    let staging_path = static_files_path.join(STAGING_DIR);
+    if staging_path.exists() {
+        reth_fs_util::remove_dir_all(&staging_path)?;
+    }
+
+    Ok(())
+}
+
+/// Moves every file of the ready directory to the static files directory, replacing the static
+/// files with the same names, and then removes the ready directory.
+fn replace_static_files(ready_path: &Path, static_files_path: &Path) -> eyre::Result<()> {
+    for entry in reth_fs_util::read_dir(ready_path)? {
+        let path = entry?.path();
+        let file_name = path.file_name().expect("read_dir entries have a file name");
+        reth_fs_util::rename(&path, static_files_path.join(file_name))?;
+    }
+    sync_dir(static_files_path)?;
+    reth_fs_util::remove_dir_all(ready_path)?;
+
+    Ok(())
+}
+
+/// Persists the renames of the directory entries.
+fn sync_dir(path: &Path) -> eyre::Result<()> {
+    File::open(path)?.sync_all()?;
+    Ok(())
+}
+
+fn load_jars(paths: &[PathBuf]) -> eyre::Result<Vec<NippyJar<SegmentHeader>>> {
+    Ok(paths.iter().map(|path| NippyJar::load(path)).collect::<Result<_, _>>()?)
+}
+
+/// Returns the size of all files of the static file.
+fn jar_size(jar: &NippyJar<SegmentHeader>) -> eyre::Result<u64> {
+    let mut size = 0;
+    for path in [
+        jar.data_path().to_path_buf(),
+        jar.offsets_path(),
+        jar.config_path(),
+        jar.checksums_path(),
+        jar.index_path(),
+    ] {
+        if path.exists() {
+            size += reth_fs_util::metadata(&path)?.len();
+        }
+    }
+    Ok(size)
+}
+
+/// Returns up to `samples` rows spread evenly over the static files, as the index of the static
+/// file and the row in it.
+fn sample_rows(
+    jars: &[NippyJar<SegmentHeader>],
+    samples: usize,
+) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let total_rows = jars.iter().map(|jar| jar.rows()).sum::<usize>();
+    let step = (total_rows / samples.max(1)).max(1);
+
+    jars.iter()
+        .enumerate()
+        .flat_map(|(index, jar)| (0..jar.rows()).map(move |row| (index, row)))
+        .step_by(step)
+        .take(samples)
+}
+
+/// Returns the average time to read a row of the static files.
+fn lookup_latency(jars: &[NippyJar<SegmentHeader>], samples: usize) -> eyre::Result<Duration> {
+    let mut cursors = jars.iter().map(NippyJarCursor::new).collect::<Result<Vec<_>, _>>()?;
+
+    let mut elapsed = Duration::ZERO;
+    let mut lookups = 0;
+    for (jar, row) in sample_rows(jars, samples) {
+        let start = Instant::now();
+        cursors[jar].row_by_number(row)?;
+        elapsed += start.elapsed();
+        lookups += 1;
+    }
+
+    Ok(elapsed.checked_div(lookups).unwrap_or_default())
+}
+
+/// Writes the rows of the static file to a new one at `path`, compressed with `compressor`.
+fn rewrite_jar(
+    jar: &NippyJar<SegmentHeader>,
+    compressor: &Compressors,
+    path: &Path,
+) -> eyre::Result<()> {
+    let new_jar = NippyJar::new(jar.columns(), path, jar.user_header().clone())
+        .with_compressor(compressor.clone());
+    let mut writer = NippyJarWriter::new(new_jar, ConsistencyFailStrategy::Heal)?;
+
+    let mut cursor = NippyJarCursor::new(jar)?;
+    while let Some(row) = cursor.next_row()? {
+        for value in row {
+            writer.append_column(Some(Ok(value)))?;
+        }
+    }
+    writer.commit()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::Environment;
+    use reth_primitives::{Header, U256};
+    use reth_provider::{
+        providers::StaticFileWriter, HeaderProvider, StageCheckpointWriter,
+        StaticFileProviderFactory,
+    };
+    use reth_stages::{StageCheckpoint, StageId};
+
+    #[test]
+    fn recompress_headers() {
+        let dir = tempfile::tempdir().unwrap();
+        let env = EnvironmentArgs::try_parse_from([
+            "reth",
+            "--datadir",
+            dir.path().to_str().unwrap(),
+            "--chain",
+            "dev",
+        ])
+        .unwrap();
+
+        let Environment { provider_factory, .. } = env.init(AccessRights::RW).unwrap();
+        let static_file_provider = provider_factory.static_file_provider();
+        let mut writer = static_file_provider.latest_writer(StaticFileSegment::Headers).unwrap();
+        let mut headers = vec![static_file_provider.header_by_number(0).unwrap().unwrap()];
+        for number in 1..2_000 {
+            let header = Header {
+                number,
+                parent_hash: headers.last().unwrap().hash_slow(),
+                timestamp: 12 * number,
+                gas_limit: 30_000_000,
+                gas_used: number * 1_000,
+                base_fee_per_gas: Some(7),
+                ..Default::default()
+            };
+            writer.append_header(&header, U256::from(number), &header.hash_slow()).unwrap();
+            headers.push(header);
+        }
+        writer.commit().unwrap();
+        drop(writer);
+        let provider_rw = provider_factory.provider_rw().unwrap();
+        provider_rw.save_stage_checkpoint(StageId::Headers, StageCheckpoint::new(1_999)).unwrap();
+        provider_rw.commit().unwrap();
+        drop(static_file_provider);
+        drop(provider_factory);
+
+        Command::try_parse_from(["recompress", "--segments", "headers"])
+            .unwrap()
+            .execute(&env)
+            .unwrap();
+
+        let static_files_path = dir.path().join("static_files");
+        assert!(!static_files_path.join(STAGING_DIR).exists());
+        assert!(!static_files_path.join(READY_DIR).exists());
+
+        let Environment { provider_factory, .. } = env.init(AccessRights::RO).unwrap();
+        let static_file_provider = provider_factory.static_file_provider();
+        for header in headers {
+            assert_eq!(static_file_provider.header_by_number(header.number).unwrap(), Some(header));
+        }
+    }
+}
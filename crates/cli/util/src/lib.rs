@@ -10,7 +10,7 @@
 
 /// Helper function to load a secret key from a file.
 pub mod load_secret_key;
-pub use load_secret_key::get_secret_key;
+pub use load_secret_key::{get_secret_key, load_secret_key};
 
 /// Cli parsers functions.
 pub mod parsers;
@@ -39,13 +39,7 @@ pub fn get_secret_key(secret_key_path: &Path) -> Result<SecretKey, SecretKeyErro
     let exists = secret_key_path.try_exists();
 
     match exists {
-        Ok(true) => {
-            let contents = fs::read_to_string(secret_key_path)?;
-            Ok(contents
-                .as_str()
-                .parse::<SecretKey>()
-                .map_err(SecretKeyError::SecretKeyDecodeError)?)
-        }
+        Ok(true) => load_secret_key(secret_key_path),
         Ok(false) => {
             if let Some(dir) = secret_key_path.parent() {
                 // Create parent directory
@@ -63,3 +57,9 @@ pub fn get_secret_key(secret_key_path: &Path) -> Result<SecretKey, SecretKeyErro
         }),
     }
 }
+
+/// Loads the hex encoded [`SecretKey`] stored at the specified path.
+pub fn load_secret_key(secret_key_path: &Path) -> Result<SecretKey, SecretKeyError> {
+    let contents = fs::read_to_string(secret_key_path)?;
+    contents.trim().parse::<SecretKey>().map_err(SecretKeyError::SecretKeyDecodeError)
+}
@@ -85,6 +85,10 @@ pub struct StageConfig {
     pub index_account_history: IndexHistoryConfig,
     /// Index Storage History stage configuration.
     pub index_storage_history: IndexHistoryConfig,
+    /// Index Address Appearances stage configuration.
+    pub index_address_appearances: IndexAddressAppearancesConfig,
+    /// Snap sync stage configuration.
+    pub snap_sync: SnapSyncConfig,
     /// Common ETL related configuration.
     pub etl: EtlConfig,
 }
@@ -338,6 +342,50 @@ impl Default for IndexHistoryConfig {
     }
 }
 
+/// Address appearances indexing stage configuration.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct IndexAddressAppearancesConfig {
+    /// Whether the stage is enabled. The index is only needed to serve the
+    /// `ots_searchTransactionsBefore` and `ots_searchTransactionsAfter` RPC methods.
+    pub enabled: bool,
+    /// The maximum number of blocks to process before committing progress to the database.
+    pub commit_threshold: u64,
+}
+
+impl Default for IndexAddressAppearancesConfig {
+    fn default() -> Self {
+        Self { enabled: false, commit_threshold: 100_000 }
+    }
+}
+
+/// Snap sync stage configuration.
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default)]
+pub struct SnapSyncConfig {
+    /// Whether to download the state of a recent pivot block over `snap/1` instead of executing
+    /// all blocks from genesis. The history before the pivot block is not available.
+    pub enabled: bool,
+    /// The distance of the pivot block from the sync target. Peers only serve the state of recent
+    /// blocks, so the pivot should be well within the range of blocks they keep.
+    pub pivot_distance: u64,
+    /// The maximum number of requests to send concurrently.
+    pub max_concurrent_requests: usize,
+    /// The soft limit of the response size to request, in bytes.
+    pub response_bytes: u64,
+}
+
+impl Default for SnapSyncConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            pivot_distance: 64,
+            max_concurrent_requests: 16,
+            response_bytes: 512 * 1024,
+        }
+    }
+}
+
 /// Pruning configuration.
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default)]
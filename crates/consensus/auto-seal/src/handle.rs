@@ -0,0 +1,259 @@
+//! A handle to control the [`MiningTask`](crate::MiningTask) and the state of the dev chain.
+
+use parking_lot::Mutex;
+use reth_primitives::{Address, B256};
+use reth_revm::overlay::StateOverlay;
+use reth_transaction_pool::ImpersonatedAccounts;
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
+
+/// Commands that are sent from the [`AutoSealHandle`] to the [`MiningTask`](crate::MiningTask).
+#[derive(Debug)]
+pub(crate) enum MiningCommand {
+    /// Mines one block per sender and sends back the hash of the mined block.
+    Mine {
+        /// The number of seconds between the timestamps of the mined blocks.
+        interval: Option<u64>,
+        /// Notified once the blocks are mined.
+        blocks: Vec<oneshot::Sender<B256>>,
+    },
+    /// Enables or disables mining a block for every new transaction.
+    SetAutomine(bool),
+    /// Mines a new block every given number of seconds, `0` disables interval mining.
+    SetIntervalMining(u64),
+}
+
+/// Settings for the blocks that are built by the [`MiningTask`](crate::MiningTask).
+#[derive(Debug, Default)]
+pub(crate) struct MiningSettings {
+    /// Whether a block is mined for every new transaction.
+    pub(crate) automine: bool,
+    /// The beneficiary of new blocks.
+    coinbase: Address,
+    /// The timestamp of the next block.
+    next_timestamp: Option<u64>,
+    /// The number of seconds that are added to the current time.
+    time_offset: i64,
+    /// The fixed number of seconds between the timestamps of two blocks.
+    timestamp_interval: Option<u64>,
+    /// The base fee of the next block.
+    next_base_fee: Option<u64>,
+    /// The gas limit of new blocks.
+    gas_limit: Option<u64>,
+    /// The `PREVRANDAO` value of the next block.
+    next_prev_randao: Option<B256>,
+}
+
+impl MiningSettings {
+    /// Creates new settings with the given automine flag.
+    pub(crate) fn new(automine: bool) -> Self {
+        Self { automine, ..Default::default() }
+    }
+
+    /// Returns the header overrides for the next block and resets all settings that only apply to
+    /// a single block.
+    ///
+    /// An `interval` that is requested for this block takes precedence over the configured
+    /// timestamp interval.
+    pub(crate) fn next_block(&mut self, interval: Option<u64>) -> HeaderOverrides {
+        HeaderOverrides {
+            timestamp: self.next_timestamp.take(),
+            timestamp_interval: interval.or(self.timestamp_interval),
+            time_offset: self.time_offset,
+            coinbase: self.coinbase,
+            base_fee: self.next_base_fee.take(),
+            gas_limit: self.gas_limit,
+            prev_randao: self.next_prev_randao.take(),
+        }
+    }
+}
+
+/// Header fields of the next block that are configured via the [`AutoSealHandle`].
+#[derive(Debug, Default)]
+pub(crate) struct HeaderOverrides {
+    timestamp: Option<u64>,
+    timestamp_interval: Option<u64>,
+    time_offset: i64,
+    pub(crate) coinbase: Address,
+    pub(crate) base_fee: Option<u64>,
+    pub(crate) gas_limit: Option<u64>,
+    pub(crate) prev_randao: Option<B256>,
+}
+
+impl HeaderOverrides {
+    /// Returns the timestamp of the block that is built on top of a parent with the given
+    /// timestamp.
+    pub(crate) fn timestamp(&self, parent_timestamp: u64) -> u64 {
+        if let Some(timestamp) = self.timestamp {
+            return timestamp
+        }
+        if let Some(interval) = self.timestamp_interval {
+            return parent_timestamp + interval
+        }
+        now().saturating_add_signed(self.time_offset)
+    }
+}
+
+/// A handle to interact with the [`MiningTask`](crate::MiningTask) and the state of the dev chain.
+///
+/// Account modifications are recorded in the [`StateOverlay`] and take effect in the next mined
+/// block.
+#[derive(Debug, Clone)]
+pub struct AutoSealHandle {
+    /// Sends commands to the mining task.
+    to_task: UnboundedSender<MiningCommand>,
+    /// Settings for new blocks, shared with the mining task.
+    settings: Arc<Mutex<MiningSettings>>,
+    /// Account modifications applied in the next mined block.
+    state_overlay: StateOverlay,
+    /// Accounts that skip the signer checks of the pool.
+    impersonated_accounts: ImpersonatedAccounts,
+}
+
+impl AutoSealHandle {
+    /// Creates a new handle.
+    pub(crate) const fn new(
+        to_task: UnboundedSender<MiningCommand>,
+        settings: Arc<Mutex<MiningSettings>>,
+        state_overlay: StateOverlay,
+        impersonated_accounts: ImpersonatedAccounts,
+    ) -> Self {
+        Self { to_task, settings, state_overlay, impersonated_accounts }
+    }
+
+    /// Returns the [`StateOverlay`] that is applied in the next mined block.
+    pub const fn state_overlay(&self) -> &StateOverlay {
+        &self.state_overlay
+    }
+
+    /// Returns the impersonated accounts.
+    pub const fn impersonated_accounts(&self) -> &ImpersonatedAccounts {
+        &self.impersonated_accounts
+    }
+
+    /// Returns `true` if a block is mined for every new transaction.
+    pub fn automine(&self) -> bool {
+        self.settings.lock().automine
+    }
+
+    /// Enables or disables mining a block for every new transaction.
+    pub fn set_automine(&self, enabled: bool) {
+        self.settings.lock().automine = enabled;
+        let _ = self.to_task.send(MiningCommand::SetAutomine(enabled));
+    }
+
+    /// Mines a new block every `interval` seconds, an interval of `0` disables interval mining.
+    ///
+    /// This disables automine.
+    pub fn set_interval_mining(&self, interval: u64) {
+        self.settings.lock().automine = false;
+        let _ = self.to_task.send(MiningCommand::SetIntervalMining(interval));
+    }
+
+    /// Mines the given number of blocks, regardless of the configured mining mode.
+    ///
+    /// The timestamps of the blocks are `interval` seconds apart, if set.
+    ///
+    /// Returns the hashes of the mined blocks once they are canonical, or `None` if a block could
+    /// not be mined.
+    pub async fn mine(&self, blocks: u64, interval: Option<u64>) -> Option<Vec<B256>> {
+        let (senders, receivers): (Vec<_>, Vec<_>) =
+            (0..blocks).map(|_| oneshot::channel()).unzip();
+        self.to_task.send(MiningCommand::Mine { interval, blocks: senders }).ok()?;
+
+        let mut hashes = Vec::with_capacity(receivers.len());
+        for rx in receivers {
+            hashes.push(rx.await.ok()?);
+        }
+        Some(hashes)
+    }
+
+    /// Sets the beneficiary of new blocks.
+    pub fn set_coinbase(&self, coinbase: Address) {
+        self.settings.lock().coinbase = coinbase;
+    }
+
+    /// Sets the timestamp of the next block.
+    pub fn set_next_block_timestamp(&self, timestamp: u64) {
+        self.settings.lock().next_timestamp = Some(timestamp);
+    }
+
+    /// Moves the time of new blocks forward by the given number of seconds.
+    ///
+    /// Returns the total number of seconds that are added to the current time.
+    pub fn increase_time(&self, seconds: u64) -> i64 {
+        let mut settings = self.settings.lock();
+        settings.time_offset = settings.time_offset.saturating_add_unsigned(seconds);
+        settings.time_offset
+    }
+
+    /// Sets the current time of new blocks to the given timestamp.
+    ///
+    /// Returns the number of seconds the time was moved forward.
+    pub fn set_time(&self, timestamp: u64) -> u64 {
+        let now = now();
+        self.settings.lock().time_offset = timestamp as i64 - now as i64;
+        timestamp.saturating_sub(now)
+    }
+
+    /// Sets the base fee of the next block.
+    pub fn set_next_block_base_fee(&self, base_fee: u64) {
+        self.settings.lock().next_base_fee = Some(base_fee);
+    }
+
+    /// Sets the gas limit of new blocks.
+    pub fn set_block_gas_limit(&self, gas_limit: u64) {
+        self.settings.lock().gas_limit = Some(gas_limit);
+    }
+
+    /// Sets a fixed number of seconds between the timestamps of two blocks.
+    pub fn set_block_timestamp_interval(&self, interval: u64) {
+        self.settings.lock().timestamp_interval = Some(interval);
+    }
+
+    /// Removes the fixed interval between the timestamps of two blocks.
+    ///
+    /// Returns `true` if an interval was set.
+    pub fn remove_block_timestamp_interval(&self) -> bool {
+        self.settings.lock().timestamp_interval.take().is_some()
+    }
+
+    /// Sets the `PREVRANDAO` value of the next block.
+    pub fn set_next_block_prev_randao(&self, prev_randao: B256) {
+        self.settings.lock().next_prev_randao = Some(prev_randao);
+    }
+}
+
+/// Returns the current unix timestamp in seconds.
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_block_overrides() {
+        let mut settings = MiningSettings {
+            next_timestamp: Some(100),
+            next_base_fee: Some(7),
+            gas_limit: Some(30_000_000),
+            ..Default::default()
+        };
+
+        let overrides = settings.next_block(None);
+        assert_eq!(overrides.timestamp(50), 100);
+        assert_eq!(overrides.base_fee, Some(7));
+        assert_eq!(overrides.gas_limit, Some(30_000_000));
+
+        // single block settings are reset
+        let overrides = settings.next_block(Some(12));
+        assert_eq!(overrides.timestamp(100), 112);
+        assert_eq!(overrides.base_fee, None);
+        assert_eq!(overrides.gas_limit, Some(30_000_000));
+    }
+}
@@ -19,29 +19,28 @@ use reth_beacon_consensus::BeaconEngineMessage;
 use reth_chainspec::{ChainSpec, EthereumHardforks};
 use reth_consensus::{Consensus, ConsensusError, PostExecutionInput};
 use reth_engine_primitives::EngineTypes;
-use reth_execution_errors::{BlockExecutionError, BlockValidationError};
+use reth_execution_errors::BlockExecutionError;
 use reth_execution_types::ExecutionOutcome;
 use reth_primitives::{
     eip4844::calculate_excess_blob_gas, proofs, Block, BlockBody, BlockHash, BlockHashOrNumber,
-    BlockNumber, BlockWithSenders, Bloom, Header, Requests, SealedBlock, SealedHeader,
-    TransactionSigned, Withdrawals, B256, U256,
+    BlockNumber, BlockWithSenders, Bloom, Header, Requests, SealedBlock, SealedBlockWithSenders,
+    SealedHeader, TransactionSigned, TransactionSignedEcRecovered, Withdrawals, B256, U256,
 };
 use reth_provider::{BlockReaderIdExt, StateProviderFactory, StateRootProvider};
-use reth_revm::database::StateProviderDatabase;
-use reth_transaction_pool::TransactionPool;
-use std::{
-    collections::HashMap,
-    sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
-};
+use reth_revm::{database::StateProviderDatabase, overlay::StateOverlay};
+use reth_transaction_pool::{ImpersonatedAccounts, TransactionPool};
+use std::{collections::HashMap, sync::Arc};
 use tokio::sync::{mpsc::UnboundedSender, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use tracing::trace;
 
 mod client;
+mod handle;
 mod mode;
 mod task;
 
 pub use crate::client::AutoSealClient;
+pub use handle::AutoSealHandle;
+use handle::HeaderOverrides;
 pub use mode::{FixedBlockTimeMiner, MiningMode, ReadyTransactionMiner};
 use reth_evm::execute::{BlockExecutionOutput, BlockExecutorProvider, Executor};
 pub use task::MiningTask;
@@ -105,6 +104,8 @@ pub struct AutoSealBuilder<Client, Pool, Engine: EngineTypes, EvmConfig> {
     storage: Storage,
     to_engine: UnboundedSender<BeaconEngineMessage<Engine>>,
     evm_config: EvmConfig,
+    state_overlay: StateOverlay,
+    impersonated_accounts: ImpersonatedAccounts,
 }
 
 // === impl AutoSealBuilder ===
@@ -138,6 +139,8 @@ where
             mode,
             to_engine,
             evm_config,
+            state_overlay: Default::default(),
+            impersonated_accounts: Default::default(),
         }
     }
 
@@ -147,12 +150,36 @@ where
         self
     }
 
+    /// Sets the [`StateOverlay`] whose account modifications are applied in the next mined block.
+    ///
+    /// This must be the same overlay the block executor is configured with.
+    pub fn state_overlay(mut self, state_overlay: StateOverlay) -> Self {
+        self.state_overlay = state_overlay;
+        self
+    }
+
+    /// Sets the [`ImpersonatedAccounts`] the transaction pool is configured with.
+    pub fn impersonated_accounts(mut self, impersonated_accounts: ImpersonatedAccounts) -> Self {
+        self.impersonated_accounts = impersonated_accounts;
+        self
+    }
+
     /// Consumes the type and returns all components
     #[track_caller]
     pub fn build(
         self,
     ) -> (AutoSealConsensus, AutoSealClient, MiningTask<Client, Pool, EvmConfig, Engine>) {
-        let Self { client, consensus, pool, mode, storage, to_engine, evm_config } = self;
+        let Self {
+            client,
+            consensus,
+            pool,
+            mode,
+            storage,
+            to_engine,
+            evm_config,
+            state_overlay,
+            impersonated_accounts,
+        } = self;
         let auto_client = AutoSealClient::new(storage.clone());
         let task = MiningTask::new(
             Arc::clone(&consensus.chain_spec),
@@ -162,6 +189,8 @@ where
             client,
             pool,
             evm_config,
+            state_overlay,
+            impersonated_accounts,
         );
         (consensus, auto_client, task)
     }
@@ -332,21 +361,30 @@ impl StorageInner {
 
     /// Builds and executes a new block with the given transactions, on the provided executor.
     ///
-    /// This returns the header of the executed block, as well as the poststate from execution.
+    /// The senders of the transactions are taken as given and are not recovered from the
+    /// signatures.
+    ///
+    /// This returns the executed block with its senders, as well as the poststate from execution.
     #[allow(clippy::too_many_arguments)]
     pub(crate) fn build_and_execute<Provider, Executor>(
         &mut self,
-        transactions: Vec<TransactionSigned>,
+        transactions: Vec<TransactionSignedEcRecovered>,
         ommers: Vec<Header>,
         provider: &Provider,
         chain_spec: Arc<ChainSpec>,
         executor: &Executor,
-    ) -> Result<(SealedHeader, ExecutionOutcome), BlockExecutionError>
+        overrides: HeaderOverrides,
+    ) -> Result<(SealedBlockWithSenders, ExecutionOutcome), BlockExecutionError>
     where
         Executor: BlockExecutorProvider,
         Provider: StateProviderFactory,
     {
-        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+        let (transactions, senders): (Vec<_>, Vec<_>) =
+            transactions.into_iter().map(TransactionSignedEcRecovered::to_components).unzip();
+
+        let parent_timestamp =
+            self.headers.get(&self.best_block).map(|parent| parent.timestamp).unwrap_or_default();
+        let timestamp = overrides.timestamp(parent_timestamp);
 
         // if shanghai is active, include empty withdrawals
         let withdrawals =
@@ -355,7 +393,7 @@ impl StorageInner {
         let requests =
             chain_spec.is_prague_active_at_timestamp(timestamp).then_some(Requests::default());
 
-        let header = self.build_header_template(
+        let mut header = self.build_header_template(
             timestamp,
             &transactions,
             &ommers,
@@ -364,6 +402,18 @@ impl StorageInner {
             &chain_spec,
         );
 
+        // apply the header fields configured via the handle
+        header.beneficiary = overrides.coinbase;
+        if let Some(gas_limit) = overrides.gas_limit {
+            header.gas_limit = gas_limit;
+        }
+        if let (Some(base_fee), Some(_)) = (overrides.base_fee, header.base_fee_per_gas) {
+            header.base_fee_per_gas = Some(base_fee);
+        }
+        if let Some(prev_randao) = overrides.prev_randao {
+            header.mix_hash = prev_randao;
+        }
+
         let block = Block {
             header,
             body: transactions,
@@ -371,8 +421,7 @@ impl StorageInner {
             withdrawals: withdrawals.clone(),
             requests: requests.clone(),
         }
-        .with_recovered_senders()
-        .ok_or(BlockExecutionError::Validation(BlockValidationError::SenderRecoveryError))?;
+        .with_senders_unchecked(senders);
 
         trace!(target: "consensus::auto", transactions=?&block.body, "executing transactions");
 
@@ -399,7 +448,7 @@ impl StorageInner {
         // means we need to extract the requests from the execution output and compute the requests
         // root here
 
-        let Block { mut header, body, .. } = block.block;
+        let BlockWithSenders { block: Block { mut header, body, .. }, senders } = block;
         let body = BlockBody { transactions: body, ommers, withdrawals, requests };
 
         trace!(target: "consensus::auto", ?execution_outcome, ?header, ?body, "executed block, calculating state root and completing header");
@@ -431,11 +480,14 @@ impl StorageInner {
         trace!(target: "consensus::auto", root=?header.state_root, ?body, "calculated root");
 
         // finally insert into storage
-        self.insert_new_block(header.clone(), body);
+        self.insert_new_block(header.clone(), body.clone());
 
-        // set new header with hash that should have been updated by insert_new_block
-        let new_header = header.seal(self.best_hash);
+        // seal the block with the hash that should have been updated by insert_new_block
+        let BlockBody { transactions, ommers, withdrawals, requests } = body;
+        let block = Block { header, body: transactions, ommers, withdrawals, requests }
+            .seal(self.best_hash)
+            .with_senders_unchecked(senders);
 
-        Ok((new_header, execution_outcome))
+        Ok((block, execution_outcome))
     }
 }
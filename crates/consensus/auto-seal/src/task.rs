@@ -1,23 +1,36 @@
-use crate::{mode::MiningMode, Storage};
+use crate::{
+    handle::{AutoSealHandle, MiningCommand, MiningSettings},
+    mode::MiningMode,
+    Storage,
+};
 use futures_util::{future::BoxFuture, FutureExt};
+use parking_lot::Mutex;
 use reth_beacon_consensus::{BeaconEngineMessage, ForkchoiceStatus};
+use reth_blockchain_tree_api::{BlockValidationKind, BlockchainTreeEngine};
 use reth_chainspec::ChainSpec;
 use reth_engine_primitives::EngineTypes;
 use reth_evm::execute::BlockExecutorProvider;
-use reth_primitives::IntoRecoveredTransaction;
+use reth_primitives::{IntoRecoveredTransaction, B256};
 use reth_provider::{CanonChainTracker, StateProviderFactory};
+use reth_revm::overlay::StateOverlay;
 use reth_rpc_types::engine::ForkchoiceState;
 use reth_stages_api::PipelineEvent;
 use reth_tokio_util::EventStream;
-use reth_transaction_pool::{TransactionPool, ValidPoolTransaction};
+use reth_transaction_pool::{
+    ImpersonatedAccounts, PoolTransaction, TransactionPool, ValidPoolTransaction,
+};
 use std::{
     collections::VecDeque,
     future::Future,
     pin::Pin,
     sync::Arc,
     task::{Context, Poll},
+    time::Duration,
+};
+use tokio::sync::{
+    mpsc::{UnboundedReceiver, UnboundedSender},
+    oneshot,
 };
-use tokio::sync::{mpsc::UnboundedSender, oneshot};
 use tracing::{debug, error, warn};
 
 /// A Future that listens for new ready transactions and puts new blocks into storage
@@ -34,14 +47,38 @@ pub struct MiningTask<Client, Pool: TransactionPool, Executor, Engine: EngineTyp
     storage: Storage,
     /// Pool where transactions are stored
     pool: Pool,
-    /// backlog of sets of transactions ready to be mined
-    queued: VecDeque<Vec<Arc<ValidPoolTransaction<<Pool as TransactionPool>::Transaction>>>>,
+    /// backlog of blocks ready to be mined
+    queued: VecDeque<QueuedBlock<<Pool as TransactionPool>::Transaction>>,
     // TODO: ideally this would just be a sender of hashes
     to_engine: UnboundedSender<BeaconEngineMessage<Engine>>,
     /// The pipeline events to listen on
     pipe_line_events: Option<EventStream<PipelineEvent>>,
     /// The type used for block execution
     block_executor: Executor,
+    /// Receives commands from the [`AutoSealHandle`]
+    from_handle: UnboundedReceiver<MiningCommand>,
+    /// Settings for new blocks, shared with the [`AutoSealHandle`]
+    settings: Arc<Mutex<MiningSettings>>,
+    /// Account modifications that are applied in the next block
+    state_overlay: StateOverlay,
+    /// The handle that is returned by [`MiningTask::handle`]
+    handle: AutoSealHandle,
+}
+
+/// A block that is ready to be mined.
+struct QueuedBlock<T: PoolTransaction> {
+    /// The transactions of the block
+    transactions: Vec<Arc<ValidPoolTransaction<T>>>,
+    /// The number of seconds between the timestamps of the block and its parent, if requested
+    interval: Option<u64>,
+    /// Notified with the hash of the block once it is canonical
+    on_mined: Option<oneshot::Sender<B256>>,
+}
+
+impl<T: PoolTransaction> QueuedBlock<T> {
+    const fn new(transactions: Vec<Arc<ValidPoolTransaction<T>>>) -> Self {
+        Self { transactions, interval: None, on_mined: None }
+    }
 }
 
 // === impl MiningTask ===
@@ -59,7 +96,18 @@ impl<Executor, Client, Pool: TransactionPool, Engine: EngineTypes>
         client: Client,
         pool: Pool,
         block_executor: Executor,
+        state_overlay: StateOverlay,
+        impersonated_accounts: ImpersonatedAccounts,
     ) -> Self {
+        let (to_task, from_handle) = tokio::sync::mpsc::unbounded_channel();
+        let settings =
+            Arc::new(Mutex::new(MiningSettings::new(matches!(miner, MiningMode::Auto(_)))));
+        let handle = AutoSealHandle::new(
+            to_task,
+            Arc::clone(&settings),
+            state_overlay.clone(),
+            impersonated_accounts,
+        );
         Self {
             chain_spec,
             client,
@@ -71,6 +119,10 @@ impl<Executor, Client, Pool: TransactionPool, Engine: EngineTypes>
             queued: Default::default(),
             pipe_line_events: None,
             block_executor,
+            from_handle,
+            settings,
+            state_overlay,
+            handle,
         }
     }
 
@@ -78,11 +130,51 @@ impl<Executor, Client, Pool: TransactionPool, Engine: EngineTypes>
     pub fn set_pipeline_events(&mut self, events: EventStream<PipelineEvent>) {
         self.pipe_line_events = Some(events);
     }
+
+    /// Returns a handle to control the task and the state of the dev chain.
+    pub fn handle(&self) -> AutoSealHandle {
+        self.handle.clone()
+    }
+
+    /// Applies a command received from the [`AutoSealHandle`].
+    fn on_command(&mut self, command: MiningCommand) {
+        match command {
+            MiningCommand::Mine { interval, blocks } => {
+                // only the first block includes the pending transactions, all others are empty
+                let mut transactions = Some(self.pool.best_transactions().collect::<Vec<_>>());
+                for on_mined in blocks {
+                    self.queued.push_back(QueuedBlock {
+                        transactions: transactions.take().unwrap_or_default(),
+                        interval,
+                        on_mined: Some(on_mined),
+                    });
+                }
+            }
+            MiningCommand::SetAutomine(true) => {
+                self.miner = MiningMode::instant(1, self.pool.pending_transactions_listener());
+            }
+            MiningCommand::SetAutomine(false) => {
+                if matches!(self.miner, MiningMode::Auto(_)) {
+                    self.miner = MiningMode::None;
+                }
+            }
+            MiningCommand::SetIntervalMining(0) => {
+                if matches!(self.miner, MiningMode::FixedBlockTime(_)) {
+                    self.miner = MiningMode::None;
+                }
+            }
+            MiningCommand::SetIntervalMining(interval) => {
+                self.miner = MiningMode::interval(Duration::from_secs(interval));
+            }
+        }
+        debug!(target: "consensus::auto", mode=%self.miner, "applied mining command");
+    }
 }
 
 impl<Executor, Client, Pool, Engine> Future for MiningTask<Client, Pool, Executor, Engine>
 where
-    Client: StateProviderFactory + CanonChainTracker + Clone + Unpin + 'static,
+    Client:
+        StateProviderFactory + CanonChainTracker + BlockchainTreeEngine + Clone + Unpin + 'static,
     Pool: TransactionPool + Unpin + 'static,
     <Pool as TransactionPool>::Transaction: IntoRecoveredTransaction,
     Engine: EngineTypes,
@@ -95,9 +187,13 @@ where
 
         // this drives block production and
         loop {
+            while let Poll::Ready(Some(command)) = this.from_handle.poll_recv(cx) {
+                this.on_command(command);
+            }
+
             if let Poll::Ready(transactions) = this.miner.poll(&this.pool, cx) {
                 // miner returned a set of transaction that we feed to the producer
-                this.queued.push_back(transactions);
+                this.queued.push_back(QueuedBlock::new(transactions));
             }
 
             if this.insert_task.is_none() {
@@ -108,7 +204,8 @@ where
 
                 // ready to queue in new insert task
                 let storage = this.storage.clone();
-                let transactions = this.queued.pop_front().expect("not empty");
+                let QueuedBlock { transactions, interval, on_mined } =
+                    this.queued.pop_front().expect("not empty");
 
                 let to_engine = this.to_engine.clone();
                 let client = this.client.clone();
@@ -116,46 +213,69 @@ where
                 let pool = this.pool.clone();
                 let events = this.pipe_line_events.take();
                 let executor = this.block_executor.clone();
+                let settings = Arc::clone(&this.settings);
+                let state_overlay = this.state_overlay.clone();
+                let impersonated_accounts = this.handle.impersonated_accounts().clone();
 
                 // Create the mining future that creates a block, notifies the engine that drives
                 // the pipeline
                 this.insert_task = Some(Box::pin(async move {
                     let mut storage = storage.write().await;
 
-                    let transactions: Vec<_> = transactions
-                        .into_iter()
-                        .map(|tx| {
-                            let recovered = tx.to_recovered_transaction();
-                            recovered.into_signed()
-                        })
-                        .collect();
+                    // the senders are known to the pool, transactions of impersonated accounts
+                    // can't be recovered from their signatures
+                    let transactions: Vec<_> =
+                        transactions.into_iter().map(|tx| tx.to_recovered_transaction()).collect();
                     let ommers = vec![];
 
+                    for tx in &transactions {
+                        if impersonated_accounts.is_impersonated(&tx.signer()) {
+                            state_overlay.impersonate(tx.signer());
+                        }
+                    }
+
+                    // assign the pending account modifications to the new block
+                    let block_number = storage.best_block + 1;
+                    state_overlay.seal(block_number);
+                    let overrides = settings.lock().next_block(interval);
+
                     match storage.build_and_execute(
                         transactions.clone(),
                         ommers.clone(),
                         &client,
                         chain_spec,
                         &executor,
+                        overrides,
                     ) {
-                        Ok((new_header, _bundle_state)) => {
+                        Ok((block, _bundle_state)) => {
                             // clear all transactions from pool
                             pool.remove_transactions(
                                 transactions.iter().map(|tx| tx.hash()).collect(),
                             );
 
+                            let new_header = block.header.clone();
+                            drop(storage);
+
+                            // insert the block with its senders into the tree, so the engine
+                            // doesn't need to download it and recover the senders
+                            if let Err(err) =
+                                client.insert_block(block, BlockValidationKind::Exhaustive)
+                            {
+                                error!(target: "consensus::auto", %err, "failed to insert block");
+                                return None
+                            }
+
                             let state = ForkchoiceState {
                                 head_block_hash: new_header.hash(),
                                 finalized_block_hash: new_header.hash(),
                                 safe_block_hash: new_header.hash(),
                             };
-                            drop(storage);
 
                             // TODO: make this a future
                             // await the fcu call rx for SYNCING, then wait for a VALID response
                             loop {
-                                // send the new update to the engine, this will trigger the engine
-                                // to download and execute the block we just inserted
+                                // send the new update to the engine, this will make the block we
+                                // just inserted canonical
                                 let (tx, rx) = oneshot::channel();
                                 let _ = to_engine.send(BeaconEngineMessage::ForkchoiceUpdated {
                                     state,
@@ -190,8 +310,13 @@ where
                             client.set_canonical_head(new_header.clone());
                             client.set_safe(new_header.clone());
                             client.set_finalized(new_header.clone());
+
+                            if let Some(on_mined) = on_mined {
+                                let _ = on_mined.send(new_header.hash());
+                            }
                         }
                         Err(err) => {
+                            state_overlay.unseal(block_number);
                             warn!(target: "consensus::auto", %err, "failed to execute block")
                         }
                     }
@@ -1663,7 +1663,9 @@ where
                             self.latest_valid_hash_for_invalid_payload(block.parent_hash)?
                         };
                         // keep track of the invalid header
-                        self.invalid_headers.insert(block.header);
+                        self.invalid_headers.insert(block.header.clone());
+                        self.event_sender
+                            .notify(BeaconConsensusEngineEvent::InvalidBlock(Arc::new(block)));
                         PayloadStatus::new(
                             PayloadStatusEnum::Invalid { validation_error: error.to_string() },
                             latest_valid_hash,
@@ -1772,7 +1774,9 @@ where
                             let (block, err) = err.split();
                             warn!(target: "consensus::engine", invalid_number=?block.number, invalid_hash=?block.hash(), %err, "Marking block as invalid");
 
-                            self.invalid_headers.insert(block.header);
+                            self.invalid_headers.insert(block.header.clone());
+                            self.event_sender
+                                .notify(BeaconConsensusEngineEvent::InvalidBlock(Arc::new(block)));
                         }
                     }
                 }
@@ -5,13 +5,19 @@ use reth::{
     args::{DiscoveryArgs, NetworkArgs, RpcServerArgs},
     builder::{NodeBuilder, NodeConfig, NodeHandle},
     rpc::api::eth::{helpers::AddDevSigners, FullEthApiServer},
-    tasks::TaskManager,
+    tasks::{TaskExecutor, TaskManager},
 };
 use reth_chainspec::ChainSpec;
-use reth_db::{test_utils::TempDatabase, DatabaseEnv};
+use reth_db::{
+    database::Database,
+    database_metrics::{DatabaseMetadata, DatabaseMetrics},
+    mem::MemDatabase,
+    test_utils::TempDatabase,
+    DatabaseEnv,
+};
 use reth_node_builder::{
     components::NodeComponentsBuilder, rpc::EthApiBuilderProvider, FullNodeTypesAdapter, Node,
-    NodeAdapter, NodeAddOns, RethFullAdapter,
+    NodeAdapter, NodeAddOns, RethFullAdapter, WithLaunchContext,
 };
 use reth_provider::providers::BlockchainProvider;
 use tracing::{span, Level};
@@ -50,6 +56,41 @@ where
     N: Default + Node<TmpNodeAdapter<N>>,
     <N::AddOns as NodeAddOns<Adapter<N>>>::EthApi:
         FullEthApiServer + AddDevSigners + EthApiBuilderProvider<Adapter<N>>,
+{
+    setup_with_db(num_nodes, chain_spec, is_dev, |builder, exec| builder.testing_node(exec)).await
+}
+
+/// Creates the initial setup with `num_nodes` started and interconnected, each backed by an
+/// in-memory database.
+pub async fn setup_with_mem_db<N>(
+    num_nodes: usize,
+    chain_spec: Arc<ChainSpec>,
+    is_dev: bool,
+) -> eyre::Result<(Vec<NodeHelperType<N, N::AddOns, MemDB>>, TaskManager, Wallet)>
+where
+    N: Default + Node<TmpNodeAdapter<N, MemDB>>,
+    <N::AddOns as NodeAddOns<Adapter<N, MemDB>>>::EthApi:
+        FullEthApiServer + AddDevSigners + EthApiBuilderProvider<Adapter<N, MemDB>>,
+{
+    setup_with_db(num_nodes, chain_spec, is_dev, |builder, exec| {
+        builder.testing_node_with_mem_db(exec)
+    })
+    .await
+}
+
+/// Creates the initial setup with `num_nodes` started and interconnected, using `with_db` to
+/// configure the database of each node.
+async fn setup_with_db<N, DB>(
+    num_nodes: usize,
+    chain_spec: Arc<ChainSpec>,
+    is_dev: bool,
+    with_db: impl Fn(NodeBuilder<()>, TaskExecutor) -> WithLaunchContext<NodeBuilder<DB>>,
+) -> eyre::Result<(Vec<NodeHelperType<N, N::AddOns, DB>>, TaskManager, Wallet)>
+where
+    DB: Database + DatabaseMetrics + DatabaseMetadata + Clone + Unpin + 'static,
+    N: Default + Node<TmpNodeAdapter<N, DB>>,
+    <N::AddOns as NodeAddOns<Adapter<N, DB>>>::EthApi:
+        FullEthApiServer + AddDevSigners + EthApiBuilderProvider<Adapter<N, DB>>,
 {
     let tasks = TaskManager::current();
     let exec = tasks.executor();
@@ -72,11 +113,11 @@ where
 
         let span = span!(Level::INFO, "node", idx);
         let _enter = span.enter();
-        let NodeHandle { node, node_exit_future: _ } = NodeBuilder::new(node_config.clone())
-            .testing_node(exec.clone())
-            .node(Default::default())
-            .launch()
-            .await?;
+        let NodeHandle { node, node_exit_future: _ } =
+            with_db(NodeBuilder::new(node_config.clone()), exec.clone())
+                .node(Default::default())
+                .launch()
+                .await?;
 
         let mut node = NodeTestContext::new(node).await?;
 
@@ -101,14 +142,15 @@ where
 // Type aliases
 
 type TmpDB = Arc<TempDatabase<DatabaseEnv>>;
-type TmpNodeAdapter<N> = FullNodeTypesAdapter<N, TmpDB, BlockchainProvider<TmpDB>>;
+type MemDB = Arc<MemDatabase>;
+type TmpNodeAdapter<N, DB = TmpDB> = FullNodeTypesAdapter<N, DB, BlockchainProvider<DB>>;
 
-type Adapter<N> = NodeAdapter<
-    RethFullAdapter<TmpDB, N>,
-    <<N as Node<TmpNodeAdapter<N>>>::ComponentsBuilder as NodeComponentsBuilder<
-        RethFullAdapter<TmpDB, N>,
+type Adapter<N, DB = TmpDB> = NodeAdapter<
+    RethFullAdapter<DB, N>,
+    <<N as Node<TmpNodeAdapter<N, DB>>>::ComponentsBuilder as NodeComponentsBuilder<
+        RethFullAdapter<DB, N>,
     >>::Components,
 >;
 
 /// Type alias for a type of NodeHelper
-pub type NodeHelperType<N, AO> = NodeTestContext<Adapter<N>, AO>;
+pub type NodeHelperType<N, AO, DB = TmpDB> = NodeTestContext<Adapter<N, DB>, AO>;
@@ -238,6 +238,20 @@ impl<DB: Database> PersistenceService<DB> {
         transactions_writer.prune_transactions(total_txs, block_number)?;
         header_writer.prune_headers(highest_static_file_block.saturating_sub(block_number))?;
 
+        // changesets are only moved to static files once the block is finalized, so they may
+        // not exist at all
+        for segment in [StaticFileSegment::AccountChangeSets, StaticFileSegment::StorageChangeSets]
+        {
+            if let Some(highest_changeset_block) = sf_provider
+                .get_highest_static_file_block(segment)
+                .filter(|highest_changeset_block| *highest_changeset_block > block_number)
+            {
+                sf_provider
+                    .latest_writer(segment)?
+                    .prune_changesets(highest_changeset_block - block_number)?;
+            }
+        }
+
         sf_provider.commit()?;
 
         Ok(())
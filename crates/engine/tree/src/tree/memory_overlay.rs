@@ -1,10 +1,13 @@
 use super::ExecutedBlock;
 use reth_errors::ProviderResult;
-use reth_primitives::{Account, Address, BlockNumber, Bytecode, StorageKey, StorageValue, B256};
+use reth_primitives::{
+    Account, Address, BlockNumber, Bytecode, StorageKey, StorageValue, B256, U256,
+};
 use reth_provider::{
-    AccountReader, BlockHashReader, StateProofProvider, StateProvider, StateRootProvider,
+    AccountReader, BlockHashReader, HashedStateRangeProvider, StateProofProvider, StateProvider,
+    StateRootProvider,
 };
-use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState};
+use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState, HashedRange};
 
 /// A state provider that stores references to in-memory blocks along with their state as well as
 /// the historical state provider for fallback lookups.
@@ -119,6 +122,34 @@ where
     }
 }
 
+impl<H> HashedStateRangeProvider for MemoryOverlayStateProvider<H>
+where
+    H: HashedStateRangeProvider + Send,
+{
+    fn hashed_account_range(
+        &self,
+        hashed_state: &HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedRange<Account>> {
+        let mut state = self.hashed_post_state.clone();
+        state.extend(hashed_state.clone());
+        self.historical.hashed_account_range(&state, start, limit)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_state: &HashedPostState,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedRange<U256>> {
+        let mut state = self.hashed_post_state.clone();
+        state.extend(hashed_state.clone());
+        self.historical.hashed_storage_range(&state, hashed_address, start, limit)
+    }
+}
+
 impl<H> StateProvider for MemoryOverlayStateProvider<H>
 where
     H: StateProvider + Send,
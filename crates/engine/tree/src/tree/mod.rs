@@ -302,7 +302,7 @@ pub trait EngineApiTreeHandler {
         &mut self,
         payload: ExecutionPayload,
         cancun_fields: Option<CancunPayloadFields>,
-    ) -> ProviderResult<TreeOutcome<PayloadStatus>>;
+    ) -> Result<TreeOutcome<PayloadStatus>, InsertBlockErrorKind>;
 
     /// Invoked when we receive a new forkchoice update message. Calls into the blockchain tree
     /// to resolve chain forks and ensure that the Execution Layer is working with the latest valid
@@ -1058,11 +1058,46 @@ where
                 // its missing branch first
                 return self.on_disconnected_downloaded_block(block_num_hash, missing_ancestor, head)
             }
+            Err(error) => {
+                if let Err(error) = self.on_insert_block_error(error) {
+                    warn!(target: "engine::tree", %error, "Failed to insert downloaded block");
+                }
+            }
             _ => {}
         }
         None
     }
 
+    /// Handles an error that occurred while inserting a block.
+    ///
+    /// If the block is invalid, it's marked as invalid and the payload status for it is returned.
+    /// Any other error is returned as is.
+    fn on_insert_block_error(
+        &mut self,
+        error: InsertBlockError,
+    ) -> Result<PayloadStatus, InsertBlockErrorKind> {
+        let (block, error) = error.split();
+        if !error.is_invalid_block() {
+            return Err(error)
+        }
+
+        warn!(target: "engine::tree", invalid_hash=?block.hash(), invalid_number=?block.number, %error, "Invalid block error on insert");
+        let latest_valid_hash = if error.is_block_pre_merge() {
+            // zero hash must be returned if block is pre-merge
+            Some(B256::ZERO)
+        } else {
+            self.latest_valid_hash_for_invalid_payload(block.parent_hash)?
+        };
+
+        // keep track of the invalid header
+        self.state.invalid_headers.insert(block.header.clone());
+        self.emit_event(BeaconConsensusEngineEvent::InvalidBlock(Arc::new(block)));
+        Ok(PayloadStatus::new(
+            PayloadStatusEnum::Invalid { validation_error: error.to_string() },
+            latest_valid_hash,
+        ))
+    }
+
     fn insert_block_without_senders(
         &mut self,
         block: SealedBlock,
@@ -1242,7 +1277,7 @@ where
         &mut self,
         payload: ExecutionPayload,
         cancun_fields: Option<CancunPayloadFields>,
-    ) -> ProviderResult<TreeOutcome<PayloadStatus>> {
+    ) -> Result<TreeOutcome<PayloadStatus>, InsertBlockErrorKind> {
         // Ensures that the given payload does not violate any consensus rules that concern the
         // block's layout, like:
         //    - missing or invalid base fee
@@ -1310,20 +1345,20 @@ where
             self.buffer_block_without_senders(block).unwrap();
             PayloadStatus::from_status(PayloadStatusEnum::Syncing)
         } else {
-            let mut latest_valid_hash = None;
-            let status = match self.insert_block_without_senders(block).unwrap() {
-                InsertPayloadOk::Inserted(BlockStatus::Valid(_)) |
-                InsertPayloadOk::AlreadySeen(BlockStatus::Valid(_)) => {
-                    latest_valid_hash = Some(block_hash);
-                    PayloadStatusEnum::Valid
-                }
-                InsertPayloadOk::Inserted(BlockStatus::Disconnected { .. }) |
-                InsertPayloadOk::AlreadySeen(BlockStatus::Disconnected { .. }) => {
+            match self.insert_block_without_senders(block) {
+                Ok(
+                    InsertPayloadOk::Inserted(BlockStatus::Valid(_)) |
+                    InsertPayloadOk::AlreadySeen(BlockStatus::Valid(_)),
+                ) => PayloadStatus::new(PayloadStatusEnum::Valid, Some(block_hash)),
+                Ok(
+                    InsertPayloadOk::Inserted(BlockStatus::Disconnected { .. }) |
+                    InsertPayloadOk::AlreadySeen(BlockStatus::Disconnected { .. }),
+                ) => {
                     // not known to be invalid, but we don't know anything else
-                    PayloadStatusEnum::Syncing
+                    PayloadStatus::from_status(PayloadStatusEnum::Syncing)
                 }
-            };
-            PayloadStatus::new(status, latest_valid_hash)
+                Err(error) => self.on_insert_block_error(error)?,
+            }
         };
 
         let mut outcome = TreeOutcome::new(status);
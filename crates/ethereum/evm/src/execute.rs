@@ -26,6 +26,7 @@ use reth_prune_types::PruneModes;
 use reth_revm::{
     batch::{BlockBatchRecord, BlockExecutorStats},
     db::states::bundle_state::BundleRetention,
+    overlay::{apply_state_overlay, StateOverlay},
     state_change::{apply_blockhashes_update, post_block_balance_increments},
     Evm, State,
 };
@@ -44,6 +45,8 @@ use std::sync::Arc;
 pub struct EthExecutorProvider<EvmConfig = EthEvmConfig> {
     chain_spec: Arc<ChainSpec>,
     evm_config: EvmConfig,
+    /// Irregular state changes that are applied before the transactions of a block.
+    state_overlay: Option<StateOverlay>,
 }
 
 impl EthExecutorProvider {
@@ -61,7 +64,16 @@ impl EthExecutorProvider {
 impl<EvmConfig> EthExecutorProvider<EvmConfig> {
     /// Creates a new executor provider.
     pub const fn new(chain_spec: Arc<ChainSpec>, evm_config: EvmConfig) -> Self {
-        Self { chain_spec, evm_config }
+        Self { chain_spec, evm_config, state_overlay: None }
+    }
+
+    /// Configures the [`StateOverlay`] whose changes are applied before the transactions of a
+    /// block are executed.
+    ///
+    /// This is intended for development chains only.
+    pub fn with_state_overlay(mut self, state_overlay: StateOverlay) -> Self {
+        self.state_overlay = Some(state_overlay);
+        self
     }
 }
 
@@ -73,11 +85,13 @@ where
     where
         DB: Database<Error: Into<ProviderError>>,
     {
-        EthBlockExecutor::new(
+        let mut executor = EthBlockExecutor::new(
             self.chain_spec.clone(),
             self.evm_config.clone(),
             State::builder().with_database(db).with_bundle_update().without_state_clear().build(),
-        )
+        );
+        executor.executor.state_overlay = self.state_overlay.clone();
+        executor
     }
 }
 
@@ -126,6 +140,8 @@ struct EthEvmExecutor<EvmConfig> {
     chain_spec: Arc<ChainSpec>,
     /// How to create an EVM.
     evm_config: EvmConfig,
+    /// Irregular state changes that are applied before the transactions of a block.
+    state_overlay: Option<StateOverlay>,
 }
 
 impl<EvmConfig> EthEvmExecutor<EvmConfig>
@@ -167,6 +183,16 @@ where
             block.number,
             block.parent_hash,
         )?;
+        if let Some(changes) =
+            self.state_overlay.as_ref().and_then(|overlay| overlay.changes_for_block(block.number))
+        {
+            apply_state_overlay(evm.db_mut(), &changes)?;
+        }
+        let impersonated_senders = self
+            .state_overlay
+            .as_ref()
+            .and_then(|overlay| overlay.impersonated_senders(block.number))
+            .unwrap_or_default();
 
         // execute transactions
         let mut cumulative_gas_used = 0;
@@ -184,6 +210,8 @@ where
             }
 
             self.evm_config.fill_tx_env(evm.tx_mut(), transaction, *sender);
+            // impersonated accounts of development chains may send transactions, even contracts
+            evm.cfg_mut().disable_eip3607 = impersonated_senders.contains(sender);
 
             // Execute transaction.
             let ResultAndState { result, state } = evm.transact().map_err(move |err| {
@@ -259,7 +287,7 @@ pub struct EthBlockExecutor<EvmConfig, DB> {
 impl<EvmConfig, DB> EthBlockExecutor<EvmConfig, DB> {
     /// Creates a new Ethereum block executor.
     pub const fn new(chain_spec: Arc<ChainSpec>, evm_config: EvmConfig, state: State<DB>) -> Self {
-        Self { executor: EthEvmExecutor { chain_spec, evm_config }, state }
+        Self { executor: EthEvmExecutor { chain_spec, evm_config, state_overlay: None }, state }
     }
 
     #[inline]
@@ -529,7 +557,7 @@ mod tests {
     }
 
     fn executor_provider(chain_spec: Arc<ChainSpec>) -> EthExecutorProvider<EthEvmConfig> {
-        EthExecutorProvider { chain_spec, evm_config: Default::default() }
+        EthExecutorProvider::new(chain_spec, Default::default())
     }
 
     #[test]
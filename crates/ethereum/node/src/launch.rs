@@ -110,7 +110,7 @@ where
             ctx.configs().clone(),
         )
         .launch()
-        .await;
+        .await?;
 
         // create pipeline
         let network_client = ctx.components().network().fetch_client().await?;
@@ -136,11 +136,13 @@ where
             ctx.consensus(),
             ctx.provider_factory().clone(),
             ctx.task_executor(),
+            ctx.snap_client(),
             ctx.sync_metrics_tx(),
             ctx.prune_config(),
             max_block,
             static_file_producer,
             ctx.components().block_executor().clone(),
+            ctx.components().evm_config().clone(),
             pipeline_exex_handle,
         )?;
 
@@ -178,6 +180,8 @@ where
 
         info!(target: "reth::cli", "Consensus engine initialized");
 
+        let bad_block_store = ctx.spawn_bad_block_store(beacon_engine_handle.event_listener())?;
+
         let events = stream_select!(
             ctx.components().network().event_listener().map(Into::into),
             beacon_engine_handle.event_listener().map(Into::into),
@@ -229,6 +233,10 @@ where
             engine_api,
             ctx.node_config(),
             jwt_secret,
+            ctx.consensus(),
+            ctx.bundle_pool().clone(),
+            None,
+            bad_block_store,
             rpc,
         )
         .await?;
@@ -114,7 +114,11 @@ where
     ) -> eyre::Result<(Self::EVM, Self::Executor)> {
         let chain_spec = ctx.chain_spec();
         let evm_config = EthEvmConfig::default();
-        let executor = EthExecutorProvider::new(chain_spec, evm_config);
+        let mut executor = EthExecutorProvider::new(chain_spec, evm_config);
+        if ctx.is_dev() {
+            // apply the account modifications of the `anvil_` and `hardhat_` namespaces
+            executor = executor.with_state_overlay(ctx.state_overlay().clone());
+        }
 
         Ok((evm_config, executor))
     }
@@ -140,16 +144,19 @@ where
         let data_dir = ctx.config().datadir();
         let pool_config = ctx.pool_config();
         let blob_store = DiskFileBlobStore::open(data_dir.blobstore(), Default::default())?;
-        let validator = TransactionValidationTaskExecutor::eth_builder(ctx.chain_spec())
+        let mut validator = TransactionValidationTaskExecutor::eth_builder(ctx.chain_spec())
             .with_head_timestamp(ctx.head().timestamp)
             .kzg_settings(ctx.kzg_settings()?)
             .with_local_transactions_config(pool_config.local_transactions_config.clone())
-            .with_additional_tasks(1)
-            .build_with_tasks(
-                ctx.provider().clone(),
-                ctx.task_executor().clone(),
-                blob_store.clone(),
-            );
+            .with_additional_tasks(1);
+        if ctx.is_dev() {
+            validator = validator.with_impersonated_accounts(ctx.impersonated_accounts().clone());
+        }
+        let validator = validator.build_with_tasks(
+            ctx.provider().clone(),
+            ctx.task_executor().clone(),
+            blob_store.clone(),
+        );
 
         let transaction_pool =
             reth_transaction_pool::Pool::eth_pool(validator, blob_store, pool_config);
@@ -213,8 +220,11 @@ where
         ctx: &BuilderContext<Node>,
         pool: Pool,
     ) -> eyre::Result<PayloadBuilderHandle<Node::Engine>> {
-        let payload_builder = reth_ethereum_payload_builder::EthereumPayloadBuilder::default();
         let conf = ctx.payload_builder_config();
+        let mut payload_builder = reth_ethereum_payload_builder::EthereumPayloadBuilder::default();
+        if let Some(secret_key) = conf.refund_secret_key() {
+            payload_builder = payload_builder.with_refund_secret_key(secret_key);
+        }
 
         let payload_job_config = BasicPayloadJobGeneratorConfig::default()
             .interval(conf.interval())
@@ -229,7 +239,8 @@ where
             payload_job_config,
             ctx.chain_spec(),
             payload_builder,
-        );
+        )
+        .with_bundle_provider(Arc::new(ctx.bundle_pool().clone()));
         let (payload_service, payload_builder) =
             PayloadBuilderService::new(payload_generator, ctx.provider().canonical_state_stream());
 
@@ -0,0 +1,182 @@
+use std::sync::Arc;
+
+use alloy_genesis::Genesis;
+use alloy_primitives::{address, hex, keccak256, Bytes, B256, U256};
+use futures::StreamExt;
+use reth::{
+    core::rpc::eth::helpers::EthTransactions,
+    rpc::{
+        api::DebugApiServer,
+        types::{
+            trace::geth::{BlockTraceResult, TraceResult},
+            BlockId,
+        },
+    },
+};
+use reth_chainspec::ChainSpec;
+use reth_e2e_test_utils::setup;
+use reth_provider::CanonStateSubscriptions;
+
+use crate::utils::EthNode;
+
+#[tokio::test]
+async fn can_page_account_range() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+    let chain_spec = chain_with_storage();
+    let (mut nodes, _tasks, _) = setup(1, chain_spec.clone(), true).await?;
+    let node: EthNode = nodes.pop().unwrap();
+    let debug_api = node.inner.rpc_registry.debug_api();
+    let block_id = BlockId::number(0);
+
+    // the accounts are ordered by hashed address
+    let mut hashed_addresses = [
+        address!("6Be02d1d3665660d22FF9624b7BE0551ee1Ac91b"),
+        address!("1000000000000000000000000000000000000000"),
+    ]
+    .map(keccak256);
+    hashed_addresses.sort();
+
+    // a zero limit returns up to the maximum number of accounts
+    let all =
+        debug_api.debug_account_range(block_id, Bytes::default(), 0, true, false, true).await?;
+    assert_eq!(
+        all.accounts.values().map(|account| account.address_hash).collect::<Vec<_>>(),
+        hashed_addresses
+    );
+    assert_eq!(all.next, None);
+    assert_eq!(all.root, chain_spec.genesis_header().state_root);
+
+    let storage = all
+        .accounts
+        .values()
+        .find_map(|account| account.storage.clone())
+        .expect("the contract has storage");
+    assert_eq!(
+        storage.into_iter().collect::<Vec<_>>(),
+        vec![(keccak256(B256::with_last_byte(1)), "02".to_string())]
+    );
+
+    // pages end before the next account
+    let first =
+        debug_api.debug_account_range(block_id, Bytes::default(), 1, true, true, true).await?;
+    assert_eq!(first.accounts.len(), 1);
+    assert!(first.accounts.values().all(|account| account.storage.is_none()));
+    assert_eq!(first.next, Some(hashed_addresses[1]));
+
+    let second = debug_api
+        .debug_account_range(block_id, hashed_addresses[1].into(), 1, true, true, true)
+        .await?;
+    assert_eq!(
+        second.accounts.values().map(|account| account.address_hash).collect::<Vec<_>>(),
+        vec![hashed_addresses[1]]
+    );
+    assert_eq!(second.next, None);
+
+    // the addresses of the accounts are unknown
+    let complete =
+        debug_api.debug_account_range(block_id, Bytes::default(), 1, true, true, false).await?;
+    assert!(complete.accounts.is_empty());
+    assert_eq!(complete.next, None);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn last_intermediate_root_matches_state_root() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+    let (mut nodes, _tasks, _) = setup(1, chain_with_storage(), true).await?;
+    let node: EthNode = nodes.pop().unwrap();
+    let mut notifications = node.inner.provider.canonical_state_stream();
+
+    let raw_tx = hex!("02f876820a28808477359400847735940082520894ab0840c0e43688012c1adb0f5e3fc665188f83d28a029d394a5d630544000080c080a0a044076b7e67b5deecc63f61a8d7913fab86ca365b344b5759d1fe3563b4c39ea019eab979dd000da04dfc72bb0377c092d30fd9e1cab5ae487de49586cc8b0090");
+    node.inner.rpc_registry.eth_api().send_raw_transaction(raw_tx.into()).await?;
+    let head = notifications.next().await.unwrap();
+    let tip = head.tip();
+
+    let roots = node.inner.rpc_registry.debug_api().debug_intermediate_roots(tip.hash()).await?;
+    assert_eq!(roots.len(), 1);
+    assert_eq!(roots.last(), Some(&tip.state_root));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn can_subscribe_to_trace_chain() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+    let (mut nodes, _tasks, _) = setup(1, chain_with_storage(), true).await?;
+    let node: EthNode = nodes.pop().unwrap();
+    let mut notifications = node.inner.provider.canonical_state_stream();
+
+    let raw_tx = hex!("02f876820a28808477359400847735940082520894ab0840c0e43688012c1adb0f5e3fc665188f83d28a029d394a5d630544000080c080a0a044076b7e67b5deecc63f61a8d7913fab86ca365b344b5759d1fe3563b4c39ea019eab979dd000da04dfc72bb0377c092d30fd9e1cab5ae487de49586cc8b0090");
+    let hash = node.inner.rpc_registry.eth_api().send_raw_transaction(raw_tx.into()).await?;
+    let head = notifications.next().await.unwrap();
+
+    let module = node.inner.rpc_registry.debug_api().into_rpc();
+    let mut subscription = module
+        .subscribe_unbounded("debug_subscribe", ("traceChain", "0x0", "0x1", None::<()>))
+        .await?;
+    let (result, _) = subscription.next::<BlockTraceResult>().await.unwrap()?;
+    assert_eq!(result.block, U256::from(1));
+    assert_eq!(result.hash, head.tip().hash());
+    let [TraceResult::Success { tx_hash, .. }] = result.traces.as_slice() else {
+        panic!("unexpected traces: {:?}", result.traces)
+    };
+    assert_eq!(*tx_hash, Some(hash));
+
+    // the subscription is closed once the last block has been traced
+    assert!(subscription.next::<BlockTraceResult>().await.is_none());
+
+    // unknown kinds of subscriptions are rejected
+    assert!(module.subscribe_unbounded("debug_subscribe", ("newHeads",)).await.is_err());
+
+    Ok(())
+}
+
+fn chain_with_storage() -> Arc<ChainSpec> {
+    let genesis = r#"
+{
+    "nonce": "0x42",
+    "timestamp": "0x0",
+    "extraData": "0x5343",
+    "gasLimit": "0x1388",
+    "difficulty": "0x400000000",
+    "mixHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+    "coinbase": "0x0000000000000000000000000000000000000000",
+    "alloc": {
+        "0x6Be02d1d3665660d22FF9624b7BE0551ee1Ac91b": {
+            "balance": "0x4a47e3c12448f4ad000000"
+        },
+        "0x1000000000000000000000000000000000000000": {
+            "balance": "0x0",
+            "code": "0x00",
+            "storage": {
+                "0x0000000000000000000000000000000000000000000000000000000000000001": "0x0000000000000000000000000000000000000000000000000000000000000002"
+            }
+        }
+    },
+    "number": "0x0",
+    "gasUsed": "0x0",
+    "parentHash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+    "config": {
+        "ethash": {},
+        "chainId": 2600,
+        "homesteadBlock": 0,
+        "eip150Block": 0,
+        "eip155Block": 0,
+        "eip158Block": 0,
+        "byzantiumBlock": 0,
+        "constantinopleBlock": 0,
+        "petersburgBlock": 0,
+        "istanbulBlock": 0,
+        "berlinBlock": 0,
+        "londonBlock": 0,
+        "terminalTotalDifficulty": 0,
+        "terminalTotalDifficultyPassed": true,
+        "mergeNetsplitBlock": 0,
+        "shanghaiTime": 0
+    }
+}
+"#;
+    let genesis: Genesis = serde_json::from_str(genesis).unwrap();
+    Arc::new(genesis.into())
+}
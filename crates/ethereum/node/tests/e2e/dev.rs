@@ -1,9 +1,12 @@
 use std::sync::Arc;
 
 use alloy_genesis::Genesis;
-use alloy_primitives::{b256, hex};
+use alloy_primitives::{address, b256, hex, Address, TxKind, U256};
 use futures::StreamExt;
-use reth::core::rpc::eth::helpers::EthTransactions;
+use reth::{
+    core::rpc::eth::helpers::EthTransactions,
+    rpc::{api::AnvilApiServer, types::TransactionRequest},
+};
 use reth_chainspec::ChainSpec;
 use reth_e2e_test_utils::setup;
 use reth_provider::CanonStateSubscriptions;
@@ -41,6 +44,62 @@ async fn assert_chain_advances(node: EthNode) {
     println!("mined transaction: {hash}");
 }
 
+#[tokio::test]
+async fn can_mine_impersonated_transaction() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+    let (mut nodes, _tasks, _) = setup(1, custom_chain(), true).await?;
+    let node: EthNode = nodes.pop().unwrap();
+    let mut notifications = node.inner.provider.canonical_state_stream();
+
+    // the node has no key for this funded genesis account
+    let sender = address!("6Be02d1d3665660d22FF9624b7BE0551ee1Ac91b");
+    let anvil = node.inner.rpc_registry.anvil_api().expect("anvil api is available in dev mode");
+    anvil.anvil_impersonate_account(sender).await?;
+
+    // the genesis gas limit is too low for a transfer, so build on top of a mined block
+    anvil.anvil_mine(Some(U256::from(1)), None).await?;
+    notifications.next().await.unwrap();
+
+    let recipient = Address::with_last_byte(0xaa);
+    let request = TransactionRequest {
+        from: Some(sender),
+        to: Some(TxKind::Call(recipient)),
+        value: Some(U256::from(1)),
+        max_fee_per_gas: Some(2_000_000_000),
+        max_priority_fee_per_gas: Some(2_000_000_000),
+        ..Default::default()
+    };
+    let eth_api = node.inner.rpc_registry.eth_api();
+    let hash = eth_api.send_transaction(request).await.unwrap();
+
+    let head = notifications.next().await.unwrap();
+    let tip = head.tip();
+    let (tx_sender, tx) = tip.transactions_with_sender().next().unwrap();
+    assert_eq!(tx.hash(), hash);
+    assert_eq!(*tx_sender, sender);
+    assert!(head
+        .committed()
+        .execution_outcome()
+        .receipts()
+        .iter()
+        .flatten()
+        .flatten()
+        .all(|r| r.success));
+
+    // the sender can't send any more transactions once it is no longer impersonated
+    anvil.anvil_stop_impersonating_account(sender).await?;
+    let request = TransactionRequest {
+        from: Some(sender),
+        to: Some(TxKind::Call(recipient)),
+        max_fee_per_gas: Some(2_000_000_000),
+        max_priority_fee_per_gas: Some(2_000_000_000),
+        ..Default::default()
+    };
+    assert!(eth_api.send_transaction(request).await.is_err());
+
+    Ok(())
+}
+
 fn custom_chain() -> Arc<ChainSpec> {
     let custom_genesis = r#"
 {
@@ -75,6 +134,7 @@ fn custom_chain() -> Arc<ChainSpec> {
         "londonBlock": 0,
         "terminalTotalDifficulty": 0,
         "terminalTotalDifficultyPassed": true,
+        "mergeNetsplitBlock": 0,
         "shanghaiTime": 0
     }
 }
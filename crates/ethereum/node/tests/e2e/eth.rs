@@ -7,7 +7,8 @@ use reth::{
 };
 use reth_chainspec::{ChainSpecBuilder, MAINNET};
 use reth_e2e_test_utils::{
-    node::NodeTestContext, setup, transaction::TransactionTestContext, wallet::Wallet,
+    node::NodeTestContext, setup, setup_with_mem_db, transaction::TransactionTestContext,
+    wallet::Wallet,
 };
 use reth_node_ethereum::EthereumNode;
 use std::sync::Arc;
@@ -48,6 +49,42 @@ async fn can_run_eth_node() -> eyre::Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn can_run_eth_node_with_mem_db() -> eyre::Result<()> {
+    reth_tracing::init_test_tracing();
+
+    let (mut nodes, _tasks, _wallet) = setup_with_mem_db::<EthereumNode>(
+        1,
+        Arc::new(
+            ChainSpecBuilder::default()
+                .chain(MAINNET.chain)
+                .genesis(serde_json::from_str(include_str!("../assets/genesis.json")).unwrap())
+                .cancun_activated()
+                .build(),
+        ),
+        false,
+    )
+    .await?;
+
+    let mut node = nodes.pop().unwrap();
+    let wallet = Wallet::default();
+    let raw_tx = TransactionTestContext::transfer_tx_bytes(1, wallet.inner).await;
+
+    // make the node advance
+    let tx_hash = node.rpc.inject_tx(raw_tx).await?;
+
+    // make the node advance
+    let (payload, _) = node.advance_block(vec![], eth_payload_attributes).await?;
+
+    let block_hash = payload.block().hash();
+    let block_number = payload.block().number;
+
+    // assert the block has been committed to the in-memory database
+    node.assert_new_block(tx_hash, block_hash, block_number).await?;
+
+    Ok(())
+}
+
 #[tokio::test]
 #[cfg(unix)]
 async fn can_run_eth_node_with_auth_engine_api_over_ipc() -> eyre::Result<()> {
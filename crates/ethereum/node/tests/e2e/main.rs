@@ -1,4 +1,5 @@
 mod blobs;
+mod debug;
 mod dev;
 mod eth;
 mod p2p;
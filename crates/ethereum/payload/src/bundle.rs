@@ -0,0 +1,552 @@
+//! Selection of the MEV bundles that are included at the top of a payload.
+
+use reth_chainspec::ChainSpec;
+use reth_evm::{system_calls::pre_block_beacon_root_contract_call, ConfigureEvm};
+use reth_payload_builder::{error::PayloadBuilderError, EthPayloadBuilderAttributes};
+use reth_primitives::{
+    public_key_to_address, sign_message, Address, Transaction, TransactionSigned,
+    TransactionSignedEcRecovered, TxEip1559, TxKind, B256, U256,
+};
+use reth_provider::ProviderError;
+use reth_revm::state_change::apply_blockhashes_update;
+use reth_transaction_pool::MevBundle;
+use revm::{
+    db::CacheDB,
+    primitives::{
+        BlockEnv, CfgEnvWithHandlerCfg, EVMError, EnvWithHandlerCfg, EvmState, ResultAndState,
+    },
+    Database, DatabaseCommit, DatabaseRef,
+};
+use secp256k1::{SecretKey, SECP256K1};
+use std::sync::Arc;
+use tracing::trace;
+
+/// The gas limit of a refund payout, a plain value transfer.
+const REFUND_PAYOUT_GAS: u64 = 21_000;
+
+/// Signs the refund payouts of bundles on behalf of the block's beneficiary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RefundSigner {
+    /// The secret key of the beneficiary.
+    secret_key: SecretKey,
+    /// The address of the beneficiary.
+    address: Address,
+}
+
+impl RefundSigner {
+    /// Creates a new signer for the given secret key.
+    pub(crate) fn new(secret_key: SecretKey) -> Self {
+        let address = public_key_to_address(secret_key.public_key(SECP256K1));
+        Self { secret_key, address }
+    }
+
+    /// Returns a transaction that transfers `value` to the recipient and pays no priority fee.
+    fn transfer(
+        &self,
+        chain_id: u64,
+        nonce: u64,
+        base_fee: u128,
+        to: Address,
+        value: U256,
+    ) -> Result<TransactionSignedEcRecovered, PayloadBuilderError> {
+        let transaction = Transaction::Eip1559(TxEip1559 {
+            chain_id,
+            nonce,
+            gas_limit: REFUND_PAYOUT_GAS,
+            max_fee_per_gas: base_fee,
+            max_priority_fee_per_gas: 0,
+            to: TxKind::Call(to),
+            value,
+            ..Default::default()
+        });
+        let signature =
+            sign_message(B256::from_slice(self.secret_key.as_ref()), transaction.signature_hash())
+                .map_err(PayloadBuilderError::other)?;
+        let signed = TransactionSigned::from_transaction_and_signature(transaction, signature);
+        Ok(TransactionSignedEcRecovered::from_signed_transaction(signed, self.address))
+    }
+}
+
+/// The bundles selected for inclusion at the top of a payload.
+#[derive(Debug, Default)]
+pub(crate) struct SelectedBundles {
+    /// The bundles in the order they must be included.
+    pub(crate) bundles: Vec<Arc<MevBundle>>,
+    /// The transactions of the bundles, each followed by the refund payouts of its bundle.
+    pub(crate) transactions: Vec<TransactionSignedEcRecovered>,
+    /// The total value the bundles pay to the block's beneficiary, after refunds.
+    pub(crate) profit: U256,
+}
+
+/// The outcome of a successful bundle simulation.
+#[derive(Debug)]
+struct SimulatedBundle {
+    /// The value the bundle pays to the block's beneficiary, after refunds.
+    profit: U256,
+    /// The gas used by all transactions of the bundle, including the refund payouts.
+    gas_used: u64,
+    /// The state changes of the bundle's transactions.
+    states: Vec<EvmState>,
+    /// The transactions that pay the refunds of the bundle.
+    refunds: Vec<TransactionSignedEcRecovered>,
+}
+
+/// Selects the most profitable valid bundles that can be included at the top of the block.
+///
+/// All bundles are first simulated on top of the block's pre-state to rank them by the value they
+/// pay to the beneficiary. They are then applied greedily in that order, a bundle is skipped if it
+/// is no longer valid on top of the previously selected bundles or does not fit into the block.
+///
+/// A bundle is valid if none of its transactions is invalid, only transactions that are allowed
+/// to revert do revert and it pays a non-zero value to the beneficiary after its refunds. Bundles
+/// that contain blob transactions are never selected.
+///
+/// The refunds of a bundle are paid right after its transactions by transfers from the
+/// beneficiary, so bundles that require refunds are only selected if the refund signer is the
+/// block's beneficiary. Each refund is the refunded percent of the value the bundle's other
+/// transactions pay to the beneficiary, minus the cost of the transfer.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn select_bundles<EvmConfig, DB>(
+    evm_config: &EvmConfig,
+    db: DB,
+    chain_spec: &ChainSpec,
+    initialized_cfg: &CfgEnvWithHandlerCfg,
+    initialized_block_env: &BlockEnv,
+    attributes: &EthPayloadBuilderAttributes,
+    parent_hash: B256,
+    bundles: Vec<Arc<MevBundle>>,
+    refund_signer: Option<&RefundSigner>,
+) -> Result<SelectedBundles, PayloadBuilderError>
+where
+    EvmConfig: ConfigureEvm,
+    DB: DatabaseRef<Error = ProviderError>,
+{
+    let mut selected = SelectedBundles::default();
+    let block_number = initialized_block_env.number.to::<u64>();
+    let block_gas_limit: u64 =
+        initialized_block_env.gas_limit.try_into().unwrap_or(chain_spec.max_gas_limit);
+
+    // the bundles are simulated on top of the same pre-block system calls as the payload
+    let mut pre_state = CacheDB::new(db);
+    pre_block_beacon_root_contract_call(
+        &mut pre_state,
+        evm_config,
+        chain_spec,
+        initialized_cfg,
+        initialized_block_env,
+        block_number,
+        attributes.timestamp,
+        attributes.parent_beacon_block_root,
+    )
+    .map_err(|err| PayloadBuilderError::Internal(err.into()))?;
+    apply_blockhashes_update(
+        &mut pre_state,
+        chain_spec,
+        initialized_block_env.timestamp.to::<u64>(),
+        block_number,
+        parent_hash,
+    )
+    .map_err(|err| PayloadBuilderError::Internal(err.into()))?;
+
+    // refunds can only be paid if the signer receives the value of the bundles
+    let refund_signer =
+        refund_signer.filter(|signer| signer.address == initialized_block_env.coinbase);
+
+    // rank the bundles by their profit on top of the pre-state
+    let mut ranked = Vec::with_capacity(bundles.len());
+    for bundle in bundles {
+        if bundle.requires_refund() && refund_signer.is_none() {
+            trace!(target: "payload_builder", bundle=?bundle.hash(), "skipping bundle that requires refunds the beneficiary can't pay");
+            continue
+        }
+        if bundle.transactions().iter().any(|tx| tx.transaction.is_eip4844()) {
+            trace!(target: "payload_builder", bundle=?bundle.hash(), "skipping bundle with blob transactions");
+            continue
+        }
+
+        let mut db = CacheDB::new(&pre_state);
+        if let Some(sim) = simulate_bundle(
+            evm_config,
+            &mut db,
+            chain_spec,
+            initialized_cfg,
+            initialized_block_env,
+            &bundle,
+            refund_signer,
+        )? {
+            ranked.push((sim.profit, bundle));
+        }
+    }
+    ranked.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+    // greedily apply the bundles in order of their profit
+    let mut state = CacheDB::new(&pre_state);
+    let mut cumulative_gas_used = 0;
+    for (_, bundle) in ranked {
+        let payouts = bundle
+            .refunds()
+            .iter()
+            .map(|refund| bundle.refund_recipients(refund).len() as u64)
+            .sum::<u64>();
+        if cumulative_gas_used + bundle.gas_limit() + payouts * REFUND_PAYOUT_GAS > block_gas_limit
+        {
+            trace!(target: "payload_builder", bundle=?bundle.hash(), "skipping bundle that exceeds the block gas limit");
+            continue
+        }
+
+        let mut db = CacheDB::new(&state);
+        let Some(sim) = simulate_bundle(
+            evm_config,
+            &mut db,
+            chain_spec,
+            initialized_cfg,
+            initialized_block_env,
+            &bundle,
+            refund_signer,
+        )?
+        else {
+            trace!(target: "payload_builder", bundle=?bundle.hash(), "skipping bundle that is invalid on top of the selected bundles");
+            continue
+        };
+        drop(db);
+
+        for changes in sim.states {
+            state.commit(changes);
+        }
+        cumulative_gas_used += sim.gas_used;
+        selected.profit += sim.profit;
+        selected.transactions.extend(bundle.transactions().iter().map(|tx| tx.transaction.clone()));
+        selected.transactions.extend(sim.refunds);
+        selected.bundles.push(bundle);
+    }
+
+    Ok(selected)
+}
+
+/// Simulates the bundle and the payouts of its refunds on top of the given state.
+///
+/// Returns `None` if the bundle is invalid or its refunds can't be paid.
+fn simulate_bundle<EvmConfig, DB>(
+    evm_config: &EvmConfig,
+    db: &mut CacheDB<DB>,
+    chain_spec: &ChainSpec,
+    initialized_cfg: &CfgEnvWithHandlerCfg,
+    initialized_block_env: &BlockEnv,
+    bundle: &MevBundle,
+    refund_signer: Option<&RefundSigner>,
+) -> Result<Option<SimulatedBundle>, PayloadBuilderError>
+where
+    EvmConfig: ConfigureEvm,
+    DB: DatabaseRef<Error = ProviderError>,
+{
+    let coinbase = initialized_block_env.coinbase;
+    let balance_before = db.basic(coinbase)?.map(|acc| acc.balance).unwrap_or_default();
+
+    let mut gas_used = 0;
+    let mut states = Vec::with_capacity(bundle.transactions().len());
+    // executes the transaction and returns false if it is invalid or reverted
+    let mut execute = |db: &mut CacheDB<DB>,
+                       tx: &TransactionSignedEcRecovered,
+                       can_revert: bool|
+     -> Result<bool, PayloadBuilderError> {
+        let env = EnvWithHandlerCfg::new_with_cfg_env(
+            initialized_cfg.clone(),
+            initialized_block_env.clone(),
+            evm_config.tx_env(tx),
+        );
+        let mut evm = evm_config.evm_with_env(&mut *db, env);
+
+        let ResultAndState { result, state } = match evm.transact() {
+            Ok(res) => res,
+            Err(EVMError::Transaction(_)) => return Ok(false),
+            Err(err) => return Err(PayloadBuilderError::EvmExecutionError(err)),
+        };
+        drop(evm);
+
+        if !result.is_success() && !can_revert {
+            return Ok(false)
+        }
+
+        gas_used += result.gas_used();
+        db.commit(state.clone());
+        states.push(state);
+        Ok(true)
+    };
+
+    // the value paid by transactions that receive refunds is not refundable
+    let mut refundable_value = U256::ZERO;
+    let mut balance = balance_before;
+    for (idx, tx) in bundle.transactions().iter().enumerate() {
+        if !execute(db, &tx.transaction, tx.can_revert)? {
+            return Ok(None)
+        }
+        let balance_after = db.basic(coinbase)?.map(|acc| acc.balance).unwrap_or_default();
+        if !bundle.refunds().iter().any(|refund| refund.body_idx == idx) {
+            refundable_value += balance_after.saturating_sub(balance);
+        }
+        balance = balance_after;
+    }
+
+    let mut refunds = Vec::new();
+    if bundle.requires_refund() {
+        let Some(signer) = refund_signer else { return Ok(None) };
+        let base_fee = initialized_block_env.basefee;
+        let payout_cost = U256::from(REFUND_PAYOUT_GAS) * base_fee;
+        for refund in bundle.refunds() {
+            let refund_value = refundable_value * U256::from(refund.percent) / U256::from(100);
+            for recipient in bundle.refund_recipients(refund) {
+                let value = refund_value * U256::from(recipient.percent) / U256::from(100);
+                if value <= payout_cost {
+                    trace!(target: "payload_builder", bundle=?bundle.hash(), ?value, "refund does not cover the cost of its payout");
+                    return Ok(None)
+                }
+
+                let nonce = db.basic(signer.address)?.map(|acc| acc.nonce).unwrap_or_default();
+                let payout = signer.transfer(
+                    chain_spec.chain().id(),
+                    nonce,
+                    base_fee.saturating_to(),
+                    recipient.address,
+                    value - payout_cost,
+                )?;
+                if !execute(db, &payout, false)? {
+                    return Ok(None)
+                }
+                refunds.push(payout);
+            }
+        }
+    }
+
+    let balance_after = db.basic(coinbase)?.map(|acc| acc.balance).unwrap_or_default();
+    let profit = balance_after.saturating_sub(balance_before);
+    if profit.is_zero() {
+        return Ok(None)
+    }
+
+    Ok(Some(SimulatedBundle { profit, gas_used, states, refunds }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_chainspec::ChainSpecBuilder;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_payload_builder::PayloadId;
+    use reth_primitives::{public_key_to_address, Address, Bytes, Transaction, TxEip1559, TxKind};
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_revm::database::StateProviderDatabase;
+    use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
+    use reth_transaction_pool::bundle::{BundleRefund, BundleTransaction};
+    use revm::primitives::{CfgEnv, SpecId};
+    use secp256k1::Keypair;
+
+    const BASE_FEE: u64 = 1_000_000_000;
+    const TRANSFER_GAS: u64 = 21_000;
+
+    fn attributes(suggested_fee_recipient: Address) -> EthPayloadBuilderAttributes {
+        EthPayloadBuilderAttributes {
+            id: PayloadId::new([0; 8]),
+            parent: B256::ZERO,
+            timestamp: 1_700_000_000,
+            suggested_fee_recipient,
+            prev_randao: B256::ZERO,
+            withdrawals: Default::default(),
+            parent_beacon_block_root: None,
+        }
+    }
+
+    /// Returns a transfer that pays the given priority fee.
+    fn transfer(signer: Keypair, nonce: u64, to: Address, priority_fee: u128) -> BundleTransaction {
+        let tx = Transaction::Eip1559(TxEip1559 {
+            chain_id: 1,
+            nonce,
+            gas_limit: 50_000,
+            max_fee_per_gas: BASE_FEE as u128 + priority_fee,
+            max_priority_fee_per_gas: priority_fee,
+            to: TxKind::Call(to),
+            ..Default::default()
+        });
+        let transaction = sign_tx_with_key_pair(signer, tx).into_ecrecovered().unwrap();
+        BundleTransaction { transaction, can_revert: false }
+    }
+
+    /// Returns a bundle with a single transfer that pays the given priority fee.
+    fn bundle(signer: Keypair, nonce: u64, to: Address, priority_fee: u128) -> MevBundle {
+        MevBundle::new(1, 1, vec![transfer(signer, nonce, to, priority_fee)], vec![])
+    }
+
+    #[test]
+    fn selects_valid_bundles_by_profit() {
+        let chain_spec = ChainSpecBuilder::mainnet().shanghai_activated().build();
+        let mut rng = generators::rng();
+        let keys = generators::generate_keys(&mut rng, 2);
+        let (first, second) = (keys[0], keys[1]);
+
+        let provider = MockEthProvider::default();
+        for key in &keys {
+            provider.add_account(
+                public_key_to_address(key.public_key()),
+                ExtendedAccount::new(0, U256::from(10).pow(U256::from(18))),
+            );
+        }
+        // a contract that always reverts: PUSH0 PUSH0 REVERT
+        let reverts = Address::with_last_byte(0xaa);
+        provider.add_account(
+            reverts,
+            ExtendedAccount::new(0, U256::ZERO)
+                .with_bytecode(Bytes::from_static(&[0x5f, 0x5f, 0xfd])),
+        );
+
+        let coinbase = Address::with_last_byte(0xcb);
+        let recipient = Address::with_last_byte(0xbb);
+        let cfg = CfgEnvWithHandlerCfg::new_with_spec_id(CfgEnv::default(), SpecId::SHANGHAI);
+        let block_env = BlockEnv {
+            number: U256::from(1),
+            coinbase,
+            timestamp: U256::from(1_700_000_000),
+            gas_limit: U256::from(30_000_000),
+            basefee: U256::from(BASE_FEE),
+            ..Default::default()
+        };
+        let attributes = attributes(coinbase);
+
+        let low = bundle(first, 0, recipient, 1);
+        let high = bundle(second, 0, recipient, 3);
+        // valid on its own, but conflicts with the more profitable bundle of the same sender
+        let conflicting = bundle(second, 0, recipient, 2);
+        let reverting = bundle(first, 0, reverts, 10);
+        let with_refund = MevBundle::new(
+            1,
+            1,
+            bundle(first, 0, recipient, 10).transactions().to_vec(),
+            vec![BundleRefund { body_idx: 0, percent: 10 }],
+        );
+
+        let selected = select_bundles(
+            &EthEvmConfig::default(),
+            StateProviderDatabase::new(provider),
+            &chain_spec,
+            &cfg,
+            &block_env,
+            &attributes,
+            B256::ZERO,
+            vec![low.clone(), conflicting, reverting, with_refund, high.clone()]
+                .into_iter()
+                .map(Arc::new)
+                .collect(),
+            None,
+        )
+        .unwrap();
+
+        let hashes = selected.bundles.iter().map(|bundle| bundle.hash()).collect::<Vec<_>>();
+        assert_eq!(hashes, vec![high.hash(), low.hash()]);
+        assert_eq!(selected.transactions.len(), 2);
+        assert_eq!(selected.profit, U256::from(TRANSFER_GAS * 3 + TRANSFER_GAS));
+    }
+
+    #[test]
+    fn skips_bundles_exceeding_the_gas_limit() {
+        let chain_spec = ChainSpecBuilder::mainnet().shanghai_activated().build();
+        let key = generators::generate_keys(&mut generators::rng(), 1)[0];
+        let provider = MockEthProvider::default();
+        provider.add_account(
+            public_key_to_address(key.public_key()),
+            ExtendedAccount::new(0, U256::from(10).pow(U256::from(18))),
+        );
+
+        let cfg = CfgEnvWithHandlerCfg::new_with_spec_id(CfgEnv::default(), SpecId::SHANGHAI);
+        // the gas limit of the transaction is 50k
+        let block_env = BlockEnv {
+            number: U256::from(1),
+            gas_limit: U256::from(40_000),
+            basefee: U256::from(BASE_FEE),
+            ..Default::default()
+        };
+        let attributes = attributes(Address::ZERO);
+
+        let selected = select_bundles(
+            &EthEvmConfig::default(),
+            StateProviderDatabase::new(provider),
+            &chain_spec,
+            &cfg,
+            &block_env,
+            &attributes,
+            B256::ZERO,
+            vec![Arc::new(bundle(key, 0, Address::with_last_byte(0xbb), 1))],
+            None,
+        )
+        .unwrap();
+        assert!(selected.bundles.is_empty());
+        assert!(selected.profit.is_zero());
+    }
+
+    #[test]
+    fn pays_refunds_from_the_beneficiary() {
+        const GWEI: u128 = 1_000_000_000;
+
+        let chain_spec = ChainSpecBuilder::mainnet().shanghai_activated().build();
+        let keys = generators::generate_keys(&mut generators::rng(), 3);
+        let (user, searcher, beneficiary) = (keys[0], keys[1], keys[2]);
+        let provider = MockEthProvider::default();
+        for key in [user, searcher] {
+            provider.add_account(
+                public_key_to_address(key.public_key()),
+                ExtendedAccount::new(0, U256::from(10).pow(U256::from(18))),
+            );
+        }
+
+        let coinbase = public_key_to_address(beneficiary.public_key());
+        let cfg = CfgEnvWithHandlerCfg::new_with_spec_id(CfgEnv::default(), SpecId::SHANGHAI);
+        let block_env = BlockEnv {
+            number: U256::from(1),
+            coinbase,
+            timestamp: U256::from(1_700_000_000),
+            gas_limit: U256::from(30_000_000),
+            basefee: U256::from(BASE_FEE),
+            ..Default::default()
+        };
+        let attributes = attributes(coinbase);
+
+        // the searcher backruns the user and refunds half of the value of the backrun
+        let recipient = Address::with_last_byte(0xbb);
+        let user_address = public_key_to_address(user.public_key());
+        let bundle = Arc::new(MevBundle::new(
+            1,
+            1,
+            vec![transfer(user, 0, recipient, GWEI), transfer(searcher, 0, recipient, 100 * GWEI)],
+            vec![BundleRefund { body_idx: 0, percent: 50 }],
+        ));
+
+        let select = |signer: Option<&RefundSigner>| {
+            select_bundles(
+                &EthEvmConfig::default(),
+                StateProviderDatabase::new(provider.clone()),
+                &chain_spec,
+                &cfg,
+                &block_env,
+                &attributes,
+                B256::ZERO,
+                vec![bundle.clone()],
+                signer,
+            )
+            .unwrap()
+        };
+
+        // the refund can't be paid without the key of the beneficiary
+        assert!(select(None).bundles.is_empty());
+        let other = RefundSigner::new(SecretKey::from_keypair(&searcher));
+        assert!(select(Some(&other)).bundles.is_empty());
+
+        let signer = RefundSigner::new(SecretKey::from_keypair(&beneficiary));
+        let selected = select(Some(&signer));
+        assert_eq!(selected.bundles.len(), 1);
+        assert_eq!(selected.transactions.len(), 3);
+
+        let refund = U256::from(TRANSFER_GAS as u128 * 100 * GWEI / 2);
+        let payout = &selected.transactions[2];
+        assert_eq!(payout.signer(), coinbase);
+        assert_eq!(payout.to(), Some(user_address));
+        assert_eq!(payout.value(), refund - U256::from(TRANSFER_GAS * BASE_FEE));
+        assert_eq!(selected.profit, U256::from(TRANSFER_GAS as u128 * 101 * GWEI) - refund);
+    }
+}
@@ -9,6 +9,7 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 #![allow(clippy::useless_let_if_seq)]
 
+use crate::bundle::{select_bundles, RefundSigner, SelectedBundles};
 use reth_basic_payload_builder::{
     commit_withdrawals, is_better_payload, BuildArguments, BuildOutcome, PayloadBuilder,
     PayloadConfig, WithdrawalsOutcome,
@@ -42,19 +43,33 @@ use revm::{
     primitives::{EVMError, EnvWithHandlerCfg, InvalidTransaction, ResultAndState},
     DatabaseCommit, State,
 };
+use secp256k1::SecretKey;
 use tracing::{debug, trace, warn};
 
+mod bundle;
+
 /// Ethereum payload builder
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct EthereumPayloadBuilder<EvmConfig = EthEvmConfig> {
     /// The type responsible for creating the evm.
     evm_config: EvmConfig,
+    /// Signs the refund payouts of MEV bundles, if any.
+    refund_signer: Option<RefundSigner>,
 }
 
 impl<EvmConfig> EthereumPayloadBuilder<EvmConfig> {
     /// `EthereumPayloadBuilder` constructor.
     pub const fn new(evm_config: EvmConfig) -> Self {
-        Self { evm_config }
+        Self { evm_config, refund_signer: None }
+    }
+
+    /// Sets the secret key of the fee recipient that pays the refunds of MEV bundles.
+    ///
+    /// Bundles that require refunds are only included in payloads whose fee recipient is the
+    /// address of this key.
+    pub fn with_refund_secret_key(mut self, secret_key: SecretKey) -> Self {
+        self.refund_signer = Some(RefundSigner::new(secret_key));
+        self
     }
 }
 
@@ -78,7 +93,7 @@ where
         &self,
         args: BuildArguments<Pool, Client, EthPayloadBuilderAttributes, EthBuiltPayload>,
     ) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError> {
-        default_ethereum_payload_builder(self.evm_config.clone(), args)
+        build_ethereum_payload(self.evm_config.clone(), args, self.refund_signer.as_ref())
     }
 
     fn build_empty_payload(
@@ -251,6 +266,9 @@ where
 /// Given build arguments including an Ethereum client, transaction pool,
 /// and configuration, this function creates a transaction payload. Returns
 /// a result indicating success with the payload or an error in case of failure.
+///
+/// Bundles that require refunds are never included, see
+/// [`EthereumPayloadBuilder::with_refund_secret_key`].
 #[inline]
 pub fn default_ethereum_payload_builder<EvmConfig, Pool, Client>(
     evm_config: EvmConfig,
@@ -261,12 +279,25 @@ where
     Client: StateProviderFactory,
     Pool: TransactionPool,
 {
-    let BuildArguments { client, pool, mut cached_reads, config, cancel, best_payload } = args;
+    build_ethereum_payload(evm_config, args, None)
+}
+
+/// Constructs an Ethereum transaction payload, the refunds of the included MEV bundles are signed
+/// by the given signer.
+fn build_ethereum_payload<EvmConfig, Pool, Client>(
+    evm_config: EvmConfig,
+    args: BuildArguments<Pool, Client, EthPayloadBuilderAttributes, EthBuiltPayload>,
+    refund_signer: Option<&RefundSigner>,
+) -> Result<BuildOutcome<EthBuiltPayload>, PayloadBuilderError>
+where
+    EvmConfig: ConfigureEvm,
+    Client: StateProviderFactory,
+    Pool: TransactionPool,
+{
+    let BuildArguments { client, pool, mut cached_reads, config, cancel, best_payload, bundles } =
+        args;
 
     let state_provider = client.state_by_block_hash(config.parent_block.hash())?;
-    let state = StateProviderDatabase::new(state_provider);
-    let mut db =
-        State::builder().with_database_ref(cached_reads.as_db(state)).with_bundle_update().build();
     let extra_data = config.extra_data();
     let PayloadConfig {
         initialized_block_env,
@@ -277,6 +308,27 @@ where
         ..
     } = config;
 
+    // select the bundles that are included at the top of the block
+    let selected_bundles = if bundles.is_empty() {
+        SelectedBundles::default()
+    } else {
+        select_bundles(
+            &evm_config,
+            cached_reads.as_db(StateProviderDatabase::new(&state_provider)),
+            &chain_spec,
+            &initialized_cfg,
+            &initialized_block_env,
+            &attributes,
+            parent_block.hash(),
+            bundles,
+            refund_signer,
+        )?
+    };
+
+    let state = StateProviderDatabase::new(state_provider);
+    let mut db =
+        State::builder().with_database_ref(cached_reads.as_db(state)).with_bundle_update().build();
+
     debug!(target: "payload_builder", id=%attributes.id, parent_hash = ?parent_block.hash(), parent_number = parent_block.number, "building new payload");
     let mut cumulative_gas_used = 0;
     let mut sum_blob_gas_used = 0;
@@ -326,6 +378,43 @@ where
     .map_err(|err| PayloadBuilderError::Internal(err.into()))?;
 
     let mut receipts = Vec::new();
+
+    // include the selected bundles and their refund payouts ahead of the pool transactions, they
+    // were already checked for validity on top of the same state
+    for tx in selected_bundles.transactions {
+        // check if the job was cancelled, if so we can exit early
+        if cancel.is_cancelled() {
+            return Ok(BuildOutcome::Cancelled)
+        }
+
+        let env = EnvWithHandlerCfg::new_with_cfg_env(
+            initialized_cfg.clone(),
+            initialized_block_env.clone(),
+            evm_config.tx_env(&tx),
+        );
+        let mut evm = evm_config.evm_with_env(&mut db, env);
+        let ResultAndState { result, state } =
+            evm.transact().map_err(PayloadBuilderError::EvmExecutionError)?;
+        // drop evm so db is released.
+        drop(evm);
+        // commit changes
+        db.commit(state);
+
+        cumulative_gas_used += result.gas_used();
+
+        #[allow(clippy::needless_update)] // side-effect of optimism fields
+        receipts.push(Some(Receipt {
+            tx_type: tx.tx_type(),
+            success: result.is_success(),
+            cumulative_gas_used,
+            logs: result.into_logs().into_iter().map(Into::into).collect(),
+            ..Default::default()
+        }));
+
+        executed_txs.push(tx.into_signed());
+    }
+    total_fees += selected_bundles.profit;
+
     while let Some(pool_tx) = best_txs.next() {
         // ensure we still have capacity for this transaction
         if cumulative_gas_used + pool_tx.gas_limit() > block_gas_limit {
@@ -16,21 +16,41 @@
 
 use std::{
     cmp::Reverse,
-    collections::BinaryHeap,
+    collections::{BinaryHeap, VecDeque},
+    fs::File,
     io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    marker::PhantomData,
     path::{Path, PathBuf},
+    sync::mpsc,
+    thread::JoinHandle,
 };
 
 use rayon::prelude::*;
 use reth_db_api::table::{Compress, Encode, Key, Value};
 use tempfile::{NamedTempFile, TempDir};
 
+/// Uncompressed size of the blocks in which the entries of an ETL file are compressed.
+const BLOCK_SIZE: usize = 64 * 1024;
+
+/// Maximum number of key ranges merged in parallel by [`Collector::par_iter`].
+const MAX_MERGE_PARTITIONS: usize = 8;
+
+/// Number of entries sent at once by a merge thread of [`EtlParIter`].
+const MERGE_BATCH_SIZE: usize = 1024;
+
+/// Number of batches a merge thread of [`EtlParIter`] can be ahead of the consumer.
+const MERGE_CHANNEL_CAPACITY: usize = 16;
+
 /// An ETL (extract, transform, load) data collector.
 ///
 /// Data is pushed (extract) to the collector which internally flushes the data in a sorted
 /// (transform) manner to files of some specified capacity. the data can later be iterated over
 /// (load) in a sorted manner.
 ///
+/// The files are compressed with LZ4 in blocks. A full buffer is sorted on the rayon thread pool
+/// and written to a file by a background thread while the next one is filled, so up to twice the
+/// buffer capacity can be held in memory.
+///
 /// Used mainly to insert data into `MDBX` in a sorted manner. This is important because performance
 /// and storage space degrades greatly if the data is inserted unsorted (eg. tables with hashes as
 /// keys.) as opposed to append & sorted insert. Some benchmarks can be found [here](https://github.com/paradigmxyz/reth/pull/1130#issuecomment-1418642755).
@@ -51,9 +71,12 @@ where
     /// Maximum buffer capacity in bytes, triggers flush when reached
     buffer_capacity_bytes: usize,
     /// In-memory buffer storing encoded and compressed key-value pairs
-    buffer: Vec<(<K as Encode>::Encoded, <V as Compress>::Compressed)>,
+    buffer: EtlBuffer,
+    /// Previous buffer being sorted and written to a file in the background
+    pending_flush: Option<JoinHandle<io::Result<EtlFile>>>,
     /// Total number of elements in the collector, including all files
     len: usize,
+    _marker: PhantomData<(K, V)>,
 }
 
 impl<K, V> Collector<K, V>
@@ -71,8 +94,10 @@ where
             buffer_size_bytes: 0,
             files: Vec::new(),
             buffer_capacity_bytes,
-            buffer: Vec::new(),
+            buffer: EtlBuffer::default(),
+            pending_flush: None,
             len: 0,
+            _marker: PhantomData,
         }
     }
 
@@ -88,10 +113,14 @@ where
 
     /// Clears the collector, removing all data, including the temporary directory.
     pub fn clear(&mut self) {
+        // Wait for the file being written, so that the directory can be removed
+        if let Some(pending_flush) = self.pending_flush.take() {
+            let _ = pending_flush.join();
+        }
         self.dir = None;
         // Clear vectors and free the allocated memory
         self.files = Vec::new();
-        self.buffer = Vec::new();
+        self.buffer = EtlBuffer::default();
         self.buffer_size_bytes = 0;
         self.len = 0;
     }
@@ -101,7 +130,7 @@ where
         let key = key.encode();
         let value = value.compress();
         self.buffer_size_bytes += key.as_ref().len() + value.as_ref().len();
-        self.buffer.push((key, value));
+        self.buffer.push(key.as_ref(), value.as_ref());
         if self.buffer_size_bytes > self.buffer_capacity_bytes {
             self.flush()?;
         }
@@ -127,18 +156,46 @@ where
         Ok(self.dir.as_ref().unwrap())
     }
 
+    /// Sorts the buffer and writes it to a file in the background, after waiting for the previous
+    /// buffer to be written.
     fn flush(&mut self) -> io::Result<()> {
+        self.finish_flush()?;
+
         self.buffer_size_bytes = 0;
-        self.buffer.par_sort_unstable_by(|a, b| a.0.cmp(&b.0));
-        let mut buf = Vec::with_capacity(self.buffer.len());
-        std::mem::swap(&mut buf, &mut self.buffer);
+        let mut buffer = EtlBuffer::with_capacity_of(&self.buffer);
+        std::mem::swap(&mut buffer, &mut self.buffer);
 
         let path = self.dir()?.path().to_path_buf();
-        self.files.push(EtlFile::new(path.as_path(), buf)?);
+        let pending_flush =
+            std::thread::Builder::new().name("etl-flush".to_string()).spawn(move || {
+                buffer.sort();
+                EtlFile::new(path.as_path(), &buffer)
+            })?;
+        self.pending_flush = Some(pending_flush);
 
         Ok(())
     }
 
+    /// Waits for the buffer being written in the background, if any.
+    fn finish_flush(&mut self) -> io::Result<()> {
+        if let Some(pending_flush) = self.pending_flush.take() {
+            let file = pending_flush.join().map_err(|_| {
+                io::Error::other("ETL file was not written, flush thread panicked")
+            })??;
+            self.files.push(file);
+        }
+
+        Ok(())
+    }
+
+    /// Flushes the remaining items to disk, and waits for all files to be written.
+    fn flush_all(&mut self) -> io::Result<()> {
+        if !self.buffer.is_empty() {
+            self.flush()?;
+        }
+        self.finish_flush()
+    }
+
     /// Returns an iterator over the collector data.
     ///
     /// The items of the iterator are sorted across all underlying files.
@@ -148,19 +205,168 @@ where
     /// The keys and values have been pre-encoded, meaning they *SHOULD NOT* be encoded or
     /// compressed again.
     pub fn iter(&mut self) -> std::io::Result<EtlIter<'_>> {
-        // Flush the remaining items to disk
-        if self.buffer_size_bytes > 0 {
-            self.flush()?;
+        self.flush_all()?;
+
+        EtlIter::new(&self.files, None, None)
+    }
+
+    /// Returns iterators over consecutive ranges of the collector data, which can be consumed
+    /// concurrently, e.g. by several insert workers.
+    ///
+    /// The key space is split into up to `partitions` ranges with roughly the same number of
+    /// entries. The items of each iterator are sorted, and all items of an iterator are lower than
+    /// the items of the next one.
+    ///
+    /// # Note
+    ///
+    /// The keys and values have been pre-encoded, meaning they *SHOULD NOT* be encoded or
+    /// compressed again.
+    pub fn partitions(&mut self, partitions: usize) -> std::io::Result<Vec<EtlIter<'_>>> {
+        self.flush_all()?;
+
+        // The first keys of the blocks of all files are spread like the keys of all entries.
+        let mut block_keys = self
+            .files
+            .iter()
+            .flat_map(|file| file.blocks.iter().map(|block| block.first_key.as_slice()))
+            .collect::<Vec<_>>();
+        block_keys.sort_unstable();
+        let mut split_keys = (1..partitions)
+            .filter_map(|partition| {
+                block_keys.get(partition * block_keys.len() / partitions).copied()
+            })
+            .collect::<Vec<_>>();
+        split_keys.dedup();
+
+        let starts = std::iter::once(None).chain(split_keys.iter().copied().map(Some));
+        let ends = split_keys.iter().copied().map(Some).chain(std::iter::once(None));
+        starts.zip(ends).map(|(start, end)| EtlIter::new(&self.files, start, end)).collect()
+    }
+
+    /// Returns an iterator over the collector data, which is merged by several threads in the
+    /// background.
+    ///
+    /// The key space is split as in [`Collector::partitions`], and each range is merged on its
+    /// own thread ahead of the consumer. The items of the iterator are sorted across all
+    /// underlying files, as with [`Collector::iter`].
+    ///
+    /// # Note
+    ///
+    /// The keys and values have been pre-encoded, meaning they *SHOULD NOT* be encoded or
+    /// compressed again.
+    pub fn par_iter(&mut self) -> std::io::Result<EtlParIter<'_>> {
+        let partitions = std::thread::available_parallelism()
+            .map_or(1, |parallelism| parallelism.get())
+            .min(MAX_MERGE_PARTITIONS);
+
+        let mut receivers = VecDeque::new();
+        for (index, partition) in self.partitions(partitions)?.into_iter().enumerate() {
+            let (tx, rx) = mpsc::sync_channel(MERGE_CHANNEL_CAPACITY);
+            let merge = partition.merge;
+            std::thread::Builder::new().name(format!("etl-merge-{index}")).spawn(move || {
+                let mut batch = Vec::with_capacity(MERGE_BATCH_SIZE);
+                for entry in merge {
+                    match entry {
+                        Ok(entry) => batch.push(entry),
+                        Err(err) => {
+                            let _ = tx.send(Err(err));
+                            return
+                        }
+                    }
+
+                    if batch.len() == MERGE_BATCH_SIZE {
+                        let full_batch =
+                            std::mem::replace(&mut batch, Vec::with_capacity(MERGE_BATCH_SIZE));
+                        // The iterator was dropped
+                        if tx.send(Ok(full_batch)).is_err() {
+                            return
+                        }
+                    }
+                }
+                if !batch.is_empty() {
+                    let _ = tx.send(Ok(batch));
+                }
+            })?;
+            receivers.push_back(rx);
         }
 
-        let mut heap = BinaryHeap::new();
-        for (current_id, file) in self.files.iter_mut().enumerate() {
-            if let Some((current_key, current_value)) = file.read_next()? {
-                heap.push((Reverse((current_key, current_value)), current_id));
-            }
+        Ok(EtlParIter { receivers, batch: Vec::new().into_iter(), _collector: PhantomData })
+    }
+}
+
+impl<K, V> Drop for Collector<K, V>
+where
+    K: Encode + Ord,
+    V: Compress,
+{
+    fn drop(&mut self) {
+        // Wait for the file being written, so that the directory can be removed
+        if let Some(pending_flush) = self.pending_flush.take() {
+            let _ = pending_flush.join();
         }
+    }
+}
 
-        Ok(EtlIter { heap, files: &mut self.files })
+/// In-memory buffer of a [`Collector`], storing the encoded keys and compressed values of all
+/// entries in a single allocation.
+#[derive(Debug, Default)]
+struct EtlBuffer {
+    /// Keys and values of all entries, one after another.
+    data: Vec<u8>,
+    /// Position of the key of each entry in `data`, followed by its value.
+    entries: Vec<EtlBufferEntry>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct EtlBufferEntry {
+    offset: usize,
+    key_len: u32,
+    value_len: u32,
+}
+
+impl EtlBuffer {
+    /// Creates an empty buffer with the same capacity as `other`.
+    fn with_capacity_of(other: &Self) -> Self {
+        Self {
+            data: Vec::with_capacity(other.data.capacity()),
+            entries: Vec::with_capacity(other.entries.capacity()),
+        }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn push(&mut self, key: &[u8], value: &[u8]) {
+        self.entries.push(EtlBufferEntry {
+            offset: self.data.len(),
+            key_len: key.len() as u32,
+            value_len: value.len() as u32,
+        });
+        self.data.extend_from_slice(key);
+        self.data.extend_from_slice(value);
+    }
+
+    fn key(&self, entry: &EtlBufferEntry) -> &[u8] {
+        &self.data[entry.offset..entry.offset + entry.key_len as usize]
+    }
+
+    fn value(&self, entry: &EtlBufferEntry) -> &[u8] {
+        let start = entry.offset + entry.key_len as usize;
+        &self.data[start..start + entry.value_len as usize]
+    }
+
+    /// Sorts the entries by key.
+    fn sort(&mut self) {
+        let Self { data, entries } = self;
+        let key =
+            |entry: &EtlBufferEntry| &data[entry.offset..entry.offset + entry.key_len as usize];
+        entries.par_sort_unstable_by(|a, b| key(a).cmp(key(b)));
+    }
+
+    /// Returns the entries in their current order.
+    fn iter(&self) -> impl Iterator<Item = (&[u8], &[u8])> {
+        self.entries.iter().map(|entry| (self.key(entry), self.value(entry)))
     }
 }
 
@@ -181,28 +387,60 @@ type HeapItem = (Reverse<(Vec<u8>, Vec<u8>)>, usize);
 /// managing the iteration order.
 #[derive(Debug)]
 pub struct EtlIter<'a> {
-    /// Heap managing the next items to be iterated.
-    heap: BinaryHeap<HeapItem>,
-    /// Reference to the vector of ETL files being iterated over.
-    files: &'a mut Vec<EtlFile>,
+    /// Merge of the key range of the ETL files being iterated over.
+    merge: EtlMerge,
+    /// The ETL files are owned by the collector.
+    _collector: PhantomData<&'a ()>,
 }
 
 impl<'a> EtlIter<'a> {
+    /// Creates an iterator over the entries of the files with keys in `start..end`.
+    fn new(files: &[EtlFile], start: Option<&[u8]>, end: Option<&[u8]>) -> io::Result<Self> {
+        let mut heap = BinaryHeap::new();
+        let mut readers = Vec::with_capacity(files.len());
+        for (current_id, file) in files.iter().enumerate() {
+            let mut reader = EtlFileReader::new(file, start, end)?;
+            if let Some((current_key, current_value)) = reader.read_next()? {
+                heap.push((Reverse((current_key, current_value)), current_id));
+            }
+            readers.push(reader);
+        }
+
+        Ok(Self { merge: EtlMerge { heap, readers }, _collector: PhantomData })
+    }
+
     /// Peeks into the next element
     pub fn peek(&self) -> Option<&(Vec<u8>, Vec<u8>)> {
-        self.heap.peek().map(|(Reverse(entry), _)| entry)
+        self.merge.heap.peek().map(|(Reverse(entry), _)| entry)
     }
 }
 
 impl<'a> Iterator for EtlIter<'a> {
     type Item = std::io::Result<(Vec<u8>, Vec<u8>)>;
 
+    fn next(&mut self) -> Option<Self::Item> {
+        self.merge.next()
+    }
+}
+
+/// K-way merge of the entries of several ETL files, each read with its own file handle.
+#[derive(Debug)]
+struct EtlMerge {
+    /// Heap managing the next items to be iterated.
+    heap: BinaryHeap<HeapItem>,
+    /// Readers of the ETL files being iterated over.
+    readers: Vec<EtlFileReader>,
+}
+
+impl Iterator for EtlMerge {
+    type Item = std::io::Result<(Vec<u8>, Vec<u8>)>;
+
     fn next(&mut self) -> Option<Self::Item> {
         // Get the next sorted entry from the heap
         let (Reverse(entry), id) = self.heap.pop()?;
 
         // Populate the heap with the next entry from the same file
-        match self.files[id].read_next() {
+        match self.readers[id].read_next() {
             Ok(Some((key, value))) => {
                 self.heap.push((Reverse((key, value)), id));
                 Some(Ok(entry))
@@ -213,66 +451,216 @@ impl<'a> Iterator for EtlIter<'a> {
     }
 }
 
+/// Batch of entries sent by a merge thread of [`EtlParIter`].
+type MergeBatch = Vec<(Vec<u8>, Vec<u8>)>;
+
+/// Iterator over the data of a [`Collector`], merged by several threads in the background.
+///
+/// Returned by [`Collector::par_iter`].
+#[derive(Debug)]
+pub struct EtlParIter<'a> {
+    /// Batches of entries of each key range, in ascending order of the ranges.
+    receivers: VecDeque<mpsc::Receiver<io::Result<MergeBatch>>>,
+    /// Remaining entries of the current batch.
+    batch: std::vec::IntoIter<(Vec<u8>, Vec<u8>)>,
+    /// The ETL files are owned by the collector.
+    _collector: PhantomData<&'a ()>,
+}
+
+impl<'a> Iterator for EtlParIter<'a> {
+    type Item = std::io::Result<(Vec<u8>, Vec<u8>)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(entry) = self.batch.next() {
+                return Some(Ok(entry))
+            }
+
+            match self.receivers.front()?.recv() {
+                Ok(Ok(batch)) => self.batch = batch.into_iter(),
+                Ok(Err(err)) => {
+                    self.receivers.clear();
+                    return Some(Err(err))
+                }
+                // The range is fully merged
+                Err(_) => {
+                    self.receivers.pop_front();
+                }
+            }
+        }
+    }
+}
+
 /// A temporary ETL file.
+///
+/// The entries are written in blocks of about [`BLOCK_SIZE`] bytes, each compressed with LZ4 and
+/// prefixed with its compressed size. The first key of each block is kept in memory, to read only
+/// the blocks of a key range.
 #[derive(Debug)]
 struct EtlFile {
-    file: BufReader<NamedTempFile>,
-    len: usize,
+    file: NamedTempFile,
+    blocks: Vec<EtlBlock>,
+}
+
+/// A compressed block of entries of an [`EtlFile`].
+#[derive(Debug)]
+struct EtlBlock {
+    /// Position of the block in the file.
+    offset: u64,
+    /// Key of the first entry of the block.
+    first_key: Vec<u8>,
 }
 
 impl EtlFile {
     /// Create a new file with the given data (which should be pre-sorted) at the given path.
     ///
     /// The file will be a temporary file.
-    pub(crate) fn new<K, V>(dir: &Path, buffer: Vec<(K, V)>) -> std::io::Result<Self>
-    where
-        Self: Sized,
-        K: AsRef<[u8]>,
-        V: AsRef<[u8]>,
-    {
+    fn new(dir: &Path, buffer: &EtlBuffer) -> std::io::Result<Self> {
         let file = NamedTempFile::new_in(dir)?;
         let mut w = BufWriter::new(file);
-        for entry in &buffer {
-            let k = entry.0.as_ref();
-            let v = entry.1.as_ref();
-
-            w.write_all(&k.len().to_be_bytes())?;
-            w.write_all(&v.len().to_be_bytes())?;
-            w.write_all(k)?;
-            w.write_all(v)?;
+
+        let mut blocks = Vec::new();
+        let mut offset = 0;
+        let mut block = Vec::with_capacity(BLOCK_SIZE * 2);
+        for (k, v) in buffer.iter() {
+            if block.is_empty() {
+                blocks.push(EtlBlock { offset, first_key: k.to_vec() });
+            }
+
+            block.extend_from_slice(&k.len().to_be_bytes());
+            block.extend_from_slice(&v.len().to_be_bytes());
+            block.extend_from_slice(k);
+            block.extend_from_slice(v);
+
+            if block.len() >= BLOCK_SIZE {
+                offset += Self::write_block(&mut w, &block)?;
+                block.clear();
+            }
+        }
+        if !block.is_empty() {
+            Self::write_block(&mut w, &block)?;
+        }
+
+        let file = w.into_inner()?;
+        Ok(Self { file, blocks })
+    }
+
+    /// Compresses the block and writes it, returning the number of bytes written.
+    fn write_block(w: &mut impl Write, block: &[u8]) -> std::io::Result<u64> {
+        let compressed = lz4_flex::block::compress_prepend_size(block);
+        w.write_all(&compressed.len().to_be_bytes())?;
+        w.write_all(&compressed)?;
+
+        Ok((std::mem::size_of::<usize>() + compressed.len()) as u64)
+    }
+}
+
+/// Reader of the entries of an [`EtlFile`] within a key range.
+#[derive(Debug)]
+struct EtlFileReader {
+    file: BufReader<File>,
+    /// Number of blocks left to read.
+    blocks: usize,
+    /// Decompressed block being read.
+    block: Vec<u8>,
+    /// Position of the next entry in `block`.
+    position: usize,
+    /// Inclusive start of the key range.
+    start: Option<Vec<u8>>,
+    /// Exclusive end of the key range.
+    end: Option<Vec<u8>>,
+}
+
+impl EtlFileReader {
+    fn new(file: &EtlFile, start: Option<&[u8]>, end: Option<&[u8]>) -> io::Result<Self> {
+        // The entries with the start key may be at the end of the last block starting before it,
+        // and the blocks starting at or after the end key don't have entries in the range.
+        let first_block = start.map_or(0, |start| {
+            file.blocks
+                .partition_point(|block| block.first_key.as_slice() < start)
+                .saturating_sub(1)
+        });
+        let last_block = end.map_or(file.blocks.len(), |end| {
+            file.blocks.partition_point(|block| block.first_key.as_slice() < end)
+        });
+
+        let mut reader = file.file.reopen()?;
+        if let Some(block) = file.blocks.get(first_block) {
+            reader.seek(SeekFrom::Start(block.offset))?;
         }
 
-        let mut file = BufReader::new(w.into_inner()?);
-        file.seek(SeekFrom::Start(0))?;
-        let len = buffer.len();
-        Ok(Self { file, len })
+        Ok(Self {
+            file: BufReader::new(reader),
+            blocks: last_block.saturating_sub(first_block),
+            block: Vec::new(),
+            position: 0,
+            start: start.map(<[u8]>::to_vec),
+            end: end.map(<[u8]>::to_vec),
+        })
     }
 
-    /// Read the next entry in the file.
+    /// Read the next entry in the key range.
     ///
     /// Can return error if it reaches EOF before filling the internal buffers.
-    pub(crate) fn read_next(&mut self) -> std::io::Result<Option<(Vec<u8>, Vec<u8>)>> {
-        if self.len == 0 {
-            return Ok(None)
+    fn read_next(&mut self) -> std::io::Result<Option<(Vec<u8>, Vec<u8>)>> {
+        loop {
+            if self.position == self.block.len() {
+                if self.blocks == 0 {
+                    return Ok(None)
+                }
+                self.read_block()?;
+            }
+
+            let (key, value) = self.read_entry()?;
+            if self.start.as_ref().is_some_and(|start| &key < start) {
+                continue
+            }
+            if self.end.as_ref().is_some_and(|end| &key >= end) {
+                self.blocks = 0;
+                self.position = self.block.len();
+                return Ok(None)
+            }
+
+            return Ok(Some((key, value)))
         }
+    }
+
+    /// Reads and decompresses the next block.
+    fn read_block(&mut self) -> std::io::Result<()> {
+        let mut buffer_length = [0; 8];
+        self.file.read_exact(&mut buffer_length)?;
+        let mut compressed = vec![0; usize::from_be_bytes(buffer_length)];
+        self.file.read_exact(&mut compressed)?;
+
+        self.block = lz4_flex::block::decompress_size_prepended(&compressed)
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err.to_string()))?;
+        self.position = 0;
+        self.blocks -= 1;
+
+        Ok(())
+    }
+
+    /// Reads the next entry of the current block.
+    fn read_entry(&mut self) -> std::io::Result<(Vec<u8>, Vec<u8>)> {
+        let mut block = &self.block[self.position..];
 
         let mut buffer_key_length = [0; 8];
         let mut buffer_value_length = [0; 8];
 
-        self.file.read_exact(&mut buffer_key_length)?;
-        self.file.read_exact(&mut buffer_value_length)?;
+        block.read_exact(&mut buffer_key_length)?;
+        block.read_exact(&mut buffer_value_length)?;
 
         let key_length = usize::from_be_bytes(buffer_key_length);
         let value_length = usize::from_be_bytes(buffer_value_length);
         let mut key = vec![0; key_length];
         let mut value = vec![0; value_length];
 
-        self.file.read_exact(&mut key)?;
-        self.file.read_exact(&mut value)?;
+        block.read_exact(&mut key)?;
+        block.read_exact(&mut value)?;
 
-        self.len -= 1;
+        self.position = self.block.len() - block.len();
 
-        Ok(Some((key, value)))
+        Ok((key, value))
     }
 }
 
@@ -314,4 +702,44 @@ mod tests {
         assert!(collector.is_empty());
         assert!(!temp_dir_path.exists());
     }
+
+    #[test]
+    fn etl_partitions() {
+        // Duplicate keys across files
+        let mut entries: Vec<_> = (0..50_000)
+            .map(|id| (TxHash::with_last_byte((id % 200) as u8), id as TxNumber))
+            .chain((0..50_000).map(|id| (TxHash::random(), id as TxNumber)))
+            .collect();
+
+        let mut collector = Collector::new(512 * 1024, None);
+        for (k, v) in entries.clone() {
+            collector.insert(k, v).unwrap();
+        }
+        entries.sort_by_key(|entry| entry.0);
+        let expected = entries
+            .iter()
+            .map(|(k, v)| (k.encode().to_vec(), v.compress().to_vec()))
+            .collect::<Vec<_>>();
+        let keys = |entries: &[(Vec<u8>, Vec<u8>)]| {
+            entries.iter().map(|(k, _)| k.clone()).collect::<Vec<_>>()
+        };
+
+        let iterated = collector.iter().unwrap().collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(keys(&iterated), keys(&expected));
+        let mut sorted = iterated.clone();
+        sorted.sort();
+        let mut expected_sorted = expected.clone();
+        expected_sorted.sort();
+        assert_eq!(sorted, expected_sorted);
+        assert!(collector.files.len() > 1);
+        assert!(collector.files.iter().all(|file| file.blocks.len() > 1));
+
+        let partitions = collector.partitions(4).unwrap();
+        assert_eq!(partitions.len(), 4);
+        let partitioned = partitions.into_iter().flatten().collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(partitioned, iterated);
+
+        let merged = collector.par_iter().unwrap().collect::<io::Result<Vec<_>>>().unwrap();
+        assert_eq!(merged, iterated);
+    }
 }
@@ -90,6 +90,9 @@ pub enum BlockValidationError {
     /// [EIP-2935]: https://eips.ethereum.org/EIPS/eip-2935
     #[error(transparent)]
     BlockHashAccountLoadingFailed(#[from] ProviderError),
+    /// Provider error while loading an account that is modified by a state overlay.
+    #[error("failed to load account for state overlay: {0}")]
+    StateOverlayAccountLoadingFailed(ProviderError),
     /// EVM error during withdrawal requests contract call [EIP-7002]
     ///
     /// [EIP-7002]: https://eips.ethereum.org/EIPS/eip-7002
@@ -23,6 +23,13 @@
 //! event. To clarify: if the `ExEx` emits `ExExEvent::FinishedHeight(0)` it will receive
 //! notifications for any `block_number > 0`.
 //!
+//! # Write-ahead log
+//!
+//! All notifications are written to a [`Wal`] in the data directory before they are sent to the
+//! `ExEx`'s. After a restart, every `ExEx` receives the notifications it did not process yet,
+//! starting from the last `FinishedHeight` it emitted. Notifications are removed from the log once
+//! all `ExEx`'s have emitted a `FinishedHeight` above them.
+//!
 //! [`Future`]: std::future::Future
 //! [`ExExContext`]: crate::ExExContext
 //! [`CanonStateNotification`]: reth_provider::CanonStateNotification
@@ -46,6 +53,9 @@ pub use event::*;
 mod manager;
 pub use manager::*;
 
+mod wal;
+pub use wal::*;
+
 // Re-export exex types
 #[doc(inline)]
 pub use reth_exex_types::*;
@@ -1,10 +1,10 @@
-use crate::{ExExEvent, ExExNotification, FinishedExExHeight};
+use crate::{wal::ExExCheckpoint, ExExEvent, ExExNotification, FinishedExExHeight, Wal};
 use metrics::Gauge;
 use reth_metrics::{metrics::Counter, Metrics};
 use reth_primitives::BlockNumber;
 use reth_tracing::tracing::debug;
 use std::{
-    collections::VecDeque,
+    collections::{BTreeMap, HashMap, VecDeque},
     future::{poll_fn, Future},
     pin::Pin,
     sync::{
@@ -52,6 +52,9 @@ pub struct ExExHandle {
     ///
     /// If this is `None`, the `ExEx` has not emitted a `FinishedHeight` event.
     finished_height: Option<BlockNumber>,
+    /// The ID of the first notification that was not yet delivered to the `ExEx` when it emitted
+    /// its last `FinishedHeight` event.
+    finished_notification_id: Option<usize>,
 }
 
 impl ExExHandle {
@@ -71,6 +74,7 @@ impl ExExHandle {
                 receiver: event_rx,
                 next_notification_id: 0,
                 finished_height: None,
+                finished_notification_id: None,
             },
             event_tx,
             notification_rx,
@@ -198,6 +202,9 @@ pub struct ExExManager {
     handle: ExExManagerHandle,
     /// Metrics for the `ExEx` manager.
     metrics: ExExManagerMetrics,
+
+    /// Write-ahead log of all notifications, if enabled.
+    wal: Option<Wal>,
 }
 
 impl ExExManager {
@@ -248,7 +255,39 @@ impl ExExManager {
                 finished_height: finished_height_rx,
             },
             metrics,
+
+            wal: None,
+        }
+    }
+
+    /// Create a new [`ExExManager`] that writes all notifications to the given [`Wal`].
+    ///
+    /// The notifications in the log are replayed to each `ExEx`, starting from the last
+    /// `FinishedHeight` the `ExEx` reported before the node was stopped. Notifications are removed
+    /// from the log once all `ExEx`'s have finished processing them.
+    pub fn with_wal(handles: Vec<ExExHandle>, max_capacity: usize, wal: Wal) -> eyre::Result<Self> {
+        let mut manager = Self::new(handles, max_capacity);
+
+        let (min_id, next_id) = (wal.first_id(), wal.next_id());
+        for exex in &mut manager.exex_handles {
+            exex.next_notification_id = min_id;
+            if let Some(checkpoint) = wal.checkpoint(&exex.id) {
+                debug!(exex_id = %exex.id, ?checkpoint, "Restoring exex checkpoint");
+                exex.next_notification_id = checkpoint.next_notification_id.clamp(min_id, next_id);
+                exex.finished_height = Some(checkpoint.finished_height);
+                exex.finished_notification_id = Some(checkpoint.next_notification_id);
+            }
+        }
+
+        for notification in wal.notifications() {
+            manager.buffer.push_back(notification?);
         }
+        manager.min_id = min_id;
+        manager.next_id = next_id;
+        manager.wal = Some(wal);
+        manager.update_capacity();
+
+        Ok(manager)
     }
 
     /// Returns the handle to the manager.
@@ -271,10 +310,46 @@ impl ExExManager {
 
     /// Pushes a new notification into the managers internal buffer, assigning the notification a
     /// unique ID.
-    fn push_notification(&mut self, notification: ExExNotification) {
+    ///
+    /// The notification is written to the [`Wal`] first, if enabled.
+    fn push_notification(&mut self, notification: ExExNotification) -> eyre::Result<()> {
         let next_id = self.next_id;
+        if let Some(wal) = &mut self.wal {
+            let id = wal.commit(&notification)?;
+            debug_assert_eq!(id, next_id, "WAL and manager notification IDs diverged");
+        }
         self.buffer.push_back((next_id, notification));
         self.next_id += 1;
+        Ok(())
+    }
+
+    /// Persists the checkpoints of all `ExEx`'s and removes the notifications from the [`Wal`]
+    /// that are no longer needed, if enabled.
+    ///
+    /// The log is only truncated if all `ExEx`'s have emitted a `FinishedHeight` event.
+    fn update_wal(&mut self, finished_height: Option<BlockNumber>) -> eyre::Result<()> {
+        let Some(wal) = &mut self.wal else { return Ok(()) };
+
+        let checkpoints = self
+            .exex_handles
+            .iter()
+            .filter_map(|exex| {
+                let checkpoint = ExExCheckpoint {
+                    finished_height: exex.finished_height?,
+                    next_notification_id: exex.finished_notification_id?,
+                };
+                Some((exex.id.clone(), checkpoint))
+            })
+            .collect::<BTreeMap<_, _>>();
+        let notification_id =
+            checkpoints.values().map(|checkpoint| checkpoint.next_notification_id).min();
+        wal.save_checkpoints(checkpoints)?;
+
+        if let Some((finished_height, notification_id)) = finished_height.zip(notification_id) {
+            wal.finalize(notification_id, finished_height)?;
+        }
+
+        Ok(())
     }
 }
 
@@ -290,12 +365,22 @@ impl Future for ExExManager {
                     reverted_tip = ?notification.reverted_chain().map(|chain| chain.tip().number),
                     "Received new notification"
                 );
-                self.push_notification(notification);
+                if let Err(err) = self.push_notification(notification) {
+                    return Poll::Ready(Err(err))
+                }
                 continue
             }
             break
         }
 
+        // events that are received in this poll were emitted after all notifications that were
+        // delivered before it
+        let delivered = self
+            .exex_handles
+            .iter()
+            .map(|exex| (exex.id.clone(), exex.next_notification_id))
+            .collect::<HashMap<_, _>>();
+
         // update capacity
         self.update_capacity();
 
@@ -304,16 +389,23 @@ impl Future for ExExManager {
         for idx in (0..self.exex_handles.len()).rev() {
             let mut exex = self.exex_handles.swap_remove(idx);
 
-            // it is a logic error for this to ever underflow since the manager manages the
-            // notification IDs
-            let notification_index = exex
-                .next_notification_id
-                .checked_sub(self.min_id)
-                .expect("exex expected notification ID outside the manager's range");
-            if let Some(notification) = self.buffer.get(notification_index) {
-                if let Poll::Ready(Err(err)) = exex.send(cx, notification) {
-                    // the channel was closed, which is irrecoverable for the manager
-                    return Poll::Ready(Err(err.into()))
+            // send notifications until the channel is full, so the exex is woken up again once it
+            // has capacity, which is required to drain notifications replayed from the WAL
+            loop {
+                // it is a logic error for this to ever underflow since the manager manages the
+                // notification IDs
+                let notification_index = exex
+                    .next_notification_id
+                    .checked_sub(self.min_id)
+                    .expect("exex expected notification ID outside the manager's range");
+                let Some(notification) = self.buffer.get(notification_index) else { break };
+                match exex.send(cx, notification) {
+                    Poll::Ready(Ok(())) => {}
+                    Poll::Ready(Err(err)) => {
+                        // the channel was closed, which is irrecoverable for the manager
+                        return Poll::Ready(Err(err.into()))
+                    }
+                    Poll::Pending => break,
                 }
             }
             min_id = min_id.min(exex.next_notification_id);
@@ -329,12 +421,17 @@ impl Future for ExExManager {
         self.update_capacity();
 
         // handle incoming exex events
+        let mut received_events = false;
         for exex in &mut self.exex_handles {
             while let Poll::Ready(Some(event)) = exex.receiver.poll_recv(cx) {
                 debug!(exex_id = %exex.id, ?event, "Received event from exex");
                 exex.metrics.events_sent_total.increment(1);
+                received_events = true;
                 match event {
-                    ExExEvent::FinishedHeight(height) => exex.finished_height = Some(height),
+                    ExExEvent::FinishedHeight(height) => {
+                        exex.finished_height = Some(height);
+                        exex.finished_notification_id = delivered.get(&exex.id).copied();
+                    }
                 }
             }
         }
@@ -356,6 +453,13 @@ impl Future for ExExManager {
             let _ = self.finished_height.send(FinishedExExHeight::Height(finished_height));
         }
 
+        // persist the progress of the exexs
+        if received_events {
+            if let Err(err) = self.update_wal(finished_height.ok()) {
+                return Poll::Ready(Err(err))
+            }
+        }
+
         Poll::Pending
     }
 }
@@ -481,9 +585,61 @@ impl Clone for ExExManagerHandle {
 
 #[cfg(test)]
 mod tests {
+    use super::*;
+    use reth_primitives::{Receipts, B256};
+    use reth_provider::{Chain, ExecutionOutcome};
+    use reth_revm::db::BundleState;
+    use reth_testing_utils::generators::{self, random_block};
+
     #[tokio::test]
     async fn delivers_events() {}
 
+    #[tokio::test]
+    async fn replays_wal() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut rng = generators::rng();
+        let notifications = (0..3)
+            .map(|number| {
+                let block = random_block(&mut rng, number, Some(B256::ZERO), Some(0), None)
+                    .seal_with_senders()
+                    .unwrap();
+                let receipts = Receipts { receipt_vec: vec![Vec::new()] };
+                let execution_outcome =
+                    ExecutionOutcome::new(BundleState::default(), receipts, number, Vec::new());
+                ExExNotification::ChainCommitted {
+                    new: Arc::new(Chain::from_block(block, execution_outcome, None)),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut wal = Wal::new(dir.path()).unwrap();
+        for notification in &notifications {
+            wal.commit(notification).unwrap();
+        }
+        // the exex finished block 0 before the notification for block 1 was delivered
+        let checkpoint = ExExCheckpoint { finished_height: 0, next_notification_id: 1 };
+        wal.save_checkpoints(BTreeMap::from([("test".to_string(), checkpoint)])).unwrap();
+
+        let (handle, events, mut rx) = ExExHandle::new("test".to_string());
+        let manager =
+            ExExManager::with_wal(vec![handle], 10, Wal::new(dir.path()).unwrap()).unwrap();
+        let mut finished_height = manager.handle().finished_height();
+        tokio::spawn(manager);
+
+        assert_eq!(rx.recv().await.unwrap(), notifications[1]);
+        assert_eq!(rx.recv().await.unwrap(), notifications[2]);
+
+        // the log is truncated once the exex finished all blocks
+        events.send(ExExEvent::FinishedHeight(2)).unwrap();
+        finished_height
+            .wait_for(|height| matches!(height, FinishedExExHeight::Height(2)))
+            .await
+            .unwrap();
+        let wal = Wal::new(dir.path()).unwrap();
+        assert!(wal.is_empty());
+        assert_eq!(wal.checkpoint("test").map(|checkpoint| checkpoint.finished_height), Some(2));
+    }
+
     #[tokio::test]
     async fn capacity() {}
 
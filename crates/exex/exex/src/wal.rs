@@ -0,0 +1,403 @@
+//! Write-ahead log of [`ExExNotification`]s.
+//!
+//! Every notification that is received by the [`ExExManager`](crate::ExExManager) is written to
+//! the log before it is delivered, so the notifications that an `ExEx` did not finish processing
+//! can be replayed after a restart. Notifications are only removed from the log once all `ExEx`'s
+//! have reported a `FinishedHeight` above them.
+
+use crate::ExExNotification;
+use reth_primitives::{BlockNumHash, BlockNumber};
+use reth_provider::{BlockHashReader, Chain, ChainSplit, ChainSplitTarget};
+use reth_tracing::tracing::{debug, info};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// File extension of the files that contain a single notification.
+const NOTIFICATION_FILE_EXTENSION: &str = "wal";
+
+/// Name of the file that contains the [`ExExCheckpoint`]s.
+const CHECKPOINTS_FILE_NAME: &str = "checkpoints.json";
+
+/// The progress of an `ExEx` that is persisted alongside the [`Wal`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExExCheckpoint {
+    /// The last `FinishedHeight` reported by the `ExEx`.
+    pub finished_height: BlockNumber,
+    /// The ID of the first notification that was not yet delivered to the `ExEx` when it reported
+    /// the finished height.
+    pub next_notification_id: usize,
+}
+
+/// A write-ahead log that stores [`ExExNotification`]s on disk.
+///
+/// Each notification is stored in a separate file in the directory of the log, named after the ID
+/// of the notification. IDs are monotonically increasing and match the IDs the
+/// [`ExExManager`](crate::ExExManager) assigns to the notifications.
+#[derive(Debug)]
+pub struct Wal {
+    /// The directory that contains the log.
+    directory: PathBuf,
+    /// The highest block number of each notification in the log, by notification ID.
+    entries: BTreeMap<usize, BlockNumber>,
+    /// The ID of the next notification.
+    next_id: usize,
+    /// The checkpoints of all `ExEx`'s, by `ExEx` ID.
+    checkpoints: BTreeMap<String, ExExCheckpoint>,
+}
+
+impl Wal {
+    /// Opens the log in the given directory, creating the directory if it does not exist.
+    pub fn new(directory: impl Into<PathBuf>) -> eyre::Result<Self> {
+        let directory = directory.into();
+        reth_fs_util::create_dir_all(&directory)?;
+
+        let mut entries = BTreeMap::new();
+        for entry in reth_fs_util::read_dir(&directory)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some(NOTIFICATION_FILE_EXTENSION) {
+                continue
+            }
+            let Some(id) =
+                path.file_stem().and_then(|stem| stem.to_str()).and_then(|stem| stem.parse().ok())
+            else {
+                continue
+            };
+            entries.insert(id, highest_block(&read_notification(&path)?));
+        }
+
+        let checkpoints_path = directory.join(CHECKPOINTS_FILE_NAME);
+        let checkpoints = if checkpoints_path.exists() {
+            reth_fs_util::read_json_file(&checkpoints_path)?
+        } else {
+            BTreeMap::new()
+        };
+
+        // the log can be empty after it was finalized, in which case the checkpoints still
+        // reference the IDs of the removed notifications
+        let next_id = entries
+            .last_key_value()
+            .map(|(id, _)| id + 1)
+            .into_iter()
+            .chain(
+                checkpoints
+                    .values()
+                    .map(|checkpoint: &ExExCheckpoint| checkpoint.next_notification_id),
+            )
+            .max()
+            .unwrap_or_default();
+        debug!(target: "exex::wal", ?directory, notifications = entries.len(), next_id, "Opened WAL");
+
+        Ok(Self { directory, entries, next_id, checkpoints })
+    }
+
+    /// Returns the directory of the log.
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// Returns the number of notifications in the log.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the log contains no notifications.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the ID of the oldest notification in the log, or the ID of the next notification
+    /// if the log is empty.
+    pub fn first_id(&self) -> usize {
+        self.entries.first_key_value().map_or(self.next_id, |(id, _)| *id)
+    }
+
+    /// Returns the ID that is assigned to the next notification.
+    pub const fn next_id(&self) -> usize {
+        self.next_id
+    }
+
+    /// Returns the persisted checkpoint of the `ExEx` with the given ID, if any.
+    pub fn checkpoint(&self, exex_id: &str) -> Option<ExExCheckpoint> {
+        self.checkpoints.get(exex_id).copied()
+    }
+
+    /// Appends the notification to the log and returns its ID.
+    pub fn commit(&mut self, notification: &ExExNotification) -> eyre::Result<usize> {
+        let id = self.next_id;
+        let path = self.notification_path(id);
+        let tmp_path = path.with_extension("tmp");
+
+        let mut writer = BufWriter::new(reth_fs_util::create_file(&tmp_path)?);
+        rmp_serde::encode::write_named(&mut writer, notification)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        reth_fs_util::rename(&tmp_path, &path)?;
+
+        self.entries.insert(id, highest_block(notification));
+        self.next_id += 1;
+        debug!(target: "exex::wal", id, "Committed notification to WAL");
+
+        Ok(id)
+    }
+
+    /// Reads the notification with the given ID.
+    pub fn read(&self, id: usize) -> eyre::Result<ExExNotification> {
+        read_notification(&self.notification_path(id))
+    }
+
+    /// Returns an iterator over all notifications in the log, from the oldest to the newest.
+    pub fn notifications(
+        &self,
+    ) -> impl Iterator<Item = eyre::Result<(usize, ExExNotification)>> + '_ {
+        self.entries.keys().map(|id| Ok((*id, self.read(*id)?)))
+    }
+
+    /// Persists the checkpoints of all `ExEx`'s, replacing the previous checkpoints.
+    pub fn save_checkpoints(
+        &mut self,
+        checkpoints: BTreeMap<String, ExExCheckpoint>,
+    ) -> eyre::Result<()> {
+        if checkpoints == self.checkpoints {
+            return Ok(())
+        }
+
+        let path = self.directory.join(CHECKPOINTS_FILE_NAME);
+        let tmp_path = path.with_extension("tmp");
+        reth_fs_util::write_json_file(&tmp_path, &checkpoints)?;
+        reth_fs_util::rename(&tmp_path, &path)?;
+        self.checkpoints = checkpoints;
+
+        Ok(())
+    }
+
+    /// Removes the oldest notifications that are no longer needed by any `ExEx`.
+    ///
+    /// A notification is removed if its ID is lower than `notification_id` and it does not
+    /// contain any blocks above `finished_height`. Removal stops at the first notification that
+    /// is still needed, so the log always stays contiguous.
+    ///
+    /// Returns the number of removed notifications.
+    pub fn finalize(
+        &mut self,
+        notification_id: usize,
+        finished_height: BlockNumber,
+    ) -> eyre::Result<usize> {
+        let mut removed = 0;
+        while let Some((&id, &highest_block)) = self.entries.first_key_value() {
+            if id >= notification_id || highest_block > finished_height {
+                break
+            }
+            reth_fs_util::remove_file(self.notification_path(id))?;
+            self.entries.remove(&id);
+            removed += 1;
+        }
+
+        if removed > 0 {
+            debug!(target: "exex::wal", removed, first_id = self.first_id(), "Finalized WAL");
+        }
+
+        Ok(removed)
+    }
+
+    /// Appends the notifications that are required to bring the log back in line with the
+    /// canonical chain of the given provider.
+    ///
+    /// The canonical chain can diverge from the log if blocks were unwound while the node was
+    /// down. Starting from the newest notification, every notification whose chain is no longer
+    /// canonical is undone by committing its inverse, until the tip of the log is canonical again.
+    ///
+    /// Returns the number of appended notifications.
+    pub fn revert_non_canonical<P: BlockHashReader>(
+        &mut self,
+        provider: &P,
+    ) -> eyre::Result<usize> {
+        let is_canonical = |block: BlockNumHash| -> eyre::Result<bool> {
+            Ok(provider.block_hash(block.number)? == Some(block.hash))
+        };
+
+        let mut inverse = Vec::new();
+        for id in self.entries.keys().rev() {
+            let notification = self.read(*id)?;
+            let tip = match &notification {
+                ExExNotification::ChainCommitted { new } |
+                ExExNotification::ChainReorged { new, .. } => new.tip().num_hash(),
+                ExExNotification::ChainReverted { old } => {
+                    let fork_block = old.fork_block();
+                    BlockNumHash::new(fork_block.number, fork_block.hash)
+                }
+            };
+            if is_canonical(tip)? {
+                break
+            }
+
+            // if part of the committed chain is still canonical, only the blocks above it have to
+            // be reverted and the log is in line with the canonical chain afterwards
+            if let Some(new) = notification.committed_chain() {
+                let mut canonical_tip = None;
+                for block in new.blocks_iter() {
+                    if !is_canonical(block.num_hash())? {
+                        break
+                    }
+                    canonical_tip = Some(block.number);
+                }
+                if let Some(canonical_tip) = canonical_tip {
+                    let old = split_above(&new, canonical_tip);
+                    inverse.push(ExExNotification::ChainReverted { old: Arc::new(old) });
+                    break
+                }
+            }
+
+            inverse.push(match notification {
+                ExExNotification::ChainCommitted { new } => {
+                    ExExNotification::ChainReverted { old: new }
+                }
+                ExExNotification::ChainReorged { old, new } => {
+                    ExExNotification::ChainReorged { old: new, new: old }
+                }
+                ExExNotification::ChainReverted { old } => {
+                    ExExNotification::ChainCommitted { new: old }
+                }
+            });
+        }
+
+        for notification in &inverse {
+            self.commit(notification)?;
+        }
+        if !inverse.is_empty() {
+            info!(target: "exex::wal", notifications = inverse.len(), "Reverted non-canonical blocks in WAL");
+        }
+
+        Ok(inverse.len())
+    }
+
+    /// Returns the path of the file that contains the notification with the given ID.
+    fn notification_path(&self, id: usize) -> PathBuf {
+        self.directory.join(format!("{id}.{NOTIFICATION_FILE_EXTENSION}"))
+    }
+}
+
+/// Reads the notification that is stored in the given file.
+fn read_notification(path: &Path) -> eyre::Result<ExExNotification> {
+    let reader = BufReader::new(File::open(path)?);
+    Ok(rmp_serde::decode::from_read(reader)?)
+}
+
+/// Returns the highest block number that is committed or reverted by the notification.
+fn highest_block(notification: &ExExNotification) -> BlockNumber {
+    let committed = notification.committed_chain().map(|chain| chain.tip().number);
+    let reverted = notification.reverted_chain().map(|chain| chain.tip().number);
+    committed.max(reverted).unwrap_or_default()
+}
+
+/// Returns the part of the chain above the given block number.
+///
+/// The block number must be in the range of the chain, excluding its tip.
+fn split_above(chain: &Chain, block_number: BlockNumber) -> Chain {
+    match chain.clone().split(ChainSplitTarget::Number(block_number)) {
+        ChainSplit::Split { pending, .. } => pending,
+        ChainSplit::NoSplitPending(chain) | ChainSplit::NoSplitCanonical(chain) => chain,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Receipts, SealedBlockWithSenders, B256};
+    use reth_provider::{test_utils::MockEthProvider, ExecutionOutcome};
+    use reth_revm::db::BundleState;
+    use reth_testing_utils::generators::{self, random_block_range};
+
+    fn chain(blocks: &[SealedBlockWithSenders]) -> Arc<Chain> {
+        let receipts = Receipts { receipt_vec: vec![Vec::new(); blocks.len()] };
+        let execution_outcome =
+            ExecutionOutcome::new(BundleState::default(), receipts, blocks[0].number, Vec::new());
+        Arc::new(Chain::new(blocks.to_vec(), execution_outcome, None))
+    }
+
+    fn blocks(range: std::ops::RangeInclusive<BlockNumber>) -> Vec<SealedBlockWithSenders> {
+        let mut rng = generators::rng();
+        random_block_range(&mut rng, range, B256::ZERO, 0..2)
+            .into_iter()
+            .map(|block| block.seal_with_senders().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn commit_and_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let blocks = blocks(0..=3);
+
+        let mut wal = Wal::new(dir.path()).unwrap();
+        let first = ExExNotification::ChainCommitted { new: chain(&blocks[..2]) };
+        let second = ExExNotification::ChainCommitted { new: chain(&blocks[2..]) };
+        assert_eq!(wal.commit(&first).unwrap(), 0);
+        assert_eq!(wal.commit(&second).unwrap(), 1);
+
+        let checkpoint = ExExCheckpoint { finished_height: 1, next_notification_id: 1 };
+        wal.save_checkpoints(BTreeMap::from([("exex".to_string(), checkpoint)])).unwrap();
+
+        let wal = Wal::new(dir.path()).unwrap();
+        assert_eq!(wal.len(), 2);
+        assert_eq!(wal.next_id(), 2);
+        assert_eq!(wal.checkpoint("exex"), Some(checkpoint));
+        let notifications = wal.notifications().collect::<eyre::Result<Vec<_>>>().unwrap();
+        assert_eq!(notifications, vec![(0, first), (1, second)]);
+    }
+
+    #[test]
+    fn finalize() {
+        let dir = tempfile::tempdir().unwrap();
+        let blocks = blocks(0..=3);
+
+        let mut wal = Wal::new(dir.path()).unwrap();
+        for block in &blocks {
+            wal.commit(&ExExNotification::ChainCommitted {
+                new: chain(std::slice::from_ref(block)),
+            })
+            .unwrap();
+        }
+
+        // the notification for block 2 was not delivered yet
+        assert_eq!(wal.finalize(2, 3).unwrap(), 2);
+        assert_eq!(wal.first_id(), 2);
+
+        // block 3 was not processed yet
+        assert_eq!(wal.finalize(4, 2).unwrap(), 1);
+        assert_eq!(wal.first_id(), 3);
+
+        let wal = Wal::new(dir.path()).unwrap();
+        assert_eq!(wal.len(), 1);
+        assert_eq!(wal.next_id(), 4);
+    }
+
+    #[test]
+    fn revert_non_canonical() {
+        let dir = tempfile::tempdir().unwrap();
+        let blocks = blocks(0..=4);
+
+        let mut wal = Wal::new(dir.path()).unwrap();
+        wal.commit(&ExExNotification::ChainCommitted { new: chain(&blocks[..2]) }).unwrap();
+        wal.commit(&ExExNotification::ChainCommitted { new: chain(&blocks[2..]) }).unwrap();
+
+        // blocks above 2 were unwound
+        let provider = MockEthProvider::default();
+        provider.extend_blocks(
+            blocks[..3].iter().map(|block| (block.hash(), block.block.clone().unseal())),
+        );
+
+        assert_eq!(wal.revert_non_canonical(&provider).unwrap(), 1);
+        let ExExNotification::ChainReverted { old } = wal.read(2).unwrap() else {
+            panic!("expected revert")
+        };
+        assert_eq!(old.range(), 3..=4);
+
+        // the log is in line with the canonical chain
+        assert_eq!(wal.revert_non_canonical(&provider).unwrap(), 0);
+    }
+}
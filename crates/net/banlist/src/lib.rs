@@ -10,7 +10,18 @@
 
 type PeerId = alloy_primitives::B512;
 
-use std::{collections::HashMap, net::IpAddr, time::Instant};
+use std::{
+    collections::HashMap,
+    fmt,
+    net::IpAddr,
+    str::FromStr,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+#[cfg(feature = "serde")]
+use serde_with::{DeserializeFromStr, SerializeDisplay};
+
+pub use ipnet::IpNet;
 
 /// Determines whether or not the IP is globally routable.
 /// Should be replaced with [`IpAddr::is_global`](std::net::IpAddr::is_global) once it is stable.
@@ -25,12 +36,93 @@ pub const fn is_global(ip: &IpAddr) -> bool {
     }
 }
 
+/// A peer or a range of IP addresses that can be banned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(SerializeDisplay, DeserializeFromStr))]
+pub enum BanTarget {
+    /// A peer, by its [`PeerId`].
+    Peer(PeerId),
+    /// A range of IP addresses, a single address is a network with the maximum prefix length.
+    Ip(IpNet),
+}
+
+impl From<PeerId> for BanTarget {
+    fn from(peer_id: PeerId) -> Self {
+        Self::Peer(peer_id)
+    }
+}
+
+impl From<IpAddr> for BanTarget {
+    fn from(ip: IpAddr) -> Self {
+        Self::Ip(ip.into())
+    }
+}
+
+impl From<IpNet> for BanTarget {
+    fn from(network: IpNet) -> Self {
+        Self::Ip(network)
+    }
+}
+
+impl fmt::Display for BanTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Peer(peer_id) => write!(f, "{peer_id}"),
+            Self::Ip(network) if is_host(network) => write!(f, "{}", network.addr()),
+            Self::Ip(network) => write!(f, "{network}"),
+        }
+    }
+}
+
+/// Error when parsing a [`BanTarget`]
+#[derive(Debug, thiserror::Error)]
+pub enum ParseBanTargetError {
+    /// Failed to parse the IP address or network.
+    #[error(transparent)]
+    AddrParseError(#[from] ipnet::AddrParseError),
+    /// Failed to parse the peer id.
+    #[error("invalid peer id: {0}")]
+    InvalidPeerId(String),
+}
+
+impl FromStr for BanTarget {
+    type Err = ParseBanTargetError;
+
+    /// Parses a peer id, an IP address or a network in CIDR notation.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(ip) = s.parse::<IpAddr>() {
+            return Ok(ip.into())
+        }
+        if s.contains('/') {
+            return Ok(Self::Ip(s.parse()?))
+        }
+        s.parse::<PeerId>()
+            .map(Self::Peer)
+            .map_err(|_| ParseBanTargetError::InvalidPeerId(s.to_string()))
+    }
+}
+
+/// A ban as it is persisted or reported to operators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct BanEntry {
+    /// The banned peer or range of IP addresses.
+    pub target: BanTarget,
+    /// The unix timestamp in seconds at which the ban expires, or `None` if it is indefinite.
+    pub expires_at: Option<u64>,
+}
+
 /// Stores peers that should be taken out of circulation either indefinitely or until a certain
 /// timestamp
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct BanList {
     /// A set of IPs whose packets get dropped instantly.
     banned_ips: HashMap<IpAddr, Option<Instant>>,
+    /// A list of IP networks whose packets get dropped instantly.
+    ///
+    /// Networks are looked up by containment, so they're kept in a list rather than a map.
+    banned_networks: Vec<(IpNet, Option<Instant>)>,
     /// A set of [`PeerId`] whose packets get dropped instantly.
     banned_peers: HashMap<PeerId, Option<Instant>>,
 }
@@ -52,7 +144,16 @@ impl BanList {
         banned_peers: HashMap<PeerId, Option<Instant>>,
         banned_ips: HashMap<IpAddr, Option<Instant>>,
     ) -> Self {
-        Self { banned_ips, banned_peers }
+        Self { banned_ips, banned_networks: Vec::new(), banned_peers }
+    }
+
+    /// Creates a new ban list from persisted entries.
+    ///
+    /// Entries that already expired are skipped.
+    pub fn from_entries(entries: impl IntoIterator<Item = BanEntry>) -> Self {
+        let mut ban_list = Self::default();
+        ban_list.extend_entries(entries);
+        ban_list
     }
 
     /// Removes all peers that are no longer banned.
@@ -85,11 +186,27 @@ impl BanList {
         evicted
     }
 
+    /// Removes all ip networks that are no longer banned.
+    pub fn evict_networks(&mut self, now: Instant) -> Vec<IpNet> {
+        let mut evicted = Vec::new();
+        self.banned_networks.retain(|(network, until)| {
+            if let Some(until) = until {
+                if now > *until {
+                    evicted.push(*network);
+                    return false
+                }
+            }
+            true
+        });
+        evicted
+    }
+
     /// Removes all entries that should no longer be banned.
     ///
-    /// Returns the evicted entries.
+    /// Returns the evicted ips and peers, evicted networks are dropped silently.
     pub fn evict(&mut self, now: Instant) -> (Vec<IpAddr>, Vec<PeerId>) {
         let ips = self.evict_ips(now);
+        self.evict_networks(now);
         let peers = self.evict_peers(now);
         (ips, peers)
     }
@@ -100,10 +217,12 @@ impl BanList {
         self.is_banned_peer(peer_id) || self.is_banned_ip(ip)
     }
 
-    /// checks the ban list to see if it contains the given ip
+    /// checks the ban list to see if it contains the given ip, either directly or in a banned
+    /// network
     #[inline]
     pub fn is_banned_ip(&self, ip: &IpAddr) -> bool {
-        self.banned_ips.contains_key(ip)
+        self.banned_ips.contains_key(ip) ||
+            self.banned_networks.iter().any(|(network, _)| network.contains(ip))
     }
 
     /// checks the ban list to see if it contains the given ip
@@ -159,6 +278,98 @@ impl BanList {
             self.banned_ips.insert(ip, until);
         }
     }
+
+    /// Bans the target indefinitely or until the given timeout.
+    ///
+    /// Unlike [`Self::ban_ip_with`], this also bans non-global IPs, since the target was chosen
+    /// explicitly.
+    pub fn ban_with(&mut self, target: BanTarget, until: Option<Instant>) {
+        match target {
+            BanTarget::Peer(peer_id) => self.ban_peer_with(peer_id, until),
+            BanTarget::Ip(network) if is_host(&network) => {
+                self.banned_ips.insert(network.addr(), until);
+            }
+            BanTarget::Ip(network) => {
+                let network = network.trunc();
+                match self.banned_networks.iter_mut().find(|(banned, _)| *banned == network) {
+                    Some((_, banned_until)) => *banned_until = until,
+                    None => self.banned_networks.push((network, until)),
+                }
+            }
+        }
+    }
+
+    /// Unbans the target.
+    ///
+    /// Returns `true` if the target was banned.
+    pub fn unban(&mut self, target: &BanTarget) -> bool {
+        match target {
+            BanTarget::Peer(peer_id) => self.banned_peers.remove(peer_id).is_some(),
+            BanTarget::Ip(network) if is_host(network) => {
+                self.banned_ips.remove(&network.addr()).is_some()
+            }
+            BanTarget::Ip(network) => {
+                let network = network.trunc();
+                let len = self.banned_networks.len();
+                self.banned_networks.retain(|(banned, _)| *banned != network);
+                self.banned_networks.len() != len
+            }
+        }
+    }
+
+    /// Returns all bans, sorted by target.
+    ///
+    /// Expiry timestamps are converted to unix time so that they survive restarts.
+    pub fn entries(&self) -> Vec<BanEntry> {
+        let now = Instant::now();
+        let unix_now = unix_now();
+        let expires_at = |until: &Option<Instant>| {
+            until.map(|until| (unix_now + until.saturating_duration_since(now)).as_secs())
+        };
+
+        let peers = self.banned_peers.iter().map(|(peer_id, until)| BanEntry {
+            target: (*peer_id).into(),
+            expires_at: expires_at(until),
+        });
+        let ips = self
+            .banned_ips
+            .iter()
+            .map(|(ip, until)| BanEntry { target: (*ip).into(), expires_at: expires_at(until) });
+        let networks = self.banned_networks.iter().map(|(network, until)| BanEntry {
+            target: (*network).into(),
+            expires_at: expires_at(until),
+        });
+
+        let mut entries = peers.chain(ips).chain(networks).collect::<Vec<_>>();
+        entries.sort_unstable();
+        entries
+    }
+
+    /// Bans all targets of the given entries until their expiry.
+    ///
+    /// Entries that already expired are skipped.
+    pub fn extend_entries(&mut self, entries: impl IntoIterator<Item = BanEntry>) {
+        let now = Instant::now();
+        let unix_now = unix_now().as_secs();
+        for BanEntry { target, expires_at } in entries {
+            let until = match expires_at {
+                Some(expires_at) if expires_at <= unix_now => continue,
+                Some(expires_at) => Some(now + Duration::from_secs(expires_at - unix_now)),
+                None => None,
+            };
+            self.ban_with(target, until);
+        }
+    }
+}
+
+/// Returns true if the network consists of a single address.
+pub fn is_host(network: &IpNet) -> bool {
+    network.prefix_len() == network.max_prefix_len()
+}
+
+/// Returns the time elapsed since the unix epoch.
+fn unix_now() -> Duration {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default()
 }
 
 #[cfg(test)]
@@ -208,4 +419,95 @@ mod tests {
         banlist.ban_ip(ip);
         assert!(!banlist.is_banned_ip(&ip));
     }
+
+    #[test]
+    fn can_ban_unban_network() {
+        let target: BanTarget = "1.2.3.4/24".parse().unwrap();
+        let mut banlist = BanList::default();
+        banlist.ban_with(target, None);
+        assert!(banlist.is_banned_ip(&IpAddr::from([1, 2, 3, 200])));
+        assert!(!banlist.is_banned_ip(&IpAddr::from([1, 2, 4, 1])));
+
+        // the network is stored truncated, so any address of it unbans
+        assert!(banlist.unban(&"1.2.3.0/24".parse().unwrap()));
+        assert!(!banlist.is_banned_ip(&IpAddr::from([1, 2, 3, 200])));
+        assert!(!banlist.unban(&target));
+    }
+
+    #[test]
+    fn explicit_ban_of_non_global_ip() {
+        let ip = IpAddr::from([10, 0, 0, 1]);
+        let mut banlist = BanList::default();
+        banlist.ban_with(ip.into(), None);
+        assert!(banlist.is_banned_ip(&ip));
+        assert!(banlist.unban(&ip.into()));
+        assert!(!banlist.is_banned_ip(&ip));
+    }
+
+    #[test]
+    fn evict_network() {
+        let now = Instant::now();
+        let mut banlist = BanList::default();
+        banlist.ban_with("10.0.0.0/8".parse().unwrap(), Some(now));
+        assert!(banlist.is_banned_ip(&IpAddr::from([10, 1, 2, 3])));
+        banlist.evict(now + Duration::from_secs(1));
+        assert!(!banlist.is_banned_ip(&IpAddr::from([10, 1, 2, 3])));
+    }
+
+    #[test]
+    fn parse_ban_target() {
+        let peer_id = PeerId::random();
+        for target in [
+            BanTarget::Peer(peer_id),
+            BanTarget::Ip("1.2.3.4/32".parse().unwrap()),
+            BanTarget::Ip("1.2.3.0/24".parse().unwrap()),
+            BanTarget::Ip("2001:db8::/32".parse().unwrap()),
+        ] {
+            assert_eq!(target.to_string().parse::<BanTarget>().unwrap(), target);
+        }
+
+        assert_eq!("1.2.3.4".parse::<BanTarget>().unwrap().to_string(), "1.2.3.4");
+        assert!(matches!(
+            "1.2.3.4/33".parse::<BanTarget>(),
+            Err(ParseBanTargetError::AddrParseError(_))
+        ));
+        assert!(matches!("foo".parse::<BanTarget>(), Err(ParseBanTargetError::InvalidPeerId(_))));
+    }
+
+    #[test]
+    fn entries_roundtrip() {
+        let now = Instant::now();
+        let peer_id = PeerId::random();
+        let mut banlist = BanList::default();
+        banlist.ban_peer(peer_id);
+        banlist.ban_ip_until(IpAddr::from([1, 1, 1, 1]), now + Duration::from_secs(3600));
+        banlist.ban_with("1.2.3.0/24".parse().unwrap(), None);
+        // already expired, but not yet evicted
+        banlist.ban_ip_until(IpAddr::from([2, 2, 2, 2]), now);
+
+        let entries = banlist.entries();
+        assert_eq!(entries.len(), 4);
+        let expires_at = entries
+            .iter()
+            .find(|entry| entry.target == IpAddr::from([1, 1, 1, 1]).into())
+            .unwrap()
+            .expires_at
+            .unwrap();
+        assert!(expires_at.abs_diff(unix_now().as_secs() + 3600) <= 1);
+
+        let restored = BanList::from_entries(entries);
+        assert!(restored.is_banned_peer(&peer_id));
+        assert!(restored.is_banned_ip(&IpAddr::from([1, 1, 1, 1])));
+        assert!(restored.is_banned_ip(&IpAddr::from([1, 2, 3, 4])));
+        assert!(!restored.is_banned_ip(&IpAddr::from([2, 2, 2, 2])));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serde_entry() {
+        let entry = BanEntry { target: "1.2.3.0/24".parse().unwrap(), expires_at: Some(100) };
+        let json = serde_json::to_string(&entry).unwrap();
+        assert_eq!(json, r#"{"target":"1.2.3.0/24","expiresAt":100}"#);
+        assert_eq!(serde_json::from_str::<BanEntry>(&json).unwrap(), entry);
+    }
 }
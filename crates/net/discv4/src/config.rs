@@ -100,8 +100,11 @@ impl Discv4Config {
 
     /// Returns the corresponding [`ResolveNatInterval`], if a [`NatResolver`] and an interval was
     /// configured
+    ///
+    /// Resolvers that map ports on the gateway are skipped, the external address reported with the
+    /// mappings is set via [`Discv4::set_external_addr`](crate::Discv4::set_external_addr).
     pub fn resolve_external_ip_interval(&self) -> Option<ResolveNatInterval> {
-        let resolver = self.external_ip_resolver?;
+        let resolver = self.external_ip_resolver.filter(|resolver| !resolver.maps_ports())?;
         let interval = self.resolve_external_ip_interval?;
         Some(ResolveNatInterval::interval(resolver, interval))
     }
@@ -83,9 +83,9 @@ pub use reth_network_peers::NodeRecord;
 pub mod test_utils;
 
 use crate::table::PongTable;
-use reth_net_nat::ResolveNatInterval;
 /// reexport to get public ip.
 pub use reth_net_nat::{external_ip, NatResolver};
+use reth_net_nat::{ExternalAddr, ResolveNatInterval};
 
 /// The default address for discv4 via UDP
 ///
@@ -374,6 +374,14 @@ impl Discv4 {
         self.send_to_service(cmd);
     }
 
+    /// Sets the external address of the node, as reported by the gateway the ports are mapped on.
+    ///
+    /// This will update our [`NodeRecord`] and [`Enr`].
+    pub fn set_external_addr(&self, addr: ExternalAddr) {
+        let cmd = Discv4Command::SetExternalAddr(addr);
+        self.send_to_service(cmd);
+    }
+
     /// Sets the pair in the EIP-868 [`Enr`] of the node.
     ///
     /// If the key already exists, this will update it.
@@ -625,6 +633,35 @@ impl Discv4Service {
         }
     }
 
+    /// Sets the given address as the node's external address in the node record announced in
+    /// discovery.
+    ///
+    /// This is the address reported by the gateway the ports are mapped on, the ports may differ
+    /// from the local ones.
+    pub fn set_external_addr(&mut self, addr: ExternalAddr) {
+        self.set_external_ip_addr(addr.ip);
+
+        let tcp_port = addr.tcp_port.unwrap_or(self.local_node_record.tcp_port);
+        let udp_port = addr.udp_port.unwrap_or(self.local_node_record.udp_port);
+        if self.local_node_record.tcp_port != tcp_port ||
+            self.local_node_record.udp_port != udp_port
+        {
+            debug!(target: "discv4", tcp_port, udp_port, "Updating external ports");
+            self.local_node_record.tcp_port = tcp_port;
+            self.local_node_record.udp_port = udp_port;
+            if addr.ip.is_ipv4() {
+                let _ = self.local_eip_868_enr.set_tcp4(tcp_port, &self.secret_key);
+                let _ = self.local_eip_868_enr.set_udp4(udp_port, &self.secret_key);
+            } else {
+                let _ = self.local_eip_868_enr.set_tcp6(tcp_port, &self.secret_key);
+                let _ = self.local_eip_868_enr.set_udp6(udp_port, &self.secret_key);
+            }
+            let mut lock = self.shared_node_record.lock();
+            *lock = self.local_node_record;
+            debug!(target: "discv4", enr=?self.local_eip_868_enr, "Updated local ENR");
+        }
+    }
+
     /// Returns the [`PeerId`] that identifies this node
     pub const fn local_peer_id(&self) -> &PeerId {
         &self.local_node_record.id
@@ -1640,6 +1677,9 @@ impl Discv4Service {
 
                         let _ = self.local_eip_868_enr.insert_raw_rlp(key, rlp, &self.secret_key);
                     }
+                    Discv4Command::SetExternalAddr(addr) => {
+                        self.set_external_addr(addr);
+                    }
                     Discv4Command::SetTcpPort(port) => {
                         debug!(target: "discv4", %port, "Update tcp port");
                         self.local_node_record.tcp_port = port;
@@ -1930,6 +1970,7 @@ impl Default for ReceiveCache {
 enum Discv4Command {
     Add(NodeRecord),
     SetTcpPort(u16),
+    SetExternalAddr(ExternalAddr),
     SetEIP868RLPPair { key: Vec<u8>, rlp: Bytes },
     Ban(PeerId, IpAddr),
     BanPeer(PeerId),
@@ -2309,6 +2350,28 @@ mod tests {
         assert_eq!(expected, decoded);
     }
 
+    #[tokio::test]
+    async fn test_set_external_addr() {
+        let (discv4, mut service) = create_discv4().await;
+        let ip: IpAddr = "1.2.3.4".parse().unwrap();
+        let seq = service.local_eip_868_enr.seq();
+
+        service.set_external_addr(ExternalAddr { ip, tcp_port: Some(40404), udp_port: None });
+
+        let record = discv4.node_record();
+        assert_eq!(record.address, ip);
+        assert_eq!(record.tcp_port, 40404);
+        assert_eq!(record.udp_port, service.local_addr().port());
+        assert_eq!(service.local_eip_868_enr.ip4(), Some("1.2.3.4".parse().unwrap()));
+        assert_eq!(service.local_eip_868_enr.tcp4(), Some(40404));
+        assert!(service.local_eip_868_enr.seq() > seq);
+
+        // unchanged address does not update the record
+        let seq = service.local_eip_868_enr.seq();
+        service.set_external_addr(ExternalAddr { ip, tcp_port: Some(40404), udp_port: None });
+        assert_eq!(service.local_eip_868_enr.seq(), seq);
+    }
+
     #[test]
     fn test_enr_forkid_entry_decode() {
         let raw: [u8; 8] = [0xc7, 0xc6, 0x84, 0xdc, 0xe9, 0x6c, 0x2d, 0x80];
@@ -122,6 +122,14 @@ impl Discv5 {
         self.set_eip868_in_local_enr(key, buf.into())
     }
 
+    /// Sets the external socket of the node in the local [`Enr`], e.g. the address of a port mapped
+    /// on the gateway.
+    ///
+    /// Returns `true` if the [`Enr`] was updated.
+    pub fn set_external_socket(&self, socket: SocketAddr, is_tcp: bool) -> bool {
+        self.discv5.update_local_enr_socket(socket, is_tcp)
+    }
+
     /// Adds the peer and id to the ban list.
     ///
     /// This will prevent any future inclusion in the table
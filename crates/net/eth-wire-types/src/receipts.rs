@@ -2,7 +2,7 @@
 
 use alloy_rlp::{RlpDecodableWrapper, RlpEncodableWrapper};
 use reth_codecs_derive::derive_arbitrary;
-use reth_primitives::{ReceiptWithBloom, B256};
+use reth_primitives::{Receipt, ReceiptWithBloom, B256};
 
 /// A request for transaction receipts from the given block hashes.
 #[derive_arbitrary(rlp)]
@@ -23,9 +23,47 @@ pub struct Receipts(
     pub Vec<Vec<ReceiptWithBloom>>,
 );
 
+/// The response to [`GetReceipts`] of `eth/69`, containing receipt lists that correspond to each
+/// block requested.
+///
+/// Compared to [`Receipts`], the receipts are sent without their bloom, which is derived from the
+/// logs, and encoded as `[tx-type, status, cumulative-gas-used, logs]` for all transaction types.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodableWrapper, RlpDecodableWrapper, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Receipts69(
+    /// Each receipt hash should correspond to a block hash in the request.
+    pub Vec<Vec<Receipt>>,
+);
+
+impl From<Receipts> for Receipts69 {
+    fn from(receipts: Receipts) -> Self {
+        Self(
+            receipts
+                .0
+                .into_iter()
+                .map(|receipts| receipts.into_iter().map(|receipt| receipt.receipt).collect())
+                .collect(),
+        )
+    }
+}
+
+/// Computes the bloom of the receipts.
+impl From<Receipts69> for Receipts {
+    fn from(receipts: Receipts69) -> Self {
+        Self(
+            receipts
+                .0
+                .into_iter()
+                .map(|receipts| receipts.into_iter().map(Receipt::with_bloom).collect())
+                .collect(),
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{message::RequestPair, GetReceipts, Receipts};
+    use crate::{message::RequestPair, GetReceipts, Receipts, Receipts69};
     use alloy_rlp::{Decodable, Encodable};
     use reth_primitives::{hex, Log, Receipt, ReceiptWithBloom, TxType};
 
@@ -148,4 +186,33 @@ mod tests {
             }
         );
     }
+
+    #[test]
+    #[allow(clippy::needless_update)]
+    fn receipts69_without_bloom() {
+        let receipt = Receipt {
+            tx_type: TxType::Eip1559,
+            success: true,
+            cumulative_gas_used: 21000,
+            logs: vec![Log::new_unchecked(
+                hex!("0000000000000000000000000000000000000011").into(),
+                vec![
+                    hex!("000000000000000000000000000000000000000000000000000000000000dead").into()
+                ],
+                hex!("0100ff")[..].into(),
+            )],
+            ..Default::default()
+        };
+        let receipts = Receipts(vec![vec![receipt.with_bloom()]]);
+
+        let receipts69 = Receipts69::from(receipts.clone());
+        let mut out = vec![];
+        receipts69.encode(&mut out);
+        // `[[[tx-type, status, cumulative-gas-used, logs]]]`, without the 256 byte bloom
+        assert!(out.len() < 256);
+        assert_eq!(Receipts69::decode(&mut &out[..]).unwrap(), receipts69);
+
+        // The bloom is derived from the logs
+        assert_eq!(Receipts::from(receipts69), receipts);
+    }
 }
@@ -0,0 +1,464 @@
+//! Implements the `snap/1` protocol messages.
+//!
+//! See also <https://github.com/ethereum/devp2p/blob/master/caps/snap.md>
+
+use alloy_rlp::{Decodable, Encodable, Header, RlpDecodable, RlpEncodable};
+use bytes::{BufMut, BytesMut};
+use reth_codecs_derive::derive_arbitrary;
+use reth_primitives::{constants::EMPTY_ROOT_HASH, Bytes, B256, KECCAK_EMPTY, U256};
+
+/// A request for the accounts of the state trie with the given root, starting at `starting_hash`.
+///
+/// The peer should stop serving accounts after `limit_hash`, or once `response_bytes` is reached.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GetAccountRange {
+    /// The ID of the request, echoed in the response.
+    pub request_id: u64,
+    /// The root of the state trie to serve.
+    pub root_hash: B256,
+    /// The hash of the first account to serve.
+    pub starting_hash: B256,
+    /// The hash after which to stop serving accounts.
+    pub limit_hash: B256,
+    /// The soft limit of the size of the response.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetAccountRange`], containing consecutive accounts and the proof of the
+/// first requested hash and the last returned account.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccountRange {
+    /// The ID of the request.
+    pub request_id: u64,
+    /// The accounts, in ascending order of their hashes.
+    pub accounts: Vec<AccountData>,
+    /// The trie nodes proving the range of accounts.
+    pub proof: Vec<Bytes>,
+}
+
+/// An account of an [`AccountRange`].
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccountData {
+    /// The hash of the account address.
+    pub hash: B256,
+    /// The account.
+    pub body: SnapAccount,
+}
+
+/// An account in the "slim" format of the `snap` protocol.
+///
+/// The empty storage root and the empty code hash are encoded as empty strings.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SnapAccount {
+    /// The nonce of the account.
+    pub nonce: u64,
+    /// The balance of the account.
+    pub balance: U256,
+    /// The root of the storage trie of the account.
+    pub storage_root: B256,
+    /// The hash of the code of the account.
+    pub code_hash: B256,
+}
+
+impl Default for SnapAccount {
+    fn default() -> Self {
+        Self {
+            nonce: 0,
+            balance: U256::ZERO,
+            storage_root: EMPTY_ROOT_HASH,
+            code_hash: KECCAK_EMPTY,
+        }
+    }
+}
+
+impl SnapAccount {
+    fn slim_storage_root(&self) -> &[u8] {
+        if self.storage_root == EMPTY_ROOT_HASH {
+            &[]
+        } else {
+            self.storage_root.as_slice()
+        }
+    }
+
+    fn slim_code_hash(&self) -> &[u8] {
+        if self.code_hash == KECCAK_EMPTY {
+            &[]
+        } else {
+            self.code_hash.as_slice()
+        }
+    }
+
+    fn payload_length(&self) -> usize {
+        self.nonce.length() +
+            self.balance.length() +
+            self.slim_storage_root().length() +
+            self.slim_code_hash().length()
+    }
+
+    /// Decodes an empty string as `empty`, or a hash otherwise.
+    fn decode_slim_hash(buf: &mut &[u8], empty: B256) -> alloy_rlp::Result<B256> {
+        let hash = Header::decode_bytes(buf, false)?;
+        match hash.len() {
+            0 => Ok(empty),
+            32 => Ok(B256::from_slice(hash)),
+            _ => Err(alloy_rlp::Error::UnexpectedLength),
+        }
+    }
+}
+
+impl Encodable for SnapAccount {
+    fn encode(&self, out: &mut dyn BufMut) {
+        Header { list: true, payload_length: self.payload_length() }.encode(out);
+        self.nonce.encode(out);
+        self.balance.encode(out);
+        self.slim_storage_root().encode(out);
+        self.slim_code_hash().encode(out);
+    }
+
+    fn length(&self) -> usize {
+        let payload_length = self.payload_length();
+        payload_length + alloy_rlp::length_of_length(payload_length)
+    }
+}
+
+impl Decodable for SnapAccount {
+    fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let header = Header::decode(buf)?;
+        if !header.list {
+            return Err(alloy_rlp::Error::UnexpectedString)
+        }
+        let started_len = buf.len();
+
+        let account = Self {
+            nonce: Decodable::decode(buf)?,
+            balance: Decodable::decode(buf)?,
+            storage_root: Self::decode_slim_hash(buf, EMPTY_ROOT_HASH)?,
+            code_hash: Self::decode_slim_hash(buf, KECCAK_EMPTY)?,
+        };
+
+        let consumed = started_len - buf.len();
+        if consumed != header.payload_length {
+            return Err(alloy_rlp::Error::ListLengthMismatch {
+                expected: header.payload_length,
+                got: consumed,
+            })
+        }
+
+        Ok(account)
+    }
+}
+
+/// A request for the storage slots of several accounts of the state trie with the given root.
+///
+/// The starting hash only applies to the first account, and the limit hash only to the last one.
+/// Empty hashes mean the start and the end of the storage of the accounts.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GetStorageRanges {
+    /// The ID of the request, echoed in the response.
+    pub request_id: u64,
+    /// The root of the state trie to serve.
+    pub root_hash: B256,
+    /// The hashes of the addresses of the accounts to serve.
+    pub account_hashes: Vec<B256>,
+    /// The hash of the first storage slot of the first account to serve.
+    pub starting_hash: Bytes,
+    /// The hash after which to stop serving the storage slots of the last account.
+    pub limit_hash: Bytes,
+    /// The soft limit of the size of the response.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetStorageRanges`], containing the storage slots of consecutive requested
+/// accounts.
+///
+/// The proof is only present if the storage of the last account is not served completely.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StorageRanges {
+    /// The ID of the request.
+    pub request_id: u64,
+    /// The storage slots of each account, in ascending order of their hashes.
+    pub slots: Vec<Vec<StorageData>>,
+    /// The trie nodes proving the range of storage slots of the last account.
+    pub proof: Vec<Bytes>,
+}
+
+/// A storage slot of a [`StorageRanges`].
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StorageData {
+    /// The hash of the storage key.
+    pub hash: B256,
+    /// The RLP encoded storage value.
+    pub data: Bytes,
+}
+
+/// A request for contract bytecodes by their hashes.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GetByteCodes {
+    /// The ID of the request, echoed in the response.
+    pub request_id: u64,
+    /// The hashes of the bytecodes.
+    pub hashes: Vec<B256>,
+    /// The soft limit of the size of the response.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetByteCodes`], containing the bytecodes in request order.
+///
+/// Bytecodes the peer doesn't have are skipped.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ByteCodes {
+    /// The ID of the request.
+    pub request_id: u64,
+    /// The bytecodes.
+    pub codes: Vec<Bytes>,
+}
+
+/// A request for trie nodes of the state trie with the given root, by their paths.
+///
+/// Each path set is either a single compact encoded path in the account trie, or the hash of an
+/// account followed by compact encoded paths in the storage trie of that account.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GetTrieNodes {
+    /// The ID of the request, echoed in the response.
+    pub request_id: u64,
+    /// The root of the state trie to serve.
+    pub root_hash: B256,
+    /// The path sets of the trie nodes.
+    pub paths: Vec<Vec<Bytes>>,
+    /// The soft limit of the size of the response.
+    pub response_bytes: u64,
+}
+
+/// The response to [`GetTrieNodes`], containing the RLP encoded trie nodes in request order.
+///
+/// The response stops at the first trie node the peer doesn't have.
+#[derive_arbitrary(rlp)]
+#[derive(Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TrieNodes {
+    /// The ID of the request.
+    pub request_id: u64,
+    /// The trie nodes.
+    pub nodes: Vec<Bytes>,
+}
+
+/// Represents message IDs for `snap` protocol messages.
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SnapMessageID {
+    /// Get account range message.
+    GetAccountRange = 0x00,
+    /// Account range message.
+    AccountRange = 0x01,
+    /// Get storage ranges message.
+    GetStorageRanges = 0x02,
+    /// Storage ranges message.
+    StorageRanges = 0x03,
+    /// Get byte codes message.
+    GetByteCodes = 0x04,
+    /// Byte codes message.
+    ByteCodes = 0x05,
+    /// Get trie nodes message.
+    GetTrieNodes = 0x06,
+    /// Trie nodes message.
+    TrieNodes = 0x07,
+}
+
+impl SnapMessageID {
+    /// Returns the max value.
+    pub const fn max() -> u8 {
+        Self::TrieNodes as u8
+    }
+}
+
+impl TryFrom<u8> for SnapMessageID {
+    type Error = alloy_rlp::Error;
+
+    fn try_from(id: u8) -> Result<Self, Self::Error> {
+        Ok(match id {
+            0x00 => Self::GetAccountRange,
+            0x01 => Self::AccountRange,
+            0x02 => Self::GetStorageRanges,
+            0x03 => Self::StorageRanges,
+            0x04 => Self::GetByteCodes,
+            0x05 => Self::ByteCodes,
+            0x06 => Self::GetTrieNodes,
+            0x07 => Self::TrieNodes,
+            _ => return Err(alloy_rlp::Error::Custom("Invalid message ID")),
+        })
+    }
+}
+
+/// A `snap` protocol message.
+///
+/// Encoded with the message ID as first byte, as expected by the `RLPx` multiplexer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SnapMessage {
+    /// Represents a `GetAccountRange` request.
+    GetAccountRange(GetAccountRange),
+    /// Represents an `AccountRange` response.
+    AccountRange(AccountRange),
+    /// Represents a `GetStorageRanges` request.
+    GetStorageRanges(GetStorageRanges),
+    /// Represents a `StorageRanges` response.
+    StorageRanges(StorageRanges),
+    /// Represents a `GetByteCodes` request.
+    GetByteCodes(GetByteCodes),
+    /// Represents a `ByteCodes` response.
+    ByteCodes(ByteCodes),
+    /// Represents a `GetTrieNodes` request.
+    GetTrieNodes(GetTrieNodes),
+    /// Represents a `TrieNodes` response.
+    TrieNodes(TrieNodes),
+}
+
+impl SnapMessage {
+    /// Returns the message's ID.
+    pub const fn message_id(&self) -> SnapMessageID {
+        match self {
+            Self::GetAccountRange(_) => SnapMessageID::GetAccountRange,
+            Self::AccountRange(_) => SnapMessageID::AccountRange,
+            Self::GetStorageRanges(_) => SnapMessageID::GetStorageRanges,
+            Self::StorageRanges(_) => SnapMessageID::StorageRanges,
+            Self::GetByteCodes(_) => SnapMessageID::GetByteCodes,
+            Self::ByteCodes(_) => SnapMessageID::ByteCodes,
+            Self::GetTrieNodes(_) => SnapMessageID::GetTrieNodes,
+            Self::TrieNodes(_) => SnapMessageID::TrieNodes,
+        }
+    }
+
+    /// Returns the ID of the request the message belongs to.
+    pub const fn request_id(&self) -> u64 {
+        match self {
+            Self::GetAccountRange(msg) => msg.request_id,
+            Self::AccountRange(msg) => msg.request_id,
+            Self::GetStorageRanges(msg) => msg.request_id,
+            Self::StorageRanges(msg) => msg.request_id,
+            Self::GetByteCodes(msg) => msg.request_id,
+            Self::ByteCodes(msg) => msg.request_id,
+            Self::GetTrieNodes(msg) => msg.request_id,
+            Self::TrieNodes(msg) => msg.request_id,
+        }
+    }
+
+    /// Sets the ID of the request the message belongs to.
+    pub fn set_request_id(&mut self, request_id: u64) {
+        match self {
+            Self::GetAccountRange(msg) => msg.request_id = request_id,
+            Self::AccountRange(msg) => msg.request_id = request_id,
+            Self::GetStorageRanges(msg) => msg.request_id = request_id,
+            Self::StorageRanges(msg) => msg.request_id = request_id,
+            Self::GetByteCodes(msg) => msg.request_id = request_id,
+            Self::ByteCodes(msg) => msg.request_id = request_id,
+            Self::GetTrieNodes(msg) => msg.request_id = request_id,
+            Self::TrieNodes(msg) => msg.request_id = request_id,
+        }
+    }
+
+    /// Returns true if the message is a request.
+    pub const fn is_request(&self) -> bool {
+        matches!(
+            self,
+            Self::GetAccountRange(_) |
+                Self::GetStorageRanges(_) |
+                Self::GetByteCodes(_) |
+                Self::GetTrieNodes(_)
+        )
+    }
+
+    /// Encodes the message ID followed by the RLP encoded message.
+    pub fn encoded(&self) -> BytesMut {
+        let mut buf = BytesMut::new();
+        buf.put_u8(self.message_id() as u8);
+        match self {
+            Self::GetAccountRange(msg) => msg.encode(&mut buf),
+            Self::AccountRange(msg) => msg.encode(&mut buf),
+            Self::GetStorageRanges(msg) => msg.encode(&mut buf),
+            Self::StorageRanges(msg) => msg.encode(&mut buf),
+            Self::GetByteCodes(msg) => msg.encode(&mut buf),
+            Self::ByteCodes(msg) => msg.encode(&mut buf),
+            Self::GetTrieNodes(msg) => msg.encode(&mut buf),
+            Self::TrieNodes(msg) => msg.encode(&mut buf),
+        }
+        buf
+    }
+
+    /// Decodes a message from the message ID followed by the RLP encoded message.
+    pub fn decode_message(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
+        let (&id, rest) = buf.split_first().ok_or(alloy_rlp::Error::InputTooShort)?;
+        *buf = rest;
+
+        Ok(match SnapMessageID::try_from(id)? {
+            SnapMessageID::GetAccountRange => Self::GetAccountRange(Decodable::decode(buf)?),
+            SnapMessageID::AccountRange => Self::AccountRange(Decodable::decode(buf)?),
+            SnapMessageID::GetStorageRanges => Self::GetStorageRanges(Decodable::decode(buf)?),
+            SnapMessageID::StorageRanges => Self::StorageRanges(Decodable::decode(buf)?),
+            SnapMessageID::GetByteCodes => Self::GetByteCodes(Decodable::decode(buf)?),
+            SnapMessageID::ByteCodes => Self::ByteCodes(Decodable::decode(buf)?),
+            SnapMessageID::GetTrieNodes => Self::GetTrieNodes(Decodable::decode(buf)?),
+            SnapMessageID::TrieNodes => Self::TrieNodes(Decodable::decode(buf)?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::hex;
+
+    #[test]
+    fn snap_account_slim_encoding() {
+        let account = SnapAccount { nonce: 1, balance: U256::from(2), ..Default::default() };
+        let encoded = alloy_rlp::encode(account);
+        assert_eq!(encoded, hex!("c401028080"));
+        assert_eq!(SnapAccount::decode(&mut &encoded[..]).unwrap(), account);
+
+        let account = SnapAccount {
+            storage_root: B256::repeat_byte(1),
+            code_hash: B256::repeat_byte(2),
+            ..Default::default()
+        };
+        let encoded = alloy_rlp::encode(account);
+        assert_eq!(encoded.len(), account.length());
+        assert_eq!(SnapAccount::decode(&mut &encoded[..]).unwrap(), account);
+    }
+
+    #[test]
+    fn snap_message_roundtrip() {
+        let message = SnapMessage::GetStorageRanges(GetStorageRanges {
+            request_id: 7,
+            root_hash: B256::repeat_byte(1),
+            account_hashes: vec![B256::repeat_byte(2), B256::repeat_byte(3)],
+            starting_hash: Bytes::new(),
+            limit_hash: B256::repeat_byte(0xff).into(),
+            response_bytes: 512 * 1024,
+        });
+        let encoded = message.encoded();
+        assert_eq!(encoded[0], SnapMessageID::GetStorageRanges as u8);
+        assert_eq!(SnapMessage::decode_message(&mut &encoded[..]).unwrap(), message);
+
+        assert!(SnapMessage::decode_message(&mut &[0x08, 0xc0][..]).is_err());
+    }
+}
@@ -5,7 +5,10 @@ use alloy_rlp::{RlpDecodable, RlpEncodable};
 use reth_chainspec::{ChainSpec, MAINNET};
 use reth_codecs_derive::derive_arbitrary;
 use reth_primitives::{hex, EthereumHardfork, ForkId, Head, B256, U256};
-use std::fmt::{Debug, Display};
+use std::{
+    fmt::{Debug, Display},
+    ops::RangeInclusive,
+};
 
 /// The status message is used in the eth protocol handshake to ensure that peers are on the same
 /// network and are following the same fork.
@@ -225,9 +228,220 @@ impl StatusBuilder {
     }
 }
 
+/// The status message of `eth/69`, sent during the protocol handshake.
+///
+/// Compared to [`Status`], the total difficulty is dropped, and the range of blocks the peer can
+/// serve is announced instead.
+///
+/// See also <https://eips.ethereum.org/EIPS/eip-7642>
+#[derive_arbitrary(rlp)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StatusEth69 {
+    /// The current protocol version, 69.
+    pub version: u8,
+
+    /// The chain id, as introduced in
+    /// [EIP155](https://eips.ethereum.org/EIPS/eip-155#list-of-chain-ids).
+    pub chain: Chain,
+
+    /// The genesis hash of the peer's chain.
+    pub genesis: B256,
+
+    /// The fork identifier, as defined by
+    /// [EIP-2124](https://github.com/ethereum/EIPs/blob/master/EIPS/eip-2124.md).
+    pub forkid: ForkId,
+
+    /// The earliest block the peer can serve.
+    pub earliest: u64,
+
+    /// The latest block of the peer.
+    pub latest: u64,
+
+    /// The hash of the latest block of the peer.
+    pub blockhash: B256,
+}
+
+impl StatusEth69 {
+    /// Creates the `eth/69` status from the given status and the range of blocks that can be
+    /// served.
+    pub const fn new(status: &Status, block_range: BlockRangeUpdate) -> Self {
+        Self {
+            version: EthVersion::Eth69 as u8,
+            chain: status.chain,
+            genesis: status.genesis,
+            forkid: status.forkid,
+            earliest: block_range.earliest,
+            latest: block_range.latest,
+            blockhash: block_range.latest_hash,
+        }
+    }
+
+    /// Returns the range of blocks the peer can serve.
+    pub const fn block_range(&self) -> BlockRangeUpdate {
+        BlockRangeUpdate {
+            earliest: self.earliest,
+            latest: self.latest,
+            latest_hash: self.blockhash,
+        }
+    }
+}
+
+impl Display for StatusEth69 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "StatusEth69 {{ version: {}, chain: {}, genesis: {}, forkid: {:X?}, earliest: {}, latest: {}, blockhash: {} }}",
+            self.version,
+            self.chain,
+            hex::encode(self.genesis),
+            self.forkid,
+            self.earliest,
+            self.latest,
+            hex::encode(self.blockhash),
+        )
+    }
+}
+
+/// Converts the `eth/69` status into a [`Status`].
+///
+/// The total difficulty is not part of the `eth/69` status, and is set to zero.
+impl From<StatusEth69> for Status {
+    fn from(status: StatusEth69) -> Self {
+        Self {
+            version: status.version,
+            chain: status.chain,
+            total_difficulty: U256::ZERO,
+            blockhash: status.blockhash,
+            genesis: status.genesis,
+            forkid: status.forkid,
+        }
+    }
+}
+
+/// The status message of the negotiated `eth` version.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StatusMessage {
+    /// The status message of `eth/66`, `eth/67` and `eth/68`.
+    Legacy(Status),
+    /// The status message of `eth/69`.
+    Eth69(StatusEth69),
+}
+
+impl StatusMessage {
+    /// Returns the protocol version.
+    pub const fn version(&self) -> u8 {
+        match self {
+            Self::Legacy(status) => status.version,
+            Self::Eth69(status) => status.version,
+        }
+    }
+
+    /// Returns the chain id.
+    pub const fn chain(&self) -> Chain {
+        match self {
+            Self::Legacy(status) => status.chain,
+            Self::Eth69(status) => status.chain,
+        }
+    }
+
+    /// Returns the genesis hash.
+    pub const fn genesis(&self) -> B256 {
+        match self {
+            Self::Legacy(status) => status.genesis,
+            Self::Eth69(status) => status.genesis,
+        }
+    }
+
+    /// Returns the fork id.
+    pub const fn forkid(&self) -> ForkId {
+        match self {
+            Self::Legacy(status) => status.forkid,
+            Self::Eth69(status) => status.forkid,
+        }
+    }
+
+    /// Returns the hash of the best block.
+    pub const fn blockhash(&self) -> B256 {
+        match self {
+            Self::Legacy(status) => status.blockhash,
+            Self::Eth69(status) => status.blockhash,
+        }
+    }
+
+    /// Returns the range of blocks the peer can serve, if announced.
+    ///
+    /// This is only announced by `eth/69` peers.
+    pub const fn block_range(&self) -> Option<BlockRangeUpdate> {
+        match self {
+            Self::Legacy(_) => None,
+            Self::Eth69(status) => Some(status.block_range()),
+        }
+    }
+
+    /// Converts the message into a [`Status`].
+    ///
+    /// See also [`Status::from`] for the `eth/69` status.
+    pub fn into_status(self) -> Status {
+        match self {
+            Self::Legacy(status) => status,
+            Self::Eth69(status) => status.into(),
+        }
+    }
+}
+
+impl From<Status> for StatusMessage {
+    fn from(status: Status) -> Self {
+        Self::Legacy(status)
+    }
+}
+
+impl From<StatusEth69> for StatusMessage {
+    fn from(status: StatusEth69) -> Self {
+        Self::Eth69(status)
+    }
+}
+
+impl Display for StatusMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Legacy(status) => Display::fmt(status, f),
+            Self::Eth69(status) => Display::fmt(status, f),
+        }
+    }
+}
+
+/// Announces the range of blocks a peer can serve, sent by `eth/69` peers whenever it changes.
+///
+/// This is also part of the [`StatusEth69`] message.
+#[derive_arbitrary(rlp)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BlockRangeUpdate {
+    /// The earliest block the peer can serve.
+    pub earliest: u64,
+    /// The latest block of the peer.
+    pub latest: u64,
+    /// The hash of the latest block of the peer.
+    pub latest_hash: B256,
+}
+
+impl BlockRangeUpdate {
+    /// Returns `true` if the earliest block is not after the latest block.
+    pub const fn is_valid(&self) -> bool {
+        self.earliest <= self.latest
+    }
+
+    /// Returns `true` if the peer can serve all blocks in the range.
+    pub const fn contains(&self, range: &RangeInclusive<u64>) -> bool {
+        self.earliest <= *range.start() && *range.end() <= self.latest
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::{EthVersion, Status};
+    use crate::{BlockRangeUpdate, EthVersion, Status, StatusEth69, StatusMessage};
     use alloy_genesis::Genesis;
     use alloy_rlp::{Decodable, Encodable};
     use rand::Rng;
@@ -279,6 +493,37 @@ mod tests {
         assert_eq!(status, expected);
     }
 
+    #[test]
+    fn eth69_status_roundtrip() {
+        let status = Status::default();
+        let block_range =
+            BlockRangeUpdate { earliest: 100, latest: 200, latest_hash: B256::repeat_byte(1) };
+        let status69 = StatusEth69::new(&status, block_range);
+        assert_eq!(status69.version, EthVersion::Eth69 as u8);
+        assert_eq!(status69.block_range(), block_range);
+
+        let mut rlp_status = vec![];
+        status69.encode(&mut rlp_status);
+        assert_eq!(StatusEth69::decode(&mut &rlp_status[..]).unwrap(), status69);
+
+        let message = StatusMessage::from(status69);
+        assert_eq!(message.block_range(), Some(block_range));
+        let converted = message.into_status();
+        assert_eq!(converted.blockhash, block_range.latest_hash);
+        assert_eq!(converted.genesis, status.genesis);
+        assert_eq!(converted.total_difficulty, U256::ZERO);
+    }
+
+    #[test]
+    fn block_range_contains() {
+        let block_range = BlockRangeUpdate { earliest: 100, latest: 200, ..Default::default() };
+        assert!(block_range.is_valid());
+        assert!(block_range.contains(&(100..=200)));
+        assert!(!block_range.contains(&(99..=150)));
+        assert!(!block_range.contains(&(150..=201)));
+        assert!(!BlockRangeUpdate { earliest: 2, latest: 1, ..Default::default() }.is_valid());
+    }
+
     #[test]
     fn encode_network_status_message() {
         let expected = hex!("f850423884024190faa0f8514c4680ef27700751b08f37645309ce65a449616a3ea966bf39dd935bb27ba00d21840abff46b96c84b2ac9e10e4f5cdaeb5693cb665db62a2f3b02d2d57b5bc6845d43d2fd80");
@@ -2,7 +2,7 @@ use crate::{
     errors::{EthHandshakeError, EthStreamError},
     message::{EthBroadcastMessage, ProtocolBroadcastMessage},
     p2pstream::HANDSHAKE_TIMEOUT,
-    CanDisconnect, DisconnectReason, EthMessage, EthVersion, ProtocolMessage, Status,
+    CanDisconnect, DisconnectReason, EthMessage, EthVersion, ProtocolMessage, StatusMessage,
 };
 use futures::{ready, Sink, SinkExt, StreamExt};
 use pin_project::pin_project;
@@ -52,21 +52,24 @@ where
     /// Consumes the [`UnauthedEthStream`] and returns an [`EthStream`] after the `Status`
     /// handshake is completed successfully. This also returns the `Status` message sent by the
     /// remote peer.
+    ///
+    /// The `eth` version is determined by the given status, which must be a
+    /// [`StatusEth69`](crate::StatusEth69) for `eth/69`.
     pub async fn handshake(
         self,
-        status: Status,
+        status: impl Into<StatusMessage>,
         fork_filter: ForkFilter,
-    ) -> Result<(EthStream<S>, Status), EthStreamError> {
+    ) -> Result<(EthStream<S>, StatusMessage), EthStreamError> {
         self.handshake_with_timeout(status, fork_filter, HANDSHAKE_TIMEOUT).await
     }
 
     /// Wrapper around handshake which enforces a timeout.
     pub async fn handshake_with_timeout(
         self,
-        status: Status,
+        status: impl Into<StatusMessage>,
         fork_filter: ForkFilter,
         timeout_limit: Duration,
-    ) -> Result<(EthStream<S>, Status), EthStreamError> {
+    ) -> Result<(EthStream<S>, StatusMessage), EthStreamError> {
         timeout(timeout_limit, Self::handshake_without_timeout(self, status, fork_filter))
             .await
             .map_err(|_| EthStreamError::StreamTimeout)?
@@ -75,9 +78,10 @@ where
     /// Handshake with no timeout
     pub async fn handshake_without_timeout(
         mut self,
-        status: Status,
+        status: impl Into<StatusMessage>,
         fork_filter: ForkFilter,
-    ) -> Result<(EthStream<S>, Status), EthStreamError> {
+    ) -> Result<(EthStream<S>, StatusMessage), EthStreamError> {
+        let status = status.into();
         trace!(
             %status,
             "sending eth status to peer"
@@ -86,7 +90,7 @@ where
         // we need to encode and decode here on our own because we don't have an `EthStream` yet
         // The max length for a status with TTD is: <msg id = 1 byte> + <rlp(status) = 88 byte>
         self.inner
-            .send(alloy_rlp::encode(ProtocolMessage::from(EthMessage::Status(status))).into())
+            .send(alloy_rlp::encode(ProtocolMessage::from(EthMessage::from(status))).into())
             .await?;
 
         let their_msg_res = self.inner.next().await;
@@ -104,7 +108,7 @@ where
             return Err(EthStreamError::MessageTooBig(their_msg.len()))
         }
 
-        let version = EthVersion::try_from(status.version)?;
+        let version = EthVersion::try_from(status.version())?;
         let msg = match ProtocolMessage::decode_message(version, &mut their_msg.as_ref()) {
             Ok(m) => m,
             Err(err) => {
@@ -116,69 +120,82 @@ where
 
         // The following checks should match the checks in go-ethereum:
         // https://github.com/ethereum/go-ethereum/blob/9244d5cd61f3ea5a7645fdf2a1a96d53421e412f/eth/protocols/eth/handshake.go#L87-L89
-        match msg.message {
-            EthMessage::Status(resp) => {
-                trace!(
-                    status=%resp,
-                    "validating incoming eth status from peer"
-                );
-                if status.genesis != resp.genesis {
-                    self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
-                    return Err(EthHandshakeError::MismatchedGenesis(
-                        GotExpected { expected: status.genesis, got: resp.genesis }.into(),
-                    )
-                    .into())
-                }
+        let resp = match msg.message {
+            EthMessage::Status(resp) => StatusMessage::Legacy(resp),
+            EthMessage::StatusEth69(resp) => StatusMessage::Eth69(resp),
+            _ => {
+                self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
+                return Err(EthStreamError::EthHandshakeError(
+                    EthHandshakeError::NonStatusMessageInHandshake,
+                ))
+            }
+        };
 
-                if status.version != resp.version {
-                    self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
-                    return Err(EthHandshakeError::MismatchedProtocolVersion(GotExpected {
-                        got: resp.version,
-                        expected: status.version,
-                    })
-                    .into())
-                }
+        trace!(
+            status=%resp,
+            "validating incoming eth status from peer"
+        );
+        if status.genesis() != resp.genesis() {
+            self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
+            return Err(EthHandshakeError::MismatchedGenesis(
+                GotExpected { expected: status.genesis(), got: resp.genesis() }.into(),
+            )
+            .into())
+        }
 
-                if status.chain != resp.chain {
-                    self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
-                    return Err(EthHandshakeError::MismatchedChain(GotExpected {
-                        got: resp.chain,
-                        expected: status.chain,
-                    })
-                    .into())
-                }
+        if status.version() != resp.version() {
+            self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
+            return Err(EthHandshakeError::MismatchedProtocolVersion(GotExpected {
+                got: resp.version(),
+                expected: status.version(),
+            })
+            .into())
+        }
 
-                // TD at mainnet block #7753254 is 76 bits. If it becomes 100 million times
-                // larger, it will still fit within 100 bits
-                if status.total_difficulty.bit_len() > 100 {
-                    self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
-                    return Err(EthHandshakeError::TotalDifficultyBitLenTooLarge {
-                        got: status.total_difficulty.bit_len(),
-                        maximum: 100,
-                    }
-                    .into())
-                }
+        if status.chain() != resp.chain() {
+            self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
+            return Err(EthHandshakeError::MismatchedChain(GotExpected {
+                got: resp.chain(),
+                expected: status.chain(),
+            })
+            .into())
+        }
 
-                if let Err(err) =
-                    fork_filter.validate(resp.forkid).map_err(EthHandshakeError::InvalidFork)
-                {
-                    self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
-                    return Err(err.into())
+        // TD at mainnet block #7753254 is 76 bits. If it becomes 100 million times
+        // larger, it will still fit within 100 bits
+        if let StatusMessage::Legacy(status) = status {
+            if status.total_difficulty.bit_len() > 100 {
+                self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
+                return Err(EthHandshakeError::TotalDifficultyBitLenTooLarge {
+                    got: status.total_difficulty.bit_len(),
+                    maximum: 100,
                 }
-
-                // now we can create the `EthStream` because the peer has successfully completed
-                // the handshake
-                let stream = EthStream::new(version, self.inner);
-
-                Ok((stream, resp))
+                .into())
             }
-            _ => {
-                self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
-                Err(EthStreamError::EthHandshakeError(
-                    EthHandshakeError::NonStatusMessageInHandshake,
-                ))
+        }
+
+        // eth/69 peers announce the range of blocks they can serve
+        if let Some(block_range) = resp.block_range().filter(|range| !range.is_valid()) {
+            self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
+            return Err(EthHandshakeError::InvalidBlockRange {
+                earliest: block_range.earliest,
+                latest: block_range.latest,
             }
+            .into())
+        }
+
+        if let Err(err) =
+            fork_filter.validate(resp.forkid()).map_err(EthHandshakeError::InvalidFork)
+        {
+            self.inner.disconnect(DisconnectReason::ProtocolBreach).await?;
+            return Err(err.into())
         }
+
+        // now we can create the `EthStream` because the peer has successfully completed
+        // the handshake
+        let stream = EthStream::new(version, self.inner);
+
+        Ok((stream, resp))
     }
 }
 
@@ -281,7 +298,7 @@ where
             }
         };
 
-        if matches!(msg.message, EthMessage::Status(_)) {
+        if matches!(msg.message, EthMessage::Status(_) | EthMessage::StatusEth69(_)) {
             return Poll::Ready(Some(Err(EthStreamError::EthHandshakeError(
                 EthHandshakeError::StatusNotInHandshake,
             ))))
@@ -303,7 +320,7 @@ where
     }
 
     fn start_send(self: Pin<&mut Self>, item: EthMessage) -> Result<(), Self::Error> {
-        if matches!(item, EthMessage::Status(_)) {
+        if matches!(item, EthMessage::Status(_) | EthMessage::StatusEth69(_)) {
             // TODO: to disconnect here we would need to do something similar to P2PStream's
             // start_disconnect, which would ideally be a part of the CanDisconnect trait, or at
             // least similar.
@@ -350,7 +367,8 @@ mod tests {
         errors::{EthHandshakeError, EthStreamError},
         hello::DEFAULT_TCP_PORT,
         p2pstream::{ProtocolVersion, UnauthedP2PStream},
-        EthMessage, EthStream, EthVersion, HelloMessageWithProtocols, PassthroughCodec, Status,
+        BlockRangeUpdate, EthMessage, EthStream, EthVersion, HelloMessageWithProtocols,
+        PassthroughCodec, Status, StatusEth69, StatusMessage,
     };
     use futures::{SinkExt, StreamExt};
     use reth_chainspec::NamedChain;
@@ -392,7 +410,7 @@ mod tests {
                 .unwrap();
 
             // just make sure it equals our status (our status is a clone of their status)
-            assert_eq!(their_status, status_clone);
+            assert_eq!(their_status, StatusMessage::Legacy(status_clone));
         });
 
         let outgoing = TcpStream::connect(local_addr).await.unwrap();
@@ -403,7 +421,7 @@ mod tests {
             UnauthedEthStream::new(sink).handshake(status, fork_filter).await.unwrap();
 
         // their status is a clone of our status, these should be equal
-        assert_eq!(their_status, status);
+        assert_eq!(their_status, StatusMessage::Legacy(status));
 
         // wait for it to finish
         handle.await.unwrap();
@@ -439,7 +457,7 @@ mod tests {
                 .unwrap();
 
             // just make sure it equals our status, and that the handshake succeeded
-            assert_eq!(their_status, status_clone);
+            assert_eq!(their_status, StatusMessage::Legacy(status_clone));
         });
 
         let outgoing = TcpStream::connect(local_addr).await.unwrap();
@@ -450,7 +468,7 @@ mod tests {
             UnauthedEthStream::new(sink).handshake(status, fork_filter).await.unwrap();
 
         // their status is a clone of our status, these should be equal
-        assert_eq!(their_status, status);
+        assert_eq!(their_status, StatusMessage::Legacy(status));
 
         // await the other handshake
         handle.await.unwrap();
@@ -510,6 +528,132 @@ mod tests {
         handle.await.unwrap();
     }
 
+    #[tokio::test]
+    async fn can_handshake_eth69() {
+        let genesis = B256::random();
+        let fork_filter = ForkFilter::new(Head::default(), genesis, 0, Vec::new());
+
+        let status = StatusEth69 {
+            version: EthVersion::Eth69 as u8,
+            chain: NamedChain::Mainnet.into(),
+            genesis,
+            forkid: fork_filter.current(),
+            earliest: 0,
+            latest: 100,
+            blockhash: B256::random(),
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+
+        let status_clone = status;
+        let fork_filter_clone = fork_filter.clone();
+        let handle = tokio::spawn(async move {
+            let (incoming, _) = listener.accept().await.unwrap();
+            let stream = PassthroughCodec::default().framed(incoming);
+            let (stream, their_status) = UnauthedEthStream::new(stream)
+                .handshake(status_clone, fork_filter_clone)
+                .await
+                .unwrap();
+
+            assert_eq!(stream.version(), EthVersion::Eth69);
+            assert_eq!(their_status, StatusMessage::Eth69(status_clone));
+        });
+
+        let outgoing = TcpStream::connect(local_addr).await.unwrap();
+        let sink = PassthroughCodec::default().framed(outgoing);
+
+        let (_, their_status) =
+            UnauthedEthStream::new(sink).handshake(status, fork_filter).await.unwrap();
+        assert_eq!(
+            their_status.block_range(),
+            Some(BlockRangeUpdate { earliest: 0, latest: 100, latest_hash: status.blockhash })
+        );
+
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn fail_handshake_on_invalid_block_range() {
+        let genesis = B256::random();
+        let fork_filter = ForkFilter::new(Head::default(), genesis, 0, Vec::new());
+
+        let status = StatusEth69 {
+            version: EthVersion::Eth69 as u8,
+            chain: NamedChain::Mainnet.into(),
+            genesis,
+            forkid: fork_filter.current(),
+            earliest: 0,
+            latest: 100,
+            blockhash: B256::random(),
+        };
+        let invalid_status = StatusEth69 { earliest: 101, ..status };
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+
+        let fork_filter_clone = fork_filter.clone();
+        let handle = tokio::spawn(async move {
+            let (incoming, _) = listener.accept().await.unwrap();
+            let stream = PassthroughCodec::default().framed(incoming);
+            let handshake_res =
+                UnauthedEthStream::new(stream).handshake(status, fork_filter_clone).await;
+
+            assert!(matches!(
+                handshake_res,
+                Err(EthStreamError::EthHandshakeError(EthHandshakeError::InvalidBlockRange {
+                    earliest: 101,
+                    latest: 100
+                }))
+            ));
+        });
+
+        let outgoing = TcpStream::connect(local_addr).await.unwrap();
+        let sink = PassthroughCodec::default().framed(outgoing);
+
+        // the peer disconnects after receiving our invalid status
+        let _ = UnauthedEthStream::new(sink).handshake(invalid_status, fork_filter).await;
+
+        handle.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn fail_handshake_on_mismatched_status_version() {
+        let genesis = B256::random();
+        let fork_filter = ForkFilter::new(Head::default(), genesis, 0, Vec::new());
+
+        let status = Status {
+            version: EthVersion::Eth68 as u8,
+            chain: NamedChain::Mainnet.into(),
+            total_difficulty: U256::ZERO,
+            blockhash: B256::random(),
+            genesis,
+            forkid: fork_filter.current(),
+        };
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let local_addr = listener.local_addr().unwrap();
+
+        let fork_filter_clone = fork_filter.clone();
+        let handle = tokio::spawn(async move {
+            let (incoming, _) = listener.accept().await.unwrap();
+            let stream = PassthroughCodec::default().framed(incoming);
+            let handshake_res =
+                UnauthedEthStream::new(stream).handshake(status, fork_filter_clone).await;
+            assert!(handshake_res.is_err());
+        });
+
+        let outgoing = TcpStream::connect(local_addr).await.unwrap();
+        let sink = PassthroughCodec::default().framed(outgoing);
+
+        // an eth/69 status is not a valid eth/68 status
+        let status69 = StatusEth69::new(&status, BlockRangeUpdate::default());
+        let handshake_res = UnauthedEthStream::new(sink).handshake(status69, fork_filter).await;
+        assert!(handshake_res.is_err());
+
+        handle.await.unwrap();
+    }
+
     #[tokio::test]
     async fn can_write_and_read_cleartext() {
         let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -700,7 +844,7 @@ mod tests {
                 .unwrap();
 
             // just make sure it equals our status (our status is a clone of their status)
-            assert_eq!(their_status, status_clone);
+            assert_eq!(their_status, StatusMessage::Legacy(status_clone));
         });
 
         let outgoing = TcpStream::connect(local_addr).await.unwrap();
@@ -1,5 +1,7 @@
 //! Helpers for resolving the external IP.
 //!
+//! Port mappings on the gateway can be maintained with a [`PortMapper`] via NAT-PMP or PCP.
+//!
 //! ## Feature Flags
 //!
 //! - `serde` (default): Enable serde support
@@ -12,10 +14,19 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
+mod mapping;
+mod natpmp;
+mod pcp;
+
+pub use mapping::{
+    default_gateway, ExternalAddr, PortMapper, PortMapping, PortMappingError, PortMappingProtocol,
+    TransportProtocol, DEFAULT_MAPPING_LIFETIME, NAT_PMP_PORT,
+};
+
 use std::{
     fmt,
     future::{poll_fn, Future},
-    net::{AddrParseError, IpAddr},
+    net::{AddrParseError, IpAddr, SocketAddr},
     pin::Pin,
     str::FromStr,
     task::{Context, Poll},
@@ -44,6 +55,12 @@ pub enum NatResolver {
     PublicIp,
     /// Use the given [`IpAddr`]
     ExternalIp(IpAddr),
+    /// Map ports and resolve the external IP via NAT-PMP on the given gateway, or the default
+    /// gateway if unset.
+    NatPmp(Option<IpAddr>),
+    /// Map ports and resolve the external IP via PCP on the given gateway, or the default gateway
+    /// if unset.
+    Pcp(Option<IpAddr>),
     /// Resolve nothing
     None,
 }
@@ -53,6 +70,34 @@ impl NatResolver {
     pub async fn external_addr(self) -> Option<IpAddr> {
         external_addr_with(self).await
     }
+
+    /// Returns the address of the NAT-PMP or PCP server, if this resolver maps ports on the
+    /// gateway.
+    pub fn gateway(&self) -> Option<SocketAddr> {
+        match self {
+            Self::NatPmp(gateway) | Self::Pcp(gateway) => {
+                let gateway = gateway.or_else(default_gateway)?;
+                Some(SocketAddr::new(gateway, NAT_PMP_PORT))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns true if this resolver maps ports on the gateway, which reports the external address
+    /// with the mappings.
+    pub const fn maps_ports(&self) -> bool {
+        matches!(self, Self::NatPmp(_) | Self::Pcp(_))
+    }
+
+    /// Returns a [`PortMapper`] without any mappings, if this resolver maps ports on the gateway.
+    pub fn port_mapper(&self) -> Option<PortMapper> {
+        let protocol = match self {
+            Self::NatPmp(_) => PortMappingProtocol::NatPmp,
+            Self::Pcp(_) => PortMappingProtocol::Pcp,
+            _ => return None,
+        };
+        Some(PortMapper::new(protocol, self.gateway()?))
+    }
 }
 
 impl fmt::Display for NatResolver {
@@ -62,6 +107,10 @@ impl fmt::Display for NatResolver {
             Self::Upnp => f.write_str("upnp"),
             Self::PublicIp => f.write_str("publicip"),
             Self::ExternalIp(ip) => write!(f, "extip:{ip}"),
+            Self::NatPmp(None) => f.write_str("natpmp"),
+            Self::NatPmp(Some(gateway)) => write!(f, "natpmp:{gateway}"),
+            Self::Pcp(None) => f.write_str("pcp"),
+            Self::Pcp(Some(gateway)) => write!(f, "pcp:{gateway}"),
             Self::None => f.write_str("none"),
         }
     }
@@ -87,7 +136,15 @@ impl FromStr for NatResolver {
             "upnp" => Self::Upnp,
             "none" => Self::None,
             "publicip" | "public-ip" => Self::PublicIp,
+            "natpmp" => Self::NatPmp(None),
+            "pcp" => Self::Pcp(None),
             s => {
+                if let Some(gateway) = s.strip_prefix("natpmp:") {
+                    return Ok(Self::NatPmp(Some(gateway.parse::<IpAddr>()?)))
+                }
+                if let Some(gateway) = s.strip_prefix("pcp:") {
+                    return Ok(Self::Pcp(Some(gateway.parse::<IpAddr>()?)))
+                }
                 let Some(ip) = s.strip_prefix("extip:") else {
                     return Err(ParseNatResolverError::UnknownVariant(format!(
                         "Unknown Nat Resolver: {s}"
@@ -181,7 +238,12 @@ pub async fn external_addr_with(resolver: NatResolver) -> Option<IpAddr> {
     match resolver {
         NatResolver::Any | NatResolver::Upnp | NatResolver::PublicIp => resolve_external_ip().await,
         NatResolver::ExternalIp(ip) => Some(ip),
-        NatResolver::None => None,
+        NatResolver::NatPmp(_) => {
+            natpmp::external_address(resolver.gateway()?).await.ok().map(IpAddr::V4)
+        }
+        // PCP has no request for just the external address, it is reported with the mappings of
+        // the `PortMapper`
+        NatResolver::Pcp(_) | NatResolver::None => None,
     }
 }
 
@@ -235,5 +297,15 @@ mod tests {
         let s = "extip:0.0.0.0";
         assert_eq!(ip, s.parse().unwrap());
         assert_eq!(ip.to_string().as_str(), s);
+
+        for (resolver, s) in [
+            (NatResolver::NatPmp(None), "natpmp"),
+            (NatResolver::NatPmp(Some(Ipv4Addr::new(192, 168, 1, 1).into())), "natpmp:192.168.1.1"),
+            (NatResolver::Pcp(None), "pcp"),
+            (NatResolver::Pcp(Some(Ipv4Addr::new(10, 0, 0, 1).into())), "pcp:10.0.0.1"),
+        ] {
+            assert_eq!(resolver, s.parse().unwrap());
+            assert_eq!(resolver.to_string().as_str(), s);
+        }
     }
 }
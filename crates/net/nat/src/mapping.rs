@@ -0,0 +1,490 @@
+//! Port mappings on the gateway via NAT-PMP or PCP.
+
+use crate::{natpmp, pcp};
+use std::{
+    fmt,
+    future::{poll_fn, Future},
+    io,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    pin::Pin,
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::{net::UdpSocket, time::Instant};
+use tracing::{debug, trace};
+
+/// The port NAT-PMP and PCP servers listen on.
+pub const NAT_PMP_PORT: u16 = 5351;
+
+/// The lifetime requested for port mappings, as recommended by RFC 6886.
+pub const DEFAULT_MAPPING_LIFETIME: Duration = Duration::from_secs(2 * 60 * 60);
+
+/// The timeout of the first attempt of a request, doubled on every retry.
+const INITIAL_REQUEST_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// How often a request is sent before giving up.
+const MAX_REQUEST_ATTEMPTS: u32 = 5;
+
+/// How long to wait before retrying after the gateway failed to map the ports.
+const RETRY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Lower bound for the renewal interval, in case the gateway grants very short lifetimes.
+const MIN_RENEW_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The transport protocol of a port mapping.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum TransportProtocol {
+    /// TCP, used by `RLPx`.
+    Tcp,
+    /// UDP, used by discovery.
+    Udp,
+}
+
+/// The protocol used to talk to the gateway.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum PortMappingProtocol {
+    /// NAT Port Mapping Protocol, see [RFC 6886](https://datatracker.ietf.org/doc/html/rfc6886)
+    NatPmp,
+    /// Port Control Protocol, see [RFC 6887](https://datatracker.ietf.org/doc/html/rfc6887)
+    Pcp,
+}
+
+/// A port mapping granted by the gateway.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PortMapping {
+    /// The transport protocol of the mapping.
+    pub protocol: TransportProtocol,
+    /// The local port.
+    pub internal_port: u16,
+    /// The port on the gateway, which may differ from the requested one.
+    pub external_port: u16,
+    /// The external address of the gateway.
+    pub external_ip: IpAddr,
+    /// How long the mapping is valid for.
+    pub lifetime: Duration,
+}
+
+/// The external address of the node as reported by the gateway.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ExternalAddr {
+    /// The external IP address.
+    pub ip: IpAddr,
+    /// The external TCP port, if a TCP port is mapped.
+    pub tcp_port: Option<u16>,
+    /// The external UDP port, if a UDP port is mapped.
+    pub udp_port: Option<u16>,
+}
+
+/// Errors when requesting port mappings.
+#[derive(Debug, thiserror::Error)]
+pub enum PortMappingError {
+    /// Failed to talk to the gateway.
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    /// The gateway did not respond.
+    #[error("no response from gateway")]
+    Timeout,
+    /// The gateway rejected the request.
+    #[error("gateway rejected request with result code {0}")]
+    ResultCode(u16),
+}
+
+/// Returns the default gateway of the host, if it can be determined.
+///
+/// This is only supported on Linux, on other platforms the gateway has to be configured.
+pub fn default_gateway() -> Option<IpAddr> {
+    #[cfg(target_os = "linux")]
+    {
+        let routes = std::fs::read_to_string("/proc/net/route").ok()?;
+        parse_default_gateway(&routes)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        None
+    }
+}
+
+/// Parses the gateway of the default route from the contents of `/proc/net/route`.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_default_gateway(routes: &str) -> Option<IpAddr> {
+    routes.lines().skip(1).find_map(|line| {
+        let mut fields = line.split_whitespace().skip(1);
+        let destination = fields.next()?;
+        let gateway = u32::from_str_radix(fields.next()?, 16).ok()?;
+        // the addresses are in network byte order, printed as a host order integer
+        (destination == "00000000" && gateway != 0)
+            .then(|| IpAddr::V4(Ipv4Addr::from(gateway.to_le_bytes())))
+    })
+}
+
+/// Binds a UDP socket connected to the gateway.
+pub(crate) async fn bind(gateway: SocketAddr) -> io::Result<UdpSocket> {
+    let local = match gateway {
+        SocketAddr::V4(_) => SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0),
+        SocketAddr::V6(_) => SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 0),
+    };
+    let socket = UdpSocket::bind(local).await?;
+    socket.connect(gateway).await?;
+    Ok(socket)
+}
+
+/// Sends the request to the gateway until a response is received, doubling the timeout on every
+/// attempt.
+///
+/// Datagrams for which `decode` returns `None` are ignored.
+pub(crate) async fn send_request<T>(
+    socket: &UdpSocket,
+    request: &[u8],
+    decode: impl Fn(&[u8]) -> Option<Result<T, PortMappingError>>,
+) -> Result<T, PortMappingError> {
+    let mut buf = [0u8; 1100];
+    let mut timeout = INITIAL_REQUEST_TIMEOUT;
+    for _ in 0..MAX_REQUEST_ATTEMPTS {
+        socket.send(request).await?;
+        let deadline = Instant::now() + timeout;
+        loop {
+            let Ok(res) = tokio::time::timeout_at(deadline, socket.recv(&mut buf)).await else {
+                break
+            };
+            if let Some(res) = decode(&buf[..res?]) {
+                return res
+            }
+        }
+        timeout *= 2;
+    }
+    Err(PortMappingError::Timeout)
+}
+
+/// A port to keep mapped.
+#[derive(Debug, Clone, Copy)]
+struct MappingRequest {
+    protocol: TransportProtocol,
+    port: u16,
+    /// Identifies the mapping for PCP.
+    nonce: pcp::Nonce,
+}
+
+/// Requests all mappings from the gateway.
+async fn request_mappings(
+    protocol: PortMappingProtocol,
+    gateway: SocketAddr,
+    requests: Vec<MappingRequest>,
+    lifetime: Duration,
+) -> Result<Vec<PortMapping>, PortMappingError> {
+    let mut mappings = Vec::with_capacity(requests.len());
+    for request in requests {
+        let mapping = match protocol {
+            PortMappingProtocol::NatPmp => {
+                natpmp::map_port(gateway, request.protocol, request.port, lifetime).await?
+            }
+            PortMappingProtocol::Pcp => {
+                pcp::map_port(gateway, request.nonce, request.protocol, request.port, lifetime)
+                    .await?
+            }
+        };
+        trace!(target: "net::nat", ?mapping, "Mapped port");
+        mappings.push(mapping);
+    }
+    Ok(mappings)
+}
+
+/// Keeps ports mapped on the gateway via NAT-PMP or PCP.
+///
+/// The mappings are requested on the first tick and renewed when half of their lifetime has
+/// passed. Every attempt yields the external address reported by the gateway.
+///
+/// The mappings expire on the gateway if they aren't renewed, they can be removed earlier with
+/// [`PortMapper::remove_mappings`].
+#[must_use = "Does nothing unless polled"]
+pub struct PortMapper {
+    protocol: PortMappingProtocol,
+    gateway: SocketAddr,
+    lifetime: Duration,
+    requests: Vec<MappingRequest>,
+    mappings: Vec<PortMapping>,
+    #[allow(clippy::type_complexity)]
+    future:
+        Option<Pin<Box<dyn Future<Output = Result<Vec<PortMapping>, PortMappingError>> + Send>>>,
+    renew: Pin<Box<tokio::time::Sleep>>,
+}
+
+impl fmt::Debug for PortMapper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PortMapper")
+            .field("protocol", &self.protocol)
+            .field("gateway", &self.gateway)
+            .field("lifetime", &self.lifetime)
+            .field("requests", &self.requests)
+            .field("mappings", &self.mappings)
+            .field("future", &self.future.as_ref().map(drop))
+            .field("renew", &self.renew.deadline())
+            .finish()
+    }
+}
+
+impl PortMapper {
+    /// Creates a new [`PortMapper`] for the gateway at the given address.
+    pub fn new(protocol: PortMappingProtocol, gateway: SocketAddr) -> Self {
+        Self {
+            protocol,
+            gateway,
+            lifetime: DEFAULT_MAPPING_LIFETIME,
+            requests: Vec::new(),
+            mappings: Vec::new(),
+            future: None,
+            renew: Box::pin(tokio::time::sleep(Duration::ZERO)),
+        }
+    }
+
+    /// Sets the lifetime to request for the mappings.
+    ///
+    /// Defaults to [`DEFAULT_MAPPING_LIFETIME`]
+    pub const fn with_lifetime(mut self, lifetime: Duration) -> Self {
+        self.lifetime = lifetime;
+        self
+    }
+
+    /// Adds a port to map, the same port is requested on the gateway.
+    pub fn with_mapping(mut self, protocol: TransportProtocol, port: u16) -> Self {
+        self.requests.push(MappingRequest { protocol, port, nonce: rand::random() });
+        self
+    }
+
+    /// Returns the address of the gateway.
+    pub const fn gateway(&self) -> SocketAddr {
+        self.gateway
+    }
+
+    /// Returns the currently active mappings.
+    pub fn mappings(&self) -> &[PortMapping] {
+        &self.mappings
+    }
+
+    /// Returns the external address of the active mappings.
+    pub fn external_addr(&self) -> Option<ExternalAddr> {
+        let ip = self.mappings.first()?.external_ip;
+        let port = |protocol| {
+            self.mappings.iter().find(|m| m.protocol == protocol).map(|m| m.external_port)
+        };
+        Some(ExternalAddr {
+            ip,
+            tcp_port: port(TransportProtocol::Tcp),
+            udp_port: port(TransportProtocol::Udp),
+        })
+    }
+
+    /// Returns the external port of the active mapping of the given internal port.
+    pub fn external_port(&self, protocol: TransportProtocol, internal_port: u16) -> Option<u16> {
+        self.mappings
+            .iter()
+            .find(|m| m.protocol == protocol && m.internal_port == internal_port)
+            .map(|m| m.external_port)
+    }
+
+    /// Removes the mappings from the gateway, by requesting them with a lifetime of zero.
+    ///
+    /// A pending request or renewal is cancelled. The mappings are requested again on the next
+    /// tick.
+    pub async fn remove_mappings(&mut self) -> Result<(), PortMappingError> {
+        // a pending request may have been granted already
+        let pending = self.future.take().is_some();
+        self.renew.as_mut().reset(Instant::now());
+        if self.mappings.is_empty() && !pending {
+            return Ok(())
+        }
+        self.mappings.clear();
+        request_mappings(self.protocol, self.gateway, self.requests.clone(), Duration::ZERO)
+            .await?;
+        trace!(target: "net::nat", gateway=%self.gateway, "Removed port mappings");
+        Ok(())
+    }
+
+    /// Completes when the mappings have been requested or renewed.
+    pub async fn tick(&mut self) -> Option<ExternalAddr> {
+        poll_fn(|cx| self.poll_tick(cx)).await
+    }
+
+    /// Polls for the next request or renewal of the mappings.
+    ///
+    /// This method can return the following values:
+    ///
+    ///  * `Poll::Pending` if the mappings are not due yet or the request is in progress.
+    ///  * `Poll::Ready(Option<ExternalAddr>)` if the mappings have been requested. This returns
+    ///    `None` if the attempt was unsuccessful, in which case it is retried later.
+    pub fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<Option<ExternalAddr>> {
+        if self.future.is_none() && self.renew.as_mut().poll(cx).is_ready() {
+            self.future = Some(Box::pin(request_mappings(
+                self.protocol,
+                self.gateway,
+                self.requests.clone(),
+                self.lifetime,
+            )));
+        }
+
+        let Some(mut fut) = self.future.take() else { return Poll::Pending };
+        let next = match fut.as_mut().poll(cx) {
+            Poll::Pending => {
+                self.future = Some(fut);
+                return Poll::Pending
+            }
+            Poll::Ready(Ok(mappings)) => {
+                // renew when half of the shortest lifetime has passed
+                let lifetime = mappings.iter().map(|m| m.lifetime).min().unwrap_or(self.lifetime);
+                self.mappings = mappings;
+                (lifetime / 2).max(MIN_RENEW_INTERVAL)
+            }
+            Poll::Ready(Err(err)) => {
+                debug!(target: "net::nat", %err, gateway=%self.gateway, "Failed to map ports");
+                self.mappings.clear();
+                RETRY_INTERVAL
+            }
+        };
+
+        self.renew.as_mut().reset(Instant::now() + next);
+        // register the waker for the next renewal
+        let _ = self.renew.as_mut().poll(cx);
+
+        Poll::Ready(self.external_addr())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicU32, AtomicU8, Ordering},
+        Arc,
+    };
+
+    /// A NAT-PMP and PCP gateway that maps every port to the same external port.
+    struct MockGateway {
+        addr: SocketAddr,
+        /// The last octet of the external address.
+        external_ip: Arc<AtomicU8>,
+        /// The number of received mapping requests.
+        requests: Arc<AtomicU32>,
+        /// The lifetime of the last mapping request.
+        lifetime: Arc<AtomicU32>,
+    }
+
+    impl MockGateway {
+        async fn spawn(lifetime: u32) -> Self {
+            let socket = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+            let addr = socket.local_addr().unwrap();
+            let external_ip = Arc::new(AtomicU8::new(1));
+            let requests = Arc::new(AtomicU32::new(0));
+            let last_lifetime = Arc::new(AtomicU32::new(0));
+
+            let (ip, count, requested) =
+                (external_ip.clone(), requests.clone(), last_lifetime.clone());
+            tokio::spawn(async move {
+                let mut buf = [0u8; 1100];
+                loop {
+                    let (len, from) = socket.recv_from(&mut buf).await.unwrap();
+                    let request = &buf[..len];
+                    let external = Ipv4Addr::new(1, 2, 3, ip.load(Ordering::SeqCst));
+                    let response = match (request[0], request[1]) {
+                        // NAT-PMP external address
+                        (0, 0) => {
+                            let mut resp = vec![0, 128, 0, 0, 0, 0, 0, 1];
+                            resp.extend_from_slice(&external.octets());
+                            resp
+                        }
+                        // NAT-PMP mapping
+                        (0, op) => {
+                            count.fetch_add(1, Ordering::SeqCst);
+                            requested.store(
+                                u32::from_be_bytes(request[8..12].try_into().unwrap()),
+                                Ordering::SeqCst,
+                            );
+                            let mut resp = vec![0, 128 + op, 0, 0, 0, 0, 0, 1];
+                            resp.extend_from_slice(&request[4..6]);
+                            resp.extend_from_slice(&request[6..8]);
+                            resp.extend_from_slice(&lifetime.to_be_bytes());
+                            resp
+                        }
+                        // PCP mapping
+                        (2, 1) => {
+                            count.fetch_add(1, Ordering::SeqCst);
+                            requested.store(
+                                u32::from_be_bytes(request[4..8].try_into().unwrap()),
+                                Ordering::SeqCst,
+                            );
+                            let mut resp = request.to_vec();
+                            resp[1] |= 0x80;
+                            resp[4..8].copy_from_slice(&lifetime.to_be_bytes());
+                            resp[44..60].copy_from_slice(&external.to_ipv6_mapped().octets());
+                            resp
+                        }
+                        _ => continue,
+                    };
+                    socket.send_to(&response, from).await.unwrap();
+                }
+            });
+
+            Self { addr, external_ip, requests, lifetime: last_lifetime }
+        }
+    }
+
+    #[test]
+    fn parse_route_table() {
+        let routes = "\
+Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT
+eth0\t0001A8C0\t00000000\t0001\t0\t0\t0\t00FFFFFF\t0\t0\t0
+eth0\t00000000\t0101A8C0\t0003\t0\t0\t100\t00000000\t0\t0\t0
+";
+        assert_eq!(parse_default_gateway(routes), Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 1))));
+    }
+
+    async fn renews_mappings(protocol: PortMappingProtocol) {
+        let gateway = MockGateway::spawn(2).await;
+        let mut mapper = PortMapper::new(protocol, gateway.addr)
+            .with_mapping(TransportProtocol::Tcp, 30303)
+            .with_mapping(TransportProtocol::Udp, 30304);
+
+        let addr = mapper.tick().await.unwrap();
+        assert_eq!(
+            addr,
+            ExternalAddr {
+                ip: Ipv4Addr::new(1, 2, 3, 1).into(),
+                tcp_port: Some(30303),
+                udp_port: Some(30304),
+            }
+        );
+        assert_eq!(mapper.mappings().len(), 2);
+        assert_eq!(gateway.requests.load(Ordering::SeqCst), 2);
+
+        // the mappings are renewed before they expire and pick up the new address
+        gateway.external_ip.store(2, Ordering::SeqCst);
+        let start = Instant::now();
+        let addr = mapper.tick().await.unwrap();
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert_eq!(addr.ip, IpAddr::V4(Ipv4Addr::new(1, 2, 3, 2)));
+        assert_eq!(gateway.requests.load(Ordering::SeqCst), 4);
+        assert_eq!(mapper.external_port(TransportProtocol::Udp, 30304), Some(30304));
+        assert_eq!(mapper.external_port(TransportProtocol::Tcp, 30304), None);
+
+        // the mappings are removed with a lifetime of zero
+        mapper.remove_mappings().await.unwrap();
+        assert!(mapper.mappings().is_empty());
+        assert_eq!(gateway.requests.load(Ordering::SeqCst), 6);
+        assert_eq!(gateway.lifetime.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn natpmp_renews_mappings() {
+        renews_mappings(PortMappingProtocol::NatPmp).await;
+    }
+
+    #[tokio::test]
+    async fn pcp_renews_mappings() {
+        renews_mappings(PortMappingProtocol::Pcp).await;
+    }
+
+    #[tokio::test]
+    async fn natpmp_external_address() {
+        let gateway = MockGateway::spawn(2).await;
+        let ip = natpmp::external_address(gateway.addr).await.unwrap();
+        assert_eq!(ip, Ipv4Addr::new(1, 2, 3, 1));
+    }
+}
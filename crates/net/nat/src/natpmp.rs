@@ -0,0 +1,171 @@
+//! NAT Port Mapping Protocol (NAT-PMP) client.
+//!
+//! See also [RFC 6886](https://datatracker.ietf.org/doc/html/rfc6886)
+
+use crate::mapping::{bind, send_request, PortMapping, PortMappingError, TransportProtocol};
+use std::{
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+use tokio::net::UdpSocket;
+
+/// The NAT-PMP version.
+const VERSION: u8 = 0;
+
+/// Opcode to request the external address of the gateway.
+const OP_EXTERNAL_ADDRESS: u8 = 0;
+
+/// Opcode to map a UDP port.
+const OP_MAP_UDP: u8 = 1;
+
+/// Opcode to map a TCP port.
+const OP_MAP_TCP: u8 = 2;
+
+/// Responses have the opcode of the request plus 128.
+const OP_RESPONSE: u8 = 128;
+
+/// Length of the external address response: version, opcode, result code, epoch, address.
+const EXTERNAL_ADDRESS_RESPONSE_LEN: usize = 12;
+
+/// Length of a mapping request: version, opcode, reserved, internal port, suggested external
+/// port, lifetime.
+const MAP_REQUEST_LEN: usize = 12;
+
+/// Length of a mapping response: version, opcode, result code, epoch, internal port, external
+/// port, lifetime.
+const MAP_RESPONSE_LEN: usize = 16;
+
+/// Requests the external address of the gateway.
+pub(crate) async fn external_address(gateway: SocketAddr) -> Result<Ipv4Addr, PortMappingError> {
+    let socket = bind(gateway).await?;
+    request_external_address(&socket).await
+}
+
+async fn request_external_address(socket: &UdpSocket) -> Result<Ipv4Addr, PortMappingError> {
+    let request = [VERSION, OP_EXTERNAL_ADDRESS];
+    send_request(socket, &request, decode_external_address).await
+}
+
+/// Requests a mapping of the given internal port on the gateway.
+///
+/// A lifetime of zero removes the mapping.
+pub(crate) async fn map_port(
+    gateway: SocketAddr,
+    protocol: TransportProtocol,
+    internal_port: u16,
+    lifetime: Duration,
+) -> Result<PortMapping, PortMappingError> {
+    let socket = bind(gateway).await?;
+    // NAT-PMP reports the external address with a separate request
+    let external_ip = request_external_address(&socket).await?;
+    let request = encode_map_request(protocol, internal_port, lifetime);
+    let (external_port, lifetime) =
+        send_request(&socket, &request, |response| decode_map_response(protocol, response)).await?;
+
+    Ok(PortMapping {
+        protocol,
+        internal_port,
+        external_port,
+        external_ip: IpAddr::V4(external_ip),
+        lifetime,
+    })
+}
+
+const fn map_opcode(protocol: TransportProtocol) -> u8 {
+    match protocol {
+        TransportProtocol::Udp => OP_MAP_UDP,
+        TransportProtocol::Tcp => OP_MAP_TCP,
+    }
+}
+
+fn encode_map_request(
+    protocol: TransportProtocol,
+    internal_port: u16,
+    lifetime: Duration,
+) -> [u8; MAP_REQUEST_LEN] {
+    let mut buf = [0u8; MAP_REQUEST_LEN];
+    buf[0] = VERSION;
+    buf[1] = map_opcode(protocol);
+    buf[4..6].copy_from_slice(&internal_port.to_be_bytes());
+    // suggest the same port externally, requests to remove the mapping must not suggest a port
+    if !lifetime.is_zero() {
+        buf[6..8].copy_from_slice(&internal_port.to_be_bytes());
+    }
+    buf[8..12].copy_from_slice(&lifetime_secs(lifetime).to_be_bytes());
+    buf
+}
+
+/// Decodes the response header and returns an error if the gateway did not succeed.
+///
+/// Returns `None` if the response is not a response to the expected opcode.
+fn decode_header(opcode: u8, response: &[u8], len: usize) -> Option<Result<(), PortMappingError>> {
+    if response.len() < len || response[0] != VERSION || response[1] != OP_RESPONSE + opcode {
+        return None
+    }
+    let result_code = u16::from_be_bytes([response[2], response[3]]);
+    if result_code != 0 {
+        return Some(Err(PortMappingError::ResultCode(result_code)))
+    }
+    Some(Ok(()))
+}
+
+fn decode_external_address(response: &[u8]) -> Option<Result<Ipv4Addr, PortMappingError>> {
+    Some(
+        decode_header(OP_EXTERNAL_ADDRESS, response, EXTERNAL_ADDRESS_RESPONSE_LEN)?
+            .map(|_| Ipv4Addr::new(response[8], response[9], response[10], response[11])),
+    )
+}
+
+fn decode_map_response(
+    protocol: TransportProtocol,
+    response: &[u8],
+) -> Option<Result<(u16, Duration), PortMappingError>> {
+    Some(decode_header(map_opcode(protocol), response, MAP_RESPONSE_LEN)?.map(|_| {
+        let external_port = u16::from_be_bytes([response[10], response[11]]);
+        let lifetime = u32::from_be_bytes([response[12], response[13], response[14], response[15]]);
+        (external_port, Duration::from_secs(lifetime as u64))
+    }))
+}
+
+/// Converts the lifetime to seconds, saturating at [`u32::MAX`].
+pub(crate) fn lifetime_secs(lifetime: Duration) -> u32 {
+    lifetime.as_secs().try_into().unwrap_or(u32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_map_tcp() {
+        let request = encode_map_request(TransportProtocol::Tcp, 30303, Duration::from_secs(7200));
+        assert_eq!(request, [0, 2, 0, 0, 0x76, 0x5f, 0x76, 0x5f, 0, 0, 0x1c, 0x20]);
+    }
+
+    #[test]
+    fn encode_unmap_tcp() {
+        let request = encode_map_request(TransportProtocol::Tcp, 30303, Duration::ZERO);
+        assert_eq!(request, [0, 2, 0, 0, 0x76, 0x5f, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn decode_map_udp() {
+        let response = [0, 129, 0, 0, 0, 0, 0, 1, 0x76, 0x5f, 0x76, 0x60, 0, 0, 0x0e, 0x10];
+        let (port, lifetime) =
+            decode_map_response(TransportProtocol::Udp, &response).unwrap().unwrap();
+        assert_eq!(port, 30304);
+        assert_eq!(lifetime, Duration::from_secs(3600));
+
+        // response to a different opcode is ignored
+        assert!(decode_map_response(TransportProtocol::Tcp, &response).is_none());
+    }
+
+    #[test]
+    fn decode_result_code() {
+        let response = [0, 128, 0, 2, 0, 0, 0, 1, 0, 0, 0, 0];
+        assert!(matches!(
+            decode_external_address(&response),
+            Some(Err(PortMappingError::ResultCode(2)))
+        ));
+    }
+}
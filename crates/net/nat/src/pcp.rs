@@ -0,0 +1,177 @@
+//! Port Control Protocol (PCP) client.
+//!
+//! See also [RFC 6887](https://datatracker.ietf.org/doc/html/rfc6887)
+
+use crate::{
+    mapping::{bind, send_request, PortMapping, PortMappingError, TransportProtocol},
+    natpmp::lifetime_secs,
+};
+use std::{
+    net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+    time::Duration,
+};
+
+/// The PCP version.
+const VERSION: u8 = 2;
+
+/// The `MAP` opcode.
+const OP_MAP: u8 = 1;
+
+/// The bit set in the opcode of responses.
+const RESPONSE_BIT: u8 = 0x80;
+
+/// Length of the common request and response header.
+const HEADER_LEN: usize = 24;
+
+/// Length of a `MAP` request or response: header, nonce, protocol, reserved, internal port,
+/// external port, external address.
+const MAP_LEN: usize = HEADER_LEN + 36;
+
+/// The IANA protocol number of TCP.
+const PROTOCOL_TCP: u8 = 6;
+
+/// The IANA protocol number of UDP.
+const PROTOCOL_UDP: u8 = 17;
+
+/// The nonce that identifies a mapping.
+///
+/// Renewals of a mapping must use the same nonce.
+pub(crate) type Nonce = [u8; 12];
+
+/// Requests a mapping of the given internal port on the gateway.
+///
+/// A lifetime of zero removes the mapping.
+pub(crate) async fn map_port(
+    gateway: SocketAddr,
+    nonce: Nonce,
+    protocol: TransportProtocol,
+    internal_port: u16,
+    lifetime: Duration,
+) -> Result<PortMapping, PortMappingError> {
+    let socket = bind(gateway).await?;
+    let client_ip = socket.local_addr()?.ip();
+    let request = encode_map_request(client_ip, nonce, protocol, internal_port, lifetime);
+    let (external_port, external_ip, lifetime) =
+        send_request(&socket, &request, |response| decode_map_response(nonce, protocol, response))
+            .await?;
+
+    Ok(PortMapping { protocol, internal_port, external_port, external_ip, lifetime })
+}
+
+const fn protocol_number(protocol: TransportProtocol) -> u8 {
+    match protocol {
+        TransportProtocol::Tcp => PROTOCOL_TCP,
+        TransportProtocol::Udp => PROTOCOL_UDP,
+    }
+}
+
+/// PCP always encodes addresses as IPv6, with IPv4 addresses mapped.
+const fn to_ipv6(ip: IpAddr) -> Ipv6Addr {
+    match ip {
+        IpAddr::V4(ip) => ip.to_ipv6_mapped(),
+        IpAddr::V6(ip) => ip,
+    }
+}
+
+fn encode_map_request(
+    client_ip: IpAddr,
+    nonce: Nonce,
+    protocol: TransportProtocol,
+    internal_port: u16,
+    lifetime: Duration,
+) -> [u8; MAP_LEN] {
+    let mut buf = [0u8; MAP_LEN];
+    buf[0] = VERSION;
+    buf[1] = OP_MAP;
+    buf[4..8].copy_from_slice(&lifetime_secs(lifetime).to_be_bytes());
+    buf[8..24].copy_from_slice(&to_ipv6(client_ip).octets());
+
+    buf[24..36].copy_from_slice(&nonce);
+    buf[36] = protocol_number(protocol);
+    buf[40..42].copy_from_slice(&internal_port.to_be_bytes());
+    // suggest the same port externally
+    buf[42..44].copy_from_slice(&internal_port.to_be_bytes());
+    // no preference for the external address, of the same family as the client
+    let suggested_ip = match client_ip {
+        IpAddr::V4(_) => Ipv4Addr::UNSPECIFIED.to_ipv6_mapped(),
+        IpAddr::V6(_) => Ipv6Addr::UNSPECIFIED,
+    };
+    buf[44..60].copy_from_slice(&suggested_ip.octets());
+    buf
+}
+
+/// Decodes a `MAP` response into the assigned external port, address and lifetime.
+///
+/// Returns `None` if the response does not belong to the request with the given nonce.
+fn decode_map_response(
+    nonce: Nonce,
+    protocol: TransportProtocol,
+    response: &[u8],
+) -> Option<Result<(u16, IpAddr, Duration), PortMappingError>> {
+    if response.len() < MAP_LEN ||
+        response[0] != VERSION ||
+        response[1] != RESPONSE_BIT | OP_MAP ||
+        response[24..36] != nonce ||
+        response[36] != protocol_number(protocol)
+    {
+        return None
+    }
+
+    let result_code = response[3];
+    if result_code != 0 {
+        return Some(Err(PortMappingError::ResultCode(result_code as u16)))
+    }
+
+    let lifetime = u32::from_be_bytes([response[4], response[5], response[6], response[7]]);
+    let external_port = u16::from_be_bytes([response[42], response[43]]);
+    let mut octets = [0u8; 16];
+    octets.copy_from_slice(&response[44..60]);
+    let external_ip = Ipv6Addr::from(octets);
+    let external_ip =
+        external_ip.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(IpAddr::V6(external_ip));
+
+    Some(Ok((external_port, external_ip, Duration::from_secs(lifetime as u64))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_roundtrip() {
+        let nonce = [7u8; 12];
+        let client_ip = IpAddr::V4(Ipv4Addr::new(192, 168, 1, 2));
+        let request = encode_map_request(
+            client_ip,
+            nonce,
+            TransportProtocol::Udp,
+            30303,
+            Duration::from_secs(7200),
+        );
+        assert_eq!(&request[..2], &[2, 1]);
+        assert_eq!(&request[8..24], &Ipv4Addr::new(192, 168, 1, 2).to_ipv6_mapped().octets());
+        assert_eq!(request[36], PROTOCOL_UDP);
+
+        // a gateway answers with the request, the response bit and its assignment
+        let mut response = request;
+        response[1] |= RESPONSE_BIT;
+        response[42..44].copy_from_slice(&30304u16.to_be_bytes());
+        response[44..60].copy_from_slice(&Ipv4Addr::new(1, 2, 3, 4).to_ipv6_mapped().octets());
+
+        let (port, ip, lifetime) =
+            decode_map_response(nonce, TransportProtocol::Udp, &response).unwrap().unwrap();
+        assert_eq!(port, 30304);
+        assert_eq!(ip, IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4)));
+        assert_eq!(lifetime, Duration::from_secs(7200));
+
+        // responses for other mappings are ignored
+        assert!(decode_map_response([0u8; 12], TransportProtocol::Udp, &response).is_none());
+        assert!(decode_map_response(nonce, TransportProtocol::Tcp, &response).is_none());
+
+        response[3] = 8;
+        assert!(matches!(
+            decode_map_response(nonce, TransportProtocol::Udp, &response),
+            Some(Err(PortMappingError::ResultCode(8)))
+        ));
+    }
+}
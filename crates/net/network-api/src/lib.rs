@@ -17,8 +17,14 @@ pub use alloy_rpc_types_admin::EthProtocolInfo;
 pub use error::NetworkError;
 pub use reputation::{Reputation, ReputationChangeKind};
 use reth_eth_wire::{capability::Capabilities, DisconnectReason, EthVersion, Status};
+pub use reth_net_banlist::{BanEntry, BanTarget};
 use reth_network_peers::NodeRecord;
-use std::{future::Future, net::SocketAddr, sync::Arc, time::Instant};
+use std::{
+    future::Future,
+    net::SocketAddr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 /// The `PeerId` type.
 pub type PeerId = alloy_primitives::B512;
@@ -154,6 +160,19 @@ pub trait Peers: PeersInfo {
         &self,
         peer_id: PeerId,
     ) -> impl Future<Output = Result<Option<Reputation>, NetworkError>> + Send;
+
+    /// Bans a peer or a range of IP addresses, indefinitely or for the given duration.
+    ///
+    /// Connected peers that match the target are disconnected.
+    fn ban(&self, target: BanTarget, duration: Option<Duration>);
+
+    /// Lifts the ban of a peer or a range of IP addresses.
+    ///
+    /// Returns `true` if the target was banned.
+    fn unban(&self, target: BanTarget) -> impl Future<Output = Result<bool, NetworkError>> + Send;
+
+    /// Returns all active bans.
+    fn bans(&self) -> impl Future<Output = Result<Vec<BanEntry>, NetworkError>> + Send;
 }
 
 /// Represents the kind of peer
@@ -4,14 +4,17 @@
 //! generic over it.
 
 use crate::{
-    NetworkError, NetworkInfo, NetworkStatus, PeerId, PeerInfo, PeerKind, Peers, PeersInfo,
-    Reputation, ReputationChangeKind,
+    BanEntry, BanTarget, NetworkError, NetworkInfo, NetworkStatus, PeerId, PeerInfo, PeerKind,
+    Peers, PeersInfo, Reputation, ReputationChangeKind,
 };
 use alloy_rpc_types_admin::EthProtocolInfo;
 use enr::{secp256k1::SecretKey, Enr};
 use reth_eth_wire::{DisconnectReason, ProtocolVersion};
 use reth_network_peers::NodeRecord;
-use std::net::{IpAddr, SocketAddr};
+use std::{
+    net::{IpAddr, SocketAddr},
+    time::Duration,
+};
 
 /// A type that implements all network trait that does nothing.
 ///
@@ -107,4 +110,14 @@ impl Peers for NoopNetwork {
     async fn reputation_by_id(&self, _peer_id: PeerId) -> Result<Option<Reputation>, NetworkError> {
         Ok(None)
     }
+
+    fn ban(&self, _target: BanTarget, _duration: Option<Duration>) {}
+
+    async fn unban(&self, _target: BanTarget) -> Result<bool, NetworkError> {
+        Ok(false)
+    }
+
+    async fn bans(&self) -> Result<Vec<BanEntry>, NetworkError> {
+        Ok(vec![])
+    }
 }
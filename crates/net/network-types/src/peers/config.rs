@@ -1,12 +1,12 @@
 //! Configuration for peering.
 
 use crate::{BackoffKind, ReputationChangeWeights};
-use reth_net_banlist::BanList;
+use reth_net_banlist::{BanEntry, BanList};
 use reth_network_peers::NodeRecord;
 use std::{
     collections::HashSet,
     io::{self, ErrorKind},
-    path::Path,
+    path::{Path, PathBuf},
     time::Duration,
 };
 use tracing::info;
@@ -145,6 +145,9 @@ pub struct PeersConfig {
     /// Restrictions on `PeerIds` and Ips.
     #[cfg_attr(feature = "serde", serde(skip))]
     pub ban_list: BanList,
+    /// The file the ban list is persisted to, if any.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub ban_list_file: Option<PathBuf>,
     /// Restrictions on connections.
     pub connection_info: ConnectionsConfig,
     /// How to weigh reputation changes.
@@ -162,6 +165,7 @@ impl Default for PeersConfig {
             connection_info: Default::default(),
             reputation_weights: Default::default(),
             ban_list: Default::default(),
+            ban_list_file: None,
             // Ban peers for 12h
             ban_duration: Duration::from_secs(60 * 60 * 12),
             backoff_durations: Default::default(),
@@ -280,6 +284,30 @@ impl PeersConfig {
         Ok(self.with_basic_nodes(nodes))
     }
 
+    /// Read bans from file and add them to the [`BanList`].
+    ///
+    /// The file is also where changes to the ban list are persisted, so it is not required to
+    /// exist yet.
+    pub fn with_ban_list_from_file(
+        mut self,
+        optional_file: Option<impl Into<PathBuf>>,
+    ) -> Result<Self, io::Error> {
+        let Some(file_path) = optional_file else { return Ok(self) };
+        let file_path = file_path.into();
+        let reader = match std::fs::File::open(&file_path) {
+            Ok(file) => Some(io::BufReader::new(file)),
+            Err(e) if e.kind() == ErrorKind::NotFound => None,
+            Err(e) => Err(e)?,
+        };
+        if let Some(reader) = reader {
+            info!(target: "net::peers", file = %file_path.display(), "Loading saved bans");
+            let entries: Vec<BanEntry> = serde_json::from_reader(reader)?;
+            self.ban_list.extend_entries(entries);
+        }
+        self.ban_list_file = Some(file_path);
+        Ok(self)
+    }
+
     /// Returns settings for testing
     #[cfg(any(test, feature = "test-utils"))]
     pub fn test() -> Self {
@@ -10,7 +10,7 @@ use reth_chainspec::{ChainSpec, MAINNET};
 use reth_discv4::{Discv4Config, Discv4ConfigBuilder, NatResolver, DEFAULT_DISCOVERY_ADDRESS};
 use reth_discv5::NetworkStackId;
 use reth_dns_discovery::DnsDiscoveryConfig;
-use reth_eth_wire::{HelloMessage, HelloMessageWithProtocols, Status};
+use reth_eth_wire::{BlockRangeUpdate, HelloMessage, HelloMessageWithProtocols, Status};
 use reth_network_peers::{mainnet_nodes, pk2id, sepolia_nodes, PeerId, TrustedPeer};
 use reth_network_types::{PeersConfig, SessionsConfig};
 use reth_primitives::{ForkFilter, Head};
@@ -48,6 +48,9 @@ pub struct NetworkConfig<C> {
     pub discovery_v4_config: Option<Discv4Config>,
     /// How to set up discovery version 5.
     pub discovery_v5_config: Option<reth_discv5::Config>,
+    /// How to resolve the external address, ports are mapped on the gateway if the resolver
+    /// supports it.
+    pub nat: Option<NatResolver>,
     /// Address to listen for incoming connections
     pub listener_addr: SocketAddr,
     /// How to instantiate peer manager.
@@ -71,6 +74,8 @@ pub struct NetworkConfig<C> {
     pub executor: Box<dyn TaskSpawner>,
     /// The `Status` message to send to peers at the beginning.
     pub status: Status,
+    /// The range of blocks announced to `eth/69` peers at the beginning.
+    pub block_range: BlockRangeUpdate,
     /// Sets the hello message for the p2p handshake in `RLPx`
     pub hello_message: HelloMessageWithProtocols,
     /// Additional protocols to announce and handle in `RLPx`
@@ -159,6 +164,8 @@ pub struct NetworkConfigBuilder {
     discovery_v4_builder: Option<Discv4ConfigBuilder>,
     /// How to set up discovery version 5.
     discovery_v5_builder: Option<reth_discv5::ConfigBuilder>,
+    /// How to resolve the external address.
+    nat: Option<NatResolver>,
     /// All boot nodes to start network discovery with.
     boot_nodes: HashSet<TrustedPeer>,
     /// Address to use for discovery
@@ -181,6 +188,8 @@ pub struct NetworkConfigBuilder {
     extra_protocols: RlpxSubProtocols,
     /// Head used to start set for the fork filter and status.
     head: Option<Head>,
+    /// The earliest block the node can serve to `eth/69` peers.
+    earliest_block: Option<u64>,
     /// Whether tx gossip is disabled
     tx_gossip_disabled: bool,
     /// The block importer type
@@ -205,6 +214,7 @@ impl NetworkConfigBuilder {
             dns_discovery_config: Some(Default::default()),
             discovery_v4_builder: Some(Default::default()),
             discovery_v5_builder: None,
+            nat: None,
             boot_nodes: Default::default(),
             discovery_addr: None,
             listener_addr: None,
@@ -216,6 +226,7 @@ impl NetworkConfigBuilder {
             hello_message: None,
             extra_protocols: Default::default(),
             head: None,
+            earliest_block: None,
             tx_gossip_disabled: false,
             block_import: None,
             transactions_manager_config: Default::default(),
@@ -262,6 +273,15 @@ impl NetworkConfigBuilder {
         self
     }
 
+    /// Sets the earliest block the node can serve, e.g. because older history was pruned.
+    ///
+    /// This is announced to `eth/69` peers as part of the block range. If not set, this defaults
+    /// to genesis.
+    pub const fn earliest_block(mut self, number: u64) -> Self {
+        self.earliest_block = Some(number);
+        self
+    }
+
     /// Sets the `HelloMessage` to send when connecting to peers.
     ///
     /// ```
@@ -349,8 +369,10 @@ impl NetworkConfigBuilder {
     /// If no [`Discv4ConfigBuilder`] is set via [`Self::discovery`], this will create a new one.
     ///
     /// This is a convenience function for setting the external ip resolver on the default
-    /// [`Discv4Config`] config.
+    /// [`Discv4Config`] config. If the resolver maps ports on the gateway, the network maps the
+    /// `RLPx` and discovery ports.
     pub fn external_ip_resolver(mut self, resolver: NatResolver) -> Self {
+        self.nat = Some(resolver);
         self.discovery_v4_builder
             .get_or_insert_with(Discv4Config::builder)
             .external_ip_resolver(Some(resolver));
@@ -474,6 +496,7 @@ impl NetworkConfigBuilder {
             mut dns_discovery_config,
             discovery_v4_builder,
             mut discovery_v5_builder,
+            nat,
             boot_nodes,
             discovery_addr,
             listener_addr,
@@ -485,6 +508,7 @@ impl NetworkConfigBuilder {
             hello_message,
             extra_protocols,
             head,
+            earliest_block,
             tx_gossip_disabled,
             block_import,
             transactions_manager_config,
@@ -515,6 +539,11 @@ impl NetworkConfigBuilder {
 
         // set the status
         let status = Status::spec_builder(&chain_spec, &head).build();
+        let block_range = BlockRangeUpdate {
+            earliest: earliest_block.unwrap_or_default().min(head.number),
+            latest: head.number,
+            latest_hash: head.hash,
+        };
 
         // set a fork filter based on the chain spec and head
         let fork_filter = chain_spec.fork_filter(head);
@@ -537,6 +566,7 @@ impl NetworkConfigBuilder {
             dns_discovery_config,
             discovery_v4_config: discovery_v4_builder.map(|builder| builder.build()),
             discovery_v5_config: discovery_v5_builder.map(|builder| builder.build()),
+            nat,
             discovery_v4_addr: discovery_addr.unwrap_or(DEFAULT_DISCOVERY_ADDRESS),
             listener_addr,
             peers_config: peers_config.unwrap_or_default(),
@@ -546,6 +576,7 @@ impl NetworkConfigBuilder {
             network_mode,
             executor: executor.unwrap_or_else(|| Box::<TokioTaskExecutor>::default()),
             status,
+            block_range,
             hello_message,
             extra_protocols,
             fork_filter,
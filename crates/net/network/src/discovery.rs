@@ -13,6 +13,8 @@ use reth_discv5::{DiscoveredPeer, Discv5};
 use reth_dns_discovery::{
     DnsDiscoveryConfig, DnsDiscoveryHandle, DnsDiscoveryService, DnsNodeRecordUpdate, DnsResolver,
 };
+use reth_net_banlist::IpNet;
+use reth_net_nat::{ExternalAddr, NatResolver, PortMapper, TransportProtocol};
 use reth_network_peers::{NodeRecord, PeerId};
 use reth_primitives::{EnrForkIdEntry, ForkId};
 use secp256k1::SecretKey;
@@ -52,6 +54,8 @@ pub struct Discovery {
     _discv4_service: Option<JoinHandle<()>>,
     /// Handler to interact with the Discovery v5 service
     discv5: Option<Discv5>,
+    /// The UDP port the Discovery v5 service listens on.
+    discv5_udp_port: Option<u16>,
     /// All KAD table updates from the discv5 service.
     discv5_updates: Option<ReceiverStream<discv5::Event>>,
     /// Handler to interact with the DNS discovery service
@@ -60,6 +64,8 @@ pub struct Discovery {
     dns_discovery_updates: Option<ReceiverStream<DnsNodeRecordUpdate>>,
     /// The handle to the spawned DNS discovery service
     _dns_disc_service: Option<JoinHandle<()>>,
+    /// Networks whose discovered nodes are dropped.
+    banned_networks: Vec<IpNet>,
     /// Events buffered until polled.
     queued_events: VecDeque<DiscoveryEvent>,
     /// List of listeners subscribed to discovery events.
@@ -98,6 +104,7 @@ impl Discovery {
             Ok((Some(discv4), Some(discv4_updates), Some(discv4_service)))
         };
 
+        let discv5_udp_port = discv5_config.as_ref().map(|config| config.discovery_socket().port());
         let discv5_future = async {
             let Some(config) = discv5_config else { return Ok::<_, NetworkError>((None, None)) };
             let (discv5, discv5_updates, _local_enr_discv5) = Discv5::start(&sk, config).await?;
@@ -128,8 +135,10 @@ impl Discovery {
             discv4_updates,
             _discv4_service,
             discv5,
+            discv5_udp_port,
             discv5_updates,
             discovered_nodes: LruMap::new(DEFAULT_MAX_CAPACITY_DISCOVERED_PEERS_CACHE),
+            banned_networks: Default::default(),
             queued_events: Default::default(),
             _dns_disc_service,
             _dns_discovery,
@@ -177,6 +186,53 @@ impl Discovery {
         }
     }
 
+    /// Drops all nodes discovered in the network from now on.
+    pub(crate) fn ban_network(&mut self, network: IpNet) {
+        if !self.banned_networks.contains(&network) {
+            self.banned_networks.push(network);
+        }
+    }
+
+    /// Lifts the ban of the network.
+    pub(crate) fn unban_network(&mut self, network: &IpNet) {
+        self.banned_networks.retain(|banned| banned != network);
+    }
+
+    /// Returns a [`PortMapper`] for the `RLPx` port and the ports of the discovery services, if the
+    /// [`NatResolver`] maps ports on the gateway.
+    pub(crate) fn port_mapper(&self, nat: NatResolver) -> Option<PortMapper> {
+        let mut mapper =
+            nat.port_mapper()?.with_mapping(TransportProtocol::Tcp, self.local_enr.tcp_port);
+        let discv4_port = self.discv4.as_ref().map(|discv4| discv4.local_addr().port());
+        if let Some(port) = discv4_port {
+            mapper = mapper.with_mapping(TransportProtocol::Udp, port);
+        }
+        if let Some(port) = self.discv5_udp_port.filter(|port| Some(*port) != discv4_port) {
+            mapper = mapper.with_mapping(TransportProtocol::Udp, port);
+        }
+        Some(mapper)
+    }
+
+    /// Sets the external address of the active mappings of the [`PortMapper`] in the local records
+    /// of the discovery services.
+    pub(crate) fn set_external_addr(&self, mapper: &PortMapper) {
+        let Some(addr) = mapper.external_addr() else { return };
+        if let Some(discv4) = &self.discv4 {
+            let udp_port = mapper.external_port(TransportProtocol::Udp, discv4.local_addr().port());
+            discv4.set_external_addr(ExternalAddr { udp_port, ..addr });
+        }
+        if let Some(discv5) = &self.discv5 {
+            let udp_port = self
+                .discv5_udp_port
+                .and_then(|port| mapper.external_port(TransportProtocol::Udp, port));
+            for (port, is_tcp) in [(udp_port, false), (addr.tcp_port, true)] {
+                if let Some(port) = port {
+                    discv5.set_external_socket(SocketAddr::new(addr.ip, port), is_tcp);
+                }
+            }
+        }
+    }
+
     /// Returns a shared reference to the discv4.
     pub fn discv4(&self) -> Option<Discv4> {
         self.discv4.clone()
@@ -205,6 +261,11 @@ impl Discovery {
 
     /// Processes an incoming [`NodeRecord`] update from a discovery service
     fn on_node_record_update(&mut self, record: NodeRecord, fork_id: Option<ForkId>) {
+        if self.banned_networks.iter().any(|network| network.contains(&record.address)) {
+            trace!(target: "net::discovery", ?record, "dropping node of banned network");
+            return
+        }
+
         let peer_id = record.id;
         let tcp_addr = record.tcp_addr();
         let udp_addr = record.udp_addr();
@@ -315,7 +376,9 @@ impl Discovery {
             discv4: Default::default(),
             discv4_updates: Default::default(),
             discv5: None,
+            discv5_udp_port: None,
             discv5_updates: None,
+            banned_networks: Default::default(),
             queued_events: Default::default(),
             _discv4_service: Default::default(),
             _dns_discovery: None,
@@ -359,8 +422,32 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn drops_nodes_of_banned_networks() {
+        let mut discovery = Discovery::noop();
+        discovery.discovered_nodes = LruMap::new(10);
+        let network: IpNet = "1.2.3.0/24".parse().unwrap();
+        discovery.ban_network(network);
+
+        let record = |address: [u8; 4]| NodeRecord {
+            address: Ipv4Addr::from(address).into(),
+            tcp_port: 30303,
+            udp_port: 30303,
+            id: PeerId::random(),
+        };
+        discovery.on_node_record_update(record([1, 2, 3, 4]), None);
+        assert!(discovery.queued_events.is_empty());
+        discovery.on_node_record_update(record([1, 2, 4, 4]), None);
+        assert_eq!(discovery.queued_events.len(), 1);
+
+        discovery.unban_network(&network);
+        discovery.on_node_record_update(record([1, 2, 3, 4]), None);
+        assert_eq!(discovery.queued_events.len(), 2);
+    }
+
     use reth_discv4::Discv4ConfigBuilder;
     use reth_discv5::{enr::EnrCombinedKeyWrapper, enr_to_discv4_id};
+    use reth_net_nat::PortMappingProtocol;
     use tracing::trace;
 
     async fn start_discovery_node(udp_port_discv4: u16, udp_port_discv5: u16) -> Discovery {
@@ -389,6 +476,63 @@ mod tests {
         .expect("should build discv5 with discv4 downgrade")
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn set_external_addr_of_port_mappings() {
+        let node = start_discovery_node(40034, 40035).await;
+
+        // a NAT-PMP gateway that maps every port to the port 10000 above it on 1.2.3.4
+        let gateway = tokio::net::UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let gateway_addr = gateway.local_addr().unwrap();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 1100];
+            loop {
+                let (_, from) = gateway.recv_from(&mut buf).await.unwrap();
+                let mut response = vec![0, 128 + buf[1], 0, 0, 0, 0, 0, 1];
+                if buf[1] == 0 {
+                    response.extend_from_slice(&[1, 2, 3, 4]);
+                } else {
+                    let port = u16::from_be_bytes([buf[4], buf[5]]);
+                    response.extend_from_slice(&buf[4..6]);
+                    response.extend_from_slice(&(port + 10000).to_be_bytes());
+                    response.extend_from_slice(&7200u32.to_be_bytes());
+                }
+                gateway.send_to(&response, from).await.unwrap();
+            }
+        });
+
+        let mut mapper = PortMapper::new(PortMappingProtocol::NatPmp, gateway_addr)
+            .with_mapping(TransportProtocol::Tcp, 40034)
+            .with_mapping(TransportProtocol::Udp, 40034)
+            .with_mapping(TransportProtocol::Udp, 40035);
+        mapper.tick().await.unwrap();
+        node.set_external_addr(&mapper);
+
+        let ip = IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4));
+        let discv5_enr = node.discv5.as_ref().unwrap().with_discv5(|discv5| discv5.local_enr());
+        assert_eq!(
+            discv5_enr.udp4_socket(),
+            Some(SocketAddrV4::new(Ipv4Addr::new(1, 2, 3, 4), 50035))
+        );
+        assert_eq!(
+            discv5_enr.tcp4_socket(),
+            Some(SocketAddrV4::new(Ipv4Addr::new(1, 2, 3, 4), 50034))
+        );
+
+        // the discv4 record is updated by the service
+        let discv4 = node.discv4.as_ref().unwrap();
+        tokio::time::timeout(std::time::Duration::from_secs(5), async {
+            loop {
+                let record = discv4.node_record();
+                if (record.address, record.tcp_port, record.udp_port) == (ip, 50034, 50034) {
+                    break
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn discv5_and_discv4_same_pk() {
         reth_tracing::init_test_tracing();
@@ -10,12 +10,16 @@ use reth_network_p2p::{
     error::{PeerRequestResult, RequestError},
     headers::client::{HeadersClient, HeadersRequest},
     priority::Priority,
+    receipts::client::{ReceiptsClient, ReceiptsFut},
 };
 use reth_network_peers::PeerId;
 use reth_primitives::{Header, B256};
-use std::sync::{
-    atomic::{AtomicUsize, Ordering},
-    Arc,
+use std::{
+    ops::RangeInclusive,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
 };
 use tokio::sync::{mpsc::UnboundedSender, oneshot};
 
@@ -80,11 +84,46 @@ impl BodiesClient for FetchClient {
         &self,
         request: Vec<B256>,
         priority: Priority,
+    ) -> Self::Output {
+        self.get_block_bodies_with_range_hint(request, priority, None)
+    }
+
+    /// Sends a `GetBlockBodies` request to an available peer, preferring peers that announced
+    /// they can serve the given range.
+    fn get_block_bodies_with_range_hint(
+        &self,
+        request: Vec<B256>,
+        priority: Priority,
+        range_hint: Option<RangeInclusive<u64>>,
+    ) -> Self::Output {
+        let (response, rx) = oneshot::channel();
+        if self
+            .request_tx
+            .send(DownloadRequest::GetBlockBodies { request, response, priority, range_hint })
+            .is_ok()
+        {
+            Box::pin(FlattenedResponse::from(rx))
+        } else {
+            Box::pin(future::err(RequestError::ChannelClosed))
+        }
+    }
+}
+
+impl ReceiptsClient for FetchClient {
+    type Output = ReceiptsFut;
+
+    /// Sends a `GetReceipts` request to an available peer, preferring peers that announced they
+    /// can serve the given range.
+    fn get_receipts_with_range_hint(
+        &self,
+        request: Vec<B256>,
+        priority: Priority,
+        range_hint: Option<RangeInclusive<u64>>,
     ) -> Self::Output {
         let (response, rx) = oneshot::channel();
         if self
             .request_tx
-            .send(DownloadRequest::GetBlockBodies { request, response, priority })
+            .send(DownloadRequest::GetReceipts { request, response, priority, range_hint })
             .is_ok()
         {
             Box::pin(FlattenedResponse::from(rx))
@@ -2,7 +2,7 @@
 
 use crate::{message::BlockRequest, peers::PeersHandle};
 use futures::StreamExt;
-use reth_eth_wire::{GetBlockBodies, GetBlockHeaders};
+use reth_eth_wire::{BlockRangeUpdate, GetBlockBodies, GetBlockHeaders, GetReceipts};
 use reth_network_api::ReputationChangeKind;
 use reth_network_p2p::{
     error::{EthResponseValidator, PeerRequestResult, RequestError, RequestResult},
@@ -10,9 +10,10 @@ use reth_network_p2p::{
     priority::Priority,
 };
 use reth_network_peers::PeerId;
-use reth_primitives::{BlockBody, Header, B256};
+use reth_primitives::{BlockBody, Header, ReceiptWithBloom, B256};
 use std::{
     collections::{HashMap, VecDeque},
+    ops::RangeInclusive,
     sync::{
         atomic::{AtomicU64, AtomicUsize, Ordering},
         Arc,
@@ -25,6 +26,9 @@ use tokio_stream::wrappers::UnboundedReceiverStream;
 mod client;
 pub use client::FetchClient;
 
+/// The result of a [`GetReceipts`] request.
+type ReceiptsResult = PeerRequestResult<Vec<Vec<ReceiptWithBloom>>>;
+
 /// Manages data fetching operations.
 ///
 /// This type is hooked into the staged sync pipeline and delegates download request to available
@@ -39,6 +43,8 @@ pub struct StateFetcher {
     /// Currently active [`GetBlockBodies`] requests
     inflight_bodies_requests:
         HashMap<PeerId, Request<Vec<B256>, PeerRequestResult<Vec<BlockBody>>>>,
+    /// Currently active [`GetReceipts`] requests
+    inflight_receipts_requests: HashMap<PeerId, Request<Vec<B256>, ReceiptsResult>>,
     /// The list of _available_ peers for requests.
     peers: HashMap<PeerId, Peer>,
     /// The handle to the peers manager
@@ -61,6 +67,7 @@ impl StateFetcher {
         Self {
             inflight_headers_requests: Default::default(),
             inflight_bodies_requests: Default::default(),
+            inflight_receipts_requests: Default::default(),
             peers: Default::default(),
             peers_handle,
             num_active_peers,
@@ -71,12 +78,15 @@ impl StateFetcher {
     }
 
     /// Invoked when connected to a new peer.
+    ///
+    /// The block range is only announced by `eth/69` peers.
     pub(crate) fn new_active_peer(
         &mut self,
         peer_id: PeerId,
         best_hash: B256,
         best_number: u64,
         timeout: Arc<AtomicU64>,
+        block_range: Option<BlockRangeUpdate>,
     ) {
         self.peers.insert(
             peer_id,
@@ -86,6 +96,7 @@ impl StateFetcher {
                 best_number,
                 timeout,
                 last_response_likely_bad: false,
+                block_range,
             },
         );
     }
@@ -104,6 +115,9 @@ impl StateFetcher {
         if let Some(req) = self.inflight_bodies_requests.remove(peer) {
             let _ = req.response.send(Err(RequestError::ConnectionDropped));
         }
+        if let Some(req) = self.inflight_receipts_requests.remove(peer) {
+            let _ = req.response.send(Err(RequestError::ConnectionDropped));
+        }
     }
 
     /// Updates the block information for the peer.
@@ -120,6 +134,20 @@ impl StateFetcher {
         false
     }
 
+    /// Updates the range of blocks the peer can serve, announced by `eth/69` peers.
+    ///
+    /// This also updates the best block of the peer, see [`Self::update_peer_block`].
+    pub(crate) fn update_peer_block_range(
+        &mut self,
+        peer_id: &PeerId,
+        block_range: BlockRangeUpdate,
+    ) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.block_range = Some(block_range);
+        }
+        self.update_peer_block(peer_id, block_range.latest_hash, block_range.latest);
+    }
+
     /// Invoked when an active session is about to be disconnected.
     pub(crate) fn on_pending_disconnect(&mut self, peer_id: &PeerId) {
         if let Some(peer) = self.peers.get_mut(peer_id) {
@@ -130,12 +158,23 @@ impl StateFetcher {
     /// Returns the _next_ idle peer that's ready to accept a request,
     /// prioritizing those with the lowest timeout/latency and those that recently responded with
     /// adequate data.
-    fn next_best_peer(&self) -> Option<PeerId> {
-        let mut idle = self.peers.iter().filter(|(_, peer)| peer.state.is_idle());
+    ///
+    /// If the request targets a range of blocks, peers that announced a block range that doesn't
+    /// cover it are only selected if no other peer is idle.
+    fn next_best_peer(&self, range_hint: Option<&RangeInclusive<u64>>) -> Option<PeerId> {
+        let idle = self.peers.iter().filter(|(_, peer)| peer.state.is_idle());
+
+        Self::best_peer(
+            idle.clone().filter(|(_, peer)| range_hint.map_or(true, |range| peer.can_serve(range))),
+        )
+        .or_else(|| Self::best_peer(idle))
+    }
 
-        let mut best_peer = idle.next()?;
+    /// Returns the best of the given peers, see [`Self::next_best_peer`].
+    fn best_peer<'a>(mut peers: impl Iterator<Item = (&'a PeerId, &'a Peer)>) -> Option<PeerId> {
+        let mut best_peer = peers.next()?;
 
-        for maybe_better in idle {
+        for maybe_better in peers {
             // replace best peer if our current best peer sent us a bad response last time
             if best_peer.1.last_response_likely_bad && !maybe_better.1.last_response_likely_bad {
                 best_peer = maybe_better;
@@ -160,7 +199,10 @@ impl StateFetcher {
             return PollAction::NoRequests
         }
 
-        let Some(peer_id) = self.next_best_peer() else { return PollAction::NoPeersAvailable };
+        let range_hint = self.queued_requests.front().and_then(DownloadRequest::range_hint);
+        let Some(peer_id) = self.next_best_peer(range_hint) else {
+            return PollAction::NoPeersAvailable
+        };
 
         let request = self.queued_requests.pop_front().expect("not empty");
         let request = self.prepare_block_request(peer_id, request);
@@ -235,13 +277,27 @@ impl StateFetcher {
                 self.inflight_bodies_requests.insert(peer_id, inflight);
                 BlockRequest::GetBlockBodies(GetBlockBodies(request))
             }
+            DownloadRequest::GetReceipts { request, response, .. } => {
+                let inflight = Request { request: request.clone(), response };
+                self.inflight_receipts_requests.insert(peer_id, inflight);
+                BlockRequest::GetReceipts(GetReceipts(request))
+            }
         }
     }
 
     /// Returns a new followup request for the peer.
     ///
+    /// If the peer announced that it can't serve the next request, it's left for another peer.
+    ///
     /// Caution: this expects that the peer is _not_ closed.
     fn followup_request(&mut self, peer_id: PeerId) -> Option<BlockResponseOutcome> {
+        let can_serve = self.queued_requests.front()?.range_hint().map_or(true, |range| {
+            self.peers.get(&peer_id).is_some_and(|peer| peer.can_serve(range))
+        });
+        if !can_serve {
+            return None
+        }
+
         let req = self.queued_requests.pop_front()?;
         let req = self.prepare_block_request(peer_id, req);
         Some(BlockResponseOutcome::Request(peer_id, req))
@@ -311,6 +367,28 @@ impl StateFetcher {
         None
     }
 
+    /// Called on a `GetReceipts` response from a peer
+    pub(crate) fn on_receipts_response(
+        &mut self,
+        peer_id: PeerId,
+        res: RequestResult<Vec<Vec<ReceiptWithBloom>>>,
+    ) -> Option<BlockResponseOutcome> {
+        let is_likely_bad_response = res.as_ref().map_or(true, |receipts| receipts.is_empty());
+
+        if let Some(resp) = self.inflight_receipts_requests.remove(&peer_id) {
+            let _ = resp.response.send(res.map(|r| (peer_id, r).into()));
+        }
+        if let Some(peer) = self.peers.get_mut(&peer_id) {
+            // update the peer's response state
+            peer.last_response_likely_bad = is_likely_bad_response;
+
+            if peer.state.on_request_finished() && !is_likely_bad_response {
+                return self.followup_request(peer_id)
+            }
+        }
+        None
+    }
+
     /// Returns a new [`FetchClient`] that can send requests to this type.
     pub(crate) fn client(&self) -> FetchClient {
         FetchClient {
@@ -346,12 +424,19 @@ struct Peer {
     /// downloaded), but we still want to avoid requesting from the same peer again if it has the
     /// lowest timeout.
     last_response_likely_bad: bool,
+    /// The range of blocks the peer can serve, only announced by `eth/69` peers.
+    block_range: Option<BlockRangeUpdate>,
 }
 
 impl Peer {
     fn timeout(&self) -> u64 {
         self.timeout.load(Ordering::Relaxed)
     }
+
+    /// Returns `false` if the peer announced that it can't serve all blocks in the range.
+    fn can_serve(&self, range: &RangeInclusive<u64>) -> bool {
+        self.block_range.map_or(true, |block_range| block_range.contains(range))
+    }
 }
 
 /// Tracks the state of an individual peer
@@ -363,6 +448,8 @@ enum PeerState {
     GetBlockHeaders,
     /// Peer is handling a `GetBlockBodies` request.
     GetBlockBodies,
+    /// Peer is handling a `GetReceipts` request.
+    GetReceipts,
     /// Peer session is about to close
     Closing,
 }
@@ -414,6 +501,14 @@ pub(crate) enum DownloadRequest {
         request: Vec<B256>,
         response: oneshot::Sender<PeerRequestResult<Vec<BlockBody>>>,
         priority: Priority,
+        range_hint: Option<RangeInclusive<u64>>,
+    },
+    /// Download the requested receipts and send response through channel
+    GetReceipts {
+        request: Vec<B256>,
+        response: oneshot::Sender<PeerRequestResult<Vec<Vec<ReceiptWithBloom>>>>,
+        priority: Priority,
+        range_hint: Option<RangeInclusive<u64>>,
     },
 }
 
@@ -425,14 +520,25 @@ impl DownloadRequest {
         match self {
             Self::GetBlockHeaders { .. } => PeerState::GetBlockHeaders,
             Self::GetBlockBodies { .. } => PeerState::GetBlockBodies,
+            Self::GetReceipts { .. } => PeerState::GetReceipts,
         }
     }
 
     /// Returns the requested priority of this request
     const fn get_priority(&self) -> &Priority {
         match self {
-            Self::GetBlockHeaders { priority, .. } | Self::GetBlockBodies { priority, .. } => {
-                priority
+            Self::GetBlockHeaders { priority, .. } |
+            Self::GetBlockBodies { priority, .. } |
+            Self::GetReceipts { priority, .. } => priority,
+        }
+    }
+
+    /// Returns the numbers of the requested blocks, if known.
+    const fn range_hint(&self) -> Option<&RangeInclusive<u64>> {
+        match self {
+            Self::GetBlockHeaders { .. } => None,
+            Self::GetBlockBodies { range_hint, .. } | Self::GetReceipts { range_hint, .. } => {
+                range_hint.as_ref()
             }
         }
     }
@@ -484,6 +590,7 @@ mod tests {
                 request: vec![],
                 response: tx,
                 priority: Priority::default(),
+                range_hint: None,
             });
             assert!(fetcher.poll(cx).is_pending());
 
@@ -499,20 +606,20 @@ mod tests {
         // Add a few random peers
         let peer1 = B512::random();
         let peer2 = B512::random();
-        fetcher.new_active_peer(peer1, B256::random(), 1, Arc::new(AtomicU64::new(1)));
-        fetcher.new_active_peer(peer2, B256::random(), 2, Arc::new(AtomicU64::new(1)));
+        fetcher.new_active_peer(peer1, B256::random(), 1, Arc::new(AtomicU64::new(1)), None);
+        fetcher.new_active_peer(peer2, B256::random(), 2, Arc::new(AtomicU64::new(1)), None);
 
-        let first_peer = fetcher.next_best_peer().unwrap();
+        let first_peer = fetcher.next_best_peer(None).unwrap();
         assert!(first_peer == peer1 || first_peer == peer2);
         // Pending disconnect for first_peer
         fetcher.on_pending_disconnect(&first_peer);
         // first_peer now isn't idle, so we should get other peer
-        let second_peer = fetcher.next_best_peer().unwrap();
+        let second_peer = fetcher.next_best_peer(None).unwrap();
         assert!(first_peer == peer1 || first_peer == peer2);
         assert_ne!(first_peer, second_peer);
         // without idle peers, returns None
         fetcher.on_pending_disconnect(&second_peer);
-        assert_eq!(fetcher.next_best_peer(), None);
+        assert_eq!(fetcher.next_best_peer(None), None);
     }
 
     #[tokio::test]
@@ -526,18 +633,64 @@ mod tests {
 
         let peer2_timeout = Arc::new(AtomicU64::new(300));
 
-        fetcher.new_active_peer(peer1, B256::random(), 1, Arc::new(AtomicU64::new(30)));
-        fetcher.new_active_peer(peer2, B256::random(), 2, Arc::clone(&peer2_timeout));
-        fetcher.new_active_peer(peer3, B256::random(), 3, Arc::new(AtomicU64::new(50)));
+        fetcher.new_active_peer(peer1, B256::random(), 1, Arc::new(AtomicU64::new(30)), None);
+        fetcher.new_active_peer(peer2, B256::random(), 2, Arc::clone(&peer2_timeout), None);
+        fetcher.new_active_peer(peer3, B256::random(), 3, Arc::new(AtomicU64::new(50)), None);
 
         // Must always get peer1 (lowest timeout)
-        assert_eq!(fetcher.next_best_peer(), Some(peer1));
-        assert_eq!(fetcher.next_best_peer(), Some(peer1));
+        assert_eq!(fetcher.next_best_peer(None), Some(peer1));
+        assert_eq!(fetcher.next_best_peer(None), Some(peer1));
         // peer2's timeout changes below peer1's
         peer2_timeout.store(10, Ordering::Relaxed);
         // Then we get peer 2 always (now lowest)
-        assert_eq!(fetcher.next_best_peer(), Some(peer2));
-        assert_eq!(fetcher.next_best_peer(), Some(peer2));
+        assert_eq!(fetcher.next_best_peer(None), Some(peer2));
+        assert_eq!(fetcher.next_best_peer(None), Some(peer2));
+    }
+
+    #[tokio::test]
+    async fn test_peer_block_range() {
+        let manager = PeersManager::new(PeersConfig::default());
+        let mut fetcher = StateFetcher::new(manager.handle(), Default::default());
+        let peer1 = B512::random();
+        let peer2 = B512::random();
+        let peer3 = B512::random();
+
+        // peer1 is the fastest, but pruned
+        let pruned = BlockRangeUpdate { earliest: 100, latest: 200, ..Default::default() };
+        fetcher.new_active_peer(
+            peer1,
+            B256::random(),
+            200,
+            Arc::new(AtomicU64::new(10)),
+            Some(pruned),
+        );
+        // peer2 is a legacy peer that doesn't announce its range
+        fetcher.new_active_peer(peer2, B256::random(), 200, Arc::new(AtomicU64::new(50)), None);
+        let full = BlockRangeUpdate { earliest: 0, latest: 200, ..Default::default() };
+        fetcher.new_active_peer(
+            peer3,
+            B256::random(),
+            200,
+            Arc::new(AtomicU64::new(30)),
+            Some(full),
+        );
+
+        assert_eq!(fetcher.next_best_peer(None), Some(peer1));
+        assert_eq!(fetcher.next_best_peer(Some(&(100..=150))), Some(peer1));
+        assert_eq!(fetcher.next_best_peer(Some(&(50..=150))), Some(peer3));
+        assert_eq!(fetcher.next_best_peer(Some(&(150..=250))), Some(peer2));
+
+        // falls back to any idle peer
+        fetcher.on_pending_disconnect(&peer2);
+        assert_eq!(fetcher.next_best_peer(Some(&(150..=250))), Some(peer1));
+
+        // the range moves with the announced updates
+        let block_range =
+            BlockRangeUpdate { earliest: 150, latest: 250, latest_hash: B256::random() };
+        fetcher.update_peer_block_range(&peer3, block_range);
+        assert_eq!(fetcher.next_best_peer(Some(&(150..=250))), Some(peer3));
+        assert_eq!(fetcher.peers[&peer3].best_number, 250);
+        assert_eq!(fetcher.peers[&peer3].best_hash, block_range.latest_hash);
     }
 
     #[tokio::test]
@@ -596,6 +749,7 @@ mod tests {
             Default::default(),
             Default::default(),
             Default::default(),
+            None,
         );
 
         let (req, header) = request_pair();
@@ -43,6 +43,7 @@ use reth_eth_wire::{
 };
 use reth_fs_util::{self as fs, FsPathError};
 use reth_metrics::common::mpsc::UnboundedMeteredSender;
+use reth_net_nat::PortMapper;
 use reth_network_api::{EthProtocolInfo, NetworkStatus, PeerInfo, ReputationChangeKind};
 use reth_network_peers::{NodeRecord, PeerId};
 use reth_primitives::ForkId;
@@ -112,6 +113,11 @@ pub struct NetworkManager {
     metrics: NetworkMetrics,
     /// Disconnect metrics for the Network
     disconnect_metrics: DisconnectMetrics,
+    /// Keeps the `RLPx` and discovery ports mapped on the gateway, if the [`NatResolver`]
+    /// supports port mapping.
+    ///
+    /// [`NatResolver`]: reth_discv4::NatResolver
+    port_mapper: Option<PortMapper>,
 }
 
 // === impl NetworkManager ===
@@ -174,6 +180,7 @@ impl NetworkManager {
             discovery_v4_addr,
             mut discovery_v4_config,
             discovery_v5_config,
+            nat,
             listener_addr,
             peers_config,
             sessions_config,
@@ -184,6 +191,7 @@ impl NetworkManager {
             executor,
             hello_message,
             status,
+            block_range,
             fork_filter,
             dns_discovery_config,
             extra_protocols,
@@ -224,6 +232,8 @@ impl NetworkManager {
             dns_discovery_config,
         )
         .await?;
+        // map the listener and discovery ports on the gateway, if supported by the resolver
+        let port_mapper = nat.and_then(|nat| discovery.port_mapper(nat));
         // need to retrieve the addr here since provided port could be `0`
         let local_peer_id = discovery.local_id();
         let discv4 = discovery.discv4();
@@ -235,6 +245,7 @@ impl NetworkManager {
             sessions_config,
             executor,
             status,
+            block_range,
             hello_message,
             fork_filter,
             extra_protocols,
@@ -278,6 +289,7 @@ impl NetworkManager {
             num_active_peers,
             metrics: Default::default(),
             disconnect_metrics: Default::default(),
+            port_mapper,
         })
     }
 
@@ -362,6 +374,12 @@ impl NetworkManager {
         Ok(())
     }
 
+    /// Write the ban list of the [`NetworkManager`] to the file configured in
+    /// [`PeersConfig::ban_list_file`](crate::PeersConfig::ban_list_file), if any.
+    pub fn write_bans_to_file(&self) -> Result<(), FsPathError> {
+        self.swarm.state().peers().write_ban_list()
+    }
+
     /// Returns a new [`FetchClient`] that can be cloned and shared.
     ///
     /// The [`FetchClient`] is the entrypoint for sending requests to the network.
@@ -531,6 +549,9 @@ impl NetworkManager {
             PeerMessage::EthRequest(req) => {
                 self.on_eth_request(peer_id, req);
             }
+            PeerMessage::BlockRangeUpdate(block_range) => {
+                self.swarm.state_mut().on_block_range_update(peer_id, block_range);
+            }
             PeerMessage::ReceivedTransaction(msg) => {
                 self.notify_tx_manager(NetworkTransactionEvent::IncomingTransactions {
                     peer_id,
@@ -622,6 +643,9 @@ impl NetworkManager {
                     self.swarm.state_mut().update_fork_id(transition.current);
                 }
             }
+            NetworkHandleMessage::EarliestBlockUpdate { number } => {
+                self.swarm.sessions_mut().on_earliest_block_update(number);
+            }
             NetworkHandleMessage::GetPeerInfos(tx) => {
                 let _ = tx.send(self.get_peer_infos());
             }
@@ -963,6 +987,13 @@ impl NetworkManager {
             },
         }
 
+        // the mappings would otherwise stay on the gateway until they expire
+        if let Some(mapper) = &mut self.port_mapper {
+            if let Err(err) = mapper.remove_mappings().await {
+                debug!(target: "net", %err, "Failed to remove port mappings");
+            }
+        }
+
         let res = shutdown_hook(self);
         drop(graceful_guard);
         res
@@ -983,6 +1014,13 @@ impl Future for NetworkManager {
             this.on_block_import_result(outcome);
         }
 
+        // request or renew the port mappings, the gateway reports the external address
+        if let Some(mapper) = &mut this.port_mapper {
+            if mapper.poll_tick(cx).is_ready() {
+                this.swarm.state_mut().discovery_mut().set_external_addr(mapper);
+            }
+        }
+
         // These loops drive the entire state of network and does a lot of work. Under heavy load
         // (many messages/events), data may arrive faster than it can be processed (incoming
         // messages/requests -> events), and it is possible that more data has already arrived by
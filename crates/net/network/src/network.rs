@@ -14,12 +14,12 @@ use parking_lot::Mutex;
 use reth_discv4::Discv4;
 use reth_eth_wire::{DisconnectReason, NewBlock, NewPooledTransactionHashes, SharedTransactions};
 use reth_network_api::{
-    NetworkError, NetworkInfo, NetworkStatus, PeerInfo, PeerKind, Peers, PeersInfo, Reputation,
-    ReputationChangeKind,
+    BanEntry, BanTarget, NetworkError, NetworkInfo, NetworkStatus, PeerInfo, PeerKind, Peers,
+    PeersInfo, Reputation, ReputationChangeKind,
 };
 use reth_network_p2p::sync::{NetworkSyncUpdater, SyncState, SyncStateProvider};
 use reth_network_peers::{NodeRecord, PeerId};
-use reth_primitives::{Head, TransactionSigned, B256};
+use reth_primitives::{BlockNumber, Head, TransactionSigned, B256};
 use reth_tokio_util::{EventSender, EventStream};
 use secp256k1::SecretKey;
 use std::{
@@ -28,6 +28,7 @@ use std::{
         atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering},
         Arc,
     },
+    time::Duration,
 };
 use tokio::sync::{
     mpsc::{self, UnboundedSender},
@@ -120,6 +121,13 @@ impl NetworkHandle {
         self.send_message(NetworkHandleMessage::StatusUpdate { head });
     }
 
+    /// Updates the earliest block the node can serve, e.g. after older blocks were pruned.
+    ///
+    /// This is announced to `eth/69` peers as part of the block range.
+    pub fn update_earliest_block(&self, number: BlockNumber) {
+        self.send_message(NetworkHandleMessage::EarliestBlockUpdate { number });
+    }
+
     /// Announce a block over devp2p
     ///
     /// Caution: in `PoS` this is a noop because new blocks are no longer announced over devp2p.
@@ -326,6 +334,19 @@ impl Peers for NetworkHandle {
         let _ = self.manager().send(NetworkHandleMessage::GetReputationById(peer_id, tx));
         Ok(rx.await?)
     }
+
+    /// Bans the target via the [`PeersHandle`].
+    fn ban(&self, target: BanTarget, duration: Option<Duration>) {
+        self.peers_handle().ban(target, duration)
+    }
+
+    async fn unban(&self, target: BanTarget) -> Result<bool, NetworkError> {
+        Ok(self.peers_handle().unban(target).await)
+    }
+
+    async fn bans(&self) -> Result<Vec<BanEntry>, NetworkError> {
+        Ok(self.peers_handle().bans().await)
+    }
 }
 
 impl NetworkInfo for NetworkHandle {
@@ -470,6 +491,11 @@ pub(crate) enum NetworkHandleMessage {
         /// The head status to apply.
         head: Head,
     },
+    /// Updates the earliest block the node can serve.
+    EarliestBlockUpdate {
+        /// The number of the earliest block.
+        number: BlockNumber,
+    },
     /// Retrieves the current status via a oneshot sender.
     GetStatus(oneshot::Sender<NetworkStatus>),
     /// Gets `PeerInfo` for the specified peer IDs.
@@ -7,7 +7,8 @@ use crate::{
 };
 use futures::StreamExt;
 use reth_eth_wire::{errors::EthStreamError, DisconnectReason};
-use reth_net_banlist::BanList;
+use reth_fs_util::{self as fs, FsPathError};
+use reth_net_banlist::{is_host, BanEntry, BanList, BanTarget, IpNet};
 use reth_network_api::{PeerKind, ReputationChangeKind};
 use reth_network_peers::{NodeRecord, PeerId};
 use reth_network_types::{
@@ -25,6 +26,7 @@ use std::{
     fmt::Display,
     io::{self},
     net::{IpAddr, SocketAddr},
+    path::PathBuf,
     task::{Context, Poll},
     time::Duration,
 };
@@ -34,7 +36,7 @@ use tokio::{
     time::{Instant, Interval},
 };
 use tokio_stream::wrappers::UnboundedReceiverStream;
-use tracing::trace;
+use tracing::{trace, warn};
 
 /// A communication channel to the [`PeersManager`] to apply manual changes to the peer set.
 #[derive(Clone, Debug)]
@@ -80,6 +82,31 @@ impl PeersHandle {
 
         rx.await.unwrap_or_default()
     }
+
+    /// Bans a peer or a range of IP addresses, indefinitely or for the given duration.
+    ///
+    /// Connected peers that match the target are disconnected.
+    pub fn ban(&self, target: BanTarget, duration: Option<Duration>) {
+        self.send(PeerCommand::Ban(target, duration));
+    }
+
+    /// Lifts the ban of a peer or a range of IP addresses.
+    ///
+    /// Returns `true` if the target was banned.
+    pub async fn unban(&self, target: BanTarget) -> bool {
+        let (tx, rx) = oneshot::channel();
+        self.send(PeerCommand::Unban(target, tx));
+
+        rx.await.unwrap_or_default()
+    }
+
+    /// Returns all active bans.
+    pub async fn bans(&self) -> Vec<BanEntry> {
+        let (tx, rx) = oneshot::channel();
+        self.send(PeerCommand::GetBans(tx));
+
+        rx.await.unwrap_or_default()
+    }
 }
 
 /// Maintains the state of _all_ the peers known to the network.
@@ -111,6 +138,8 @@ pub struct PeersManager {
     connection_info: ConnectionInfo,
     /// Tracks unwanted ips/peer ids.
     ban_list: BanList,
+    /// The file the ban list is persisted to.
+    ban_list_file: Option<PathBuf>,
     /// Tracks currently backed off peers.
     backed_off_peers: HashMap<PeerId, std::time::Instant>,
     /// Interval at which to check for peers to unban and release from the backoff map.
@@ -139,6 +168,7 @@ impl PeersManager {
             connection_info,
             reputation_weights,
             ban_list,
+            ban_list_file,
             ban_duration,
             backoff_durations,
             trusted_nodes,
@@ -168,17 +198,28 @@ impl PeersManager {
             });
         }
 
+        // bans loaded from the ban list file also apply to discovery
+        let queued_actions = ban_list
+            .entries()
+            .into_iter()
+            .filter_map(|entry| match entry.target {
+                BanTarget::Ip(network) => Some(discovery_ban(network)),
+                BanTarget::Peer(_) => None,
+            })
+            .collect();
+
         Self {
             peers,
             trusted_peer_ids,
             manager_tx,
             handle_rx: UnboundedReceiverStream::new(handle_rx),
-            queued_actions: Default::default(),
+            queued_actions,
             reputation_weights,
             refill_slots_interval: tokio::time::interval(refill_slots_interval),
             release_interval: tokio::time::interval_at(now + unban_interval, unban_interval),
             connection_info: ConnectionInfo::new(connection_info),
             ban_list,
+            ban_list_file,
             backed_off_peers: Default::default(),
             ban_duration,
             backoff_durations,
@@ -190,7 +231,7 @@ impl PeersManager {
     }
 
     /// Returns a new [`PeersHandle`] that can send commands to this type.
-    pub(crate) fn handle(&self) -> PeersHandle {
+    pub fn handle(&self) -> PeersHandle {
         PeersHandle { manager_tx: self.manager_tx.clone() }
     }
 
@@ -407,6 +448,85 @@ impl PeersManager {
         self.queued_actions.push_back(PeerAction::UnBanPeer { peer_id });
     }
 
+    /// Bans the target on request of the operator, indefinitely or for the given duration.
+    ///
+    /// Unlike bans for bad behaviour, this also applies to trusted peers and non-global IPs.
+    /// Matching peers are removed from the set and disconnected.
+    pub(crate) fn ban_target(&mut self, target: BanTarget, duration: Option<Duration>) {
+        trace!(target: "net::peers", %target, ?duration, "banning");
+        let until = duration.map(|duration| std::time::Instant::now() + duration);
+        self.ban_list.ban_with(target, until);
+        if let BanTarget::Ip(network) = target {
+            self.queued_actions.push_back(discovery_ban(network));
+        }
+
+        let banned_peers = self
+            .peers
+            .iter()
+            .filter(|(peer_id, peer)| match target {
+                BanTarget::Peer(banned) => **peer_id == banned,
+                BanTarget::Ip(network) => network.contains(&peer.addr.tcp.ip()),
+            })
+            .map(|(peer_id, _)| *peer_id)
+            .collect::<Vec<_>>();
+        for peer_id in banned_peers {
+            if let Some(peer) = self.peers.remove(&peer_id) {
+                self.queued_actions.push_back(PeerAction::BanPeer { peer_id });
+                self.on_peer_removed(peer_id, peer);
+            }
+        }
+
+        self.persist_ban_list();
+    }
+
+    /// Lifts the ban of the target, whether it was banned by the operator or for bad behaviour.
+    ///
+    /// Returns `true` if the target was banned.
+    pub(crate) fn unban_target(&mut self, target: BanTarget) -> bool {
+        let mut unbanned = self.ban_list.unban(&target);
+        match target {
+            BanTarget::Peer(peer_id) => {
+                if let Some(peer) = self.peers.get_mut(&peer_id).filter(|peer| peer.is_banned()) {
+                    peer.unban();
+                    unbanned = true;
+                }
+                if unbanned {
+                    self.queued_actions.push_back(PeerAction::UnBanPeer { peer_id });
+                }
+            }
+            BanTarget::Ip(network) if unbanned && !is_host(&network) => {
+                self.queued_actions
+                    .push_back(PeerAction::DiscoveryUnbanNetwork { network: network.trunc() });
+            }
+            BanTarget::Ip(_) => {}
+        }
+
+        if unbanned {
+            trace!(target: "net::peers", %target, "unbanned");
+            self.persist_ban_list();
+        }
+        unbanned
+    }
+
+    /// Returns all active bans.
+    pub(crate) fn bans(&self) -> Vec<BanEntry> {
+        self.ban_list.entries()
+    }
+
+    /// Writes the ban list to the file configured in [`PeersConfig::ban_list_file`], if any.
+    pub(crate) fn write_ban_list(&self) -> Result<(), FsPathError> {
+        let Some(file) = &self.ban_list_file else { return Ok(()) };
+        file.parent().map(fs::create_dir_all).transpose()?;
+        fs::write_json_file(file, &self.ban_list.entries())
+    }
+
+    /// Persists changes of the operator to the ban list right away, so they survive a crash.
+    fn persist_ban_list(&self) {
+        if let Err(err) = self.write_ban_list() {
+            warn!(target: "net::peers", %err, "Failed to write ban list to file");
+        }
+    }
+
     /// Tick function to update reputation of all connected peers.
     /// Peers are rewarded with reputation increases for the time they are connected since the last
     /// tick. This is to prevent peers from being disconnected eventually due to slashed
@@ -746,13 +866,19 @@ impl PeersManager {
         if entry.get().is_trusted() {
             return
         }
-        let mut peer = entry.remove();
+        let peer = entry.remove();
 
         trace!(target: "net::peers", ?peer_id, "remove discovered node");
+        self.on_peer_removed(peer_id, peer);
+    }
+
+    /// Emits the removal of the peer, which was taken out of the set, and disconnects it if it is
+    /// connected.
+    fn on_peer_removed(&mut self, peer_id: PeerId, mut peer: Peer) {
         self.queued_actions.push_back(PeerAction::PeerRemoved(peer_id));
 
         if peer.state.is_connected() {
-            trace!(target: "net::peers", ?peer_id, "disconnecting on remove from discovery");
+            trace!(target: "net::peers", ?peer_id, "disconnecting on remove");
             // we terminate the active session here, but only remove the peer after the session
             // was disconnected, this prevents the case where the session is scheduled for
             // disconnect but the node is immediately rediscovered, See also
@@ -892,11 +1018,21 @@ impl PeersManager {
                     PeerCommand::GetPeers(tx) => {
                         let _ = tx.send(self.iter_peers().collect());
                     }
+                    PeerCommand::Ban(target, duration) => self.ban_target(target, duration),
+                    PeerCommand::Unban(target, tx) => {
+                        let _ = tx.send(self.unban_target(target));
+                    }
+                    PeerCommand::GetBans(tx) => {
+                        let _ = tx.send(self.bans());
+                    }
                 }
             }
 
             if self.release_interval.poll_tick(cx).is_ready() {
                 let now = std::time::Instant::now();
+                for network in self.ban_list.evict_networks(now) {
+                    self.queued_actions.push_back(PeerAction::DiscoveryUnbanNetwork { network });
+                }
                 let (_, unbanned_peers) = self.ban_list.evict(now);
 
                 for peer_id in unbanned_peers {
@@ -1244,6 +1380,12 @@ pub(crate) enum PeerCommand {
     GetPeer(PeerId, oneshot::Sender<Option<Peer>>),
     /// Get node information on all peers
     GetPeers(oneshot::Sender<Vec<NodeRecord>>),
+    /// Ban a peer or a range of IP addresses, indefinitely or for the given duration.
+    Ban(BanTarget, Option<Duration>),
+    /// Lift the ban of a peer or a range of IP addresses.
+    Unban(BanTarget, oneshot::Sender<bool>),
+    /// Get all active bans
+    GetBans(oneshot::Sender<Vec<BanEntry>>),
 }
 
 /// Actions the peer manager can trigger.
@@ -1286,6 +1428,16 @@ pub enum PeerAction {
         /// The IP address.
         ip_addr: IpAddr,
     },
+    /// Ban all IPs of the network in discovery.
+    DiscoveryBanNetwork {
+        /// The IP network.
+        network: IpNet,
+    },
+    /// Lift the ban of the network in discovery.
+    DiscoveryUnbanNetwork {
+        /// The IP network.
+        network: IpNet,
+    },
     /// Ban the peer temporarily
     BanPeer {
         /// The peer ID.
@@ -1302,6 +1454,17 @@ pub enum PeerAction {
     PeerRemoved(PeerId),
 }
 
+/// Returns the action that bans the network in discovery.
+///
+/// Single addresses are banned as IPs, wider networks are filtered by the discovery manager.
+fn discovery_ban(network: IpNet) -> PeerAction {
+    if is_host(&network) {
+        PeerAction::DiscoveryBanIp { ip_addr: network.addr() }
+    } else {
+        PeerAction::DiscoveryBanNetwork { network: network.trunc() }
+    }
+}
+
 /// Error thrown when a incoming connection is rejected right away
 #[derive(Debug, Error, PartialEq, Eq)]
 pub enum InboundConnectionError {
@@ -1333,7 +1496,7 @@ mod tests {
         errors::{EthHandshakeError, EthStreamError, P2PHandshakeError, P2PStreamError},
         DisconnectReason,
     };
-    use reth_net_banlist::BanList;
+    use reth_net_banlist::{BanList, IpNet};
     use reth_network_api::{Direction, ReputationChangeKind};
     use reth_network_peers::PeerId;
     use reth_network_types::{peers::reputation::DEFAULT_REPUTATION, BackoffKind};
@@ -1478,6 +1641,126 @@ mod tests {
         .await;
     }
 
+    #[tokio::test]
+    async fn test_ban_target_disconnects() {
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let mut peers = PeersManager::new(PeersConfig::test());
+        peers.add_peer(peer, PeerAddr::tcp(socket_addr), None);
+
+        match event!(peers) {
+            PeerAction::PeerAdded(peer_id) => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+        match event!(peers) {
+            PeerAction::Connect { peer_id, .. } => {
+                assert_eq!(peer_id, peer);
+                peers.on_active_outgoing_established(peer_id);
+            }
+            _ => unreachable!(),
+        }
+
+        // operator bans also apply to non-global IPs
+        peers.ban_target("127.0.0.0/8".parse().unwrap(), None);
+
+        match event!(peers) {
+            PeerAction::DiscoveryBanNetwork { network } => {
+                assert_eq!(network, "127.0.0.0/8".parse().unwrap());
+            }
+            _ => unreachable!(),
+        }
+        match event!(peers) {
+            PeerAction::BanPeer { peer_id } => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+        match event!(peers) {
+            PeerAction::PeerRemoved(peer_id) => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+        match event!(peers) {
+            PeerAction::Disconnect { peer_id, .. } => {
+                assert_eq!(peer_id, peer);
+            }
+            _ => unreachable!(),
+        }
+
+        // the peer is removed once disconnected and not rediscovered
+        peers.on_active_session_gracefully_closed(peer);
+        assert!(peers.peers.is_empty());
+        peers.add_peer(peer, PeerAddr::tcp(socket_addr), None);
+        assert!(peers.peers.is_empty());
+        assert_eq!(
+            peers.on_incoming_pending_session(socket_addr.ip()),
+            Err(InboundConnectionError::IpBanned)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unban_target() {
+        let peer = PeerId::random();
+        let socket_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 1, 2)), 8008);
+        let mut peers = PeersManager::default();
+        peers.add_peer(peer, PeerAddr::tcp(socket_addr), None);
+        assert!(matches!(event!(peers), PeerAction::PeerAdded(_)));
+
+        // a peer banned for its reputation can be unbanned too
+        peers.apply_reputation_change(&peer, ReputationChangeKind::BadProtocol);
+        assert!(peers.peers.get(&peer).unwrap().is_banned());
+        assert!(peers.ban_list.is_banned_peer(&peer));
+        assert!(peers.unban_target(peer.into()));
+        assert!(!peers.peers.get(&peer).unwrap().is_banned());
+        assert!(!peers.ban_list.is_banned_peer(&peer));
+        assert!(!peers.unban_target(peer.into()));
+        peers.queued_actions.clear();
+
+        peers.ban_target(socket_addr.ip().into(), Some(Duration::from_secs(60)));
+        assert!(matches!(
+            event!(peers),
+            PeerAction::DiscoveryBanIp { ip_addr } if ip_addr == socket_addr.ip()
+        ));
+        assert_eq!(peers.bans().len(), 1);
+        assert!(peers.unban_target(socket_addr.ip().into()));
+        assert!(peers.bans().is_empty());
+        peers.queued_actions.clear();
+
+        let network: IpNet = "10.0.0.0/8".parse().unwrap();
+        peers.ban_target(network.into(), None);
+        assert!(matches!(event!(peers), PeerAction::DiscoveryBanNetwork { .. }));
+        assert!(peers.unban_target(network.into()));
+        assert!(matches!(
+            event!(peers),
+            PeerAction::DiscoveryUnbanNetwork { network: unbanned } if unbanned == network
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_ban_list_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("banned-peers.json");
+        let peer = PeerId::random();
+
+        let config = PeersConfig::test().with_ban_list_from_file(Some(&file)).unwrap();
+        let mut peers = PeersManager::new(config);
+        peers.ban_target(peer.into(), None);
+        peers.ban_target("1.2.3.0/24".parse().unwrap(), Some(Duration::from_secs(60)));
+
+        let config = PeersConfig::test().with_ban_list_from_file(Some(&file)).unwrap();
+        assert!(config.ban_list.is_banned_peer(&peer));
+        assert!(config.ban_list.is_banned_ip(&IpAddr::V4(Ipv4Addr::new(1, 2, 3, 4))));
+
+        let mut peers = PeersManager::new(config);
+        assert!(peers.unban_target(peer.into()));
+        let config = PeersConfig::test().with_ban_list_from_file(Some(&file)).unwrap();
+        assert!(!config.ban_list.is_banned_peer(&peer));
+        assert_eq!(config.ban_list.entries().len(), 1);
+    }
+
     #[tokio::test]
     async fn test_backoff_on_busy() {
         let peer = PeerId::random();
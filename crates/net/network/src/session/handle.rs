@@ -9,7 +9,7 @@ use reth_ecies::ECIESError;
 use reth_eth_wire::{
     capability::{Capabilities, CapabilityMessage},
     errors::EthStreamError,
-    DisconnectReason, EthVersion, Status,
+    BlockRangeUpdate, DisconnectReason, EthVersion, Status,
 };
 use reth_network_api::{PeerInfo, PeerKind};
 use reth_network_peers::{NodeRecord, PeerId};
@@ -174,7 +174,11 @@ pub enum PendingSessionEvent {
         /// All capabilities the peer announced
         capabilities: Arc<Capabilities>,
         /// The Status message the peer sent for the `eth` handshake
+        ///
+        /// The total difficulty is zero for `eth/69` peers.
         status: Arc<Status>,
+        /// The range of blocks the peer can serve, announced by `eth/69` peers in the handshake
+        block_range: Option<BlockRangeUpdate>,
         /// The actual connection stream which can be used to send and receive `eth` protocol
         /// messages
         conn: EthRlpxConnection,
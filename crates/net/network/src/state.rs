@@ -15,7 +15,8 @@ use crate::{
 use rand::seq::SliceRandom;
 
 use reth_eth_wire::{
-    capability::Capabilities, BlockHashNumber, DisconnectReason, NewBlockHashes, Status,
+    capability::Capabilities, BlockHashNumber, BlockRangeUpdate, DisconnectReason, NewBlockHashes,
+    Status,
 };
 use reth_network_api::PeerKind;
 use reth_network_peers::PeerId;
@@ -147,15 +148,23 @@ impl NetworkState {
         peer: PeerId,
         capabilities: Arc<Capabilities>,
         status: Arc<Status>,
+        block_range: Option<BlockRangeUpdate>,
         request_tx: PeerRequestSender,
         timeout: Arc<AtomicU64>,
     ) {
         debug_assert!(!self.active_peers.contains_key(&peer), "Already connected; not possible");
 
-        // find the corresponding block number
-        let block_number =
-            self.client.block_number(status.blockhash).ok().flatten().unwrap_or_default();
-        self.state_fetcher.new_active_peer(peer, status.blockhash, block_number, timeout);
+        // find the corresponding block number, which is announced by eth/69 peers
+        let block_number = block_range.map(|range| range.latest).unwrap_or_else(|| {
+            self.client.block_number(status.blockhash).ok().flatten().unwrap_or_default()
+        });
+        self.state_fetcher.new_active_peer(
+            peer,
+            status.blockhash,
+            block_number,
+            timeout,
+            block_range,
+        );
 
         self.active_peers.insert(
             peer,
@@ -278,6 +287,14 @@ impl NetworkState {
         }
     }
 
+    /// Invoked for a `BlockRangeUpdate` message of an `eth/69` peer.
+    pub(crate) fn on_block_range_update(&mut self, peer_id: PeerId, block_range: BlockRangeUpdate) {
+        if let Some(peer) = self.active_peers.get_mut(&peer_id) {
+            peer.best_hash = block_range.latest_hash;
+        }
+        self.state_fetcher.update_peer_block_range(&peer_id, block_range);
+    }
+
     /// Bans the [`IpAddr`] in the discovery service.
     pub(crate) fn ban_ip_discovery(&self, ip: IpAddr) {
         trace!(target: "net", ?ip, "Banning discovery");
@@ -344,6 +361,14 @@ impl NetworkState {
                 self.ban_discovery(peer_id, ip_addr)
             }
             PeerAction::DiscoveryBanIp { ip_addr } => self.ban_ip_discovery(ip_addr),
+            PeerAction::DiscoveryBanNetwork { network } => {
+                trace!(target: "net", %network, "Banning network in discovery");
+                self.discovery.ban_network(network)
+            }
+            PeerAction::DiscoveryUnbanNetwork { network } => {
+                trace!(target: "net", %network, "Unbanning network in discovery");
+                self.discovery.unban_network(&network)
+            }
             PeerAction::PeerAdded(peer_id) => {
                 self.queued_messages.push_back(StateAction::PeerAdded(peer_id))
             }
@@ -373,6 +398,12 @@ impl NetworkState {
                     let response = PeerResponse::BlockBodies { response: rx };
                     (request, response)
                 }
+                BlockRequest::GetReceipts(request) => {
+                    let (response, rx) = oneshot::channel();
+                    let request = PeerRequest::GetReceipts { request, response };
+                    let response = PeerResponse::Receipts { response: rx };
+                    (request, response)
+                }
             };
             let _ = peer.request_tx.to_session_tx.try_send(request);
             peer.pending_response = Some(response);
@@ -407,6 +438,10 @@ impl NetworkState {
                 let outcome = self.state_fetcher.on_block_bodies_response(peer, res)?;
                 self.on_block_response_outcome(outcome)
             }
+            PeerResponseResult::Receipts(res) => {
+                let outcome = self.state_fetcher.on_receipts_response(peer, res)?;
+                self.on_block_response_outcome(outcome)
+            }
             _ => None,
         }
     }
@@ -600,6 +635,7 @@ mod tests {
             peer_id,
             capabilities(),
             Arc::default(),
+            None,
             peer_tx,
             Arc::new(AtomicU64::new(1)),
         );
@@ -120,6 +120,7 @@ impl Swarm {
                 capabilities,
                 version,
                 status,
+                block_range,
                 messages,
                 direction,
                 timeout,
@@ -128,6 +129,7 @@ impl Swarm {
                     peer_id,
                     capabilities.clone(),
                     status.clone(),
+                    block_range,
                     messages.clone(),
                     timeout,
                 );
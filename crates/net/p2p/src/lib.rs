@@ -38,6 +38,12 @@ pub mod error;
 /// Priority enum for `BlockHeader` and `BlockBody` requests
 pub mod priority;
 
+/// Traits for implementing P2P receipt clients.
+pub mod receipts;
+
+/// Traits for implementing `snap` protocol clients.
+pub mod snap;
+
 /// Syncing related traits.
 pub mod sync;
 
@@ -0,0 +1,34 @@
+use crate::{download::DownloadClient, error::PeerRequestResult, priority::Priority};
+use futures::Future;
+use reth_primitives::{ReceiptWithBloom, B256};
+use std::{ops::RangeInclusive, pin::Pin};
+
+/// The receipts future type
+pub type ReceiptsFut =
+    Pin<Box<dyn Future<Output = PeerRequestResult<Vec<Vec<ReceiptWithBloom>>>> + Send + Sync>>;
+
+/// A client capable of downloading the receipts of blocks.
+#[auto_impl::auto_impl(&, Arc, Box)]
+pub trait ReceiptsClient: DownloadClient {
+    /// The output of the request future for querying receipts.
+    type Output: Future<Output = PeerRequestResult<Vec<Vec<ReceiptWithBloom>>>>
+        + Sync
+        + Send
+        + Unpin;
+
+    /// Fetches the receipts of the requested blocks.
+    fn get_receipts(&self, hashes: Vec<B256>) -> Self::Output {
+        self.get_receipts_with_range_hint(hashes, Priority::Normal, None)
+    }
+
+    /// Fetches the receipts of the requested blocks with priority.
+    ///
+    /// The range hint contains the numbers of the requested blocks, which is used to select a
+    /// peer that can serve them.
+    fn get_receipts_with_range_hint(
+        &self,
+        hashes: Vec<B256>,
+        priority: Priority,
+        range_hint: Option<RangeInclusive<u64>>,
+    ) -> Self::Output;
+}
@@ -0,0 +1,2 @@
+/// Traits and types for receipt clients.
+pub mod client;
@@ -0,0 +1,29 @@
+use crate::{download::DownloadClient, error::PeerRequestResult};
+use futures::Future;
+use std::pin::Pin;
+
+pub use reth_eth_wire_types::{
+    AccountData, AccountRange, ByteCodes, GetAccountRange, GetByteCodes, GetStorageRanges,
+    GetTrieNodes, SnapAccount, StorageData, StorageRanges, TrieNodes,
+};
+
+/// The future type of the `snap` requests.
+pub type SnapFut<T> = Pin<Box<dyn Future<Output = PeerRequestResult<T>> + Send + Sync>>;
+
+/// A client capable of downloading the state of recent blocks over the `snap` protocol.
+///
+/// The request ids of the requests are assigned by the client.
+#[auto_impl::auto_impl(&, Arc, Box)]
+pub trait SnapClient: DownloadClient {
+    /// Fetches a range of accounts of the account trie.
+    fn get_account_range(&self, request: GetAccountRange) -> SnapFut<AccountRange>;
+
+    /// Fetches the storage slots of a list of accounts.
+    fn get_storage_ranges(&self, request: GetStorageRanges) -> SnapFut<StorageRanges>;
+
+    /// Fetches bytecodes by their hashes.
+    fn get_byte_codes(&self, request: GetByteCodes) -> SnapFut<ByteCodes>;
+
+    /// Fetches nodes of the account and storage tries by their paths.
+    fn get_trie_nodes(&self, request: GetTrieNodes) -> SnapFut<TrieNodes>;
+}
@@ -0,0 +1,215 @@
+//! A [`SnapClient`] sending requests over the `snap/1` connections of the network.
+
+use futures::Future;
+use parking_lot::Mutex;
+use reth_eth_wire::{
+    AccountRange, ByteCodes, GetAccountRange, GetByteCodes, GetStorageRanges, GetTrieNodes,
+    SnapMessage, StorageRanges, TrieNodes,
+};
+use reth_network::peers::PeersHandle;
+use reth_network_api::ReputationChangeKind;
+use reth_network_p2p::{
+    download::DownloadClient,
+    error::{PeerRequestResult, RequestError},
+    snap::client::{SnapClient, SnapFut},
+};
+use reth_network_peers::{PeerId, WithPeerId};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::sync::{mpsc, oneshot};
+
+/// Default timeout of a `snap` request.
+const SNAP_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long to wait before checking again for a peer to send a request to, if there are no peers
+/// with a `snap/1` connection.
+const NO_PEERS_BACKOFF: Duration = Duration::from_secs(1);
+
+/// A request to send over a `snap/1` connection.
+#[derive(Debug)]
+pub struct OutgoingSnapRequest {
+    /// The request. The request id is assigned by the connection.
+    pub request: SnapMessage,
+    /// The sender for the response of the peer.
+    pub response: oneshot::Sender<SnapMessage>,
+}
+
+/// The peers with an active `snap/1` connection.
+///
+/// Shared by the [`SnapProtocolHandler`](crate::SnapProtocolHandler), which registers the
+/// connections, and the [`SnapFetchClient`], which sends requests over them.
+#[derive(Debug, Clone, Default)]
+pub struct SnapPeers {
+    inner: Arc<Mutex<HashMap<PeerId, SnapPeer>>>,
+    next_connection_id: Arc<AtomicU64>,
+}
+
+/// An active `snap/1` connection.
+#[derive(Debug)]
+struct SnapPeer {
+    /// Identifies the connection, in case the peer reconnects before the old connection is
+    /// dropped.
+    connection_id: u64,
+    /// Sender half of the request channel of the connection.
+    to_connection: mpsc::UnboundedSender<OutgoingSnapRequest>,
+    /// Number of requests sent to the peer that are awaiting a response.
+    inflight_requests: usize,
+}
+
+impl SnapPeers {
+    /// Returns the number of peers with an active `snap/1` connection.
+    pub fn len(&self) -> usize {
+        self.inner.lock().len()
+    }
+
+    /// Returns `true` if there are no peers with an active `snap/1` connection.
+    pub fn is_empty(&self) -> bool {
+        self.inner.lock().is_empty()
+    }
+
+    /// Registers a new connection to the peer, returning the connection id and the receiver half
+    /// of its request channel.
+    pub(crate) fn register(
+        &self,
+        peer_id: PeerId,
+    ) -> (u64, mpsc::UnboundedReceiver<OutgoingSnapRequest>) {
+        let connection_id = self.next_connection_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = mpsc::unbounded_channel();
+        self.inner
+            .lock()
+            .insert(peer_id, SnapPeer { connection_id, to_connection: tx, inflight_requests: 0 });
+        (connection_id, rx)
+    }
+
+    /// Removes the connection to the peer, unless the peer has reconnected since.
+    pub(crate) fn unregister(&self, peer_id: &PeerId, connection_id: u64) {
+        let mut peers = self.inner.lock();
+        if peers.get(peer_id).is_some_and(|peer| peer.connection_id == connection_id) {
+            peers.remove(peer_id);
+        }
+    }
+
+    /// Sends the request to the peer with the fewest requests in flight.
+    ///
+    /// Returns `None` if there are no peers.
+    fn send(&self, request: SnapMessage) -> Option<(PeerId, oneshot::Receiver<SnapMessage>)> {
+        let mut peers = self.inner.lock();
+        let (peer_id, peer) = peers
+            .iter_mut()
+            .filter(|(_, peer)| !peer.to_connection.is_closed())
+            .min_by_key(|(_, peer)| peer.inflight_requests)?;
+
+        let (tx, rx) = oneshot::channel();
+        peer.to_connection.send(OutgoingSnapRequest { request, response: tx }).ok()?;
+        peer.inflight_requests += 1;
+        Some((*peer_id, rx))
+    }
+
+    /// Marks a request sent to the peer as finished.
+    fn on_response(&self, peer_id: &PeerId) {
+        if let Some(peer) = self.inner.lock().get_mut(peer_id) {
+            peer.inflight_requests = peer.inflight_requests.saturating_sub(1);
+        }
+    }
+}
+
+/// Sends `snap` requests to the peers with an active `snap/1` connection.
+///
+/// If there are no such peers, the requests wait until a peer connects.
+#[derive(Debug, Clone)]
+pub struct SnapFetchClient {
+    /// The peers to send requests to.
+    snap_peers: SnapPeers,
+    /// Used for reporting peers.
+    peers: PeersHandle,
+    /// The timeout of a request.
+    timeout: Duration,
+}
+
+impl SnapFetchClient {
+    /// Create a new instance
+    pub const fn new(snap_peers: SnapPeers, peers: PeersHandle) -> Self {
+        Self { snap_peers, peers, timeout: SNAP_REQUEST_TIMEOUT }
+    }
+
+    /// Sets the timeout of the requests.
+    pub const fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sends the request, and maps the response of the peer with the given function.
+    fn request<T: Send + Sync + 'static>(
+        &self,
+        request: SnapMessage,
+        map_response: fn(SnapMessage) -> Option<T>,
+    ) -> impl Future<Output = PeerRequestResult<T>> + Send + Sync + 'static {
+        let snap_peers = self.snap_peers.clone();
+        let timeout = self.timeout;
+        async move {
+            let (peer_id, rx) = loop {
+                if let Some(sent) = snap_peers.send(request.clone()) {
+                    break sent
+                }
+                tokio::time::sleep(NO_PEERS_BACKOFF).await;
+            };
+
+            let response = tokio::time::timeout(timeout, rx).await;
+            snap_peers.on_response(&peer_id);
+
+            match response {
+                Ok(Ok(response)) => map_response(response)
+                    .map(|response| WithPeerId::new(peer_id, response))
+                    .ok_or(RequestError::BadResponse),
+                Ok(Err(_)) => Err(RequestError::ConnectionDropped),
+                Err(_) => Err(RequestError::Timeout),
+            }
+        }
+    }
+}
+
+impl DownloadClient for SnapFetchClient {
+    fn report_bad_message(&self, peer_id: PeerId) {
+        self.peers.reputation_change(peer_id, ReputationChangeKind::BadMessage);
+    }
+
+    fn num_connected_peers(&self) -> usize {
+        self.snap_peers.len()
+    }
+}
+
+impl SnapClient for SnapFetchClient {
+    fn get_account_range(&self, request: GetAccountRange) -> SnapFut<AccountRange> {
+        Box::pin(self.request(SnapMessage::GetAccountRange(request), |response| match response {
+            SnapMessage::AccountRange(response) => Some(response),
+            _ => None,
+        }))
+    }
+
+    fn get_storage_ranges(&self, request: GetStorageRanges) -> SnapFut<StorageRanges> {
+        Box::pin(self.request(SnapMessage::GetStorageRanges(request), |response| match response {
+            SnapMessage::StorageRanges(response) => Some(response),
+            _ => None,
+        }))
+    }
+
+    fn get_byte_codes(&self, request: GetByteCodes) -> SnapFut<ByteCodes> {
+        Box::pin(self.request(SnapMessage::GetByteCodes(request), |response| match response {
+            SnapMessage::ByteCodes(response) => Some(response),
+            _ => None,
+        }))
+    }
+
+    fn get_trie_nodes(&self, request: GetTrieNodes) -> SnapFut<TrieNodes> {
+        Box::pin(self.request(SnapMessage::GetTrieNodes(request), |response| match response {
+            SnapMessage::TrieNodes(response) => Some(response),
+            _ => None,
+        }))
+    }
+}
@@ -0,0 +1,58 @@
+//! Implementation of the [`snap/1`](https://github.com/ethereum/devp2p/blob/master/caps/snap.md)
+//! protocol as `RLPx` subprotocol.
+//!
+//! The protocol is served from the hashed state and trie tables, which hold the latest state. The
+//! state of recent blocks is served with the reverts of the blocks after them on top. Requests for
+//! other state roots are answered with empty responses, as allowed by the protocol.
+//!
+//! The [`SnapFetchClient`] sends requests to the peers, to download the state of a recent block.
+
+#![doc(
+    html_logo_url = "https://raw.githubusercontent.com/paradigmxyz/reth/main/assets/reth-docs.png",
+    html_favicon_url = "https://avatars0.githubusercontent.com/u/97369466?s=256",
+    issue_tracker_base_url = "https://github.com/paradigmxyz/reth/issues/"
+)]
+#![cfg_attr(not(test), warn(unused_crate_dependencies))]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+
+use reth_db_api::database::Database;
+use reth_network::peers::PeersHandle;
+use reth_provider::DatabaseProviderFactory;
+use tokio::sync::mpsc;
+
+mod metrics;
+
+pub mod client;
+pub use client::{SnapFetchClient, SnapPeers};
+
+pub mod protocol;
+pub use protocol::{SnapConnection, SnapConnectionHandler, SnapProtocolHandler};
+
+pub mod server;
+pub use server::{IncomingSnapRequest, SnapRequestHandler};
+
+/// Maximum number of requests buffered for the [`SnapRequestHandler`].
+const SNAP_REQUEST_CHANNEL_CAPACITY: usize = 256;
+
+/// Creates the `snap` protocol handler to install on the network, and the [`SnapRequestHandler`]
+/// answering the requests of the peers from the database.
+///
+/// The `snap/1` connections are registered in `snap_peers`, for the [`SnapFetchClient`] to send
+/// requests over.
+///
+/// The request handler serves the requests on blocking tasks, and should be spawned as a task.
+pub fn snap_server<DB, Provider>(
+    provider: Provider,
+    peers: PeersHandle,
+    snap_peers: SnapPeers,
+) -> (SnapProtocolHandler, SnapRequestHandler<DB, Provider>)
+where
+    DB: Database,
+    Provider: DatabaseProviderFactory<DB>,
+{
+    let (tx, rx) = mpsc::channel(SNAP_REQUEST_CHANNEL_CAPACITY);
+    (
+        SnapProtocolHandler::new(peers.clone(), tx, snap_peers),
+        SnapRequestHandler::new(provider, peers, rx),
+    )
+}
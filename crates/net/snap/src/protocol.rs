@@ -0,0 +1,279 @@
+//! The `snap` protocol handlers installed on the network.
+
+use crate::{
+    client::{OutgoingSnapRequest, SnapPeers},
+    server::IncomingSnapRequest,
+};
+use futures::{future::BoxFuture, stream::FuturesUnordered, FutureExt, Stream, StreamExt};
+use reth_eth_wire::{
+    capability::{Capability, SharedCapabilities},
+    multiplex::ProtocolConnection,
+    protocol::Protocol,
+    SnapMessage, SnapMessageID,
+};
+use reth_network::{
+    peers::PeersHandle,
+    protocol::{ConnectionHandler, OnNotSupported, ProtocolHandler},
+};
+use reth_network_api::{Direction, ReputationChangeKind};
+use reth_network_peers::PeerId;
+use reth_primitives::BytesMut;
+use std::{
+    collections::HashMap,
+    fmt,
+    net::SocketAddr,
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+use tokio::sync::{mpsc, oneshot};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tracing::trace;
+
+/// Maximum number of requests of a peer being served at once.
+///
+/// Further requests are dropped, and the peer is penalized.
+const MAX_CONCURRENT_PEER_REQUESTS: usize = 16;
+
+/// Returns the `snap/1` protocol.
+pub const fn snap_protocol() -> Protocol {
+    Protocol::new(Capability::new_static("snap", 1), SnapMessageID::max() + 1)
+}
+
+/// Offers the `snap/1` protocol on all connections, serving the requests of the peers with the
+/// [`SnapRequestHandler`](crate::SnapRequestHandler).
+///
+/// The established connections are registered in [`SnapPeers`], so that the
+/// [`SnapFetchClient`](crate::SnapFetchClient) can send requests over them.
+#[derive(Debug, Clone)]
+pub struct SnapProtocolHandler {
+    /// Used for reporting peers.
+    peers: PeersHandle,
+    /// Sender half of the channel of the request handler.
+    to_request_handler: mpsc::Sender<IncomingSnapRequest>,
+    /// The active `snap/1` connections.
+    snap_peers: SnapPeers,
+}
+
+impl SnapProtocolHandler {
+    /// Create a new instance
+    pub const fn new(
+        peers: PeersHandle,
+        to_request_handler: mpsc::Sender<IncomingSnapRequest>,
+        snap_peers: SnapPeers,
+    ) -> Self {
+        Self { peers, to_request_handler, snap_peers }
+    }
+
+    fn connection_handler(&self) -> SnapConnectionHandler {
+        SnapConnectionHandler {
+            peers: self.peers.clone(),
+            to_request_handler: self.to_request_handler.clone(),
+            snap_peers: self.snap_peers.clone(),
+        }
+    }
+}
+
+impl ProtocolHandler for SnapProtocolHandler {
+    type ConnectionHandler = SnapConnectionHandler;
+
+    fn on_incoming(&self, _socket_addr: SocketAddr) -> Option<Self::ConnectionHandler> {
+        Some(self.connection_handler())
+    }
+
+    fn on_outgoing(
+        &self,
+        _socket_addr: SocketAddr,
+        _peer_id: PeerId,
+    ) -> Option<Self::ConnectionHandler> {
+        Some(self.connection_handler())
+    }
+}
+
+/// Negotiates the `snap/1` protocol on a connection.
+#[derive(Debug)]
+pub struct SnapConnectionHandler {
+    peers: PeersHandle,
+    to_request_handler: mpsc::Sender<IncomingSnapRequest>,
+    snap_peers: SnapPeers,
+}
+
+impl ConnectionHandler for SnapConnectionHandler {
+    type Connection = SnapConnection;
+
+    fn protocol(&self) -> Protocol {
+        snap_protocol()
+    }
+
+    fn on_unsupported_by_peer(
+        self,
+        _supported: &SharedCapabilities,
+        _direction: Direction,
+        _peer_id: PeerId,
+    ) -> OnNotSupported {
+        OnNotSupported::KeepAlive
+    }
+
+    fn into_connection(
+        self,
+        _direction: Direction,
+        peer_id: PeerId,
+        conn: ProtocolConnection,
+    ) -> Self::Connection {
+        let (connection_id, requests) = self.snap_peers.register(peer_id);
+        SnapConnection {
+            conn,
+            peer_id,
+            peers: self.peers,
+            to_request_handler: self.to_request_handler,
+            pending_responses: FuturesUnordered::new(),
+            snap_peers: self.snap_peers,
+            connection_id,
+            requests: UnboundedReceiverStream::new(requests),
+            inflight_requests: HashMap::new(),
+            next_request_id: 0,
+        }
+    }
+}
+
+/// A `snap/1` connection to a peer.
+///
+/// Forwards the requests of the peer to the [`SnapRequestHandler`](crate::SnapRequestHandler),
+/// and yields the responses to send back. Also sends the requests of the
+/// [`SnapFetchClient`](crate::SnapFetchClient) to the peer, and routes its responses back.
+pub struct SnapConnection {
+    /// The messages received from the peer.
+    conn: ProtocolConnection,
+    /// The ID of the peer.
+    peer_id: PeerId,
+    /// Used for reporting the peer.
+    peers: PeersHandle,
+    /// Sender half of the channel of the request handler.
+    to_request_handler: mpsc::Sender<IncomingSnapRequest>,
+    /// Responses to the requests being served.
+    pending_responses: FuturesUnordered<BoxFuture<'static, Option<SnapMessage>>>,
+    /// The active `snap/1` connections, this connection is removed from on drop.
+    snap_peers: SnapPeers,
+    /// The id of this connection in [`SnapPeers`].
+    connection_id: u64,
+    /// The requests to send to the peer.
+    requests: UnboundedReceiverStream<OutgoingSnapRequest>,
+    /// The requests sent to the peer that are awaiting a response, by request id.
+    inflight_requests: HashMap<u64, oneshot::Sender<SnapMessage>>,
+    /// The id of the next request sent to the peer.
+    next_request_id: u64,
+}
+
+impl SnapConnection {
+    /// Assigns a request id to the request, and returns the message to send to the peer.
+    fn on_outgoing_request(&mut self, request: OutgoingSnapRequest) -> BytesMut {
+        let OutgoingSnapRequest { mut request, response } = request;
+        let request_id = self.next_request_id;
+        self.next_request_id = self.next_request_id.wrapping_add(1);
+
+        request.set_request_id(request_id);
+        self.inflight_requests.insert(request_id, response);
+        request.encoded()
+    }
+
+    /// Routes a response of the peer to the sender of the request.
+    fn on_response(&mut self, message: SnapMessage) {
+        let Some(response) = self.inflight_requests.remove(&message.request_id()) else {
+            trace!(target: "net::snap", peer_id=?self.peer_id, "Unsolicited snap response");
+            self.peers.reputation_change(self.peer_id, ReputationChangeKind::BadMessage);
+            return
+        };
+        let _ = response.send(message);
+    }
+
+    /// Forwards a request to the request handler.
+    fn on_request(&self, message: SnapMessage) {
+        if self.pending_responses.len() >= MAX_CONCURRENT_PEER_REQUESTS {
+            trace!(target: "net::snap", peer_id=?self.peer_id, "Too many concurrent snap requests");
+            self.peers.reputation_change(self.peer_id, ReputationChangeKind::BadMessage);
+            return
+        }
+
+        let peer_id = self.peer_id;
+        let (request, response) = match message {
+            SnapMessage::GetAccountRange(request) => {
+                let (tx, rx) = oneshot::channel();
+                let response = rx.map(|res| res.ok().map(SnapMessage::AccountRange)).boxed();
+                (IncomingSnapRequest::GetAccountRange { peer_id, request, response: tx }, response)
+            }
+            SnapMessage::GetStorageRanges(request) => {
+                let (tx, rx) = oneshot::channel();
+                let response = rx.map(|res| res.ok().map(SnapMessage::StorageRanges)).boxed();
+                (IncomingSnapRequest::GetStorageRanges { peer_id, request, response: tx }, response)
+            }
+            SnapMessage::GetByteCodes(request) => {
+                let (tx, rx) = oneshot::channel();
+                let response = rx.map(|res| res.ok().map(SnapMessage::ByteCodes)).boxed();
+                (IncomingSnapRequest::GetByteCodes { peer_id, request, response: tx }, response)
+            }
+            SnapMessage::GetTrieNodes(request) => {
+                let (tx, rx) = oneshot::channel();
+                let response = rx.map(|res| res.ok().map(SnapMessage::TrieNodes)).boxed();
+                (IncomingSnapRequest::GetTrieNodes { peer_id, request, response: tx }, response)
+            }
+            SnapMessage::AccountRange(_) |
+            SnapMessage::StorageRanges(_) |
+            SnapMessage::ByteCodes(_) |
+            SnapMessage::TrieNodes(_) => unreachable!("responses are handled separately"),
+        };
+
+        // The request is dropped if the node is too busy to serve it
+        if self.to_request_handler.try_send(request).is_ok() {
+            self.pending_responses.push(response);
+        }
+    }
+}
+
+impl Stream for SnapConnection {
+    type Item = BytesMut;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Poll::Ready(Some(response)) = this.pending_responses.poll_next_unpin(cx) {
+                match response {
+                    Some(response) => return Poll::Ready(Some(response.encoded())),
+                    // The request handler dropped the request
+                    None => continue,
+                }
+            }
+
+            if let Poll::Ready(Some(request)) = this.requests.poll_next_unpin(cx) {
+                return Poll::Ready(Some(this.on_outgoing_request(request)))
+            }
+
+            let Some(msg) = ready!(this.conn.poll_next_unpin(cx)) else { return Poll::Ready(None) };
+
+            match SnapMessage::decode_message(&mut &msg[..]) {
+                Ok(message) if message.is_request() => this.on_request(message),
+                Ok(message) => this.on_response(message),
+                Err(err) => {
+                    trace!(target: "net::snap", peer_id=?this.peer_id, %err, "Failed to decode snap message");
+                    this.peers.reputation_change(this.peer_id, ReputationChangeKind::BadProtocol);
+                    return Poll::Ready(None)
+                }
+            }
+        }
+    }
+}
+
+impl fmt::Debug for SnapConnection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SnapConnection")
+            .field("peer_id", &self.peer_id)
+            .field("pending_responses", &self.pending_responses.len())
+            .field("inflight_requests", &self.inflight_requests.len())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for SnapConnection {
+    fn drop(&mut self) {
+        self.snap_peers.unregister(&self.peer_id, self.connection_id);
+    }
+}
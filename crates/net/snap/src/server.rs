@@ -0,0 +1,918 @@
+//! Serves the `snap` requests of the peers from the database.
+
+use crate::metrics::SnapRequestHandlerMetrics;
+use alloy_rlp::Encodable;
+use futures::{stream::FuturesUnordered, StreamExt};
+use reth_db::tables;
+use reth_db_api::{database::Database, transaction::DbTx};
+use reth_eth_wire::{
+    AccountData, AccountRange, ByteCodes, GetAccountRange, GetByteCodes, GetStorageRanges,
+    GetTrieNodes, SnapAccount, StorageData, StorageRanges, TrieNodes,
+};
+use reth_network::peers::PeersHandle;
+use reth_network_api::ReputationChangeKind;
+use reth_network_peers::PeerId;
+use reth_primitives::{Bytes, B256, KECCAK_EMPTY};
+use reth_provider::{
+    BlockNumReader, DatabaseProviderFactory, DatabaseProviderRO, HeaderProvider, ProviderError,
+};
+use reth_storage_errors::{db::DatabaseError, provider::ProviderResult};
+use reth_trie::{
+    hashed_cursor::{
+        DatabaseHashedCursorFactory, HashedCursor, HashedCursorFactory,
+        HashedPostStateCursorFactory,
+    },
+    prefix_set::TriePrefixSetsMut,
+    proof::Proof,
+    HashedPostState, HashedPostStateSorted, Nibbles,
+};
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    marker::PhantomData,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+use tokio::{
+    sync::{mpsc::Receiver, oneshot},
+    task::JoinHandle,
+};
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::{debug, trace};
+
+// Limits: <https://github.com/ethereum/go-ethereum/blob/v1.14.7/eth/protocols/snap/handler.go#L35-L56>
+
+/// Maximum size of replies to data retrievals.
+const SOFT_RESPONSE_LIMIT: u64 = 2 * 1024 * 1024;
+
+/// Maximum number of accounts to serve the storage of.
+///
+/// Used to limit lookups.
+const MAX_STORAGE_ACCOUNTS_SERVE: usize = 1024;
+
+/// Maximum number of bytecodes to serve.
+///
+/// Used to limit lookups.
+const MAX_CODE_LOOKUPS: usize = 1024;
+
+/// Maximum number of trie nodes to serve.
+///
+/// Used to limit lookups.
+const MAX_TRIE_NODE_LOOKUPS: usize = 1024;
+
+/// Number of latest blocks whose state is served, same as the number of diff layers geth keeps.
+pub const SERVED_BLOCKS: u64 = 128;
+
+/// Maximum number of requests served concurrently.
+pub const MAX_CONCURRENT_REQUESTS: usize = 16;
+
+/// Manages `snap` requests on top of the p2p network.
+///
+/// The requests are answered from the state of the latest [`SERVED_BLOCKS`] blocks. The state of
+/// older blocks is read from the latest state in the hashed state and trie tables, with the
+/// reverts of the blocks after them on top. Range proofs are generated with the [`Proof`]
+/// machinery of `reth_trie`.
+///
+/// Each request is served on a blocking task, up to [`MAX_CONCURRENT_REQUESTS`] at a time.
+///
+/// This can be spawned to another task and is supposed to be run as background service.
+#[derive(Debug)]
+#[must_use = "Manager does nothing unless polled."]
+pub struct SnapRequestHandler<DB, Provider> {
+    /// Serves the requests, shared with the blocking tasks.
+    server: Arc<SnapServer<DB, Provider>>,
+    /// Incoming requests from the connections of the peers.
+    incoming_requests: ReceiverStream<IncomingSnapRequest>,
+    /// The blocking tasks serving requests.
+    inflight_requests: FuturesUnordered<JoinHandle<()>>,
+}
+
+// === impl SnapRequestHandler ===
+impl<DB, Provider> SnapRequestHandler<DB, Provider> {
+    /// Create a new instance
+    pub fn new(
+        provider: Provider,
+        peers: PeersHandle,
+        incoming: Receiver<IncomingSnapRequest>,
+    ) -> Self {
+        Self {
+            server: Arc::new(SnapServer::new(provider, peers)),
+            incoming_requests: ReceiverStream::new(incoming),
+            inflight_requests: Default::default(),
+        }
+    }
+}
+
+/// Serves the `snap` requests from the database.
+#[derive(Debug)]
+struct SnapServer<DB, Provider> {
+    /// The provider of the database.
+    provider: Provider,
+    /// Used for reporting peers sending invalid requests.
+    peers: PeersHandle,
+    /// Metrics for the snap request handler.
+    metrics: SnapRequestHandlerMetrics,
+    _db: PhantomData<fn() -> DB>,
+}
+
+impl<DB, Provider> SnapServer<DB, Provider> {
+    fn new(provider: Provider, peers: PeersHandle) -> Self {
+        Self { provider, peers, metrics: Default::default(), _db: PhantomData }
+    }
+}
+
+impl<DB, Provider> SnapServer<DB, Provider>
+where
+    DB: Database,
+    Provider: DatabaseProviderFactory<DB>,
+{
+    /// Answers the request and sends the response through its channel.
+    fn on_request(&self, incoming: IncomingSnapRequest) {
+        match incoming {
+            IncomingSnapRequest::GetAccountRange { peer_id, request, response } => {
+                self.on_account_range_request(peer_id, request, response)
+            }
+            IncomingSnapRequest::GetStorageRanges { peer_id, request, response } => {
+                self.on_storage_ranges_request(peer_id, request, response)
+            }
+            IncomingSnapRequest::GetByteCodes { peer_id, request, response } => {
+                self.on_byte_codes_request(peer_id, request, response)
+            }
+            IncomingSnapRequest::GetTrieNodes { peer_id, request, response } => {
+                self.on_trie_nodes_request(peer_id, request, response)
+            }
+        }
+    }
+
+    /// Penalizes a peer for an invalid request.
+    fn on_invalid_request(&self, peer_id: PeerId, request: &str) {
+        trace!(target: "net::snap", ?peer_id, request, "Invalid snap request");
+        self.metrics.invalid_requests_total.increment(1);
+        self.peers.reputation_change(peer_id, ReputationChangeKind::BadMessage);
+    }
+
+    /// Returns the state with the given root, if it's the state of one of the latest
+    /// [`SERVED_BLOCKS`] blocks.
+    fn state_at(&self, root: B256) -> ProviderResult<Option<SnapState<DB>>> {
+        let provider = self.provider.database_provider_ro()?;
+
+        let best_block = provider.best_block_number()?;
+        let first_block = best_block.saturating_sub(SERVED_BLOCKS - 1);
+        let block = provider
+            .headers_range(first_block..=best_block)?
+            .iter()
+            .rposition(|header| header.state_root == root)
+            .map(|index| first_block + index as u64);
+        let Some(block) = block else {
+            self.metrics.unknown_root_requests_total.increment(1);
+            return Ok(None)
+        };
+
+        let reverts = if block == best_block {
+            HashedPostState::default()
+        } else {
+            match provider.hashed_state_reverts(block) {
+                Ok(reverts) => reverts,
+                Err(ProviderError::StateAtBlockPruned(_)) => {
+                    self.metrics.unknown_root_requests_total.increment(1);
+                    return Ok(None)
+                }
+                Err(err) => return Err(err),
+            }
+        };
+
+        Ok(Some(SnapState {
+            prefix_sets: reverts.construct_prefix_sets(),
+            reverts: reverts.into_sorted(),
+            provider,
+        }))
+    }
+
+    fn on_account_range_request(
+        &self,
+        peer_id: PeerId,
+        request: GetAccountRange,
+        response: oneshot::Sender<AccountRange>,
+    ) {
+        self.metrics.account_range_requests_received_total.increment(1);
+
+        let mut account_range =
+            AccountRange { request_id: request.request_id, ..Default::default() };
+        if request.starting_hash > request.limit_hash {
+            self.on_invalid_request(peer_id, "GetAccountRange");
+        } else {
+            match self.get_account_range(&request) {
+                Ok(Some(range)) => account_range = range,
+                Ok(None) => {}
+                Err(err) => debug!(target: "net::snap", %err, "Failed to serve account range"),
+            }
+        }
+
+        let _ = response.send(account_range);
+    }
+
+    /// Returns the accounts from the starting hash up to the limit hash, and the proof of the
+    /// starting hash and the last account.
+    fn get_account_range(&self, request: &GetAccountRange) -> ProviderResult<Option<AccountRange>> {
+        let Some(state) = self.state_at(request.root_hash)? else { return Ok(None) };
+        let proof = state.proof();
+
+        let limit = request.response_bytes.min(SOFT_RESPONSE_LIMIT) as usize;
+        let mut size = 0;
+        let mut accounts = Vec::new();
+
+        let mut cursor = state.hashed_cursor_factory().hashed_account_cursor()?;
+        let mut entry = cursor.seek(request.starting_hash)?;
+        while let Some((hash, account)) = entry {
+            let storage_root = proof.storage_root(hash).map_err(DatabaseError::from)?;
+            let body = SnapAccount {
+                nonce: account.nonce,
+                balance: account.balance,
+                storage_root,
+                code_hash: account.bytecode_hash.unwrap_or(KECCAK_EMPTY),
+            };
+
+            size += hash.len() + body.length();
+            accounts.push(AccountData { hash, body });
+
+            if hash >= request.limit_hash || size >= limit {
+                break
+            }
+            entry = cursor.next()?;
+        }
+
+        let mut targets = vec![Nibbles::unpack(request.starting_hash)];
+        targets.extend(accounts.last().map(|account| Nibbles::unpack(account.hash)));
+        let proof = proof.account_trie_nodes(targets).map_err(DatabaseError::from)?;
+
+        Ok(Some(AccountRange {
+            request_id: request.request_id,
+            accounts,
+            proof: proof.into_values().collect(),
+        }))
+    }
+
+    fn on_storage_ranges_request(
+        &self,
+        peer_id: PeerId,
+        request: GetStorageRanges,
+        response: oneshot::Sender<StorageRanges>,
+    ) {
+        self.metrics.storage_ranges_requests_received_total.increment(1);
+
+        let mut storage_ranges =
+            StorageRanges { request_id: request.request_id, ..Default::default() };
+        let bounds = decode_hash(&request.starting_hash, B256::ZERO)
+            .zip(decode_hash(&request.limit_hash, B256::repeat_byte(0xff)))
+            .filter(|(start, limit)| request.account_hashes.len() > 1 || start <= limit);
+        match bounds {
+            Some(_) if request.account_hashes.is_empty() => {
+                self.on_invalid_request(peer_id, "GetStorageRanges")
+            }
+            Some((start, limit)) => match self.get_storage_ranges(&request, start, limit) {
+                Ok(Some(ranges)) => storage_ranges = ranges,
+                Ok(None) => {}
+                Err(err) => debug!(target: "net::snap", %err, "Failed to serve storage ranges"),
+            },
+            None => self.on_invalid_request(peer_id, "GetStorageRanges"),
+        }
+
+        let _ = response.send(storage_ranges);
+    }
+
+    /// Returns the storage slots of the requested accounts.
+    ///
+    /// The start hash only applies to the first account, and the limit hash to the last one. If
+    /// the storage of the last served account is incomplete, its range is proven.
+    fn get_storage_ranges(
+        &self,
+        request: &GetStorageRanges,
+        start: B256,
+        limit_hash: B256,
+    ) -> ProviderResult<Option<StorageRanges>> {
+        let Some(state) = self.state_at(request.root_hash)? else { return Ok(None) };
+        let hashed_cursor_factory = state.hashed_cursor_factory();
+        let proof = state.proof();
+
+        let limit = request.response_bytes.min(SOFT_RESPONSE_LIMIT) as usize;
+        let mut size = 0;
+        let mut storage_ranges =
+            StorageRanges { request_id: request.request_id, ..Default::default() };
+
+        let accounts =
+            &request.account_hashes[..request.account_hashes.len().min(MAX_STORAGE_ACCOUNTS_SERVE)];
+        for (index, hashed_address) in accounts.iter().copied().enumerate() {
+            if size >= limit {
+                break
+            }
+
+            let origin = if index == 0 { start } else { B256::ZERO };
+            let end =
+                if index == accounts.len() - 1 { limit_hash } else { B256::repeat_byte(0xff) };
+
+            let mut slots = Vec::new();
+            let mut incomplete = false;
+            let mut cursor = hashed_cursor_factory.hashed_storage_cursor(hashed_address)?;
+            let mut entry = cursor.seek(origin)?;
+            while let Some((key, value)) = entry {
+                if size >= limit {
+                    incomplete = true;
+                    break
+                }
+
+                let data = Bytes::from(alloy_rlp::encode(value));
+                size += key.len() + data.len();
+                slots.push(StorageData { hash: key, data });
+
+                if key >= end {
+                    break
+                }
+                entry = cursor.next()?;
+            }
+
+            let last = slots.last().map(|slot| slot.hash);
+            storage_ranges.slots.push(slots);
+
+            // Proofs are only needed if the storage of the account isn't served completely.
+            if origin != B256::ZERO || incomplete {
+                let mut targets = vec![Nibbles::unpack(origin)];
+                targets.extend(last.map(Nibbles::unpack));
+                let proof = proof
+                    .storage_trie_nodes(hashed_address, targets)
+                    .map_err(DatabaseError::from)?;
+                storage_ranges.proof = proof.into_values().collect();
+                break
+            }
+        }
+
+        Ok(Some(storage_ranges))
+    }
+
+    fn on_byte_codes_request(
+        &self,
+        _peer_id: PeerId,
+        request: GetByteCodes,
+        response: oneshot::Sender<ByteCodes>,
+    ) {
+        self.metrics.byte_codes_requests_received_total.increment(1);
+
+        let byte_codes = self.get_byte_codes(&request).unwrap_or_else(|err| {
+            debug!(target: "net::snap", %err, "Failed to serve bytecodes");
+            ByteCodes { request_id: request.request_id, ..Default::default() }
+        });
+
+        let _ = response.send(byte_codes);
+    }
+
+    /// Returns the requested bytecodes, skipping unknown ones.
+    fn get_byte_codes(&self, request: &GetByteCodes) -> ProviderResult<ByteCodes> {
+        let provider = self.provider.database_provider_ro()?;
+        let tx = provider.tx_ref();
+
+        let limit = request.response_bytes.min(SOFT_RESPONSE_LIMIT) as usize;
+        let mut size = 0;
+        let mut codes = Vec::new();
+
+        for hash in request.hashes.iter().take(MAX_CODE_LOOKUPS) {
+            if *hash == KECCAK_EMPTY {
+                codes.push(Bytes::new());
+            } else if let Some(code) = tx.get::<tables::Bytecodes>(*hash)? {
+                let code = code.original_bytes();
+                size += code.len();
+                codes.push(code);
+            }
+
+            if size >= limit {
+                break
+            }
+        }
+
+        Ok(ByteCodes { request_id: request.request_id, codes })
+    }
+
+    fn on_trie_nodes_request(
+        &self,
+        peer_id: PeerId,
+        request: GetTrieNodes,
+        response: oneshot::Sender<TrieNodes>,
+    ) {
+        self.metrics.trie_nodes_requests_received_total.increment(1);
+
+        let mut trie_nodes = TrieNodes { request_id: request.request_id, ..Default::default() };
+        match request.paths.iter().map(|paths| TrieNodePathSet::decode(paths)).collect() {
+            Some(path_sets) => match self.get_trie_nodes(&request, path_sets) {
+                Ok(Some(nodes)) => trie_nodes = nodes,
+                Ok(None) => {}
+                Err(err) => debug!(target: "net::snap", %err, "Failed to serve trie nodes"),
+            },
+            None => self.on_invalid_request(peer_id, "GetTrieNodes"),
+        }
+
+        let _ = response.send(trie_nodes);
+    }
+
+    /// Returns the requested trie nodes, up to the first unknown one.
+    fn get_trie_nodes(
+        &self,
+        request: &GetTrieNodes,
+        mut path_sets: Vec<TrieNodePathSet>,
+    ) -> ProviderResult<Option<TrieNodes>> {
+        let Some(state) = self.state_at(request.root_hash)? else { return Ok(None) };
+        let proof = state.proof();
+
+        // Limit the number of lookups
+        let mut lookups = 0;
+        path_sets.retain_mut(|path_set| {
+            let paths = match path_set {
+                TrieNodePathSet::Account(_) => 1,
+                TrieNodePathSet::Storage(_, paths) => {
+                    paths.truncate(MAX_TRIE_NODE_LOOKUPS.saturating_sub(lookups));
+                    paths.len()
+                }
+            };
+            lookups += paths;
+            paths > 0 && lookups <= MAX_TRIE_NODE_LOOKUPS
+        });
+
+        let account_paths = path_sets
+            .iter()
+            .filter_map(|path_set| match path_set {
+                TrieNodePathSet::Account(path) => Some(path.clone()),
+                TrieNodePathSet::Storage(..) => None,
+            })
+            .collect::<Vec<_>>();
+        let account_nodes = if account_paths.is_empty() {
+            BTreeMap::new()
+        } else {
+            proof.account_trie_nodes(account_paths).map_err(DatabaseError::from)?
+        };
+
+        let limit = request.response_bytes.min(SOFT_RESPONSE_LIMIT) as usize;
+        let mut size = 0;
+        let mut nodes = Vec::new();
+
+        'path_sets: for path_set in path_sets {
+            match path_set {
+                TrieNodePathSet::Account(path) => {
+                    let Some(node) = account_nodes.get(&path) else { break };
+                    size += node.len();
+                    nodes.push(node.clone());
+                }
+                TrieNodePathSet::Storage(hashed_address, paths) => {
+                    let mut storage_nodes = proof
+                        .storage_trie_nodes(hashed_address, paths.clone())
+                        .map_err(DatabaseError::from)?;
+                    for path in paths {
+                        let Some(node) = storage_nodes.remove(&path) else { break 'path_sets };
+                        size += node.len();
+                        nodes.push(node);
+
+                        if size >= limit {
+                            break 'path_sets
+                        }
+                    }
+                }
+            }
+
+            if size >= limit {
+                break
+            }
+        }
+
+        Ok(Some(TrieNodes { request_id: request.request_id, nodes }))
+    }
+}
+
+/// The state of one of the latest [`SERVED_BLOCKS`] blocks.
+///
+/// The state is read from the latest state, with the reverts of the blocks after the block on top.
+struct SnapState<DB: Database> {
+    /// The read-only provider of the latest state.
+    provider: DatabaseProviderRO<DB>,
+    /// The reverts of the blocks after the block, empty for the latest block.
+    reverts: HashedPostStateSorted,
+    /// The prefix sets of the reverts, the trie nodes on their paths are recomputed.
+    prefix_sets: TriePrefixSetsMut,
+}
+
+impl<DB: Database> SnapState<DB> {
+    /// Returns the cursor factory of the hashed state.
+    const fn hashed_cursor_factory(
+        &self,
+    ) -> HashedPostStateCursorFactory<'_, DatabaseHashedCursorFactory<'_, DB::TX>> {
+        HashedPostStateCursorFactory::new(
+            DatabaseHashedCursorFactory::new(self.provider.tx_ref()),
+            &self.reverts,
+        )
+    }
+
+    /// Returns the proof generator of the state.
+    fn proof(
+        &self,
+    ) -> Proof<'_, DB::TX, HashedPostStateCursorFactory<'_, DatabaseHashedCursorFactory<'_, DB::TX>>>
+    {
+        Proof::from_tx(self.provider.tx_ref())
+            .with_hashed_cursor_factory(self.hashed_cursor_factory())
+            .with_prefix_sets_mut(self.prefix_sets.clone())
+    }
+}
+
+/// An endless future.
+///
+/// This should be spawned or used as part of `tokio::select!`.
+impl<DB, Provider> Future for SnapRequestHandler<DB, Provider>
+where
+    DB: Database + 'static,
+    Provider: DatabaseProviderFactory<DB> + Send + Sync + 'static,
+{
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        loop {
+            while this.inflight_requests.len() < MAX_CONCURRENT_REQUESTS {
+                let Poll::Ready(Some(incoming)) = this.incoming_requests.poll_next_unpin(cx) else {
+                    break
+                };
+                let server = Arc::clone(&this.server);
+                this.inflight_requests
+                    .push(tokio::task::spawn_blocking(move || server.on_request(incoming)));
+            }
+
+            // Requests are taken again once one of the inflight requests is served.
+            match this.inflight_requests.poll_next_unpin(cx) {
+                Poll::Ready(Some(result)) => {
+                    if let Err(err) = result {
+                        debug!(target: "net::snap", %err, "Failed to serve snap request");
+                    }
+                }
+                Poll::Ready(None) | Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// All `snap` requests delegated by the connections of the peers.
+#[derive(Debug)]
+pub enum IncomingSnapRequest {
+    /// Request a range of accounts.
+    ///
+    /// The response should be sent through the channel.
+    GetAccountRange {
+        /// The ID of the peer requesting the accounts.
+        peer_id: PeerId,
+        /// The specific range of accounts requested.
+        request: GetAccountRange,
+        /// The channel sender for the response containing the accounts.
+        response: oneshot::Sender<AccountRange>,
+    },
+    /// Request the storage slots of accounts.
+    ///
+    /// The response should be sent through the channel.
+    GetStorageRanges {
+        /// The ID of the peer requesting the storage slots.
+        peer_id: PeerId,
+        /// The specific storage slots requested.
+        request: GetStorageRanges,
+        /// The channel sender for the response containing the storage slots.
+        response: oneshot::Sender<StorageRanges>,
+    },
+    /// Request bytecodes.
+    ///
+    /// The response should be sent through the channel.
+    GetByteCodes {
+        /// The ID of the peer requesting the bytecodes.
+        peer_id: PeerId,
+        /// The specific bytecodes requested.
+        request: GetByteCodes,
+        /// The channel sender for the response containing the bytecodes.
+        response: oneshot::Sender<ByteCodes>,
+    },
+    /// Request trie nodes.
+    ///
+    /// The response should be sent through the channel.
+    GetTrieNodes {
+        /// The ID of the peer requesting the trie nodes.
+        peer_id: PeerId,
+        /// The specific trie nodes requested.
+        request: GetTrieNodes,
+        /// The channel sender for the response containing the trie nodes.
+        response: oneshot::Sender<TrieNodes>,
+    },
+}
+
+/// The decoded paths of a path set of a [`GetTrieNodes`] request.
+#[derive(Debug)]
+enum TrieNodePathSet {
+    /// A path in the account trie.
+    Account(Nibbles),
+    /// Paths in the storage trie of the account.
+    Storage(B256, Vec<Nibbles>),
+}
+
+impl TrieNodePathSet {
+    /// Decodes a path set, returning `None` if it is malformed.
+    fn decode(paths: &[Bytes]) -> Option<Self> {
+        match paths {
+            [] => None,
+            [path] => decode_compact_path(path).map(Self::Account),
+            [hashed_address, paths @ ..] => Some(Self::Storage(
+                (hashed_address.len() == 32).then(|| B256::from_slice(hashed_address))?,
+                paths.iter().map(|path| decode_compact_path(path)).collect::<Option<_>>()?,
+            )),
+        }
+    }
+}
+
+/// Decodes a hash of a request, which can be empty to use the default.
+fn decode_hash(hash: &[u8], default: B256) -> Option<B256> {
+    match hash.len() {
+        0 => Some(default),
+        32 => Some(B256::from_slice(hash)),
+        _ => None,
+    }
+}
+
+/// Decodes a compact (hex-prefix) encoded trie path.
+///
+/// An empty path is the path of the root node.
+fn decode_compact_path(path: &[u8]) -> Option<Nibbles> {
+    let Some((&first, rest)) = path.split_first() else { return Some(Nibbles::default()) };
+
+    let flag = first >> 4;
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    match flag {
+        // Even number of nibbles, with a zero padding nibble
+        0 | 2 if first & 0x0f == 0 => {}
+        // Odd number of nibbles
+        1 | 3 => nibbles.push(first & 0x0f),
+        _ => return None,
+    }
+    for byte in rest {
+        nibbles.push(byte >> 4);
+        nibbles.push(byte & 0x0f);
+    }
+
+    Some(Nibbles::from_nibbles_unchecked(nibbles))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{test_utils::TempDatabase, DatabaseEnv};
+    use reth_db_api::{cursor::DbCursorRO, models::AccountBeforeTx, transaction::DbTxMut};
+    use reth_network::peers::PeersManager;
+    use reth_primitives::{keccak256, Account, Address, Bytecode, Header, StorageEntry, U256};
+    use reth_provider::{
+        test_utils::create_test_provider_factory, ProviderFactory, StageCheckpointWriter,
+    };
+    use reth_stages_types::{StageCheckpoint, StageId};
+    use reth_trie::StateRoot;
+    use reth_trie_db::DatabaseStateRoot;
+    use std::sync::Arc;
+
+    type TestServer =
+        SnapServer<Arc<TempDatabase<DatabaseEnv>>, ProviderFactory<Arc<TempDatabase<DatabaseEnv>>>>;
+
+    /// Creates a state with accounts of increasing balances, the first ones with storage and code.
+    fn setup(accounts: u64) -> (TestServer, B256) {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xf3]));
+        let code_hash = keccak256(code.original_bytes());
+        tx.put::<tables::Bytecodes>(code_hash, code).unwrap();
+
+        for index in 1..=accounts {
+            let hashed_address = keccak256(B256::from(U256::from(index)));
+            let account = Account {
+                nonce: index,
+                balance: U256::from(index),
+                bytecode_hash: (index <= 10).then_some(code_hash),
+            };
+            tx.put::<tables::HashedAccounts>(hashed_address, account).unwrap();
+
+            if index <= 10 {
+                for slot in 1..=100 * index {
+                    let entry = StorageEntry {
+                        key: keccak256(B256::from(U256::from(slot))),
+                        value: U256::from(slot),
+                    };
+                    tx.put::<tables::HashedStorages>(hashed_address, entry).unwrap();
+                }
+            }
+        }
+
+        let (root, updates) = StateRoot::from_tx(tx).root_with_updates().unwrap();
+        updates.write_to_database(tx).unwrap();
+
+        let header = Header { state_root: root, ..Default::default() };
+        tx.put::<tables::CanonicalHeaders>(0, header.hash_slow()).unwrap();
+        tx.put::<tables::Headers>(0, header).unwrap();
+        provider.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(0)).unwrap();
+        provider.commit().unwrap();
+
+        (SnapServer::new(factory, PeersManager::default().handle()), root)
+    }
+
+    fn storage_request(
+        root: B256,
+        account_hashes: Vec<B256>,
+        response_bytes: u64,
+    ) -> GetStorageRanges {
+        GetStorageRanges {
+            request_id: 1,
+            root_hash: root,
+            account_hashes,
+            starting_hash: Bytes::new(),
+            limit_hash: Bytes::new(),
+            response_bytes,
+        }
+    }
+
+    #[tokio::test]
+    async fn serve_account_range() {
+        let (server, root) = setup(1000);
+
+        let request = GetAccountRange {
+            request_id: 1,
+            root_hash: root,
+            starting_hash: B256::ZERO,
+            limit_hash: B256::repeat_byte(0xff),
+            response_bytes: SOFT_RESPONSE_LIMIT,
+        };
+        let range = server.get_account_range(&request).unwrap().unwrap();
+        assert_eq!(range.accounts.len(), 1000);
+        assert!(range.accounts.windows(2).all(|accounts| accounts[0].hash < accounts[1].hash));
+        assert_eq!(keccak256(&range.proof[0]), root);
+
+        // The slim accounts have the storage roots of the accounts with storage
+        let with_storage = range
+            .accounts
+            .iter()
+            .filter(|account| account.body.storage_root != reth_trie::EMPTY_ROOT_HASH)
+            .count();
+        assert_eq!(with_storage, 10);
+
+        // The response is limited in size, and continues from the last account
+        let request = GetAccountRange { response_bytes: 1000, ..request };
+        let first = server.get_account_range(&request).unwrap().unwrap();
+        assert!(first.accounts.len() < 100);
+        assert_eq!(first.accounts[..], range.accounts[..first.accounts.len()]);
+
+        let request =
+            GetAccountRange { starting_hash: first.accounts.last().unwrap().hash, ..request };
+        let second = server.get_account_range(&request).unwrap().unwrap();
+        assert_eq!(second.accounts[0], *first.accounts.last().unwrap());
+
+        // Unknown roots are not served
+        let request = GetAccountRange { root_hash: B256::ZERO, ..request };
+        assert!(server.get_account_range(&request).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn serve_state_of_recent_blocks() {
+        let (server, root) = setup(100);
+
+        // Block 1 creates an account
+        let address = Address::with_last_byte(1);
+        let provider = server.provider.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        tx.put::<tables::HashedAccounts>(
+            keccak256(address),
+            Account { nonce: 1, ..Default::default() },
+        )
+        .unwrap();
+        tx.put::<tables::AccountChangeSets>(1, AccountBeforeTx { address, info: None }).unwrap();
+        let (latest_root, updates) = StateRoot::incremental_root_with_updates(tx, 1..=1).unwrap();
+        updates.write_to_database(tx).unwrap();
+        let header = Header { number: 1, state_root: latest_root, ..Default::default() };
+        tx.put::<tables::CanonicalHeaders>(1, header.hash_slow()).unwrap();
+        tx.put::<tables::Headers>(1, header).unwrap();
+        provider.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(1)).unwrap();
+        provider.commit().unwrap();
+
+        let request = |root_hash| GetAccountRange {
+            request_id: 1,
+            root_hash,
+            starting_hash: B256::ZERO,
+            limit_hash: B256::repeat_byte(0xff),
+            response_bytes: SOFT_RESPONSE_LIMIT,
+        };
+
+        // The state of the latest block has the new account
+        let latest = server.get_account_range(&request(latest_root)).unwrap().unwrap();
+        assert_eq!(latest.accounts.len(), 101);
+        assert_eq!(keccak256(&latest.proof[0]), latest_root);
+
+        // The state of the previous block is served with the reverts of the latest block
+        let previous = server.get_account_range(&request(root)).unwrap().unwrap();
+        assert_eq!(previous.accounts.len(), 100);
+        assert!(previous.accounts.iter().all(|account| account.hash != keccak256(address)));
+        assert_eq!(keccak256(&previous.proof[0]), root);
+    }
+
+    #[tokio::test]
+    async fn serve_storage_ranges() {
+        let (server, root) = setup(100);
+        let range = server
+            .get_account_range(&GetAccountRange {
+                request_id: 1,
+                root_hash: root,
+                starting_hash: B256::ZERO,
+                limit_hash: B256::repeat_byte(0xff),
+                response_bytes: SOFT_RESPONSE_LIMIT,
+            })
+            .unwrap()
+            .unwrap();
+        let accounts = range
+            .accounts
+            .iter()
+            .filter(|account| account.body.storage_root != reth_trie::EMPTY_ROOT_HASH)
+            .map(|account| account.hash)
+            .collect::<Vec<_>>();
+
+        // The storage of all accounts is served completely, without proof
+        let request = storage_request(root, accounts.clone(), SOFT_RESPONSE_LIMIT);
+        let ranges = server
+            .get_storage_ranges(&request, B256::ZERO, B256::repeat_byte(0xff))
+            .unwrap()
+            .unwrap();
+        assert_eq!(ranges.slots.len(), 10);
+        assert_eq!(ranges.slots.iter().map(Vec::len).sum::<usize>(), (1..=10).sum::<usize>() * 100);
+        assert!(ranges.proof.is_empty());
+
+        // The storage of the last served account is incomplete and proven
+        let request = storage_request(root, accounts, 10_000);
+        let ranges = server
+            .get_storage_ranges(&request, B256::ZERO, B256::repeat_byte(0xff))
+            .unwrap()
+            .unwrap();
+        let last = ranges.slots.last().unwrap();
+        assert!(!last.is_empty());
+        assert!(last.windows(2).all(|slots| slots[0].hash < slots[1].hash));
+        assert!(!ranges.proof.is_empty());
+    }
+
+    #[tokio::test]
+    async fn serve_byte_codes_and_trie_nodes() {
+        let (server, root) = setup(10);
+
+        let code_hash = server
+            .provider
+            .provider()
+            .unwrap()
+            .tx_ref()
+            .cursor_read::<tables::Bytecodes>()
+            .unwrap()
+            .first()
+            .unwrap()
+            .unwrap()
+            .0;
+        let request = GetByteCodes {
+            request_id: 1,
+            hashes: vec![code_hash, B256::ZERO, KECCAK_EMPTY],
+            response_bytes: SOFT_RESPONSE_LIMIT,
+        };
+        let codes = server.get_byte_codes(&request).unwrap().codes;
+        assert_eq!(codes.len(), 2);
+        assert_eq!(keccak256(&codes[0]), code_hash);
+        assert!(codes[1].is_empty());
+
+        let paths = vec![vec![Bytes::from_static(&[0x00])]];
+        let path_sets = paths
+            .iter()
+            .map(|paths| TrieNodePathSet::decode(paths))
+            .collect::<Option<Vec<_>>>()
+            .unwrap();
+        let request = GetTrieNodes {
+            request_id: 1,
+            root_hash: root,
+            paths,
+            response_bytes: SOFT_RESPONSE_LIMIT,
+        };
+        let nodes = server.get_trie_nodes(&request, path_sets).unwrap().unwrap().nodes;
+        assert_eq!(nodes.len(), 1);
+        assert_eq!(keccak256(&nodes[0]), root);
+    }
+
+    #[test]
+    fn decode_paths() {
+        assert_eq!(decode_compact_path(&[]), Some(Nibbles::default()));
+        assert_eq!(decode_compact_path(&[0x00]), Some(Nibbles::default()));
+        assert_eq!(decode_compact_path(&[0x11, 0x23]), Some(Nibbles::from_nibbles([1, 2, 3])));
+        assert_eq!(decode_compact_path(&[0x00, 0x12]), Some(Nibbles::from_nibbles([1, 2])));
+        assert_eq!(decode_compact_path(&[0x01, 0x12]), None);
+        assert_eq!(decode_compact_path(&[0x41]), None);
+
+        assert!(TrieNodePathSet::decode(&[]).is_none());
+        assert!(TrieNodePathSet::decode(&[Bytes::from_static(&[1; 31]), Bytes::new()]).is_none());
+        assert!(matches!(
+            TrieNodePathSet::decode(&[Bytes::from_static(&[1; 32]), Bytes::new()]),
+            Some(TrieNodePathSet::Storage(_, paths)) if paths == vec![Nibbles::default()]
+        ));
+    }
+}
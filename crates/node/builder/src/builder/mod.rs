@@ -25,13 +25,17 @@ use reth_node_core::{
     cli::config::{PayloadBuilderConfig, RethTransactionPoolConfig},
     dirs::{ChainPath, DataDirPath},
     node_config::NodeConfig,
-    primitives::Head,
+    primitives::{BlockNumber, Head},
     rpc::eth::{helpers::AddDevSigners, FullEthApiServer},
 };
 use reth_primitives::revm_primitives::EnvKzgSettings;
-use reth_provider::{providers::BlockchainProvider, ChainSpecProvider, FullProvider};
+use reth_provider::{
+    providers::BlockchainProvider, ChainSpecProvider, DatabaseProviderFactory, FullProvider,
+};
+use reth_revm::overlay::StateOverlay;
+use reth_snap::SnapPeers;
 use reth_tasks::TaskExecutor;
-use reth_transaction_pool::{PoolConfig, TransactionPool};
+use reth_transaction_pool::{BundlePool, ImpersonatedAccounts, PoolConfig, TransactionPool};
 use secp256k1::SecretKey;
 use tracing::{info, trace, warn};
 
@@ -40,6 +44,7 @@ use crate::{
     components::NodeComponentsBuilder,
     node::FullNode,
     rpc::{EthApiBuilderProvider, RethRpcServerHandles, RpcContext},
+    setup::earliest_available_block,
     DefaultNodeLauncher, Node, NodeHandle,
 };
 
@@ -197,6 +202,28 @@ impl<DB> NodeBuilder<DB> {
 
         WithLaunchContext { builder: self.with_database(db), task_executor }
     }
+
+    /// Creates an _ephemeral_ preconfigured node for testing purposes, backed by an in-memory
+    /// database.
+    ///
+    /// Only the static files are written to a temporary data directory.
+    #[cfg(feature = "test-utils")]
+    pub fn testing_node_with_mem_db(
+        mut self,
+        task_executor: TaskExecutor,
+    ) -> WithLaunchContext<NodeBuilder<Arc<reth_db::mem::MemDatabase>>> {
+        let path = reth_node_core::dirs::MaybePlatformPath::<DataDirPath>::from(
+            reth_db::test_utils::tempdir_path(),
+        );
+        self.config = self.config.with_datadir_args(reth_node_core::args::DatadirArgs {
+            datadir: path,
+            ..Default::default()
+        });
+
+        let db = reth_db::test_utils::create_test_mem_db();
+
+        WithLaunchContext { builder: self.with_database(db), task_executor }
+    }
 }
 
 impl<DB> NodeBuilder<DB>
@@ -467,6 +494,19 @@ where
     }
 }
 
+/// State that is shared between the components of the node and its RPC add-ons.
+#[derive(Debug, Clone, Default)]
+pub struct SharedState {
+    /// The pool that stores MEV bundles until they are included in a block.
+    pub bundle_pool: BundlePool,
+    /// Account modifications that are applied in the next block in --dev mode.
+    pub state_overlay: StateOverlay,
+    /// Accounts that are impersonated in --dev mode.
+    pub impersonated_accounts: ImpersonatedAccounts,
+    /// The peers with an active `snap/1` connection.
+    pub snap_peers: SnapPeers,
+}
+
 /// Captures the necessary context for building the components of the node.
 pub struct BuilderContext<Node: FullNodeTypes> {
     /// The current head of the blockchain at launch.
@@ -477,6 +517,8 @@ pub struct BuilderContext<Node: FullNodeTypes> {
     pub(crate) executor: TaskExecutor,
     /// Config container
     pub(crate) config_container: WithConfigs,
+    /// State that is shared between the components and the add-ons of the node.
+    pub(crate) shared: SharedState,
 }
 
 impl<Node: FullNodeTypes> BuilderContext<Node> {
@@ -486,8 +528,9 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
         provider: Node::Provider,
         executor: TaskExecutor,
         config_container: WithConfigs,
+        shared: SharedState,
     ) -> Self {
-        Self { head, provider, executor, config_container }
+        Self { head, provider, executor, config_container, shared }
     }
 
     /// Returns the configured provider to interact with the blockchain.
@@ -532,6 +575,38 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
         self.config().txpool.pool_config()
     }
 
+    /// Returns the pool that stores MEV bundles.
+    ///
+    /// This pool is shared with the `mev_` RPC namespace, bundles submitted there can be offered
+    /// to the payload builder via this pool.
+    pub const fn bundle_pool(&self) -> &BundlePool {
+        &self.shared.bundle_pool
+    }
+
+    /// Returns the [`StateOverlay`] for --dev mode.
+    ///
+    /// Account modifications made via the `anvil_` and `hardhat_` RPC namespaces are recorded in
+    /// this overlay. The block executor must apply it, so that the mined blocks can be re-executed.
+    pub const fn state_overlay(&self) -> &StateOverlay {
+        &self.shared.state_overlay
+    }
+
+    /// Returns the accounts that are impersonated in --dev mode.
+    ///
+    /// Transactions of these accounts skip the signer checks of the transaction pool.
+    pub const fn impersonated_accounts(&self) -> &ImpersonatedAccounts {
+        &self.shared.impersonated_accounts
+    }
+
+    /// Returns the peers with an active `snap/1` connection.
+    ///
+    /// The connections are registered by the network started with
+    /// [`start_network`](Self::start_network), and used by the pipeline to download the state
+    /// over `snap/1`.
+    pub const fn snap_peers(&self) -> &SnapPeers {
+        &self.shared.snap_peers
+    }
+
     /// Loads `EnvKzgSettings::Default`.
     pub const fn kzg_settings(&self) -> eyre::Result<EnvKzgSettings> {
         Ok(EnvKzgSettings::Default)
@@ -557,11 +632,26 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
     where
         Pool: TransactionPool + Unpin + 'static,
     {
-        let (handle, network, txpool, eth) = builder
+        let (handle, mut network, txpool, eth) = builder
             .transactions(pool, Default::default())
             .request_handler(self.provider().clone())
             .split_with_handle();
 
+        // The `snap/1` connections are needed to serve the state, or to download it with the snap
+        // sync stage. Without the request handler, the requests of the peers are dropped.
+        let serve_snap = self.config().network.snap_serve;
+        if serve_snap || self.reth_config().stages.snap_sync.enabled {
+            let (snap_protocol, snap) = reth_snap::snap_server(
+                self.provider().clone(),
+                network.peers_handle(),
+                self.shared.snap_peers.clone(),
+            );
+            network.add_rlpx_sub_protocol(snap_protocol);
+            if serve_snap {
+                self.executor.spawn(snap);
+            }
+        }
+
         self.executor.spawn_critical("p2p txpool", txpool);
         self.executor.spawn_critical("p2p eth request handler", eth);
 
@@ -583,6 +673,9 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
                             }
                         }
                     }
+                    if let Err(err) = network.write_bans_to_file() {
+                        warn!(target: "reth::cli", %err, "Failed to write network bans to file");
+                    }
                 })
             },
         );
@@ -610,11 +703,26 @@ impl<Node: FullNodeTypes> BuilderContext<Node> {
                 default_peers_path,
             )
             .with_task_executor(Box::new(self.executor.clone()))
-            .set_head(self.head);
+            .set_head(self.head)
+            .earliest_block(self.earliest_block()?);
 
         Ok(builder)
     }
 
+    /// Returns the earliest block whose body and receipts the node can serve to peers, based on
+    /// the snap sync pivot and the configured pruning of the receipts.
+    fn earliest_block(&self) -> eyre::Result<BlockNumber> {
+        let prune_modes = self
+            .reth_config()
+            .prune
+            .clone()
+            .or_else(|| self.config().prune_config())
+            .map(|config| config.segments)
+            .unwrap_or_default();
+        let provider = self.provider().database_provider_ro()?;
+        Ok(earliest_available_block(&provider, &prune_modes, self.head.number)?)
+    }
+
     /// Get the network secret from the given data dir
     fn network_secret(&self, data_dir: &ChainPath<DataDirPath>) -> eyre::Result<SecretKey> {
         let network_secret_path =
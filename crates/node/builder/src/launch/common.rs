@@ -3,13 +3,15 @@
 use crate::{
     components::{NodeComponents, NodeComponentsBuilder},
     hooks::OnComponentInitializedHook,
-    BuilderContext, NodeAdapter,
+    setup::earliest_available_block,
+    BuilderContext, NodeAdapter, SharedState,
 };
 use backon::{ConstantBuilder, Retryable};
 use eyre::Context;
+use futures::{Stream, StreamExt};
 use rayon::ThreadPoolBuilder;
 use reth_auto_seal_consensus::MiningMode;
-use reth_beacon_consensus::EthBeaconConsensus;
+use reth_beacon_consensus::{BeaconConsensusEngineEvent, EthBeaconConsensus};
 use reth_blockchain_tree::{
     noop::NoopBlockchainTree, BlockchainTree, BlockchainTreeConfig, ShareableBlockchainTree,
     TreeExternals,
@@ -30,15 +32,19 @@ use reth_node_core::{
 use reth_primitives::{BlockNumber, Head, B256};
 use reth_provider::{
     providers::{BlockchainProvider, StaticFileProvider},
-    CanonStateNotificationSender, ProviderFactory, StaticFileProviderFactory,
+    BadBlockStore, CanonStateNotificationSender, CanonStateSubscriptions, ProviderFactory,
+    StaticFileProviderFactory, DEFAULT_MAX_BAD_BLOCKS,
 };
-use reth_prune::{PruneModes, PrunerBuilder};
+use reth_prune::{PruneModes, PrunerBuilder, PrunerEvent};
+use reth_revm::overlay::StateOverlay;
 use reth_rpc_builder::config::RethRpcServerConfig;
 use reth_rpc_layer::JwtSecret;
+use reth_snap::{SnapFetchClient, SnapPeers};
 use reth_stages::{sets::DefaultStages, MetricEvent, Pipeline, PipelineTarget};
 use reth_static_file::StaticFileProducer;
 use reth_tasks::TaskExecutor;
 use reth_tracing::tracing::{debug, error, info, warn};
+use reth_transaction_pool::{bundle::maintain_bundle_pool, BundlePool, ImpersonatedAccounts};
 use std::{marker::PhantomData, sync::Arc, thread::available_parallelism};
 use tokio::sync::{
     mpsc::{unbounded_channel, Receiver, UnboundedSender},
@@ -340,6 +346,26 @@ impl<R> LaunchContextWith<Attached<WithConfigs, R>> {
         Ok(secret)
     }
 
+    /// Opens the store of blocks rejected as invalid and spawns a task that records every
+    /// [`BeaconConsensusEngineEvent::InvalidBlock`] of the given stream in it.
+    pub fn spawn_bad_block_store<St>(&self, mut engine_events: St) -> eyre::Result<BadBlockStore>
+    where
+        St: Stream<Item = BeaconConsensusEngineEvent> + Send + Unpin + 'static,
+    {
+        let store = BadBlockStore::open(self.data_dir().bad_blocks(), DEFAULT_MAX_BAD_BLOCKS)?;
+        let bad_blocks = store.clone();
+        self.task_executor().spawn(async move {
+            while let Some(event) = engine_events.next().await {
+                if let BeaconConsensusEngineEvent::InvalidBlock(block) = event {
+                    if let Err(err) = bad_blocks.insert(Arc::unwrap_or_clone(block)) {
+                        warn!(target: "reth::cli", %err, "Failed to persist bad block");
+                    }
+                }
+            }
+        });
+        Ok(store)
+    }
+
     /// Returns the [`MiningMode`] intended for --dev mode.
     pub fn dev_mining_mode(&self, pending_transactions_listener: Receiver<B256>) -> MiningMode {
         if let Some(interval) = self.node_config().dev.block_time {
@@ -626,6 +652,7 @@ where
             self.blockchain_db().clone(),
             self.task_executor().clone(),
             self.configs().clone(),
+            SharedState::default(),
         );
 
         debug!(target: "reth::cli", "creating components");
@@ -652,6 +679,12 @@ where
 
         debug!(target: "reth::cli", "configured blockchain tree");
 
+        // evict included and expired bundles from the bundle pool
+        self.task_executor().spawn(Box::pin(maintain_bundle_pool(
+            builder_ctx.bundle_pool().clone(),
+            blockchain_db.canonical_state_stream(),
+        )));
+
         let node_adapter = NodeAdapter {
             components,
             task_executor: self.task_executor().clone(),
@@ -671,6 +704,10 @@ where
             node_adapter,
             head,
             consensus,
+            bundle_pool: builder_ctx.bundle_pool().clone(),
+            state_overlay: builder_ctx.state_overlay().clone(),
+            impersonated_accounts: builder_ctx.impersonated_accounts().clone(),
+            snap_peers: builder_ctx.snap_peers().clone(),
         };
 
         let ctx = LaunchContextWith {
@@ -732,6 +769,26 @@ where
         self.right().consensus.clone()
     }
 
+    /// Returns the pool that stores MEV bundles.
+    pub const fn bundle_pool(&self) -> &BundlePool {
+        &self.right().bundle_pool
+    }
+
+    /// Returns the [`StateOverlay`] for --dev mode.
+    pub const fn state_overlay(&self) -> &StateOverlay {
+        &self.right().state_overlay
+    }
+
+    /// Returns the accounts that are impersonated in --dev mode.
+    pub const fn impersonated_accounts(&self) -> &ImpersonatedAccounts {
+        &self.right().impersonated_accounts
+    }
+
+    /// Returns the peers with an active `snap/1` connection.
+    pub const fn snap_peers(&self) -> &SnapPeers {
+        &self.right().snap_peers
+    }
+
     /// Returns the metrics sender.
     pub fn sync_metrics_tx(&self) -> UnboundedSender<MetricEvent> {
         self.right().db_provider_container.metrics_sender.clone()
@@ -746,6 +803,41 @@ where
     pub const fn components(&self) -> &CB::Components {
         &self.node_adapter().components
     }
+
+    /// Spawns a task that updates the earliest block the network announces to peers, every time
+    /// the pruner of the given event stream finished.
+    pub fn spawn_earliest_block_updates<St>(&self, mut pruner_events: St)
+    where
+        St: Stream<Item = PrunerEvent> + Send + Unpin + 'static,
+    {
+        let provider_factory = self.provider_factory().clone();
+        let prune_modes = self.prune_modes();
+        let network = self.components().network().clone();
+        self.task_executor().spawn(async move {
+            while let Some(event) = pruner_events.next().await {
+                if let PrunerEvent::Finished { tip_block_number, .. } = event {
+                    match earliest_available_block(
+                        &provider_factory,
+                        &prune_modes,
+                        tip_block_number,
+                    ) {
+                        Ok(earliest) => network.update_earliest_block(earliest),
+                        Err(err) => {
+                            warn!(target: "reth::cli", %err, "Failed to read the earliest available block")
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    /// Returns the client that downloads the state over the `snap/1` connections of the network.
+    pub fn snap_client(&self) -> SnapFetchClient {
+        SnapFetchClient::new(
+            self.snap_peers().clone(),
+            self.components().network().peers_handle().clone(),
+        )
+    }
 }
 
 /// Joins two attachments together.
@@ -842,6 +934,10 @@ where
     node_adapter: NodeAdapter<T, CB::Components>,
     head: Head,
     consensus: Arc<dyn Consensus>,
+    bundle_pool: BundlePool,
+    state_overlay: StateOverlay,
+    impersonated_accounts: ImpersonatedAccounts,
+    snap_peers: SnapPeers,
 }
 
 #[cfg(test)]
@@ -157,7 +157,7 @@ where
             ctx.configs().clone(),
         )
         .launch()
-        .await;
+        .await?;
 
         // create pipeline
         let network_client = ctx.components().network().fetch_client().await?;
@@ -186,7 +186,7 @@ where
         // Configure the pipeline
         let pipeline_exex_handle =
             exex_manager_handle.clone().unwrap_or_else(ExExManagerHandle::empty);
-        let (pipeline, client) = if ctx.is_dev() {
+        let (pipeline, client, auto_seal_handle) = if ctx.is_dev() {
             info!(target: "reth::cli", "Starting Reth in dev mode");
 
             for (idx, (address, alloc)) in ctx.chain_spec().genesis.alloc.iter().enumerate() {
@@ -206,7 +206,10 @@ where
                 mining_mode,
                 ctx.components().block_executor().clone(),
             )
+            .state_overlay(ctx.state_overlay().clone())
+            .impersonated_accounts(ctx.impersonated_accounts().clone())
             .build();
+            let auto_seal_handle = task.handle();
 
             let pipeline = crate::setup::build_networked_pipeline(
                 &ctx.toml_config().stages,
@@ -214,11 +217,13 @@ where
                 ctx.consensus(),
                 ctx.provider_factory().clone(),
                 ctx.task_executor(),
+                ctx.snap_client(),
                 ctx.sync_metrics_tx(),
                 ctx.prune_config(),
                 max_block,
                 static_file_producer,
                 ctx.components().block_executor().clone(),
+                ctx.components().evm_config().clone(),
                 pipeline_exex_handle,
             )?;
 
@@ -227,7 +232,7 @@ where
             debug!(target: "reth::cli", "Spawning auto mine task");
             ctx.task_executor().spawn(Box::pin(task));
 
-            (pipeline, Either::Left(client))
+            (pipeline, Either::Left(client), Some(auto_seal_handle))
         } else {
             let pipeline = crate::setup::build_networked_pipeline(
                 &ctx.toml_config().stages,
@@ -235,15 +240,17 @@ where
                 ctx.consensus(),
                 ctx.provider_factory().clone(),
                 ctx.task_executor(),
+                ctx.snap_client(),
                 ctx.sync_metrics_tx(),
                 ctx.prune_config(),
                 max_block,
                 static_file_producer,
                 ctx.components().block_executor().clone(),
+                ctx.components().evm_config().clone(),
                 pipeline_exex_handle,
             )?;
 
-            (pipeline, Either::Right(network_client.clone()))
+            (pipeline, Either::Right(network_client.clone()), None)
         };
 
         let pipeline_events = pipeline.events();
@@ -258,6 +265,7 @@ where
         let pruner = pruner_builder.build_with_provider_factory(ctx.provider_factory().clone());
 
         let pruner_events = pruner.events();
+        ctx.spawn_earliest_block_updates(pruner.events());
         info!(target: "reth::cli", prune_config=?ctx.prune_config().unwrap_or_default(), "Pruner initialized");
         hooks.add(PruneHook::new(pruner, Box::new(ctx.task_executor().clone())));
 
@@ -278,6 +286,8 @@ where
         )?;
         info!(target: "reth::cli", "Consensus engine initialized");
 
+        let bad_block_store = ctx.spawn_bad_block_store(beacon_engine_handle.event_listener())?;
+
         let events = stream_select!(
             ctx.components().network().event_listener().map(Into::into),
             beacon_engine_handle.event_listener().map(Into::into),
@@ -329,6 +339,10 @@ where
             engine_api,
             ctx.node_config(),
             jwt_secret,
+            ctx.consensus(),
+            ctx.bundle_pool().clone(),
+            auto_seal_handle,
+            bad_block_store,
             rpc,
         )
         .await?;
@@ -336,6 +350,7 @@ where
         // in dev mode we generate 20 random dev-signer accounts
         if ctx.is_dev() {
             rpc_registry.eth_api().with_dev_accounts();
+            rpc_registry.eth_api().with_impersonated_accounts(ctx.impersonated_accounts().clone());
         }
 
         // Run consensus engine to completion
@@ -3,9 +3,12 @@
 use std::{
     fmt,
     ops::{Deref, DerefMut},
+    sync::Arc,
 };
 
 use futures::TryFutureExt;
+use reth_auto_seal_consensus::AutoSealHandle;
+use reth_consensus::Consensus;
 use reth_network::NetworkHandle;
 use reth_node_api::{BuilderProvider, FullNodeComponents};
 use reth_node_core::{
@@ -13,6 +16,7 @@ use reth_node_core::{
     rpc::{api::EngineApiServer, eth::FullEthApiServer},
 };
 use reth_payload_builder::PayloadBuilderHandle;
+use reth_provider::BadBlockStore;
 use reth_rpc_builder::{
     auth::{AuthRpcModule, AuthServerHandle},
     config::RethRpcServerConfig,
@@ -21,6 +25,7 @@ use reth_rpc_builder::{
 use reth_rpc_layer::JwtSecret;
 use reth_tasks::TaskExecutor;
 use reth_tracing::tracing::{debug, info};
+use reth_transaction_pool::BundlePool;
 
 use crate::{EthApiBuilderCtx, RpcAddOns};
 
@@ -165,6 +170,8 @@ pub struct RpcRegistry<Node: FullNodeComponents, EthApi> {
         TaskExecutor,
         Node::Provider,
         EthApi,
+        Node::Executor,
+        Arc<dyn Consensus>,
     >,
 }
 
@@ -176,6 +183,8 @@ impl<Node: FullNodeComponents, EthApi> Deref for RpcRegistry<Node, EthApi> {
         TaskExecutor,
         Node::Provider,
         EthApi,
+        Node::Executor,
+        Arc<dyn Consensus>,
     >;
 
     fn deref(&self) -> &Self::Target {
@@ -252,11 +261,16 @@ impl<'a, Node: FullNodeComponents, EthApi> RpcContext<'a, Node, EthApi> {
 }
 
 /// Launch the rpc servers.
+#[allow(clippy::too_many_arguments)]
 pub async fn launch_rpc_servers<Node, Engine, EthApi>(
     node: Node,
     engine_api: Engine,
     config: &NodeConfig,
     jwt_secret: JwtSecret,
+    consensus: Arc<dyn Consensus>,
+    bundle_pool: BundlePool,
+    auto_seal_handle: Option<AutoSealHandle>,
+    bad_block_store: BadBlockStore,
     add_ons: RpcAddOns<Node, EthApi>,
 ) -> eyre::Result<(RethRpcServerHandles, RpcRegistry<Node, EthApi>)>
 where
@@ -275,6 +289,11 @@ where
         .with_events(node.provider().clone())
         .with_executor(node.task_executor().clone())
         .with_evm_config(node.evm_config().clone())
+        .with_block_executor(node.block_executor().clone())
+        .with_consensus(consensus)
+        .with_bundle_pool(bundle_pool)
+        .with_auto_seal_handle(auto_seal_handle)
+        .with_bad_block_store(bad_block_store)
         .build_with_auth_server(module_config, engine_api, EthApi::eth_api_builder());
 
     let mut registry = RpcRegistry { registry };
@@ -7,15 +7,23 @@ use reth_downloaders::{
     bodies::bodies::BodiesDownloaderBuilder,
     headers::reverse_headers::ReverseHeadersDownloaderBuilder,
 };
-use reth_evm::execute::BlockExecutorProvider;
+use reth_evm::{execute::BlockExecutorProvider, ConfigureEvm};
 use reth_exex::ExExManagerHandle;
 use reth_network_p2p::{
     bodies::{client::BodiesClient, downloader::BodyDownloader},
     headers::{client::HeadersClient, downloader::HeaderDownloader},
+    snap::client::SnapClient,
 };
 use reth_node_core::primitives::{BlockNumber, B256};
-use reth_provider::ProviderFactory;
-use reth_stages::{prelude::DefaultStages, stages::ExecutionStage, Pipeline, StageSet};
+use reth_provider::{
+    ProviderFactory, ProviderResult, PruneCheckpointReader, StageCheckpointReader,
+};
+use reth_prune::{PruneModes, PrunePurpose, PruneSegment};
+use reth_stages::{
+    prelude::DefaultStages,
+    stages::{snap_sync_pivot, ExecutionStage, IndexAddressAppearancesStage, SnapSyncStage},
+    Pipeline, StageId, StageSet,
+};
 use reth_static_file::StaticFileProducer;
 use reth_tasks::TaskExecutor;
 use reth_tracing::tracing::debug;
@@ -24,23 +32,27 @@ use tokio::sync::watch;
 
 /// Constructs a [Pipeline] that's wired to the network
 #[allow(clippy::too_many_arguments)]
-pub fn build_networked_pipeline<DB, Client, Executor>(
+pub fn build_networked_pipeline<DB, Client, SnapC, Executor, EvmConfig>(
     config: &StageConfig,
     client: Client,
     consensus: Arc<dyn Consensus>,
     provider_factory: ProviderFactory<DB>,
     task_executor: &TaskExecutor,
+    snap_client: SnapC,
     metrics_tx: reth_stages::MetricEventsSender,
     prune_config: Option<PruneConfig>,
     max_block: Option<BlockNumber>,
     static_file_producer: StaticFileProducer<DB>,
     executor: Executor,
+    evm_config: EvmConfig,
     exex_manager_handle: ExExManagerHandle,
 ) -> eyre::Result<Pipeline<DB>>
 where
     DB: Database + Unpin + Clone + 'static,
     Client: HeadersClient + BodiesClient + Clone + 'static,
+    SnapC: SnapClient + 'static,
     Executor: BlockExecutorProvider,
+    EvmConfig: ConfigureEvm,
 {
     // building network downloaders using the fetch client
     let header_downloader = ReverseHeadersDownloaderBuilder::new(config.headers)
@@ -56,12 +68,14 @@ where
         config,
         header_downloader,
         body_downloader,
+        snap_client,
         consensus,
         max_block,
         metrics_tx,
         prune_config,
         static_file_producer,
         executor,
+        evm_config,
         exex_manager_handle,
     )?;
 
@@ -69,25 +83,32 @@ where
 }
 
 /// Builds the [Pipeline] with the given [`ProviderFactory`] and downloaders.
+///
+/// The `snap_client` is only used if the snap sync stage is enabled, the `evm_config` only if the
+/// address appearances are indexed.
 #[allow(clippy::too_many_arguments)]
-pub fn build_pipeline<DB, H, B, Executor>(
+pub fn build_pipeline<DB, H, B, SnapC, Executor, EvmConfig>(
     provider_factory: ProviderFactory<DB>,
     stage_config: &StageConfig,
     header_downloader: H,
     body_downloader: B,
+    snap_client: SnapC,
     consensus: Arc<dyn Consensus>,
     max_block: Option<u64>,
     metrics_tx: reth_stages::MetricEventsSender,
     prune_config: Option<PruneConfig>,
     static_file_producer: StaticFileProducer<DB>,
     executor: Executor,
+    evm_config: EvmConfig,
     exex_manager_handle: ExExManagerHandle,
 ) -> eyre::Result<Pipeline<DB>>
 where
     DB: Database + Clone + 'static,
     H: HeaderDownloader + 'static,
     B: BodyDownloader + 'static,
+    SnapC: SnapClient + 'static,
     Executor: BlockExecutorProvider,
+    EvmConfig: ConfigureEvm,
 {
     let mut builder = Pipeline::builder();
 
@@ -100,32 +121,78 @@ where
 
     let prune_modes = prune_config.map(|prune| prune.segments).unwrap_or_default();
 
+    let mut stages = DefaultStages::new(
+        provider_factory.clone(),
+        tip_rx,
+        Arc::clone(&consensus),
+        header_downloader,
+        body_downloader,
+        executor.clone(),
+        stage_config.clone(),
+        prune_modes.clone(),
+    )
+    .set(
+        ExecutionStage::new(
+            executor,
+            stage_config.execution.into(),
+            stage_config.execution_external_clean_threshold(),
+            prune_modes.clone(),
+            exex_manager_handle,
+        )
+        .with_metrics_tx(metrics_tx.clone()),
+    );
+
+    // If enabled, download the state of a recent block instead of executing all blocks.
+    if stage_config.snap_sync.enabled {
+        stages = stages
+            .add_after(SnapSyncStage::new(snap_client, stage_config.snap_sync), StageId::Bodies);
+    }
+
+    // If enabled, index the blocks in which each address appears.
+    if stage_config.index_address_appearances.enabled {
+        stages = stages.add_after(
+            IndexAddressAppearancesStage::new(
+                evm_config,
+                stage_config.index_address_appearances,
+                stage_config.etl.clone(),
+                prune_modes.address_appearances,
+            ),
+            StageId::IndexAccountHistory,
+        );
+    }
+
     let pipeline = builder
         .with_tip_sender(tip_tx)
-        .with_metrics_tx(metrics_tx.clone())
-        .add_stages(
-            DefaultStages::new(
-                provider_factory.clone(),
-                tip_rx,
-                Arc::clone(&consensus),
-                header_downloader,
-                body_downloader,
-                executor.clone(),
-                stage_config.clone(),
-                prune_modes.clone(),
-            )
-            .set(
-                ExecutionStage::new(
-                    executor,
-                    stage_config.execution.into(),
-                    stage_config.execution_external_clean_threshold(),
-                    prune_modes,
-                    exex_manager_handle,
-                )
-                .with_metrics_tx(metrics_tx),
-            ),
-        )
+        .with_metrics_tx(metrics_tx)
+        .add_stages(stages)
         .build(provider_factory, static_file_producer);
 
     Ok(pipeline)
 }
+
+/// Returns the earliest block whose body and receipts the node can serve to peers.
+///
+/// The receipts up to the pivot block of the snap sync were never downloaded, and the receipts
+/// pruned with the given [`PruneModes`] at the given head block are removed.
+pub fn earliest_available_block<P>(
+    provider: &P,
+    prune_modes: &PruneModes,
+    head: BlockNumber,
+) -> ProviderResult<BlockNumber>
+where
+    P: StageCheckpointReader + PruneCheckpointReader,
+{
+    let snap_pivot = snap_sync_pivot(provider)?;
+    let pruned = provider
+        .get_prune_checkpoint(PruneSegment::Receipts)?
+        .and_then(|checkpoint| checkpoint.block_number);
+    // the pruner may not have caught up with the configured mode yet
+    let prune_target = prune_modes
+        .receipts
+        .and_then(|mode| {
+            mode.prune_target_block(head, PruneSegment::Receipts, PrunePurpose::User).ok().flatten()
+        })
+        .map(|(block, _)| block);
+
+    Ok(snap_pivot.max(pruned).max(prune_target).map_or(0, |block| block + 1))
+}
@@ -23,9 +23,13 @@ use secp256k1::SecretKey;
 use std::{
     net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
     ops::Not,
-    path::PathBuf,
+    path::{Path, PathBuf},
     sync::Arc,
 };
+use tracing::warn;
+
+/// The name of the file the ban list is persisted to, next to the known peers file.
+const BANNED_PEERS_FILE_NAME: &str = "banned-peers.json";
 
 /// Parameters for configuring the network more granularity via CLI
 #[derive(Debug, Clone, Args, PartialEq, Eq)]
@@ -76,7 +80,8 @@ pub struct NetworkArgs {
     #[arg(long, verbatim_doc_comment)]
     pub no_persist_peers: bool,
 
-    /// NAT resolution method (any|none|upnp|publicip|extip:\<IP\>)
+    /// NAT resolution method
+    /// (any|none|upnp|publicip|extip:\<IP\>|natpmp[:\<GATEWAY\>]|pcp[:\<GATEWAY\>])
     #[arg(long, default_value = "any")]
     pub nat: NatResolver,
 
@@ -115,6 +120,12 @@ pub struct NetworkArgs {
     /// Default is 128 KiB.
     #[arg(long = "pooled-tx-pack-soft-limit", value_name = "BYTES", default_value_t = DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ, verbatim_doc_comment)]
     pub soft_limit_byte_size_pooled_transactions_response_on_pack_request: usize,
+
+    /// Serve the state of the latest block to peers over the `snap/1` protocol.
+    ///
+    /// Requests for the state of other blocks are answered with empty responses.
+    #[arg(long = "snap.serve")]
+    pub snap_serve: bool,
 }
 
 impl NetworkArgs {
@@ -154,6 +165,14 @@ impl NetworkArgs {
             .clone()
             .with_max_inbound_opt(self.max_inbound_peers)
             .with_max_outbound_opt(self.max_outbound_peers);
+        let bans_file = self.persistent_bans_file(&peers_file);
+        let peers_config = match peers_config.clone().with_ban_list_from_file(bans_file) {
+            Ok(peers_config) => peers_config,
+            Err(err) => {
+                warn!(target: "reth::cli", %err, "Failed to load bans from file");
+                peers_config
+            }
+        };
 
         // Configure transactions manager
         let transactions_manager_config = TransactionsManagerConfig {
@@ -206,6 +225,12 @@ impl NetworkArgs {
         self.no_persist_peers.not().then_some(peers_file)
     }
 
+    /// If `no_persist_peers` is false then this returns the path to the persistent ban list, which
+    /// is stored next to the given peers file.
+    pub fn persistent_bans_file(&self, peers_file: &Path) -> Option<PathBuf> {
+        self.no_persist_peers.not().then(|| peers_file.with_file_name(BANNED_PEERS_FILE_NAME))
+    }
+
     /// Sets the p2p port to zero, to allow the OS to assign a random unused port when
     /// the network components bind to a socket.
     pub const fn with_unused_p2p_port(mut self) -> Self {
@@ -261,6 +286,7 @@ impl Default for NetworkArgs {
             soft_limit_byte_size_pooled_transactions_response:
                 SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESPONSE,
             soft_limit_byte_size_pooled_transactions_response_on_pack_request: DEFAULT_SOFT_LIMIT_BYTE_SIZE_POOLED_TRANSACTIONS_RESP_ON_PACK_GET_POOLED_TRANSACTIONS_REQ,
+            snap_serve: false,
         }
     }
 }
@@ -455,6 +481,13 @@ mod tests {
         let args =
             CommandParser::<NetworkArgs>::parse_from(["reth", "--nat", "extip:0.0.0.0"]).args;
         assert_eq!(args.nat, NatResolver::ExternalIp("0.0.0.0".parse().unwrap()));
+
+        let args = CommandParser::<NetworkArgs>::parse_from(["reth", "--nat", "natpmp"]).args;
+        assert_eq!(args.nat, NatResolver::NatPmp(None));
+
+        let args =
+            CommandParser::<NetworkArgs>::parse_from(["reth", "--nat", "pcp:192.168.1.1"]).args;
+        assert_eq!(args.nat, NatResolver::Pcp(Some("192.168.1.1".parse().unwrap())));
     }
 
     #[test]
@@ -3,11 +3,12 @@ use clap::{
     builder::{RangedU64ValueParser, TypedValueParser},
     Arg, Args, Command,
 };
-use reth_cli_util::parse_duration_from_secs;
+use reth_cli_util::{load_secret_key, load_secret_key::SecretKeyError, parse_duration_from_secs};
 use reth_primitives::constants::{
     ETHEREUM_BLOCK_GAS_LIMIT, MAXIMUM_EXTRA_DATA_SIZE, SLOT_DURATION,
 };
-use std::{borrow::Cow, ffi::OsStr, time::Duration};
+use secp256k1::SecretKey;
+use std::{borrow::Cow, ffi::OsStr, path::Path, time::Duration};
 
 /// Parameters for configuring the Payload Builder
 #[derive(Debug, Clone, Args, PartialEq, Eq)]
@@ -32,6 +33,14 @@ pub struct PayloadBuilderArgs {
     /// Maximum number of tasks to spawn for building a payload.
     #[arg(long = "builder.max-tasks", default_value = "3", value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
     pub max_payload_tasks: usize,
+
+    /// Path to the hex encoded secret key of the fee recipient, used to pay the refunds of MEV
+    /// bundles.
+    ///
+    /// Bundles that require refunds are only included in blocks whose fee recipient is the address
+    /// of this key.
+    #[arg(long = "builder.refund-secret-key", value_name = "PATH", value_parser = parse_secret_key_file)]
+    pub refund_secret_key: Option<SecretKey>,
 }
 
 impl Default for PayloadBuilderArgs {
@@ -42,6 +51,7 @@ impl Default for PayloadBuilderArgs {
             interval: Duration::from_secs(1),
             deadline: SLOT_DURATION,
             max_payload_tasks: 3,
+            refund_secret_key: None,
         }
     }
 }
@@ -66,6 +76,15 @@ impl PayloadBuilderConfig for PayloadBuilderArgs {
     fn max_payload_tasks(&self) -> usize {
         self.max_payload_tasks
     }
+
+    fn refund_secret_key(&self) -> Option<SecretKey> {
+        self.refund_secret_key
+    }
+}
+
+/// Loads the secret key stored at the given path.
+fn parse_secret_key_file(path: &str) -> Result<SecretKey, SecretKeyError> {
+    load_secret_key(Path::new(path))
 }
 
 #[derive(Clone, Debug, Default)]
@@ -50,4 +50,8 @@ pub enum StageEnum {
     ///
     /// Manages historical data related to storage.
     StorageHistory,
+    /// The optional address appearances stage within the pipeline.
+    ///
+    /// Indexes the blocks in which an address appears.
+    AddressAppearances,
 }
@@ -3,6 +3,7 @@
 use reth_network::protocol::IntoRlpxSubProtocol;
 use reth_primitives::Bytes;
 use reth_transaction_pool::PoolConfig;
+use secp256k1::SecretKey;
 use std::{borrow::Cow, time::Duration};
 
 /// A trait that provides payload builder settings.
@@ -29,6 +30,9 @@ pub trait PayloadBuilderConfig {
 
     /// Maximum number of tasks to spawn for building a payload.
     fn max_payload_tasks(&self) -> usize;
+
+    /// The secret key of the fee recipient that pays the refunds of MEV bundles, if any.
+    fn refund_secret_key(&self) -> Option<SecretKey>;
 }
 
 /// A trait that represents the configured network and can be used to apply additional configuration
@@ -323,6 +323,20 @@ impl<D> ChainPath<D> {
         self.data_dir().join("blobstore")
     }
 
+    /// Returns the path to the write-ahead log of the execution extensions for this chain.
+    ///
+    /// `<DIR>/<CHAIN_ID>/exex/wal`
+    pub fn exex_wal(&self) -> PathBuf {
+        self.data_dir().join("exex").join("wal")
+    }
+
+    /// Returns the path to the file that stores the blocks rejected as invalid for this chain.
+    ///
+    /// `<DIR>/<CHAIN_ID>/bad-blocks.rlp`
+    pub fn bad_blocks(&self) -> PathBuf {
+        self.data_dir().join("bad-blocks.rlp")
+    }
+
     /// Returns the path to the local transactions backup file
     ///
     /// `<DIR>/<CHAIN_ID>/txpool-transactions-backup.rlp`
@@ -299,6 +299,9 @@ impl<DB> NodeState<DB> {
             BeaconConsensusEngineEvent::ForkBlockAdded(block) => {
                 info!(number=block.number, hash=?block.hash(), "Block added to fork chain");
             }
+            BeaconConsensusEngineEvent::InvalidBlock(block) => {
+                warn!(number=block.number, hash=?block.hash(), "Received invalid block");
+            }
         }
     }
 
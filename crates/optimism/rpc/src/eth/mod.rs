@@ -26,7 +26,7 @@ use reth_rpc_eth_api::{
 use reth_rpc_eth_types::EthStateCache;
 use reth_rpc_types::SyncStatus;
 use reth_tasks::{pool::BlockingTaskPool, TaskSpawner};
-use reth_transaction_pool::TransactionPool;
+use reth_transaction_pool::{ImpersonatedAccounts, TransactionPool};
 use tokio::sync::{AcquireError, OwnedSemaphorePermit};
 
 /// OP-Reth `Eth` API implementation.
@@ -160,6 +160,10 @@ impl<Eth: AddDevSigners> AddDevSigners for OpEthApi<Eth> {
     fn with_dev_accounts(&self) {
         *self.signers().write() = DevSigner::random_signers(20)
     }
+
+    fn with_impersonated_accounts(&self, accounts: ImpersonatedAccounts) {
+        self.inner.with_impersonated_accounts(accounts)
+    }
 }
 
 impl<Eth: UpdateRawTxForwarder> UpdateRawTxForwarder for OpEthApi<Eth> {
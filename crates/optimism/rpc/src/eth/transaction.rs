@@ -10,6 +10,7 @@ use reth_rpc_eth_api::{
     RawTransactionForwarder,
 };
 use reth_rpc_eth_types::{EthResult, EthStateCache};
+use reth_transaction_pool::ImpersonatedAccounts;
 use revm::L1BlockInfo;
 
 use crate::{OpEthApi, OpEthApiError};
@@ -23,6 +24,10 @@ impl<Eth: EthTransactions> EthTransactions for OpEthApi<Eth> {
         self.inner.raw_tx_forwarder()
     }
 
+    fn impersonated_accounts(&self) -> Option<ImpersonatedAccounts> {
+        self.inner.impersonated_accounts()
+    }
+
     fn signers(&self) -> &parking_lot::RwLock<Vec<Box<dyn EthSigner>>> {
         self.inner.signers()
     }
@@ -26,7 +26,10 @@ use reth_provider::{
 };
 use reth_revm::state_change::post_block_withdrawals_balance_increments;
 use reth_tasks::TaskSpawner;
-use reth_transaction_pool::TransactionPool;
+use reth_transaction_pool::{
+    bundle::{MevBundle, NoopBundleProvider},
+    BundleProvider, TransactionPool,
+};
 use revm::{
     primitives::{BlockEnv, CfgEnvWithHandlerCfg},
     Database, State,
@@ -69,6 +72,8 @@ pub struct BasicPayloadJobGenerator<Client, Pool, Tasks, Builder> {
     builder: Builder,
     /// Stored `cached_reads` for new payload jobs.
     pre_cached: Option<PrecachedState>,
+    /// Provides the bundles that are offered to the payload builder.
+    bundle_provider: Arc<dyn BundleProvider>,
 }
 
 // === impl BasicPayloadJobGenerator ===
@@ -93,9 +98,19 @@ impl<Client, Pool, Tasks, Builder> BasicPayloadJobGenerator<Client, Pool, Tasks,
             chain_spec,
             builder,
             pre_cached: None,
+            bundle_provider: Arc::new(NoopBundleProvider::default()),
         }
     }
 
+    /// Sets the [`BundleProvider`] that supplies the bundles that are offered to the payload
+    /// builder for inclusion.
+    ///
+    /// By default no bundles are offered.
+    pub fn with_bundle_provider(mut self, bundle_provider: Arc<dyn BundleProvider>) -> Self {
+        self.bundle_provider = bundle_provider;
+        self
+    }
+
     /// Returns the maximum duration a job should be allowed to run.
     ///
     /// This adheres to the following specification:
@@ -191,6 +206,7 @@ where
             payload_task_guard: self.payload_task_guard.clone(),
             metrics: Default::default(),
             builder: self.builder.clone(),
+            bundle_provider: Arc::clone(&self.bundle_provider),
         };
 
         // start the first job right away
@@ -348,6 +364,8 @@ where
     ///
     /// See [`PayloadBuilder`]
     builder: Builder,
+    /// Provides the bundles that are offered to the payload builder.
+    bundle_provider: Arc<dyn BundleProvider>,
 }
 
 impl<Client, Pool, Tasks, Builder> BasicPayloadJob<Client, Pool, Tasks, Builder>
@@ -359,6 +377,11 @@ where
     <Builder as PayloadBuilder<Pool, Client>>::Attributes: Unpin + Clone,
     <Builder as PayloadBuilder<Pool, Client>>::BuiltPayload: Unpin + Clone,
 {
+    /// Returns the bundles that are eligible for inclusion in the payload.
+    fn bundles(&self) -> Vec<Arc<MevBundle>> {
+        self.bundle_provider.bundles_for_block(self.config.parent_block.number + 1)
+    }
+
     /// Spawns a new payload build task.
     fn spawn_build_job(&mut self) {
         trace!(target: "payload_builder", "spawn new payload build task");
@@ -373,6 +396,7 @@ where
         self.metrics.inc_initiated_payload_builds();
         let cached_reads = self.cached_reads.take().unwrap_or_default();
         let builder = self.builder.clone();
+        let bundles = self.bundles();
         self.executor.spawn_blocking(Box::pin(async move {
             // acquire the permit for executing the task
             let _permit = guard.acquire().await;
@@ -383,6 +407,7 @@ where
                 config: payload_config,
                 cancel,
                 best_payload,
+                bundles,
             };
             let result = builder.try_build(args);
             let _ = tx.send(result);
@@ -507,6 +532,7 @@ where
                 config: self.config.clone(),
                 cancel: Cancelled::default(),
                 best_payload: None,
+                bundles: self.bundles(),
             };
 
             match self.builder.on_missing_payload(args) {
@@ -767,6 +793,8 @@ pub struct BuildArguments<Pool, Client, Attributes, Payload> {
     pub cancel: Cancelled,
     /// The best payload achieved so far.
     pub best_payload: Option<Payload>,
+    /// The bundles that are eligible for inclusion in the payload.
+    pub bundles: Vec<Arc<MevBundle>>,
 }
 
 impl<Pool, Client, Attributes, Payload> BuildArguments<Pool, Client, Attributes, Payload> {
@@ -778,8 +806,9 @@ impl<Pool, Client, Attributes, Payload> BuildArguments<Pool, Client, Attributes,
         config: PayloadConfig<Attributes>,
         cancel: Cancelled,
         best_payload: Option<Payload>,
+        bundles: Vec<Arc<MevBundle>>,
     ) -> Self {
-        Self { client, pool, cached_reads, config, cancel, best_payload }
+        Self { client, pool, cached_reads, config, cancel, best_payload, bundles }
     }
 }
 
@@ -0,0 +1,184 @@
+use crate::{
+    segments::{static_file::delete_pruned_static_files, PruneInput, Segment},
+    PrunerError,
+};
+use reth_db::tables;
+use reth_db_api::database::Database;
+use reth_provider::{providers::StaticFileProvider, DatabaseProviderRW};
+use reth_prune_types::{
+    PruneMode, PruneProgress, PrunePurpose, PruneSegment, SegmentOutput, SegmentOutputCheckpoint,
+};
+use reth_static_file_types::StaticFileSegment;
+use tracing::trace;
+
+#[derive(Debug)]
+pub struct AccountChangeSets {
+    static_file_provider: StaticFileProvider,
+}
+
+impl AccountChangeSets {
+    pub const fn new(static_file_provider: StaticFileProvider) -> Self {
+        Self { static_file_provider }
+    }
+}
+
+impl<DB: Database> Segment<DB> for AccountChangeSets {
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::AccountChangeSets
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        self.static_file_provider
+            .get_highest_static_file_block(StaticFileSegment::AccountChangeSets)
+            .map(PruneMode::before_inclusive)
+    }
+
+    fn purpose(&self) -> PrunePurpose {
+        PrunePurpose::StaticFile
+    }
+
+    fn prune(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        input: PruneInput,
+    ) -> Result<SegmentOutput, PrunerError> {
+        delete_pruned_static_files(
+            &self.static_file_provider,
+            provider,
+            StaticFileSegment::AccountChangeSets,
+            PruneSegment::AccountHistory,
+        )?;
+
+        let range = match input.get_next_block_range() {
+            Some(range) => range,
+            None => {
+                trace!(target: "pruner", "No account changesets to prune");
+                return Ok(SegmentOutput::done())
+            }
+        };
+        let range_end = *range.end();
+
+        let mut limiter = input.limiter;
+
+        let mut last_pruned_block = None;
+        let (pruned, done) = provider.prune_table_with_range::<tables::AccountChangeSets>(
+            range,
+            &mut limiter,
+            |_| false,
+            |(block_number, _)| last_pruned_block = Some(block_number),
+        )?;
+        trace!(target: "pruner", %pruned, %done, "Pruned account changesets");
+
+        let last_pruned_block = last_pruned_block
+            // If there's more account changesets to prune, set the checkpoint block number to
+            // previous, so we could finish pruning its account changesets on the next run.
+            .map(|block_number| if done { block_number } else { block_number.saturating_sub(1) })
+            .unwrap_or(range_end);
+
+        let progress = PruneProgress::new(done, &limiter);
+
+        Ok(SegmentOutput {
+            progress,
+            pruned,
+            checkpoint: Some(SegmentOutputCheckpoint {
+                block_number: Some(last_pruned_block),
+                tx_number: None,
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segments::{PruneInput, Segment};
+    use alloy_primitives::Address;
+    use reth_db_api::models::{AccountBeforeTx, StoredAccountChangeSet};
+    use reth_provider::{PruneCheckpointWriter, StaticFileProviderFactory, StaticFileWriter};
+    use reth_prune_types::{PruneCheckpoint, PruneLimiter, PruneMode, PruneSegment};
+    use reth_stages::test_utils::TestStageDB;
+    use reth_static_file_types::{StaticFileSegment, BLOCKS_PER_STATIC_FILE};
+
+    #[test]
+    fn delete_static_files_pruned_by_history() {
+        let db = TestStageDB::default();
+        let static_file_provider = db.factory.static_file_provider();
+
+        // Two static files, with one account changed in every block
+        let highest_block = BLOCKS_PER_STATIC_FILE + 10;
+        {
+            let mut writer =
+                static_file_provider.latest_writer(StaticFileSegment::AccountChangeSets).unwrap();
+            for block_number in 0..=highest_block {
+                let changeset = StoredAccountChangeSet {
+                    changes: vec![AccountBeforeTx {
+                        address: Address::with_last_byte(1),
+                        info: None,
+                    }],
+                };
+                writer.append_account_changeset(block_number, &changeset).unwrap();
+            }
+            writer.commit().unwrap();
+        }
+
+        let prune = |history_checkpoint: u64| {
+            let provider = db.factory.provider_rw().unwrap();
+            provider
+                .save_prune_checkpoint(
+                    PruneSegment::AccountHistory,
+                    PruneCheckpoint {
+                        block_number: Some(history_checkpoint),
+                        tx_number: None,
+                        prune_mode: PruneMode::Before(history_checkpoint + 1),
+                    },
+                )
+                .unwrap();
+            let segment = super::AccountChangeSets::new(static_file_provider.clone());
+            let input = PruneInput {
+                previous_checkpoint: None,
+                to_block: highest_block,
+                limiter: PruneLimiter::default(),
+            };
+            segment.prune(&provider, input).unwrap();
+            provider.commit().unwrap();
+        };
+
+        // The history indices of the first static file weren't fully pruned yet
+        prune(BLOCKS_PER_STATIC_FILE - 2);
+        assert_eq!(
+            static_file_provider.get_lowest_static_file_block(StaticFileSegment::AccountChangeSets),
+            Some(0)
+        );
+        assert_eq!(
+            static_file_provider.account_changesets_range(..=highest_block).unwrap().len(),
+            highest_block as usize + 1
+        );
+
+        // The first static file is deleted, the highest one is kept
+        prune(highest_block);
+        assert_eq!(
+            static_file_provider.get_lowest_static_file_block(StaticFileSegment::AccountChangeSets),
+            Some(BLOCKS_PER_STATIC_FILE)
+        );
+        assert_eq!(
+            static_file_provider
+                .get_highest_static_file_block(StaticFileSegment::AccountChangeSets),
+            Some(highest_block)
+        );
+        assert_eq!(
+            static_file_provider
+                .account_changesets_range(..=highest_block)
+                .unwrap()
+                .into_iter()
+                .map(|(block_number, _)| block_number)
+                .collect::<Vec<_>>(),
+            (BLOCKS_PER_STATIC_FILE..=highest_block).collect::<Vec<_>>()
+        );
+
+        // Deleted static files stay deleted after the index is read from disk again
+        static_file_provider.initialize_index().unwrap();
+        assert_eq!(
+            static_file_provider.get_lowest_static_file_block(StaticFileSegment::AccountChangeSets),
+            Some(BLOCKS_PER_STATIC_FILE)
+        );
+    }
+}
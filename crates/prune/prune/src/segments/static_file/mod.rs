@@ -1,7 +1,44 @@
+mod account_changesets;
 mod headers;
 mod receipts;
+mod storage_changesets;
 mod transactions;
 
+pub use account_changesets::AccountChangeSets;
 pub use headers::Headers;
 pub use receipts::Receipts;
+pub use storage_changesets::StorageChangeSets;
 pub use transactions::Transactions;
+
+use crate::PrunerError;
+use reth_db_api::database::Database;
+use reth_provider::{providers::StaticFileProvider, DatabaseProviderRW, PruneCheckpointReader};
+use reth_prune_types::PruneSegment;
+use reth_static_file_types::StaticFileSegment;
+use tracing::trace;
+
+/// Deletes the static files of a changeset segment with blocks that were already pruned by the
+/// user history segment, which prunes the history indices of these changesets.
+///
+/// Changesets are only moved to static files while history pruning is disabled, so it's only the
+/// case if it was enabled later.
+pub(crate) fn delete_pruned_static_files<DB: Database>(
+    static_file_provider: &StaticFileProvider,
+    provider: &DatabaseProviderRW<DB>,
+    segment: StaticFileSegment,
+    history_segment: PruneSegment,
+) -> Result<(), PrunerError> {
+    let Some(pruned_block) = provider
+        .get_prune_checkpoint(history_segment)?
+        .and_then(|checkpoint| checkpoint.block_number)
+    else {
+        return Ok(())
+    };
+
+    let deleted = static_file_provider.delete_jars_below(segment, pruned_block + 1)?;
+    if !deleted.is_empty() {
+        trace!(target: "pruner", ?segment, ?deleted, "Deleted pruned static files");
+    }
+
+    Ok(())
+}
@@ -1,5 +1,8 @@
 use crate::{
-    segments::{user::history::prune_history_indices, PruneInput, Segment},
+    segments::{
+        user::history::{prune_history_indices, split_static_file_range},
+        PruneInput, Segment,
+    },
     PrunerError,
 };
 use itertools::Itertools;
@@ -10,6 +13,7 @@ use reth_prune_types::{
     PruneInterruptReason, PruneMode, PruneProgress, PrunePurpose, PruneSegment, SegmentOutput,
     SegmentOutputCheckpoint,
 };
+use reth_static_file_types::StaticFileSegment;
 use rustc_hash::FxHashMap;
 use tracing::{instrument, trace};
 
@@ -80,22 +84,61 @@ impl<DB: Database> Segment<DB> for AccountHistory {
         // size should be up to 0.5MB + some hashmap overhead. `blocks_since_last_run` is
         // additionally limited by the `max_reorg_depth`, so no OOM is expected here.
         let mut highest_deleted_accounts = FxHashMap::default();
-        let (pruned_changesets, done) = provider
-            .prune_table_with_range::<tables::AccountChangeSets>(
-                range,
-                &mut limiter,
-                |_| false,
-                |(block_number, account)| {
-                    highest_deleted_accounts.insert(account.address, block_number);
-                    last_changeset_pruned_block = Some(block_number);
-                },
-            )?;
+
+        // Changesets that were moved to static files before the history pruning was enabled are
+        // read from there to prune the history indices of their accounts. The static files are
+        // deleted by the static file changeset segment once this checkpoint is past them.
+        let (static_file_range, database_range) = split_static_file_range(
+            provider
+                .static_file_provider()
+                .get_highest_static_file_block(StaticFileSegment::AccountChangeSets),
+            range,
+        );
+        let mut done = true;
+        for block_number in static_file_range.into_iter().flatten() {
+            if limiter.is_limit_reached() {
+                done = false;
+                break
+            }
+
+            let changesets = provider
+                .static_file_provider()
+                .account_changesets_range(block_number..=block_number)?;
+            limiter.increment_deleted_entries_count_by(changesets.len());
+            for (_, account) in changesets {
+                highest_deleted_accounts.insert(account.address, block_number);
+            }
+            last_changeset_pruned_block = Some(block_number);
+        }
+
+        let mut pruned_changesets = 0;
+        let interrupted_within_block = if done && !database_range.is_empty() {
+            (pruned_changesets, done) = provider
+                .prune_table_with_range::<tables::AccountChangeSets>(
+                    database_range,
+                    &mut limiter,
+                    |_| false,
+                    |(block_number, account)| {
+                        highest_deleted_accounts.insert(account.address, block_number);
+                        last_changeset_pruned_block = Some(block_number);
+                    },
+                )?;
+            !done
+        } else {
+            false
+        };
         trace!(target: "pruner", pruned = %pruned_changesets, %done, "Pruned account history (changesets)");
 
         let last_changeset_pruned_block = last_changeset_pruned_block
             // If there's more account changesets to prune, set the checkpoint block number to
             // previous, so we could finish pruning its account changesets on the next run.
-            .map(|block_number| if done { block_number } else { block_number.saturating_sub(1) })
+            .map(|block_number| {
+                if interrupted_within_block {
+                    block_number.saturating_sub(1)
+                } else {
+                    block_number
+                }
+            })
             .unwrap_or(range_end);
 
         // Sort highest deleted block numbers by account address and turn them into sharded keys.
@@ -0,0 +1,195 @@
+use crate::{
+    segments::{user::history::prune_history_indices, PruneInput, Segment},
+    PrunerError,
+};
+use reth_db::tables;
+use reth_db_api::{database::Database, models::ShardedKey, transaction::DbTx};
+use reth_provider::{BlockReader, DatabaseProviderRW};
+use reth_prune_types::{
+    PruneInterruptReason, PruneMode, PruneProgress, PrunePurpose, PruneSegment, SegmentOutput,
+    SegmentOutputCheckpoint,
+};
+use std::collections::BTreeMap;
+use tracing::{instrument, trace};
+
+/// Prunes the [`tables::AddressAppearances`] index.
+///
+/// The addresses that appeared in a pruned block are looked up in
+/// [`tables::TransactionAddressAppearances`], whose entries for the pruned blocks are deleted as
+/// well.
+#[derive(Debug)]
+pub struct AddressAppearances {
+    mode: PruneMode,
+}
+
+impl AddressAppearances {
+    pub const fn new(mode: PruneMode) -> Self {
+        Self { mode }
+    }
+}
+
+impl<DB: Database> Segment<DB> for AddressAppearances {
+    fn segment(&self) -> PruneSegment {
+        PruneSegment::AddressAppearances
+    }
+
+    fn mode(&self) -> Option<PruneMode> {
+        Some(self.mode)
+    }
+
+    fn purpose(&self) -> PrunePurpose {
+        PrunePurpose::User
+    }
+
+    #[instrument(level = "trace", target = "pruner", skip(self, provider), ret)]
+    fn prune(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        input: PruneInput,
+    ) -> Result<SegmentOutput, PrunerError> {
+        let range = match input.get_next_block_range() {
+            Some(range) => range,
+            None => {
+                trace!(target: "pruner", "No address appearances to prune");
+                return Ok(SegmentOutput::done())
+            }
+        };
+        let range_end = *range.end();
+
+        // The index is only built if the stage is enabled, don't collect the appearances of the
+        // blocks otherwise.
+        if provider.tx_ref().entries::<tables::AddressAppearances>()? == 0 {
+            return Ok(SegmentOutput {
+                progress: PruneProgress::Finished,
+                pruned: 0,
+                checkpoint: Some(SegmentOutputCheckpoint {
+                    block_number: Some(range_end),
+                    tx_number: None,
+                }),
+            })
+        }
+
+        let mut limiter = input.limiter;
+        if limiter.is_limit_reached() {
+            return Ok(SegmentOutput::not_done(
+                PruneInterruptReason::new(&limiter),
+                input.previous_checkpoint.map(SegmentOutputCheckpoint::from_prune_checkpoint),
+            ))
+        }
+
+        // Addresses that appeared in the pruned blocks with the highest block number they
+        // appeared in. Every appearance counts as one deleted entry for the limiter.
+        let mut highest_appearances = BTreeMap::new();
+        let mut last_pruned_block = None;
+        for block_number in range {
+            if limiter.is_limit_reached() {
+                break
+            }
+
+            let appearances = provider.block_address_appearances(block_number)?;
+            limiter.increment_deleted_entries_count_by(appearances.len());
+            for address in appearances {
+                highest_appearances.insert(address, block_number);
+            }
+            last_pruned_block = Some(block_number);
+        }
+        let done = last_pruned_block == Some(range_end);
+        let last_pruned_block = last_pruned_block.unwrap_or(range_end);
+
+        if let Some(body) = provider.block_body_indices(last_pruned_block)? {
+            provider.remove::<tables::TransactionAddressAppearances>(..body.next_tx_num())?;
+        }
+
+        let highest_sharded_keys = highest_appearances
+            .into_iter()
+            .map(|(address, block_number)| ShardedKey::new(address, block_number));
+        let outcomes = prune_history_indices::<DB, tables::AddressAppearances, _>(
+            provider,
+            highest_sharded_keys,
+            |a, b| a.key == b.key,
+        )?;
+        trace!(target: "pruner", ?outcomes, %done, "Pruned address appearances");
+
+        let progress = PruneProgress::new(done, &limiter);
+
+        Ok(SegmentOutput {
+            progress,
+            pruned: outcomes.deleted,
+            checkpoint: Some(SegmentOutputCheckpoint {
+                block_number: Some(last_pruned_block),
+                tx_number: None,
+            }),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segments::{AddressAppearances, PruneInput, Segment};
+    use alloy_primitives::{Address, BlockNumber};
+    use reth_db::{tables, BlockNumberList};
+    use reth_db_api::{
+        models::{ShardedKey, StoredBlockBodyIndices},
+        transaction::DbTxMut,
+    };
+    use reth_prune_types::{PruneLimiter, PruneMode};
+    use reth_stages::test_utils::TestStageDB;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn prune() {
+        let db = TestStageDB::default();
+        let address = Address::with_last_byte(1);
+
+        db.commit(|tx| {
+            for block in 0..=10 {
+                tx.put::<tables::BlockBodyIndices>(
+                    block,
+                    StoredBlockBodyIndices { first_tx_num: block, tx_count: 1 },
+                )?;
+                tx.put::<tables::TransactionAddressAppearances>(block, address)?;
+            }
+            tx.put::<tables::AddressAppearances>(
+                ShardedKey::last(address),
+                BlockNumberList::new_pre_sorted((0..=10).collect::<Vec<_>>()),
+            )?;
+            Ok(())
+        })
+        .unwrap();
+
+        let test_prune = |to_block: BlockNumber, limit: usize, expected: Vec<u64>, done: bool| {
+            let prune_mode = PruneMode::Before(to_block + 1);
+            let segment = AddressAppearances::new(prune_mode);
+            let input = PruneInput {
+                previous_checkpoint: None,
+                to_block,
+                limiter: PruneLimiter::default().set_deleted_entries_limit(limit),
+            };
+
+            let provider = db.factory.provider_rw().unwrap();
+            let result = segment.prune(&provider, input).unwrap();
+            assert_eq!(result.progress.is_finished(), done);
+            provider.commit().expect("commit");
+
+            let table = db
+                .table::<tables::AddressAppearances>()
+                .unwrap()
+                .into_iter()
+                .map(|(key, list)| (key, list.iter().collect::<Vec<_>>()))
+                .collect::<BTreeMap<_, _>>();
+            assert_eq!(table, BTreeMap::from([(ShardedKey::last(address), expected.clone())]));
+
+            let transaction_appearances = db
+                .table::<tables::TransactionAddressAppearances>()
+                .unwrap()
+                .into_iter()
+                .map(|(tx_number, _)| tx_number)
+                .collect::<Vec<_>>();
+            assert_eq!(transaction_appearances, expected);
+        };
+
+        // The limit stops the pruning after the third block.
+        test_prune(5, 3, (3..=10).collect(), false);
+        test_prune(5, 10, (6..=10).collect(), true);
+    }
+}
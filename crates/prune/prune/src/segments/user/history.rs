@@ -9,6 +9,7 @@ use reth_db_api::{
     DatabaseError,
 };
 use reth_provider::DatabaseProviderRW;
+use std::ops::RangeInclusive;
 
 enum PruneShardOutcome {
     Deleted,
@@ -23,6 +24,21 @@ pub(crate) struct PrunedIndices {
     pub(crate) unchanged: usize,
 }
 
+/// Splits the block range into the part with changesets in static files, up to the highest
+/// static file block, and the part with changesets in the database.
+pub(crate) fn split_static_file_range(
+    highest_static_file_block: Option<BlockNumber>,
+    range: RangeInclusive<BlockNumber>,
+) -> (Option<RangeInclusive<BlockNumber>>, RangeInclusive<BlockNumber>) {
+    let (start, end) = range.into_inner();
+    match highest_static_file_block {
+        Some(highest) if highest >= start => {
+            (Some(start..=highest.min(end)), highest.saturating_add(1).max(start)..=end)
+        }
+        _ => (None, start..=end),
+    }
+}
+
 /// Prune history indices according to the provided list of highest sharded keys.
 ///
 /// Returns total number of deleted, updated and unchanged entities.
@@ -1,5 +1,8 @@
 use crate::{
-    segments::{user::history::prune_history_indices, PruneInput, Segment, SegmentOutput},
+    segments::{
+        user::history::{prune_history_indices, split_static_file_range},
+        PruneInput, Segment, SegmentOutput,
+    },
     PrunerError,
 };
 use itertools::Itertools;
@@ -13,6 +16,7 @@ use reth_prune_types::{
     PruneInterruptReason, PruneMode, PruneProgress, PrunePurpose, PruneSegment,
     SegmentOutputCheckpoint,
 };
+use reth_static_file_types::StaticFileSegment;
 use rustc_hash::FxHashMap;
 use tracing::{instrument, trace};
 
@@ -83,22 +87,61 @@ impl<DB: Database> Segment<DB> for StorageHistory {
         // size should be up to 0.5MB + some hashmap overhead. `blocks_since_last_run` is
         // additionally limited by the `max_reorg_depth`, so no OOM is expected here.
         let mut highest_deleted_storages = FxHashMap::default();
-        let (pruned_changesets, done) = provider
-            .prune_table_with_range::<tables::StorageChangeSets>(
-                BlockNumberAddress::range(range),
-                &mut limiter,
-                |_| false,
-                |(BlockNumberAddress((block_number, address)), entry)| {
-                    highest_deleted_storages.insert((address, entry.key), block_number);
-                    last_changeset_pruned_block = Some(block_number);
-                },
-            )?;
+
+        // Changesets that were moved to static files before the history pruning was enabled are
+        // read from there to prune the history indices of their storage slots. The static files are
+        // deleted by the static file changeset segment once this checkpoint is past them.
+        let (static_file_range, database_range) = split_static_file_range(
+            provider
+                .static_file_provider()
+                .get_highest_static_file_block(StaticFileSegment::StorageChangeSets),
+            range,
+        );
+        let mut done = true;
+        for block_number in static_file_range.into_iter().flatten() {
+            if limiter.is_limit_reached() {
+                done = false;
+                break
+            }
+
+            let changesets = provider
+                .static_file_provider()
+                .storage_changesets_range(block_number..=block_number)?;
+            limiter.increment_deleted_entries_count_by(changesets.len());
+            for (BlockNumberAddress((_, address)), entry) in changesets {
+                highest_deleted_storages.insert((address, entry.key), block_number);
+            }
+            last_changeset_pruned_block = Some(block_number);
+        }
+
+        let mut pruned_changesets = 0;
+        let interrupted_within_block = if done && !database_range.is_empty() {
+            (pruned_changesets, done) = provider
+                .prune_table_with_range::<tables::StorageChangeSets>(
+                    BlockNumberAddress::range(database_range),
+                    &mut limiter,
+                    |_| false,
+                    |(BlockNumberAddress((block_number, address)), entry)| {
+                        highest_deleted_storages.insert((address, entry.key), block_number);
+                        last_changeset_pruned_block = Some(block_number);
+                    },
+                )?;
+            !done
+        } else {
+            false
+        };
         trace!(target: "pruner", deleted = %pruned_changesets, %done, "Pruned storage history (changesets)");
 
         let last_changeset_pruned_block = last_changeset_pruned_block
             // If there's more storage changesets to prune, set the checkpoint block number to
             // previous, so we could finish pruning its storage changesets on the next run.
-            .map(|block_number| if done { block_number } else { block_number.saturating_sub(1) })
+            .map(|block_number| {
+                if interrupted_within_block {
+                    block_number.saturating_sub(1)
+                } else {
+                    block_number
+                }
+            })
             .unwrap_or(range_end);
 
         // Sort highest deleted block numbers by account address and storage key and turn them into
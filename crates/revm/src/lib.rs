@@ -20,6 +20,9 @@ pub mod batch;
 /// State changes that are not related to transactions.
 pub mod state_change;
 
+#[cfg(feature = "std")]
+pub mod overlay;
+
 /// Common test helpers
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils;
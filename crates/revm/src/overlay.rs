@@ -0,0 +1,275 @@
+//! Irregular state changes for development chains.
+//!
+//! A [`StateOverlay`] collects account modifications, for example from `anvil_setBalance`, and
+//! assigns them to the block that is built next. Executors that share the overlay apply the
+//! modifications before the transactions of that block, so re-executing the block yields the same
+//! state.
+//!
+//! The overlay also records the impersonated senders of a block, whose transactions are executed
+//! even if the sender has code.
+
+use crate::precompile::HashMap;
+use parking_lot::RwLock;
+use reth_execution_errors::{BlockExecutionError, BlockValidationError};
+use reth_primitives::{keccak256, Address, BlockNumber, Bytes, U256};
+use reth_storage_errors::provider::ProviderError;
+use revm::{
+    primitives::{Account, Bytecode, EvmStorageSlot},
+    Database, DatabaseCommit,
+};
+use std::{
+    collections::{BTreeMap, HashSet},
+    sync::Arc,
+};
+
+/// Modifications of a single account.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountOverride {
+    /// The new balance of the account.
+    pub balance: Option<U256>,
+    /// The new nonce of the account.
+    pub nonce: Option<u64>,
+    /// The new code of the account.
+    pub code: Option<Bytes>,
+    /// Storage slots that are overwritten.
+    pub storage: HashMap<U256, U256>,
+}
+
+impl AccountOverride {
+    /// Applies the newer modifications of `other` on top of this one.
+    fn extend(&mut self, other: Self) {
+        let Self { balance, nonce, code, storage } = other;
+        if balance.is_some() {
+            self.balance = balance;
+        }
+        if nonce.is_some() {
+            self.nonce = nonce;
+        }
+        if code.is_some() {
+            self.code = code;
+        }
+        self.storage.extend(storage);
+    }
+}
+
+/// Account modifications that are applied before the transactions of a block are executed.
+///
+/// Modifications are first recorded as pending and assigned to a block with
+/// [`StateOverlay::seal`]. The overlay is shared, cloning it returns a handle to the same changes.
+#[derive(Debug, Clone, Default)]
+pub struct StateOverlay {
+    inner: Arc<RwLock<StateOverlayInner>>,
+}
+
+#[derive(Debug, Default)]
+struct StateOverlayInner {
+    /// Modifications that are not yet assigned to a block.
+    pending: HashMap<Address, AccountOverride>,
+    /// Modifications by the block they are applied in.
+    blocks: BTreeMap<BlockNumber, HashMap<Address, AccountOverride>>,
+    /// Impersonated senders that are not yet assigned to a block.
+    pending_impersonated: HashSet<Address>,
+    /// Impersonated senders by the block they send transactions in.
+    impersonated: BTreeMap<BlockNumber, HashSet<Address>>,
+}
+
+impl StateOverlay {
+    /// Sets the balance of the account.
+    pub fn set_balance(&self, address: Address, balance: U256) {
+        self.inner.write().pending.entry(address).or_default().balance = Some(balance);
+    }
+
+    /// Sets the nonce of the account.
+    pub fn set_nonce(&self, address: Address, nonce: u64) {
+        self.inner.write().pending.entry(address).or_default().nonce = Some(nonce);
+    }
+
+    /// Sets the code of the account.
+    pub fn set_code(&self, address: Address, code: Bytes) {
+        self.inner.write().pending.entry(address).or_default().code = Some(code);
+    }
+
+    /// Sets a single storage slot of the account.
+    pub fn set_storage_at(&self, address: Address, slot: U256, value: U256) {
+        self.inner.write().pending.entry(address).or_default().storage.insert(slot, value);
+    }
+
+    /// Records an impersonated sender, whose transactions are executed even if it has code.
+    pub fn impersonate(&self, sender: Address) {
+        self.inner.write().pending_impersonated.insert(sender);
+    }
+
+    /// Returns `true` if there are modifications that are not yet assigned to a block.
+    pub fn has_pending(&self) -> bool {
+        !self.inner.read().pending.is_empty()
+    }
+
+    /// Assigns all pending modifications and impersonated senders to the given block.
+    pub fn seal(&self, block_number: BlockNumber) {
+        let mut inner = self.inner.write();
+        let pending = std::mem::take(&mut inner.pending);
+        if !pending.is_empty() {
+            inner.blocks.insert(block_number, pending);
+        }
+        let impersonated = std::mem::take(&mut inner.pending_impersonated);
+        if !impersonated.is_empty() {
+            inner.impersonated.insert(block_number, impersonated);
+        }
+    }
+
+    /// Moves the modifications of the given block back to the pending modifications.
+    ///
+    /// This should be used if the block the modifications were sealed for was discarded.
+    /// Modifications that were recorded in the meantime take precedence.
+    pub fn unseal(&self, block_number: BlockNumber) {
+        let mut inner = self.inner.write();
+        if let Some(mut changes) = inner.blocks.remove(&block_number) {
+            for (address, account) in std::mem::take(&mut inner.pending) {
+                changes.entry(address).or_default().extend(account);
+            }
+            inner.pending = changes;
+        }
+        if let Some(impersonated) = inner.impersonated.remove(&block_number) {
+            inner.pending_impersonated.extend(impersonated);
+        }
+    }
+
+    /// Returns the modifications that are applied in the given block, if any.
+    pub fn changes_for_block(
+        &self,
+        block_number: BlockNumber,
+    ) -> Option<HashMap<Address, AccountOverride>> {
+        self.inner.read().blocks.get(&block_number).cloned()
+    }
+
+    /// Returns the impersonated senders of the given block, if any.
+    pub fn impersonated_senders(&self, block_number: BlockNumber) -> Option<HashSet<Address>> {
+        self.inner.read().impersonated.get(&block_number).cloned()
+    }
+}
+
+/// Applies the account modifications of a [`StateOverlay`] to the given database.
+///
+/// Fields that are not modified keep their current value. If the code is modified, the code hash
+/// is updated accordingly.
+pub fn apply_state_overlay<DB>(
+    db: &mut DB,
+    changes: &HashMap<Address, AccountOverride>,
+) -> Result<(), BlockExecutionError>
+where
+    DB: Database<Error: Into<ProviderError>> + DatabaseCommit,
+{
+    let mut state = HashMap::with_capacity(changes.len());
+    for (address, changes) in changes {
+        let info = db
+            .basic(*address)
+            .map_err(|err| BlockValidationError::StateOverlayAccountLoadingFailed(err.into()))?
+            .unwrap_or_default();
+        let mut account: Account = info.into();
+
+        if let Some(balance) = changes.balance {
+            account.info.balance = balance;
+        }
+        if let Some(nonce) = changes.nonce {
+            account.info.nonce = nonce;
+        }
+        if let Some(code) = &changes.code {
+            account.info.code_hash = keccak256(code);
+            account.info.code = Some(Bytecode::new_raw(code.clone()));
+        }
+        for (slot, value) in &changes.storage {
+            let current = db.storage(*address, *slot).map_err(|err| {
+                BlockValidationError::StateOverlayAccountLoadingFailed(err.into())
+            })?;
+            account.storage.insert(*slot, EvmStorageSlot::new_changed(current, *value));
+        }
+
+        account.mark_touch();
+        state.insert(*address, account);
+    }
+
+    db.commit(state);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{database::StateProviderDatabase, test_utils::StateProviderTest};
+    use reth_primitives::{Account as PrimitiveAccount, B256, KECCAK_EMPTY};
+    use revm::db::CacheDB;
+
+    #[test]
+    fn seal_and_unseal() {
+        let overlay = StateOverlay::default();
+        let address = Address::random();
+
+        overlay.set_balance(address, U256::from(1));
+        assert!(overlay.has_pending());
+
+        overlay.seal(1);
+        assert!(!overlay.has_pending());
+        assert_eq!(overlay.changes_for_block(1).unwrap()[&address].balance, Some(U256::from(1)));
+
+        // newer modifications take precedence when the block is discarded
+        overlay.set_balance(address, U256::from(2));
+        overlay.set_nonce(address, 3);
+        overlay.unseal(1);
+        assert!(overlay.changes_for_block(1).is_none());
+
+        overlay.seal(1);
+        let changes = overlay.changes_for_block(1).unwrap();
+        assert_eq!(changes[&address].balance, Some(U256::from(2)));
+        assert_eq!(changes[&address].nonce, Some(3));
+    }
+
+    #[test]
+    fn seal_and_unseal_impersonated() {
+        let overlay = StateOverlay::default();
+        let sender = Address::random();
+
+        overlay.impersonate(sender);
+        overlay.seal(1);
+        assert!(overlay.impersonated_senders(1).unwrap().contains(&sender));
+        assert!(overlay.impersonated_senders(2).is_none());
+
+        overlay.unseal(1);
+        assert!(overlay.impersonated_senders(1).is_none());
+        overlay.seal(2);
+        assert!(overlay.impersonated_senders(2).unwrap().contains(&sender));
+    }
+
+    #[test]
+    fn apply_overlay() {
+        let address = Address::random();
+        let mut state = StateProviderTest::default();
+        state.insert_account(
+            address,
+            PrimitiveAccount { nonce: 1, balance: U256::from(10), bytecode_hash: None },
+            None,
+            HashMap::from([(B256::with_last_byte(1), U256::from(5))]),
+        );
+        let mut db = CacheDB::new(StateProviderDatabase::new(state));
+
+        let code = Bytes::from_static(&[0x60, 0x00]);
+        let changes = HashMap::from([(
+            address,
+            AccountOverride {
+                balance: Some(U256::from(100)),
+                code: Some(code.clone()),
+                storage: HashMap::from([(U256::from(2), U256::from(7))]),
+                ..Default::default()
+            },
+        )]);
+        apply_state_overlay(&mut db, &changes).unwrap();
+
+        let info = db.basic(address).unwrap().unwrap();
+        assert_eq!(info.balance, U256::from(100));
+        assert_eq!(info.nonce, 1);
+        assert_ne!(info.code_hash, KECCAK_EMPTY);
+        assert_eq!(info.code_hash, keccak256(&code));
+        assert_eq!(db.storage(address, U256::from(1)).unwrap(), U256::from(5));
+        assert_eq!(db.storage(address, U256::from(2)).unwrap(), U256::from(7));
+    }
+}
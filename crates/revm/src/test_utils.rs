@@ -3,10 +3,11 @@ use reth_primitives::{
     keccak256, Account, Address, BlockNumber, Bytecode, Bytes, StorageKey, B256, U256,
 };
 use reth_storage_api::{
-    AccountReader, BlockHashReader, StateProofProvider, StateProvider, StateRootProvider,
+    AccountReader, BlockHashReader, HashedStateRangeProvider, StateProofProvider, StateProvider,
+    StateRootProvider,
 };
 use reth_storage_errors::provider::ProviderResult;
-use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState};
+use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState, HashedRange};
 
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
@@ -91,6 +92,27 @@ impl StateProofProvider for StateProviderTest {
     }
 }
 
+impl HashedStateRangeProvider for StateProviderTest {
+    fn hashed_account_range(
+        &self,
+        _hashed_state: &HashedPostState,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<HashedRange<Account>> {
+        unimplemented!("hashed state iteration is not supported")
+    }
+
+    fn hashed_storage_range(
+        &self,
+        _hashed_state: &HashedPostState,
+        _hashed_address: B256,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<HashedRange<U256>> {
+        unimplemented!("hashed state iteration is not supported")
+    }
+}
+
 impl StateProvider for StateProviderTest {
     fn storage(
         &self,
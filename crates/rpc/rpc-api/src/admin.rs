@@ -1,4 +1,5 @@
 use jsonrpsee::{core::RpcResult, proc_macros::rpc};
+use reth_net_banlist::BanEntry;
 use reth_network_peers::{AnyNode, NodeRecord};
 use reth_rpc_types::admin::{NodeInfo, PeerInfo};
 
@@ -27,6 +28,23 @@ pub trait AdminApi {
     #[method(name = "removeTrustedPeer")]
     fn remove_trusted_peer(&self, record: AnyNode) -> RpcResult<bool>;
 
+    /// Bans a peer or a range of IP addresses, indefinitely or for the given number of seconds.
+    ///
+    /// The target is an enode, a peer id, an IP address or a network in CIDR notation. Connected
+    /// peers that match the target are disconnected.
+    #[method(name = "banPeer")]
+    fn ban_peer(&self, target: String, duration: Option<u64>) -> RpcResult<bool>;
+
+    /// Lifts the ban of a peer or a range of IP addresses.
+    ///
+    /// Returns true if the target was banned.
+    #[method(name = "unbanPeer")]
+    async fn unban_peer(&self, target: String) -> RpcResult<bool>;
+
+    /// Returns all active bans.
+    #[method(name = "listBans")]
+    async fn list_bans(&self) -> RpcResult<Vec<BanEntry>>;
+
     /// The peers administrative property can be queried for all the information known about the
     /// connected remote nodes at the networking granularity. These include general information
     /// about the nodes themselves as participants of the devp2p P2P overlay protocol, as well as
@@ -37,8 +37,10 @@ pub use servers::*;
 pub mod servers {
     pub use crate::{
         admin::AdminApiServer,
+        anvil::AnvilApiServer,
         debug::DebugApiServer,
         engine::{EngineApiServer, EngineEthApiServer},
+        hardhat::HardhatApiServer,
         mev::MevApiServer,
         net::NetApiServer,
         otterscan::OtterscanServer,
@@ -16,7 +16,7 @@
 //! Configure only an http server with a selection of [`RethRpcModule`]s
 //!
 //! ```
-//! use reth_evm::ConfigureEvm;
+//! use reth_evm::{execute::BlockExecutorProvider, ConfigureEvm};
 //! use reth_network_api::{NetworkInfo, Peers};
 //! use reth_provider::{AccountReader, CanonStateSubscriptions, ChangeSetReader, FullRpcProvider};
 //! use reth_rpc::EthApi;
@@ -26,18 +26,22 @@
 //! use reth_tasks::TokioTaskExecutor;
 //! use reth_transaction_pool::TransactionPool;
 //!
-//! pub async fn launch<Provider, Pool, Network, Events, EvmConfig>(
+//! pub async fn launch<Provider, Pool, Network, Events, EvmConfig, BlockExecutor, Consensus>(
 //!     provider: Provider,
 //!     pool: Pool,
 //!     network: Network,
 //!     events: Events,
 //!     evm_config: EvmConfig,
+//!     block_executor: BlockExecutor,
+//!     consensus: Consensus,
 //! ) where
 //!     Provider: FullRpcProvider + AccountReader + ChangeSetReader,
 //!     Pool: TransactionPool + 'static,
 //!     Network: NetworkInfo + Peers + Clone + 'static,
 //!     Events: CanonStateSubscriptions + Clone + 'static,
 //!     EvmConfig: ConfigureEvm,
+//!     BlockExecutor: BlockExecutorProvider,
+//!     Consensus: reth_consensus::Consensus + Clone + 'static,
 //! {
 //!     // configure the rpc module per transport
 //!     let transports = TransportRpcModuleConfig::default().with_http(vec![
@@ -53,6 +57,8 @@
 //!         TokioTaskExecutor::default(),
 //!         events,
 //!         evm_config,
+//!         block_executor,
+//!         consensus,
 //!     )
 //!     .build(transports, Box::new(EthApi::with_spawner));
 //!     let handle = RpcServerConfig::default()
@@ -67,7 +73,7 @@
 //!
 //! ```
 //! use reth_engine_primitives::EngineTypes;
-//! use reth_evm::ConfigureEvm;
+//! use reth_evm::{execute::BlockExecutorProvider, ConfigureEvm};
 //! use reth_network_api::{NetworkInfo, Peers};
 //! use reth_provider::{AccountReader, CanonStateSubscriptions, ChangeSetReader, FullRpcProvider};
 //! use reth_rpc::EthApi;
@@ -80,13 +86,25 @@
 //! use reth_tasks::TokioTaskExecutor;
 //! use reth_transaction_pool::TransactionPool;
 //! use tokio::try_join;
-//! pub async fn launch<Provider, Pool, Network, Events, EngineApi, EngineT, EvmConfig>(
+//! pub async fn launch<
+//!     Provider,
+//!     Pool,
+//!     Network,
+//!     Events,
+//!     EngineApi,
+//!     EngineT,
+//!     EvmConfig,
+//!     BlockExecutor,
+//!     Consensus,
+//! >(
 //!     provider: Provider,
 //!     pool: Pool,
 //!     network: Network,
 //!     events: Events,
 //!     engine_api: EngineApi,
 //!     evm_config: EvmConfig,
+//!     block_executor: BlockExecutor,
+//!     consensus: Consensus,
 //! ) where
 //!     Provider: FullRpcProvider + AccountReader + ChangeSetReader,
 //!     Pool: TransactionPool + 'static,
@@ -95,6 +113,8 @@
 //!     EngineApi: EngineApiServer<EngineT>,
 //!     EngineT: EngineTypes,
 //!     EvmConfig: ConfigureEvm,
+//!     BlockExecutor: BlockExecutorProvider,
+//!     Consensus: reth_consensus::Consensus + Clone + 'static,
 //! {
 //!     // configure the rpc module per transport
 //!     let transports = TransportRpcModuleConfig::default().with_http(vec![
@@ -110,6 +130,8 @@
 //!         TokioTaskExecutor::default(),
 //!         events,
 //!         evm_config,
+//!         block_executor,
+//!         consensus,
 //!     );
 //!
 //!     // configure the server modules
@@ -151,16 +173,17 @@ use jsonrpsee::{
     },
     Methods, RpcModule,
 };
+use reth_auto_seal_consensus::AutoSealHandle;
 use reth_engine_primitives::EngineTypes;
-use reth_evm::ConfigureEvm;
+use reth_evm::{execute::BlockExecutorProvider, ConfigureEvm};
 use reth_network_api::{noop::NoopNetwork, NetworkInfo, Peers};
 use reth_provider::{
-    AccountReader, BlockReader, CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader,
-    EvmEnvProvider, FullRpcProvider, StateProviderFactory,
+    AccountReader, BadBlockStore, BlockReader, CanonStateSubscriptions, ChainSpecProvider,
+    ChangeSetReader, EvmEnvProvider, FullRpcProvider, StateProviderFactory,
 };
 use reth_rpc::{
-    AdminApi, DebugApi, EngineEthApi, EthBundle, NetApi, OtterscanApi, RPCApi, RethApi, TraceApi,
-    TxPoolApi, Web3Api,
+    AdminApi, AnvilApi, DebugApi, EngineEthApi, EthBundle, MevApi, NetApi, OtterscanApi, RPCApi,
+    RethApi, TraceApi, TxPoolApi, ValidationApi, Web3Api,
 };
 use reth_rpc_api::servers::*;
 use reth_rpc_eth_api::{
@@ -172,7 +195,7 @@ use reth_rpc_eth_api::{
 use reth_rpc_eth_types::{EthConfig, EthStateCache, EthSubscriptionIdProvider};
 use reth_rpc_layer::{AuthLayer, Claims, JwtAuthValidator, JwtSecret};
 use reth_tasks::{pool::BlockingTaskGuard, TaskSpawner, TokioTaskExecutor};
-use reth_transaction_pool::{noop::NoopTransactionPool, TransactionPool};
+use reth_transaction_pool::{noop::NoopTransactionPool, BundlePool, TransactionPool};
 use serde::{Deserialize, Serialize};
 use tower::Layer;
 use tower_http::cors::CorsLayer;
@@ -213,7 +236,17 @@ mod metrics;
 
 /// Convenience function for starting a server in one step.
 #[allow(clippy::too_many_arguments)]
-pub async fn launch<Provider, Pool, Network, Tasks, Events, EvmConfig, EthApi>(
+pub async fn launch<
+    Provider,
+    Pool,
+    Network,
+    Tasks,
+    Events,
+    EvmConfig,
+    EthApi,
+    BlockExecutor,
+    Consensus,
+>(
     provider: Provider,
     pool: Pool,
     network: Network,
@@ -223,6 +256,8 @@ pub async fn launch<Provider, Pool, Network, Tasks, Events, EvmConfig, EthApi>(
     events: Events,
     evm_config: EvmConfig,
     eth: DynEthApiBuilder<Provider, Pool, EvmConfig, Network, Tasks, Events, EthApi>,
+    block_executor: BlockExecutor,
+    consensus: Consensus,
 ) -> Result<RpcServerHandle, RpcError>
 where
     Provider: FullRpcProvider + AccountReader + ChangeSetReader,
@@ -232,13 +267,24 @@ where
     Events: CanonStateSubscriptions + Clone + 'static,
     EvmConfig: ConfigureEvm,
     EthApi: FullEthApiServer,
+    BlockExecutor: BlockExecutorProvider,
+    Consensus: reth_consensus::Consensus + Clone + 'static,
 {
     let module_config = module_config.into();
     server_config
         .into()
         .start(
-            &RpcModuleBuilder::new(provider, pool, network, executor, events, evm_config)
-                .build(module_config, eth),
+            &RpcModuleBuilder::new(
+                provider,
+                pool,
+                network,
+                executor,
+                events,
+                evm_config,
+                block_executor,
+                consensus,
+            )
+            .build(module_config, eth),
         )
         .await
 }
@@ -247,7 +293,16 @@ where
 ///
 /// This is the main entrypoint and the easiest way to configure an RPC server.
 #[derive(Debug, Clone)]
-pub struct RpcModuleBuilder<Provider, Pool, Network, Tasks, Events, EvmConfig> {
+pub struct RpcModuleBuilder<
+    Provider,
+    Pool,
+    Network,
+    Tasks,
+    Events,
+    EvmConfig,
+    BlockExecutor,
+    Consensus,
+> {
     /// The Provider type to when creating all rpc handlers
     provider: Provider,
     /// The Pool type to when creating all rpc handlers
@@ -260,14 +315,25 @@ pub struct RpcModuleBuilder<Provider, Pool, Network, Tasks, Events, EvmConfig> {
     events: Events,
     /// Defines how the EVM should be configured before execution.
     evm_config: EvmConfig,
+    /// The provider for getting a block executor that executes blocks
+    block_executor: BlockExecutor,
+    /// The consensus implementation.
+    consensus: Consensus,
+    /// The pool that stores the bundles submitted via the `mev_` namespace.
+    bundle_pool: Option<BundlePool>,
+    /// Controls the miner of a dev chain, required by the `anvil_` and `hardhat_` namespaces.
+    auto_seal_handle: Option<AutoSealHandle>,
+    /// The blocks the engine rejected as invalid, served by the `debug_` namespace.
+    bad_block_store: Option<BadBlockStore>,
 }
 
 // === impl RpcBuilder ===
 
-impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
-    RpcModuleBuilder<Provider, Pool, Network, Tasks, Events, EvmConfig>
+impl<Provider, Pool, Network, Tasks, Events, EvmConfig, BlockExecutor, Consensus>
+    RpcModuleBuilder<Provider, Pool, Network, Tasks, Events, EvmConfig, BlockExecutor, Consensus>
 {
     /// Create a new instance of the builder
+    #[allow(clippy::too_many_arguments)]
     pub const fn new(
         provider: Provider,
         pool: Pool,
@@ -275,32 +341,94 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
         executor: Tasks,
         events: Events,
         evm_config: EvmConfig,
+        block_executor: BlockExecutor,
+        consensus: Consensus,
     ) -> Self {
-        Self { provider, pool, network, executor, events, evm_config }
+        Self {
+            provider,
+            pool,
+            network,
+            executor,
+            events,
+            evm_config,
+            block_executor,
+            consensus,
+            bundle_pool: None,
+            auto_seal_handle: None,
+            bad_block_store: None,
+        }
     }
 
     /// Configure the provider instance.
     pub fn with_provider<P>(
         self,
         provider: P,
-    ) -> RpcModuleBuilder<P, Pool, Network, Tasks, Events, EvmConfig>
+    ) -> RpcModuleBuilder<P, Pool, Network, Tasks, Events, EvmConfig, BlockExecutor, Consensus>
     where
         P: BlockReader + StateProviderFactory + EvmEnvProvider + 'static,
     {
-        let Self { pool, network, executor, events, evm_config, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events, evm_config }
+        let Self {
+            pool,
+            network,
+            executor,
+            events,
+            evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+            ..
+        } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            executor,
+            events,
+            evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+        }
     }
 
     /// Configure the transaction pool instance.
     pub fn with_pool<P>(
         self,
         pool: P,
-    ) -> RpcModuleBuilder<Provider, P, Network, Tasks, Events, EvmConfig>
+    ) -> RpcModuleBuilder<Provider, P, Network, Tasks, Events, EvmConfig, BlockExecutor, Consensus>
     where
         P: TransactionPool + 'static,
     {
-        let Self { provider, network, executor, events, evm_config, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events, evm_config }
+        let Self {
+            provider,
+            network,
+            executor,
+            events,
+            evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+            ..
+        } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            executor,
+            events,
+            evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+        }
     }
 
     /// Configure a [`NoopTransactionPool`] instance.
@@ -310,15 +438,41 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
     /// [`EthApi`](reth_rpc::eth::EthApi) which requires a [`TransactionPool`] implementation.
     pub fn with_noop_pool(
         self,
-    ) -> RpcModuleBuilder<Provider, NoopTransactionPool, Network, Tasks, Events, EvmConfig> {
-        let Self { provider, executor, events, network, evm_config, .. } = self;
+    ) -> RpcModuleBuilder<
+        Provider,
+        NoopTransactionPool,
+        Network,
+        Tasks,
+        Events,
+        EvmConfig,
+        BlockExecutor,
+        Consensus,
+    > {
+        let Self {
+            provider,
+            executor,
+            events,
+            network,
+            evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+            ..
+        } = self;
         RpcModuleBuilder {
             provider,
             executor,
             events,
             network,
             evm_config,
+            block_executor,
+            consensus,
             pool: NoopTransactionPool::default(),
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
         }
     }
 
@@ -326,12 +480,36 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
     pub fn with_network<N>(
         self,
         network: N,
-    ) -> RpcModuleBuilder<Provider, Pool, N, Tasks, Events, EvmConfig>
+    ) -> RpcModuleBuilder<Provider, Pool, N, Tasks, Events, EvmConfig, BlockExecutor, Consensus>
     where
         N: NetworkInfo + Peers + 'static,
     {
-        let Self { provider, pool, executor, events, evm_config, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events, evm_config }
+        let Self {
+            provider,
+            pool,
+            executor,
+            events,
+            evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+            ..
+        } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            executor,
+            events,
+            evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+        }
     }
 
     /// Configure a [`NoopNetwork`] instance.
@@ -341,8 +519,29 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
     /// [`EthApi`](reth_rpc::eth::EthApi) which requires a [`NetworkInfo`] implementation.
     pub fn with_noop_network(
         self,
-    ) -> RpcModuleBuilder<Provider, Pool, NoopNetwork, Tasks, Events, EvmConfig> {
-        let Self { provider, pool, executor, events, evm_config, .. } = self;
+    ) -> RpcModuleBuilder<
+        Provider,
+        Pool,
+        NoopNetwork,
+        Tasks,
+        Events,
+        EvmConfig,
+        BlockExecutor,
+        Consensus,
+    > {
+        let Self {
+            provider,
+            pool,
+            executor,
+            events,
+            evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+            ..
+        } = self;
         RpcModuleBuilder {
             provider,
             pool,
@@ -350,6 +549,11 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
             events,
             network: NoopNetwork::default(),
             evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
         }
     }
 
@@ -357,12 +561,36 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
     pub fn with_executor<T>(
         self,
         executor: T,
-    ) -> RpcModuleBuilder<Provider, Pool, Network, T, Events, EvmConfig>
+    ) -> RpcModuleBuilder<Provider, Pool, Network, T, Events, EvmConfig, BlockExecutor, Consensus>
     where
         T: TaskSpawner + 'static,
     {
-        let Self { pool, network, provider, events, evm_config, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events, evm_config }
+        let Self {
+            pool,
+            network,
+            provider,
+            events,
+            evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+            ..
+        } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            executor,
+            events,
+            evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+        }
     }
 
     /// Configure [`TokioTaskExecutor`] as the task executor to use for additional tasks.
@@ -371,8 +599,29 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
     /// [`TokioTaskExecutor`].
     pub fn with_tokio_executor(
         self,
-    ) -> RpcModuleBuilder<Provider, Pool, Network, TokioTaskExecutor, Events, EvmConfig> {
-        let Self { pool, network, provider, events, evm_config, .. } = self;
+    ) -> RpcModuleBuilder<
+        Provider,
+        Pool,
+        Network,
+        TokioTaskExecutor,
+        Events,
+        EvmConfig,
+        BlockExecutor,
+        Consensus,
+    > {
+        let Self {
+            pool,
+            network,
+            provider,
+            events,
+            evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+            ..
+        } = self;
         RpcModuleBuilder {
             provider,
             network,
@@ -380,6 +629,11 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
             events,
             executor: TokioTaskExecutor::default(),
             evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
         }
     }
 
@@ -387,29 +641,173 @@ impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
     pub fn with_events<E>(
         self,
         events: E,
-    ) -> RpcModuleBuilder<Provider, Pool, Network, Tasks, E, EvmConfig>
+    ) -> RpcModuleBuilder<Provider, Pool, Network, Tasks, E, EvmConfig, BlockExecutor, Consensus>
     where
         E: CanonStateSubscriptions + 'static,
     {
-        let Self { provider, pool, executor, network, evm_config, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events, evm_config }
+        let Self {
+            provider,
+            pool,
+            executor,
+            network,
+            evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+            ..
+        } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            executor,
+            events,
+            evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+        }
     }
 
     /// Configure the evm configuration type
     pub fn with_evm_config<E>(
         self,
         evm_config: E,
-    ) -> RpcModuleBuilder<Provider, Pool, Network, Tasks, Events, E>
+    ) -> RpcModuleBuilder<Provider, Pool, Network, Tasks, Events, E, BlockExecutor, Consensus>
     where
         E: ConfigureEvm + 'static,
     {
-        let Self { provider, pool, executor, network, events, .. } = self;
-        RpcModuleBuilder { provider, network, pool, executor, events, evm_config }
+        let Self {
+            provider,
+            pool,
+            executor,
+            network,
+            events,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+            ..
+        } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            executor,
+            events,
+            evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+        }
+    }
+
+    /// Configure the block executor provider
+    pub fn with_block_executor<BE>(
+        self,
+        block_executor: BE,
+    ) -> RpcModuleBuilder<Provider, Pool, Network, Tasks, Events, EvmConfig, BE, Consensus>
+    where
+        BE: BlockExecutorProvider,
+    {
+        let Self {
+            provider,
+            network,
+            pool,
+            executor,
+            events,
+            evm_config,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+            ..
+        } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            executor,
+            events,
+            evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+        }
+    }
+
+    /// Configure the consensus implementation.
+    pub fn with_consensus<C>(
+        self,
+        consensus: C,
+    ) -> RpcModuleBuilder<Provider, Pool, Network, Tasks, Events, EvmConfig, BlockExecutor, C>
+    where
+        C: reth_consensus::Consensus + Clone + 'static,
+    {
+        let Self {
+            provider,
+            network,
+            pool,
+            executor,
+            events,
+            evm_config,
+            block_executor,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+            ..
+        } = self;
+        RpcModuleBuilder {
+            provider,
+            network,
+            pool,
+            executor,
+            events,
+            evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+        }
+    }
+
+    /// Configure the pool that stores the bundles submitted via the `mev_` namespace.
+    ///
+    /// If not set, the `mev_` namespace uses a pool that is not shared with anything else.
+    pub fn with_bundle_pool(mut self, bundle_pool: BundlePool) -> Self {
+        self.bundle_pool = Some(bundle_pool);
+        self
+    }
+
+    /// Configure the handle to the miner of a dev chain.
+    ///
+    /// The `anvil_` and `hardhat_` namespaces are only available if a handle is set.
+    pub fn with_auto_seal_handle(mut self, auto_seal_handle: Option<AutoSealHandle>) -> Self {
+        self.auto_seal_handle = auto_seal_handle;
+        self
+    }
+
+    /// Configure the store of the blocks the engine rejected as invalid.
+    ///
+    /// This is served by `debug_getBadBlocks` and `debug_traceBadBlock`.
+    pub fn with_bad_block_store(mut self, bad_block_store: BadBlockStore) -> Self {
+        self.bad_block_store = Some(bad_block_store);
+        self
     }
 }
 
-impl<Provider, Pool, Network, Tasks, Events, EvmConfig>
-    RpcModuleBuilder<Provider, Pool, Network, Tasks, Events, EvmConfig>
+impl<Provider, Pool, Network, Tasks, Events, EvmConfig, BlockExecutor, Consensus>
+    RpcModuleBuilder<Provider, Pool, Network, Tasks, Events, EvmConfig, BlockExecutor, Consensus>
 where
     Provider: FullRpcProvider + AccountReader + ChangeSetReader,
     Pool: TransactionPool + 'static,
@@ -417,6 +815,8 @@ where
     Tasks: TaskSpawner + Clone + 'static,
     Events: CanonStateSubscriptions + Clone + 'static,
     EvmConfig: ConfigureEvm,
+    BlockExecutor: BlockExecutorProvider,
+    Consensus: reth_consensus::Consensus + Clone + 'static,
 {
     /// Configures all [`RpcModule`]s specific to the given [`TransportRpcModuleConfig`] which can
     /// be used to start the transport server(s).
@@ -433,19 +833,43 @@ where
     ) -> (
         TransportRpcModules,
         AuthRpcModule,
-        RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi>,
+        RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi, BlockExecutor, Consensus>,
     )
     where
         EngineT: EngineTypes,
         EngineApi: EngineApiServer<EngineT>,
         EthApi: FullEthApiServer,
     {
-        let Self { provider, pool, network, executor, events, evm_config } = self;
+        let Self {
+            provider,
+            pool,
+            network,
+            executor,
+            events,
+            evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+        } = self;
 
         let config = module_config.config.clone().unwrap_or_default();
 
         let mut registry = RpcRegistryInner::new(
-            provider, pool, network, executor, events, config, evm_config, eth,
+            provider,
+            pool,
+            network,
+            executor,
+            events,
+            config,
+            evm_config,
+            eth,
+            block_executor,
+            consensus,
+            bundle_pool.unwrap_or_default(),
+            auto_seal_handle,
+            bad_block_store.unwrap_or_default(),
         );
 
         let modules = registry.create_transport_rpc_modules(module_config);
@@ -488,12 +912,38 @@ where
         self,
         config: RpcModuleConfig,
         eth: DynEthApiBuilder<Provider, Pool, EvmConfig, Network, Tasks, Events, EthApi>,
-    ) -> RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi>
+    ) -> RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi, BlockExecutor, Consensus>
     where
         EthApi: 'static,
     {
-        let Self { provider, pool, network, executor, events, evm_config } = self;
-        RpcRegistryInner::new(provider, pool, network, executor, events, config, evm_config, eth)
+        let Self {
+            provider,
+            pool,
+            network,
+            executor,
+            events,
+            evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+        } = self;
+        RpcRegistryInner::new(
+            provider,
+            pool,
+            network,
+            executor,
+            events,
+            config,
+            evm_config,
+            eth,
+            block_executor,
+            consensus,
+            bundle_pool.unwrap_or_default(),
+            auto_seal_handle,
+            bad_block_store.unwrap_or_default(),
+        )
     }
 
     /// Configures all [`RpcModule`]s specific to the given [`TransportRpcModuleConfig`] which can
@@ -508,7 +958,19 @@ where
     {
         let mut modules = TransportRpcModules::default();
 
-        let Self { provider, pool, network, executor, events, evm_config } = self;
+        let Self {
+            provider,
+            pool,
+            network,
+            executor,
+            events,
+            evm_config,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
+        } = self;
 
         if !module_config.is_empty() {
             let TransportRpcModuleConfig { http, ws, ipc, config } = module_config.clone();
@@ -522,6 +984,11 @@ where
                 config.unwrap_or_default(),
                 evm_config,
                 eth,
+                block_executor,
+                consensus,
+                bundle_pool.unwrap_or_default(),
+                auto_seal_handle,
+                bad_block_store.unwrap_or_default(),
             );
 
             modules.config = module_config;
@@ -534,9 +1001,9 @@ where
     }
 }
 
-impl Default for RpcModuleBuilder<(), (), (), (), (), ()> {
+impl Default for RpcModuleBuilder<(), (), (), (), (), (), (), ()> {
     fn default() -> Self {
-        Self::new((), (), (), (), (), ())
+        Self::new((), (), (), (), (), (), (), ())
     }
 }
 
@@ -610,12 +1077,29 @@ impl RpcModuleConfigBuilder {
 
 /// A Helper type the holds instances of the configured modules.
 #[derive(Debug, Clone)]
-pub struct RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi> {
+pub struct RpcRegistryInner<
+    Provider,
+    Pool,
+    Network,
+    Tasks,
+    Events,
+    EthApi,
+    BlockExecutor,
+    Consensus,
+> {
     provider: Provider,
     pool: Pool,
     network: Network,
     executor: Tasks,
     events: Events,
+    block_executor: BlockExecutor,
+    consensus: Consensus,
+    /// Stores the bundles submitted via the `mev_` namespace
+    bundle_pool: BundlePool,
+    /// Controls the miner of a dev chain
+    auto_seal_handle: Option<AutoSealHandle>,
+    /// Stores the blocks the engine rejected as invalid
+    bad_block_store: BadBlockStore,
     /// Holds a all `eth_` namespace handlers
     eth: EthHandlers<Provider, Pool, Network, Events, EthApi>,
     /// to put trace calls behind semaphore
@@ -626,8 +1110,8 @@ pub struct RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi> {
 
 // === impl RpcRegistryInner ===
 
-impl<Provider, Pool, Network, Tasks, Events, EthApi>
-    RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi>
+impl<Provider, Pool, Network, Tasks, Events, EthApi, BlockExecutor, Consensus>
+    RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi, BlockExecutor, Consensus>
 where
     Provider: StateProviderFactory + BlockReader + EvmEnvProvider + Clone + Unpin + 'static,
     Pool: Send + Sync + Clone + 'static,
@@ -655,6 +1139,11 @@ where
             Events,
             EthApi,
         >,
+        block_executor: BlockExecutor,
+        consensus: Consensus,
+        bundle_pool: BundlePool,
+        auto_seal_handle: Option<AutoSealHandle>,
+        bad_block_store: BadBlockStore,
     ) -> Self
     where
         EvmConfig: ConfigureEvm,
@@ -682,12 +1171,17 @@ where
             modules: Default::default(),
             blocking_pool_guard,
             events,
+            block_executor,
+            consensus,
+            bundle_pool,
+            auto_seal_handle,
+            bad_block_store,
         }
     }
 }
 
-impl<Provider, Pool, Network, Tasks, Events, EthApi>
-    RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi>
+impl<Provider, Pool, Network, Tasks, Events, EthApi, BlockExecutor, Consensus>
+    RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi, BlockExecutor, Consensus>
 {
     /// Returns a reference to the installed [`EthApi`](reth_rpc::eth::EthApi).
     pub const fn eth_api(&self) -> &EthApi {
@@ -727,6 +1221,31 @@ impl<Provider, Pool, Network, Tasks, Events, EthApi>
         &self.provider
     }
 
+    /// Returns a reference to the block executor provider
+    pub const fn block_executor(&self) -> &BlockExecutor {
+        &self.block_executor
+    }
+
+    /// Returns a reference to the consensus implementation
+    pub const fn consensus(&self) -> &Consensus {
+        &self.consensus
+    }
+
+    /// Returns a reference to the pool that stores the bundles submitted via the `mev_` namespace
+    pub const fn bundle_pool(&self) -> &BundlePool {
+        &self.bundle_pool
+    }
+
+    /// Returns a reference to the handle to the miner of a dev chain, if any
+    pub const fn auto_seal_handle(&self) -> Option<&AutoSealHandle> {
+        self.auto_seal_handle.as_ref()
+    }
+
+    /// Returns a reference to the store of the blocks the engine rejected as invalid
+    pub const fn bad_block_store(&self) -> &BadBlockStore {
+        &self.bad_block_store
+    }
+
     /// Returns all installed methods
     pub fn methods(&self) -> Vec<Methods> {
         self.modules.values().cloned().collect()
@@ -742,8 +1261,8 @@ impl<Provider, Pool, Network, Tasks, Events, EthApi>
     }
 }
 
-impl<Provider, Pool, Network, Tasks, Events, EthApi>
-    RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi>
+impl<Provider, Pool, Network, Tasks, Events, EthApi, BlockExecutor, Consensus>
+    RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi, BlockExecutor, Consensus>
 where
     EthApi: UpdateRawTxForwarder,
 {
@@ -756,8 +1275,16 @@ where
     }
 }
 
-impl<Provider: ChainSpecProvider, Pool, Network, Tasks, Events, EthApi>
-    RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi>
+impl<
+        Provider: ChainSpecProvider,
+        Pool,
+        Network,
+        Tasks,
+        Events,
+        EthApi,
+        BlockExecutor,
+        Consensus,
+    > RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi, BlockExecutor, Consensus>
 where
     Network: NetworkInfo + Clone + 'static,
 {
@@ -792,8 +1319,8 @@ where
     }
 }
 
-impl<Provider, Pool, Network, Tasks, Events, EthApi>
-    RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi>
+impl<Provider, Pool, Network, Tasks, Events, EthApi, BlockExecutor, Consensus>
+    RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi, BlockExecutor, Consensus>
 where
     Provider: FullRpcProvider + AccountReader + ChangeSetReader,
     Network: NetworkInfo + Peers + Clone + 'static,
@@ -914,17 +1441,51 @@ where
         EthBundle::new(eth_api, self.blocking_pool_guard.clone())
     }
 
+    /// Instantiates [`MevApi`]
+    ///
+    /// # Panics
+    ///
+    /// If called outside of the tokio runtime. See also [`Self::eth_api`]
+    pub fn mev_api(&self) -> MevApi<EthApi, Pool>
+    where
+        EthApi: EthTransactions + LoadPendingBlock + Call,
+        Pool: Clone,
+    {
+        MevApi::new(
+            self.eth_api().clone(),
+            self.pool.clone(),
+            self.bundle_pool.clone(),
+            self.blocking_pool_guard.clone(),
+        )
+    }
+
+    /// Instantiates [`AnvilApi`], which implements both the `anvil_` and `hardhat_` namespaces.
+    ///
+    /// Returns `None` if no [`AutoSealHandle`] is configured.
+    ///
+    /// # Panics
+    ///
+    /// If called outside of the tokio runtime. See also [`Self::eth_api`]
+    pub fn anvil_api(&self) -> Option<AnvilApi<EthApi, Pool>>
+    where
+        EthApi: Clone,
+        Pool: Clone,
+    {
+        let handle = self.auto_seal_handle.clone()?;
+        Some(AnvilApi::new(self.eth_api().clone(), self.pool.clone(), handle))
+    }
+
     /// Instantiates `OtterscanApi`
     ///
     /// # Panics
     ///
     /// If called outside of the tokio runtime. See also [`Self::eth_api`]
-    pub fn otterscan_api(&self) -> OtterscanApi<EthApi>
+    pub fn otterscan_api(&self) -> OtterscanApi<Provider, EthApi>
     where
         EthApi: EthApiServer,
     {
         let eth_api = self.eth_api().clone();
-        OtterscanApi::new(eth_api)
+        OtterscanApi::new(self.provider.clone(), eth_api)
     }
 
     /// Instantiates `DebugApi`
@@ -937,7 +1498,12 @@ where
         EthApi: EthApiSpec + EthTransactions + TraceExt,
     {
         let eth_api = self.eth_api().clone();
-        DebugApi::new(self.provider.clone(), eth_api, self.blocking_pool_guard.clone())
+        DebugApi::new(
+            self.provider.clone(),
+            eth_api,
+            self.blocking_pool_guard.clone(),
+            self.bad_block_store.clone(),
+        )
     }
 
     /// Instantiates `NetApi`
@@ -959,8 +1525,34 @@ where
     }
 }
 
-impl<Provider, Pool, Network, Tasks, Events, EthApi>
-    RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi>
+impl<Provider, Pool, Network, Tasks, Events, EthApi, BlockExecutor, Consensus>
+    RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi, BlockExecutor, Consensus>
+where
+    Provider: FullRpcProvider,
+    Tasks: TaskSpawner + Clone + 'static,
+    BlockExecutor: BlockExecutorProvider,
+    Consensus: reth_consensus::Consensus + Clone + 'static,
+{
+    /// Instantiates `ValidationApi`
+    pub fn validation_api(&self) -> ValidationApi<Provider, BlockExecutor> {
+        ValidationApi::new(
+            self.provider.clone(),
+            Arc::new(self.consensus.clone()),
+            self.block_executor.clone(),
+            Box::new(self.executor.clone()),
+        )
+    }
+
+    /// Register Flashbots namespace
+    pub fn register_flashbots(&mut self) -> &mut Self {
+        let validation_api = self.validation_api();
+        self.modules.insert(RethRpcModule::Flashbots, validation_api.into_rpc().into());
+        self
+    }
+}
+
+impl<Provider, Pool, Network, Tasks, Events, EthApi, BlockExecutor, Consensus>
+    RpcRegistryInner<Provider, Pool, Network, Tasks, Events, EthApi, BlockExecutor, Consensus>
 where
     Provider: FullRpcProvider + AccountReader + ChangeSetReader,
     Pool: TransactionPool + 'static,
@@ -968,6 +1560,8 @@ where
     Tasks: TaskSpawner + Clone + 'static,
     Events: CanonStateSubscriptions + Clone + 'static,
     EthApi: FullEthApiServer,
+    BlockExecutor: BlockExecutorProvider,
+    Consensus: reth_consensus::Consensus + Clone + 'static,
 {
     /// Configures the auth module that includes the
     ///   * `engine_` namespace
@@ -1060,6 +1654,7 @@ where
                             self.provider.clone(),
                             eth_api.clone(),
                             self.blocking_pool_guard.clone(),
+                            self.bad_block_store.clone(),
                         )
                         .into_rpc()
                         .into(),
@@ -1093,7 +1688,11 @@ where
                         )
                         .into_rpc()
                         .into(),
-                        RethRpcModule::Ots => OtterscanApi::new(eth_api.clone()).into_rpc().into(),
+                        RethRpcModule::Ots => {
+                            OtterscanApi::new(self.provider.clone(), eth_api.clone())
+                                .into_rpc()
+                                .into()
+                        }
                         RethRpcModule::Reth => {
                             RethApi::new(self.provider.clone(), Box::new(self.executor.clone()))
                                 .into_rpc()
@@ -1104,6 +1703,39 @@ where
                                 .into_rpc()
                                 .into()
                         }
+                        RethRpcModule::Flashbots => ValidationApi::new(
+                            self.provider.clone(),
+                            Arc::new(self.consensus.clone()),
+                            self.block_executor.clone(),
+                            Box::new(self.executor.clone()),
+                        )
+                        .into_rpc()
+                        .into(),
+                        RethRpcModule::Mev => MevApi::new(
+                            eth_api.clone(),
+                            self.pool.clone(),
+                            self.bundle_pool.clone(),
+                            self.blocking_pool_guard.clone(),
+                        )
+                        .into_rpc()
+                        .into(),
+                        // the cheatcodes require a miner that is only available in --dev mode
+                        RethRpcModule::Anvil => self
+                            .auto_seal_handle
+                            .clone()
+                            .map(|handle| {
+                                let api = AnvilApi::new(eth_api.clone(), self.pool.clone(), handle);
+                                AnvilApiServer::into_rpc(api).into()
+                            })
+                            .unwrap_or_default(),
+                        RethRpcModule::Hardhat => self
+                            .auto_seal_handle
+                            .clone()
+                            .map(|handle| {
+                                let api = AnvilApi::new(eth_api.clone(), self.pool.clone(), handle);
+                                HardhatApiServer::into_rpc(api).into()
+                            })
+                            .unwrap_or_default(),
                     })
                     .clone()
             })
@@ -1918,6 +2550,10 @@ mod tests {
                 "rpc" => RethRpcModule::Rpc,
                 "ots" => RethRpcModule::Ots,
                 "reth" => RethRpcModule::Reth,
+                "flashbots" => RethRpcModule::Flashbots,
+                "mev" => RethRpcModule::Mev,
+                "anvil" => RethRpcModule::Anvil,
+                "hardhat" => RethRpcModule::Hardhat,
             );
     }
 
@@ -22,8 +22,9 @@ use reth_rpc_api::{
 };
 use reth_rpc_server_types::RethRpcModule;
 use reth_rpc_types::{
-    trace::filter::TraceFilter, FeeHistory, Filter, Index, Log, PendingTransactionFilterKind,
-    RichBlock, SyncStatus, Transaction, TransactionReceipt, TransactionRequest,
+    debug::DebugSubscriptionKind, trace::filter::TraceFilter, FeeHistory, Filter, Index, Log,
+    PendingTransactionFilterKind, RichBlock, SyncStatus, Transaction, TransactionReceipt,
+    TransactionRequest,
 };
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
@@ -39,6 +40,17 @@ fn is_unimplemented(err: jsonrpsee::core::client::Error) -> bool {
     }
 }
 
+fn is_address_appearances_not_indexed(err: jsonrpsee::core::client::Error) -> bool {
+    const MESSAGE: &str =
+        "address appearance index is not built, enable the IndexAddressAppearances stage";
+    match err {
+        jsonrpsee::core::client::Error::Call(error_obj) => {
+            error_obj.code() == ErrorCode::InternalError.code() && error_obj.message() == MESSAGE
+        }
+        _ => false,
+    }
+}
+
 async fn test_rpc_call_ok<R>(client: &HttpClient, method_name: &str, params: ArrayParams)
 where
     R: DeserializeOwned,
@@ -143,6 +155,10 @@ where
     AdminApiClient::remove_peer(client, node.into()).await.unwrap();
     AdminApiClient::add_trusted_peer(client, node.into()).await.unwrap();
     AdminApiClient::remove_trusted_peer(client, node.into()).await.unwrap();
+    AdminApiClient::ban_peer(client, url.to_string(), Some(60)).await.unwrap();
+    AdminApiClient::ban_peer(client, "10.3.0.0/16".to_string(), None).await.unwrap();
+    AdminApiClient::unban_peer(client, "10.3.0.0/16".to_string()).await.unwrap();
+    AdminApiClient::list_bans(client).await.unwrap();
     AdminApiClient::node_info(client).await.unwrap();
 }
 
@@ -237,7 +253,26 @@ where
     DebugApiClient::raw_block(client, block_id).await.unwrap_err();
     DebugApiClient::raw_transaction(client, B256::default()).await.unwrap();
     DebugApiClient::raw_receipts(client, block_id).await.unwrap();
-    assert!(is_unimplemented(DebugApiClient::bad_blocks(client).await.err().unwrap()));
+    assert!(DebugApiClient::bad_blocks(client).await.unwrap().is_empty());
+    assert!(DebugApiClient::debug_get_modified_accounts_by_number(client, 0, None)
+        .await
+        .unwrap()
+        .is_empty());
+    DebugApiClient::debug_get_modified_accounts_by_number(client, 0, Some(0)).await.unwrap_err();
+    DebugApiClient::debug_intermediate_roots(client, B256::default(), None).await.unwrap_err();
+    DebugApiClient::debug_account_range(client, block_id, Bytes::default(), 10, true, true, true)
+        .await
+        .unwrap_err();
+    DebugApiClient::debug_storage_range_at(
+        client,
+        B256::default(),
+        0,
+        Address::default(),
+        B256::default(),
+        10,
+    )
+    .await
+    .unwrap_err();
 }
 
 async fn test_basic_net_calls<C>(client: &C)
@@ -323,17 +358,16 @@ where
         .err()
         .unwrap();
 
-    assert!(is_unimplemented(
-        OtterscanClient::search_transactions_before(client, address, block_number, page_size,)
+    // the address appearance index is not built
+    assert!(is_address_appearances_not_indexed(
+        OtterscanClient::search_transactions_before(client, address, block_number, page_size)
             .await
-            .err()
-            .unwrap()
+            .unwrap_err()
     ));
-    assert!(is_unimplemented(
-        OtterscanClient::search_transactions_after(client, address, block_number, page_size,)
+    assert!(is_address_appearances_not_indexed(
+        OtterscanClient::search_transactions_after(client, address, block_number, page_size)
             .await
-            .err()
-            .unwrap()
+            .unwrap_err()
     ));
     assert!(OtterscanClient::get_transaction_by_sender_and_nonce(client, sender, nonce)
         .await
@@ -421,6 +455,17 @@ async fn test_call_debug_functions_ws() {
     let handle = launch_ws(vec![RethRpcModule::Debug]).await;
     let client = handle.ws_client().await.unwrap();
     test_basic_debug_calls(&client).await;
+
+    // the end block must be later than the start block
+    DebugApiClient::debug_subscribe(
+        &client,
+        DebugSubscriptionKind::TraceChain,
+        0u64.into(),
+        0u64.into(),
+        None,
+    )
+    .await
+    .unwrap_err();
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -2,8 +2,9 @@ use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
 
 use reth_beacon_consensus::BeaconConsensusEngineHandle;
 use reth_chainspec::MAINNET;
+use reth_consensus::noop::NoopConsensus;
 use reth_ethereum_engine_primitives::EthEngineTypes;
-use reth_evm_ethereum::EthEvmConfig;
+use reth_evm_ethereum::{execute::EthExecutorProvider, EthEvmConfig};
 use reth_network_api::noop::NoopNetwork;
 use reth_payload_builder::test_utils::spawn_test_payload_service;
 use reth_provider::test_utils::{NoopProvider, TestCanonStateSubscriptions};
@@ -119,6 +120,8 @@ pub fn test_rpc_builder() -> RpcModuleBuilder<
     TokioTaskExecutor,
     TestCanonStateSubscriptions,
     EthEvmConfig,
+    EthExecutorProvider,
+    NoopConsensus,
 > {
     RpcModuleBuilder::default()
         .with_provider(NoopProvider::default())
@@ -127,4 +130,6 @@ pub fn test_rpc_builder() -> RpcModuleBuilder<
         .with_executor(TokioTaskExecutor::default())
         .with_events(TestCanonStateSubscriptions::default())
         .with_evm_config(EthEvmConfig::default())
+        .with_block_executor(EthExecutorProvider::mainnet())
+        .with_consensus(NoopConsensus::default())
 }
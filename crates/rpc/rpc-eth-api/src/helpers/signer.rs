@@ -7,6 +7,7 @@ use dyn_clone::DynClone;
 use reth_primitives::{Address, Signature, TransactionSigned};
 use reth_rpc_eth_types::SignError;
 use reth_rpc_types::TypedTransactionRequest;
+use reth_transaction_pool::ImpersonatedAccounts;
 
 /// Result returned by [`EthSigner`] methods.
 pub type Result<T> = result::Result<T, SignError>;
@@ -47,4 +48,8 @@ pub trait AddDevSigners {
     /// Generates 20 random developer accounts.
     /// Used in DEV mode.
     fn with_dev_accounts(&self);
+
+    /// Allows the given impersonated accounts to send transactions without a signer.
+    /// Used in DEV mode.
+    fn with_impersonated_accounts(&self, accounts: ImpersonatedAccounts);
 }
@@ -7,7 +7,8 @@ use alloy_dyn_abi::TypedData;
 use futures::Future;
 use reth_primitives::{
     Address, BlockId, Bytes, FromRecoveredPooledTransaction, IntoRecoveredTransaction, Receipt,
-    SealedBlockWithSenders, TransactionMeta, TransactionSigned, TxHash, TxKind, B256, U256,
+    SealedBlockWithSenders, Signature, TransactionMeta, TransactionSigned,
+    TransactionSignedEcRecovered, TxHash, TxKind, B256, U256,
 };
 use reth_provider::{BlockReaderIdExt, ReceiptProvider, TransactionsProvider};
 use reth_rpc_eth_types::{
@@ -21,8 +22,10 @@ use reth_rpc_types::{
     },
     AnyTransactionReceipt, Transaction, TransactionRequest, TypedTransactionRequest,
 };
-use reth_rpc_types_compat::transaction::from_recovered_with_block_context;
-use reth_transaction_pool::{TransactionOrigin, TransactionPool};
+use reth_rpc_types_compat::transaction::{
+    from_recovered_with_block_context, to_primitive_transaction,
+};
+use reth_transaction_pool::{ImpersonatedAccounts, TransactionOrigin, TransactionPool};
 
 use super::EthSigner;
 
@@ -62,6 +65,11 @@ pub trait EthTransactions: LoadTransaction {
     /// Access to transaction forwarder in default (L1) trait method implementations.
     fn raw_tx_forwarder(&self) -> Option<Arc<dyn RawTransactionForwarder>>;
 
+    /// Returns the accounts that can send transactions without a signer, if configured.
+    ///
+    /// Only configured on development chains.
+    fn impersonated_accounts(&self) -> Option<ImpersonatedAccounts>;
+
     /// Returns a handle for signing data.
     ///
     /// Singer access in default (L1) trait method implementations.
@@ -272,7 +280,10 @@ pub trait EthTransactions: LoadTransaction {
                 None => return Err(SignError::NoAccount.into()),
             };
 
-            if self.find_signer(&from).is_err() {
+            let impersonated = self
+                .impersonated_accounts()
+                .is_some_and(|accounts| accounts.is_impersonated(&from));
+            if !impersonated && self.find_signer(&from).is_err() {
                 return Err(SignError::NoAccount.into());
             }
 
@@ -450,10 +461,24 @@ pub trait EthTransactions: LoadTransaction {
                 None => return Err(EthApiError::ConflictingFeeFieldsInRequest),
             };
 
-            let signed_tx = self.sign_request(&from, transaction)?;
-
-            let recovered =
-                signed_tx.into_ecrecovered().ok_or(EthApiError::InvalidTransactionSignature)?;
+            let recovered = if impersonated {
+                // transactions of impersonated accounts can't be signed, the sender is set directly
+                // and the placeholder signature only keeps the hashes of different senders apart
+                let transaction = to_primitive_transaction(transaction)
+                    .ok_or(SignError::InvalidTransactionRequest)?;
+                let signature = Signature {
+                    r: U256::from_be_slice(from.as_slice()),
+                    s: U256::from(1),
+                    odd_y_parity: false,
+                };
+                TransactionSignedEcRecovered::from_signed_transaction(
+                    TransactionSigned::from_transaction_and_signature(transaction, signature),
+                    from,
+                )
+            } else {
+                let signed_tx = self.sign_request(&from, transaction)?;
+                signed_tx.into_ecrecovered().ok_or(EthApiError::InvalidTransactionSignature)?
+            };
 
             let pool_transaction = match recovered.try_into() {
                 Ok(converted) => <<Self as LoadTransaction>::Pool as TransactionPool>::Transaction::from_recovered_pooled_transaction(converted),
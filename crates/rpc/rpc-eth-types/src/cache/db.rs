@@ -42,6 +42,27 @@ impl<'a> reth_provider::StateProofProvider for StateProviderTraitObjWrapper<'a>
     }
 }
 
+impl<'a> reth_provider::HashedStateRangeProvider for StateProviderTraitObjWrapper<'a> {
+    fn hashed_account_range(
+        &self,
+        hashed_state: &reth_trie::HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> reth_errors::ProviderResult<reth_trie::HashedRange<reth_primitives::Account>> {
+        self.0.hashed_account_range(hashed_state, start, limit)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_state: &reth_trie::HashedPostState,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> reth_errors::ProviderResult<reth_trie::HashedRange<U256>> {
+        self.0.hashed_storage_range(hashed_state, hashed_address, start, limit)
+    }
+}
+
 impl<'a> reth_provider::AccountReader for StateProviderTraitObjWrapper<'a> {
     fn basic_account(
         &self,
@@ -260,6 +260,14 @@ pub enum RethRpcModule {
     /// This is separate from [`RethRpcModule::Eth`] because it is a non standardized call that
     /// should be opt-in.
     EthCallBundle,
+    /// `flashbots_` module
+    Flashbots,
+    /// `mev_` module
+    Mev,
+    /// `anvil_` module
+    Anvil,
+    /// `hardhat_` module
+    Hardhat,
 }
 
 // === impl RethRpcModule ===
@@ -309,6 +317,10 @@ impl FromStr for RethRpcModule {
             "reth" => Self::Reth,
             "ots" => Self::Ots,
             "eth-call-bundle" | "eth_callBundle" => Self::EthCallBundle,
+            "flashbots" => Self::Flashbots,
+            "mev" => Self::Mev,
+            "anvil" => Self::Anvil,
+            "hardhat" => Self::Hardhat,
             _ => return Err(ParseError::VariantNotFound),
         })
     }
@@ -0,0 +1,89 @@
+//! Types for the `debug` namespace that are not part of alloy.
+
+use alloy_primitives::{Address, Bytes, B256};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Response of `debug_storageRangeAt`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageRangeResult {
+    /// The storage slots of this page, keyed by the hash of the slot.
+    pub storage: BTreeMap<B256, StorageEntry>,
+    /// The hashed slot the next page starts at, `None` if this is the last page.
+    pub next_key: Option<B256>,
+}
+
+/// A storage slot returned by `debug_storageRangeAt`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageEntry {
+    /// The preimage of the hashed slot, `None` if unknown.
+    pub key: Option<B256>,
+    /// The value of the slot.
+    pub value: B256,
+}
+
+/// The kind of subscription created by `debug_subscribe`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DebugSubscriptionKind {
+    /// Traces all blocks of a range and emits the traces of each block, see `debug_traceChain`.
+    TraceChain,
+}
+
+/// Response of `debug_accountRange`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountRangeResult {
+    /// The state root of the block.
+    pub root: B256,
+    /// The accounts of this page, keyed by their address or by `pre(<hashed address>)` if the
+    /// address is unknown.
+    pub accounts: BTreeMap<String, DumpAccount>,
+    /// The hashed address the next page starts at, `None` if this is the last page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next: Option<B256>,
+}
+
+/// An account returned by `debug_accountRange`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DumpAccount {
+    /// The balance of the account as a decimal string.
+    pub balance: String,
+    /// The nonce of the account.
+    pub nonce: u64,
+    /// The hash of the code of the account.
+    pub code_hash: B256,
+    /// The code of the account, omitted if code was not requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<Bytes>,
+    /// The storage of the account as hex strings keyed by hashed slot, omitted if storage was not
+    /// requested.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<B256, String>>,
+    /// The address of the account, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+    /// The hashed address of the account.
+    #[serde(rename = "key")]
+    pub address_hash: B256,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_debug_subscription_kind() {
+        let kind: DebugSubscriptionKind = serde_json::from_str(r#""traceChain""#).unwrap();
+        assert_eq!(kind, DebugSubscriptionKind::TraceChain);
+    }
+
+    #[test]
+    fn serde_storage_range_result() {
+        let s = r#"{"storage":{"0x290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563":{"key":null,"value":"0x0000000000000000000000000000000000000000000000000000000000000001"}},"nextKey":null}"#;
+        let result: StorageRangeResult = serde_json::from_str(s).unwrap();
+        assert_eq!(result.storage.len(), 1);
+        assert_eq!(serde_json::to_string(&result).unwrap(), s);
+    }
+}
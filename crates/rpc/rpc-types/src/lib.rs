@@ -12,6 +12,8 @@
 #[allow(hidden_glob_reexports)]
 mod eth;
 
+pub mod debug;
+
 /// Alias for a peer identifier
 pub type PeerId = B512;
 
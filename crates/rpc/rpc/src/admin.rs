@@ -1,14 +1,14 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use alloy_genesis::ChainConfig;
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use reth_chainspec::ChainSpec;
-use reth_network_api::{NetworkInfo, PeerKind, Peers};
+use reth_network_api::{BanEntry, BanTarget, NetworkInfo, PeerKind, Peers};
 use reth_network_peers::{id2pk, AnyNode, NodeRecord};
 use reth_primitives::EthereumHardfork;
 use reth_rpc_api::AdminApiServer;
-use reth_rpc_server_types::ToRpcResult;
+use reth_rpc_server_types::{result::invalid_params_rpc_err, ToRpcResult};
 use reth_rpc_types::admin::{
     EthInfo, EthPeerInfo, EthProtocolInfo, NodeInfo, PeerInfo, PeerNetworkInfo, PeerProtocolInfo,
     Ports, ProtocolInfo,
@@ -63,6 +63,24 @@ where
         Ok(true)
     }
 
+    /// Handler for `admin_banPeer`
+    fn ban_peer(&self, target: String, duration: Option<u64>) -> RpcResult<bool> {
+        let target = parse_ban_target(&target)?;
+        self.network.ban(target, duration.map(Duration::from_secs));
+        Ok(true)
+    }
+
+    /// Handler for `admin_unbanPeer`
+    async fn unban_peer(&self, target: String) -> RpcResult<bool> {
+        let target = parse_ban_target(&target)?;
+        self.network.unban(target).await.to_rpc_result()
+    }
+
+    /// Handler for `admin_listBans`
+    async fn list_bans(&self) -> RpcResult<Vec<BanEntry>> {
+        self.network.bans().await.to_rpc_result()
+    }
+
     /// Handler for `admin_peers`
     async fn peers(&self) -> RpcResult<Vec<PeerInfo>> {
         let peers = self.network.get_all_peers().await.to_rpc_result()?;
@@ -184,6 +202,17 @@ where
     }
 }
 
+/// Parses the target of a ban, which is either a node in any of the [`AnyNode`] formats, a peer id,
+/// an IP address or a network in CIDR notation.
+fn parse_ban_target(target: &str) -> RpcResult<BanTarget> {
+    if let Ok(node) = target.parse::<AnyNode>() {
+        return Ok(BanTarget::Peer(node.peer_id()))
+    }
+    target
+        .parse::<BanTarget>()
+        .map_err(|err| invalid_params_rpc_err(format!("invalid ban target {target}: {err}")))
+}
+
 impl<N> std::fmt::Debug for AdminApi<N> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("AdminApi").finish_non_exhaustive()
@@ -0,0 +1,449 @@
+//! Implementation of the `anvil_` and `hardhat_` cheatcode namespaces for dev mode.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use reth_auto_seal_consensus::AutoSealHandle;
+use reth_errors::RethError;
+use reth_primitives::{Address, BlockId, Bytes, B256, U256};
+use reth_rpc_api::{AnvilApiServer, HardhatApiServer};
+use reth_rpc_eth_api::helpers::{EthBlocks, LoadPendingBlock, SpawnBlocking};
+use reth_rpc_eth_types::{EthApiError, EthResult};
+use reth_rpc_types::{
+    anvil::{Forking, Metadata, MineOptions, NodeInfo},
+    Block,
+};
+use reth_transaction_pool::TransactionPool;
+
+/// `anvil_` and `hardhat_` API implementation.
+///
+/// This controls the auto seal miner of a node running in dev mode via an [`AutoSealHandle`].
+/// Account modifications take effect in the next mined block.
+pub struct AnvilApi<Eth, Pool> {
+    inner: Arc<AnvilApiInner<Eth, Pool>>,
+}
+
+impl<Eth, Pool> AnvilApi<Eth, Pool> {
+    /// Creates a new instance of `AnvilApi`.
+    pub fn new(eth_api: Eth, pool: Pool, handle: AutoSealHandle) -> Self {
+        Self { inner: Arc::new(AnvilApiInner { eth_api, pool, handle }) }
+    }
+
+    /// Returns the handle to the auto seal miner.
+    pub fn handle(&self) -> &AutoSealHandle {
+        &self.inner.handle
+    }
+}
+
+impl<Eth, Pool> AnvilApi<Eth, Pool>
+where
+    Eth: EthBlocks + LoadPendingBlock + SpawnBlocking + 'static,
+    Pool: TransactionPool + 'static,
+{
+    /// Mines the given number of blocks, `interval` seconds apart, and returns their hashes.
+    pub async fn mine(&self, blocks: Option<U256>, interval: Option<U256>) -> EthResult<Vec<B256>> {
+        let blocks = blocks.map(|blocks| blocks.saturating_to()).unwrap_or(1);
+        let interval = interval.map(|interval| interval.saturating_to());
+        self.handle()
+            .mine(blocks, interval)
+            .await
+            .ok_or_else(|| EthApiError::Internal(RethError::msg("failed to mine block")))
+    }
+
+    /// Mines blocks according to the given options and returns the mined blocks.
+    pub async fn mine_detailed(&self, opts: Option<MineOptions>) -> EthResult<Vec<Block>> {
+        let (timestamp, blocks) = match opts.unwrap_or_default() {
+            MineOptions::Options { timestamp, blocks } => (timestamp, blocks),
+            MineOptions::Timestamp(timestamp) => (timestamp, None),
+        };
+        if let Some(timestamp) = timestamp {
+            self.handle().set_next_block_timestamp(timestamp);
+        }
+
+        let hashes = self.mine(blocks.map(U256::from), None).await?;
+        let mut mined = Vec::with_capacity(hashes.len());
+        for hash in hashes {
+            let block = self
+                .inner
+                .eth_api
+                .rpc_block(BlockId::from(hash), true)
+                .await?
+                .ok_or(EthApiError::UnknownBlockNumber)?;
+            mined.push(block.inner);
+        }
+        Ok(mined)
+    }
+
+    /// Removes the transaction from the pool and returns its hash if it existed.
+    pub fn drop_transaction(&self, tx_hash: B256) -> Option<B256> {
+        self.inner.pool.remove_transactions(vec![tx_hash]).into_iter().next().map(|tx| *tx.hash())
+    }
+
+    /// Removes all transactions of the given sender from the pool.
+    pub fn remove_pool_transactions(&self, address: Address) {
+        let hashes = self
+            .inner
+            .pool
+            .get_transactions_by_sender(address)
+            .into_iter()
+            .map(|tx| *tx.hash())
+            .collect();
+        self.inner.pool.remove_transactions(hashes);
+    }
+
+    /// Sets the balance of the account.
+    pub fn set_balance(&self, address: Address, balance: U256) {
+        self.handle().state_overlay().set_balance(address, balance);
+    }
+
+    /// Sets the code of the account.
+    pub fn set_code(&self, address: Address, code: Bytes) {
+        self.handle().state_overlay().set_code(address, code);
+    }
+
+    /// Sets the nonce of the account.
+    pub fn set_nonce(&self, address: Address, nonce: U256) -> EthResult<()> {
+        let nonce = nonce
+            .try_into()
+            .map_err(|_| EthApiError::InvalidParams("nonce exceeds u64".to_string()))?;
+        self.handle().state_overlay().set_nonce(address, nonce);
+        Ok(())
+    }
+
+    /// Writes a single storage slot of the account.
+    pub fn set_storage_at(&self, address: Address, slot: U256, value: B256) {
+        self.handle().state_overlay().set_storage_at(address, slot, value.into());
+    }
+
+    /// Sets the base fee of the next block.
+    pub fn set_next_block_base_fee(&self, base_fee: U256) -> EthResult<()> {
+        let base_fee = base_fee
+            .try_into()
+            .map_err(|_| EthApiError::InvalidParams("base fee exceeds u64".to_string()))?;
+        self.handle().set_next_block_base_fee(base_fee);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<Eth, Pool> AnvilApiServer for AnvilApi<Eth, Pool>
+where
+    Eth: EthBlocks + LoadPendingBlock + SpawnBlocking + 'static,
+    Pool: TransactionPool + 'static,
+{
+    /// Handler for `anvil_impersonateAccount`
+    async fn anvil_impersonate_account(&self, address: Address) -> RpcResult<()> {
+        self.handle().impersonated_accounts().impersonate(address);
+        Ok(())
+    }
+
+    /// Handler for `anvil_stopImpersonatingAccount`
+    async fn anvil_stop_impersonating_account(&self, address: Address) -> RpcResult<()> {
+        self.handle().impersonated_accounts().stop_impersonating(&address);
+        Ok(())
+    }
+
+    /// Handler for `anvil_autoImpersonateAccount`
+    async fn anvil_auto_impersonate_account(&self, enabled: bool) -> RpcResult<()> {
+        self.handle().impersonated_accounts().set_impersonate_all(enabled);
+        Ok(())
+    }
+
+    /// Handler for `anvil_getAutomine`
+    async fn anvil_get_automine(&self) -> RpcResult<bool> {
+        Ok(self.handle().automine())
+    }
+
+    /// Handler for `anvil_mine`
+    async fn anvil_mine(&self, blocks: Option<U256>, interval: Option<U256>) -> RpcResult<()> {
+        Ok(Self::mine(self, blocks, interval).await.map(drop)?)
+    }
+
+    /// Handler for `anvil_setAutomine`
+    async fn anvil_set_automine(&self, enabled: bool) -> RpcResult<()> {
+        self.handle().set_automine(enabled);
+        Ok(())
+    }
+
+    /// Handler for `anvil_setIntervalMining`
+    async fn anvil_set_interval_mining(&self, interval: u64) -> RpcResult<()> {
+        self.handle().set_interval_mining(interval);
+        Ok(())
+    }
+
+    /// Handler for `anvil_dropTransaction`
+    async fn anvil_drop_transaction(&self, tx_hash: B256) -> RpcResult<Option<B256>> {
+        Ok(self.drop_transaction(tx_hash))
+    }
+
+    /// Handler for `anvil_reset`
+    async fn anvil_reset(&self, _fork: Option<Forking>) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("forking is not supported").into())
+    }
+
+    /// Handler for `anvil_setRpcUrl`
+    async fn anvil_set_rpc_url(&self, _url: String) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("forking is not supported").into())
+    }
+
+    /// Handler for `anvil_setBalance`
+    async fn anvil_set_balance(&self, address: Address, balance: U256) -> RpcResult<()> {
+        self.set_balance(address, balance);
+        Ok(())
+    }
+
+    /// Handler for `anvil_setCode`
+    async fn anvil_set_code(&self, address: Address, code: Bytes) -> RpcResult<()> {
+        self.set_code(address, code);
+        Ok(())
+    }
+
+    /// Handler for `anvil_setNonce`
+    async fn anvil_set_nonce(&self, address: Address, nonce: U256) -> RpcResult<()> {
+        Ok(self.set_nonce(address, nonce)?)
+    }
+
+    /// Handler for `anvil_setStorageAt`
+    async fn anvil_set_storage_at(
+        &self,
+        address: Address,
+        slot: U256,
+        value: B256,
+    ) -> RpcResult<bool> {
+        self.set_storage_at(address, slot, value);
+        Ok(true)
+    }
+
+    /// Handler for `anvil_setCoinbase`
+    async fn anvil_set_coinbase(&self, address: Address) -> RpcResult<()> {
+        self.handle().set_coinbase(address);
+        Ok(())
+    }
+
+    /// Handler for `anvil_setChainId`
+    async fn anvil_set_chain_id(&self, _chain_id: u64) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("the chain id is fixed by the chain spec").into())
+    }
+
+    /// Handler for `anvil_setLoggingEnabled`
+    async fn anvil_set_logging_enabled(&self, _enabled: bool) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("logging is configured on startup").into())
+    }
+
+    /// Handler for `anvil_setMinGasPrice`
+    async fn anvil_set_min_gas_price(&self, _gas_price: U256) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("the minimum gas price is not supported").into())
+    }
+
+    /// Handler for `anvil_setNextBlockBaseFeePerGas`
+    async fn anvil_set_next_block_base_fee_per_gas(&self, base_fee: U256) -> RpcResult<()> {
+        Ok(self.set_next_block_base_fee(base_fee)?)
+    }
+
+    /// Handler for `anvil_setTime`
+    async fn anvil_set_time(&self, timestamp: u64) -> RpcResult<u64> {
+        Ok(self.handle().set_time(timestamp))
+    }
+
+    /// Handler for `anvil_dumpState`
+    async fn anvil_dump_state(&self) -> RpcResult<Bytes> {
+        Err(EthApiError::Unsupported("state dumps are not supported").into())
+    }
+
+    /// Handler for `anvil_loadState`
+    async fn anvil_load_state(&self, _state: Bytes) -> RpcResult<bool> {
+        Err(EthApiError::Unsupported("state dumps are not supported").into())
+    }
+
+    /// Handler for `anvil_nodeInfo`
+    async fn anvil_node_info(&self) -> RpcResult<NodeInfo> {
+        Err(EthApiError::Unsupported("node info is not supported").into())
+    }
+
+    /// Handler for `anvil_metadata`
+    async fn anvil_metadata(&self) -> RpcResult<Metadata> {
+        Err(EthApiError::Unsupported("metadata is not supported").into())
+    }
+
+    /// Handler for `anvil_snapshot`
+    async fn anvil_snapshot(&self) -> RpcResult<U256> {
+        Err(EthApiError::Unsupported("snapshots are not supported").into())
+    }
+
+    /// Handler for `anvil_revert`
+    async fn anvil_revert(&self, _id: U256) -> RpcResult<bool> {
+        Err(EthApiError::Unsupported("snapshots are not supported").into())
+    }
+
+    /// Handler for `anvil_increaseTime`
+    async fn anvil_increase_time(&self, seconds: U256) -> RpcResult<i64> {
+        Ok(self.handle().increase_time(seconds.saturating_to()))
+    }
+
+    /// Handler for `anvil_setNextBlockTimestamp`
+    async fn anvil_set_next_block_timestamp(&self, seconds: u64) -> RpcResult<()> {
+        self.handle().set_next_block_timestamp(seconds);
+        Ok(())
+    }
+
+    /// Handler for `anvil_setBlockGasLimit`
+    async fn anvil_set_block_gas_limit(&self, gas_limit: U256) -> RpcResult<bool> {
+        let gas_limit = gas_limit
+            .try_into()
+            .map_err(|_| EthApiError::InvalidParams("gas limit exceeds u64".to_string()))?;
+        self.handle().set_block_gas_limit(gas_limit);
+        Ok(true)
+    }
+
+    /// Handler for `anvil_setBlockTimestampInterval`
+    async fn anvil_set_block_timestamp_interval(&self, seconds: u64) -> RpcResult<()> {
+        self.handle().set_block_timestamp_interval(seconds);
+        Ok(())
+    }
+
+    /// Handler for `anvil_removeBlockTimestampInterval`
+    async fn anvil_remove_block_timestamp_interval(&self) -> RpcResult<bool> {
+        Ok(self.handle().remove_block_timestamp_interval())
+    }
+
+    /// Handler for `anvil_mine_detailed`
+    async fn anvil_mine_detailed(&self, opts: Option<MineOptions>) -> RpcResult<Vec<Block>> {
+        Ok(self.mine_detailed(opts).await?)
+    }
+
+    /// Handler for `anvil_enableTraces`
+    async fn anvil_enable_traces(&self) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("call traces are not supported").into())
+    }
+
+    /// Handler for `anvil_removePoolTransactions`
+    async fn anvil_remove_pool_transactions(&self, address: Address) -> RpcResult<()> {
+        self.remove_pool_transactions(address);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<Eth, Pool> HardhatApiServer for AnvilApi<Eth, Pool>
+where
+    Eth: EthBlocks + LoadPendingBlock + SpawnBlocking + 'static,
+    Pool: TransactionPool + 'static,
+{
+    /// Handler for `hardhat_dropTransaction`
+    async fn hardhat_drop_transaction(&self, tx_hash: B256) -> RpcResult<bool> {
+        Ok(self.drop_transaction(tx_hash).is_some())
+    }
+
+    /// Handler for `hardhat_impersonateAccount`
+    async fn hardhat_impersonate_account(&self, address: Address) -> RpcResult<()> {
+        self.handle().impersonated_accounts().impersonate(address);
+        Ok(())
+    }
+
+    /// Handler for `hardhat_getAutomine`
+    async fn hardhat_get_automine(&self) -> RpcResult<bool> {
+        Ok(self.handle().automine())
+    }
+
+    /// Handler for `hardhat_metadata`
+    async fn hardhat_metadata(&self) -> RpcResult<Metadata> {
+        Err(EthApiError::Unsupported("metadata is not supported").into())
+    }
+
+    /// Handler for `hardhat_mine`
+    async fn hardhat_mine(&self, blocks: Option<U256>, interval: Option<U256>) -> RpcResult<()> {
+        Ok(self.mine(blocks, interval).await.map(drop)?)
+    }
+
+    /// Handler for `hardhat_reset`
+    async fn hardhat_reset(&self, _fork: Option<Forking>) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("forking is not supported").into())
+    }
+
+    /// Handler for `hardhat_setBalance`
+    async fn hardhat_set_balance(&self, address: Address, balance: U256) -> RpcResult<()> {
+        self.set_balance(address, balance);
+        Ok(())
+    }
+
+    /// Handler for `hardhat_setCode`
+    async fn hardhat_set_code(&self, address: Address, code: Bytes) -> RpcResult<()> {
+        self.set_code(address, code);
+        Ok(())
+    }
+
+    /// Handler for `hardhat_setCoinbase`
+    async fn hardhat_set_coinbase(&self, address: Address) -> RpcResult<()> {
+        self.handle().set_coinbase(address);
+        Ok(())
+    }
+
+    /// Handler for `hardhat_setLoggingEnabled`
+    async fn hardhat_set_logging_enabled(&self, _enabled: bool) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("logging is configured on startup").into())
+    }
+
+    /// Handler for `hardhat_setMinGasPrice`
+    async fn hardhat_set_min_gas_price(&self, _gas_price: U256) -> RpcResult<()> {
+        Err(EthApiError::Unsupported("the minimum gas price is not supported").into())
+    }
+
+    /// Handler for `hardhat_setNextBlockBaseFeePerGas`
+    async fn hardhat_set_next_block_base_fee_per_gas(
+        &self,
+        base_fee_per_gas: U256,
+    ) -> RpcResult<()> {
+        Ok(self.set_next_block_base_fee(base_fee_per_gas)?)
+    }
+
+    /// Handler for `hardhat_setPrevRandao`
+    async fn hardhat_set_prev_randao(&self, prev_randao: B256) -> RpcResult<()> {
+        self.handle().set_next_block_prev_randao(prev_randao);
+        Ok(())
+    }
+
+    /// Handler for `hardhat_setNonce`
+    async fn hardhat_set_nonce(&self, address: Address, nonce: U256) -> RpcResult<()> {
+        Ok(self.set_nonce(address, nonce)?)
+    }
+
+    /// Handler for `hardhat_setStorageAt`
+    async fn hardhat_set_storage_at(
+        &self,
+        address: Address,
+        slot: U256,
+        value: B256,
+    ) -> RpcResult<()> {
+        self.set_storage_at(address, slot, value);
+        Ok(())
+    }
+
+    /// Handler for `hardhat_stopImpersonatingAccount`
+    async fn hardhat_stop_impersonating_account(&self, address: Address) -> RpcResult<()> {
+        self.handle().impersonated_accounts().stop_impersonating(&address);
+        Ok(())
+    }
+}
+
+impl<Eth, Pool> std::fmt::Debug for AnvilApi<Eth, Pool> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnvilApi").finish_non_exhaustive()
+    }
+}
+
+impl<Eth, Pool> Clone for AnvilApi<Eth, Pool> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+/// Container type for `AnvilApi` internals
+struct AnvilApiInner<Eth, Pool> {
+    /// Access to commonly used code of the `eth` namespace
+    eth_api: Eth,
+    /// The transaction pool of the node
+    pool: Pool,
+    /// Controls the auto seal miner
+    handle: AutoSealHandle,
+}
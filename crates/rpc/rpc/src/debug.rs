@@ -1,33 +1,55 @@
-use std::sync::Arc;
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    fs::File,
+    io::{self, BufWriter, Write},
+    ops::RangeInclusive,
+    rc::Rc,
+    sync::Arc,
+};
 
 use alloy_rlp::{Decodable, Encodable};
 use async_trait::async_trait;
-use jsonrpsee::core::RpcResult;
+use jsonrpsee::{
+    core::RpcResult, server::SubscriptionMessage, PendingSubscriptionSink, SubscriptionSink,
+};
 use reth_chainspec::EthereumHardforks;
-use reth_evm::ConfigureEvmEnv;
+use reth_errors::RethError;
+use reth_evm::{system_calls::pre_block_beacon_root_contract_call, ConfigureEvmEnv};
 use reth_primitives::{
-    Address, Block, BlockId, BlockNumberOrTag, Bytes, TransactionSignedEcRecovered, B256, U256,
+    hex, keccak256, Address, Block, BlockId, BlockNumber, BlockNumberOrTag, BlockWithSenders,
+    Bytes, SealedBlock, TransactionSigned, TransactionSignedEcRecovered, B256, U256,
 };
 use reth_provider::{
-    BlockReaderIdExt, ChainSpecProvider, EvmEnvProvider, HeaderProvider, StateProviderFactory,
+    BadBlockStore, BlockReaderIdExt, ChainSpecProvider, ChangeSetReader, EvmEnvProvider,
+    HashedStateRangeProvider, HeaderProvider, StateProvider, StateProviderFactory,
     TransactionVariant,
 };
 use reth_revm::database::StateProviderDatabase;
 use reth_rpc_api::DebugApiServer;
 use reth_rpc_eth_api::helpers::{Call, EthApiSpec, EthTransactions, TraceExt};
-use reth_rpc_eth_types::{EthApiError, EthResult, StateCacheDb};
+use reth_rpc_eth_types::{
+    pending_block::pre_block_blockhashes_update, EthApiError, EthResult, StateCacheDb,
+};
 use reth_rpc_server_types::{result::internal_rpc_err, ToRpcResult};
 use reth_rpc_types::{
+    debug::{
+        AccountRangeResult, DebugSubscriptionKind, DumpAccount, StorageEntry, StorageRangeResult,
+    },
     state::EvmOverrides,
     trace::geth::{
         BlockTraceResult, FourByteFrame, GethDebugBuiltInTracerType, GethDebugTracerType,
-        GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace, NoopFrame, TraceResult,
+        GethDebugTracingCallOptions, GethDebugTracingOptions, GethDefaultTracingOptions, GethTrace,
+        NoopFrame, TraceResult,
     },
-    BlockError, Bundle, RichBlock, StateContext, TransactionRequest,
+    BlockError, BlockTransactionsKind, Bundle, RichBlock, StateContext, TransactionRequest,
 };
+use reth_rpc_types_compat::block::from_block;
 use reth_tasks::pool::BlockingTaskGuard;
+use reth_trie::{HashedPostState, HashedStorage};
 use revm::{
-    db::CacheDB,
+    db::{states::bundle_state::BundleRetention, AccountState, CacheDB, State},
+    inspectors::TracerEip3155,
     primitives::{db::DatabaseCommit, BlockEnv, CfgEnvWithHandlerCfg, Env, EnvWithHandlerCfg},
 };
 use revm_inspectors::tracing::{
@@ -36,6 +58,15 @@ use revm_inspectors::tracing::{
 };
 use tokio::sync::{AcquireError, OwnedSemaphorePermit};
 
+/// The maximum number of accounts returned by `debug_accountRange`, same as geth.
+const ACCOUNT_RANGE_MAX_RESULTS: usize = 256;
+
+/// The maximum number of accounts visited by `debug_accountRange` for a single page.
+const ACCOUNT_RANGE_MAX_SCANNED: usize = 100_000;
+
+/// The maximum number of storage slots returned by `debug_accountRange` for a single page.
+const ACCOUNT_RANGE_MAX_STORAGE_SLOTS: usize = 100_000;
+
 /// `debug` API implementation.
 ///
 /// This type provides the functionality for handling `debug` related requests.
@@ -47,8 +78,14 @@ pub struct DebugApi<Provider, Eth> {
 
 impl<Provider, Eth> DebugApi<Provider, Eth> {
     /// Create a new instance of the [`DebugApi`]
-    pub fn new(provider: Provider, eth: Eth, blocking_task_guard: BlockingTaskGuard) -> Self {
-        let inner = Arc::new(DebugApiInner { provider, eth_api: eth, blocking_task_guard });
+    pub fn new(
+        provider: Provider,
+        eth: Eth,
+        blocking_task_guard: BlockingTaskGuard,
+        bad_blocks: BadBlockStore,
+    ) -> Self {
+        let inner =
+            Arc::new(DebugApiInner { provider, eth_api: eth, blocking_task_guard, bad_blocks });
         Self { inner }
     }
 
@@ -67,6 +104,7 @@ where
         + ChainSpecProvider
         + StateProviderFactory
         + EvmEnvProvider
+        + ChangeSetReader
         + 'static,
     Eth: TraceExt + 'static,
 {
@@ -83,6 +121,20 @@ where
         cfg: CfgEnvWithHandlerCfg,
         block_env: BlockEnv,
         opts: GethDebugTracingOptions,
+    ) -> EthResult<Vec<TraceResult>> {
+        self.trace_block_until_cancelled(at, transactions, cfg, block_env, opts, || false).await
+    }
+
+    /// Same as [`Self::trace_block`], but stops before the next transaction once `is_cancelled`
+    /// returns true, in which case the traces of the transactions traced so far are returned.
+    async fn trace_block_until_cancelled(
+        &self,
+        at: BlockId,
+        transactions: Vec<TransactionSignedEcRecovered>,
+        cfg: CfgEnvWithHandlerCfg,
+        block_env: BlockEnv,
+        opts: GethDebugTracingOptions,
+        is_cancelled: impl Fn() -> bool + Send + 'static,
     ) -> EthResult<Vec<TraceResult>> {
         if transactions.is_empty() {
             // nothing to trace
@@ -98,6 +150,9 @@ where
                 let mut db = CacheDB::new(StateProviderDatabase::new(state));
                 let mut transactions = transactions.into_iter().enumerate().peekable();
                 while let Some((index, tx)) = transactions.next() {
+                    if is_cancelled() {
+                        break
+                    }
                     let tx_hash = tx.hash;
 
                     let env = EnvWithHandlerCfg {
@@ -148,37 +203,489 @@ where
         let (cfg, block_env) = self.eth_api().evm_env_for_raw_block(&block.header).await?;
         // we trace on top the block's parent block
         let parent = block.parent_hash;
+        let transactions = self.recover_transactions(block.number, block.body)?;
 
+        self.trace_block(parent.into(), transactions, cfg, block_env, opts).await
+    }
+
+    /// Recovers the signers of the transactions of the block with the given number.
+    fn recover_transactions(
+        &self,
+        number: BlockNumber,
+        body: Vec<TransactionSigned>,
+    ) -> EthResult<Vec<TransactionSignedEcRecovered>> {
         // Depending on EIP-2 we need to recover the transactions differently
-        let transactions =
-            if self.inner.provider.chain_spec().is_homestead_active_at_block(block.number) {
-                block
-                    .body
-                    .into_iter()
-                    .map(|tx| {
-                        tx.into_ecrecovered()
-                            .ok_or_else(|| EthApiError::InvalidTransactionSignature)
-                    })
-                    .collect::<EthResult<Vec<_>>>()?
-            } else {
-                block
-                    .body
-                    .into_iter()
-                    .map(|tx| {
-                        tx.into_ecrecovered_unchecked()
-                            .ok_or_else(|| EthApiError::InvalidTransactionSignature)
-                    })
-                    .collect::<EthResult<Vec<_>>>()?
+        if self.inner.provider.chain_spec().is_homestead_active_at_block(number) {
+            body.into_iter()
+                .map(|tx| {
+                    tx.into_ecrecovered().ok_or_else(|| EthApiError::InvalidTransactionSignature)
+                })
+                .collect()
+        } else {
+            body.into_iter()
+                .map(|tx| {
+                    tx.into_ecrecovered_unchecked()
+                        .ok_or_else(|| EthApiError::InvalidTransactionSignature)
+                })
+                .collect()
+        }
+    }
+
+    /// Returns the block with the given hash from the store of bad blocks.
+    fn bad_block(&self, block_hash: B256) -> EthResult<SealedBlock> {
+        self.inner
+            .bad_blocks
+            .get(&block_hash)
+            .ok_or_else(|| EthApiError::InvalidParams(format!("bad block {block_hash} not found")))
+    }
+
+    /// Returns all blocks that were rejected as invalid, newest first.
+    pub fn bad_blocks(&self) -> EthResult<Vec<RichBlock>> {
+        let mut blocks = Vec::with_capacity(self.inner.bad_blocks.len());
+        for block in self.inner.bad_blocks.blocks() {
+            let hash = block.hash();
+            let total_difficulty = self
+                .inner
+                .provider
+                .header_td(&block.parent_hash)?
+                .unwrap_or_default()
+                .saturating_add(block.difficulty);
+            let block = match block.try_seal_with_senders() {
+                Ok(block) => from_block(
+                    block.unseal(),
+                    total_difficulty,
+                    BlockTransactionsKind::Full,
+                    Some(hash),
+                )?,
+                // invalid signatures may be the reason the block was rejected, so we fall back to
+                // the transaction hashes
+                Err(block) => from_block(
+                    BlockWithSenders { block: block.unseal(), senders: Vec::new() },
+                    total_difficulty,
+                    BlockTransactionsKind::Hashes,
+                    Some(hash),
+                )?,
             };
+            blocks.push(block.into());
+        }
+        Ok(blocks)
+    }
+
+    /// Replays the bad block with the given hash on top of its parent and returns the trace of
+    /// each transaction.
+    pub async fn debug_trace_bad_block(
+        &self,
+        block_hash: B256,
+        opts: GethDebugTracingOptions,
+    ) -> EthResult<Vec<TraceResult>> {
+        let block = self.bad_block(block_hash)?;
+        let (cfg, block_env) = self.eth_api().evm_env_for_raw_block(&block.header).await?;
+        let parent = block.parent_hash;
+        let transactions = self.recover_transactions(block.number, block.body)?;
 
         self.trace_block(parent.into(), transactions, cfg, block_env, opts).await
     }
 
+    /// Replays the bad block with the given hash on top of its parent and writes an [EIP-3155]
+    /// trace of each transaction to a separate file in the temporary directory.
+    ///
+    /// Returns the paths of the written files.
+    ///
+    /// [EIP-3155]: https://eips.ethereum.org/EIPS/eip-3155
+    pub async fn debug_standard_trace_bad_block_to_file(
+        &self,
+        block_hash: B256,
+        opts: GethDefaultTracingOptions,
+    ) -> EthResult<Vec<String>> {
+        let block = self.bad_block(block_hash)?;
+        let (cfg, block_env) = self.eth_api().evm_env_for_raw_block(&block.header).await?;
+        let parent = block.parent_hash;
+        let transactions = self.recover_transactions(block.number, block.body)?;
+
+        let header = block.header;
+
+        let this = self.clone();
+        self.eth_api()
+            .spawn_with_state_at_block(parent.into(), move |state| {
+                let mut db = CacheDB::new(StateProviderDatabase::new(state));
+
+                // the transactions are executed on top of the system calls of the block
+                let chain_spec = this.inner.provider.chain_spec();
+                pre_block_beacon_root_contract_call(
+                    &mut db,
+                    Call::evm_config(this.eth_api()),
+                    chain_spec.as_ref(),
+                    &cfg,
+                    &block_env,
+                    header.number,
+                    header.timestamp,
+                    header.parent_beacon_block_root,
+                )
+                .map_err(|err| EthApiError::Internal(err.into()))?;
+                pre_block_blockhashes_update(
+                    &mut db,
+                    chain_spec.as_ref(),
+                    &block_env,
+                    header.number,
+                    parent,
+                )?;
+
+                let mut files = Vec::with_capacity(transactions.len());
+                for (index, tx) in transactions.into_iter().enumerate() {
+                    let path = std::env::temp_dir().join(format!(
+                        "block_0x{}-{index}-0x{}.jsonl",
+                        hex::encode(&block_hash[..4]),
+                        hex::encode(&tx.hash[..4])
+                    ));
+                    let file = TraceFile::create(&path)
+                        .map_err(|err| EthApiError::Internal(RethError::other(err)))?;
+                    let mut inspector =
+                        TracerEip3155::new(Box::new(file.clone())).without_summary();
+                    if opts.is_memory_enabled() {
+                        inspector = inspector.with_memory();
+                    }
+
+                    let env = EnvWithHandlerCfg {
+                        env: Env::boxed(
+                            cfg.cfg_env.clone(),
+                            block_env.clone(),
+                            Call::evm_config(this.eth_api()).tx_env(&tx),
+                        ),
+                        handler_cfg: cfg.handler_cfg,
+                    };
+                    let (res, _) = this.eth_api().inspect(&mut db, env, &mut inspector)?;
+                    drop(inspector);
+                    file.finish().map_err(|err| EthApiError::Internal(RethError::other(err)))?;
+                    db.commit(res.state);
+                    files.push(path.display().to_string());
+                }
+                Ok(files)
+            })
+            .await
+    }
+
+    /// Replays the block with the given hash on top of its parent and returns the state root after
+    /// each transaction.
+    ///
+    /// The block is looked up in the database first and in the store of bad blocks second. Each
+    /// root is computed by overlaying the changes of the block up to and including the transaction
+    /// on top of the parent's state.
+    pub async fn debug_intermediate_roots(&self, block_hash: B256) -> EthResult<Vec<B256>> {
+        let block = match self.inner.provider.block_by_hash(block_hash)? {
+            Some(block) => block,
+            None => self.bad_block(block_hash)?.unseal(),
+        };
+        let (cfg, block_env) = self.eth_api().evm_env_for_raw_block(&block.header).await?;
+        let parent = block.parent_hash;
+        let transactions = self.recover_transactions(block.number, block.body)?;
+        let header = block.header;
+
+        let this = self.clone();
+        self.eth_api()
+            .spawn_with_state_at_block(parent.into(), move |state| {
+                let provider = state.0;
+                let mut db = State::builder()
+                    .with_database(StateProviderDatabase::new(state))
+                    .with_bundle_update()
+                    .build();
+
+                // same as the block executor, empty accounts are only cleared after Spurious Dragon
+                let chain_spec = this.inner.provider.chain_spec();
+                db.set_state_clear_flag(
+                    chain_spec.is_spurious_dragon_active_at_block(header.number),
+                );
+                pre_block_beacon_root_contract_call(
+                    &mut db,
+                    Call::evm_config(this.eth_api()),
+                    chain_spec.as_ref(),
+                    &cfg,
+                    &block_env,
+                    header.number,
+                    header.timestamp,
+                    header.parent_beacon_block_root,
+                )
+                .map_err(|err| EthApiError::Internal(err.into()))?;
+                pre_block_blockhashes_update(
+                    &mut db,
+                    chain_spec.as_ref(),
+                    &block_env,
+                    header.number,
+                    parent,
+                )?;
+
+                let mut roots = Vec::with_capacity(transactions.len());
+                for tx in transactions {
+                    let env = EnvWithHandlerCfg {
+                        env: Env::boxed(
+                            cfg.cfg_env.clone(),
+                            block_env.clone(),
+                            Call::evm_config(this.eth_api()).tx_env(&tx),
+                        ),
+                        handler_cfg: cfg.handler_cfg,
+                    };
+                    let (res, _) = this.eth_api().transact(&mut db, env)?;
+                    db.commit(res.state);
+
+                    // snapshot the changes of the block so far and compute the root on top of the
+                    // parent's hashed state
+                    db.merge_transitions(BundleRetention::PlainState);
+                    let hashed_state = HashedPostState::from_bundle_state(&db.bundle_state.state);
+                    roots.push(provider.hashed_state_root(&hashed_state)?);
+                }
+                Ok(roots)
+            })
+            .await
+    }
+
+    /// Returns the addresses of all accounts modified between the two given blocks.
+    ///
+    /// If no end block is given, the accounts modified in the start block are returned, otherwise
+    /// the accounts modified after the start block up to and including the end block, same as
+    /// geth. The end block must be after the start block.
+    pub fn modified_accounts(
+        &self,
+        start_number: BlockNumber,
+        end_number: Option<BlockNumber>,
+    ) -> EthResult<Vec<Address>> {
+        let range = match end_number {
+            None => start_number..=start_number,
+            Some(end_number) if end_number <= start_number => {
+                return Err(EthApiError::InvalidParams(format!(
+                    "start block height ({start_number}) must be less than end block height \
+                     ({end_number})"
+                )))
+            }
+            Some(end_number) => start_number + 1..=end_number,
+        };
+        if *range.end() > self.inner.provider.best_block_number()? {
+            return Err(EthApiError::UnknownBlockNumber)
+        }
+        Ok(self.inner.provider.changed_addresses_with_range(range)?.into_iter().collect())
+    }
+
+    /// Returns the storage of the given account in the state after executing the transactions of
+    /// the block with the given hash up to the given transaction index.
+    ///
+    /// The storage is paged by hashed slot, starting at `key_start`.
+    pub async fn debug_storage_range_at(
+        &self,
+        block_hash: B256,
+        tx_idx: usize,
+        contract_address: Address,
+        key_start: B256,
+        max_result: usize,
+    ) -> EthResult<StorageRangeResult> {
+        let block = self
+            .eth_api()
+            .block_with_senders(block_hash.into())
+            .await?
+            .ok_or(EthApiError::UnknownBlockNumber)?;
+        if tx_idx > block.body.len() {
+            return Err(EthApiError::UnknownBlockOrTxIndex)
+        }
+        let (cfg, block_env, _) = self.eth_api().evm_env_at(block_hash.into()).await?;
+
+        // we replay the transactions before the given index on top of the parent block, if the
+        // index points past the last transaction, the entire block is replayed
+        let target_tx_hash = block.body.get(tx_idx).map(|tx| tx.hash).unwrap_or_default();
+        let state_at: BlockId = block.parent_hash.into();
+        let block_txs = block.into_transactions_ecrecovered();
+
+        let this = self.clone();
+        self.eth_api()
+            .spawn_with_state_at_block(state_at, move |state| {
+                let provider = state.0;
+                let mut db = CacheDB::new(StateProviderDatabase::new(state));
+                this.eth_api().replay_transactions_until(
+                    &mut db,
+                    cfg,
+                    block_env,
+                    block_txs,
+                    target_tx_hash,
+                )?;
+
+                // overlay the storage changes of the replayed transactions, the slots touched by
+                // them are the only ones we know the preimage of
+                let hashed_address = keccak256(contract_address);
+                let mut hashed_state = HashedPostState::default();
+                let mut preimages = HashMap::new();
+                if let Some(account) = db.accounts.get(&contract_address) {
+                    let wiped = matches!(
+                        account.account_state,
+                        AccountState::StorageCleared | AccountState::NotExisting
+                    );
+                    let storage = account.storage.iter().map(|(slot, value)| {
+                        let slot = B256::new(slot.to_be_bytes());
+                        let hashed_slot = keccak256(slot);
+                        preimages.insert(hashed_slot, slot);
+                        (hashed_slot, *value)
+                    });
+                    hashed_state
+                        .storages
+                        .insert(hashed_address, HashedStorage::from_iter(wiped, storage));
+                }
+
+                let range = provider.hashed_storage_range(
+                    &hashed_state,
+                    hashed_address,
+                    key_start,
+                    max_result,
+                )?;
+                let storage = range
+                    .entries
+                    .into_iter()
+                    .map(|(hashed_slot, value)| {
+                        let entry = StorageEntry {
+                            key: preimages.get(&hashed_slot).copied(),
+                            value: B256::new(value.to_be_bytes()),
+                        };
+                        (hashed_slot, entry)
+                    })
+                    .collect();
+                Ok(StorageRangeResult { storage, next_key: range.next_key })
+            })
+            .await
+    }
+
+    /// Returns the accounts of the state at the given block, paged by hashed address starting at
+    /// `start`.
+    ///
+    /// Like geth, up to `max_results` accounts are returned, or [`ACCOUNT_RANGE_MAX_RESULTS`] if
+    /// it's zero or exceeds the limit, and accounts whose address is unknown are skipped unless
+    /// `incompletes` is set.
+    ///
+    /// Note: reth does not store the preimages of hashed addresses, so accounts are only returned
+    /// if `incompletes` is set and are keyed by `pre(<hashed address>)`. At most
+    /// [`ACCOUNT_RANGE_MAX_SCANNED`] accounts are visited per page, and the page ends before the
+    /// account whose storage would exceed [`ACCOUNT_RANGE_MAX_STORAGE_SLOTS`] slots in total.
+    pub async fn debug_account_range(
+        &self,
+        block_id: BlockId,
+        start: Bytes,
+        max_results: usize,
+        nocode: bool,
+        nostorage: bool,
+        incompletes: bool,
+    ) -> EthResult<AccountRangeResult> {
+        if start.len() > B256::len_bytes() {
+            return Err(EthApiError::InvalidParams("start key too long".to_string()))
+        }
+        let mut start_key = B256::ZERO;
+        start_key[..start.len()].copy_from_slice(&start);
+        let max_results = if max_results == 0 || max_results > ACCOUNT_RANGE_MAX_RESULTS {
+            ACCOUNT_RANGE_MAX_RESULTS
+        } else {
+            max_results
+        };
+
+        let root = self
+            .inner
+            .provider
+            .header_by_id(block_id)?
+            .ok_or(EthApiError::UnknownBlockNumber)?
+            .state_root;
+
+        self.eth_api()
+            .spawn_with_state_at_block(block_id, move |state| {
+                let mut accounts = BTreeMap::new();
+                let mut scanned = 0;
+                let mut storage_slots = 0;
+
+                let mut next = Some(start_key);
+                'pages: while let Some(page_start) = next {
+                    let range = state.hashed_account_range(
+                        &HashedPostState::default(),
+                        page_start,
+                        max_results,
+                    )?;
+                    next = range.next_key;
+
+                    for (hashed_address, account) in range.entries {
+                        if accounts.len() == max_results || scanned == ACCOUNT_RANGE_MAX_SCANNED {
+                            next = Some(hashed_address);
+                            break 'pages
+                        }
+                        scanned += 1;
+
+                        // the address of the account is unknown
+                        if !incompletes {
+                            continue
+                        }
+
+                        let storage = if nostorage {
+                            None
+                        } else {
+                            let storage = state.hashed_storage_range(
+                                &HashedPostState::default(),
+                                hashed_address,
+                                B256::ZERO,
+                                ACCOUNT_RANGE_MAX_STORAGE_SLOTS - storage_slots,
+                            )?;
+                            if storage.next_key.is_some() {
+                                if accounts.is_empty() {
+                                    return Err(EthApiError::InvalidParams(format!(
+                                        "storage of account {hashed_address} exceeds \
+                                         {ACCOUNT_RANGE_MAX_STORAGE_SLOTS} slots, use \
+                                         debug_storageRangeAt instead"
+                                    )))
+                                }
+                                // the storage is returned in full on the next page
+                                next = Some(hashed_address);
+                                break 'pages
+                            }
+                            storage_slots += storage.entries.len();
+                            Some(
+                                storage
+                                    .entries
+                                    .into_iter()
+                                    .map(|(hashed_slot, value)| {
+                                        (hashed_slot, hex::encode(value.to_be_bytes_trimmed_vec()))
+                                    })
+                                    .collect::<BTreeMap<_, _>>(),
+                            )
+                            .filter(|storage| !storage.is_empty())
+                        };
+                        let code = match account.bytecode_hash {
+                            Some(code_hash) if !nocode => {
+                                state.bytecode_by_hash(code_hash)?.map(|code| code.original_bytes())
+                            }
+                            _ => None,
+                        };
+
+                        let account = DumpAccount {
+                            balance: account.balance.to_string(),
+                            nonce: account.nonce,
+                            code_hash: account.get_bytecode_hash(),
+                            code: code.filter(|code| !code.is_empty()),
+                            storage,
+                            address: None,
+                            address_hash: hashed_address,
+                        };
+                        accounts.insert(format!("pre({hashed_address})"), account);
+                    }
+                }
+
+                Ok(AccountRangeResult { root, accounts, next })
+            })
+            .await
+    }
+
     /// Replays a block and returns the trace of each transaction.
     pub async fn debug_trace_block(
         &self,
         block_id: BlockId,
         opts: GethDebugTracingOptions,
+    ) -> EthResult<Vec<TraceResult>> {
+        self.debug_trace_block_until_cancelled(block_id, opts, || false).await
+    }
+
+    /// Same as [`Self::debug_trace_block`], but stops before the next transaction once
+    /// `is_cancelled` returns true.
+    async fn debug_trace_block_until_cancelled(
+        &self,
+        block_id: BlockId,
+        opts: GethDebugTracingOptions,
+        is_cancelled: impl Fn() -> bool + Send + 'static,
     ) -> EthResult<Vec<TraceResult>> {
         let block_hash = self
             .inner
@@ -196,16 +703,80 @@ where
         // its parent block's state
         let state_at = block.parent_hash;
 
-        self.trace_block(
+        self.trace_block_until_cancelled(
             state_at.into(),
             block.into_transactions_ecrecovered().collect(),
             cfg,
             block_env,
             opts,
+            is_cancelled,
         )
         .await
     }
 
+    /// Resolves the range of blocks traced by `debug_traceChain`, which excludes the start block.
+    fn trace_chain_range(
+        &self,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+    ) -> EthResult<RangeInclusive<BlockNumber>> {
+        let block_number = |number| -> EthResult<BlockNumber> {
+            self.inner.provider.convert_block_number(number)?.ok_or(EthApiError::UnknownBlockNumber)
+        };
+        let start = block_number(start_exclusive)?;
+        let end = block_number(end_inclusive)?;
+        if start >= end {
+            return Err(EthApiError::InvalidParams(format!(
+                "end block #{end} must be later than start block #{start}"
+            )))
+        }
+        Ok(start + 1..=end)
+    }
+
+    /// Traces all blocks in the given range and sends the traces of each block to the subscription
+    /// as soon as the block has been traced.
+    ///
+    /// Each block is traced with a permit of the blocking task guard. Tracing stops once the
+    /// subscription is closed, before the next transaction of the block that is currently traced.
+    /// If a block can't be traced, the error is returned and no further blocks are traced.
+    async fn trace_chain(
+        self,
+        sink: SubscriptionSink,
+        range: RangeInclusive<BlockNumber>,
+        opts: GethDebugTracingOptions,
+    ) -> EthResult<()> {
+        // dropping a clone of the sink would unregister the subscription, so it's shared instead
+        let sink = Arc::new(sink);
+        for number in range {
+            if sink.is_closed() {
+                break
+            }
+
+            let hash =
+                self.inner.provider.block_hash(number)?.ok_or(EthApiError::UnknownBlockNumber)?;
+            let traces = {
+                let _permit = self.acquire_trace_permit().await;
+                let sink = Arc::clone(&sink);
+                self.debug_trace_block_until_cancelled(hash.into(), opts.clone(), move || {
+                    sink.is_closed()
+                })
+                .await?
+            };
+            if sink.is_closed() {
+                break
+            }
+
+            let result = BlockTraceResult { block: U256::from(number), hash, traces };
+            let msg = SubscriptionMessage::from_json(&result)
+                .map_err(|err| EthApiError::Internal(RethError::other(err)))?;
+            if sink.send(msg).await.is_err() {
+                // subscription closed
+                break
+            }
+        }
+        Ok(())
+    }
+
     /// Trace the transaction according to the provided options.
     ///
     /// Ref: <https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers>
@@ -645,6 +1216,7 @@ where
         + ChainSpecProvider
         + StateProviderFactory
         + EvmEnvProvider
+        + ChangeSetReader
         + 'static,
     Eth: EthApiSpec + EthTransactions + TraceExt + 'static,
 {
@@ -720,16 +1292,36 @@ where
 
     /// Handler for `debug_getBadBlocks`
     async fn bad_blocks(&self) -> RpcResult<Vec<RichBlock>> {
-        Err(internal_rpc_err("unimplemented"))
+        Ok(Self::bad_blocks(self)?)
     }
 
-    /// Handler for `debug_traceChain`
-    async fn debug_trace_chain(
+    /// Handler for `debug_subscribe`
+    async fn debug_subscribe(
         &self,
-        _start_exclusive: BlockNumberOrTag,
-        _end_inclusive: BlockNumberOrTag,
-    ) -> RpcResult<Vec<BlockTraceResult>> {
-        Err(internal_rpc_err("unimplemented"))
+        pending: PendingSubscriptionSink,
+        kind: DebugSubscriptionKind,
+        start_exclusive: BlockNumberOrTag,
+        end_inclusive: BlockNumberOrTag,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> jsonrpsee::core::SubscriptionResult {
+        let range = match kind {
+            DebugSubscriptionKind::TraceChain => {
+                self.trace_chain_range(start_exclusive, end_inclusive)
+            }
+        };
+        let range = match range {
+            Ok(range) => range,
+            Err(err) => {
+                pending.reject(err).await;
+                return Ok(())
+            }
+        };
+
+        let sink = pending.accept().await?;
+        // an error closes the subscription with an error notification
+        self.clone().trace_chain(sink, range, opts.unwrap_or_default()).await?;
+
+        Ok(())
     }
 
     /// Handler for `debug_traceBlock`
@@ -797,16 +1389,27 @@ where
         Ok(())
     }
 
+    /// Handler for `debug_accountRange`
     async fn debug_account_range(
         &self,
-        _block_number: BlockNumberOrTag,
-        _start: Bytes,
-        _max_results: u64,
-        _nocode: bool,
-        _nostorage: bool,
-        _incompletes: bool,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_id: BlockId,
+        start: Bytes,
+        max_results: u64,
+        nocode: bool,
+        nostorage: bool,
+        incompletes: bool,
+    ) -> RpcResult<AccountRangeResult> {
+        let _permit = self.acquire_trace_permit().await;
+        Ok(Self::debug_account_range(
+            self,
+            block_id,
+            start,
+            max_results as usize,
+            nocode,
+            nostorage,
+            incompletes,
+        )
+        .await?)
     }
 
     async fn debug_block_profile(&self, _file: String, _seconds: u64) -> RpcResult<()> {
@@ -861,32 +1464,41 @@ where
         Ok(())
     }
 
+    /// Handler for `debug_getModifiedAccountsByHash`
     async fn debug_get_modified_accounts_by_hash(
         &self,
-        _start_hash: B256,
-        _end_hash: B256,
-    ) -> RpcResult<()> {
-        Ok(())
+        start_hash: B256,
+        end_hash: Option<B256>,
+    ) -> RpcResult<Vec<Address>> {
+        let block_number = |hash| -> EthResult<BlockNumber> {
+            self.inner.provider.block_number(hash)?.ok_or(EthApiError::UnknownBlockNumber)
+        };
+        let start_number = block_number(start_hash)?;
+        let end_number = end_hash.map(block_number).transpose()?;
+        Ok(Self::modified_accounts(self, start_number, end_number)?)
     }
 
+    /// Handler for `debug_getModifiedAccountsByNumber`
     async fn debug_get_modified_accounts_by_number(
         &self,
-        _start_number: u64,
-        _end_number: u64,
-    ) -> RpcResult<()> {
-        Ok(())
+        start_number: u64,
+        end_number: Option<u64>,
+    ) -> RpcResult<Vec<Address>> {
+        Ok(Self::modified_accounts(self, start_number, end_number)?)
     }
 
     async fn debug_go_trace(&self, _file: String, _seconds: u64) -> RpcResult<()> {
         Ok(())
     }
 
+    /// Handler for `debug_intermediateRoots`
     async fn debug_intermediate_roots(
         &self,
-        _block_hash: B256,
+        block_hash: B256,
         _opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()> {
-        Ok(())
+    ) -> RpcResult<Vec<B256>> {
+        let _permit = self.acquire_trace_permit().await;
+        Ok(Self::debug_intermediate_roots(self, block_hash).await?)
     }
 
     async fn debug_mem_stats(&self) -> RpcResult<()> {
@@ -933,12 +1545,15 @@ where
         Ok(())
     }
 
+    /// Handler for `debug_standardTraceBadBlockToFile`
     async fn debug_standard_trace_bad_block_to_file(
         &self,
-        _block: BlockNumberOrTag,
-        _opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_hash: B256,
+        opts: Option<GethDefaultTracingOptions>,
+    ) -> RpcResult<Vec<String>> {
+        let _permit = self.acquire_trace_permit().await;
+        Ok(Self::debug_standard_trace_bad_block_to_file(self, block_hash, opts.unwrap_or_default())
+            .await?)
     }
 
     async fn debug_standard_trace_block_to_file(
@@ -965,23 +1580,35 @@ where
         Ok(())
     }
 
+    /// Handler for `debug_storageRangeAt`
     async fn debug_storage_range_at(
         &self,
-        _block_hash: B256,
-        _tx_idx: usize,
-        _contract_address: Address,
-        _key_start: B256,
-        _max_result: u64,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_hash: B256,
+        tx_idx: usize,
+        contract_address: Address,
+        key_start: B256,
+        max_result: u64,
+    ) -> RpcResult<StorageRangeResult> {
+        let _permit = self.acquire_trace_permit().await;
+        Ok(Self::debug_storage_range_at(
+            self,
+            block_hash,
+            tx_idx,
+            contract_address,
+            key_start,
+            max_result as usize,
+        )
+        .await?)
     }
 
+    /// Handler for `debug_traceBadBlock`
     async fn debug_trace_bad_block(
         &self,
-        _block_hash: B256,
-        _opts: Option<GethDebugTracingCallOptions>,
-    ) -> RpcResult<()> {
-        Ok(())
+        block_hash: B256,
+        opts: Option<GethDebugTracingOptions>,
+    ) -> RpcResult<Vec<TraceResult>> {
+        let _permit = self.acquire_trace_permit().await;
+        Ok(Self::debug_trace_bad_block(self, block_hash, opts.unwrap_or_default()).await?)
     }
 
     async fn debug_verbosity(&self, _level: usize) -> RpcResult<()> {
@@ -1017,6 +1644,56 @@ impl<Provider, Eth> Clone for DebugApi<Provider, Eth> {
     }
 }
 
+/// A buffered trace file that keeps the first write error, which [`TracerEip3155`] discards.
+#[derive(Clone)]
+struct TraceFile {
+    inner: Rc<RefCell<TraceFileInner>>,
+}
+
+struct TraceFileInner {
+    writer: BufWriter<File>,
+    error: Option<io::Error>,
+}
+
+impl TraceFile {
+    /// Creates the file at the given path.
+    fn create(path: &std::path::Path) -> io::Result<Self> {
+        let writer = BufWriter::new(File::create(path)?);
+        Ok(Self { inner: Rc::new(RefCell::new(TraceFileInner { writer, error: None })) })
+    }
+
+    /// Flushes the file and returns the first error that occurred while writing it, if any.
+    fn finish(self) -> io::Result<()> {
+        let mut inner = self.inner.borrow_mut();
+        if let Some(err) = inner.error.take() {
+            return Err(err)
+        }
+        inner.writer.flush()
+    }
+
+    fn record<T>(&self, res: io::Result<T>) -> io::Result<T> {
+        if let Err(err) = &res {
+            self.inner
+                .borrow_mut()
+                .error
+                .get_or_insert_with(|| io::Error::new(err.kind(), err.to_string()));
+        }
+        res
+    }
+}
+
+impl Write for TraceFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let res = self.inner.borrow_mut().writer.write(buf);
+        self.record(res)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        let res = self.inner.borrow_mut().writer.flush();
+        self.record(res)
+    }
+}
+
 struct DebugApiInner<Provider, Eth> {
     /// The provider that can interact with the chain.
     provider: Provider,
@@ -1024,4 +1701,6 @@ struct DebugApiInner<Provider, Eth> {
     eth_api: Eth,
     // restrict the number of concurrent calls to blocking calls
     blocking_task_guard: BlockingTaskGuard,
+    /// The blocks the engine rejected as invalid
+    bad_blocks: BadBlockStore,
 }
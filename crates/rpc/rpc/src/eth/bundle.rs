@@ -7,7 +7,7 @@ use reth_evm::{ConfigureEvm, ConfigureEvmEnv};
 use reth_primitives::{
     keccak256,
     revm_primitives::db::{DatabaseCommit, DatabaseRef},
-    PooledTransactionsElement, U256,
+    PooledTransactionsElement, B256, U256,
 };
 use reth_revm::database::StateProviderDatabase;
 use reth_rpc_types::mev::{EthCallBundle, EthCallBundleResponse, EthCallBundleTransactionResult};
@@ -293,4 +293,22 @@ pub enum EthBundleError {
     /// [`MAX_BLOB_GAS_PER_BLOCK`].
     #[error("blob gas usage exceeds the limit of {MAX_BLOB_GAS_PER_BLOCK} gas per block.")]
     Eip4844BlobGasExceeded,
+    /// Thrown if a bundle references a transaction by hash that is unknown.
+    #[error("unknown bundle transaction {0}")]
+    UnknownBundleTransaction(B256),
+    /// Thrown if a bundle that is stored for later inclusion contains blob transactions.
+    #[error("blob transactions are not supported in bundles")]
+    BlobTransactionsNotSupported,
+    /// Thrown if a refund references a transaction index that is not part of the bundle.
+    #[error("refund index {0} out of bounds")]
+    InvalidRefundIndex(usize),
+    /// Thrown if the refund percentages of a bundle add up to more than 100.
+    #[error("refund percent exceeds 100")]
+    InvalidRefundPercent,
+    /// Thrown if the bundle can't be included in the targeted block.
+    #[error("bundle is not valid for block {0}")]
+    BundleNotValidForBlock(u64),
+    /// Thrown if the simulation of a bundle failed.
+    #[error("bundle simulation failed: {0}")]
+    SimulationFailed(String),
 }
@@ -19,6 +19,7 @@ use reth_tasks::{
     pool::{BlockingTaskGuard, BlockingTaskPool},
     TaskExecutor, TaskSpawner, TokioTaskExecutor,
 };
+use reth_transaction_pool::ImpersonatedAccounts;
 use tokio::sync::{AcquireError, Mutex, OwnedSemaphorePermit};
 
 /// `Eth` API implementation.
@@ -203,6 +204,8 @@ pub struct EthApiInner<Provider, Pool, Network, EvmConfig> {
     evm_config: EvmConfig,
     /// Allows forwarding received raw transactions
     raw_transaction_forwarder: parking_lot::RwLock<Option<Arc<dyn RawTransactionForwarder>>>,
+    /// Accounts that can send transactions without a signer
+    impersonated_accounts: parking_lot::RwLock<Option<ImpersonatedAccounts>>,
     /// Guard for getproof calls
     blocking_task_guard: BlockingTaskGuard,
 }
@@ -255,6 +258,7 @@ where
             fee_history_cache,
             evm_config,
             raw_transaction_forwarder: parking_lot::RwLock::new(raw_transaction_forwarder),
+            impersonated_accounts: Default::default(),
             blocking_task_guard: BlockingTaskGuard::new(proof_permits),
         }
     }
@@ -309,6 +313,18 @@ impl<Provider, Pool, Network, EvmConfig> EthApiInner<Provider, Pool, Network, Ev
         self.raw_transaction_forwarder.read().clone()
     }
 
+    /// Returns the accounts that can send transactions without a signer, if configured.
+    #[inline]
+    pub fn impersonated_accounts(&self) -> Option<ImpersonatedAccounts> {
+        self.impersonated_accounts.read().clone()
+    }
+
+    /// Sets the accounts that can send transactions without a signer.
+    #[inline]
+    pub fn set_impersonated_accounts(&self, accounts: ImpersonatedAccounts) {
+        self.impersonated_accounts.write().replace(accounts);
+    }
+
     /// Returns the gas cap.
     #[inline]
     pub const fn gas_cap(&self) -> u64 {
@@ -10,6 +10,7 @@ use reth_rpc_eth_api::helpers::{signer::Result, AddDevSigners, EthSigner};
 use reth_rpc_eth_types::SignError;
 use reth_rpc_types::TypedTransactionRequest;
 use reth_rpc_types_compat::transaction::to_primitive_transaction;
+use reth_transaction_pool::ImpersonatedAccounts;
 use secp256k1::SecretKey;
 
 use crate::EthApi;
@@ -24,6 +25,10 @@ impl<Provider, Pool, Network, EvmConfig> AddDevSigners
     fn with_dev_accounts(&self) {
         *self.signers().write() = DevSigner::random_signers(20)
     }
+
+    fn with_impersonated_accounts(&self, accounts: ImpersonatedAccounts) {
+        self.inner.set_impersonated_accounts(accounts)
+    }
 }
 
 /// Holds developer keys
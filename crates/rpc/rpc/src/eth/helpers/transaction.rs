@@ -6,7 +6,7 @@ use reth_rpc_eth_api::{
     RawTransactionForwarder,
 };
 use reth_rpc_eth_types::EthStateCache;
-use reth_transaction_pool::TransactionPool;
+use reth_transaction_pool::{ImpersonatedAccounts, TransactionPool};
 
 use crate::EthApi;
 
@@ -27,6 +27,11 @@ where
         self.inner.raw_tx_forwarder()
     }
 
+    #[inline]
+    fn impersonated_accounts(&self) -> Option<ImpersonatedAccounts> {
+        self.inner.impersonated_accounts()
+    }
+
     #[inline]
     fn signers(&self) -> &parking_lot::RwLock<Vec<Box<dyn EthSigner>>> {
         self.inner.signers()
@@ -0,0 +1,647 @@
+//! `mev` namespace implementation: MEV-Share style bundle submission and simulation.
+
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use jsonrpsee::core::RpcResult;
+use reth_evm::{ConfigureEvm, ConfigureEvmEnv};
+use reth_primitives::{
+    revm_primitives::db::{DatabaseCommit, DatabaseRef},
+    BlockId, IntoRecoveredTransaction, PooledTransactionsElement, TransactionSignedEcRecovered,
+    U256,
+};
+use reth_provider::{BlockNumReader, ChainSpecProvider, HeaderProvider};
+use reth_revm::database::StateProviderDatabase;
+use reth_rpc_api::MevApiServer;
+use reth_rpc_eth_api::helpers::{Call, EthTransactions, LoadPendingBlock};
+use reth_rpc_eth_types::{utils::recover_raw_transaction, EthApiError, EthResult};
+use reth_rpc_types::mev::{
+    BundleItem, SendBundleRequest, SendBundleResponse, SimBundleLogs, SimBundleOverrides,
+    SimBundleResponse,
+};
+use reth_tasks::pool::BlockingTaskGuard;
+use reth_transaction_pool::{
+    bundle::{BundleRefund, BundleRefundConfig, BundleTransaction},
+    BundlePool, MevBundle, TransactionPool,
+};
+use revm::{
+    db::CacheDB,
+    primitives::{EVMError, ResultAndState, TxEnv},
+};
+use revm_primitives::{EnvWithHandlerCfg, SpecId};
+
+use crate::eth::bundle::EthBundleError;
+
+/// The default timeout of a bundle simulation.
+const DEFAULT_SIM_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// `mev` API implementation.
+///
+/// Bundles submitted via `mev_sendBundle` are stored in the [`BundlePool`], from where they are
+/// offered to the payload builder.
+pub struct MevApi<Eth, Pool> {
+    /// All nested fields bundled together.
+    inner: Arc<MevApiInner<Eth, Pool>>,
+}
+
+impl<Eth, Pool> MevApi<Eth, Pool> {
+    /// Create a new `MevApi` instance.
+    pub fn new(
+        eth_api: Eth,
+        pool: Pool,
+        bundle_pool: BundlePool,
+        blocking_task_guard: BlockingTaskGuard,
+    ) -> Self {
+        Self { inner: Arc::new(MevApiInner { eth_api, pool, bundle_pool, blocking_task_guard }) }
+    }
+
+    /// Returns the pool the submitted bundles are stored in.
+    pub fn bundle_pool(&self) -> &BundlePool {
+        &self.inner.bundle_pool
+    }
+}
+
+impl<Eth, Pool> MevApi<Eth, Pool>
+where
+    Eth: EthTransactions + LoadPendingBlock + Call + 'static,
+    Pool: TransactionPool + 'static,
+{
+    /// Validates the bundle and adds it to the bundle pool.
+    ///
+    /// If the bundle targets the pending block, it is simulated on top of the pending state first
+    /// and rejected if the simulation fails.
+    ///
+    /// Refunds are paid by the payload builder when the bundle is included, see
+    /// [`BundleRefund`].
+    pub async fn send_bundle(&self, request: SendBundleRequest) -> EthResult<SendBundleResponse> {
+        let bundle = self.parse_bundle(request)?;
+
+        let pending_block =
+            LoadPendingBlock::provider(&self.inner.eth_api).best_block_number()? + 1;
+        if *bundle.block_range().end() < pending_block {
+            return Err(EthApiError::InvalidParams(
+                EthBundleError::BundleNotValidForBlock(pending_block).to_string(),
+            ))
+        }
+
+        if bundle.is_valid_for_block(pending_block) {
+            let res = self.simulate(bundle.clone(), SimBundleOverrides::default()).await?;
+            if let Some(err) = res.error {
+                return Err(EthApiError::InvalidParams(
+                    EthBundleError::SimulationFailed(err).to_string(),
+                ))
+            }
+        }
+
+        self.inner.bundle_pool.remove_expired(pending_block);
+        let bundle_hash = self
+            .inner
+            .bundle_pool
+            .add_bundle(bundle)
+            .map_err(|err| EthApiError::InvalidParams(err.to_string()))?;
+
+        Ok(SendBundleResponse { bundle_hash })
+    }
+
+    /// Simulates the bundle on top of the pending state, or the state of the given parent block.
+    pub async fn sim_bundle(
+        &self,
+        request: SendBundleRequest,
+        overrides: SimBundleOverrides,
+    ) -> EthResult<SimBundleResponse> {
+        let bundle = self.parse_bundle(request)?;
+        self.simulate(bundle, overrides).await
+    }
+
+    /// Converts the request into a fully matched [`MevBundle`].
+    ///
+    /// Transactions that are referenced by hash are looked up in the transaction pool.
+    fn parse_bundle(&self, request: SendBundleRequest) -> EthResult<MevBundle> {
+        let SendBundleRequest { inclusion, bundle_body, validity, .. } = request;
+        if bundle_body.is_empty() {
+            return Err(EthApiError::InvalidParams(
+                EthBundleError::EmptyBundleTransactions.to_string(),
+            ))
+        }
+        if inclusion.block == 0 {
+            return Err(EthApiError::InvalidParams(
+                EthBundleError::BundleMissingBlockNumber.to_string(),
+            ))
+        }
+
+        let mut transactions = Vec::with_capacity(bundle_body.len());
+        for item in bundle_body {
+            let tx = match item {
+                BundleItem::Hash { hash } => {
+                    let transaction = self
+                        .inner
+                        .pool
+                        .get(&hash)
+                        .ok_or_else(|| {
+                            EthApiError::InvalidParams(
+                                EthBundleError::UnknownBundleTransaction(hash).to_string(),
+                            )
+                        })?
+                        .to_recovered_transaction();
+                    BundleTransaction { transaction, can_revert: false }
+                }
+                BundleItem::Tx { tx, can_revert } => {
+                    let (tx, signer) = recover_raw_transaction(tx)?.into_components();
+                    if let PooledTransactionsElement::BlobTransaction(_) = tx {
+                        return Err(EthApiError::InvalidParams(
+                            EthBundleError::BlobTransactionsNotSupported.to_string(),
+                        ))
+                    }
+                    let transaction = TransactionSignedEcRecovered::from_signed_transaction(
+                        tx.into_transaction(),
+                        signer,
+                    );
+                    BundleTransaction { transaction, can_revert }
+                }
+            };
+            transactions.push(tx);
+        }
+
+        let mut refunds = Vec::new();
+        let mut refund_config = Vec::new();
+        if let Some(validity) = validity {
+            for refund in validity.refund.unwrap_or_default() {
+                let body_idx = refund.body_idx as usize;
+                if body_idx >= transactions.len() {
+                    return Err(EthApiError::InvalidParams(
+                        EthBundleError::InvalidRefundIndex(body_idx).to_string(),
+                    ))
+                }
+                refunds.push(BundleRefund { body_idx, percent: refund.percent });
+            }
+            refund_config = validity
+                .refund_config
+                .unwrap_or_default()
+                .into_iter()
+                .map(|config| BundleRefundConfig {
+                    address: config.address,
+                    percent: config.percent,
+                })
+                .collect::<Vec<_>>();
+            if refunds.iter().map(|refund| refund.percent).sum::<u64>() > 100 ||
+                refund_config.iter().map(|config| config.percent).sum::<u64>() > 100
+            {
+                return Err(EthApiError::InvalidParams(
+                    EthBundleError::InvalidRefundPercent.to_string(),
+                ))
+            }
+        }
+
+        let max_block = inclusion.max_block.unwrap_or(inclusion.block);
+        Ok(MevBundle::new(inclusion.block, max_block, transactions, refunds)
+            .with_refund_config(refund_config))
+    }
+
+    /// Simulates the bundle with the given overrides.
+    ///
+    /// A bundle that is invalid, because one of its transactions is invalid or reverts without
+    /// being allowed to, results in an unsuccessful [`SimBundleResponse`].
+    ///
+    /// The number of concurrent simulations is bounded by the blocking task guard. If the
+    /// simulation times out, it is cancelled before the next transaction is executed.
+    async fn simulate(
+        &self,
+        bundle: MevBundle,
+        overrides: SimBundleOverrides,
+    ) -> EthResult<SimBundleResponse> {
+        let SimBundleOverrides {
+            parent_block,
+            block_number,
+            coinbase,
+            timestamp,
+            gas_limit,
+            base_fee,
+            timeout,
+        } = overrides;
+
+        let (cfg, mut block_env, at) = if let Some(parent_block) = parent_block {
+            let (cfg, mut block_env, at) = self.inner.eth_api.evm_env_at(parent_block).await?;
+            // derive the header values of the next block from the parent block
+            let parent_number = block_env.number.saturating_to::<u64>();
+            block_env.number = U256::from(parent_number + 1);
+            block_env.timestamp += U256::from(12);
+            if cfg.handler_cfg.spec_id.is_enabled_in(SpecId::LONDON) {
+                let provider = LoadPendingBlock::provider(&self.inner.eth_api);
+                let parent = provider
+                    .header_by_number(parent_number)?
+                    .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+                if let Some(base_fee) = parent.next_block_base_fee(
+                    provider.chain_spec().base_fee_params_at_block(parent_number),
+                ) {
+                    block_env.basefee = U256::from(base_fee);
+                }
+            }
+            (cfg, block_env, at)
+        } else {
+            // simulate on top of the pending state by default
+            self.inner.eth_api.evm_env_at(BlockId::pending()).await?
+        };
+        let state_block = block_env.number.saturating_to::<u64>().saturating_sub(1);
+
+        if let Some(block_number) = block_number {
+            block_env.number = U256::from(block_number);
+        }
+        if let Some(coinbase) = coinbase {
+            block_env.coinbase = coinbase;
+        }
+        if let Some(timestamp) = timestamp {
+            block_env.timestamp = U256::from(timestamp);
+        }
+        if let Some(gas_limit) = gas_limit {
+            block_env.gas_limit = U256::from(gas_limit);
+        }
+        if let Some(base_fee) = base_fee {
+            block_env.basefee = U256::from(base_fee);
+        }
+
+        let block_number = block_env.number.saturating_to::<u64>();
+        if !bundle.is_valid_for_block(block_number) {
+            return Err(EthApiError::InvalidParams(
+                EthBundleError::BundleNotValidForBlock(block_number).to_string(),
+            ))
+        }
+
+        let timeout = timeout.map(Duration::from_secs).unwrap_or(DEFAULT_SIM_TIMEOUT);
+        // cancels the simulation once this future completes, times out or is dropped
+        let cancel = CancelOnDrop::default();
+        let cancelled = cancel.0.clone();
+
+        // the permit is moved into the simulation, so that it is held until the simulation
+        // actually stops
+        let permit = self.inner.blocking_task_guard.clone().acquire_owned().await;
+        let eth_api = self.inner.eth_api.clone();
+        let sim = self.inner.eth_api.spawn_with_state_at_block(at, move |state| {
+            let _permit = permit;
+            let failed = |err: String| SimBundleResponse {
+                success: false,
+                error: Some(err),
+                state_block,
+                mev_gas_price: 0,
+                profit: 0,
+                refundable_value: 0,
+                gas_used: 0,
+                logs: None,
+            };
+
+            let coinbase = block_env.coinbase;
+            let env = EnvWithHandlerCfg::new_with_cfg_env(cfg, block_env, TxEnv::default());
+            let db = CacheDB::new(StateProviderDatabase::new(state));
+
+            let initial_coinbase =
+                DatabaseRef::basic_ref(&db, coinbase)?.map(|acc| acc.balance).unwrap_or_default();
+            let mut coinbase_balance = initial_coinbase;
+            let mut refundable_value = U256::ZERO;
+            let mut gas_used = 0u64;
+            let mut logs = Vec::with_capacity(bundle.transactions().len());
+
+            // the value generated by transactions that receive refunds is not refundable
+            let refund_indices =
+                bundle.refunds().iter().map(|refund| refund.body_idx).collect::<HashSet<_>>();
+
+            let mut evm = Call::evm_config(&eth_api).evm_with_env(db, env);
+
+            for (idx, tx) in bundle.transactions().iter().enumerate() {
+                if cancelled.load(Ordering::Relaxed) {
+                    return Err(EthApiError::ExecutionTimedOut(timeout))
+                }
+
+                Call::evm_config(&eth_api).fill_tx_env(
+                    evm.tx_mut(),
+                    &tx.transaction,
+                    tx.transaction.signer(),
+                );
+                let ResultAndState { result, state } = match evm.transact() {
+                    Ok(res) => res,
+                    Err(EVMError::Transaction(err)) => {
+                        return Ok(failed(format!("tx {idx} is invalid: {err}")))
+                    }
+                    Err(err) => return Err(err.into()),
+                };
+
+                if !result.is_success() && !tx.can_revert {
+                    return Ok(failed(format!("tx {idx} reverted")))
+                }
+                gas_used += result.gas_used();
+
+                let balance =
+                    state.get(&coinbase).map(|acc| acc.info.balance).unwrap_or(coinbase_balance);
+                if !refund_indices.contains(&idx) {
+                    refundable_value += balance.saturating_sub(coinbase_balance);
+                }
+                coinbase_balance = balance;
+
+                logs.push(SimBundleLogs { tx_logs: Some(result.into_logs()), bundle_logs: None });
+
+                // need to apply the state changes of this transaction before executing the next
+                evm.context.evm.db.commit(state);
+            }
+
+            let profit = coinbase_balance.saturating_sub(initial_coinbase);
+            let mev_gas_price = profit.checked_div(U256::from(gas_used)).unwrap_or_default();
+
+            Ok(SimBundleResponse {
+                success: true,
+                error: None,
+                state_block,
+                mev_gas_price: mev_gas_price.saturating_to(),
+                profit: profit.saturating_to(),
+                refundable_value: refundable_value.saturating_to(),
+                gas_used,
+                logs: Some(logs),
+            })
+        });
+
+        tokio::time::timeout(timeout, sim)
+            .await
+            .map_err(|_| EthApiError::ExecutionTimedOut(timeout))?
+    }
+}
+
+/// Cancels a bundle simulation when dropped.
+#[derive(Debug, Default)]
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
+#[async_trait]
+impl<Eth, Pool> MevApiServer for MevApi<Eth, Pool>
+where
+    Eth: EthTransactions + LoadPendingBlock + Call + 'static,
+    Pool: TransactionPool + 'static,
+{
+    async fn send_bundle(&self, request: SendBundleRequest) -> RpcResult<SendBundleResponse> {
+        Ok(Self::send_bundle(self, request).await?)
+    }
+
+    async fn sim_bundle(
+        &self,
+        bundle: SendBundleRequest,
+        sim_overrides: SimBundleOverrides,
+    ) -> RpcResult<SimBundleResponse> {
+        Ok(Self::sim_bundle(self, bundle, sim_overrides).await?)
+    }
+}
+
+/// Container type for `MevApi` internals
+#[derive(Debug)]
+struct MevApiInner<Eth, Pool> {
+    /// Access to commonly used code of the `eth` namespace
+    eth_api: Eth,
+    /// The transaction pool used to look up transactions that are referenced by hash.
+    pool: Pool,
+    /// The pool the submitted bundles are stored in.
+    bundle_pool: BundlePool,
+    /// Restricts the number of concurrent bundle simulations.
+    blocking_task_guard: BlockingTaskGuard,
+}
+
+impl<Eth, Pool> std::fmt::Debug for MevApi<Eth, Pool> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MevApi").finish_non_exhaustive()
+    }
+}
+
+impl<Eth, Pool> Clone for MevApi<Eth, Pool> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EthApi;
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_network_api::noop::NoopNetwork;
+    use reth_primitives::{
+        public_key_to_address, Address, Bytes, Header, Transaction, TxEip1559, TxKind, B256,
+    };
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_rpc_eth_types::{
+        EthStateCache, FeeHistoryCache, FeeHistoryCacheConfig, GasPriceOracle,
+    };
+    use reth_rpc_server_types::constants::{DEFAULT_ETH_PROOF_WINDOW, DEFAULT_PROOF_PERMITS};
+    use reth_rpc_types::mev::{ProtocolVersion, Refund, RefundConfig, Validity};
+    use reth_tasks::pool::BlockingTaskPool;
+    use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
+    use reth_transaction_pool::test_utils::{testing_pool, TestPool};
+    use secp256k1::Keypair;
+
+    const GWEI: u128 = 1_000_000_000;
+
+    type TestMevApi =
+        MevApi<EthApi<MockEthProvider, TestPool, NoopNetwork, EthEvmConfig>, TestPool>;
+
+    /// A chain with the blocks 0 and 1, a funded account and a contract that always reverts.
+    struct TestChain {
+        api: TestMevApi,
+        genesis_hash: B256,
+        signer: Keypair,
+        reverts: Address,
+    }
+
+    impl TestChain {
+        fn new() -> Self {
+            let provider = MockEthProvider::default();
+            let genesis = Header {
+                number: 0,
+                gas_limit: 30_000_000,
+                base_fee_per_gas: Some(GWEI as u64),
+                ..Default::default()
+            };
+            let genesis_hash = B256::with_last_byte(1);
+            provider.add_header(genesis_hash, genesis);
+            provider
+                .add_header(B256::with_last_byte(2), Header { number: 1, ..Default::default() });
+
+            let signer = generators::generate_keys(&mut generators::rng(), 1)[0];
+            provider.add_account(
+                public_key_to_address(signer.public_key()),
+                ExtendedAccount::new(0, U256::from(10).pow(U256::from(18))),
+            );
+            // PUSH0 PUSH0 REVERT
+            let reverts = Address::with_last_byte(0xaa);
+            provider.add_account(
+                reverts,
+                ExtendedAccount::new(0, U256::ZERO)
+                    .with_bytecode(Bytes::from_static(&[0x5f, 0x5f, 0xfd])),
+            );
+
+            let evm_config = EthEvmConfig::default();
+            let cache = EthStateCache::spawn(provider.clone(), Default::default(), evm_config);
+            let eth_api = EthApi::new(
+                provider.clone(),
+                testing_pool(),
+                NoopNetwork::default(),
+                cache.clone(),
+                GasPriceOracle::new(provider, Default::default(), cache.clone()),
+                30_000_000,
+                DEFAULT_ETH_PROOF_WINDOW,
+                BlockingTaskPool::build().expect("failed to build tracing pool"),
+                FeeHistoryCache::new(cache, FeeHistoryCacheConfig::default()),
+                evm_config,
+                None,
+                DEFAULT_PROOF_PERMITS,
+            );
+            let api = MevApi::new(
+                eth_api,
+                testing_pool(),
+                BundlePool::default(),
+                BlockingTaskGuard::new(1),
+            );
+
+            Self { api, genesis_hash, signer, reverts }
+        }
+
+        /// Returns a raw transaction of the funded account that pays the given priority fee.
+        fn tx(&self, to: Address, priority_fee: u128) -> Bytes {
+            let tx = Transaction::Eip1559(TxEip1559 {
+                chain_id: 1,
+                nonce: 0,
+                gas_limit: 50_000,
+                max_fee_per_gas: 2 * GWEI + priority_fee,
+                max_priority_fee_per_gas: priority_fee,
+                to: TxKind::Call(to),
+                ..Default::default()
+            });
+            sign_tx_with_key_pair(self.signer, tx).envelope_encoded()
+        }
+
+        fn request(&self, block: u64, body: Vec<BundleItem>) -> SendBundleRequest {
+            SendBundleRequest::new(block, None, ProtocolVersion::Beta1, body)
+        }
+
+        /// Overrides that simulate the bundle in block 1.
+        fn overrides(&self) -> SimBundleOverrides {
+            SimBundleOverrides {
+                parent_block: Some(self.genesis_hash.into()),
+                coinbase: Some(Address::with_last_byte(0xcb)),
+                ..Default::default()
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn send_bundle() {
+        let chain = TestChain::new();
+        let tx =
+            BundleItem::Tx { tx: chain.tx(Address::with_last_byte(0xbb), GWEI), can_revert: false };
+
+        // the pending block is 2, bundles for later blocks are not simulated
+        let res = chain.api.send_bundle(chain.request(3, vec![tx.clone()])).await.unwrap();
+        let bundle = chain.api.bundle_pool().get(&res.bundle_hash).unwrap();
+        assert_eq!(bundle.block_range(), 3..=3);
+        assert_eq!(bundle.transactions().len(), 1);
+
+        let err = chain.api.send_bundle(chain.request(3, vec![])).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            EthApiError::InvalidParams(EthBundleError::EmptyBundleTransactions.to_string())
+                .to_string()
+        );
+
+        let err = chain.api.send_bundle(chain.request(1, vec![tx.clone()])).await.unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            EthApiError::InvalidParams(EthBundleError::BundleNotValidForBlock(2).to_string())
+                .to_string()
+        );
+
+        // the same transactions with refunds are a different bundle
+        let mut request = chain.request(3, vec![tx.clone()]);
+        let recipient = Address::with_last_byte(0xee);
+        request.validity = Some(Validity {
+            refund: Some(vec![Refund { body_idx: 0, percent: 10 }]),
+            refund_config: Some(vec![RefundConfig { address: recipient, percent: 100 }]),
+        });
+        let res = chain.api.send_bundle(request).await.unwrap();
+        let bundle = chain.api.bundle_pool().get(&res.bundle_hash).unwrap();
+        assert_eq!(bundle.refunds(), &[BundleRefund { body_idx: 0, percent: 10 }]);
+        assert_eq!(
+            bundle.refund_config(),
+            &[BundleRefundConfig { address: recipient, percent: 100 }]
+        );
+
+        // and so are the same transactions for a different block
+        chain.api.send_bundle(chain.request(4, vec![tx])).await.unwrap();
+
+        assert_eq!(chain.api.bundle_pool().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn sim_bundle() {
+        let chain = TestChain::new();
+        let transfer = chain.tx(Address::with_last_byte(0xbb), GWEI);
+
+        let res = chain
+            .api
+            .sim_bundle(
+                chain.request(1, vec![BundleItem::Tx { tx: transfer, can_revert: false }]),
+                chain.overrides(),
+            )
+            .await
+            .unwrap();
+        assert!(res.success, "{:?}", res.error);
+        assert_eq!(res.state_block, 0);
+        assert_eq!(res.gas_used, 21_000);
+        assert_eq!(res.profit, 21_000 * GWEI as u64);
+        assert_eq!(res.refundable_value, res.profit);
+        assert_eq!(res.mev_gas_price, GWEI as u64);
+
+        let revert = chain.tx(chain.reverts, GWEI);
+        let res = chain
+            .api
+            .sim_bundle(
+                chain.request(1, vec![BundleItem::Tx { tx: revert.clone(), can_revert: false }]),
+                chain.overrides(),
+            )
+            .await
+            .unwrap();
+        assert!(!res.success);
+        assert_eq!(res.error.as_deref(), Some("tx 0 reverted"));
+
+        let res = chain
+            .api
+            .sim_bundle(
+                chain.request(1, vec![BundleItem::Tx { tx: revert, can_revert: true }]),
+                chain.overrides(),
+            )
+            .await
+            .unwrap();
+        assert!(res.success, "{:?}", res.error);
+
+        // the bundle does not target the simulated block
+        let err = chain
+            .api
+            .sim_bundle(
+                chain.request(
+                    2,
+                    vec![BundleItem::Tx { tx: chain.tx(chain.reverts, GWEI), can_revert: true }],
+                ),
+                chain.overrides(),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            EthApiError::InvalidParams(EthBundleError::BundleNotValidForBlock(1).to_string())
+                .to_string()
+        );
+    }
+}
@@ -2,6 +2,7 @@ use alloy_primitives::Bytes;
 use async_trait::async_trait;
 use jsonrpsee::core::RpcResult;
 use reth_primitives::{Address, BlockNumberOrTag, TxHash, B256, U256};
+use reth_provider::AddressAppearancesReader;
 use reth_rpc_api::{EthApiServer, OtterscanServer};
 use reth_rpc_eth_api::helpers::TraceExt;
 use reth_rpc_eth_types::EthApiError;
@@ -14,7 +15,7 @@ use reth_rpc_types::{
         },
         parity::{Action, CreateAction, CreateOutput, TraceOutput},
     },
-    AnyTransactionReceipt, BlockTransactions, Header, RichBlock,
+    AnyTransactionReceipt, BlockTransactions, Header, RichBlock, Transaction,
 };
 use revm_inspectors::{
     tracing::{types::CallTraceNode, TracingInspectorConfig},
@@ -27,14 +28,16 @@ const API_LEVEL: u64 = 8;
 
 /// Otterscan API.
 #[derive(Debug)]
-pub struct OtterscanApi<Eth> {
+pub struct OtterscanApi<Provider, Eth> {
+    /// Provider of the address appearance index.
+    provider: Provider,
     eth: Eth,
 }
 
-impl<Eth> OtterscanApi<Eth> {
+impl<Provider, Eth> OtterscanApi<Provider, Eth> {
     /// Creates a new instance of `Otterscan`.
-    pub const fn new(eth: Eth) -> Self {
-        Self { eth }
+    pub const fn new(provider: Provider, eth: Eth) -> Self {
+        Self { provider, eth }
     }
 
     /// Constructs a `BlockDetails` from a block and its receipts.
@@ -56,9 +59,73 @@ impl<Eth> OtterscanApi<Eth> {
     }
 }
 
+impl<Provider, Eth> OtterscanApi<Provider, Eth>
+where
+    Provider: AddressAppearancesReader,
+    Eth: EthApiServer + TraceExt,
+{
+    /// Returns an error if the address appearance index that serves the transaction search is
+    /// not built.
+    fn ensure_address_appearances_indexed(&self) -> RpcResult<()> {
+        if self.provider.address_appearances_checkpoint().map_err(EthApiError::from)?.is_none() {
+            return Err(EthApiError::Unsupported(
+                "address appearance index is not built, enable the IndexAddressAppearances stage",
+            )
+            .into())
+        }
+        Ok(())
+    }
+
+    /// Returns the transactions of the block that the address took part in, as sender, recipient,
+    /// log emitter or in an internal call, together with their receipts.
+    ///
+    /// The transactions are looked up in the address appearance index.
+    ///
+    /// Transactions are returned in descending order.
+    async fn address_block_transactions(
+        &self,
+        address: Address,
+        block_number: u64,
+    ) -> RpcResult<(Vec<Transaction>, Vec<OtsTransactionReceipt>)> {
+        let block = self.eth.block_by_number(block_number.into(), true);
+        let receipts = self.eth.block_receipts(block_number.into());
+        let (block, receipts) = futures::try_join!(block, receipts)?;
+
+        let block = block.ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+        let receipts = receipts.ok_or_else(|| internal_rpc_err("receipts not found"))?;
+
+        let indices = self
+            .provider
+            .address_appearances_in_block(address, block_number)
+            .map_err(EthApiError::from)?;
+
+        let timestamp = Some(block.header.timestamp);
+        let BlockTransactions::Full(transactions) = block.inner.transactions else {
+            return Err(internal_rpc_err("block is not full"));
+        };
+        if transactions.len() != receipts.len() {
+            return Err(internal_rpc_err(
+                "the number of transactions does not match the number of receipts",
+            ))
+        }
+
+        let mut transactions = transactions.into_iter().zip(receipts).enumerate();
+        let mut matches = Vec::with_capacity(indices.len());
+        for index in indices {
+            let (_, (tx, receipt)) = transactions
+                .find(|(tx_index, _)| *tx_index == index)
+                .ok_or_else(|| internal_rpc_err("indexed transaction not found in block"))?;
+            matches.push((tx, ots_transaction_receipt(receipt, timestamp)));
+        }
+
+        Ok(matches.into_iter().rev().unzip())
+    }
+}
+
 #[async_trait]
-impl<Eth> OtterscanServer for OtterscanApi<Eth>
+impl<Provider, Eth> OtterscanServer for OtterscanApi<Provider, Eth>
 where
+    Provider: AddressAppearancesReader + 'static,
     Eth: EthApiServer + TraceExt + 'static,
 {
     /// Handler for `{ots,erigon}_getHeaderByNumber`
@@ -213,22 +280,7 @@ where
         let timestamp = Some(block.header.timestamp);
         let receipts = receipts
             .drain(page_start..page_end)
-            .map(|receipt| {
-                let receipt = receipt.inner.map_inner(|receipt| OtsReceipt {
-                    status: receipt
-                        .inner
-                        .receipt
-                        .status
-                        .as_eip658()
-                        .expect("ETH API returned pre-EIP-658 status"),
-                    cumulative_gas_used: receipt.inner.receipt.cumulative_gas_used as u64,
-                    logs: None,
-                    logs_bloom: None,
-                    r#type: receipt.r#type,
-                });
-
-                OtsTransactionReceipt { receipt, timestamp }
-            })
+            .map(|receipt| ots_transaction_receipt(receipt, timestamp))
             .collect();
         Ok(OtsBlockTransactions { fullblock: block.inner.into(), receipts })
     }
@@ -236,21 +288,104 @@ where
     /// Handler for `searchTransactionsBefore`
     async fn search_transactions_before(
         &self,
-        _address: Address,
-        _block_number: u64,
-        _page_size: usize,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
     ) -> RpcResult<TransactionsWithReceipts> {
-        Err(internal_rpc_err("unimplemented"))
+        self.ensure_address_appearances_indexed()?;
+
+        // block number 0 requests the first page, starting at the latest block
+        let first_page = block_number == 0;
+        let mut before = if first_page { u64::MAX } else { block_number };
+        let page_size = page_size.max(1);
+
+        // blocks are added as a whole, so the page may exceed the page size
+        let mut txs = Vec::new();
+        let mut receipts = Vec::new();
+        'search: while txs.len() < page_size {
+            let blocks = self
+                .provider
+                .address_appearances_before(address, before, page_size)
+                .map_err(EthApiError::from)?;
+            if blocks.is_empty() {
+                break
+            }
+
+            for block in blocks {
+                let (block_txs, block_receipts) =
+                    self.address_block_transactions(address, block).await?;
+                txs.extend(block_txs);
+                receipts.extend(block_receipts);
+                before = block;
+                if txs.len() >= page_size {
+                    break 'search
+                }
+            }
+        }
+
+        let last_page = self
+            .provider
+            .address_appearances_before(address, before, 1)
+            .map_err(EthApiError::from)?
+            .is_empty();
+
+        Ok(TransactionsWithReceipts { txs, receipts, first_page, last_page })
     }
 
     /// Handler for `searchTransactionsAfter`
     async fn search_transactions_after(
         &self,
-        _address: Address,
-        _block_number: u64,
-        _page_size: usize,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
     ) -> RpcResult<TransactionsWithReceipts> {
-        Err(internal_rpc_err("unimplemented"))
+        self.ensure_address_appearances_indexed()?;
+
+        // block number 0 requests the last page, starting at genesis
+        let last_page = block_number == 0;
+        let mut after = block_number;
+        let page_size = page_size.max(1);
+
+        // blocks are added as a whole, so the page may exceed the page size
+        let mut tx_count = 0;
+        let mut block_results = Vec::new();
+        'search: while tx_count < page_size {
+            let blocks = self
+                .provider
+                .address_appearances_after(address, after, page_size)
+                .map_err(EthApiError::from)?;
+            if blocks.is_empty() {
+                break
+            }
+
+            for block in blocks {
+                let result = self.address_block_transactions(address, block).await?;
+                tx_count += result.0.len();
+                block_results.push(result);
+                after = block;
+                if tx_count >= page_size {
+                    break 'search
+                }
+            }
+        }
+
+        let first_page = self
+            .provider
+            .address_appearances_after(address, after, 1)
+            .map_err(EthApiError::from)?
+            .is_empty();
+
+        // pages are returned in descending order
+        let (txs, receipts) = block_results.into_iter().rev().fold(
+            (Vec::with_capacity(tx_count), Vec::with_capacity(tx_count)),
+            |(mut txs, mut receipts), (block_txs, block_receipts)| {
+                txs.extend(block_txs);
+                receipts.extend(block_receipts);
+                (txs, receipts)
+            },
+        );
+
+        Ok(TransactionsWithReceipts { txs, receipts, first_page, last_page })
     }
 
     /// Handler for `getTransactionBySenderAndNonce`
@@ -360,6 +495,27 @@ where
     }
 }
 
+/// Converts a receipt into an [`OtsTransactionReceipt`] of a block with the given timestamp.
+fn ots_transaction_receipt(
+    receipt: AnyTransactionReceipt,
+    timestamp: Option<u64>,
+) -> OtsTransactionReceipt {
+    let receipt = receipt.inner.map_inner(|receipt| OtsReceipt {
+        status: receipt
+            .inner
+            .receipt
+            .status
+            .as_eip658()
+            .expect("ETH API returned pre-EIP-658 status"),
+        cumulative_gas_used: receipt.inner.receipt.cumulative_gas_used as u64,
+        logs: None,
+        logs_bloom: None,
+        r#type: receipt.r#type,
+    });
+
+    OtsTransactionReceipt { receipt, timestamp }
+}
+
 /// Performs a binary search within a given block range to find the desired block number.
 ///
 /// The binary search is performed by calling the provided asynchronous `check` closure on the
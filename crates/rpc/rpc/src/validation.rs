@@ -0,0 +1,747 @@
+use std::{future::Future, sync::Arc};
+
+use async_trait::async_trait;
+use jsonrpsee::{core::RpcResult, types::ErrorObject};
+use reth_consensus::{Consensus, ConsensusError, PostExecutionInput};
+use reth_errors::ProviderError;
+use reth_evm::execute::{BlockExecutionError, BlockExecutorProvider, Executor};
+use reth_primitives::{
+    Address, BlockWithSenders, GotExpected, Receipt, SealedBlock, SealedHeader, B256, U256,
+};
+use reth_provider::{HeaderProvider, StateProviderFactory};
+use reth_revm::{database::StateProviderDatabase, db::BundleState};
+use reth_rpc_api::BlockSubmissionValidationApiServer;
+use reth_rpc_server_types::result::{internal_rpc_err, invalid_params_rpc_err};
+use reth_rpc_types::{
+    beacon::relay::{BidTrace, BuilderBlockValidationRequest, BuilderBlockValidationRequestV2},
+    engine::{ExecutionPayload, PayloadError},
+};
+use reth_rpc_types_compat::engine::payload::try_into_sealed_block;
+use reth_tasks::TaskSpawner;
+use tokio::sync::oneshot;
+
+/// The divisor of the parent gas limit that bounds the gas limit change between two blocks.
+const GAS_LIMIT_BOUND_DIVISOR: u64 = 1024;
+
+/// `flashbots` API implementation.
+///
+/// This type provides the functionality for validating builder block submissions against the
+/// state of their parent block, as used by relays.
+pub struct ValidationApi<Provider, E> {
+    inner: Arc<ValidationApiInner<Provider, E>>,
+}
+
+// === impl ValidationApi ===
+
+impl<Provider, E> ValidationApi<Provider, E> {
+    /// Create a new instance of the [`ValidationApi`]
+    pub fn new(
+        provider: Provider,
+        consensus: Arc<dyn Consensus>,
+        executor_provider: E,
+        task_spawner: Box<dyn TaskSpawner>,
+    ) -> Self {
+        let inner =
+            Arc::new(ValidationApiInner { provider, consensus, executor_provider, task_spawner });
+        Self { inner }
+    }
+
+    /// The provider that can interact with the chain.
+    pub fn provider(&self) -> &Provider {
+        &self.inner.provider
+    }
+
+    /// The consensus implementation used to validate submitted blocks.
+    pub fn consensus(&self) -> &dyn Consensus {
+        &*self.inner.consensus
+    }
+}
+
+impl<Provider, E> ValidationApi<Provider, E>
+where
+    Provider: HeaderProvider + StateProviderFactory + 'static,
+    E: BlockExecutorProvider,
+{
+    /// Executes the future on a new blocking task.
+    async fn on_blocking_task<C, F, R>(&self, c: C) -> Result<R, ValidationApiError>
+    where
+        C: FnOnce(Self) -> F,
+        F: Future<Output = Result<R, ValidationApiError>> + Send + 'static,
+        R: Send + 'static,
+    {
+        let (tx, rx) = oneshot::channel();
+        let this = self.clone();
+        let f = c(this);
+        self.inner.task_spawner.spawn_blocking(Box::pin(async move {
+            let res = f.await;
+            let _ = tx.send(res);
+        }));
+        rx.await.map_err(|_| ValidationApiError::InternalBlockingTaskError)?
+    }
+
+    /// Validates the given builder submission.
+    ///
+    /// This converts the payload into a block, checks it against the [`BidTrace`], runs the
+    /// consensus checks against its parent, executes it on top of the parent state and finally
+    /// verifies the proposer payment and the state root.
+    pub fn validate_builder_submission(
+        &self,
+        payload: ExecutionPayload,
+        message: BidTrace,
+        registered_gas_limit: u64,
+        withdrawals_root: Option<B256>,
+    ) -> Result<(), ValidationApiError> {
+        let block = try_into_sealed_block(payload, None)?;
+
+        self.validate_message_against_block(&block, &message)?;
+
+        if let Some(expected) = withdrawals_root {
+            let got = block.withdrawals_root.unwrap_or_default();
+            if got != expected {
+                return Err(ValidationApiError::WithdrawalsRootMismatch(GotExpected {
+                    got,
+                    expected,
+                }))
+            }
+        }
+
+        let parent_header = self
+            .provider()
+            .header(&block.parent_hash)?
+            .ok_or(ValidationApiError::MissingParentBlock(block.parent_hash))?;
+        let parent = SealedHeader::new(parent_header, block.parent_hash);
+
+        self.validate_gas_limit(registered_gas_limit, &parent, &block.header)?;
+
+        let consensus = self.consensus();
+        consensus.validate_header(&block.header)?;
+        consensus.validate_header_against_parent(&block.header, &parent)?;
+        consensus.validate_block_pre_execution(&block)?;
+
+        let parent_td = self.provider().header_td(&parent.hash())?.unwrap_or_default();
+        let total_difficulty = parent_td + block.difficulty;
+
+        let state_root = block.state_root;
+        let block = block
+            .try_seal_with_senders()
+            .map_err(|_| ValidationApiError::InvalidTransactionSignature)?
+            .unseal();
+
+        let state_provider = self.provider().history_by_block_hash(parent.hash())?;
+        let executor =
+            self.inner.executor_provider.executor(StateProviderDatabase::new(&state_provider));
+        let output = executor.execute((&block, total_difficulty).into())?;
+
+        consensus.validate_block_post_execution(
+            &block,
+            PostExecutionInput::new(&output.receipts, &output.requests),
+        )?;
+
+        self.validate_proposer_payment(&block, &output.state, &output.receipts, &message)?;
+
+        let got = state_provider.state_root(&output.state)?;
+        if got != state_root {
+            return Err(ValidationApiError::StateRootMismatch(GotExpected {
+                got,
+                expected: state_root,
+            }))
+        }
+
+        Ok(())
+    }
+
+    /// Ensures that the header fields of the block match the ones advertised by the [`BidTrace`].
+    pub fn validate_message_against_block(
+        &self,
+        block: &SealedBlock,
+        message: &BidTrace,
+    ) -> Result<(), ValidationApiError> {
+        if block.parent_hash != message.parent_hash {
+            return Err(ValidationApiError::ParentHashMismatch(GotExpected {
+                got: block.parent_hash,
+                expected: message.parent_hash,
+            }))
+        }
+        if block.hash() != message.block_hash {
+            return Err(ValidationApiError::BlockHashMismatch(GotExpected {
+                got: block.hash(),
+                expected: message.block_hash,
+            }))
+        }
+        if block.gas_limit != message.gas_limit {
+            return Err(ValidationApiError::GasLimitMismatch(GotExpected {
+                got: block.gas_limit,
+                expected: message.gas_limit,
+            }))
+        }
+        if block.gas_used != message.gas_used {
+            return Err(ValidationApiError::GasUsedMismatch(GotExpected {
+                got: block.gas_used,
+                expected: message.gas_used,
+            }))
+        }
+
+        Ok(())
+    }
+
+    /// Ensures that the block's gas limit moves towards the gas limit registered by the proposer,
+    /// as far as the bounds relative to the parent gas limit allow.
+    pub fn validate_gas_limit(
+        &self,
+        registered_gas_limit: u64,
+        parent: &SealedHeader,
+        header: &SealedHeader,
+    ) -> Result<(), ValidationApiError> {
+        let max_delta = (parent.gas_limit / GAS_LIMIT_BOUND_DIVISOR).saturating_sub(1);
+        let max_gas_limit = parent.gas_limit.saturating_add(max_delta);
+        let min_gas_limit = parent.gas_limit.saturating_sub(max_delta);
+
+        let best_gas_limit = registered_gas_limit.clamp(min_gas_limit, max_gas_limit);
+
+        if header.gas_limit != best_gas_limit {
+            return Err(ValidationApiError::GasLimitMismatch(GotExpected {
+                got: header.gas_limit,
+                expected: best_gas_limit,
+            }))
+        }
+
+        Ok(())
+    }
+
+    /// Ensures that the proposer is paid at least the value of the bid.
+    ///
+    /// If the proposer is the fee recipient of the block, its balance increase is checked.
+    /// Otherwise the last transaction of the block must be a plain, successful transfer of exactly
+    /// the bid value from the block's fee recipient to the proposer.
+    pub fn validate_proposer_payment(
+        &self,
+        block: &BlockWithSenders,
+        state: &BundleState,
+        receipts: &[Receipt],
+        message: &BidTrace,
+    ) -> Result<(), ValidationApiError> {
+        let fee_recipient = message.proposer_fee_recipient;
+
+        if block.beneficiary == fee_recipient {
+            let (balance_before, balance_after) = state
+                .account(&fee_recipient)
+                .map(|account| {
+                    (
+                        account.original_info.as_ref().map(|info| info.balance).unwrap_or_default(),
+                        account.info.as_ref().map(|info| info.balance).unwrap_or_default(),
+                    )
+                })
+                .unwrap_or_default();
+
+            let payment = balance_after.saturating_sub(balance_before);
+            if payment < message.value {
+                return Err(ValidationApiError::ProposerPaymentTooLow(GotExpected {
+                    got: payment,
+                    expected: message.value,
+                }))
+            }
+            return Ok(())
+        }
+
+        let (Some((sender, tx)), Some(receipt)) =
+            (block.transactions_with_sender().last(), receipts.last())
+        else {
+            return Err(ValidationApiError::ProposerPaymentMissing)
+        };
+
+        if !receipt.success {
+            return Err(ValidationApiError::ProposerPaymentReverted)
+        }
+        if *sender != block.beneficiary {
+            return Err(ValidationApiError::ProposerPaymentSender(GotExpected {
+                got: *sender,
+                expected: block.beneficiary,
+            }))
+        }
+        if tx.to() != Some(fee_recipient) {
+            return Err(ValidationApiError::ProposerPaymentRecipient(GotExpected {
+                got: tx.to().unwrap_or_default(),
+                expected: fee_recipient,
+            }))
+        }
+        if tx.value() != message.value {
+            return Err(ValidationApiError::ProposerPaymentValue(GotExpected {
+                got: tx.value(),
+                expected: message.value,
+            }))
+        }
+        if !tx.input().is_empty() {
+            return Err(ValidationApiError::ProposerPaymentCalldata)
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<Provider, E> BlockSubmissionValidationApiServer for ValidationApi<Provider, E>
+where
+    Provider: HeaderProvider + StateProviderFactory + 'static,
+    E: BlockExecutorProvider,
+{
+    /// Handler for `flashbots_validateBuilderSubmissionV1`
+    async fn validate_builder_submission_v1(
+        &self,
+        request: BuilderBlockValidationRequest,
+    ) -> RpcResult<()> {
+        let BuilderBlockValidationRequest { request, registered_gas_limit } = request;
+        Ok(self
+            .on_blocking_task(|this| async move {
+                this.validate_builder_submission(
+                    request.execution_payload,
+                    request.message,
+                    registered_gas_limit,
+                    None,
+                )
+            })
+            .await?)
+    }
+
+    /// Handler for `flashbots_validateBuilderSubmissionV2`
+    async fn validate_builder_submission_v2(
+        &self,
+        request: BuilderBlockValidationRequestV2,
+    ) -> RpcResult<()> {
+        let BuilderBlockValidationRequestV2 { request, registered_gas_limit, withdrawals_root } =
+            request;
+        Ok(self
+            .on_blocking_task(|this| async move {
+                this.validate_builder_submission(
+                    request.execution_payload,
+                    request.message,
+                    registered_gas_limit,
+                    Some(withdrawals_root),
+                )
+            })
+            .await?)
+    }
+}
+
+impl<Provider, E> std::fmt::Debug for ValidationApi<Provider, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ValidationApi").finish_non_exhaustive()
+    }
+}
+
+impl<Provider, E> Clone for ValidationApi<Provider, E> {
+    fn clone(&self) -> Self {
+        Self { inner: Arc::clone(&self.inner) }
+    }
+}
+
+struct ValidationApiInner<Provider, E> {
+    /// The provider that can interact with the chain.
+    provider: Provider,
+    /// Consensus implementation used for the stateless block checks.
+    consensus: Arc<dyn Consensus>,
+    /// Executes submitted blocks on top of their parent state.
+    executor_provider: E,
+    /// The type that can spawn tasks which would otherwise block.
+    task_spawner: Box<dyn TaskSpawner>,
+}
+
+/// Errors that can occur when validating a builder submission.
+#[derive(Debug, thiserror::Error)]
+pub enum ValidationApiError {
+    /// The parent hash of the bid trace does not match the block.
+    #[error("parent hash mismatch: {0}")]
+    ParentHashMismatch(GotExpected<B256>),
+    /// The block hash of the bid trace does not match the block.
+    #[error("block hash mismatch: {0}")]
+    BlockHashMismatch(GotExpected<B256>),
+    /// The gas limit of the block is not the expected one.
+    #[error("gas limit mismatch: {0}")]
+    GasLimitMismatch(GotExpected<u64>),
+    /// The gas used of the bid trace does not match the block.
+    #[error("gas used mismatch: {0}")]
+    GasUsedMismatch(GotExpected<u64>),
+    /// The withdrawals root of the request does not match the block.
+    #[error("withdrawals root mismatch: {0}")]
+    WithdrawalsRootMismatch(GotExpected<B256>),
+    /// The state root computed after execution does not match the block.
+    #[error("state root mismatch: {0}")]
+    StateRootMismatch(GotExpected<B256>),
+    /// The parent of the block is unknown.
+    #[error("parent block {0} not found")]
+    MissingParentBlock(B256),
+    /// A transaction of the block has an invalid signature.
+    #[error("could not recover transaction signer")]
+    InvalidTransactionSignature,
+    /// The fee recipient balance did not increase by at least the bid value.
+    #[error("proposer payment too low: {0}")]
+    ProposerPaymentTooLow(GotExpected<U256>),
+    /// The block contains no proposer payment transaction.
+    #[error("proposer payment transaction missing")]
+    ProposerPaymentMissing,
+    /// The proposer payment transaction reverted.
+    #[error("proposer payment transaction reverted")]
+    ProposerPaymentReverted,
+    /// The proposer payment is not sent by the block's fee recipient.
+    #[error("proposer payment sender mismatch: {0}")]
+    ProposerPaymentSender(GotExpected<Address>),
+    /// The proposer payment is not sent to the proposer fee recipient.
+    #[error("proposer payment recipient mismatch: {0}")]
+    ProposerPaymentRecipient(GotExpected<Address>),
+    /// The proposer payment value does not match the bid value.
+    #[error("proposer payment value mismatch: {0}")]
+    ProposerPaymentValue(GotExpected<U256>),
+    /// The proposer payment transaction carries calldata.
+    #[error("proposer payment transaction contains calldata")]
+    ProposerPaymentCalldata,
+    /// The payload could not be converted into a block.
+    #[error(transparent)]
+    Payload(#[from] PayloadError),
+    /// The block failed consensus validation.
+    #[error(transparent)]
+    Consensus(#[from] ConsensusError),
+    /// The block failed to execute.
+    #[error(transparent)]
+    Execution(#[from] BlockExecutionError),
+    /// Failed to read from the database.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+    /// The blocking task was dropped before it could send a response.
+    #[error("internal blocking task error")]
+    InternalBlockingTaskError,
+}
+
+impl From<ValidationApiError> for ErrorObject<'static> {
+    fn from(error: ValidationApiError) -> Self {
+        match error {
+            ValidationApiError::Provider(_) | ValidationApiError::InternalBlockingTaskError => {
+                internal_rpc_err(error.to_string())
+            }
+            _ => invalid_params_rpc_err(error.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assert_matches::assert_matches;
+    use reth_chainspec::{ChainSpec, ChainSpecBuilder};
+    use reth_ethereum_consensus::EthBeaconConsensus;
+    use reth_evm_ethereum::execute::EthExecutorProvider;
+    use reth_primitives::{
+        constants::{EMPTY_OMMER_ROOT_HASH, EMPTY_ROOT_HASH},
+        proofs, public_key_to_address, Block, Bytes, Header, Transaction, TransactionSigned,
+        TxEip1559, TxKind, TxType, Withdrawals,
+    };
+    use reth_provider::test_utils::{ExtendedAccount, MockEthProvider};
+    use reth_rpc_types_compat::engine::payload::block_to_payload_v2;
+    use reth_tasks::TokioTaskExecutor;
+    use reth_testing_utils::generators::{self, sign_tx_with_key_pair};
+    use secp256k1::Keypair;
+
+    const GAS_LIMIT: u64 = 30_000_000;
+    const TRANSFER_GAS: u64 = 21_000;
+
+    /// A relay submission that is valid on top of the parent in the mock provider.
+    struct Submission {
+        api: ValidationApi<MockEthProvider, EthExecutorProvider>,
+        chain_spec: Arc<ChainSpec>,
+        parent: SealedHeader,
+        builder: Keypair,
+        message: BidTrace,
+    }
+
+    impl Submission {
+        fn new() -> Self {
+            let chain_spec = Arc::new(ChainSpecBuilder::mainnet().shanghai_activated().build());
+            let builder = generators::generate_keys(&mut generators::rng(), 1).remove(0);
+
+            let provider = MockEthProvider::default();
+            let parent = Header {
+                number: 1,
+                timestamp: 1_700_000_000,
+                gas_limit: GAS_LIMIT,
+                base_fee_per_gas: Some(1_000_000_000),
+                ommers_hash: EMPTY_OMMER_ROOT_HASH,
+                withdrawals_root: Some(EMPTY_ROOT_HASH),
+                ..Default::default()
+            }
+            .seal_slow();
+            provider.add_header(parent.hash(), parent.header().clone());
+            provider.add_account(
+                public_key_to_address(builder.public_key()),
+                ExtendedAccount::new(0, U256::from(10).pow(U256::from(18))),
+            );
+
+            let api = ValidationApi::new(
+                provider,
+                Arc::new(EthBeaconConsensus::new(chain_spec.clone())),
+                EthExecutorProvider::ethereum(chain_spec.clone()),
+                Box::<TokioTaskExecutor>::default(),
+            );
+            let message = BidTrace {
+                proposer_fee_recipient: Address::with_last_byte(0xfe),
+                gas_limit: GAS_LIMIT,
+                value: U256::from(1_000_000),
+                ..Default::default()
+            };
+
+            Self { api, chain_spec, parent, builder, message }
+        }
+
+        fn builder(&self) -> Address {
+            public_key_to_address(self.builder.public_key())
+        }
+
+        /// Signs a transfer from the builder with the given recipient, value and input.
+        fn transfer(&self, to: Address, value: U256, input: Bytes) -> TransactionSigned {
+            let tx = Transaction::Eip1559(TxEip1559 {
+                chain_id: self.chain_spec.chain.id(),
+                nonce: 0,
+                gas_limit: 100_000,
+                max_fee_per_gas: 10_000_000_000,
+                max_priority_fee_per_gas: 1_000_000_000,
+                to: TxKind::Call(to),
+                value,
+                input,
+                ..Default::default()
+            });
+            sign_tx_with_key_pair(self.builder, tx)
+        }
+
+        /// The transfer that pays the proposer the bid value.
+        fn payment(&self) -> TransactionSigned {
+            self.transfer(self.message.proposer_fee_recipient, self.message.value, Bytes::new())
+        }
+
+        /// Builds a block on top of the parent with the given plain transfers, which all succeed.
+        fn block(&self, transactions: Vec<TransactionSigned>) -> SealedBlock {
+            let mut gas_used = 0;
+            let receipts = transactions
+                .iter()
+                .map(|tx| {
+                    gas_used += TRANSFER_GAS +
+                        tx.input()
+                            .iter()
+                            .map(|byte| if *byte == 0 { 4 } else { 16 })
+                            .sum::<u64>();
+                    Receipt {
+                        tx_type: TxType::Eip1559,
+                        success: true,
+                        cumulative_gas_used: gas_used,
+                        ..Default::default()
+                    }
+                })
+                .collect::<Vec<_>>();
+            let timestamp = self.parent.timestamp + 12;
+            let header = Header {
+                parent_hash: self.parent.hash(),
+                number: self.parent.number + 1,
+                timestamp,
+                beneficiary: self.builder(),
+                gas_limit: GAS_LIMIT,
+                gas_used,
+                base_fee_per_gas: self
+                    .parent
+                    .next_block_base_fee(self.chain_spec.base_fee_params_at_timestamp(timestamp)),
+                ommers_hash: EMPTY_OMMER_ROOT_HASH,
+                transactions_root: proofs::calculate_transaction_root(&transactions),
+                receipts_root: proofs::calculate_receipt_root_no_memo(
+                    &receipts.iter().collect::<Vec<_>>(),
+                ),
+                withdrawals_root: Some(EMPTY_ROOT_HASH),
+                // the mock provider computes empty state roots
+                state_root: B256::ZERO,
+                ..Default::default()
+            };
+            Block {
+                header,
+                body: transactions,
+                ommers: Vec::new(),
+                withdrawals: Some(Withdrawals::default()),
+                requests: None,
+            }
+            .seal_slow()
+        }
+
+        /// Returns the bid trace of the block.
+        fn message(&self, block: &SealedBlock) -> BidTrace {
+            BidTrace {
+                parent_hash: block.parent_hash,
+                block_hash: block.hash(),
+                gas_used: block.gas_used,
+                ..self.message.clone()
+            }
+        }
+
+        fn validate(
+            &self,
+            block: SealedBlock,
+            message: BidTrace,
+            withdrawals_root: Option<B256>,
+        ) -> Result<(), ValidationApiError> {
+            let payload = ExecutionPayload::V2(block_to_payload_v2(block));
+            self.api.validate_builder_submission(payload, message, GAS_LIMIT, withdrawals_root)
+        }
+    }
+
+    #[test]
+    fn valid_submission() {
+        let submission = Submission::new();
+        let block = submission.block(vec![submission.payment()]);
+        let message = submission.message(&block);
+        submission.validate(block, message, Some(EMPTY_ROOT_HASH)).unwrap();
+    }
+
+    #[test]
+    fn message_mismatch() {
+        let submission = Submission::new();
+        let block = submission.block(vec![submission.payment()]);
+        let message = submission.message(&block);
+        let other = B256::with_last_byte(1);
+
+        let err = submission
+            .validate(block.clone(), BidTrace { parent_hash: other, ..message }, None)
+            .unwrap_err();
+        assert_matches!(err, ValidationApiError::ParentHashMismatch(GotExpected { got, expected })
+            if got == block.parent_hash && expected == other);
+
+        let err = submission
+            .validate(block.clone(), BidTrace { block_hash: other, ..message }, None)
+            .unwrap_err();
+        assert_matches!(err, ValidationApiError::BlockHashMismatch(GotExpected { got, expected })
+            if got == block.hash() && expected == other);
+
+        let err = submission
+            .validate(block.clone(), BidTrace { gas_limit: 1, ..message }, None)
+            .unwrap_err();
+        assert_matches!(err, ValidationApiError::GasLimitMismatch(GotExpected { got, expected })
+            if got == GAS_LIMIT && expected == 1);
+
+        let err = submission
+            .validate(block.clone(), BidTrace { gas_used: 1, ..message }, None)
+            .unwrap_err();
+        assert_matches!(err, ValidationApiError::GasUsedMismatch(GotExpected { got, expected })
+            if got == TRANSFER_GAS && expected == 1);
+
+        let err = submission.validate(block, message, Some(other)).unwrap_err();
+        assert_matches!(err, ValidationApiError::WithdrawalsRootMismatch(GotExpected { got, expected })
+            if got == EMPTY_ROOT_HASH && expected == other);
+    }
+
+    #[test]
+    fn block_mismatch() {
+        let submission = Submission::new();
+
+        // the gas limit must move towards the registered gas limit
+        let mut block = submission.block(vec![submission.payment()]).unseal();
+        block.header.gas_limit = GAS_LIMIT + 1;
+        let block = block.seal_slow();
+        let message = BidTrace { gas_limit: GAS_LIMIT + 1, ..submission.message(&block) };
+        assert_matches!(
+            submission.validate(block, message, None),
+            Err(ValidationApiError::GasLimitMismatch(GotExpected { got, expected }))
+                if got == GAS_LIMIT + 1 && expected == GAS_LIMIT
+        );
+
+        let mut block = submission.block(vec![submission.payment()]).unseal();
+        block.header.parent_hash = B256::with_last_byte(1);
+        let block = block.seal_slow();
+        let message = submission.message(&block);
+        assert_matches!(
+            submission.validate(block, message, None),
+            Err(ValidationApiError::MissingParentBlock(hash)) if hash == B256::with_last_byte(1)
+        );
+
+        let mut block = submission.block(vec![submission.payment()]).unseal();
+        block.header.state_root = B256::with_last_byte(1);
+        let block = block.seal_slow();
+        let message = submission.message(&block);
+        assert_matches!(
+            submission.validate(block, message, None),
+            Err(ValidationApiError::StateRootMismatch(GotExpected { got, expected }))
+                if got == B256::ZERO && expected == B256::with_last_byte(1)
+        );
+    }
+
+    #[test]
+    fn proposer_payment() {
+        let submission = Submission::new();
+        let fee_recipient = submission.message.proposer_fee_recipient;
+        let value = submission.message.value;
+
+        let validate = |transactions| {
+            let block = submission.block(transactions);
+            let message = submission.message(&block);
+            submission.validate(block, message, None)
+        };
+
+        assert_matches!(validate(vec![]), Err(ValidationApiError::ProposerPaymentMissing));
+        assert_matches!(
+            validate(vec![submission.transfer(Address::with_last_byte(0xaa), value, Bytes::new())]),
+            Err(ValidationApiError::ProposerPaymentRecipient(GotExpected { got, expected }))
+                if got == Address::with_last_byte(0xaa) && expected == fee_recipient
+        );
+        assert_matches!(
+            validate(vec![submission.transfer(fee_recipient, U256::from(1), Bytes::new())]),
+            Err(ValidationApiError::ProposerPaymentValue(GotExpected { got, expected }))
+                if got == U256::from(1) && expected == value
+        );
+        assert_matches!(
+            validate(vec![submission.transfer(fee_recipient, value, Bytes::from_static(&[1]))]),
+            Err(ValidationApiError::ProposerPaymentCalldata)
+        );
+    }
+
+    #[test]
+    fn proposer_payment_receipt() {
+        let submission = Submission::new();
+        let block = submission.block(vec![submission.payment()]);
+        let block = block.seal_with_senders().unwrap().unseal();
+        let receipt = Receipt {
+            tx_type: TxType::Eip1559,
+            success: false,
+            cumulative_gas_used: TRANSFER_GAS,
+            ..Default::default()
+        };
+        let message = submission.message.clone();
+
+        assert_matches!(
+            submission.api.validate_proposer_payment(
+                &block,
+                &BundleState::default(),
+                std::slice::from_ref(&receipt),
+                &message
+            ),
+            Err(ValidationApiError::ProposerPaymentReverted)
+        );
+
+        // the payment must be sent by the fee recipient of the block
+        let mut other_sender = block.clone();
+        other_sender.senders = vec![Address::with_last_byte(1)];
+        assert_matches!(
+            submission.api.validate_proposer_payment(
+                &other_sender,
+                &BundleState::default(),
+                &[Receipt { success: true, ..receipt }],
+                &message
+            ),
+            Err(ValidationApiError::ProposerPaymentSender(GotExpected { got, expected }))
+                if got == Address::with_last_byte(1) && expected == submission.builder()
+        );
+
+        // the proposer is the fee recipient, but its balance did not increase
+        let mut to_proposer = block;
+        to_proposer.block.header.beneficiary = message.proposer_fee_recipient;
+        assert_matches!(
+            submission.api.validate_proposer_payment(
+                &to_proposer,
+                &BundleState::default(),
+                &[],
+                &message
+            ),
+            Err(ValidationApiError::ProposerPaymentTooLow(GotExpected { got, expected }))
+                if got == U256::ZERO && expected == message.value
+        );
+    }
+}
@@ -9,7 +9,8 @@ use reth_exex::{ExExManagerHandle, ExExNotification};
 use reth_primitives::{BlockNumber, Header, StaticFileSegment};
 use reth_primitives_traits::format_gas_throughput;
 use reth_provider::{
-    providers::{StaticFileProvider, StaticFileProviderRWRefMut, StaticFileWriter},
+    providers::{is_snap_synced, StaticFileProvider, StaticFileProviderRWRefMut, StaticFileWriter},
+    writer::StorageWriter,
     BlockReader, DatabaseProviderRW, HeaderProvider, LatestStateProviderRef, OriginalValuesKnown,
     ProviderError, StateWriter, StatsReader, TransactionVariant,
 };
@@ -159,9 +160,11 @@ impl<E> ExecutionStage<E> {
         let mut prune_modes = self.prune_modes.clone();
 
         // If we're not executing MerkleStage from scratch (by threshold or first-sync), then erase
-        // changeset related pruning configurations
+        // changeset related pruning configurations. The hashing stages of snap synced nodes never
+        // run from scratch.
         if !(max_block - start_block > self.external_clean_threshold ||
-            provider.count_entries::<tables::AccountsTrie>()?.is_zero())
+            provider.count_entries::<tables::AccountsTrie>()?.is_zero()) ||
+            is_snap_synced(provider.tx_ref())?
         {
             prune_modes.account_history = None;
             prune_modes.storage_history = None;
@@ -357,9 +360,17 @@ where
             }
         }
 
+        // The state of snap synced nodes missing from the plain state is read from the hashed
+        // state, so it must be kept up to date with the plain state.
+        let hashed_state =
+            is_snap_synced(provider.tx_ref())?.then(|| state.hash_state_slow().into_sorted());
+
         let time = Instant::now();
         // write output
         state.write_to_storage(provider, static_file_producer, OriginalValuesKnown::Yes)?;
+        if let Some(hashed_state) = hashed_state {
+            StorageWriter::from_database_writer(provider).write_hashed_state(&hashed_state)?;
+        }
         let db_write_duration = time.elapsed();
         debug!(
             target: "sync::stages::execution",
@@ -409,6 +420,21 @@ where
         // This also updates `PlainStorageState` and `PlainAccountState`.
         let bundle_state_with_receipts = provider.take_state(range.clone())?;
 
+        // Changesets that were already moved to static files have been reverted by `take_state`
+        // as well, so they only need to be removed from the static files.
+        let static_file_provider = provider.static_file_provider();
+        for segment in [StaticFileSegment::AccountChangeSets, StaticFileSegment::StorageChangeSets]
+        {
+            if let Some(highest_block) = static_file_provider
+                .get_highest_static_file_block(segment)
+                .filter(|highest_block| *highest_block > unwind_to)
+            {
+                static_file_provider
+                    .latest_writer(segment)?
+                    .prune_changesets(highest_block - unwind_to)?;
+            }
+        }
+
         // Prepare the input for post unwind commit hook, where an `ExExNotification` will be sent.
         if self.exex_manager_handle.has_exexs() {
             // Get the blocks for the unwound range.
@@ -592,10 +618,20 @@ where
 
     // Get next expected receipt number in static files
     let static_file_provider = provider.static_file_provider();
-    let next_static_file_receipt_num = static_file_provider
+    let next_static_file_receipt_num = match static_file_provider
         .get_highest_static_file_tx(StaticFileSegment::Receipts)
-        .map(|num| num + 1)
-        .unwrap_or(0);
+    {
+        Some(num) => num + 1,
+        // Nodes that downloaded the state over `snap` have no receipts before the pivot block,
+        // the static files only have empty blocks up to it.
+        None if start_block > 0 &&
+            static_file_provider.get_highest_static_file_block(StaticFileSegment::Receipts) ==
+                Some(start_block - 1) =>
+        {
+            next_receipt_num
+        }
+        None => 0,
+    };
 
     let mut static_file_producer =
         static_file_provider.get_writer(start_block, StaticFileSegment::Receipts)?;
@@ -8,7 +8,9 @@ use reth_db_api::{
 };
 use reth_etl::Collector;
 use reth_primitives::{keccak256, Account, B256};
-use reth_provider::{AccountExtReader, DatabaseProviderRW, HashingWriter, StatsReader};
+use reth_provider::{
+    providers::is_snap_synced, AccountExtReader, DatabaseProviderRW, HashingWriter, StatsReader,
+};
 use reth_stages_api::{
     AccountHashingCheckpoint, EntitiesCheckpoint, ExecInput, ExecOutput, Stage, StageCheckpoint,
     StageError, StageId, UnwindInput, UnwindOutput,
@@ -142,8 +144,11 @@ impl<DB: Database> Stage<DB> for AccountHashingStage {
         // if there are more blocks then threshold it is faster to go over Plain state and hash all
         // account otherwise take changesets aggregate the sets and apply hashing to
         // AccountHashing table. Also, if we start from genesis, we need to hash from scratch, as
-        // genesis accounts are not in changeset.
-        if to_block - from_block > self.clean_threshold || from_block == 1 {
+        // genesis accounts are not in changeset. The plain state of snap synced nodes is
+        // incomplete though, so their hashed state can only be updated incrementally.
+        if (to_block - from_block > self.clean_threshold || from_block == 1) &&
+            !is_snap_synced(provider.tx_ref())?
+        {
             let tx = provider.tx_ref();
 
             // clear table, load all accounts and hash it
@@ -10,7 +10,9 @@ use reth_db_api::{
 };
 use reth_etl::Collector;
 use reth_primitives::{keccak256, BufMut, StorageEntry, B256};
-use reth_provider::{DatabaseProviderRW, HashingWriter, StatsReader, StorageReader};
+use reth_provider::{
+    providers::is_snap_synced, DatabaseProviderRW, HashingWriter, StatsReader, StorageReader,
+};
 use reth_stages_api::{
     EntitiesCheckpoint, ExecInput, ExecOutput, Stage, StageCheckpoint, StageError, StageId,
     StorageHashingCheckpoint, UnwindInput, UnwindOutput,
@@ -84,8 +86,11 @@ impl<DB: Database> Stage<DB> for StorageHashingStage {
         // if there are more blocks then threshold it is faster to go over Plain state and hash all
         // account otherwise take changesets aggregate the sets and apply hashing to
         // AccountHashing table. Also, if we start from genesis, we need to hash from scratch, as
-        // genesis accounts are not in changeset, along with their storages.
-        if to_block - from_block > self.clean_threshold || from_block == 1 {
+        // genesis accounts are not in changeset, along with their storages. The plain state of
+        // snap synced nodes is incomplete though, so their hashed state can only be updated
+        // incrementally.
+        if (to_block - from_block > self.clean_threshold || from_block == 1) && !is_snap_synced(tx)?
+        {
             // clear table, load all accounts and hash it
             tx.clear::<tables::HashedStorages>()?;
 
@@ -158,8 +158,8 @@ where
         }
 
         // Since ETL sorts all entries by hashes, we are either appending (first sync) or inserting
-        // in order (further syncs).
-        for (index, hash_to_number) in self.hash_collector.iter()?.enumerate() {
+        // in order (further syncs). The key ranges of the hashes are merged in parallel.
+        for (index, hash_to_number) in self.hash_collector.par_iter()?.enumerate() {
             let (hash, number) = hash_to_number?;
 
             if index > 0 && index % interval == 0 && total_headers > 100 {
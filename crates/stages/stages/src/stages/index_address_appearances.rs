@@ -0,0 +1,460 @@
+use super::{collect_indices, load_history_indices};
+use reth_config::config::{EtlConfig, IndexAddressAppearancesConfig};
+use reth_db::tables;
+use reth_db_api::{database::Database, models::ShardedKey, table::Decode, transaction::DbTxMut};
+use reth_evm::{
+    execute::{BlockExecutionError, BlockValidationError},
+    system_calls::pre_block_beacon_root_contract_call,
+    ConfigureEvm,
+};
+use reth_primitives::{Address, BlockNumber, TxNumber, U256};
+use reth_provider::{
+    providers::LowestAvailableBlocks, BlockReader, DatabaseProviderRW, EvmEnvProvider,
+    HistoricalStateProviderRef, HistoryWriter, ProviderError, PruneCheckpointReader,
+    PruneCheckpointWriter, TransactionVariant,
+};
+use reth_prune_types::{PruneCheckpoint, PruneMode, PrunePurpose, PruneSegment};
+use reth_revm::{
+    database::StateProviderDatabase,
+    db::CacheDB,
+    interpreter::{CallInputs, CallOutcome, CreateInputs, CreateOutcome},
+    primitives::{CfgEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg, ResultAndState, SpecId, TxEnv},
+    DatabaseCommit, EvmContext, Inspector,
+};
+use reth_stages_api::{
+    BlockErrorKind, ExecInput, ExecOutput, Stage, StageCheckpoint, StageError, StageId,
+    UnwindInput, UnwindOutput,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Debug,
+};
+use tracing::info;
+
+/// Stage is indexing the blocks in which an address appears as a transaction sender, transaction
+/// recipient or in one of the call frames of a transaction, e.g. as the target of an internal
+/// call. For more information on index sharding take a look at [`tables::AddressAppearances`].
+///
+/// The call frames are collected by replaying the transactions of each block on top of the state
+/// of its parent, which is why the stage runs after the history of the state is indexed. The
+/// addresses of each transaction are stored in [`tables::TransactionAddressAppearances`] as well,
+/// so that the transactions of a block an address appears in can be looked up without replaying
+/// the block.
+///
+/// The stage is optional and only added to the pipeline if enabled in
+/// [`IndexAddressAppearancesConfig`].
+#[derive(Debug)]
+pub struct IndexAddressAppearancesStage<EvmConfig> {
+    /// The EVM configuration used to replay the transactions.
+    evm_config: EvmConfig,
+    /// Number of blocks after which the control
+    /// flow will be returned to the pipeline for commit.
+    pub commit_threshold: u64,
+    /// Pruning configuration.
+    pub prune_mode: Option<PruneMode>,
+    /// ETL configuration
+    pub etl_config: EtlConfig,
+}
+
+impl<EvmConfig> IndexAddressAppearancesStage<EvmConfig> {
+    /// Create new instance of [`IndexAddressAppearancesStage`].
+    pub const fn new(
+        evm_config: EvmConfig,
+        config: IndexAddressAppearancesConfig,
+        etl_config: EtlConfig,
+        prune_mode: Option<PruneMode>,
+    ) -> Self {
+        Self { evm_config, commit_threshold: config.commit_threshold, etl_config, prune_mode }
+    }
+}
+
+impl<EvmConfig: ConfigureEvm> IndexAddressAppearancesStage<EvmConfig> {
+    /// Replays the transactions of the block and returns the addresses that appear in each of
+    /// them, keyed by transaction number.
+    fn block_appearances<DB: Database>(
+        &self,
+        provider: &DatabaseProviderRW<DB>,
+        block_number: BlockNumber,
+        lowest_available_blocks: LowestAvailableBlocks,
+    ) -> Result<BTreeMap<TxNumber, BTreeSet<Address>>, StageError> {
+        let body = provider
+            .block_body_indices(block_number)?
+            .ok_or(ProviderError::BlockBodyIndicesNotFound(block_number))?;
+        if body.is_empty() {
+            return Ok(BTreeMap::new())
+        }
+
+        let block = provider
+            .block_with_senders(block_number.into(), TransactionVariant::NoHash)?
+            .ok_or_else(|| ProviderError::HeaderNotFound(block_number.into()))?;
+
+        let mut cfg = CfgEnvWithHandlerCfg::new_with_spec_id(CfgEnv::default(), SpecId::LATEST);
+        let mut block_env = Default::default();
+        provider.fill_env_with_header(
+            &mut cfg,
+            &mut block_env,
+            &block.header,
+            self.evm_config.clone(),
+        )?;
+
+        let state = HistoricalStateProviderRef::new_with_lowest_available_blocks(
+            provider.tx_ref(),
+            block_number,
+            lowest_available_blocks,
+            provider.static_file_provider().clone(),
+        );
+        let mut db = CacheDB::new(StateProviderDatabase::new(state));
+
+        let block_error = |error: BlockExecutionError| StageError::Block {
+            block: Box::new(block.header.clone().seal_slow()),
+            error: BlockErrorKind::Execution(error),
+        };
+        pre_block_beacon_root_contract_call(
+            &mut db,
+            &self.evm_config,
+            provider.chain_spec(),
+            &cfg,
+            &block_env,
+            block.number,
+            block.timestamp,
+            block.parent_beacon_block_root,
+        )
+        .map_err(block_error)?;
+
+        let mut appearances = BTreeMap::new();
+        for (tx_number, (sender, transaction)) in
+            body.tx_num_range().zip(block.transactions_with_sender())
+        {
+            let mut tx_env = TxEnv::default();
+            self.evm_config.fill_tx_env(&mut tx_env, transaction, *sender);
+            let env = EnvWithHandlerCfg::new_with_cfg_env(cfg.clone(), block_env.clone(), tx_env);
+
+            let mut inspector = AppearancesInspector::default();
+            let ResultAndState { state, .. } = self
+                .evm_config
+                .evm_with_env_and_inspector(&mut db, env, &mut inspector)
+                .transact()
+                .map_err(|error| {
+                    block_error(
+                        BlockValidationError::EVM {
+                            hash: transaction.recalculate_hash(),
+                            error: Box::new(error),
+                        }
+                        .into(),
+                    )
+                })?;
+            db.commit(state);
+
+            let mut addresses = inspector.addresses;
+            addresses.insert(*sender);
+            addresses.extend(transaction.to());
+            appearances.insert(tx_number, addresses);
+        }
+
+        Ok(appearances)
+    }
+}
+
+impl<DB, EvmConfig> Stage<DB> for IndexAddressAppearancesStage<EvmConfig>
+where
+    DB: Database,
+    EvmConfig: ConfigureEvm,
+{
+    /// Return the id of the stage
+    fn id(&self) -> StageId {
+        StageId::IndexAddressAppearances
+    }
+
+    /// Execute the stage.
+    fn execute(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        mut input: ExecInput,
+    ) -> Result<ExecOutput, StageError> {
+        if let Some((target_prunable_block, prune_mode)) = self
+            .prune_mode
+            .map(|mode| {
+                mode.prune_target_block(
+                    input.target(),
+                    PruneSegment::AddressAppearances,
+                    PrunePurpose::User,
+                )
+            })
+            .transpose()?
+            .flatten()
+        {
+            if target_prunable_block > input.checkpoint().block_number {
+                input.checkpoint = Some(StageCheckpoint::new(target_prunable_block));
+
+                // Save prune checkpoint only if we don't have one already.
+                // Otherwise, pruner may skip the unpruned range of blocks.
+                if provider.get_prune_checkpoint(PruneSegment::AddressAppearances)?.is_none() {
+                    provider.save_prune_checkpoint(
+                        PruneSegment::AddressAppearances,
+                        PruneCheckpoint {
+                            block_number: Some(target_prunable_block),
+                            tx_number: None,
+                            prune_mode,
+                        },
+                    )?;
+                }
+            }
+        }
+
+        // Blocks are replayed on top of the state of their parent, which isn't available for the
+        // blocks whose state history is pruned.
+        let pruned_block = |segment| -> Result<_, StageError> {
+            Ok(provider
+                .get_prune_checkpoint(segment)?
+                .and_then(|checkpoint| checkpoint.block_number))
+        };
+        let (account_history_pruned, storage_history_pruned) = (
+            pruned_block(PruneSegment::AccountHistory)?,
+            pruned_block(PruneSegment::StorageHistory)?,
+        );
+        if let Some(pruned_block) = account_history_pruned.max(storage_history_pruned) {
+            if pruned_block > input.checkpoint().block_number {
+                input.checkpoint = Some(StageCheckpoint::new(pruned_block));
+            }
+        }
+        let lowest_available_blocks = LowestAvailableBlocks {
+            account_history_block_number: account_history_pruned.map(|block| block + 1),
+            storage_history_block_number: storage_history_pruned.map(|block| block + 1),
+        };
+
+        if input.target_reached() {
+            return Ok(ExecOutput::done(input.checkpoint()))
+        }
+
+        let (mut range, is_final_range) =
+            input.next_block_range_with_threshold(self.commit_threshold);
+        let first_sync = input.checkpoint().block_number == 0;
+
+        // On first sync we might have appearances coming from genesis. We clear the table since
+        // it's faster to rebuild from scratch.
+        if first_sync {
+            provider.tx_ref().clear::<tables::AddressAppearances>()?;
+            provider.tx_ref().clear::<tables::TransactionAddressAppearances>()?;
+            range = 0..=*range.end();
+        }
+
+        info!(target: "sync::stages::index_address_appearances::exec", ?first_sync, ?range, "Collecting indices");
+        let total_blocks = range.end() - range.start() + 1;
+        let interval = (total_blocks / 100).max(1);
+        let entries = range.clone().flat_map(|block_number| {
+            if block_number % interval == 0 && total_blocks > 100 {
+                info!(target: "sync::stages::index_address_appearances::exec", block_number, "Collecting indices");
+            }
+            let appearances = self.block_appearances(provider, block_number, lowest_available_blocks).and_then(|appearances| {
+                let addresses =
+                    appearances.values().flatten().copied().collect::<BTreeSet<_>>();
+                provider.insert_transaction_address_appearances(appearances)?;
+                Ok(addresses)
+            });
+            match appearances {
+                Ok(addresses) => addresses
+                    .into_iter()
+                    .map(|address| Ok((block_number, address)))
+                    .collect::<Vec<_>>(),
+                Err(err) => vec![Err(err)],
+            }
+        });
+        let collector = collect_indices::<tables::AddressAppearances, _>(
+            entries,
+            ShardedKey::new,
+            &self.etl_config,
+        )?;
+
+        info!(target: "sync::stages::index_address_appearances::exec", "Loading indices into database");
+        load_history_indices::<_, tables::AddressAppearances, _>(
+            provider,
+            collector,
+            first_sync,
+            ShardedKey::new,
+            ShardedKey::<Address>::decode,
+            |key| key.key,
+        )?;
+
+        Ok(ExecOutput { checkpoint: StageCheckpoint::new(*range.end()), done: is_final_range })
+    }
+
+    /// Unwind the stage.
+    fn unwind(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        let (range, unwind_progress, _) =
+            input.unwind_block_range_with_threshold(self.commit_threshold);
+
+        provider.unwind_address_appearance_indices(range)?;
+
+        Ok(UnwindOutput { checkpoint: StageCheckpoint::new(unwind_progress) })
+    }
+}
+
+/// Collects the addresses that take part in the call frames of a transaction.
+#[derive(Debug, Default)]
+struct AppearancesInspector {
+    addresses: BTreeSet<Address>,
+}
+
+impl<DB: reth_revm::Database> Inspector<DB> for AppearancesInspector {
+    fn call(
+        &mut self,
+        context: &mut EvmContext<DB>,
+        inputs: &mut CallInputs,
+    ) -> Option<CallOutcome> {
+        self.addresses.insert(inputs.caller);
+        // calls to precompiles aren't indexed, they would appear in every other block
+        if !context.precompiles.contains(&inputs.bytecode_address) {
+            self.addresses.extend([inputs.target_address, inputs.bytecode_address]);
+        }
+        None
+    }
+
+    fn create_end(
+        &mut self,
+        _context: &mut EvmContext<DB>,
+        inputs: &CreateInputs,
+        outcome: CreateOutcome,
+    ) -> CreateOutcome {
+        self.addresses.insert(inputs.caller);
+        self.addresses.extend(outcome.address);
+        outcome
+    }
+
+    fn selfdestruct(&mut self, contract: Address, target: Address, _value: U256) {
+        self.addresses.extend([contract, target]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_rlp::Decodable;
+    use reth_chainspec::ChainSpecBuilder;
+    use reth_db::BlockNumberList;
+    use reth_db_api::{cursor::DbCursorRO, transaction::DbTx};
+    use reth_evm_ethereum::EthEvmConfig;
+    use reth_primitives::{
+        address, hex_literal::hex, keccak256, Account, Bytecode, SealedBlock, StaticFileSegment,
+    };
+    use reth_provider::{
+        test_utils::create_test_provider_factory_with_chain_spec, AddressAppearancesReader,
+        ProviderFactory, StaticFileWriter,
+    };
+    use std::sync::Arc;
+
+    fn stage() -> IndexAddressAppearancesStage<EthEvmConfig> {
+        IndexAddressAppearancesStage::new(
+            EthEvmConfig::default(),
+            IndexAddressAppearancesConfig::default(),
+            EtlConfig::default(),
+            None,
+        )
+    }
+
+    type Tables = (BTreeMap<ShardedKey<Address>, Vec<u64>>, Vec<(TxNumber, Address)>);
+
+    fn cast<DB: Database>(factory: &ProviderFactory<DB>) -> Tables {
+        let tx = factory.db_ref().tx().unwrap();
+        let appearances = tx
+            .cursor_read::<tables::AddressAppearances>()
+            .unwrap()
+            .walk(None)
+            .unwrap()
+            .map(|entry| entry.map(|(k, v)| (k, v.iter().collect())))
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let transaction_appearances = tx
+            .cursor_read::<tables::TransactionAddressAppearances>()
+            .unwrap()
+            .walk(None)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        (appearances, transaction_appearances)
+    }
+
+    #[test]
+    fn index_internal_call_targets() {
+        let factory = create_test_provider_factory_with_chain_spec(Arc::new(
+            ChainSpecBuilder::mainnet().berlin_activated().build(),
+        ));
+        let provider = factory.provider_rw().unwrap();
+        let mut genesis_rlp = hex!("f901faf901f5a00000000000000000000000000000000000000000000000000000000000000000a01dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347942adc25665018aa1fe0e6bc666dac8fc2697ff9baa045571b40ae66ca7480791bbb2887286e4e4c4b1b298b191c889d6959023a32eda056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421b901000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000083020000808502540be400808000a00000000000000000000000000000000000000000000000000000000000000000880000000000000000c0c0").as_slice();
+        let genesis = SealedBlock::decode(&mut genesis_rlp).unwrap();
+        let mut block_rlp = hex!("f90262f901f9a075c371ba45999d87f4542326910a11af515897aebce5265d3f6acd1f1161f82fa01dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347942adc25665018aa1fe0e6bc666dac8fc2697ff9baa098f2dcd87c8ae4083e7017a05456c14eea4b1db2032126e27b3b1563d57d7cc0a08151d548273f6683169524b66ca9fe338b9ce42bc3540046c828fd939ae23bcba03f4e5c2ec5b2170b711d97ee755c160457bb58d8daa338e835ec02ae6860bbabb901000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000083020000018502540be40082a8798203e800a00000000000000000000000000000000000000000000000000000000000000000880000000000000000f863f861800a8405f5e10094100000000000000000000000000000000000000080801ba07e09e26678ed4fac08a249ebe8ed680bf9051a5e14ad223e4b2b9d26e0208f37a05f6e3f188e3e6eab7d7d3b6568f5eac7d687b08d307d3154ccd8c87b4630509bc0").as_slice();
+        let block = SealedBlock::decode(&mut block_rlp).unwrap();
+        provider.insert_historical_block(genesis.try_seal_with_senders().unwrap()).unwrap();
+        provider.insert_historical_block(block.try_seal_with_senders().unwrap()).unwrap();
+        provider
+            .static_file_provider()
+            .latest_writer(StaticFileSegment::Headers)
+            .unwrap()
+            .commit()
+            .unwrap();
+
+        // The recipient of the transaction calls another account.
+        let sender = address!("a94f5374fce5edbc8e2a8697c15331677e6ebf0b");
+        let recipient = address!("1000000000000000000000000000000000000000");
+        let callee = address!("2000000000000000000000000000000000000000");
+        let code = hex!("600060006000600060007320000000000000000000000000000000000000006103e8f100");
+        let code_hash = keccak256(code);
+        let db_tx = provider.tx_ref();
+        db_tx
+            .put::<tables::PlainAccountState>(
+                recipient,
+                Account { nonce: 0, balance: U256::ZERO, bytecode_hash: Some(code_hash) },
+            )
+            .unwrap();
+        db_tx
+            .put::<tables::PlainAccountState>(
+                sender,
+                Account {
+                    nonce: 0,
+                    balance: U256::from(0x3635c9adc5dea00000u128),
+                    bytecode_hash: None,
+                },
+            )
+            .unwrap();
+        db_tx.put::<tables::Bytecodes>(code_hash, Bytecode::new_raw(code.to_vec().into())).unwrap();
+        for address in [sender, recipient] {
+            db_tx
+                .put::<tables::AccountsHistory>(
+                    ShardedKey::last(address),
+                    BlockNumberList::new([0]).unwrap(),
+                )
+                .unwrap();
+        }
+        provider.commit().unwrap();
+
+        let provider = factory.provider_rw().unwrap();
+        let output =
+            stage().execute(&provider, ExecInput { target: Some(1), checkpoint: None }).unwrap();
+        assert_eq!(output, ExecOutput { checkpoint: StageCheckpoint::new(1), done: true });
+        provider.commit().unwrap();
+
+        let (appearances, transaction_appearances) = cast(&factory);
+        assert_eq!(
+            appearances,
+            BTreeMap::from([
+                (ShardedKey::last(recipient), vec![1]),
+                (ShardedKey::last(callee), vec![1]),
+                (ShardedKey::last(sender), vec![1]),
+            ])
+        );
+        assert_eq!(transaction_appearances, vec![(0, recipient), (0, callee), (0, sender)]);
+        assert_eq!(factory.address_appearances_in_block(callee, 1).unwrap(), vec![0]);
+
+        let provider = factory.provider_rw().unwrap();
+        let input =
+            UnwindInput { checkpoint: StageCheckpoint::new(1), unwind_to: 0, ..Default::default() };
+        let output = stage().unwind(&provider, input).unwrap();
+        assert_eq!(output, UnwindOutput { checkpoint: StageCheckpoint::new(0) });
+        provider.commit().unwrap();
+
+        assert_eq!(cast(&factory), (BTreeMap::new(), Vec::new()));
+    }
+}
@@ -316,7 +316,11 @@ impl<DB: Database> Stage<DB> for MerkleStage {
 
         // Unwind trie only if there are transitions
         if !range.is_empty() {
-            let (block_root, updates) = StateRoot::incremental_root_with_updates(tx, range)
+            // The changesets of the unwound blocks might have been moved to static files already.
+            let prefix_sets = provider.changeset_prefix_sets(range)?;
+            let (block_root, updates) = StateRoot::from_tx(tx)
+                .with_prefix_sets(prefix_sets)
+                .root_with_updates()
                 .map_err(|e| StageError::Fatal(Box::new(e)))?;
 
             // Validate the calculated state root
@@ -0,0 +1,1751 @@
+use alloy_rlp::Decodable;
+use futures_util::{stream::FuturesUnordered, Future, FutureExt, StreamExt};
+use reth_codecs::Compact;
+use reth_config::config::SnapSyncConfig;
+use reth_db::tables;
+use reth_db_api::{
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+    database::Database,
+    transaction::{DbTx, DbTxMut},
+    DatabaseError,
+};
+use reth_network_p2p::{
+    download::DownloadClient,
+    error::PeerRequestResult,
+    snap::client::{
+        AccountData, AccountRange, ByteCodes, GetAccountRange, GetByteCodes, GetStorageRanges,
+        GetTrieNodes, SnapClient, SnapFut, StorageRanges, TrieNodes,
+    },
+};
+use reth_network_peers::PeerId;
+use reth_primitives::{
+    keccak256, Account, BlockNumber, Bytecode, Bytes, StaticFileSegment, StorageEntry, B256,
+    KECCAK_EMPTY, U256,
+};
+use reth_provider::{
+    providers::{decode_snap_sync_pivot, StaticFileWriter},
+    DatabaseProviderRW, HeaderProvider, ProviderError, ProviderResult, PruneCheckpointWriter,
+    StageCheckpointReader, StageCheckpointWriter,
+};
+use reth_prune_types::{PruneCheckpoint, PruneMode, PruneSegment};
+use reth_stages_api::{
+    ExecInput, ExecOutput, MerkleCheckpoint, SnapSyncCheckpoint, SnapSyncPhase, Stage,
+    StageCheckpoint, StageError, StageId, UnwindInput, UnwindOutput,
+};
+use reth_trie::{
+    prefix_set::TriePrefixSetsMut, proof::Proof, verify_range_proof, IntermediateStateRootState,
+    Nibbles, StateRoot, StateRootProgress, StoredSubNode, TrieAccount, TrieNode, EMPTY_ROOT_HASH,
+};
+use reth_trie_db::DatabaseStateRoot;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tracing::*;
+
+/// The number of ranges the accounts are split into, which are downloaded concurrently.
+const ACCOUNT_RANGES: u8 = 16;
+
+/// The maximum number of accounts to request the storage of at once.
+const MAX_STORAGE_ACCOUNTS: usize = 128;
+
+/// The maximum number of bytecodes to request at once.
+const MAX_BYTE_CODES: usize = 64;
+
+/// The maximum number of trie nodes to request at once.
+const MAX_TRIE_NODES: usize = 512;
+
+/// The maximum number of accounts to check for missing bytecodes at once.
+const MAX_BYTE_CODE_SCAN: usize = 100_000;
+
+/// The number of consecutive empty responses after which the state of the pivot block is
+/// considered to be no longer served by the peers.
+const STALE_PIVOT_EMPTY_RESPONSES: usize = 16;
+
+/// The snap sync stage downloads the state of a recent block over the `snap` protocol, instead of
+/// executing all blocks up to it.
+///
+/// The stage is run after the [`BodyStage`][crate::stages::BodyStage], and picks a pivot block at
+/// a configured distance from the target. It runs in the following phases, whose progress is
+/// saved in a [`SnapSyncCheckpoint`]:
+///
+/// 1. The accounts and their storage are downloaded from the peers in ranges, into the
+///    [`HashedAccounts`][reth_db::tables::HashedAccounts] and
+///    [`HashedStorages`][reth_db::tables::HashedStorages] tables. The ranges are verified against
+///    their proofs before they are written, and the peers serving invalid ranges are penalized.
+/// 2. The trie is built from the downloaded state, like the
+///    [`MerkleStage`][crate::stages::MerkleStage] does.
+/// 3. If the state root doesn't match the one of the pivot block, because the downloaded ranges
+///    were served at different states, the trie is healed: the nodes differing from the ones of the
+///    pivot state are downloaded top-down, and the state below them is repaired.
+/// 4. The bytecodes of the accounts missing from the database are downloaded.
+///
+/// Once the state is complete, the checkpoints of the execution, hashing, merkle and history
+/// stages are moved to the pivot block, so the pipeline continues by executing the blocks after
+/// it. The history before the pivot block is not available, which is recorded as pruned.
+///
+/// The stage is skipped if it's disabled, if the pivot would be the genesis block, or if blocks
+/// were executed already.
+///
+/// If the peers stop serving the state of the pivot block, a newer block up to the target is picked
+/// as the pivot. The state downloaded so far is kept, and healed against the state of the new pivot
+/// block.
+#[derive(Debug)]
+pub struct SnapSyncStage<C> {
+    /// The client to download the state with.
+    client: C,
+    /// The configuration of the stage.
+    config: SnapSyncConfig,
+    /// The state of the sync, loaded on the first execution.
+    sync: Option<SnapSync>,
+    /// The last pivot block whose state was not served anymore.
+    stale_pivot: Option<BlockNumber>,
+    /// The requests in flight.
+    inflight: FuturesUnordered<SnapRequestFuture>,
+    /// The responses received since the last execution.
+    responses: Vec<(SnapRequest, PeerRequestResult<SnapResponse>)>,
+}
+
+impl<C> SnapSyncStage<C> {
+    /// Create new instance of [`SnapSyncStage`].
+    pub fn new(client: C, config: SnapSyncConfig) -> Self {
+        Self {
+            client,
+            config,
+            sync: None,
+            stale_pivot: None,
+            inflight: FuturesUnordered::new(),
+            responses: Vec::new(),
+        }
+    }
+
+    /// Drops the in-memory state of the sync, and the requests in flight.
+    fn reset(&mut self) {
+        self.sync = None;
+        self.inflight.clear();
+        self.responses.clear();
+    }
+}
+
+/// Returns the pivot block whose state was downloaded over `snap/1`, if the snap sync finished.
+///
+/// The receipts of the blocks up to the pivot block, and the history before it, are not available.
+pub fn snap_sync_pivot(
+    provider: &impl StageCheckpointReader,
+) -> ProviderResult<Option<BlockNumber>> {
+    let buf = provider.get_stage_checkpoint_progress(StageId::SnapSync)?.unwrap_or_default();
+    Ok(decode_snap_sync_pivot(&buf))
+}
+
+impl<DB: Database, C: SnapClient + 'static> Stage<DB> for SnapSyncStage<C> {
+    /// Return the id of the stage
+    fn id(&self) -> StageId {
+        StageId::SnapSync
+    }
+
+    fn poll_execute_ready(
+        &mut self,
+        cx: &mut Context<'_>,
+        _input: ExecInput,
+    ) -> Poll<Result<(), StageError>> {
+        let Some(sync) = &mut self.sync else { return Poll::Ready(Ok(())) };
+
+        while self.inflight.len() < self.config.max_concurrent_requests {
+            let Some(request) = sync.next_request() else { break };
+            self.inflight.push(send_request(
+                &self.client,
+                sync.state_root,
+                self.config.response_bytes,
+                request,
+            ));
+        }
+
+        while let Poll::Ready(Some(response)) = self.inflight.poll_next_unpin(cx) {
+            self.responses.push(response);
+        }
+
+        // Without requests in flight, the stage has to make progress locally
+        if self.responses.is_empty() && !self.inflight.is_empty() {
+            return Poll::Pending
+        }
+        Poll::Ready(Ok(()))
+    }
+
+    /// Download the state of the pivot block.
+    fn execute(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        input: ExecInput,
+    ) -> Result<ExecOutput, StageError> {
+        let Some(sync) = &mut self.sync else {
+            let min_pivot = self.stale_pivot.map_or(0, |pivot| pivot + 1);
+            let Some(sync) =
+                SnapSync::load(provider, input.target(), self.config.pivot_distance, min_pivot)?
+            else {
+                return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
+            };
+
+            sync.save(provider)?;
+            self.sync = Some(sync);
+            return Ok(ExecOutput { checkpoint: input.checkpoint(), done: false })
+        };
+
+        let tx = provider.tx_ref();
+        for (request, response) in std::mem::take(&mut self.responses) {
+            sync.on_response(tx, &self.client, request, response)?;
+        }
+
+        if sync.empty_responses >= STALE_PIVOT_EMPTY_RESPONSES {
+            warn!(
+                target: "sync::stages::snap_sync",
+                pivot = sync.checkpoint.pivot,
+                "The state of the pivot block is not served anymore, a new pivot will be picked"
+            );
+            self.stale_pivot = Some(sync.checkpoint.pivot);
+            sync.save(provider)?;
+            self.reset();
+            return Ok(ExecOutput { checkpoint: input.checkpoint(), done: false })
+        }
+
+        let idle = self.inflight.is_empty();
+        match sync.checkpoint.phase {
+            SnapSyncPhase::Accounts => {
+                if sync.checkpoint.account_ranges.is_empty() {
+                    info!(target: "sync::stages::snap_sync", "Accounts downloaded, computing the state root");
+                    sync.checkpoint.phase = SnapSyncPhase::StateRoot;
+                }
+            }
+            SnapSyncPhase::StateRoot => sync.compute_state_root(tx)?,
+            SnapSyncPhase::Healing => {
+                if idle && sync.heal_tasks.is_empty() {
+                    sync.finish_healing_round(tx)?;
+                }
+            }
+            SnapSyncPhase::ByteCodes => {
+                if idle && sync.code_tasks.is_empty() {
+                    sync.next_byte_codes(tx)?;
+                }
+            }
+            SnapSyncPhase::Done => {}
+        }
+        sync.save(provider)?;
+
+        if sync.checkpoint.phase == SnapSyncPhase::Done {
+            info!(target: "sync::stages::snap_sync", pivot = sync.checkpoint.pivot, "State of the pivot block downloaded");
+            sync.finish(provider)?;
+            self.reset();
+            return Ok(ExecOutput::done(StageCheckpoint::new(input.target())))
+        }
+
+        Ok(ExecOutput { checkpoint: input.checkpoint(), done: false })
+    }
+
+    /// Unwind the stage.
+    fn unwind(
+        &mut self,
+        provider: &DatabaseProviderRW<DB>,
+        input: UnwindInput,
+    ) -> Result<UnwindOutput, StageError> {
+        self.reset();
+
+        // The state before the pivot block is not available
+        if let Some(checkpoint) = SnapSync::checkpoint(provider)? {
+            if checkpoint.phase == SnapSyncPhase::Done && input.unwind_to < checkpoint.pivot {
+                return Err(StageError::Fatal(
+                    format!(
+                        "cannot unwind to block {} before the snap sync pivot block {}",
+                        input.unwind_to, checkpoint.pivot
+                    )
+                    .into(),
+                ))
+            }
+        }
+
+        Ok(UnwindOutput { checkpoint: StageCheckpoint::new(input.unwind_to) })
+    }
+}
+
+/// A request sent to a peer, with the context needed to process its response.
+#[derive(Debug)]
+enum SnapRequest {
+    /// The accounts of the account range with the given last hash, from the origin.
+    AccountRange { range: B256, origin: B256 },
+    /// The storage of the accounts.
+    StorageRanges(Vec<StorageTask>),
+    /// The bytecodes with the hashes.
+    ByteCodes(Vec<B256>),
+    /// The trie nodes to heal.
+    TrieNodes(Vec<HealTask>),
+}
+
+/// A response of a peer.
+#[derive(Debug)]
+enum SnapResponse {
+    AccountRange(AccountRange),
+    StorageRanges(StorageRanges),
+    ByteCodes(ByteCodes),
+    TrieNodes(TrieNodes),
+}
+
+/// The future of a request in flight.
+type SnapRequestFuture =
+    Pin<Box<dyn Future<Output = (SnapRequest, PeerRequestResult<SnapResponse>)> + Send + Sync>>;
+
+/// Sends the request for the state with the given root.
+fn send_request<C: SnapClient>(
+    client: &C,
+    root_hash: B256,
+    response_bytes: u64,
+    request: SnapRequest,
+) -> SnapRequestFuture {
+    let response = match &request {
+        SnapRequest::AccountRange { range, origin } => map_response(
+            client.get_account_range(GetAccountRange {
+                request_id: 0,
+                root_hash,
+                starting_hash: *origin,
+                limit_hash: *range,
+                response_bytes,
+            }),
+            SnapResponse::AccountRange,
+        ),
+        SnapRequest::StorageRanges(tasks) => map_response(
+            client.get_storage_ranges(GetStorageRanges {
+                request_id: 0,
+                root_hash,
+                account_hashes: tasks.iter().map(|task| task.account).collect(),
+                // Only the first account can continue from a slot
+                starting_hash: Bytes::copy_from_slice(tasks[0].origin.as_slice()),
+                limit_hash: Bytes::new(),
+                response_bytes,
+            }),
+            SnapResponse::StorageRanges,
+        ),
+        SnapRequest::ByteCodes(hashes) => map_response(
+            client.get_byte_codes(GetByteCodes {
+                request_id: 0,
+                hashes: hashes.clone(),
+                response_bytes,
+            }),
+            SnapResponse::ByteCodes,
+        ),
+        SnapRequest::TrieNodes(tasks) => {
+            // The paths of consecutive nodes of the same storage trie are grouped
+            let mut paths: Vec<Vec<Bytes>> = Vec::new();
+            let mut last_account = None;
+            for task in tasks {
+                let path = Bytes::copy_from_slice(&task.path.encode_path_leaf(false));
+                match task.account {
+                    Some(account) if last_account == Some(account) => {
+                        paths.last_mut().expect("account path set exists").push(path)
+                    }
+                    Some(account) => {
+                        paths.push(vec![Bytes::copy_from_slice(account.as_slice()), path])
+                    }
+                    None => paths.push(vec![path]),
+                }
+                last_account = task.account;
+            }
+
+            map_response(
+                client.get_trie_nodes(GetTrieNodes {
+                    request_id: 0,
+                    root_hash,
+                    paths,
+                    response_bytes,
+                }),
+                SnapResponse::TrieNodes,
+            )
+        }
+    };
+
+    Box::pin(response.map(|response| (request, response)))
+}
+
+/// Wraps the response of a request into a [`SnapResponse`].
+fn map_response<T: 'static>(
+    response: SnapFut<T>,
+    wrap: fn(T) -> SnapResponse,
+) -> SnapFut<SnapResponse> {
+    Box::pin(response.map(move |response| response.map(|response| response.map(wrap))))
+}
+
+/// The download of the storage of an account.
+#[derive(Debug)]
+struct StorageTask {
+    /// The last hash of the account range the account belongs to.
+    range: B256,
+    /// The hashed address of the account.
+    account: B256,
+    /// The storage root of the account in the pivot state.
+    root: B256,
+    /// The hash of the next slot to download.
+    origin: B256,
+}
+
+/// A trie node to heal.
+#[derive(Debug)]
+struct HealTask {
+    /// The hashed address of the account the storage trie belongs to, or `None` for the account
+    /// trie.
+    account: Option<B256>,
+    /// The path of the node.
+    path: Nibbles,
+    /// The hash of the node in the pivot state.
+    hash: B256,
+}
+
+/// The download status of an account range.
+#[derive(Debug)]
+enum RangeStatus {
+    /// The next accounts need to be requested.
+    Idle,
+    /// The next accounts are requested.
+    Requested,
+    /// The accounts up to the covered hash are downloaded, and the storage of some of them is
+    /// pending.
+    Storage {
+        /// The last hash covered by the downloaded accounts.
+        covered: B256,
+        /// Whether the downloaded accounts complete the range.
+        complete: bool,
+        /// The number of accounts whose storage is pending.
+        pending: usize,
+    },
+}
+
+/// The state of the snap sync.
+#[derive(Debug)]
+struct SnapSync {
+    /// The progress of the sync.
+    checkpoint: SnapSyncCheckpoint,
+    /// The state root of the pivot block.
+    state_root: B256,
+    /// The download status of the account ranges, by the last hash of the range.
+    ranges: HashMap<B256, RangeStatus>,
+    /// The storage to download.
+    storage_tasks: VecDeque<StorageTask>,
+    /// The trie nodes to heal.
+    heal_tasks: VecDeque<HealTask>,
+    /// The keys changed by the current healing round.
+    heal_prefix_sets: TriePrefixSetsMut,
+    /// The bytecodes to download.
+    code_tasks: VecDeque<B256>,
+    /// The next hashed address to check for a missing bytecode, once the bytecodes being
+    /// downloaded are complete.
+    code_batch_end: Option<B256>,
+    /// Whether all accounts were checked for a missing bytecode.
+    codes_exhausted: bool,
+    /// The number of consecutive empty responses.
+    empty_responses: usize,
+}
+
+impl SnapSync {
+    /// Reads the progress of the sync.
+    fn checkpoint<DB: Database>(
+        provider: &DatabaseProviderRW<DB>,
+    ) -> Result<Option<SnapSyncCheckpoint>, StageError> {
+        let buf = provider.get_stage_checkpoint_progress(StageId::SnapSync)?.unwrap_or_default();
+        if buf.is_empty() {
+            return Ok(None)
+        }
+
+        let (checkpoint, _) = SnapSyncCheckpoint::from_compact(&buf, buf.len());
+        Ok(Some(checkpoint))
+    }
+
+    /// Loads the state of the sync, moving the pivot block if the target moved, or if the state of
+    /// the pivot block is not served anymore.
+    ///
+    /// Returns `None` if the stage has nothing to do.
+    fn load<DB: Database>(
+        provider: &DatabaseProviderRW<DB>,
+        target: BlockNumber,
+        pivot_distance: u64,
+        min_pivot: BlockNumber,
+    ) -> Result<Option<Self>, StageError> {
+        let pivot = target.saturating_sub(pivot_distance).max(min_pivot).min(target);
+
+        let mut checkpoint = match Self::checkpoint(provider)? {
+            Some(checkpoint) => checkpoint,
+            None => {
+                let executed =
+                    provider.get_stage_checkpoint(StageId::Execution)?.unwrap_or_default();
+                if pivot == 0 || executed.block_number > 0 {
+                    return Ok(None)
+                }
+
+                debug!(target: "sync::stages::snap_sync", pivot, "Starting snap sync");
+                clear_state(provider.tx_ref())?;
+                SnapSyncCheckpoint {
+                    pivot,
+                    account_ranges: initial_account_ranges(),
+                    ..Default::default()
+                }
+            }
+        };
+
+        match checkpoint.phase {
+            SnapSyncPhase::Done => return Ok(None),
+            // The changes of the interrupted healing round are unknown, so the trie has to be
+            // rebuilt.
+            SnapSyncPhase::Healing => {
+                checkpoint.phase = SnapSyncPhase::StateRoot;
+                checkpoint.merkle = None;
+            }
+            _ => {}
+        }
+
+        if pivot > checkpoint.pivot {
+            debug!(target: "sync::stages::snap_sync", from = checkpoint.pivot, to = pivot, "Moving pivot");
+            checkpoint.pivot = pivot;
+
+            // The trie matches the state of the old pivot
+            if checkpoint.phase == SnapSyncPhase::ByteCodes {
+                checkpoint.phase = SnapSyncPhase::Healing;
+            }
+        }
+
+        let state_root = provider
+            .header_by_number(checkpoint.pivot)?
+            .ok_or_else(|| ProviderError::HeaderNotFound(checkpoint.pivot.into()))?
+            .state_root;
+
+        let mut heal_tasks = VecDeque::new();
+        if checkpoint.phase == SnapSyncPhase::Healing {
+            heal_tasks.push_back(HealTask {
+                account: None,
+                path: Nibbles::default(),
+                hash: state_root,
+            });
+        }
+
+        Ok(Some(Self {
+            ranges: checkpoint
+                .account_ranges
+                .iter()
+                .map(|(_, last)| (*last, RangeStatus::Idle))
+                .collect(),
+            checkpoint,
+            state_root,
+            storage_tasks: VecDeque::new(),
+            heal_tasks,
+            heal_prefix_sets: TriePrefixSetsMut::default(),
+            code_tasks: VecDeque::new(),
+            code_batch_end: None,
+            codes_exhausted: false,
+            empty_responses: 0,
+        }))
+    }
+
+    /// Saves the progress of the sync.
+    fn save<DB: Database>(&self, provider: &DatabaseProviderRW<DB>) -> Result<(), StageError> {
+        let mut buf = Vec::new();
+        self.checkpoint.to_compact(&mut buf);
+        Ok(provider.save_stage_checkpoint_progress(StageId::SnapSync, buf)?)
+    }
+
+    /// Returns the next request to send, if any.
+    fn next_request(&mut self) -> Option<SnapRequest> {
+        match self.checkpoint.phase {
+            SnapSyncPhase::Accounts => {
+                // The storage is downloaded first, so the account ranges can advance
+                if let Some(task) = self.storage_tasks.pop_front() {
+                    let mut tasks = vec![task];
+                    if tasks[0].origin.is_zero() {
+                        while tasks.len() < MAX_STORAGE_ACCOUNTS &&
+                            self.storage_tasks.front().is_some_and(|task| task.origin.is_zero())
+                        {
+                            tasks.extend(self.storage_tasks.pop_front());
+                        }
+                    }
+                    return Some(SnapRequest::StorageRanges(tasks))
+                }
+
+                let (origin, range) =
+                    *self.checkpoint.account_ranges.iter().find(|(_, last)| {
+                        matches!(self.ranges.get(last), Some(RangeStatus::Idle))
+                    })?;
+                self.ranges.insert(range, RangeStatus::Requested);
+                Some(SnapRequest::AccountRange { range, origin })
+            }
+            SnapSyncPhase::Healing if !self.heal_tasks.is_empty() => {
+                let count = self.heal_tasks.len().min(MAX_TRIE_NODES);
+                Some(SnapRequest::TrieNodes(self.heal_tasks.drain(..count).collect()))
+            }
+            SnapSyncPhase::ByteCodes if !self.code_tasks.is_empty() => {
+                let count = self.code_tasks.len().min(MAX_BYTE_CODES);
+                Some(SnapRequest::ByteCodes(self.code_tasks.drain(..count).collect()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Queues the request to be sent again.
+    fn retry(&mut self, request: SnapRequest) {
+        match request {
+            SnapRequest::AccountRange { range, .. } => {
+                self.ranges.insert(range, RangeStatus::Idle);
+            }
+            SnapRequest::StorageRanges(tasks) => {
+                for task in tasks.into_iter().rev() {
+                    self.storage_tasks.push_front(task);
+                }
+            }
+            SnapRequest::ByteCodes(hashes) => self.code_tasks.extend(hashes),
+            SnapRequest::TrieNodes(tasks) => self.heal_tasks.extend(tasks),
+        }
+    }
+
+    /// Processes the response of a request.
+    fn on_response<TX: DbTxMut + DbTx>(
+        &mut self,
+        tx: &TX,
+        client: &impl DownloadClient,
+        request: SnapRequest,
+        response: PeerRequestResult<SnapResponse>,
+    ) -> Result<(), StageError> {
+        let (peer_id, response) = match response {
+            Ok(response) => response.split(),
+            Err(error) => {
+                debug!(target: "sync::stages::snap_sync", %error, "Snap request failed");
+                self.retry(request);
+                return Ok(())
+            }
+        };
+
+        match (request, response) {
+            (SnapRequest::AccountRange { range, origin }, SnapResponse::AccountRange(response)) => {
+                self.on_account_range(tx, client, peer_id, range, origin, response)
+            }
+            (SnapRequest::StorageRanges(tasks), SnapResponse::StorageRanges(response)) => {
+                self.on_storage_ranges(tx, client, peer_id, tasks, response)
+            }
+            (SnapRequest::ByteCodes(hashes), SnapResponse::ByteCodes(response)) => {
+                self.on_byte_codes(tx, client, peer_id, hashes, response)
+            }
+            (SnapRequest::TrieNodes(tasks), SnapResponse::TrieNodes(response)) => {
+                self.on_trie_nodes(tx, client, peer_id, tasks, response)
+            }
+            (request, _) => {
+                self.retry(request);
+                Ok(())
+            }
+        }
+    }
+
+    /// Writes the accounts of the range, replacing the local ones, and queues the download of
+    /// their storage.
+    fn on_account_range<TX: DbTxMut + DbTx>(
+        &mut self,
+        tx: &TX,
+        client: &impl DownloadClient,
+        peer_id: PeerId,
+        range: B256,
+        origin: B256,
+        response: AccountRange,
+    ) -> Result<(), StageError> {
+        // Without a proof, the peer doesn't have the state
+        if response.accounts.is_empty() && response.proof.is_empty() {
+            self.empty_responses += 1;
+            self.ranges.insert(range, RangeStatus::Idle);
+            return Ok(())
+        }
+        self.empty_responses = 0;
+
+        let accounts = response.accounts;
+        let ordered = accounts.first().map_or(true, |account| account.hash >= origin) &&
+            accounts.windows(2).all(|accounts| accounts[0].hash < accounts[1].hash);
+        if !ordered || !verify_account_range(self.state_root, origin, &accounts, &response.proof) {
+            client.report_bad_message(peer_id);
+            self.ranges.insert(range, RangeStatus::Idle);
+            return Ok(())
+        }
+
+        // The range is complete if the peer has no more accounts in it, or it served the first one
+        // after it.
+        let (covered, complete) = match accounts.last() {
+            Some(account) if account.hash < range => (account.hash, false),
+            _ => (range, true),
+        };
+
+        delete_accounts(tx, origin, covered, |_| false)?;
+
+        let mut cursor = tx.cursor_write::<tables::HashedAccounts>()?;
+        let mut pending = 0;
+        for account in accounts.into_iter().take_while(|account| account.hash <= range) {
+            let body = account.body;
+            cursor.upsert(
+                account.hash,
+                Account {
+                    nonce: body.nonce,
+                    balance: body.balance,
+                    bytecode_hash: (body.code_hash != KECCAK_EMPTY).then_some(body.code_hash),
+                },
+            )?;
+
+            if body.storage_root != EMPTY_ROOT_HASH {
+                self.storage_tasks.push_back(StorageTask {
+                    range,
+                    account: account.hash,
+                    root: body.storage_root,
+                    origin: B256::ZERO,
+                });
+                pending += 1;
+            }
+        }
+
+        self.ranges.insert(range, RangeStatus::Storage { covered, complete, pending });
+        if pending == 0 {
+            self.advance_range(range);
+        }
+        Ok(())
+    }
+
+    /// Writes the storage slots of the accounts, and queues the rest of the storage of a
+    /// partially served account.
+    fn on_storage_ranges<TX: DbTxMut + DbTx>(
+        &mut self,
+        tx: &TX,
+        client: &impl DownloadClient,
+        peer_id: PeerId,
+        mut tasks: Vec<StorageTask>,
+        response: StorageRanges,
+    ) -> Result<(), StageError> {
+        if response.slots.is_empty() && response.proof.is_empty() {
+            self.empty_responses += 1;
+            self.retry(SnapRequest::StorageRanges(tasks));
+            return Ok(())
+        }
+        self.empty_responses = 0;
+
+        let served = response.slots.len();
+        let slots = response
+            .slots
+            .into_iter()
+            .zip(&tasks)
+            .enumerate()
+            .map(|(index, (slots, task))| {
+                let ordered = slots.first().map_or(true, |slot| slot.hash >= task.origin) &&
+                    slots.windows(2).all(|slots| slots[0].hash < slots[1].hash);
+                if !ordered {
+                    return None
+                }
+
+                // Only the storage of the last account can be served partially, with a proof
+                let proof = if index == served - 1 { &response.proof[..] } else { &[] };
+                let leaves = slots.iter().map(|slot| (slot.hash, &slot.data)).collect::<Vec<_>>();
+                verify_range_proof(task.root, task.origin, &leaves, proof).ok()?;
+                slots
+                    .into_iter()
+                    .map(|slot| Some((slot.hash, U256::decode(&mut &slot.data[..]).ok()?)))
+                    .collect::<Option<Vec<_>>>()
+            })
+            .collect::<Option<Vec<_>>>();
+        let Some(slots) = slots.filter(|_| served <= tasks.len()) else {
+            client.report_bad_message(peer_id);
+            self.retry(SnapRequest::StorageRanges(tasks));
+            return Ok(())
+        };
+
+        // The accounts which weren't served are requested again
+        let unserved = tasks.split_off(served);
+        self.retry(SnapRequest::StorageRanges(unserved));
+
+        let mut cursor = tx.cursor_dup_write::<tables::HashedStorages>()?;
+        for (index, (task, slots)) in tasks.into_iter().zip(slots).enumerate() {
+            let last = slots.last().map(|(hash, _)| *hash);
+            for (key, value) in slots {
+                write_storage_slot(&mut cursor, task.account, key, value)?;
+            }
+
+            // Only the storage of the last account can be served partially, which is proven
+            if index == served - 1 && !response.proof.is_empty() {
+                if let Some(origin) = last.and_then(next_hash) {
+                    self.storage_tasks.push_front(StorageTask { origin, ..task });
+                    continue
+                }
+            }
+
+            self.on_storage_complete(task.range);
+        }
+        Ok(())
+    }
+
+    /// Marks the storage of an account of the range as downloaded.
+    fn on_storage_complete(&mut self, range: B256) {
+        if let Some(RangeStatus::Storage { pending, .. }) = self.ranges.get_mut(&range) {
+            *pending -= 1;
+            if *pending == 0 {
+                self.advance_range(range);
+            }
+        }
+    }
+
+    /// Advances the account range past the downloaded accounts, or removes it if it's complete.
+    fn advance_range(&mut self, range: B256) {
+        let Some(RangeStatus::Storage { covered, complete, .. }) = self.ranges.remove(&range)
+        else {
+            return
+        };
+        let Some(index) =
+            self.checkpoint.account_ranges.iter().position(|(_, last)| *last == range)
+        else {
+            return
+        };
+
+        match next_hash(covered).filter(|_| !complete) {
+            Some(next) => {
+                self.checkpoint.account_ranges[index].0 = next;
+                self.ranges.insert(range, RangeStatus::Idle);
+            }
+            None => {
+                self.checkpoint.account_ranges.remove(index);
+                debug!(target: "sync::stages::snap_sync", ?range, remaining = self.checkpoint.account_ranges.len(), "Account range downloaded");
+            }
+        }
+    }
+
+    /// Writes the downloaded bytecodes.
+    fn on_byte_codes<TX: DbTxMut + DbTx>(
+        &mut self,
+        tx: &TX,
+        client: &impl DownloadClient,
+        peer_id: PeerId,
+        hashes: Vec<B256>,
+        response: ByteCodes,
+    ) -> Result<(), StageError> {
+        if response.codes.is_empty() {
+            self.empty_responses += 1;
+            self.code_tasks.extend(hashes);
+            return Ok(())
+        }
+        self.empty_responses = 0;
+
+        let mut codes: HashMap<_, _> =
+            response.codes.into_iter().map(|code| (keccak256(&code), code)).collect();
+        for hash in hashes {
+            match codes.remove(&hash) {
+                Some(code) => tx.put::<tables::Bytecodes>(hash, Bytecode::new_raw(code))?,
+                None => self.code_tasks.push_back(hash),
+            }
+        }
+
+        if !codes.is_empty() {
+            client.report_bad_message(peer_id);
+        }
+        Ok(())
+    }
+
+    /// Heals the trie with the downloaded nodes.
+    fn on_trie_nodes<TX: DbTxMut + DbTx>(
+        &mut self,
+        tx: &TX,
+        client: &impl DownloadClient,
+        peer_id: PeerId,
+        tasks: Vec<HealTask>,
+        response: TrieNodes,
+    ) -> Result<(), StageError> {
+        if response.nodes.is_empty() || response.nodes.len() > tasks.len() {
+            if response.nodes.is_empty() {
+                self.empty_responses += 1;
+            } else {
+                client.report_bad_message(peer_id);
+            }
+            self.heal_tasks.extend(tasks);
+            return Ok(())
+        }
+        self.empty_responses = 0;
+
+        let mut tasks = tasks.into_iter();
+        let mut children = Vec::new();
+        for node in response.nodes {
+            let task = tasks.next().expect("at most one node per task");
+            let decoded = (keccak256(&node) == task.hash)
+                .then(|| TrieNode::decode(&mut &node[..]).ok())
+                .flatten();
+            let Some(node) = decoded else {
+                client.report_bad_message(peer_id);
+                self.heal_tasks.push_back(task);
+                break
+            };
+
+            self.heal_node(tx, task.account, task.path, node, &mut children)?;
+        }
+        self.heal_tasks.extend(tasks);
+
+        self.queue_heal_tasks(tx, children)
+    }
+
+    /// Repairs the state below the node of the pivot state, collecting the children to heal.
+    fn heal_node<TX: DbTxMut + DbTx>(
+        &mut self,
+        tx: &TX,
+        account: Option<B256>,
+        path: Nibbles,
+        node: TrieNode,
+        children: &mut Vec<HealTask>,
+    ) -> Result<(), StageError> {
+        match node {
+            TrieNode::Branch(branch) => {
+                let mut stack = branch.stack.into_iter();
+                for nibble in 0..16 {
+                    let mut child_path = path.clone();
+                    child_path.push(nibble);
+
+                    if branch.state_mask.is_bit_set(nibble) {
+                        let child = stack.next().expect("a child for each bit of the mask");
+                        self.heal_child(tx, account, child_path, &child, children)?;
+                    } else {
+                        self.delete_subtrie(tx, account, &child_path, None)?;
+                    }
+                }
+            }
+            TrieNode::Extension(extension) => {
+                let mut child_path = path.clone();
+                child_path.extend_from_slice(&extension.key);
+
+                self.delete_subtrie(tx, account, &path, Some(&child_path))?;
+                self.heal_child(tx, account, child_path, &extension.child, children)?;
+            }
+            TrieNode::Leaf(leaf) => {
+                let mut key = path.clone();
+                key.extend_from_slice(&leaf.key);
+                if key.len() != 64 {
+                    return Err(StageError::Fatal("invalid trie leaf key length".into()))
+                }
+
+                self.delete_subtrie(tx, account, &path, Some(&key))?;
+
+                let hashed_key = B256::from_slice(&key.pack());
+                match account {
+                    None => {
+                        let account = TrieAccount::decode(&mut &leaf.value[..])
+                            .map_err(|err| StageError::Fatal(Box::new(err)))?;
+                        tx.put::<tables::HashedAccounts>(
+                            hashed_key,
+                            Account {
+                                nonce: account.nonce,
+                                balance: account.balance,
+                                bytecode_hash: (account.code_hash != KECCAK_EMPTY)
+                                    .then_some(account.code_hash),
+                            },
+                        )?;
+                        self.heal_prefix_sets.account_prefix_set.insert(key);
+
+                        if account.storage_root == EMPTY_ROOT_HASH {
+                            wipe_storage(tx, hashed_key)?;
+                        } else {
+                            let storage_root = Proof::from_tx(tx)
+                                .with_prefix_sets_mut(self.proof_prefix_sets())
+                                .storage_root(hashed_key)
+                                .map_err(|err| StageError::Fatal(Box::new(err)))?;
+                            if storage_root != account.storage_root {
+                                self.heal_tasks.push_back(HealTask {
+                                    account: Some(hashed_key),
+                                    path: Nibbles::default(),
+                                    hash: account.storage_root,
+                                });
+                            }
+                        }
+                    }
+                    Some(hashed_address) => {
+                        let value = U256::decode(&mut &leaf.value[..])
+                            .map_err(|err| StageError::Fatal(Box::new(err)))?;
+                        let mut cursor = tx.cursor_dup_write::<tables::HashedStorages>()?;
+                        write_storage_slot(&mut cursor, hashed_address, hashed_key, value)?;
+                        self.on_storage_changed(hashed_address, key);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Heals the child of a node, which is either referenced by its hash, or embedded.
+    fn heal_child<TX: DbTxMut + DbTx>(
+        &mut self,
+        tx: &TX,
+        account: Option<B256>,
+        path: Nibbles,
+        child: &[u8],
+        children: &mut Vec<HealTask>,
+    ) -> Result<(), StageError> {
+        // A hash is encoded as a 32 byte string
+        if child.len() == B256::len_bytes() + 1 {
+            children.push(HealTask { account, path, hash: B256::from_slice(&child[1..]) });
+            return Ok(())
+        }
+
+        // Nodes shorter than a hash are embedded in their parent
+        let node =
+            TrieNode::decode(&mut &child[..]).map_err(|err| StageError::Fatal(Box::new(err)))?;
+        self.heal_node(tx, account, path, node, children)
+    }
+
+    /// Deletes the state below the path, except the one below the path to keep.
+    fn delete_subtrie<TX: DbTxMut + DbTx>(
+        &mut self,
+        tx: &TX,
+        account: Option<B256>,
+        path: &Nibbles,
+        keep_path: Option<&Nibbles>,
+    ) -> Result<(), StageError> {
+        let (start, end) = prefix_bounds(path);
+        let keep = |key: &B256| keep_path.is_some_and(|keep| Nibbles::unpack(key).has_prefix(keep));
+
+        match account {
+            None => {
+                for hashed_address in delete_accounts(tx, start, end, keep)? {
+                    self.heal_prefix_sets
+                        .account_prefix_set
+                        .insert(Nibbles::unpack(hashed_address));
+                }
+            }
+            Some(hashed_address) => {
+                for key in delete_storage_slots(tx, hashed_address, start, end, keep)? {
+                    self.on_storage_changed(hashed_address, Nibbles::unpack(key));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Records a change of the storage slot of the account in the prefix sets.
+    fn on_storage_changed(&mut self, hashed_address: B256, key: Nibbles) {
+        self.heal_prefix_sets.account_prefix_set.insert(Nibbles::unpack(hashed_address));
+        self.heal_prefix_sets.storage_prefix_sets.entry(hashed_address).or_default().insert(key);
+    }
+
+    /// Returns a copy of the prefix sets of the current healing round, for computing the nodes of
+    /// the local trie.
+    fn proof_prefix_sets(&self) -> TriePrefixSetsMut {
+        TriePrefixSetsMut {
+            account_prefix_set: self.heal_prefix_sets.account_prefix_set.clone(),
+            storage_prefix_sets: self.heal_prefix_sets.storage_prefix_sets.clone(),
+            destroyed_accounts: HashSet::default(),
+        }
+    }
+
+    /// Queues the nodes to heal, skipping the ones which match the local trie.
+    fn queue_heal_tasks<TX: DbTx>(
+        &mut self,
+        tx: &TX,
+        tasks: Vec<HealTask>,
+    ) -> Result<(), StageError> {
+        let mut tries = BTreeMap::<_, Vec<_>>::new();
+        for task in tasks {
+            tries.entry(task.account).or_default().push(task);
+        }
+
+        for (account, tasks) in tries {
+            let proof = Proof::from_tx(tx).with_prefix_sets_mut(self.proof_prefix_sets());
+            let paths = tasks.iter().map(|task| task.path.clone()).collect();
+            let local_nodes = match account {
+                None => proof
+                    .account_trie_nodes(paths)
+                    .map_err(|err| StageError::Fatal(Box::new(err)))?,
+                Some(hashed_address) => proof
+                    .storage_trie_nodes(hashed_address, paths)
+                    .map_err(|err| StageError::Fatal(Box::new(err)))?,
+            };
+
+            self.heal_tasks.extend(tasks.into_iter().filter(|task| {
+                local_nodes.get(&task.path).map_or(true, |node| keccak256(node) != task.hash)
+            }));
+        }
+        Ok(())
+    }
+
+    /// Computes the state root of the downloaded state, building the trie.
+    fn compute_state_root<TX: DbTxMut + DbTx>(&mut self, tx: &TX) -> Result<(), StageError> {
+        let merkle = self.checkpoint.merkle.take();
+        if merkle.is_none() {
+            tx.clear::<tables::AccountsTrie>()?;
+            tx.clear::<tables::StoragesTrie>()?;
+        }
+
+        let progress = StateRoot::from_tx(tx)
+            .with_intermediate_state(merkle.map(IntermediateStateRootState::from))
+            .root_with_progress()
+            .map_err(|err| StageError::Fatal(Box::new(err)))?;
+        match progress {
+            StateRootProgress::Progress(state, _, updates) => {
+                updates.write_to_database(tx)?;
+                self.checkpoint.merkle = Some(MerkleCheckpoint::new(
+                    self.checkpoint.pivot,
+                    state.last_account_key,
+                    state.walker_stack.into_iter().map(StoredSubNode::from).collect(),
+                    state.hash_builder.into(),
+                ));
+            }
+            StateRootProgress::Complete(root, _, updates) => {
+                updates.write_to_database(tx)?;
+                self.on_state_root(root);
+            }
+        }
+        Ok(())
+    }
+
+    /// Updates the trie with the changes of the healing round, and checks the state root.
+    fn finish_healing_round<TX: DbTxMut + DbTx>(&mut self, tx: &TX) -> Result<(), StageError> {
+        let prefix_sets = std::mem::take(&mut self.heal_prefix_sets).freeze();
+        let (root, updates) = StateRoot::from_tx(tx)
+            .with_prefix_sets(prefix_sets)
+            .root_with_updates()
+            .map_err(|err| StageError::Fatal(Box::new(err)))?;
+        updates.write_to_database(tx)?;
+
+        self.on_state_root(root);
+        Ok(())
+    }
+
+    /// Moves on to the bytecodes if the state root matches the pivot block, and to healing
+    /// otherwise.
+    fn on_state_root(&mut self, root: B256) {
+        if root == self.state_root {
+            debug!(target: "sync::stages::snap_sync", ?root, "State root matches the pivot block");
+            self.checkpoint.phase = SnapSyncPhase::ByteCodes;
+            self.checkpoint.next_code_key = B256::ZERO;
+            self.code_batch_end = None;
+            self.codes_exhausted = false;
+        } else {
+            debug!(target: "sync::stages::snap_sync", got = ?root, expected = ?self.state_root, "Healing the trie");
+            self.checkpoint.phase = SnapSyncPhase::Healing;
+            self.heal_tasks.push_back(HealTask {
+                account: None,
+                path: Nibbles::default(),
+                hash: self.state_root,
+            });
+        }
+    }
+
+    /// Queues the download of the missing bytecodes of the next accounts, or finishes the sync
+    /// if all accounts were checked.
+    fn next_byte_codes<TX: DbTx>(&mut self, tx: &TX) -> Result<(), StageError> {
+        // The bytecodes of the previous batch are downloaded
+        if let Some(next) = self.code_batch_end.take() {
+            self.checkpoint.next_code_key = next;
+        }
+        if self.codes_exhausted {
+            self.checkpoint.phase = SnapSyncPhase::Done;
+            return Ok(())
+        }
+
+        let mut missing = HashSet::new();
+        let mut last = None;
+        let mut exhausted = true;
+        let mut cursor = tx.cursor_read::<tables::HashedAccounts>()?;
+        for (scanned, entry) in cursor.walk(Some(self.checkpoint.next_code_key))?.enumerate() {
+            if scanned == MAX_BYTE_CODE_SCAN || missing.len() == MAX_BYTE_CODES * 16 {
+                exhausted = false;
+                break
+            }
+
+            let (hashed_address, account) = entry?;
+            last = Some(hashed_address);
+            if let Some(code_hash) = account.bytecode_hash {
+                if !missing.contains(&code_hash) &&
+                    tx.get::<tables::Bytecodes>(code_hash)?.is_none()
+                {
+                    missing.insert(code_hash);
+                }
+            }
+        }
+
+        match last.and_then(next_hash).filter(|_| !exhausted) {
+            Some(next) => self.code_batch_end = Some(next),
+            None => self.codes_exhausted = true,
+        }
+        self.code_tasks.extend(missing);
+        Ok(())
+    }
+
+    /// Hands the downloaded state over to the stages which follow.
+    fn finish<DB: Database>(&self, provider: &DatabaseProviderRW<DB>) -> Result<(), StageError> {
+        let pivot = self.checkpoint.pivot;
+
+        for stage_id in [
+            StageId::Execution,
+            StageId::AccountHashing,
+            StageId::StorageHashing,
+            StageId::MerkleExecute,
+            StageId::MerkleUnwind,
+            StageId::IndexAccountHistory,
+            StageId::IndexStorageHistory,
+            StageId::IndexAddressAppearances,
+        ] {
+            provider.save_stage_checkpoint(stage_id, StageCheckpoint::new(pivot))?;
+        }
+
+        // The history before the pivot block is not available
+        for segment in [PruneSegment::AccountHistory, PruneSegment::StorageHistory] {
+            provider.save_prune_checkpoint(
+                segment,
+                PruneCheckpoint {
+                    block_number: Some(pivot),
+                    tx_number: None,
+                    prune_mode: PruneMode::Before(pivot + 1),
+                },
+            )?;
+        }
+
+        // There are no receipts before the pivot block, but the static files have to reach it for
+        // the execution to continue after it.
+        let static_file_provider = provider.static_file_provider();
+        let next_block = static_file_provider
+            .get_highest_static_file_block(StaticFileSegment::Receipts)
+            .map_or(0, |block| block + 1);
+        let mut writer = static_file_provider.latest_writer(StaticFileSegment::Receipts)?;
+        for block in next_block..=pivot {
+            writer.increment_block(StaticFileSegment::Receipts, block)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Clears the state tables, which are replaced by the downloaded state.
+fn clear_state<TX: DbTxMut>(tx: &TX) -> Result<(), DatabaseError> {
+    tx.clear::<tables::PlainAccountState>()?;
+    tx.clear::<tables::PlainStorageState>()?;
+    tx.clear::<tables::HashedAccounts>()?;
+    tx.clear::<tables::HashedStorages>()?;
+    tx.clear::<tables::AccountsTrie>()?;
+    tx.clear::<tables::StoragesTrie>()?;
+    tx.clear::<tables::AccountsHistory>()?;
+    tx.clear::<tables::StoragesHistory>()?;
+    tx.clear::<tables::AccountChangeSets>()?;
+    tx.clear::<tables::StorageChangeSets>()?;
+    Ok(())
+}
+
+/// Splits the hashed addresses into equally sized ranges, as pairs of their first and last hash.
+fn initial_account_ranges() -> Vec<(B256, B256)> {
+    (0..ACCOUNT_RANGES)
+        .map(|index| {
+            let mut first = B256::ZERO;
+            first.0[0] = index << 4;
+            let mut last = B256::repeat_byte(0xff);
+            last.0[0] = (index << 4) | 0x0f;
+            (first, last)
+        })
+        .collect()
+}
+
+/// Returns the hash following the given one, if any.
+fn next_hash(hash: B256) -> Option<B256> {
+    U256::from_be_bytes(hash.0).checked_add(U256::from(1)).map(B256::from)
+}
+
+/// Returns the first and the last hash starting with the path.
+fn prefix_bounds(path: &Nibbles) -> (B256, B256) {
+    let mut first = path.clone();
+    let mut last = path.clone();
+    for _ in path.len()..64 {
+        first.push(0);
+        last.push(0x0f);
+    }
+    (B256::from_slice(&first.pack()), B256::from_slice(&last.pack()))
+}
+
+/// Deletes the accounts within the hash range and their storage, except the ones to keep.
+///
+/// Returns the hashed addresses of the deleted accounts.
+fn delete_accounts<TX: DbTxMut + DbTx>(
+    tx: &TX,
+    start: B256,
+    end: B256,
+    keep: impl Fn(&B256) -> bool,
+) -> Result<Vec<B256>, DatabaseError> {
+    let mut deleted = Vec::new();
+    let mut cursor = tx.cursor_write::<tables::HashedAccounts>()?;
+    let mut walker = cursor.walk_range(start..=end)?;
+    while let Some((hashed_address, _)) = walker.next().transpose()? {
+        if !keep(&hashed_address) {
+            walker.delete_current()?;
+            deleted.push(hashed_address);
+        }
+    }
+
+    for hashed_address in &deleted {
+        wipe_storage(tx, *hashed_address)?;
+    }
+    Ok(deleted)
+}
+
+/// Deletes the storage slots of the account within the hash range, except the ones to keep.
+///
+/// Returns the hashes of the deleted slots.
+fn delete_storage_slots<TX: DbTxMut + DbTx>(
+    tx: &TX,
+    hashed_address: B256,
+    start: B256,
+    end: B256,
+    keep: impl Fn(&B256) -> bool,
+) -> Result<Vec<B256>, DatabaseError> {
+    let mut cursor = tx.cursor_dup_write::<tables::HashedStorages>()?;
+    let mut deleted = Vec::new();
+    let mut entry = cursor.seek_by_key_subkey(hashed_address, start)?;
+    while let Some(StorageEntry { key, .. }) = entry.filter(|entry| entry.key <= end) {
+        if !keep(&key) {
+            deleted.push(key);
+        }
+        entry = cursor.next_dup_val()?;
+    }
+
+    // Deleting the last duplicate of the account invalidates the position of the cursor
+    for key in &deleted {
+        write_storage_slot(&mut cursor, hashed_address, *key, U256::ZERO)?;
+    }
+    Ok(deleted)
+}
+
+/// Deletes the storage of the account, and its storage trie.
+fn wipe_storage<TX: DbTxMut + DbTx>(tx: &TX, hashed_address: B256) -> Result<(), DatabaseError> {
+    let mut cursor = tx.cursor_dup_write::<tables::HashedStorages>()?;
+    if cursor.seek_exact(hashed_address)?.is_some() {
+        cursor.delete_current_duplicates()?;
+    }
+
+    let mut cursor = tx.cursor_dup_write::<tables::StoragesTrie>()?;
+    if cursor.seek_exact(hashed_address)?.is_some() {
+        cursor.delete_current_duplicates()?;
+    }
+    Ok(())
+}
+
+/// Returns `true` if the accounts are all the accounts of the state with the given root from the
+/// origin up to the last account, as proven by the proof.
+fn verify_account_range(
+    root: B256,
+    origin: B256,
+    accounts: &[AccountData],
+    proof: &[Bytes],
+) -> bool {
+    let leaves = accounts
+        .iter()
+        .map(|AccountData { hash, body }| {
+            let account = TrieAccount {
+                nonce: body.nonce,
+                balance: body.balance,
+                storage_root: body.storage_root,
+                code_hash: body.code_hash,
+            };
+            (*hash, alloy_rlp::encode(account))
+        })
+        .collect::<Vec<_>>();
+    verify_range_proof(root, origin, &leaves, proof).is_ok()
+}
+
+/// Writes the value of the storage slot, replacing the existing one.
+fn write_storage_slot<C>(
+    cursor: &mut C,
+    hashed_address: B256,
+    key: B256,
+    value: U256,
+) -> Result<(), DatabaseError>
+where
+    C: DbCursorRW<tables::HashedStorages> + DbDupCursorRO<tables::HashedStorages>,
+{
+    if cursor.seek_by_key_subkey(hashed_address, key)?.filter(|entry| entry.key == key).is_some() {
+        cursor.delete_current()?;
+    }
+
+    if !value.is_zero() {
+        cursor.upsert(hashed_address, StorageEntry { key, value })?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_db::{test_utils::TempDatabase, DatabaseEnv};
+    use reth_db_api::table::Table;
+    use reth_network::peers::PeersManager;
+    use reth_network_p2p::{error::RequestError, snap::client::SnapAccount};
+    use reth_network_peers::WithPeerId;
+    use reth_primitives::Header;
+    use reth_provider::{
+        providers::is_snap_synced, test_utils::create_test_provider_factory, ProviderFactory,
+        PruneCheckpointReader, StaticFileProviderFactory,
+    };
+    use reth_snap::{IncomingSnapRequest, SnapRequestHandler};
+    use std::{future::poll_fn, sync::Arc};
+    use tokio::sync::{mpsc, oneshot};
+
+    type TestDB = Arc<TempDatabase<DatabaseEnv>>;
+
+    const PIVOT: BlockNumber = 100;
+    const PIVOT_DISTANCE: u64 = 10;
+
+    /// A client sending the requests to a [`SnapRequestHandler`].
+    #[derive(Debug, Clone)]
+    struct TestSnapClient {
+        to_handler: mpsc::Sender<IncomingSnapRequest>,
+    }
+
+    impl TestSnapClient {
+        /// Serves the state of the database.
+        fn spawn(factory: ProviderFactory<TestDB>) -> Self {
+            let (to_handler, rx) = mpsc::channel(1024);
+            tokio::spawn(SnapRequestHandler::new(factory, PeersManager::default().handle(), rx));
+            Self { to_handler }
+        }
+
+        fn request<T: Send + Sync + 'static>(
+            &self,
+            request: impl FnOnce(oneshot::Sender<T>) -> IncomingSnapRequest,
+        ) -> SnapFut<T> {
+            let (tx, rx) = oneshot::channel();
+            let sent = self.to_handler.try_send(request(tx));
+            Box::pin(async move {
+                sent.map_err(|_| RequestError::ChannelClosed)?;
+                let response = rx.await.map_err(|_| RequestError::ChannelClosed)?;
+                Ok(WithPeerId::new(PeerId::ZERO, response))
+            })
+        }
+    }
+
+    impl DownloadClient for TestSnapClient {
+        fn report_bad_message(&self, _peer_id: PeerId) {
+            panic!("the served responses are valid")
+        }
+
+        fn num_connected_peers(&self) -> usize {
+            1
+        }
+    }
+
+    impl SnapClient for TestSnapClient {
+        fn get_account_range(&self, request: GetAccountRange) -> SnapFut<AccountRange> {
+            self.request(|response| IncomingSnapRequest::GetAccountRange {
+                peer_id: PeerId::ZERO,
+                request,
+                response,
+            })
+        }
+
+        fn get_storage_ranges(&self, request: GetStorageRanges) -> SnapFut<StorageRanges> {
+            self.request(|response| IncomingSnapRequest::GetStorageRanges {
+                peer_id: PeerId::ZERO,
+                request,
+                response,
+            })
+        }
+
+        fn get_byte_codes(&self, request: GetByteCodes) -> SnapFut<ByteCodes> {
+            self.request(|response| IncomingSnapRequest::GetByteCodes {
+                peer_id: PeerId::ZERO,
+                request,
+                response,
+            })
+        }
+
+        fn get_trie_nodes(&self, request: GetTrieNodes) -> SnapFut<TrieNodes> {
+            self.request(|response| IncomingSnapRequest::GetTrieNodes {
+                peer_id: PeerId::ZERO,
+                request,
+                response,
+            })
+        }
+    }
+
+    /// Creates a served state with accounts of increasing balances, the first ones with storage
+    /// and code, and returns its root.
+    fn source_state(factory: &ProviderFactory<TestDB>) -> B256 {
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xf3]));
+        let code_hash = keccak256(code.original_bytes());
+        tx.put::<tables::Bytecodes>(code_hash, code).unwrap();
+
+        for index in 1..=1000u64 {
+            let hashed_address = keccak256(B256::from(U256::from(index)));
+            let account = Account {
+                nonce: index,
+                balance: U256::from(index),
+                bytecode_hash: (index <= 10).then_some(code_hash),
+            };
+            tx.put::<tables::HashedAccounts>(hashed_address, account).unwrap();
+
+            if index <= 10 {
+                for slot in 1..=100 * index {
+                    let entry = StorageEntry {
+                        key: keccak256(B256::from(U256::from(slot))),
+                        value: U256::from(slot),
+                    };
+                    tx.put::<tables::HashedStorages>(hashed_address, entry).unwrap();
+                }
+            }
+        }
+
+        let (root, updates) = StateRoot::from_tx(tx).root_with_updates().unwrap();
+        updates.write_to_database(tx).unwrap();
+
+        let header = Header { state_root: root, ..Default::default() };
+        tx.put::<tables::CanonicalHeaders>(0, header.hash_slow()).unwrap();
+        tx.put::<tables::Headers>(0, header).unwrap();
+        provider.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(0)).unwrap();
+        provider.commit().unwrap();
+
+        root
+    }
+
+    /// Creates a database with the pivot block of the given state root.
+    fn destination(state_root: B256) -> ProviderFactory<TestDB> {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+        let header = Header { number: PIVOT, state_root, ..Default::default() };
+        provider.tx_ref().put::<tables::CanonicalHeaders>(PIVOT, header.hash_slow()).unwrap();
+        provider.tx_ref().put::<tables::Headers>(PIVOT, header).unwrap();
+        provider.commit().unwrap();
+        factory
+    }
+
+    fn stage(client: TestSnapClient) -> SnapSyncStage<TestSnapClient> {
+        SnapSyncStage::new(
+            client,
+            SnapSyncConfig {
+                enabled: true,
+                pivot_distance: PIVOT_DISTANCE,
+                max_concurrent_requests: 4,
+                // Small responses, so the ranges and the storage are served in parts
+                response_bytes: 10_000,
+            },
+        )
+    }
+
+    /// Runs the stage until it's done, like the pipeline does.
+    async fn run(
+        stage: &mut SnapSyncStage<TestSnapClient>,
+        factory: &ProviderFactory<TestDB>,
+    ) -> ExecOutput {
+        loop {
+            let provider = factory.provider_rw().unwrap();
+            let input = ExecInput {
+                target: Some(PIVOT + PIVOT_DISTANCE),
+                checkpoint: provider.get_stage_checkpoint(StageId::SnapSync).unwrap(),
+            };
+
+            poll_fn(|cx| Stage::<TestDB>::poll_execute_ready(stage, cx, input)).await.unwrap();
+            let output = stage.execute(&provider, input).unwrap();
+
+            provider.save_stage_checkpoint(StageId::SnapSync, output.checkpoint).unwrap();
+            factory.static_file_provider().commit().unwrap();
+            provider.commit().unwrap();
+
+            if output.done {
+                return output
+            }
+        }
+    }
+
+    fn entries<T: Table>(factory: &ProviderFactory<TestDB>) -> Vec<(T::Key, T::Value)> {
+        let provider = factory.provider().unwrap();
+        let mut cursor = provider.tx_ref().cursor_read::<T>().unwrap();
+        cursor.walk(None).unwrap().collect::<Result<_, _>>().unwrap()
+    }
+
+    fn assert_state_eq(source: &ProviderFactory<TestDB>, destination: &ProviderFactory<TestDB>) {
+        assert_eq!(
+            entries::<tables::HashedAccounts>(source),
+            entries::<tables::HashedAccounts>(destination)
+        );
+        assert_eq!(
+            entries::<tables::HashedStorages>(source),
+            entries::<tables::HashedStorages>(destination)
+        );
+        assert_eq!(entries::<tables::Bytecodes>(source), entries::<tables::Bytecodes>(destination));
+    }
+
+    #[tokio::test]
+    async fn downloads_state() {
+        let source = create_test_provider_factory();
+        let root = source_state(&source);
+        let destination = destination(root);
+
+        let mut stage = stage(TestSnapClient::spawn(source.clone()));
+        let output = run(&mut stage, &destination).await;
+        assert_eq!(output.checkpoint, StageCheckpoint::new(PIVOT + PIVOT_DISTANCE));
+        assert_state_eq(&source, &destination);
+
+        // The trie is built, and the following stages continue after the pivot block
+        let provider = destination.provider().unwrap();
+        assert_eq!(StateRoot::from_tx(provider.tx_ref()).root().unwrap(), root);
+        for stage_id in [StageId::Execution, StageId::AccountHashing, StageId::MerkleExecute] {
+            assert_eq!(
+                provider.get_stage_checkpoint(stage_id).unwrap(),
+                Some(StageCheckpoint::new(PIVOT))
+            );
+        }
+        assert_eq!(
+            provider
+                .get_prune_checkpoint(PruneSegment::AccountHistory)
+                .unwrap()
+                .unwrap()
+                .block_number,
+            Some(PIVOT)
+        );
+        assert_eq!(
+            destination
+                .static_file_provider()
+                .get_highest_static_file_block(StaticFileSegment::Receipts),
+            Some(PIVOT)
+        );
+        drop(provider);
+
+        // The stage is skipped once the state is downloaded
+        let output = run(&mut stage, &destination).await;
+        assert_eq!(output.checkpoint, StageCheckpoint::new(PIVOT + PIVOT_DISTANCE));
+    }
+
+    #[tokio::test]
+    async fn heals_state() {
+        let source = create_test_provider_factory();
+        let root = source_state(&source);
+        let destination = destination(root);
+
+        // The downloaded state differs from the state of the pivot block
+        let provider = destination.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        for (hashed_address, account) in entries::<tables::HashedAccounts>(&source) {
+            tx.put::<tables::HashedAccounts>(hashed_address, account).unwrap();
+        }
+        for (hashed_address, entry) in entries::<tables::HashedStorages>(&source) {
+            tx.put::<tables::HashedStorages>(hashed_address, entry).unwrap();
+        }
+
+        let hashed_address = |index: u64| keccak256(B256::from(U256::from(index)));
+        let slot = |index: u64| keccak256(B256::from(U256::from(index)));
+        // Missing account with storage
+        delete_accounts(tx, hashed_address(1), hashed_address(1), |_| false).unwrap();
+        // Modified account
+        let account = Account { nonce: 1, ..Default::default() };
+        tx.put::<tables::HashedAccounts>(hashed_address(500), account).unwrap();
+        // Unknown account with storage
+        tx.put::<tables::HashedAccounts>(hashed_address(2000), account).unwrap();
+        let entry = StorageEntry { key: slot(1), value: U256::from(1) };
+        tx.put::<tables::HashedStorages>(hashed_address(2000), entry).unwrap();
+        // Modified, missing and unknown slots
+        let mut cursor = tx.cursor_dup_write::<tables::HashedStorages>().unwrap();
+        write_storage_slot(&mut cursor, hashed_address(2), slot(1), U256::from(100)).unwrap();
+        write_storage_slot(&mut cursor, hashed_address(3), slot(2), U256::ZERO).unwrap();
+        write_storage_slot(&mut cursor, hashed_address(4), slot(1000), U256::from(1)).unwrap();
+        drop(cursor);
+        // Missing storage
+        wipe_storage(tx, hashed_address(5)).unwrap();
+
+        let checkpoint = SnapSyncCheckpoint {
+            pivot: PIVOT,
+            phase: SnapSyncPhase::StateRoot,
+            ..Default::default()
+        };
+        let mut buf = Vec::new();
+        checkpoint.to_compact(&mut buf);
+        provider.save_stage_checkpoint_progress(StageId::SnapSync, buf).unwrap();
+        provider.commit().unwrap();
+
+        let mut stage = stage(TestSnapClient::spawn(source.clone()));
+        run(&mut stage, &destination).await;
+        assert_state_eq(&source, &destination);
+
+        let provider = destination.provider().unwrap();
+        assert_eq!(StateRoot::from_tx(provider.tx_ref()).root().unwrap(), root);
+    }
+
+    #[tokio::test]
+    async fn verifies_ranges() {
+        let source = create_test_provider_factory();
+        let root = source_state(&source);
+        let client = TestSnapClient::spawn(source);
+
+        let origin = B256::repeat_byte(0x10);
+        let request = GetAccountRange {
+            request_id: 0,
+            root_hash: root,
+            starting_hash: origin,
+            limit_hash: B256::repeat_byte(0xff),
+            response_bytes: 10_000,
+        };
+        let mut range = client.get_account_range(request.clone()).await.unwrap().into_data();
+        assert!(verify_account_range(root, origin, &range.accounts, &range.proof));
+
+        // An account missing from the range
+        let account = range.accounts.remove(1);
+        assert!(!verify_account_range(root, origin, &range.accounts, &range.proof));
+
+        // A modified account
+        let body = SnapAccount { nonce: account.body.nonce + 1, ..account.body };
+        range.accounts.insert(1, AccountData { body, ..account });
+        assert!(!verify_account_range(root, origin, &range.accounts, &range.proof));
+
+        // No accounts after the origin
+        let origin = B256::repeat_byte(0xff);
+        let range = client
+            .get_account_range(GetAccountRange { starting_hash: origin, ..request.clone() })
+            .await
+            .unwrap()
+            .into_data();
+        assert!(range.accounts.is_empty() && !range.proof.is_empty());
+        assert!(verify_account_range(root, origin, &range.accounts, &range.proof));
+
+        // The storage of an account, served partially
+        let hashed_address = keccak256(B256::from(U256::from(10)));
+        let range = client
+            .get_account_range(GetAccountRange { starting_hash: hashed_address, ..request })
+            .await
+            .unwrap()
+            .into_data();
+        let storage_root = range.accounts[0].body.storage_root;
+
+        let origin = B256::repeat_byte(0x10);
+        let ranges = client
+            .get_storage_ranges(GetStorageRanges {
+                request_id: 0,
+                root_hash: root,
+                account_hashes: vec![hashed_address],
+                starting_hash: Bytes::copy_from_slice(origin.as_slice()),
+                limit_hash: Bytes::new(),
+                response_bytes: 1_000,
+            })
+            .await
+            .unwrap()
+            .into_data();
+        assert!(!ranges.proof.is_empty());
+        let mut slots =
+            ranges.slots[0].iter().map(|slot| (slot.hash, &slot.data)).collect::<Vec<_>>();
+        assert!(verify_range_proof(storage_root, origin, &slots, &ranges.proof).is_ok());
+
+        // A slot missing from the range
+        slots.remove(1);
+        assert!(verify_range_proof(storage_root, origin, &slots, &ranges.proof).is_err());
+    }
+
+    #[tokio::test]
+    async fn stale_pivot() {
+        let source = create_test_provider_factory();
+        let root = source_state(&source);
+        let destination = destination(B256::random());
+
+        // Only the state of the block after the pivot block is served
+        let provider = destination.provider_rw().unwrap();
+        let header = Header { number: PIVOT + 1, state_root: root, ..Default::default() };
+        provider.tx_ref().put::<tables::CanonicalHeaders>(PIVOT + 1, header.hash_slow()).unwrap();
+        provider.tx_ref().put::<tables::Headers>(PIVOT + 1, header).unwrap();
+        provider.commit().unwrap();
+
+        let mut stage = stage(TestSnapClient::spawn(source.clone()));
+        let output = run(&mut stage, &destination).await;
+        assert_eq!(output.checkpoint, StageCheckpoint::new(PIVOT + PIVOT_DISTANCE));
+        assert_state_eq(&source, &destination);
+
+        let provider = destination.provider().unwrap();
+        assert_eq!(snap_sync_pivot(&provider).unwrap(), Some(PIVOT + 1));
+        assert!(is_snap_synced(provider.tx_ref()).unwrap());
+    }
+}
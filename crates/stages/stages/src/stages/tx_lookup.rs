@@ -140,7 +140,7 @@ impl<DB: Database> Stage<DB> for TransactionLookupStage {
 
                 let total_hashes = hash_collector.len();
                 let interval = (total_hashes / 10).max(1);
-                for (index, hash_to_number) in hash_collector.iter()?.enumerate() {
+                for (index, hash_to_number) in hash_collector.par_iter()?.enumerate() {
                     let (hash, number) = hash_to_number?;
                     if index > 0 && index % interval == 0 {
                         info!(
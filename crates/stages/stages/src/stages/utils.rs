@@ -50,6 +50,33 @@ where
 {
     let mut changeset_cursor = provider.tx_ref().cursor_read::<CS>()?;
 
+    // observability
+    let total_changesets = provider.tx_ref().entries::<CS>()?;
+    let interval = (total_changesets / 1000).max(1);
+
+    let entries = changeset_cursor.walk_range(range)?.enumerate().map(|(idx, entry)| {
+        if idx > 0 && idx % interval == 0 && total_changesets > 1000 {
+            info!(target: "sync::stages::index_history", progress = %format!("{:.4}%", (idx as f64 / total_changesets as f64) * 100.0), "Collecting indices");
+        }
+        Ok(partial_key_factory(entry?))
+    });
+
+    collect_indices::<H, P>(entries, sharded_key_factory, etl_config)
+}
+
+/// Collects the indices of `(block number, partial key)` entries into a [`Collector`].
+///
+/// Entries are expected to be sorted by block number. See [`collect_history_indices`] for the
+/// caching and flushing process.
+pub(crate) fn collect_indices<H, P>(
+    entries: impl Iterator<Item = Result<(BlockNumber, P), StageError>>,
+    sharded_key_factory: impl Fn(P, BlockNumber) -> H::Key,
+    etl_config: &EtlConfig,
+) -> Result<Collector<H::Key, H::Value>, StageError>
+where
+    H: Table<Value = BlockNumberList>,
+    P: Copy + Eq + Hash,
+{
     let mut collector = Collector::new(etl_config.file_size, etl_config.dir.clone());
     let mut cache: HashMap<P, Vec<u64>> = HashMap::new();
 
@@ -64,20 +91,12 @@ where
         Ok::<(), StageError>(())
     };
 
-    // observability
-    let total_changesets = provider.tx_ref().entries::<CS>()?;
-    let interval = (total_changesets / 1000).max(1);
-
     let mut flush_counter = 0;
     let mut current_block_number = u64::MAX;
-    for (idx, entry) in changeset_cursor.walk_range(range)?.enumerate() {
-        let (block_number, key) = partial_key_factory(entry?);
+    for entry in entries {
+        let (block_number, key) = entry?;
         cache.entry(key).or_default().push(block_number);
 
-        if idx > 0 && idx % interval == 0 && total_changesets > 1000 {
-            info!(target: "sync::stages::index_history", progress = %format!("{:.4}%", (idx as f64 / total_changesets as f64) * 100.0), "Collecting indices");
-        }
-
         // Make sure we only flush the cache every DEFAULT_CACHE_THRESHOLD blocks.
         if current_block_number != block_number {
             current_block_number = block_number;
@@ -74,6 +74,106 @@ impl Compact for MerkleCheckpoint {
     }
 }
 
+/// Saves the progress of SnapSync stage.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct SnapSyncCheckpoint {
+    /// The block whose state is being downloaded.
+    pub pivot: BlockNumber,
+    /// The current phase of the sync.
+    pub phase: SnapSyncPhase,
+    /// The account ranges left to download, as pairs of the next and the last hashed address of
+    /// each range.
+    pub account_ranges: Vec<(B256, B256)>,
+    /// The next hashed address to check for a missing bytecode.
+    pub next_code_key: B256,
+    /// The progress of the state root computation.
+    pub merkle: Option<MerkleCheckpoint>,
+}
+
+/// The phases of the SnapSync stage, in the order they are run.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapSyncPhase {
+    /// Downloading the accounts and their storage.
+    #[default]
+    Accounts,
+    /// Computing the state root of the downloaded state.
+    StateRoot,
+    /// Repairing the parts of the trie which don't match the pivot state root.
+    Healing,
+    /// Downloading the bytecodes missing from the database.
+    ByteCodes,
+    /// The state of the pivot block is complete.
+    Done,
+}
+
+impl Compact for SnapSyncCheckpoint {
+    fn to_compact<B>(&self, buf: &mut B) -> usize
+    where
+        B: bytes::BufMut + AsMut<[u8]>,
+    {
+        let mut len = 0;
+
+        buf.put_u64(self.pivot);
+        len += 8;
+
+        buf.put_u8(self.phase as u8);
+        len += 1;
+
+        buf.put_u16(self.account_ranges.len() as u16);
+        len += 2;
+        for (next, last) in &self.account_ranges {
+            buf.put_slice(next.as_slice());
+            buf.put_slice(last.as_slice());
+            len += 64;
+        }
+
+        buf.put_slice(self.next_code_key.as_slice());
+        len += 32;
+
+        buf.put_u8(self.merkle.is_some() as u8);
+        len += 1;
+        if let Some(merkle) = &self.merkle {
+            len += merkle.to_compact(buf);
+        }
+        len
+    }
+
+    fn from_compact(mut buf: &[u8], _len: usize) -> (Self, &[u8]) {
+        let pivot = buf.get_u64();
+
+        let phase = match buf.get_u8() {
+            0 => SnapSyncPhase::Accounts,
+            1 => SnapSyncPhase::StateRoot,
+            2 => SnapSyncPhase::Healing,
+            3 => SnapSyncPhase::ByteCodes,
+            4 => SnapSyncPhase::Done,
+            _ => unreachable!("Junk data in database: unknown SnapSyncPhase"),
+        };
+
+        let account_ranges_len = buf.get_u16() as usize;
+        let mut account_ranges = Vec::with_capacity(account_ranges_len);
+        for _ in 0..account_ranges_len {
+            let next = B256::from_slice(&buf[..32]);
+            let last = B256::from_slice(&buf[32..64]);
+            buf.advance(64);
+            account_ranges.push((next, last));
+        }
+
+        let next_code_key = B256::from_slice(&buf[..32]);
+        buf.advance(32);
+
+        let merkle = if buf.get_u8() == 1 {
+            let (merkle, rest) = MerkleCheckpoint::from_compact(buf, 0);
+            buf = rest;
+            Some(merkle)
+        } else {
+            None
+        };
+
+        (Self { pivot, phase, account_ranges, next_code_key, merkle }, buf)
+    }
+}
+
 /// Saves the progress of AccountHashing stage.
 #[reth_codec]
 #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -397,4 +497,26 @@ mod tests {
         let (decoded, _) = MerkleCheckpoint::from_compact(&buf, encoded);
         assert_eq!(decoded, checkpoint);
     }
+
+    #[test]
+    fn snap_sync_checkpoint_roundtrip() {
+        let mut rng = rand::thread_rng();
+        let checkpoint = SnapSyncCheckpoint {
+            pivot: rng.gen(),
+            phase: SnapSyncPhase::Healing,
+            account_ranges: vec![(rng.gen(), rng.gen()), (rng.gen(), rng.gen())],
+            next_code_key: rng.gen(),
+            merkle: Some(MerkleCheckpoint {
+                target_block: rng.gen(),
+                last_account_key: rng.gen(),
+                walker_stack: Vec::new(),
+                state: HashBuilderState::default(),
+            }),
+        };
+
+        let mut buf = Vec::new();
+        let encoded = checkpoint.to_compact(&mut buf);
+        let (decoded, _) = SnapSyncCheckpoint::from_compact(&buf, encoded);
+        assert_eq!(decoded, checkpoint);
+    }
 }
@@ -20,6 +20,14 @@ pub enum StageId {
     TransactionLookup,
     IndexStorageHistory,
     IndexAccountHistory,
+    /// Optional stage that indexes the blocks each address appears in.
+    ///
+    /// Not part of [`StageId::ALL`], since it's only run if enabled in the configuration.
+    IndexAddressAppearances,
+    /// Optional stage that downloads the state of a recent block over `snap/1`.
+    ///
+    /// Not part of [`StageId::ALL`], since it's only run if enabled in the configuration.
+    SnapSync,
     Prune,
     Finish,
     /// Other custom stage with a provided string identifier.
@@ -75,6 +83,8 @@ impl StageId {
             Self::TransactionLookup => "TransactionLookup",
             Self::IndexAccountHistory => "IndexAccountHistory",
             Self::IndexStorageHistory => "IndexStorageHistory",
+            Self::IndexAddressAppearances => "IndexAddressAppearances",
+            Self::SnapSync => "SnapSync",
             Self::Prune => "Prune",
             Self::Finish => "Finish",
             Self::Other(s) => s,
@@ -118,7 +128,9 @@ mod tests {
         assert_eq!(StageId::StorageHashing.to_string(), "StorageHashing");
         assert_eq!(StageId::MerkleExecute.to_string(), "MerkleExecute");
         assert_eq!(StageId::IndexAccountHistory.to_string(), "IndexAccountHistory");
+        assert_eq!(StageId::IndexAddressAppearances.to_string(), "IndexAddressAppearances");
         assert_eq!(StageId::IndexStorageHistory.to_string(), "IndexStorageHistory");
+        assert_eq!(StageId::SnapSync.to_string(), "SnapSync");
         assert_eq!(StageId::TransactionLookup.to_string(), "TransactionLookup");
         assert_eq!(StageId::Finish.to_string(), "Finish");
 
@@ -9,6 +9,12 @@ pub use headers::Headers;
 mod receipts;
 pub use receipts::Receipts;
 
+mod account_changesets;
+pub use account_changesets::AccountChangeSets;
+
+mod storage_changesets;
+pub use storage_changesets::StorageChangeSets;
+
 use alloy_primitives::BlockNumber;
 use reth_db_api::database::Database;
 use reth_provider::{providers::StaticFileProvider, DatabaseProviderRO};
@@ -7,15 +7,15 @@ use crate::{
     table::{Decode, Encode},
     DatabaseError,
 };
-use reth_codecs::{derive_arbitrary, Compact};
-use reth_primitives::{Account, Address, BlockNumber, Buf, StorageKey};
+use reth_codecs::{derive_arbitrary, reth_codec, Compact};
+use reth_primitives::{Account, Address, BlockNumber, Buf, StorageKey, B256, U256};
 use serde::{Deserialize, Serialize};
 
 /// Account as it is saved in the database.
 ///
 /// [`Address`] is the subkey.
 #[derive_arbitrary(compact)]
-#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize)]
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
 pub struct AccountBeforeTx {
     /// Address for the account. Acts as `DupSort::SubKey`.
     pub address: Address,
@@ -57,6 +57,38 @@ impl Compact for AccountBeforeTx {
     }
 }
 
+/// Storage slot as it is saved in the storage changesets static files.
+#[reth_codec]
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StorageBeforeTx {
+    /// Address of the account the storage slot belongs to.
+    pub address: Address,
+    /// Storage key of the slot.
+    pub key: B256,
+    /// Storage value before the transaction.
+    pub value: U256,
+}
+
+/// The static file representation of the account changeset of a block.
+///
+/// Entries are ordered by [`Address`], same as in the `AccountChangeSets` table.
+#[reth_codec]
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StoredAccountChangeSet {
+    /// Accounts changed by the block, alongside their state before the block.
+    pub changes: Vec<AccountBeforeTx>,
+}
+
+/// The static file representation of the storage changeset of a block.
+///
+/// Entries are ordered by [`Address`] and storage key, same as in the `StorageChangeSets` table.
+#[reth_codec]
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StoredStorageChangeSet {
+    /// Storage slots changed by the block, alongside their values before the block.
+    pub changes: Vec<StorageBeforeTx>,
+}
+
 /// [`BlockNumber`] concatenated with [`Address`].
 ///
 /// Since it's used as a key, it isn't compressed when encoding it.
@@ -9,6 +9,8 @@
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
 
 pub mod init;
+pub mod migration;
+pub mod state_dump;
 
 mod db_tool;
 pub use db_tool::*;
@@ -0,0 +1,659 @@
+//! Database schema migrations.
+//!
+//! The database records its schema version in the [`DB_VERSION_FILE_NAME`] file. Whenever a change
+//! to the layout of the tables or static files bumps [`DB_VERSION`], a [`Migration`] that upgrades
+//! the previous version to the new one should be registered in [`Migrations::default`], so that
+//! existing nodes don't need to resync.
+//!
+//! [`DB_VERSION_FILE_NAME`]: reth_db::version::DB_VERSION_FILE_NAME
+
+use reth_db::{
+    tables,
+    version::{get_db_version, write_db_version_file, DatabaseVersionError, DB_VERSION},
+};
+use reth_db_api::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    database::Database,
+    models::{BlockNumberAddress, StorageBeforeTx, StoredAccountChangeSet, StoredStorageChangeSet},
+    transaction::{DbTx, DbTxMut},
+    DatabaseError,
+};
+use reth_primitives::{BlockNumber, StaticFileSegment};
+use reth_provider::{
+    providers::StaticFileWriter, DatabaseProvider, ProviderError, ProviderFactory,
+    PruneCheckpointReader, StageCheckpointReader, StaticFileProviderFactory,
+};
+use reth_prune_types::PruneSegment;
+use reth_stages_types::StageId;
+use std::{
+    collections::BTreeMap,
+    fmt, io,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    time::Instant,
+};
+use tracing::info;
+
+/// A numbered migration of the database from version `version() - 1` to `version()`.
+///
+/// Migrations are run in batches, each in its own database transaction. Every call to
+/// [`Migration::migrate`] continues from the checkpoint returned by the previous one, which is
+/// committed together with the changes of the batch, so an interrupted migration resumes from the
+/// last committed batch. Static file changes are committed right before the database transaction.
+pub trait Migration<TX>: fmt::Debug + Send + Sync {
+    /// The database version this migration upgrades to.
+    fn version(&self) -> u64;
+
+    /// Short description of the migration, used in logs.
+    fn description(&self) -> &'static str;
+
+    /// Migrates one batch, starting at the given checkpoint. The checkpoint of the first batch is
+    /// `0`.
+    fn migrate(
+        &self,
+        provider: &DatabaseProvider<TX>,
+        checkpoint: u64,
+    ) -> Result<MigrationOutput, ProviderError>;
+}
+
+/// The output of a [`Migration`] batch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MigrationOutput {
+    /// The checkpoint to continue the migration from.
+    pub checkpoint: u64,
+    /// The total the checkpoint is progressing towards, if known. Only used to report progress.
+    pub total: Option<u64>,
+    /// Whether the migration is complete.
+    pub done: bool,
+}
+
+/// Database migration error type.
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    /// The database was written by a newer client.
+    #[error("database version (v{version}) is newer than the latest database version (v{DB_VERSION}), downgrades are not supported")]
+    Downgrade {
+        /// The version of the database.
+        version: u64,
+    },
+    /// No migration is registered for one of the versions between the database version and the
+    /// latest version.
+    #[error("no migration from database version v{} to v{version}, a resync is required", version - 1)]
+    MissingMigration {
+        /// The version the missing migration upgrades to.
+        version: u64,
+    },
+    /// Failed to read the database version.
+    #[error(transparent)]
+    Version(#[from] DatabaseVersionError),
+    /// Failed to write the database version.
+    #[error("failed to write the database version to {path}: {err}")]
+    VersionWrite {
+        /// The encountered IO error.
+        err: io::Error,
+        /// The path to the database directory.
+        path: PathBuf,
+    },
+    /// A migration failed.
+    #[error("migration to database version v{version} failed: {err}")]
+    Migration {
+        /// The version the failed migration upgrades to.
+        version: u64,
+        /// The provider error.
+        err: ProviderError,
+    },
+    /// Database error.
+    #[error(transparent)]
+    Database(#[from] DatabaseError),
+    /// Provider error.
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+}
+
+/// A registry of [`Migration`]s, keyed by the version they upgrade to.
+pub struct Migrations<TX> {
+    migrations: BTreeMap<u64, Box<dyn Migration<TX>>>,
+}
+
+impl<TX> fmt::Debug for Migrations<TX> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.migrations.iter()).finish()
+    }
+}
+
+impl<TX> Migrations<TX> {
+    /// Creates an empty registry.
+    pub const fn new() -> Self {
+        Self { migrations: BTreeMap::new() }
+    }
+
+    /// Registers a migration, replacing any migration to the same version.
+    pub fn with_migration(mut self, migration: impl Migration<TX> + 'static) -> Self {
+        self.migrations.insert(migration.version(), Box::new(migration));
+        self
+    }
+
+    /// Returns the migrations that upgrade the database from the `from` version to the `to`
+    /// version, in the order they need to run.
+    pub fn path(&self, from: u64, to: u64) -> Result<Vec<&dyn Migration<TX>>, MigrationError> {
+        if from > to {
+            return Err(MigrationError::Downgrade { version: from })
+        }
+
+        (from + 1..=to)
+            .map(|version| {
+                self.migrations
+                    .get(&version)
+                    .map(|migration| migration.as_ref())
+                    .ok_or(MigrationError::MissingMigration { version })
+            })
+            .collect()
+    }
+}
+
+impl<TX: DbTx + DbTxMut> Default for Migrations<TX> {
+    /// Returns all migrations between released database versions.
+    fn default() -> Self {
+        Self::new().with_migration(ChangeSetsToStaticFiles::default())
+    }
+}
+
+/// Copies the account and storage changesets of the database into static files (v3).
+///
+/// Nodes copy the changesets of finalized blocks into static files as they go, but a database
+/// written before they were stored in static files would otherwise copy its whole history at once
+/// on the first start. The changesets are left in the database, they're removed by the pruner.
+///
+/// Segments whose history is pruned according to the prune modes of the provider are skipped, like
+/// the static file producer does, as well as segments whose history was pruned before.
+#[derive(Debug)]
+pub struct ChangeSetsToStaticFiles {
+    /// The number of blocks copied in each batch.
+    pub batch_size: u64,
+}
+
+impl Default for ChangeSetsToStaticFiles {
+    fn default() -> Self {
+        Self { batch_size: 10_000 }
+    }
+}
+
+impl ChangeSetsToStaticFiles {
+    /// Copies the changesets of the block range into static files of the segment.
+    fn copy<TX: DbTx>(
+        provider: &DatabaseProvider<TX>,
+        segment: StaticFileSegment,
+        blocks: RangeInclusive<BlockNumber>,
+    ) -> Result<(), ProviderError> {
+        let mut writer = provider.static_file_provider().get_writer(*blocks.start(), segment)?;
+
+        // Every block gets a row, blocks without any changes get an empty changeset.
+        if segment == StaticFileSegment::AccountChangeSets {
+            let mut cursor = provider.tx_ref().cursor_dup_read::<tables::AccountChangeSets>()?;
+            for block in blocks {
+                let changes = cursor
+                    .walk_dup(Some(block), None)?
+                    .map(|entry| entry.map(|(_, account)| account))
+                    .collect::<Result<Vec<_>, _>>()?;
+                writer.append_account_changeset(block, &StoredAccountChangeSet { changes })?;
+            }
+        } else {
+            let mut cursor = provider.tx_ref().cursor_read::<tables::StorageChangeSets>()?;
+            for block in blocks {
+                let changes = cursor
+                    .walk_range(BlockNumberAddress::range(block..=block))?
+                    .map(|entry| {
+                        entry.map(|(BlockNumberAddress((_, address)), entry)| StorageBeforeTx {
+                            address,
+                            key: entry.key,
+                            value: entry.value,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                writer.append_storage_changeset(block, &StoredStorageChangeSet { changes })?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<TX: DbTx + DbTxMut> Migration<TX> for ChangeSetsToStaticFiles {
+    fn version(&self) -> u64 {
+        3
+    }
+
+    fn description(&self) -> &'static str {
+        "copy account and storage changesets into static files"
+    }
+
+    /// The checkpoint is the next block to copy. It's only used to report progress, each batch
+    /// continues after the highest block in the static files, which are committed first.
+    fn migrate(
+        &self,
+        provider: &DatabaseProvider<TX>,
+        _checkpoint: u64,
+    ) -> Result<MigrationOutput, ProviderError> {
+        // Changesets are only moved once the whole pipeline has processed the block.
+        let Some(finished) = provider.get_stage_checkpoint(StageId::Finish)? else {
+            return Ok(MigrationOutput { checkpoint: 0, total: None, done: true })
+        };
+        let total = finished.block_number + 1;
+
+        let prune_modes = provider.prune_modes_ref();
+        let mut checkpoint = total;
+        for (segment, history, prune_mode) in [
+            (
+                StaticFileSegment::AccountChangeSets,
+                PruneSegment::AccountHistory,
+                prune_modes.account_history,
+            ),
+            (
+                StaticFileSegment::StorageChangeSets,
+                PruneSegment::StorageHistory,
+                prune_modes.storage_history,
+            ),
+        ] {
+            // The changesets of a pruned history may already be partially deleted.
+            if prune_mode.is_some() || provider.get_prune_checkpoint(history)?.is_some() {
+                continue
+            }
+
+            let start = provider
+                .static_file_provider()
+                .get_highest_static_file_block(segment)
+                .map_or(0, |block| block + 1);
+            let end = start.saturating_add(self.batch_size).min(total);
+            if start < end {
+                Self::copy(provider, segment, start..=end - 1)?;
+            }
+            checkpoint = checkpoint.min(end);
+        }
+
+        Ok(MigrationOutput { checkpoint, total: Some(total), done: checkpoint == total })
+    }
+}
+
+/// Runs the pending [`Migration`]s of a database.
+#[derive(Debug)]
+pub struct Migrator<DB: Database> {
+    provider_factory: ProviderFactory<DB>,
+    db_path: PathBuf,
+    migrations: Migrations<DB::TXMut>,
+}
+
+impl<DB: Database> Migrator<DB> {
+    /// Creates a migrator of the database at the given path with the [default](Migrations::default)
+    /// migrations.
+    ///
+    /// The provider factory must be opened without checking the database version, and with the
+    /// tables the database is missing created, e.g. with
+    /// [`open_db_for_migration`](reth_db::open_db_for_migration). Dry runs only read, so the
+    /// database can be opened read-only for them.
+    pub fn new(provider_factory: ProviderFactory<DB>, db_path: impl Into<PathBuf>) -> Self {
+        Self { provider_factory, db_path: db_path.into(), migrations: Migrations::default() }
+    }
+
+    /// Sets the registry of migrations.
+    pub fn with_migrations(mut self, migrations: Migrations<DB::TXMut>) -> Self {
+        self.migrations = migrations;
+        self
+    }
+
+    /// Returns the migrations that need to run to upgrade the database to [`DB_VERSION`].
+    pub fn pending(&self) -> Result<Vec<&dyn Migration<DB::TXMut>>, MigrationError> {
+        let version = match get_db_version(&self.db_path) {
+            Ok(version) => version,
+            // The version file is created on the first start, the database is new.
+            Err(DatabaseVersionError::MissingFile) => return Ok(Vec::new()),
+            Err(err) => return Err(err.into()),
+        };
+        self.migrations.path(version, DB_VERSION)
+    }
+
+    /// Runs the pending migrations, and updates the database version after each of them.
+    ///
+    /// If `dry_run` is set, only reports the pending migrations without running them.
+    pub fn run(&self, dry_run: bool) -> Result<(), MigrationError> {
+        let pending = self.pending()?;
+        if pending.is_empty() {
+            info!(target: "reth::db::migration", version = DB_VERSION, "Database is up to date");
+            return Ok(())
+        }
+
+        for migration in pending {
+            let version = migration.version();
+            let checkpoint = match self
+                .provider_factory
+                .db_ref()
+                .view(|tx| tx.get::<tables::MigrationCheckpoints>(version))?
+            {
+                Ok(checkpoint) => checkpoint,
+                // A dry run opens the database read-only, so the table of migration checkpoints
+                // isn't created if the database was written before it was added.
+                Err(DatabaseError::Open(err))
+                    if dry_run && err.code == reth_db::mdbx::Error::NotFound.to_err_code() =>
+                {
+                    None
+                }
+                Err(err) => return Err(err.into()),
+            };
+
+            if dry_run {
+                info!(target: "reth::db::migration", version, description = migration.description(), ?checkpoint, "Pending migration");
+                continue
+            }
+
+            info!(target: "reth::db::migration", version, description = migration.description(), ?checkpoint, "Running migration");
+            self.run_migration(migration, checkpoint.unwrap_or_default())?;
+            write_db_version_file(&self.db_path, version)
+                .map_err(|err| MigrationError::VersionWrite { err, path: self.db_path.clone() })?;
+        }
+
+        Ok(())
+    }
+
+    /// Runs the batches of the migration starting at the checkpoint until it's done.
+    fn run_migration(
+        &self,
+        migration: &dyn Migration<DB::TXMut>,
+        mut checkpoint: u64,
+    ) -> Result<(), MigrationError> {
+        let version = migration.version();
+        let start = Instant::now();
+
+        loop {
+            let provider = self.provider_factory.provider_rw()?;
+            let output = migration
+                .migrate(&provider, checkpoint)
+                .map_err(|err| MigrationError::Migration { version, err })?;
+
+            if output.done {
+                provider.tx_ref().delete::<tables::MigrationCheckpoints>(version, None)?;
+            } else {
+                provider
+                    .tx_ref()
+                    .put::<tables::MigrationCheckpoints>(version, output.checkpoint)?;
+            }
+            self.provider_factory.static_file_provider().commit()?;
+            provider.commit()?;
+
+            checkpoint = output.checkpoint;
+            if output.done {
+                info!(target: "reth::db::migration", version, elapsed = ?start.elapsed(), "Migration complete");
+                return Ok(())
+            }
+
+            let progress = output
+                .total
+                .filter(|total| *total > 0)
+                .map(|total| format!("{:.2}%", checkpoint as f64 / total as f64 * 100.0));
+            info!(target: "reth::db::migration", version, checkpoint, total = ?output.total, ?progress, "Migration progress");
+        }
+    }
+}
+
+/// Returns `true` if the database at the given path has a version other than [`DB_VERSION`] and
+/// needs to be migrated.
+pub fn needs_migration(db_path: &Path) -> Result<bool, DatabaseVersionError> {
+    match get_db_version(db_path) {
+        Ok(version) => Ok(version != DB_VERSION),
+        Err(DatabaseVersionError::MissingFile) => Ok(false),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_chainspec::MAINNET;
+    use reth_db::{
+        create_db, mdbx::DatabaseArguments, open_db_for_migration, open_db_read_only,
+        version::db_version_file_path,
+    };
+    use reth_db_api::models::{AccountBeforeTx, ClientVersion};
+    use reth_primitives::{Account, Address, StorageEntry, B256, U256};
+    use reth_provider::{
+        providers::StaticFileProvider, test_utils::create_test_provider_factory,
+        StageCheckpointWriter,
+    };
+    use reth_prune_types::{PruneMode, PruneModes};
+    use reth_stages_types::StageCheckpoint;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// Indexes the canonical hashes by number, failing once at the given checkpoint.
+    #[derive(Debug)]
+    struct IndexHeaderNumbers {
+        fail_at: u64,
+        failed: AtomicBool,
+    }
+
+    impl<TX: DbTx + DbTxMut> Migration<TX> for IndexHeaderNumbers {
+        fn version(&self) -> u64 {
+            DB_VERSION
+        }
+
+        fn description(&self) -> &'static str {
+            "index header numbers"
+        }
+
+        fn migrate(
+            &self,
+            provider: &DatabaseProvider<TX>,
+            checkpoint: u64,
+        ) -> Result<MigrationOutput, ProviderError> {
+            if checkpoint == self.fail_at && !self.failed.swap(true, Ordering::Relaxed) {
+                return Err(ProviderError::UnsupportedProvider)
+            }
+
+            let total = provider.tx_ref().entries::<tables::CanonicalHeaders>()? as u64;
+            let end = (checkpoint + 4).min(total);
+            for number in checkpoint..end {
+                let hash = provider.tx_ref().get::<tables::CanonicalHeaders>(number)?.unwrap();
+                provider.tx_ref().put::<tables::HeaderNumbers>(hash, number)?;
+            }
+            Ok(MigrationOutput { checkpoint: end, total: Some(total), done: end == total })
+        }
+    }
+
+    #[test]
+    fn resumes_migration() {
+        let factory = create_test_provider_factory();
+        let db_path = factory.db_ref().path().to_path_buf();
+        let provider = factory.provider_rw().unwrap();
+        for number in 0..10 {
+            provider
+                .tx_ref()
+                .put::<tables::CanonicalHeaders>(number, B256::with_last_byte(number as u8))
+                .unwrap();
+        }
+        provider.commit().unwrap();
+        write_db_version_file(&db_path, DB_VERSION - 1).unwrap();
+        assert!(needs_migration(&db_path).unwrap());
+
+        let migrator = Migrator::new(factory.clone(), &db_path).with_migrations(
+            Migrations::new()
+                .with_migration(IndexHeaderNumbers { fail_at: 8, failed: AtomicBool::new(false) }),
+        );
+        assert_eq!(migrator.pending().unwrap().len(), 1);
+
+        // a dry run doesn't change anything
+        migrator.run(true).unwrap();
+        assert_eq!(
+            factory.provider().unwrap().tx_ref().entries::<tables::HeaderNumbers>().unwrap(),
+            0
+        );
+
+        // the first run fails after committing two batches
+        assert!(matches!(
+            migrator.run(false),
+            Err(MigrationError::Migration { version: DB_VERSION, .. })
+        ));
+        let tx = factory.provider().unwrap().into_tx();
+        assert_eq!(tx.get::<tables::MigrationCheckpoints>(DB_VERSION).unwrap(), Some(8));
+        assert_eq!(tx.entries::<tables::HeaderNumbers>().unwrap(), 8);
+        drop(tx);
+        assert!(needs_migration(&db_path).unwrap());
+
+        // the second run resumes from the checkpoint
+        migrator.run(false).unwrap();
+        let tx = factory.provider().unwrap().into_tx();
+        assert_eq!(tx.get::<tables::MigrationCheckpoints>(DB_VERSION).unwrap(), None);
+        assert_eq!(tx.entries::<tables::HeaderNumbers>().unwrap(), 10);
+        assert_eq!(
+            std::fs::read_to_string(db_version_file_path(&db_path)).unwrap(),
+            DB_VERSION.to_string()
+        );
+        assert!(migrator.pending().unwrap().is_empty());
+    }
+
+    #[test]
+    fn migration_path() {
+        let migrations = Migrations::<()>::new();
+        assert!(migrations.path(DB_VERSION, DB_VERSION).unwrap().is_empty());
+        assert!(matches!(
+            migrations.path(DB_VERSION - 1, DB_VERSION),
+            Err(MigrationError::MissingMigration { version: DB_VERSION })
+        ));
+        assert!(matches!(
+            migrations.path(DB_VERSION + 1, DB_VERSION),
+            Err(MigrationError::Downgrade { version }) if version == DB_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn migrates_database_without_checkpoints_table() {
+        let dir = tempfile::tempdir().unwrap();
+        let db_path = dir.path().join("db");
+        let args = DatabaseArguments::new(ClientVersion::default());
+
+        // a database without any tables, written by an older version
+        drop(create_db(&db_path, args.clone()).unwrap());
+        write_db_version_file(&db_path, DB_VERSION - 1).unwrap();
+        let static_files_path = dir.path().join("static_files");
+        drop(StaticFileProvider::read_write(&static_files_path).unwrap());
+
+        // a dry run opens the database read-only and doesn't create the missing tables
+        let factory = ProviderFactory::new(
+            open_db_read_only(&db_path, args.clone()).unwrap(),
+            MAINNET.clone(),
+            StaticFileProvider::read_only(&static_files_path).unwrap(),
+        );
+        Migrator::new(factory.clone(), &db_path).run(true).unwrap();
+        assert!(factory
+            .provider()
+            .unwrap()
+            .tx_ref()
+            .entries::<tables::MigrationCheckpoints>()
+            .is_err());
+        drop(factory);
+
+        let factory = ProviderFactory::new(
+            open_db_for_migration(&db_path, args).unwrap(),
+            MAINNET.clone(),
+            StaticFileProvider::read_write(&static_files_path).unwrap(),
+        );
+        Migrator::new(factory.clone(), &db_path).run(false).unwrap();
+
+        assert!(!needs_migration(&db_path).unwrap());
+        assert_eq!(
+            factory.provider().unwrap().tx_ref().entries::<tables::MigrationCheckpoints>().unwrap(),
+            0
+        );
+    }
+
+    #[test]
+    fn copies_changesets_into_static_files() {
+        let factory = create_test_provider_factory();
+        let db_path = factory.db_ref().path().to_path_buf();
+
+        let account = |block: u64| AccountBeforeTx {
+            address: Address::with_last_byte(block as u8),
+            info: Some(Account { nonce: block, ..Default::default() }),
+        };
+        let storage = |block: u64| StorageEntry {
+            key: B256::with_last_byte(block as u8),
+            value: U256::from(block),
+        };
+
+        // blocks 0 to 4 were processed, block 3 has no changes
+        let provider = factory.provider_rw().unwrap();
+        for block in (0..5).filter(|block| *block != 3) {
+            provider.tx_ref().put::<tables::AccountChangeSets>(block, account(block)).unwrap();
+            provider
+                .tx_ref()
+                .put::<tables::StorageChangeSets>(
+                    BlockNumberAddress((block, Address::with_last_byte(block as u8))),
+                    storage(block),
+                )
+                .unwrap();
+        }
+        provider.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(4)).unwrap();
+        provider.commit().unwrap();
+        write_db_version_file(&db_path, 2).unwrap();
+
+        Migrator::new(factory.clone(), &db_path)
+            .with_migrations(
+                Migrations::new().with_migration(ChangeSetsToStaticFiles { batch_size: 2 }),
+            )
+            .run(false)
+            .unwrap();
+
+        let static_file_provider = factory.static_file_provider();
+        for segment in [StaticFileSegment::AccountChangeSets, StaticFileSegment::StorageChangeSets]
+        {
+            assert_eq!(static_file_provider.get_highest_static_file_block(segment), Some(4));
+        }
+        assert_eq!(
+            static_file_provider.account_changesets_range(0..=4).unwrap(),
+            [0, 1, 2, 4].map(|block| (block, account(block)))
+        );
+        assert_eq!(
+            static_file_provider.storage_changesets_range(0..=4).unwrap(),
+            [0, 1, 2, 4].map(|block| (
+                BlockNumberAddress((block, Address::with_last_byte(block as u8))),
+                storage(block)
+            ))
+        );
+        assert_eq!(get_db_version(&db_path).unwrap(), 3);
+    }
+
+    #[test]
+    fn skips_changesets_of_pruned_history() {
+        let factory = create_test_provider_factory().with_prune_modes(PruneModes {
+            account_history: Some(PruneMode::Full),
+            ..Default::default()
+        });
+        let db_path = factory.db_ref().path().to_path_buf();
+
+        let provider = factory.provider_rw().unwrap();
+        provider
+            .tx_ref()
+            .put::<tables::AccountChangeSets>(
+                0,
+                AccountBeforeTx { address: Address::with_last_byte(1), info: None },
+            )
+            .unwrap();
+        provider.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(0)).unwrap();
+        provider.commit().unwrap();
+        write_db_version_file(&db_path, 2).unwrap();
+
+        Migrator::new(factory.clone(), &db_path)
+            .with_migrations(Migrations::new().with_migration(ChangeSetsToStaticFiles::default()))
+            .run(false)
+            .unwrap();
+
+        // the account history is pruned, only the storage changesets are copied
+        let static_file_provider = factory.static_file_provider();
+        assert_eq!(
+            static_file_provider
+                .get_highest_static_file_block(StaticFileSegment::AccountChangeSets),
+            None
+        );
+        assert_eq!(
+            static_file_provider
+                .get_highest_static_file_block(StaticFileSegment::StorageChangeSets),
+            Some(0)
+        );
+    }
+}
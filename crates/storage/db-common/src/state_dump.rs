@@ -0,0 +1,250 @@
+//! Reth state dump utility functions.
+
+use crate::init::{GenesisAccountWithAddress, StateRoot};
+use alloy_genesis::GenesisAccount;
+use reth_db::tables;
+use reth_db_api::{
+    cursor::{DbCursorRO, DbDupCursorRO},
+    models::{AccountBeforeTx, BlockNumberAddress},
+    transaction::DbTx,
+};
+use reth_primitives::{Account, Address, BlockNumber, StorageEntry, B256, U256};
+use reth_provider::{
+    DatabaseProvider, HeaderProvider, ProviderError, PruneCheckpointReader, StageCheckpointReader,
+};
+use reth_prune_types::PruneSegment;
+use reth_stages_types::StageId;
+use std::{collections::BTreeMap, io::Write, ops::RangeInclusive};
+use tracing::{info, trace};
+
+/// Number of dumped accounts after which to log progress.
+const LOG_INTERVAL_DUMPED_ACCOUNTS: usize = 100_000;
+
+/// Values of accounts and storage slots before the changes of a range of blocks.
+type Reverts = (BTreeMap<Address, Option<Account>>, BTreeMap<Address, BTreeMap<B256, U256>>);
+
+/// Writes the state at the given block to the writer, in the format that is consumed by
+/// [`init_from_state_dump`](crate::init::init_from_state_dump).
+///
+/// The first line holds the state root of the block, every following line holds an account with
+/// its code and storage, ordered by address. The state at the last executed block is read from the
+/// plain state tables, the state at older blocks is reconstructed by reverting the changesets of
+/// all blocks after it.
+///
+/// Returns the number of dumped accounts.
+pub fn dump_state_at<TX: DbTx>(
+    provider: &DatabaseProvider<TX>,
+    block: BlockNumber,
+    mut writer: impl Write,
+) -> eyre::Result<usize> {
+    let tip = provider.get_stage_checkpoint(StageId::Execution)?.unwrap_or_default().block_number;
+    if block > tip {
+        eyre::bail!("block #{block} is after the last executed block #{tip}")
+    }
+    let root = provider
+        .header_by_number(block)?
+        .ok_or_else(|| ProviderError::HeaderNotFound(block.into()))?
+        .state_root;
+
+    let (account_reverts, mut storage_reverts) =
+        if block < tip { collect_reverts(provider, block + 1..=tip)? } else { Default::default() };
+
+    serde_json::to_writer(&mut writer, &StateRoot { root })?;
+    writeln!(writer)?;
+
+    let tx = provider.tx_ref();
+    let mut storage_cursor = tx.cursor_dup_read::<tables::PlainStorageState>()?;
+    let mut account_reverts = account_reverts.into_iter().peekable();
+    let mut dumped_accounts = 0;
+    let mut dump_account = |address: Address, account: Option<Account>| -> eyre::Result<()> {
+        // the account did not exist at the block
+        let Some(account) = account else { return Ok(()) };
+
+        let mut storage = BTreeMap::new();
+        for entry in storage_cursor.walk_dup(Some(address), None)? {
+            let (_, StorageEntry { key, value }) = entry?;
+            storage.insert(key, value);
+        }
+        storage.extend(storage_reverts.remove(&address).unwrap_or_default());
+        storage.retain(|_, value| !value.is_zero());
+
+        let code = match account.bytecode_hash {
+            Some(code_hash) => {
+                tx.get::<tables::Bytecodes>(code_hash)?.map(|code| code.original_bytes())
+            }
+            None => None,
+        };
+
+        let genesis_account = GenesisAccount::default()
+            .with_nonce(Some(account.nonce))
+            .with_balance(account.balance)
+            .with_code(code)
+            .with_storage((!storage.is_empty()).then(|| {
+                storage.into_iter().map(|(key, value)| (key, B256::from(value))).collect()
+            }));
+        serde_json::to_writer(
+            &mut writer,
+            &GenesisAccountWithAddress { genesis_account, address },
+        )?;
+        writeln!(writer)?;
+
+        dumped_accounts += 1;
+        if dumped_accounts % LOG_INTERVAL_DUMPED_ACCOUNTS == 0 {
+            info!(target: "reth::cli", dumped_accounts, "Dumping accounts");
+        }
+        Ok(())
+    };
+
+    // merge the plain state with the reverted accounts, both are ordered by address
+    for entry in tx.cursor_read::<tables::PlainAccountState>()?.walk(None)? {
+        let (address, account) = entry?;
+        while let Some((reverted, _)) = account_reverts.peek() {
+            if *reverted >= address {
+                break
+            }
+            // the account existed at the block but was destroyed afterwards
+            let (reverted, account) = account_reverts.next().expect("peeked");
+            dump_account(reverted, account)?;
+        }
+        match account_reverts.next_if(|(reverted, _)| *reverted == address) {
+            Some((_, account)) => dump_account(address, account)?,
+            None => dump_account(address, Some(account))?,
+        }
+    }
+    for (address, account) in account_reverts {
+        dump_account(address, account)?;
+    }
+
+    trace!(target: "reth::cli", block, %root, dumped_accounts, "Dumped state");
+    Ok(dumped_accounts)
+}
+
+/// Collects the values of all accounts and storage slots before they were first changed in the
+/// given range of blocks.
+fn collect_reverts<TX: DbTx>(
+    provider: &DatabaseProvider<TX>,
+    range: RangeInclusive<BlockNumber>,
+) -> eyre::Result<Reverts> {
+    for segment in [PruneSegment::AccountHistory, PruneSegment::StorageHistory] {
+        if let Some(pruned) =
+            provider.get_prune_checkpoint(segment)?.and_then(|checkpoint| checkpoint.block_number)
+        {
+            if *range.start() <= pruned {
+                return Err(ProviderError::ChangeSetsPruned(*range.start()).into())
+            }
+        }
+    }
+
+    // the first changeset entry after the block holds the value at the block
+    let mut accounts = BTreeMap::new();
+    for (_, AccountBeforeTx { address, info }) in
+        provider.account_changesets_range(range.clone())?
+    {
+        accounts.entry(address).or_insert(info);
+    }
+
+    let mut storages = BTreeMap::<_, BTreeMap<_, _>>::new();
+    for (BlockNumberAddress((_, address)), StorageEntry { key, value }) in
+        provider.storage_changesets_range(range)?
+    {
+        storages.entry(address).or_default().entry(key).or_insert(value);
+    }
+
+    Ok((accounts, storages))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::init::init_genesis;
+    use alloy_genesis::Genesis;
+    use reth_chainspec::{Chain, ChainSpec};
+    use reth_db_api::transaction::DbTxMut;
+    use reth_provider::{
+        test_utils::create_test_provider_factory_with_chain_spec, StageCheckpointWriter,
+    };
+    use reth_stages_types::StageCheckpoint;
+    use std::sync::Arc;
+
+    fn parse_dump(dump: &[u8]) -> (B256, BTreeMap<Address, GenesisAccount>) {
+        let mut lines = dump.split(|byte| *byte == b'\n').filter(|line| !line.is_empty());
+        let root = serde_json::from_slice::<StateRoot>(lines.next().unwrap()).unwrap().root;
+        let accounts = lines
+            .map(|line| {
+                let GenesisAccountWithAddress { genesis_account, address } =
+                    serde_json::from_slice(line).unwrap();
+                (address, genesis_account)
+            })
+            .collect();
+        (root, accounts)
+    }
+
+    #[test]
+    fn dump_state_reverts_changesets() {
+        let destroyed = Address::with_last_byte(1);
+        let created = Address::with_last_byte(2);
+        let with_storage = Address::with_last_byte(3);
+        let (storage_key, storage_value) = (B256::with_last_byte(1), B256::with_last_byte(2));
+        let alloc = BTreeMap::from([
+            (destroyed, GenesisAccount { balance: U256::from(1), ..Default::default() }),
+            (
+                with_storage,
+                GenesisAccount {
+                    nonce: Some(1),
+                    storage: Some(BTreeMap::from([(storage_key, storage_value)])),
+                    ..Default::default()
+                },
+            ),
+        ]);
+        let chain_spec = Arc::new(ChainSpec {
+            chain: Chain::from_id(1),
+            genesis: Genesis { alloc: alloc.clone(), ..Default::default() },
+            ..Default::default()
+        });
+        let expected_accounts = alloc
+            .into_iter()
+            .map(|(address, account)| {
+                let nonce = account.nonce.unwrap_or_default();
+                (address, account.with_nonce(Some(nonce)))
+            })
+            .collect::<BTreeMap<_, _>>();
+
+        let factory = create_test_provider_factory_with_chain_spec(chain_spec.clone());
+        init_genesis(factory.clone()).unwrap();
+
+        let mut dump = Vec::new();
+        assert_eq!(dump_state_at(&factory.provider().unwrap(), 0, &mut dump).unwrap(), 2);
+        let (root, accounts) = parse_dump(&dump);
+        assert_eq!(root, chain_spec.genesis_header().state_root);
+        assert_eq!(accounts, expected_accounts);
+
+        // destroy an account, create another one and clear the storage at block 1
+        let provider_rw = factory.provider_rw().unwrap();
+        let tx = provider_rw.tx_ref();
+        let destroyed_account = tx.get::<tables::PlainAccountState>(destroyed).unwrap();
+        tx.delete::<tables::PlainAccountState>(destroyed, None).unwrap();
+        tx.put::<tables::AccountChangeSets>(
+            1,
+            AccountBeforeTx { address: destroyed, info: destroyed_account },
+        )
+        .unwrap();
+        tx.put::<tables::PlainAccountState>(created, Account::default()).unwrap();
+        tx.put::<tables::AccountChangeSets>(1, AccountBeforeTx { address: created, info: None })
+            .unwrap();
+        tx.delete::<tables::PlainStorageState>(with_storage, None).unwrap();
+        tx.put::<tables::StorageChangeSets>(
+            (1, with_storage).into(),
+            StorageEntry { key: storage_key, value: U256::from_be_bytes(storage_value.0) },
+        )
+        .unwrap();
+        provider_rw.save_stage_checkpoint(StageId::Execution, StageCheckpoint::new(1)).unwrap();
+        provider_rw.commit().unwrap();
+
+        let provider = factory.provider().unwrap();
+        let mut dump = Vec::new();
+        assert_eq!(dump_state_at(&provider, 0, &mut dump).unwrap(), 2);
+        assert_eq!(parse_dump(&dump), (root, expected_accounts));
+
+        assert!(dump_state_at(&provider, 2, &mut Vec::new()).is_err());
+    }
+}
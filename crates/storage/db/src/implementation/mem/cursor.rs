@@ -0,0 +1,426 @@
+//! Cursor of the in-memory database.
+
+use super::{
+    compress_value, is_dupsort, key_exist_error, key_mismatch_error, put_entry, MemTable,
+    TransactionKind, TxTables, RW,
+};
+use crate::{tables::utils::decoder, DatabaseError};
+use reth_db_api::{
+    common::{PairResult, ValueOnlyResult},
+    cursor::{
+        DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW, DupWalker, RangeWalker,
+        ReverseWalker, Walker,
+    },
+    table::{Decompress, DupSort, Encode, Table},
+};
+use reth_storage_errors::db::{DatabaseWriteError, DatabaseWriteOperation};
+use std::{
+    borrow::Cow,
+    fmt,
+    marker::PhantomData,
+    ops::{Bound, RangeBounds},
+};
+
+/// Encoded `(key, value)` pair.
+type RawEntry = (Vec<u8>, Vec<u8>);
+
+/// Position of a [`Cursor`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Position {
+    /// The cursor hasn't been positioned yet, so moving forward starts at the first entry and
+    /// moving backward at the last one.
+    Unset,
+    /// The cursor points at an entry. The entry may have been deleted since.
+    At(Vec<u8>, Vec<u8>),
+    /// The cursor moved past the end of the table or a seek failed.
+    End,
+}
+
+/// Cursor over a table of the in-memory database.
+///
+/// The cursor remembers the entry it points at rather than an index, so it keeps working while
+/// the table is modified through the same transaction, including deletion of the current entry.
+pub struct Cursor<K: TransactionKind, T: Table> {
+    /// Tables of the transaction the cursor belongs to.
+    tables: TxTables,
+    /// Current position.
+    position: Position,
+    /// Whether the table is `DUPSORT`.
+    dupsort: bool,
+    _kind: PhantomData<(K, T)>,
+}
+
+impl<K: TransactionKind, T: Table> fmt::Debug for Cursor<K, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cursor")
+            .field("table", &T::NAME)
+            .field("position", &self.position)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<K: TransactionKind, T: Table> Cursor<K, T> {
+    pub(crate) fn new(tables: TxTables) -> Self {
+        Self { tables, position: Position::Unset, dupsort: is_dupsort(T::NAME), _kind: PhantomData }
+    }
+
+    /// Calls `f` with the contents of the table and the current position.
+    fn read<R>(&self, f: impl FnOnce(&MemTable, &Position) -> R) -> R {
+        self.tables.read(T::NAME, |table| f(table, &self.position))
+    }
+
+    /// Positions the cursor at the entry, or at the end if there is none, and decodes it.
+    fn seek_to(&mut self, entry: Option<RawEntry>) -> PairResult<T> {
+        self.move_to(entry, Position::End)
+    }
+
+    /// Moves the cursor to the entry if there is one, otherwise sets it to `fallback`.
+    fn move_to(&mut self, entry: Option<RawEntry>, fallback: Position) -> PairResult<T> {
+        self.position = match &entry {
+            Some((key, value)) => Position::At(key.clone(), value.clone()),
+            None => fallback,
+        };
+        entry.map(decode_entry::<T>).transpose()
+    }
+}
+
+/// Decodes an encoded `(key, value)` pair.
+fn decode_entry<T: Table>((key, value): RawEntry) -> Result<(T::Key, T::Value), DatabaseError> {
+    decoder::<T>((Cow::Owned(key), Cow::Owned(value)))
+}
+
+/// Returns the first entry of the table.
+fn first_entry(table: &MemTable) -> Option<RawEntry> {
+    table.iter().find_map(|(key, values)| Some((key.clone(), values.first()?.clone())))
+}
+
+/// Returns the last entry of the table.
+fn last_entry(table: &MemTable) -> Option<RawEntry> {
+    table.iter().rev().find_map(|(key, values)| Some((key.clone(), values.last()?.clone())))
+}
+
+/// Returns the first entry with a key greater than or equal to `key`.
+fn seek_entry(table: &MemTable, key: &[u8]) -> Option<RawEntry> {
+    table
+        .range::<[u8], _>((Bound::Included(key), Bound::Unbounded))
+        .find_map(|(key, values)| Some((key.clone(), values.first()?.clone())))
+}
+
+/// Returns the first entry with the exact `key`.
+fn seek_exact_entry(table: &MemTable, key: &[u8]) -> Option<RawEntry> {
+    let value = table.get(key)?.first()?;
+    Some((key.to_vec(), value.clone()))
+}
+
+/// Returns the first value under `key` that is greater than or equal to `subkey`.
+fn seek_value(table: &MemTable, key: &[u8], subkey: &[u8]) -> Option<Vec<u8>> {
+    table.get(key)?.range::<[u8], _>((Bound::Included(subkey), Bound::Unbounded)).next().cloned()
+}
+
+/// Returns the next value of `key` after `value`.
+fn next_dup_entry(table: &MemTable, key: &[u8], value: &[u8]) -> Option<RawEntry> {
+    let next =
+        table.get(key)?.range::<[u8], _>((Bound::Excluded(value), Bound::Unbounded)).next()?;
+    Some((key.to_vec(), next.clone()))
+}
+
+/// Returns the first entry with a key greater than `key`.
+fn next_key_entry(table: &MemTable, key: &[u8]) -> Option<RawEntry> {
+    table
+        .range::<[u8], _>((Bound::Excluded(key), Bound::Unbounded))
+        .find_map(|(key, values)| Some((key.clone(), values.first()?.clone())))
+}
+
+/// Returns the entry following `(key, value)`.
+fn next_entry(table: &MemTable, key: &[u8], value: &[u8]) -> Option<RawEntry> {
+    next_dup_entry(table, key, value).or_else(|| next_key_entry(table, key))
+}
+
+/// Returns the entry preceding `(key, value)`.
+fn prev_entry(table: &MemTable, key: &[u8], value: &[u8]) -> Option<RawEntry> {
+    if let Some(prev) = table.get(key).and_then(|values| {
+        values.range::<[u8], _>((Bound::Unbounded, Bound::Excluded(value))).last()
+    }) {
+        return Some((key.to_vec(), prev.clone()))
+    }
+
+    table
+        .range::<[u8], _>((Bound::Unbounded, Bound::Excluded(key)))
+        .rev()
+        .find_map(|(key, values)| Some((key.clone(), values.last()?.clone())))
+}
+
+impl<K: TransactionKind, T: Table> DbCursorRO<T> for Cursor<K, T> {
+    fn first(&mut self) -> PairResult<T> {
+        let entry = self.read(|table, _| first_entry(table));
+        self.seek_to(entry)
+    }
+
+    fn seek_exact(&mut self, key: <T as Table>::Key) -> PairResult<T> {
+        let key = key.encode();
+        let entry = self.read(|table, _| seek_exact_entry(table, key.as_ref()));
+        self.seek_to(entry)
+    }
+
+    fn seek(&mut self, key: <T as Table>::Key) -> PairResult<T> {
+        let key = key.encode();
+        let entry = self.read(|table, _| seek_entry(table, key.as_ref()));
+        self.seek_to(entry)
+    }
+
+    fn next(&mut self) -> PairResult<T> {
+        let entry = self.read(|table, position| match position {
+            Position::Unset => first_entry(table),
+            Position::At(key, value) => next_entry(table, key, value),
+            Position::End => None,
+        });
+        self.move_to(entry, Position::End)
+    }
+
+    fn prev(&mut self) -> PairResult<T> {
+        let entry = self.read(|table, position| match position {
+            Position::Unset | Position::End => last_entry(table),
+            Position::At(key, value) => prev_entry(table, key, value),
+        });
+        self.move_to(entry, Position::Unset)
+    }
+
+    fn last(&mut self) -> PairResult<T> {
+        let entry = self.read(|table, _| last_entry(table));
+        self.seek_to(entry)
+    }
+
+    fn current(&mut self) -> PairResult<T> {
+        self.read(|table, position| match position {
+            Position::At(key, value) => table
+                .get(key)
+                .filter(|values| values.contains(value))
+                .map(|_| (key.clone(), value.clone())),
+            Position::Unset | Position::End => None,
+        })
+        .map(decode_entry::<T>)
+        .transpose()
+    }
+
+    fn walk(&mut self, start_key: Option<T::Key>) -> Result<Walker<'_, T, Self>, DatabaseError> {
+        let start = if let Some(start_key) = start_key {
+            self.seek(start_key).transpose()
+        } else {
+            self.first().transpose()
+        };
+
+        Ok(Walker::new(self, start))
+    }
+
+    fn walk_range(
+        &mut self,
+        range: impl RangeBounds<T::Key>,
+    ) -> Result<RangeWalker<'_, T, Self>, DatabaseError> {
+        let start = match range.start_bound().cloned() {
+            Bound::Included(key) => self.seek(key),
+            Bound::Excluded(_key) => {
+                unreachable!("Rust doesn't allow for Bound::Excluded in starting bounds");
+            }
+            Bound::Unbounded => self.first(),
+        }
+        .transpose();
+        Ok(RangeWalker::new(self, start, range.end_bound().cloned()))
+    }
+
+    fn walk_back(
+        &mut self,
+        start_key: Option<T::Key>,
+    ) -> Result<ReverseWalker<'_, T, Self>, DatabaseError> {
+        let start =
+            if let Some(start_key) = start_key { self.seek(start_key) } else { self.last() }
+                .transpose();
+
+        Ok(ReverseWalker::new(self, start))
+    }
+}
+
+impl<K: TransactionKind, T: DupSort> DbDupCursorRO<T> for Cursor<K, T> {
+    /// Returns the next `(key, value)` pair of a DUPSORT table.
+    fn next_dup(&mut self) -> PairResult<T> {
+        let entry = self.read(|table, position| match position {
+            Position::At(key, value) => next_dup_entry(table, key, value),
+            Position::Unset | Position::End => None,
+        });
+        let position = self.position.clone();
+        self.move_to(entry, position)
+    }
+
+    /// Returns the next `(key, value)` pair skipping the duplicates.
+    fn next_no_dup(&mut self) -> PairResult<T> {
+        let entry = self.read(|table, position| match position {
+            Position::Unset => first_entry(table),
+            Position::At(key, _) => next_key_entry(table, key),
+            Position::End => None,
+        });
+        self.move_to(entry, Position::End)
+    }
+
+    /// Returns the next `value` of a duplicate `key`.
+    fn next_dup_val(&mut self) -> ValueOnlyResult<T> {
+        Ok(self.next_dup()?.map(|(_, value)| value))
+    }
+
+    fn seek_by_key_subkey(
+        &mut self,
+        key: <T as Table>::Key,
+        subkey: <T as DupSort>::SubKey,
+    ) -> ValueOnlyResult<T> {
+        let key: Vec<u8> = key.encode().into();
+        let subkey = subkey.encode();
+        let value = self.read(|table, _| seek_value(table, &key, subkey.as_ref()));
+        self.position = match &value {
+            Some(value) => Position::At(key, value.clone()),
+            None => Position::End,
+        };
+        value.map(T::Value::decompress_owned).transpose()
+    }
+
+    /// Depending on its arguments, returns an iterator starting at:
+    /// - Some(key), Some(subkey): a `key` item whose data is >= than `subkey`
+    /// - Some(key), None: first item of a specified `key`
+    /// - None, Some(subkey): like first case, but in the first key
+    /// - None, None: first item in the table of a DUPSORT table.
+    fn walk_dup(
+        &mut self,
+        key: Option<T::Key>,
+        subkey: Option<T::SubKey>,
+    ) -> Result<DupWalker<'_, T, Self>, DatabaseError> {
+        let start = match (key, subkey) {
+            (Some(key), Some(subkey)) => {
+                let key: Vec<u8> = key.encode().into();
+                let subkey = subkey.encode();
+                let entry = self
+                    .read(|table, _| seek_value(table, &key, subkey.as_ref()))
+                    .map(|value| (key, value));
+                self.seek_to(entry).transpose()
+            }
+            (Some(key), None) => self.seek_exact(key).transpose(),
+            (None, Some(subkey)) => {
+                let subkey = subkey.encode();
+                let entry = self.read(|table, _| {
+                    let (key, _) = first_entry(table)?;
+                    let value = seek_value(table, &key, subkey.as_ref())?;
+                    Some((key, value))
+                });
+                self.seek_to(entry).transpose()
+            }
+            (None, None) => self.first().transpose(),
+        };
+
+        Ok(DupWalker::<'_, T, Self> { cursor: self, start })
+    }
+}
+
+impl<T: Table> Cursor<RW, T> {
+    /// Stores the entry and positions the cursor at it.
+    fn put_current(&mut self, key: Vec<u8>, value: Vec<u8>) {
+        let dupsort = self.dupsort;
+        self.position = Position::At(key.clone(), value.clone());
+        self.tables.write(T::NAME, |table| put_entry(table, dupsort, key, value));
+    }
+}
+
+impl<T: Table> DbCursorRW<T> for Cursor<RW, T> {
+    /// Database operation that will update an existing row if a specified value already
+    /// exists in a table, and insert a new row if the specified value doesn't already exist
+    ///
+    /// For a DUPSORT table, `upsert` will not actually update-or-insert. If the key already exists,
+    /// it will append the value to the subkey, even if the subkeys are the same. So if you want
+    /// to properly upsert, you'll need to `seek_exact` & `delete_current` if the key+subkey was
+    /// found, before calling `upsert`.
+    fn upsert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        self.put_current(key.encode().into(), compress_value(value));
+        Ok(())
+    }
+
+    fn insert(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let key: Vec<u8> = key.encode().into();
+        if self.read(|table, _| table.contains_key(&key)) {
+            return Err(DatabaseWriteError {
+                info: key_exist_error(),
+                operation: DatabaseWriteOperation::CursorInsert,
+                table_name: T::NAME,
+                key,
+            }
+            .into())
+        }
+
+        self.put_current(key, compress_value(value));
+        Ok(())
+    }
+
+    /// Appends the data to the end of the table. Consequently, the append operation
+    /// will fail if the inserted key is less than the last table key
+    fn append(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let key: Vec<u8> = key.encode().into();
+        let value = compress_value(value);
+        // Same as in MDBX, the key may be equal to the last one. Its value is then replaced, or
+        // inserted among the existing duplicates for `DUPSORT` tables.
+        let in_order =
+            self.read(|table, _| table.last_key_value().map_or(true, |(last, _)| key >= *last));
+        if !in_order {
+            return Err(DatabaseWriteError {
+                info: key_mismatch_error(),
+                operation: DatabaseWriteOperation::CursorAppend,
+                table_name: T::NAME,
+                key,
+            }
+            .into())
+        }
+
+        self.put_current(key, value);
+        Ok(())
+    }
+
+    fn delete_current(&mut self) -> Result<(), DatabaseError> {
+        let Position::At(key, value) = &self.position else { return Ok(()) };
+        let dupsort = self.dupsort;
+        self.tables.write(T::NAME, |table| {
+            if dupsort {
+                if let Some(values) = table.get_mut(key) {
+                    values.remove(value);
+                    if values.is_empty() {
+                        table.remove(key);
+                    }
+                }
+            } else {
+                table.remove(key);
+            }
+        });
+        Ok(())
+    }
+}
+
+impl<T: DupSort> DbDupCursorRW<T> for Cursor<RW, T> {
+    fn delete_current_duplicates(&mut self) -> Result<(), DatabaseError> {
+        if let Position::At(key, _) = &self.position {
+            self.tables.write(T::NAME, |table| table.remove(key));
+        }
+        Ok(())
+    }
+
+    fn append_dup(&mut self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let key: Vec<u8> = key.encode().into();
+        let value = compress_value(value);
+        let in_order = self.read(|table, _| {
+            table.get(&key).and_then(|values| values.last()).map_or(true, |last| value > *last)
+        });
+        if !in_order {
+            return Err(DatabaseWriteError {
+                info: key_mismatch_error(),
+                operation: DatabaseWriteOperation::CursorAppendDup,
+                table_name: T::NAME,
+                key,
+            }
+            .into())
+        }
+
+        self.put_current(key, value);
+        Ok(())
+    }
+}
@@ -0,0 +1,565 @@
+//! Pure-Rust in-memory database.
+//!
+//! Every table is an ordered map of encoded keys to the ordered set of compressed values stored
+//! under that key, so the byte-wise ordering and the `DUPSORT` semantics match MDBX. Read
+//! transactions operate on an immutable snapshot of the committed tables, while a single write
+//! transaction at a time works on a copy-on-write clone that replaces the committed state on
+//! commit.
+
+use crate::{tables::Tables, DatabaseError};
+use parking_lot::{Condvar, Mutex, RwLock};
+use reth_db_api::{
+    database::Database,
+    database_metrics::{DatabaseMetadata, DatabaseMetadataValue, DatabaseMetrics},
+    table::Compress,
+};
+use reth_storage_errors::db::DatabaseErrorInfo;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt,
+    sync::Arc,
+};
+use tx::Tx;
+
+pub mod cursor;
+pub mod tx;
+
+/// Error code returned when a key/data pair already exists. Mirrors `MDBX_KEYEXIST`.
+const KEY_EXIST_CODE: i32 = -30799;
+
+/// Error code returned when an append breaks the table ordering. Mirrors `MDBX_EKEYMISMATCH`.
+const KEY_MISMATCH_CODE: i32 = -30418;
+
+/// Contents of a single table: encoded keys mapped to their compressed values.
+///
+/// Tables that are not `DUPSORT` hold exactly one value per key. Keys without values are never
+/// stored.
+pub(crate) type MemTable = BTreeMap<Vec<u8>, BTreeSet<Vec<u8>>>;
+
+/// Set of tables visible to a transaction, keyed by table name.
+pub(crate) type Snapshot = HashMap<&'static str, Arc<MemTable>>;
+
+/// Marker trait for the transaction kind of the in-memory database.
+pub trait TransactionKind: fmt::Debug + Send + Sync + 'static {
+    /// Whether the transaction is read-only.
+    const IS_READ_ONLY: bool;
+}
+
+/// Read-only transaction marker.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct RO;
+
+/// Read-write transaction marker.
+#[derive(Debug)]
+#[non_exhaustive]
+pub struct RW;
+
+impl TransactionKind for RO {
+    const IS_READ_ONLY: bool = true;
+}
+
+impl TransactionKind for RW {
+    const IS_READ_ONLY: bool = false;
+}
+
+/// In-memory implementation of [`Database`].
+///
+/// Cloning the database is cheap and yields a handle to the same underlying storage.
+#[derive(Clone, Default)]
+pub struct MemDatabase {
+    inner: Arc<MemDatabaseInner>,
+}
+
+#[derive(Default)]
+struct MemDatabaseInner {
+    /// Tables as of the last committed write transaction.
+    committed: RwLock<Arc<Snapshot>>,
+    /// Whether a write transaction is currently open.
+    writer_active: Mutex<bool>,
+    /// Notified when the open write transaction is committed or dropped.
+    writer_released: Condvar,
+}
+
+impl fmt::Debug for MemDatabase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemDatabase").finish_non_exhaustive()
+    }
+}
+
+impl MemDatabase {
+    /// Creates a new empty in-memory database.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the committed tables.
+    fn snapshot(&self) -> TxTables {
+        TxTables(Arc::new(RwLock::new(self.inner.committed.read().as_ref().clone())))
+    }
+
+    /// Blocks until no other write transaction is open and claims the writer slot.
+    fn acquire_writer(&self) -> WriterGuard {
+        let mut active = self.inner.writer_active.lock();
+        while *active {
+            self.inner.writer_released.wait(&mut active);
+        }
+        *active = true;
+        WriterGuard { inner: self.inner.clone() }
+    }
+}
+
+impl Database for MemDatabase {
+    type TX = Tx<RO>;
+    type TXMut = Tx<RW>;
+
+    fn tx(&self) -> Result<Self::TX, DatabaseError> {
+        Ok(Tx::new(self.snapshot(), None))
+    }
+
+    fn tx_mut(&self) -> Result<Self::TXMut, DatabaseError> {
+        // Take the writer slot first, so the snapshot includes the previous writer's changes.
+        let writer = self.acquire_writer();
+        Ok(Tx::new(self.snapshot(), Some(writer)))
+    }
+}
+
+impl DatabaseMetrics for MemDatabase {}
+
+impl DatabaseMetadata for MemDatabase {
+    fn metadata(&self) -> DatabaseMetadataValue {
+        DatabaseMetadataValue::new(None)
+    }
+}
+
+/// Exclusive right to write to a [`MemDatabase`]. Released on drop.
+pub(crate) struct WriterGuard {
+    inner: Arc<MemDatabaseInner>,
+}
+
+impl WriterGuard {
+    /// Replaces the committed tables of the database.
+    fn commit(&self, snapshot: Snapshot) {
+        *self.inner.committed.write() = Arc::new(snapshot);
+    }
+}
+
+impl Drop for WriterGuard {
+    fn drop(&mut self) {
+        *self.inner.writer_active.lock() = false;
+        self.inner.writer_released.notify_one();
+    }
+}
+
+/// Tables of an open transaction, shared between the transaction and its cursors so that cursors
+/// observe writes made through the transaction and vice versa.
+#[derive(Clone)]
+pub(crate) struct TxTables(Arc<RwLock<Snapshot>>);
+
+impl TxTables {
+    /// Returns a copy of the tables. Only the table handles are cloned, not their contents.
+    fn snapshot(&self) -> Snapshot {
+        self.0.read().clone()
+    }
+
+    /// Calls `f` with the contents of the table, or an empty table if nothing was written to it.
+    fn read<R>(&self, table: &str, f: impl FnOnce(&MemTable) -> R) -> R {
+        static EMPTY: MemTable = BTreeMap::new();
+        f(self.0.read().get(table).map_or(&EMPTY, |table| table.as_ref()))
+    }
+
+    /// Calls `f` with mutable access to the table, copying it first if it's shared with another
+    /// transaction.
+    fn write<R>(&self, table: &'static str, f: impl FnOnce(&mut MemTable) -> R) -> R {
+        f(Arc::make_mut(self.0.write().entry(table).or_default()))
+    }
+
+    /// Removes all entries from the table.
+    fn clear(&self, table: &'static str) {
+        self.0.write().insert(table, Arc::default());
+    }
+}
+
+/// Stores `value` under `key`, replacing the existing value unless the table is `DUPSORT`.
+fn put_entry(table: &mut MemTable, dupsort: bool, key: Vec<u8>, value: Vec<u8>) {
+    if dupsort {
+        table.entry(key).or_default().insert(value);
+    } else {
+        table.insert(key, BTreeSet::from([value]));
+    }
+}
+
+/// Compresses a value the same way it would be stored in MDBX.
+fn compress_value<V: Compress>(value: V) -> Vec<u8> {
+    if let Some(value) = value.uncompressable_ref() {
+        return value.to_vec()
+    }
+    let mut buf = Vec::new();
+    value.compress_to_buf(&mut buf);
+    buf
+}
+
+/// Returns `true` if the table with the given name is `DUPSORT`.
+fn is_dupsort(table: &str) -> bool {
+    table.parse::<Tables>().is_ok_and(|table| table.is_dupsort())
+}
+
+/// Returns the number of `(key, value)` pairs in the table.
+fn entries(table: &MemTable) -> usize {
+    table.values().map(BTreeSet::len).sum()
+}
+
+/// Error returned when inserting a key that already exists.
+fn key_exist_error() -> DatabaseErrorInfo {
+    DatabaseErrorInfo { message: "key/data pair already exists".to_string(), code: KEY_EXIST_CODE }
+}
+
+/// Error returned when appending out of order.
+fn key_mismatch_error() -> DatabaseErrorInfo {
+    DatabaseErrorInfo {
+        message: "appended key/data pair is out of order".to_string(),
+        code: KEY_MISMATCH_CODE,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        tables::{CanonicalHeaders, PlainStorageState},
+        test_utils::{create_test_mem_db, create_test_rw_db},
+    };
+    use reth_db_api::{
+        cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW},
+        transaction::{DbTx, DbTxMut},
+    };
+    use reth_primitives::{Address, StorageEntry, B256, U256};
+    use reth_storage_errors::db::DatabaseWriteOperation;
+    use std::{sync::mpsc, thread, time::Duration};
+
+    /// Runs every contract test against both MDBX and the in-memory database, so that both
+    /// backends are checked to behave the same way.
+    macro_rules! contract_tests {
+        ($($name:ident),* $(,)?) => {
+            $(
+                mod $name {
+                    use super::*;
+
+                    #[test]
+                    fn mdbx() {
+                        super::$name(create_test_rw_db());
+                    }
+
+                    #[test]
+                    fn mem() {
+                        super::$name(create_test_mem_db());
+                    }
+                }
+            )*
+        };
+    }
+
+    contract_tests!(
+        put_get_delete,
+        cursor_navigation,
+        cursor_walkers,
+        cursor_write_errors,
+        dup_sort,
+        delete_current_while_walking,
+        snapshot_isolation,
+    );
+
+    fn storage_entry(key: u8, value: u64) -> StorageEntry {
+        StorageEntry { key: B256::with_last_byte(key), value: U256::from(value) }
+    }
+
+    /// Returns the operation and the error code of a failed write.
+    fn write_error(err: DatabaseError) -> (DatabaseWriteOperation, i32) {
+        match err {
+            DatabaseError::Write(err) => (err.operation, err.info.code),
+            err => panic!("unexpected error: {err:?}"),
+        }
+    }
+
+    fn put_get_delete<DB: Database>(db: DB) {
+        let tx = db.tx_mut().unwrap();
+        tx.put::<CanonicalHeaders>(1, B256::with_last_byte(1)).unwrap();
+        tx.put::<CanonicalHeaders>(2, B256::with_last_byte(2)).unwrap();
+        tx.put::<CanonicalHeaders>(1, B256::with_last_byte(3)).unwrap();
+        assert_eq!(tx.get::<CanonicalHeaders>(1), Ok(Some(B256::with_last_byte(3))));
+        assert_eq!(tx.entries::<CanonicalHeaders>(), Ok(2));
+
+        // A value that is passed must match the stored one.
+        assert_eq!(tx.delete::<CanonicalHeaders>(1, Some(B256::ZERO)), Ok(false));
+        assert_eq!(tx.delete::<CanonicalHeaders>(1, Some(B256::with_last_byte(3))), Ok(true));
+        assert_eq!(tx.delete::<CanonicalHeaders>(1, None), Ok(false));
+        assert_eq!(tx.get::<CanonicalHeaders>(1), Ok(None));
+        tx.commit().unwrap();
+
+        let tx = db.tx_mut().unwrap();
+        assert_eq!(tx.get::<CanonicalHeaders>(2), Ok(Some(B256::with_last_byte(2))));
+        tx.clear::<CanonicalHeaders>().unwrap();
+        assert_eq!(tx.entries::<CanonicalHeaders>(), Ok(0));
+        tx.commit().unwrap();
+
+        assert_eq!(db.tx().unwrap().get::<CanonicalHeaders>(2), Ok(None));
+    }
+
+    fn cursor_navigation<DB: Database>(db: DB) {
+        let tx = db.tx_mut().unwrap();
+        for key in [0, 1, 3, 5] {
+            tx.put::<CanonicalHeaders>(key, B256::with_last_byte(key as u8)).unwrap();
+        }
+        tx.commit().unwrap();
+
+        let tx = db.tx().unwrap();
+        let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+        let entry = |key: u64| Ok(Some((key, B256::with_last_byte(key as u8))));
+
+        assert_eq!(cursor.next(), entry(0));
+        assert_eq!(cursor.next(), entry(1));
+        assert_eq!(cursor.current(), entry(1));
+        assert_eq!(cursor.seek(2), entry(3));
+        assert_eq!(cursor.prev(), entry(1));
+        assert_eq!(cursor.seek_exact(2), Ok(None));
+        assert_eq!(cursor.seek_exact(3), entry(3));
+        assert_eq!(cursor.next(), entry(5));
+        assert_eq!(cursor.next(), Ok(None));
+        assert_eq!(cursor.seek(6), Ok(None));
+        assert_eq!(cursor.first(), entry(0));
+        assert_eq!(cursor.prev(), Ok(None));
+        assert_eq!(cursor.last(), entry(5));
+        assert_eq!(cursor.prev(), entry(3));
+    }
+
+    fn cursor_walkers<DB: Database>(db: DB) {
+        let tx = db.tx_mut().unwrap();
+        for key in [0, 1, 3, 5, 7] {
+            tx.put::<CanonicalHeaders>(key, B256::ZERO).unwrap();
+        }
+        tx.commit().unwrap();
+
+        let tx = db.tx().unwrap();
+        let mut cursor = tx.cursor_read::<CanonicalHeaders>().unwrap();
+        let keys = |walker: &mut dyn Iterator<Item = Result<(u64, B256), DatabaseError>>| {
+            walker.map(|entry| entry.unwrap().0).collect::<Vec<_>>()
+        };
+
+        assert_eq!(keys(&mut cursor.walk(None).unwrap()), vec![0, 1, 3, 5, 7]);
+        assert_eq!(keys(&mut cursor.walk(Some(2)).unwrap()), vec![3, 5, 7]);
+        assert_eq!(keys(&mut cursor.walk_range(1..5).unwrap()), vec![1, 3]);
+        assert_eq!(keys(&mut cursor.walk_range(1..=5).unwrap()), vec![1, 3, 5]);
+        assert_eq!(keys(&mut cursor.walk_range(4..).unwrap()), vec![5, 7]);
+        assert_eq!(keys(&mut cursor.walk_back(None).unwrap()), vec![7, 5, 3, 1, 0]);
+        assert_eq!(keys(&mut cursor.walk_back(Some(4)).unwrap()), vec![5, 3, 1, 0]);
+        assert_eq!(keys(&mut cursor.walk(Some(3)).unwrap().rev()), vec![3, 1, 0]);
+    }
+
+    fn cursor_write_errors<DB: Database>(db: DB) {
+        let tx = db.tx_mut().unwrap();
+        let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+        cursor.append(1, B256::ZERO).unwrap();
+        cursor.append(3, B256::ZERO).unwrap();
+        assert_eq!(
+            write_error(cursor.append(2, B256::ZERO).unwrap_err()),
+            (DatabaseWriteOperation::CursorAppend, KEY_MISMATCH_CODE)
+        );
+        // Appending the last key again replaces its value.
+        cursor.append(3, B256::with_last_byte(3)).unwrap();
+        assert_eq!(tx.get::<CanonicalHeaders>(3), Ok(Some(B256::with_last_byte(3))));
+
+        cursor.insert(2, B256::ZERO).unwrap();
+        assert_eq!(cursor.current(), Ok(Some((2, B256::ZERO))));
+        assert_eq!(
+            write_error(cursor.insert(2, B256::with_last_byte(1)).unwrap_err()),
+            (DatabaseWriteOperation::CursorInsert, KEY_EXIST_CODE)
+        );
+        assert_eq!(cursor.current(), Ok(Some((2, B256::ZERO))));
+
+        let address = Address::with_last_byte(1);
+        let mut cursor = tx.cursor_dup_write::<PlainStorageState>().unwrap();
+        cursor.append_dup(address, storage_entry(1, 1)).unwrap();
+        cursor.append_dup(address, storage_entry(3, 3)).unwrap();
+        assert_eq!(
+            write_error(cursor.append_dup(address, storage_entry(2, 2)).unwrap_err()),
+            (DatabaseWriteOperation::CursorAppendDup, KEY_MISMATCH_CODE)
+        );
+        // Appending to the last key only checks the key order.
+        cursor.append(address, storage_entry(2, 2)).unwrap();
+        assert_eq!(
+            write_error(cursor.append(Address::ZERO, storage_entry(4, 4)).unwrap_err()),
+            (DatabaseWriteOperation::CursorAppend, KEY_MISMATCH_CODE)
+        );
+        assert_eq!(
+            write_error(cursor.insert(address, storage_entry(4, 4)).unwrap_err()),
+            (DatabaseWriteOperation::CursorInsert, KEY_EXIST_CODE)
+        );
+        assert_eq!(
+            cursor.walk_dup(Some(address), None).unwrap().collect::<Result<Vec<_>, _>>(),
+            Ok(vec![
+                (address, storage_entry(1, 1)),
+                (address, storage_entry(2, 2)),
+                (address, storage_entry(3, 3))
+            ])
+        );
+    }
+
+    fn dup_sort<DB: Database>(db: DB) {
+        let first = Address::with_last_byte(1);
+        let second = Address::with_last_byte(2);
+
+        let tx = db.tx_mut().unwrap();
+        tx.put::<PlainStorageState>(second, storage_entry(1, 10)).unwrap();
+        tx.put::<PlainStorageState>(first, storage_entry(3, 3)).unwrap();
+        tx.put::<PlainStorageState>(first, storage_entry(1, 1)).unwrap();
+        tx.put::<PlainStorageState>(first, storage_entry(2, 2)).unwrap();
+        // The same subkey with another value is stored as a separate duplicate.
+        tx.put::<PlainStorageState>(first, storage_entry(2, 20)).unwrap();
+        // Exactly the same pair is only stored once.
+        tx.put::<PlainStorageState>(first, storage_entry(1, 1)).unwrap();
+        assert_eq!(tx.entries::<PlainStorageState>(), Ok(5));
+        assert_eq!(tx.get::<PlainStorageState>(first), Ok(Some(storage_entry(1, 1))));
+        tx.commit().unwrap();
+
+        let tx = db.tx().unwrap();
+        let mut cursor = tx.cursor_dup_read::<PlainStorageState>().unwrap();
+        assert_eq!(
+            cursor.walk(None).unwrap().collect::<Result<Vec<_>, _>>(),
+            Ok(vec![
+                (first, storage_entry(1, 1)),
+                (first, storage_entry(2, 2)),
+                (first, storage_entry(2, 20)),
+                (first, storage_entry(3, 3)),
+                (second, storage_entry(1, 10)),
+            ])
+        );
+
+        assert_eq!(
+            cursor.seek_by_key_subkey(first, B256::with_last_byte(2)),
+            Ok(Some(storage_entry(2, 2)))
+        );
+        assert_eq!(cursor.next_dup(), Ok(Some((first, storage_entry(2, 20)))));
+        assert_eq!(cursor.next_dup_val(), Ok(Some(storage_entry(3, 3))));
+        assert_eq!(cursor.next_dup(), Ok(None));
+        assert_eq!(cursor.next_no_dup(), Ok(Some((second, storage_entry(1, 10)))));
+        assert_eq!(cursor.next_no_dup(), Ok(None));
+        assert_eq!(cursor.seek_by_key_subkey(first, B256::with_last_byte(4)), Ok(None));
+        assert_eq!(cursor.seek_by_key_subkey(Address::ZERO, B256::ZERO), Ok(None));
+
+        let values = |key, subkey, cursor: &mut <DB::TX as DbTx>::DupCursor<PlainStorageState>| {
+            cursor
+                .walk_dup(key, subkey)
+                .unwrap()
+                .map(|entry| entry.unwrap().1.value.to::<u64>())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(values(None, None, &mut cursor), vec![1, 2, 20, 3]);
+        assert_eq!(values(Some(second), None, &mut cursor), vec![10]);
+        assert_eq!(values(Some(first), Some(B256::with_last_byte(2)), &mut cursor), vec![2, 20, 3]);
+        assert_eq!(values(None, Some(B256::with_last_byte(3)), &mut cursor), vec![3]);
+        drop(tx);
+
+        let tx = db.tx_mut().unwrap();
+        assert_eq!(tx.delete::<PlainStorageState>(first, Some(storage_entry(2, 20))), Ok(true));
+        assert_eq!(tx.delete::<PlainStorageState>(first, Some(storage_entry(2, 20))), Ok(false));
+        assert_eq!(tx.entries::<PlainStorageState>(), Ok(4));
+        assert_eq!(tx.delete::<PlainStorageState>(first, None), Ok(true));
+        assert_eq!(tx.entries::<PlainStorageState>(), Ok(1));
+        assert_eq!(tx.get::<PlainStorageState>(first), Ok(None));
+
+        let mut cursor = tx.cursor_dup_write::<PlainStorageState>().unwrap();
+        cursor.upsert(second, storage_entry(2, 20)).unwrap();
+        cursor.seek_exact(second).unwrap();
+        cursor.delete_current_duplicates().unwrap();
+        assert_eq!(tx.entries::<PlainStorageState>(), Ok(0));
+    }
+
+    fn delete_current_while_walking<DB: Database>(db: DB) {
+        let tx = db.tx_mut().unwrap();
+        for key in 0..6 {
+            tx.put::<CanonicalHeaders>(key, B256::ZERO).unwrap();
+        }
+        let address = Address::with_last_byte(1);
+        for subkey in 0..4 {
+            tx.put::<PlainStorageState>(address, storage_entry(subkey, subkey as u64)).unwrap();
+        }
+
+        let mut cursor = tx.cursor_write::<CanonicalHeaders>().unwrap();
+        let mut walker = cursor.walk(None).unwrap();
+        while let Some((key, _)) = walker.next().transpose().unwrap() {
+            if key % 2 == 0 {
+                walker.delete_current().unwrap();
+            }
+        }
+        let mut walker = cursor.walk_back(None).unwrap();
+        while let Some((key, _)) = walker.next().transpose().unwrap() {
+            if key == 3 {
+                walker.delete_current().unwrap();
+            }
+        }
+        assert_eq!(
+            cursor.walk(None).unwrap().map(|entry| entry.unwrap().0).collect::<Vec<_>>(),
+            vec![1, 5]
+        );
+
+        let mut cursor = tx.cursor_dup_write::<PlainStorageState>().unwrap();
+        let mut walker = cursor.walk_dup(Some(address), None).unwrap();
+        while let Some((_, entry)) = walker.next().transpose().unwrap() {
+            if entry.value != U256::from(2) {
+                walker.cursor.delete_current().unwrap();
+            }
+        }
+        assert_eq!(
+            cursor.walk(None).unwrap().collect::<Result<Vec<_>, _>>(),
+            Ok(vec![(address, storage_entry(2, 2))])
+        );
+    }
+
+    fn snapshot_isolation<DB: Database>(db: DB) {
+        let tx = db.tx_mut().unwrap();
+        tx.put::<CanonicalHeaders>(1, B256::with_last_byte(1)).unwrap();
+        tx.commit().unwrap();
+
+        let before = db.tx().unwrap();
+
+        let tx = db.tx_mut().unwrap();
+        tx.put::<CanonicalHeaders>(1, B256::with_last_byte(2)).unwrap();
+        tx.put::<CanonicalHeaders>(2, B256::with_last_byte(2)).unwrap();
+        // Uncommitted changes are only visible to the writing transaction.
+        assert_eq!(tx.get::<CanonicalHeaders>(2), Ok(Some(B256::with_last_byte(2))));
+        assert_eq!(db.tx().unwrap().get::<CanonicalHeaders>(2), Ok(None));
+        tx.commit().unwrap();
+
+        // Aborted changes are discarded.
+        let tx = db.tx_mut().unwrap();
+        tx.put::<CanonicalHeaders>(3, B256::with_last_byte(3)).unwrap();
+        tx.abort();
+
+        let after = db.tx().unwrap();
+        assert_eq!(before.get::<CanonicalHeaders>(1), Ok(Some(B256::with_last_byte(1))));
+        assert_eq!(before.entries::<CanonicalHeaders>(), Ok(1));
+        assert_eq!(after.get::<CanonicalHeaders>(1), Ok(Some(B256::with_last_byte(2))));
+        assert_eq!(after.entries::<CanonicalHeaders>(), Ok(2));
+        assert_eq!(after.get::<CanonicalHeaders>(3), Ok(None));
+    }
+
+    #[test]
+    fn single_writer() {
+        let db = MemDatabase::new();
+        let tx = db.tx_mut().unwrap();
+
+        let (sender, receiver) = mpsc::channel();
+        let handle = thread::spawn(move || {
+            let tx = db.tx_mut().unwrap();
+            sender.send(tx.get::<CanonicalHeaders>(1).unwrap()).unwrap();
+        });
+
+        // The second writer waits until the first one is closed.
+        assert!(receiver.recv_timeout(Duration::from_millis(100)).is_err());
+        tx.put::<CanonicalHeaders>(1, B256::ZERO).unwrap();
+        tx.commit().unwrap();
+
+        assert_eq!(receiver.recv().unwrap(), Some(B256::ZERO));
+        handle.join().unwrap();
+    }
+}
@@ -0,0 +1,129 @@
+//! Transaction of the in-memory database.
+
+use super::{
+    compress_value, cursor::Cursor, entries, is_dupsort, put_entry, TransactionKind, TxTables,
+    WriterGuard, RW,
+};
+use crate::DatabaseError;
+use reth_db_api::{
+    table::{Decompress, DupSort, Encode, Table, TableImporter},
+    transaction::{DbTx, DbTxMut},
+};
+use std::{fmt, marker::PhantomData};
+
+/// In-memory database transaction.
+///
+/// Read-only transactions see the database as of the moment they were opened. Changes made by a
+/// read-write transaction become visible to new transactions once it's committed, and are
+/// discarded if it's dropped without committing.
+pub struct Tx<K: TransactionKind> {
+    /// Tables visible to this transaction.
+    tables: TxTables,
+    /// Writer slot of the database, held by read-write transactions until they are closed.
+    writer: Option<WriterGuard>,
+    _kind: PhantomData<K>,
+}
+
+impl<K: TransactionKind> fmt::Debug for Tx<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Tx").field("read_only", &K::IS_READ_ONLY).finish_non_exhaustive()
+    }
+}
+
+impl<K: TransactionKind> Tx<K> {
+    pub(crate) const fn new(tables: TxTables, writer: Option<WriterGuard>) -> Self {
+        Self { tables, writer, _kind: PhantomData }
+    }
+
+    /// Creates a cursor over the table `T`.
+    fn new_cursor<T: Table>(&self) -> Cursor<K, T> {
+        Cursor::new(self.tables.clone())
+    }
+}
+
+impl<K: TransactionKind> DbTx for Tx<K> {
+    type Cursor<T: Table> = Cursor<K, T>;
+    type DupCursor<T: DupSort> = Cursor<K, T>;
+
+    fn get<T: Table>(&self, key: T::Key) -> Result<Option<T::Value>, DatabaseError> {
+        let key = key.encode();
+        self.tables
+            .read(T::NAME, |table| {
+                table.get(key.as_ref()).and_then(|values| values.first()).cloned()
+            })
+            .map(T::Value::decompress_owned)
+            .transpose()
+    }
+
+    fn commit(self) -> Result<bool, DatabaseError> {
+        if let Some(writer) = self.writer {
+            writer.commit(self.tables.snapshot());
+        }
+        Ok(false)
+    }
+
+    fn abort(self) {}
+
+    fn cursor_read<T: Table>(&self) -> Result<Self::Cursor<T>, DatabaseError> {
+        Ok(self.new_cursor())
+    }
+
+    fn cursor_dup_read<T: DupSort>(&self) -> Result<Self::DupCursor<T>, DatabaseError> {
+        Ok(self.new_cursor())
+    }
+
+    fn entries<T: Table>(&self) -> Result<usize, DatabaseError> {
+        Ok(self.tables.read(T::NAME, entries))
+    }
+
+    /// Read transactions of the in-memory database never time out.
+    fn disable_long_read_transaction_safety(&mut self) {}
+}
+
+impl DbTxMut for Tx<RW> {
+    type CursorMut<T: Table> = Cursor<RW, T>;
+    type DupCursorMut<T: DupSort> = Cursor<RW, T>;
+
+    fn put<T: Table>(&self, key: T::Key, value: T::Value) -> Result<(), DatabaseError> {
+        let key = key.encode().into();
+        let value = compress_value(value);
+        self.tables.write(T::NAME, |table| put_entry(table, is_dupsort(T::NAME), key, value));
+        Ok(())
+    }
+
+    fn delete<T: Table>(
+        &self,
+        key: T::Key,
+        value: Option<T::Value>,
+    ) -> Result<bool, DatabaseError> {
+        let key = key.encode();
+        let value = value.map(compress_value);
+
+        Ok(self.tables.write(T::NAME, |table| match value {
+            Some(value) => {
+                let Some(values) = table.get_mut(key.as_ref()) else { return false };
+                let removed = values.remove(&value);
+                if values.is_empty() {
+                    table.remove(key.as_ref());
+                }
+                removed
+            }
+            None => table.remove(key.as_ref()).is_some(),
+        }))
+    }
+
+    fn clear<T: Table>(&self) -> Result<(), DatabaseError> {
+        self.tables.clear(T::NAME);
+        Ok(())
+    }
+
+    fn cursor_write<T: Table>(&self) -> Result<Self::CursorMut<T>, DatabaseError> {
+        Ok(self.new_cursor())
+    }
+
+    fn cursor_dup_write<T: DupSort>(&self) -> Result<Self::DupCursorMut<T>, DatabaseError> {
+        Ok(self.new_cursor())
+    }
+}
+
+impl TableImporter for Tx<RW> {}
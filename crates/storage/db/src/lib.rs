@@ -17,6 +17,7 @@
 
 mod implementation;
 pub mod lockfile;
+pub mod mem;
 #[cfg(feature = "mdbx")]
 mod metrics;
 pub mod static_file;
@@ -34,7 +35,10 @@ pub use tables::*;
 pub use utils::is_database_empty;
 
 #[cfg(feature = "mdbx")]
-pub use mdbx::{create_db, init_db, open_db, open_db_read_only, DatabaseEnv, DatabaseEnvKind};
+pub use mdbx::{
+    create_db, init_db, open_db, open_db_for_migration, open_db_read_only, DatabaseEnv,
+    DatabaseEnvKind,
+};
 
 pub use reth_db_api::*;
 
@@ -42,7 +46,7 @@ pub use reth_db_api::*;
 #[cfg(any(test, feature = "test-utils"))]
 pub mod test_utils {
     use super::*;
-    use crate::mdbx::DatabaseArguments;
+    use crate::{mdbx::DatabaseArguments, mem::MemDatabase};
     use reth_db_api::{
         database::Database,
         database_metrics::{DatabaseMetadata, DatabaseMetadataValue, DatabaseMetrics},
@@ -152,6 +156,11 @@ pub mod test_utils {
         Arc::new(TempDatabase { db: Some(db), path })
     }
 
+    /// Create in-memory read/write database for testing
+    pub fn create_test_mem_db() -> Arc<MemDatabase> {
+        Arc::new(MemDatabase::new())
+    }
+
     /// Create read/write database for testing
     pub fn create_test_rw_db_with_path<P: AsRef<Path>>(path: P) -> Arc<TempDatabase<DatabaseEnv>> {
         let path = path.as_ref().to_path_buf();
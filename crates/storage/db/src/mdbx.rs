@@ -52,3 +52,13 @@ pub fn open_db(path: &Path, args: DatabaseArguments) -> eyre::Result<DatabaseEnv
     db.record_client_version(args.client_version().clone())?;
     Ok(db)
 }
+
+/// Opens up an existing database without checking its version, and creates the tables it's
+/// missing. Read/Write mode.
+///
+/// Used to migrate databases written by older versions, which lack the tables added since.
+pub fn open_db_for_migration(path: &Path, args: DatabaseArguments) -> eyre::Result<DatabaseEnv> {
+    let db = open_db(path, args)?;
+    db.create_tables()?;
+    Ok(db)
+}
@@ -0,0 +1,4 @@
+//! Pure-Rust in-memory database, mostly useful for tests and short-lived nodes that don't need to
+//! persist anything to disk.
+
+pub use crate::implementation::mem::*;
@@ -16,7 +16,6 @@ pub mod codecs;
 mod raw;
 pub use raw::{RawDupSort, RawKey, RawTable, RawValue, TableRawRow};
 
-#[cfg(feature = "mdbx")]
 pub(crate) mod utils;
 
 use reth_db_api::{
@@ -409,6 +408,26 @@ tables! {
 
     /// Stores generic chain state info, like the last finalized block.
     table ChainState<Key = ChainStateKey, Value = BlockNumber>;
+
+    /// Stores pointers to the blocks in which an address appears as a transaction sender,
+    /// transaction recipient or in one of the call frames of a transaction, e.g. as the target
+    /// of an internal call or as a log emitter.
+    ///
+    /// Populated by the optional `IndexAddressAppearances` stage and sharded the same way as
+    /// [`AccountsHistory`]. Used to serve the `ots_searchTransactionsBefore` and
+    /// `ots_searchTransactionsAfter` RPC methods.
+    table AddressAppearances<Key = ShardedKey<Address>, Value = BlockNumberList>;
+
+    /// Stores the addresses that appear in each transaction, as its sender, its recipient or in
+    /// one of its call frames.
+    ///
+    /// Populated by the optional `IndexAddressAppearances` stage alongside
+    /// [`AddressAppearances`], of which it's the per-transaction reverse index.
+    table TransactionAddressAppearances<Key = TxNumber, Value = Address, SubKey = Address>;
+
+    /// Stores the checkpoint of each unfinished database migration, keyed by the database version
+    /// the migration upgrades to.
+    table MigrationCheckpoints<Key = u64, Value = u64>;
 }
 
 /// Keys for the `ChainState` table.
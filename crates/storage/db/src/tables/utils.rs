@@ -26,6 +26,7 @@ where
 }
 
 /// Helper function to decode only a value from a `(key, value)` pair.
+#[cfg(feature = "mdbx")]
 pub(crate) fn decode_value<'a, T>(
     kv: (Cow<'a, [u8]>, Cow<'a, [u8]>),
 ) -> Result<T::Value, DatabaseError>
@@ -39,6 +40,7 @@ where
 }
 
 /// Helper function to decode a value. It can be a key or subkey.
+#[cfg(feature = "mdbx")]
 pub(crate) fn decode_one<T>(value: Cow<'_, [u8]>) -> Result<T::Value, DatabaseError>
 where
     T: Table,
@@ -9,7 +9,7 @@ use std::{
 pub const DB_VERSION_FILE_NAME: &str = "database.version";
 /// The version of the database stored in the [`DB_VERSION_FILE_NAME`] file in the same directory as
 /// database.
-pub const DB_VERSION: u64 = 2;
+pub const DB_VERSION: u64 = 3;
 
 /// Error when checking a database version using [`check_db_version_file`]
 #[derive(thiserror::Error, Debug)]
@@ -25,7 +25,8 @@ pub enum DatabaseVersionError {
     /// Your database version is incompatible with the latest database version.
     #[error(
         "breaking database change detected: your database version (v{version}) \
-         is incompatible with the latest database version (v{DB_VERSION})"
+         is incompatible with the latest database version (v{DB_VERSION}), \
+         run `reth db migrate` to migrate it"
     )]
     VersionMismatch {
         /// The detected version in the database.
@@ -75,7 +76,14 @@ pub fn get_db_version<P: AsRef<Path>>(db_path: P) -> Result<u64, DatabaseVersion
 /// This function will create a file if it does not exist,
 /// and will entirely replace its contents if it does.
 pub fn create_db_version_file<P: AsRef<Path>>(db_path: P) -> io::Result<()> {
-    fs::write(db_version_file_path(db_path), DB_VERSION.to_string())
+    write_db_version_file(db_path, DB_VERSION)
+}
+
+/// Writes the given version to the database version file with [`DB_VERSION_FILE_NAME`] name.
+///
+/// Used by migrations to record each version they upgrade the database to.
+pub fn write_db_version_file<P: AsRef<Path>>(db_path: P, version: u64) -> io::Result<()> {
+    fs::write(db_version_file_path(db_path), version.to_string())
 }
 
 /// Returns a database version file path.
@@ -108,6 +108,9 @@ pub enum ProviderError {
     /// State is not available for the given block number because it is pruned.
     #[error("state at block #{0} is pruned")]
     StateAtBlockPruned(BlockNumber),
+    /// Changesets are not available for the given block number because they are pruned.
+    #[error("changesets at block #{0} are pruned")]
+    ChangeSetsPruned(BlockNumber),
     /// Provider does not support this particular request.
     #[error("this provider does not support this request")]
     UnsupportedProvider,
@@ -7,12 +7,12 @@ use std::{
     sync::Arc,
 };
 use tracing::*;
-use zstd::bulk::Compressor;
+use zstd::{bulk::Compressor, stream::raw::CParameter};
 pub use zstd::{bulk::Decompressor, dict::DecoderDictionary};
 
 type RawDictionary = Vec<u8>;
 
-#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ZstdState {
     #[default]
     PendingDictionary,
@@ -20,7 +20,7 @@ pub enum ZstdState {
 }
 
 #[cfg_attr(test, derive(PartialEq))]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 /// Zstd compression structure. Supports a compression dictionary per column.
 pub struct Zstd {
     /// State. Should be ready before compressing.
@@ -67,7 +67,7 @@ impl Zstd {
     }
 
     /// If using dictionaries, creates a list of [`Compressor`].
-    pub fn compressors(&self) -> Result<Option<Vec<Compressor<'_>>>, NippyJarError> {
+    pub fn compressors(&self) -> Result<Option<Vec<Compressor<'static>>>, NippyJarError> {
         match self.state {
             ZstdState::PendingDictionary => Err(NippyJarError::CompressorNotReady),
             ZstdState::Ready => {
@@ -77,7 +77,7 @@ impl Zstd {
 
                 if let Some(dictionaries) = &self.dictionaries {
                     debug!(target: "nippy-jar", count=?dictionaries.len(), "Generating ZSTD compressor dictionaries.");
-                    return Ok(Some(dictionaries.compressors()?))
+                    return Ok(Some(dictionaries.compressors(self.level)?))
                 }
                 Ok(None)
             }
@@ -185,8 +185,7 @@ impl Compression for Zstd {
         matches!(self.state, ZstdState::Ready)
     }
 
-    #[cfg(test)]
-    /// If using it with dictionaries, prepares a dictionary for each column.
+    /// If using it with dictionaries, trains a dictionary for each column from the given samples.
     fn prepare_compression(
         &mut self,
         columns: Vec<impl IntoIterator<Item = Vec<u8>>>,
@@ -214,7 +213,7 @@ impl Compression for Zstd {
             // ZSTD requires all training data to be continuous in memory, alongside the size of
             // each entry
             let mut sizes = vec![];
-            let data: Vec<_> = column
+            let mut data: Vec<_> = column
                 .into_iter()
                 .flat_map(|data| {
                     sizes.push(data.len());
@@ -222,7 +221,16 @@ impl Compression for Zstd {
                 })
                 .collect();
 
-            dictionaries.push(zstd::dict::from_continuous(&data, &sizes, self.max_dict_size)?);
+            let dictionary = match zstd::dict::from_continuous(&data, &sizes, self.max_dict_size) {
+                Ok(dictionary) => dictionary,
+                Err(err) => {
+                    // Training fails if there are too few or too similar samples, in which case
+                    // the most recent samples are used as a raw content dictionary instead.
+                    debug!(target: "nippy-jar", %err, "Failed to train ZSTD dictionary, using raw samples.");
+                    data.split_off(data.len().saturating_sub(self.max_dict_size))
+                }
+            };
+            dictionaries.push(dictionary);
         }
 
         debug_assert_eq!(dictionaries.len(), self.columns);
@@ -273,7 +281,6 @@ impl<'a> std::fmt::Debug for ZstdDictionaries<'a> {
 }
 
 impl<'a> ZstdDictionaries<'a> {
-    #[cfg(test)]
     /// Creates [`ZstdDictionaries`].
     pub(crate) fn new(raw: Vec<RawDictionary>) -> Self {
         Self(raw.into_iter().map(ZstdDictionary::Raw).collect())
@@ -283,7 +290,10 @@ impl<'a> ZstdDictionaries<'a> {
     pub(crate) fn load(raw: Vec<RawDictionary>) -> Self {
         Self(
             raw.into_iter()
-                .map(|dict| ZstdDictionary::Loaded(DecoderDictionary::copy(&dict)))
+                .map(|dict| {
+                    let loaded = DecoderDictionary::copy(&dict);
+                    ZstdDictionary::Loaded(dict, loaded)
+                })
                 .collect(),
         )
     }
@@ -300,33 +310,37 @@ impl<'a> ZstdDictionaries<'a> {
             .collect::<Result<Vec<_>, _>>()?)
     }
 
-    /// Creates a list of compressors from a list of [`ZstdDictionary::Raw`].
-    pub(crate) fn compressors(&self) -> Result<Vec<Compressor<'_>>, NippyJarError> {
-        Ok(self
-            .iter()
-            .flat_map(|dict| {
-                dict.raw()
-                    .ok_or(NippyJarError::CompressorNotAllowed)
-                    .map(|dict| Compressor::with_dictionary(0, dict))
+    /// Creates a list of compressors with the given compression level.
+    ///
+    /// The dictionary ID is omitted from the compressed values, since each column is always
+    /// decompressed with its own dictionary.
+    pub(crate) fn compressors(
+        &self,
+        level: i32,
+    ) -> Result<Vec<Compressor<'static>>, NippyJarError> {
+        self.iter()
+            .map(|dict| {
+                let mut compressor = Compressor::with_dictionary(level, dict.raw())?;
+                compressor.set_parameter(CParameter::DictIdFlag(false))?;
+                Ok(compressor)
             })
-            .collect::<Result<Vec<_>, _>>()?)
+            .collect()
     }
 }
 
-/// A Zstd dictionary. It's created and serialized with [`ZstdDictionary::Raw`], and deserialized as
-/// [`ZstdDictionary::Loaded`].
+/// A Zstd dictionary. It's created with [`ZstdDictionary::Raw`], and deserialized as
+/// [`ZstdDictionary::Loaded`], which keeps the raw dictionary so that the jar can still be
+/// appended to and serialized.
 pub(crate) enum ZstdDictionary<'a> {
-    #[allow(dead_code)]
     Raw(RawDictionary),
-    Loaded(DecoderDictionary<'a>),
+    Loaded(RawDictionary, DecoderDictionary<'a>),
 }
 
 impl<'a> ZstdDictionary<'a> {
-    /// Returns a reference to the expected `RawDictionary`
-    pub(crate) const fn raw(&self) -> Option<&RawDictionary> {
+    /// Returns a reference to the `RawDictionary`
+    pub(crate) const fn raw(&self) -> &RawDictionary {
         match self {
-            ZstdDictionary::Raw(dict) => Some(dict),
-            ZstdDictionary::Loaded(_) => None,
+            ZstdDictionary::Raw(dict) | ZstdDictionary::Loaded(dict, _) => dict,
         }
     }
 
@@ -334,7 +348,7 @@ impl<'a> ZstdDictionary<'a> {
     pub(crate) const fn loaded(&self) -> Option<&DecoderDictionary<'_>> {
         match self {
             ZstdDictionary::Raw(_) => None,
-            ZstdDictionary::Loaded(dict) => Some(dict),
+            ZstdDictionary::Loaded(_, dict) => Some(dict),
         }
     }
 }
@@ -345,7 +359,8 @@ impl<'de, 'a> Deserialize<'de> for ZstdDictionary<'a> {
         D: Deserializer<'de>,
     {
         let dict = RawDictionary::deserialize(deserializer)?;
-        Ok(Self::Loaded(DecoderDictionary::copy(&dict)))
+        let loaded = DecoderDictionary::copy(&dict);
+        Ok(Self::Loaded(dict, loaded))
     }
 }
 
@@ -354,19 +369,13 @@ impl<'a> Serialize for ZstdDictionary<'a> {
     where
         S: Serializer,
     {
-        match self {
-            ZstdDictionary::Raw(r) => r.serialize(serializer),
-            ZstdDictionary::Loaded(_) => unreachable!(),
-        }
+        self.raw().serialize(serializer)
     }
 }
 
 #[cfg(test)]
 impl<'a> PartialEq for ZstdDictionary<'a> {
     fn eq(&self, other: &Self) -> bool {
-        if let (Self::Raw(a), Self::Raw(b)) = (self, &other) {
-            return a == b
-        }
-        unimplemented!("`DecoderDictionary` can't be compared. So comparison should be done after decompressing a value.");
+        self.raw() == other.raw()
     }
 }
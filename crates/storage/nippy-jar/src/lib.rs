@@ -17,7 +17,8 @@ use memmap2::Mmap;
 use serde::{Deserialize, Serialize};
 use std::{
     error::Error as StdError,
-    fs::{File, OpenOptions},
+    fs::{self, File, OpenOptions},
+    io,
     ops::Range,
     path::{Path, PathBuf},
 };
@@ -28,9 +29,7 @@ pub mod filter;
 use filter::{Cuckoo, InclusionFilter, InclusionFilters};
 
 pub mod compression;
-#[cfg(test)]
-use compression::Compression;
-use compression::Compressors;
+use compression::{Compression, Compressors};
 
 pub mod phf;
 pub use phf::PHFKey;
@@ -50,6 +49,13 @@ const NIPPY_JAR_VERSION: usize = 1;
 const INDEX_FILE_EXTENSION: &str = "idx";
 const OFFSETS_FILE_EXTENSION: &str = "off";
 const CONFIG_FILE_EXTENSION: &str = "conf";
+const CHECKSUMS_FILE_EXTENSION: &str = "crc";
+
+/// Size of the header of the checksums file, which holds the number of the first row with a
+/// checksum.
+const CHECKSUMS_HEADER_SIZE: u64 = 8;
+/// Size of the checksum of one row in bytes.
+const CHECKSUM_SIZE: u64 = 4;
 
 /// A [`RefRow`] is a list of column value slices pointing to either an internal buffer or a
 /// memory-mapped file.
@@ -190,6 +196,13 @@ impl<H: NippyJarHeader> NippyJar<H> {
         self
     }
 
+    /// Adds an already prepared compression, such as [`compression::Zstd`] with the dictionaries
+    /// trained for another jar.
+    pub fn with_compressor(mut self, compressor: Compressors) -> Self {
+        self.compressor = Some(compressor);
+        self
+    }
+
     /// Adds [`filter::Cuckoo`] filter.
     pub fn with_cuckoo_filter(mut self, max_capacity: usize) -> Self {
         self.filter = Some(InclusionFilters::Cuckoo(Cuckoo::new(max_capacity)));
@@ -288,13 +301,22 @@ impl<H: NippyJarHeader> NippyJar<H> {
         self.path.with_extension(CONFIG_FILE_EXTENSION)
     }
 
+    /// Returns the path for the checksums file
+    pub fn checksums_path(&self) -> PathBuf {
+        self.path.with_extension(CHECKSUMS_FILE_EXTENSION)
+    }
+
     /// Deletes from disk this [`NippyJar`] alongside every satellite file.
     pub fn delete(self) -> Result<(), NippyJarError> {
         // TODO(joshie): ensure consistency on unexpected shutdown
 
-        for path in
-            [self.data_path().into(), self.index_path(), self.offsets_path(), self.config_path()]
-        {
+        for path in [
+            self.data_path().into(),
+            self.index_path(),
+            self.offsets_path(),
+            self.config_path(),
+            self.checksums_path(),
+        ] {
             if path.exists() {
                 reth_fs_util::remove_file(path)?;
             }
@@ -308,6 +330,58 @@ impl<H: NippyJarHeader> NippyJar<H> {
         DataReader::new(self.data_path())
     }
 
+    /// Verifies the data of every row against the checksum recorded by [`NippyJarWriter`] when
+    /// the row was appended.
+    ///
+    /// Rows appended before the jar had a checksums file have no checksum and are not verified.
+    pub fn verify_checksums(&self) -> Result<ChecksumVerification, NippyJarError> {
+        let checksums = match fs::read(self.checksums_path()) {
+            Ok(checksums) if checksums.len() as u64 >= CHECKSUMS_HEADER_SIZE => checksums,
+            Ok(_) => return Ok(ChecksumVerification::default()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                return Ok(ChecksumVerification::default())
+            }
+            Err(err) => return Err(err.into()),
+        };
+        let (header, checksums) = checksums.split_at(CHECKSUMS_HEADER_SIZE as usize);
+        let first_row = u64::from_le_bytes(header.try_into().expect("qed")) as usize;
+        let checked = first_row.min(self.rows)..
+            (first_row + checksums.len() / CHECKSUM_SIZE as usize).min(self.rows);
+
+        let reader = self.open_data_reader()?;
+        let row_data = |row: usize| -> Option<&[u8]> {
+            let start = reader.offset(row * self.columns).ok()? as usize;
+            let end = reader.offset((row + 1) * self.columns).ok()? as usize;
+            (start <= end && end <= reader.size()).then(|| reader.data(start..end))
+        };
+
+        let corrupted = checked
+            .clone()
+            .zip(checksums.chunks_exact(CHECKSUM_SIZE as usize))
+            .filter(|(row, checksum)| {
+                let checksum = u32::from_le_bytes((*checksum).try_into().expect("qed"));
+                row_data(*row).map_or(true, |data| crc32fast::hash(data) != checksum)
+            })
+            .map(|(row, _)| row)
+            .collect();
+
+        Ok(ChecksumVerification { checked, corrupted })
+    }
+
+    /// If required, prepares any compression algorithm to an early pass of the data, such as
+    /// training the zstd dictionaries from a sample of the values of each column.
+    pub fn prepare_compression(
+        &mut self,
+        columns: Vec<impl IntoIterator<Item = Vec<u8>>>,
+    ) -> Result<(), NippyJarError> {
+        // Makes any necessary preparations for the compressors
+        if let Some(compression) = &mut self.compressor {
+            debug!(target: "nippy-jar", columns=columns.len(), "Preparing compression.");
+            compression.prepare_compression(columns)?;
+        }
+        Ok(())
+    }
+
     /// Writes all necessary configuration to file.
     fn freeze_config(&self) -> Result<(), NippyJarError> {
         // Atomic writes are hard: <https://github.com/paradigmxyz/reth/issues/8622>
@@ -358,19 +432,6 @@ impl<H: NippyJarHeader> PerfectHashingFunction for NippyJar<H> {
 
 #[cfg(test)]
 impl<H: NippyJarHeader> NippyJar<H> {
-    /// If required, prepares any compression algorithm to an early pass of the data.
-    pub fn prepare_compression(
-        &mut self,
-        columns: Vec<impl IntoIterator<Item = Vec<u8>>>,
-    ) -> Result<(), NippyJarError> {
-        // Makes any necessary preparations for the compressors
-        if let Some(compression) = &mut self.compressor {
-            debug!(target: "nippy-jar", columns=columns.len(), "Preparing compression.");
-            compression.prepare_compression(columns)?;
-        }
-        Ok(())
-    }
-
     /// Prepares beforehand the offsets index for querying rows based on `values` (eg. transaction
     /// hash). Expects `values` to be sorted in the same way as the data that is going to be
     /// later on inserted.
@@ -483,6 +544,15 @@ impl<H: NippyJarHeader> NippyJar<H> {
     }
 }
 
+/// Result of [`NippyJar::verify_checksums`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChecksumVerification {
+    /// Rows that have a checksum and were verified.
+    pub checked: Range<usize>,
+    /// Rows whose data or offsets don't match their checksum, in ascending order.
+    pub corrupted: Vec<usize>,
+}
+
 /// Manages the reading of static file data using memory-mapped files.
 ///
 /// Holds file and mmap descriptors of the data and offsets files of a `static_file`.
@@ -791,6 +861,41 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_zstd_with_dictionaries_append() {
+        let (col1, col2) = test_data(None);
+        let num_rows = col1.len() as u64;
+        let num_columns = 2;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        let mut nippy =
+            NippyJar::new_without_header(num_columns, file_path.path()).with_zstd(true, 5000);
+        nippy.prepare_compression(vec![col1.clone(), col2.clone()]).unwrap();
+        nippy.freeze(vec![clone_with_result(&col1), clone_with_result(&col2)], num_rows).unwrap();
+
+        // Appending to a jar with loaded dictionaries compresses with the same dictionaries
+        let (col3, col4) = test_data(None);
+        let loaded_nippy = NippyJar::load_without_header(file_path.path()).unwrap();
+        let mut writer =
+            NippyJarWriter::new(loaded_nippy, ConsistencyFailStrategy::ThrowError).unwrap();
+        writer
+            .append_rows(vec![clone_with_result(&col3), clone_with_result(&col4)], num_rows)
+            .unwrap();
+        writer.commit().unwrap();
+
+        let loaded_nippy = NippyJar::load_without_header(file_path.path()).unwrap();
+        assert_eq!(loaded_nippy.rows, 2 * num_rows as usize);
+        assert!(loaded_nippy.verify_checksums().unwrap().corrupted.is_empty());
+
+        let mut cursor = NippyJarCursor::new(&loaded_nippy).unwrap();
+        for (row_index, (value1, value2)) in
+            col1.iter().chain(&col3).zip(col2.iter().chain(&col4)).enumerate()
+        {
+            let row = cursor.row_by_number(row_index).unwrap().unwrap();
+            assert_eq!((row[0], row[1]), (value1.as_slice(), value2.as_slice()));
+        }
+    }
+
     #[test]
     fn test_lz4() {
         let (col1, col2) = test_data(None);
@@ -1101,6 +1206,60 @@ mod tests {
         test_append_consistency_partial_commit(file_path.path(), &col1, &col2);
     }
 
+    #[test]
+    fn test_checksums() {
+        let (col1, col2) = test_data(None);
+        let num_columns = 2;
+        let file_path = tempfile::NamedTempFile::new().unwrap();
+
+        let mut writer = NippyJarWriter::new(
+            NippyJar::new_without_header(num_columns, file_path.path()).with_lz4(),
+            ConsistencyFailStrategy::Heal,
+        )
+        .unwrap();
+        writer.append_rows(vec![clone_with_result(&col1), clone_with_result(&col2)], 10).unwrap();
+        writer.commit().unwrap();
+        let nippy = writer.into_jar();
+        assert_eq!(
+            nippy.verify_checksums().unwrap(),
+            ChecksumVerification { checked: 0..10, corrupted: vec![] }
+        );
+
+        // Flip a byte of the fourth row
+        let offset = nippy.open_data_reader().unwrap().offset(3 * num_columns).unwrap();
+        let mut data = fs::read(nippy.data_path()).unwrap();
+        data[offset as usize] ^= 0xff;
+        fs::write(nippy.data_path(), data).unwrap();
+        assert_eq!(
+            nippy.verify_checksums().unwrap(),
+            ChecksumVerification { checked: 0..10, corrupted: vec![3] }
+        );
+
+        // Pruning the corrupted row drops its checksum
+        let mut writer = NippyJarWriter::new(nippy, ConsistencyFailStrategy::Heal).unwrap();
+        writer.prune_rows(7).unwrap();
+        let nippy = writer.into_jar();
+        assert_eq!(
+            nippy.verify_checksums().unwrap(),
+            ChecksumVerification { checked: 0..3, corrupted: vec![] }
+        );
+        assert_eq!(
+            fs::metadata(nippy.checksums_path()).unwrap().len(),
+            CHECKSUMS_HEADER_SIZE + 3 * CHECKSUM_SIZE
+        );
+
+        // A jar without checksums gets them for the rows appended from now on
+        fs::remove_file(nippy.checksums_path()).unwrap();
+        assert_eq!(nippy.verify_checksums().unwrap(), ChecksumVerification::default());
+        let mut writer = NippyJarWriter::new(nippy, ConsistencyFailStrategy::Heal).unwrap();
+        writer.append_rows(vec![clone_with_result(&col1), clone_with_result(&col2)], 2).unwrap();
+        writer.commit().unwrap();
+        assert_eq!(
+            writer.into_jar().verify_checksums().unwrap(),
+            ChecksumVerification { checked: 3..5, corrupted: vec![] }
+        );
+    }
+
     #[test]
     fn test_pruner() {
         let (col1, col2) = test_data(None);
@@ -1,10 +1,15 @@
-use crate::{compression::Compression, ColumnResult, NippyJar, NippyJarError, NippyJarHeader};
+use crate::{
+    compression::{Compression, Compressors},
+    ColumnResult, NippyJar, NippyJarError, NippyJarHeader, CHECKSUMS_HEADER_SIZE, CHECKSUM_SIZE,
+};
 use std::{
     cmp::Ordering,
+    fmt,
     fs::{File, OpenOptions},
     io::{BufWriter, Read, Seek, SeekFrom, Write},
     path::Path,
 };
+use zstd::bulk::Compressor;
 
 /// Size of one offset in bytes.
 const OFFSET_SIZE_BYTES: u8 = 8;
@@ -22,6 +27,11 @@ const OFFSET_SIZE_BYTES: u8 = 8;
 ///
 /// ## Data file layout
 /// The data file is represented just as a sequence of bytes of data without any delimiters
+///
+/// ## Checksums file layout
+/// The first 8 bytes are the number of the first row with a checksum, which is `0` unless the jar
+/// was created before it had a checksums file. Then, the file contains one CRC32 checksum of the
+/// stored data of each row, 4 bytes each.
 #[derive(Debug)]
 pub struct NippyJarWriter<H: NippyJarHeader = ()> {
     /// Associated [`NippyJar`], containing all necessary configurations for data
@@ -31,12 +41,20 @@ pub struct NippyJarWriter<H: NippyJarHeader = ()> {
     data_file: BufWriter<File>,
     /// File handle to where the offsets are stored.
     offsets_file: BufWriter<File>,
+    /// File handle to where the row checksums are stored.
+    checksums_file: File,
     /// Temporary buffer to reuse when compressing data.
     tmp_buf: Vec<u8>,
+    /// Zstd compressors with the dictionary of each column, if the jar uses dictionaries.
+    dictionary_compressors: DictionaryCompressors,
     /// Used to find the maximum uncompressed size of a row in a jar.
     uncompressed_row_size: usize,
     /// Partial offset list which hasn't been flushed to disk.
     offsets: Vec<u64>,
+    /// Checksums of the rows which haven't been flushed to disk.
+    checksums: Vec<u32>,
+    /// Checksum of the row being written.
+    row_hasher: crc32fast::Hasher,
     /// Column where writer is going to write next.
     column: usize,
     /// Whether the writer has changed data that needs to be committed.
@@ -54,6 +72,11 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
     ) -> Result<Self, NippyJarError> {
         let (data_file, offsets_file, is_created) =
             Self::create_or_open_files(jar.data_path(), &jar.offsets_path())?;
+        let checksums_file = Self::create_or_open_checksums_file(&jar)?;
+        let dictionary_compressors = match jar.compressor() {
+            Some(Compressors::Zstd(zstd)) => zstd.compressors()?.unwrap_or_default(),
+            _ => Vec::new(),
+        };
 
         // Makes sure we don't have dangling data and offset files
         jar.freeze_config()?;
@@ -62,9 +85,13 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
             jar,
             data_file: BufWriter::new(data_file),
             offsets_file: BufWriter::new(offsets_file),
+            checksums_file,
             tmp_buf: Vec::with_capacity(1_000_000),
+            dictionary_compressors: DictionaryCompressors(dictionary_compressors),
             uncompressed_row_size: 0,
             offsets: Vec::with_capacity(1_000_000),
+            checksums: Vec::new(),
+            row_hasher: crc32fast::Hasher::new(),
             column: 0,
             dirty: false,
         };
@@ -147,6 +174,25 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         Ok((data_file, offsets_file, is_created))
     }
 
+    /// Opens the checksums file of the jar. If it doesn't exist, it's created to cover the rows
+    /// appended from now on.
+    fn create_or_open_checksums_file(jar: &NippyJar<H>) -> Result<File, NippyJarError> {
+        let path = jar.checksums_path();
+        let is_created = !path.exists();
+
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(is_created)
+            .truncate(false)
+            .open(path)?;
+        if is_created {
+            file.write_all(&(jar.rows as u64).to_le_bytes())?;
+        }
+
+        Ok(file)
+    }
+
     /// Performs consistency checks on the [`NippyJar`] file and might self-heal or throw an error
     /// according to [`ConsistencyFailStrategy`].
     /// * Is the offsets file size expected?
@@ -242,12 +288,60 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
             Ordering::Equal => {}
         }
 
+        // Checksums were flushed, but the configuration wasn't
+        if check_mode.should_err() && self.checksummed_rows()?.end > self.jar.rows as u64 {
+            return Err(NippyJarError::InconsistentState)
+        }
+        if check_mode.should_heal() {
+            self.truncate_checksums()?;
+        }
+
         self.offsets_file.seek(SeekFrom::End(0))?;
         self.data_file.seek(SeekFrom::End(0))?;
 
         Ok(())
     }
 
+    /// Returns the rows that have a checksum on disk.
+    fn checksummed_rows(&self) -> Result<std::ops::Range<u64>, NippyJarError> {
+        let len = self.checksums_file.metadata()?.len();
+        if len < CHECKSUMS_HEADER_SIZE {
+            return Ok(self.jar.rows as u64..self.jar.rows as u64)
+        }
+
+        let mut header = [0; CHECKSUMS_HEADER_SIZE as usize];
+        (&self.checksums_file).seek(SeekFrom::Start(0))?;
+        (&self.checksums_file).read_exact(&mut header)?;
+        let first_row = u64::from_le_bytes(header);
+
+        Ok(first_row..first_row + (len - CHECKSUMS_HEADER_SIZE) / CHECKSUM_SIZE)
+    }
+
+    /// Drops the checksums, on disk or not yet flushed, of rows the jar no longer has.
+    ///
+    /// If the jar has rows before the checksummed ones, or rows that are missing a checksum, the
+    /// checksums are reset to cover the rows appended from now on.
+    fn truncate_checksums(&mut self) -> Result<(), NippyJarError> {
+        let rows = self.jar.rows as u64;
+        let on_disk = self.checksummed_rows()?;
+
+        if on_disk.start <= rows && rows <= on_disk.end {
+            self.checksums.clear();
+            self.checksums_file
+                .set_len(CHECKSUMS_HEADER_SIZE + (rows - on_disk.start) * CHECKSUM_SIZE)?;
+        } else if on_disk.start <= rows && rows <= on_disk.end + self.checksums.len() as u64 {
+            self.checksums.truncate((rows - on_disk.end) as usize);
+        } else {
+            self.checksums.clear();
+            self.checksums_file.set_len(0)?;
+            self.checksums_file.seek(SeekFrom::Start(0))?;
+            self.checksums_file.write_all(&rows.to_le_bytes())?;
+        }
+        self.checksums_file.sync_all()?;
+
+        Ok(())
+    }
+
     /// Appends rows to data file.  `fn commit()` should be called to flush offsets and config to
     /// disk.
     ///
@@ -315,13 +409,23 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
     /// Writes column to data file. If it's the last column of the row, call `finalize_row()`
     fn write_column(&mut self, value: &[u8]) -> Result<usize, NippyJarError> {
         self.uncompressed_row_size += value.len();
-        let len = if let Some(compression) = &self.jar.compressor {
+        let len = if let Some(compressor) = self.dictionary_compressors.0.get_mut(self.column) {
+            // The previous columns of the row were already written, so the buffer can be reused.
+            self.tmp_buf.clear();
+            self.tmp_buf.reserve(zstd::zstd_safe::compress_bound(value.len()));
+            let len = compressor.compress_to_buffer(value, &mut self.tmp_buf)?;
+            self.data_file.write_all(&self.tmp_buf)?;
+            self.row_hasher.update(&self.tmp_buf);
+            len
+        } else if let Some(compression) = &self.jar.compressor {
             let before = self.tmp_buf.len();
             let len = compression.compress_to(value, &mut self.tmp_buf)?;
             self.data_file.write_all(&self.tmp_buf[before..before + len])?;
+            self.row_hasher.update(&self.tmp_buf[before..before + len]);
             len
         } else {
             self.data_file.write_all(value)?;
+            self.row_hasher.update(value);
             value.len()
         };
 
@@ -412,6 +516,7 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         if self.jar.rows == 0 {
             self.jar.max_row_size = 0;
         }
+        self.truncate_checksums()?;
         self.jar.freeze_config()?;
 
         Ok(())
@@ -422,6 +527,7 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
     fn finalize_row(&mut self) {
         self.jar.max_row_size = self.jar.max_row_size.max(self.uncompressed_row_size);
         self.jar.rows += 1;
+        self.checksums.push(std::mem::take(&mut self.row_hasher).finalize());
 
         self.tmp_buf.clear();
         self.uncompressed_row_size = 0;
@@ -434,6 +540,8 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         self.data_file.get_ref().sync_all()?;
 
         self.commit_offsets()?;
+        self.commit_checksums()?;
+        self.checksums_file.sync_all()?;
 
         // Flushes `max_row_size` and total `rows` to disk.
         self.jar.freeze_config()?;
@@ -447,6 +555,7 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         self.data_file.flush()?;
 
         self.commit_offsets_without_sync_all()?;
+        self.commit_checksums()?;
 
         // Flushes `max_row_size` and total `rows` to disk.
         self.jar.freeze_config()?;
@@ -500,6 +609,17 @@ impl<H: NippyJarHeader> NippyJarWriter<H> {
         Ok(())
     }
 
+    /// Appends the checksums of the new rows to disk.
+    ///
+    /// CAUTION: Does not call `sync_all` on the checksums file.
+    fn commit_checksums(&mut self) -> Result<(), NippyJarError> {
+        let buf = self.checksums.drain(..).flat_map(u32::to_le_bytes).collect::<Vec<_>>();
+        self.checksums_file.seek(SeekFrom::End(0))?;
+        self.checksums_file.write_all(&buf)?;
+
+        Ok(())
+    }
+
     #[cfg(test)]
     pub const fn max_row_size(&self) -> usize {
         self.jar.max_row_size
@@ -561,3 +681,12 @@ impl ConsistencyFailStrategy {
         matches!(self, Self::ThrowError)
     }
 }
+
+/// Zstd compressors of a [`NippyJarWriter`], one per column.
+struct DictionaryCompressors(Vec<Compressor<'static>>);
+
+impl fmt::Debug for DictionaryCompressors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DictionaryCompressors").field("num", &self.0.len()).finish_non_exhaustive()
+    }
+}
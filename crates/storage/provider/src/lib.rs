@@ -40,8 +40,9 @@ pub use bundle_state::{OriginalValuesKnown, StateChanges, StateReverts};
 pub mod writer;
 
 pub use reth_chain_state::{
-    CanonStateNotification, CanonStateNotificationSender, CanonStateNotificationStream,
-    CanonStateNotifications, CanonStateSubscriptions,
+    BadBlockStore, CanonStateNotification, CanonStateNotificationSender,
+    CanonStateNotificationStream, CanonStateNotifications, CanonStateSubscriptions,
+    DEFAULT_MAX_BAD_BLOCKS,
 };
 
 pub(crate) fn to_range<R: std::ops::RangeBounds<u64>>(bounds: R) -> std::ops::Range<u64> {
@@ -1,12 +1,12 @@
 use crate::{
-    providers::BundleStateProvider, AccountReader, BlockHashReader, BlockIdReader, BlockNumReader,
-    BlockReader, BlockReaderIdExt, BlockSource, BlockchainTreePendingStateProvider,
-    CanonChainTracker, CanonStateNotifications, CanonStateSubscriptions, ChainSpecProvider,
-    ChangeSetReader, DatabaseProviderFactory, DatabaseProviderRO, EvmEnvProvider,
-    FullExecutionDataProvider, HeaderProvider, ProviderError, ProviderFactory,
-    PruneCheckpointReader, ReceiptProvider, ReceiptProviderIdExt, RequestsProvider,
-    StageCheckpointReader, StateProviderBox, StateProviderFactory, StaticFileProviderFactory,
-    TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    providers::BundleStateProvider, AccountReader, AddressAppearancesReader, BlockHashReader,
+    BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt, BlockSource,
+    BlockchainTreePendingStateProvider, CanonChainTracker, CanonStateNotifications,
+    CanonStateSubscriptions, ChainSpecProvider, ChangeSetReader, DatabaseProviderFactory,
+    DatabaseProviderRO, EvmEnvProvider, FullExecutionDataProvider, HeaderProvider, ProviderError,
+    ProviderFactory, PruneCheckpointReader, ReceiptProvider, ReceiptProviderIdExt,
+    RequestsProvider, StageCheckpointReader, StateProviderBox, StateProviderFactory,
+    StaticFileProviderFactory, TransactionVariant, TransactionsProvider, WithdrawalsProvider,
 };
 use alloy_rpc_types_engine::ForkchoiceState;
 use reth_chain_state::CanonicalInMemoryState;
@@ -27,6 +27,7 @@ use reth_stages_types::{StageCheckpoint, StageId};
 use reth_storage_errors::provider::ProviderResult;
 use revm::primitives::{BlockEnv, CfgEnvWithHandlerCfg};
 use std::{
+    collections::BTreeSet,
     ops::{RangeBounds, RangeInclusive},
     sync::Arc,
     time::Instant,
@@ -476,6 +477,41 @@ where
     }
 }
 
+impl<DB> AddressAppearancesReader for BlockchainProvider2<DB>
+where
+    DB: Database,
+{
+    fn address_appearances_checkpoint(&self) -> ProviderResult<Option<BlockNumber>> {
+        self.database.provider()?.address_appearances_checkpoint()
+    }
+
+    fn address_appearances_before(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.database.provider()?.address_appearances_before(address, block_number, limit)
+    }
+
+    fn address_appearances_after(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.database.provider()?.address_appearances_after(address, block_number, limit)
+    }
+
+    fn address_appearances_in_block(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<usize>> {
+        self.database.provider()?.address_appearances_in_block(address, block_number)
+    }
+}
+
 impl<DB> StageCheckpointReader for BlockchainProvider2<DB>
 where
     DB: Database,
@@ -799,6 +835,40 @@ where
     ) -> ProviderResult<Vec<AccountBeforeTx>> {
         self.database.provider()?.account_block_changeset(block_number)
     }
+
+    fn changed_addresses_with_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeSet<Address>> {
+        let mut addresses = BTreeSet::new();
+
+        // the most recent blocks may not be persisted yet
+        let mut persisted_end = Some(*range.end());
+        for block_number in range.clone().rev() {
+            let Some(block_state) = self.canonical_in_memory_state.state_by_number(block_number)
+            else {
+                break
+            };
+            addresses.extend(
+                block_state
+                    .block()
+                    .execution_outcome()
+                    .bundle_accounts_iter()
+                    .map(|(address, _)| address),
+            );
+            persisted_end = block_number.checked_sub(1);
+        }
+
+        if let Some(persisted_end) = persisted_end.filter(|end| end >= range.start()) {
+            addresses.extend(
+                self.database
+                    .provider()?
+                    .changed_addresses_with_range(*range.start()..=persisted_end)?,
+            );
+        }
+
+        Ok(addresses)
+    }
 }
 
 impl<DB> AccountReader for BlockchainProvider2<DB>
@@ -1,10 +1,10 @@
 use crate::{
     AccountReader, BlockHashReader, ExecutionDataProvider, StateProvider, StateRootProvider,
 };
-use reth_primitives::{Account, Address, BlockNumber, Bytecode, B256};
-use reth_storage_api::StateProofProvider;
+use reth_primitives::{Account, Address, BlockNumber, Bytecode, B256, U256};
+use reth_storage_api::{HashedStateRangeProvider, StateProofProvider};
 use reth_storage_errors::provider::ProviderResult;
-use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState};
+use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState, HashedRange};
 use revm::db::BundleState;
 
 /// A state provider that resolves to data from either a wrapped [`crate::ExecutionOutcome`]
@@ -114,6 +114,35 @@ impl<SP: StateProvider, EDP: ExecutionDataProvider> StateProofProvider
     }
 }
 
+impl<SP: StateProvider, EDP: ExecutionDataProvider> HashedStateRangeProvider
+    for BundleStateProvider<SP, EDP>
+{
+    fn hashed_account_range(
+        &self,
+        hashed_state: &HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedRange<Account>> {
+        let bundle_state = self.block_execution_data_provider.execution_outcome().state();
+        let mut state = HashedPostState::from_bundle_state(&bundle_state.state);
+        state.extend(hashed_state.clone());
+        self.state_provider.hashed_account_range(&state, start, limit)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_state: &HashedPostState,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedRange<U256>> {
+        let bundle_state = self.block_execution_data_provider.execution_outcome().state();
+        let mut state = HashedPostState::from_bundle_state(&bundle_state.state);
+        state.extend(hashed_state.clone());
+        self.state_provider.hashed_storage_range(&state, hashed_address, start, limit)
+    }
+}
+
 impl<SP: StateProvider, EDP: ExecutionDataProvider> StateProvider for BundleStateProvider<SP, EDP> {
     fn storage(
         &self,
@@ -2,10 +2,11 @@ use crate::{
     providers::{state::latest::LatestStateProvider, StaticFileProvider},
     to_range,
     traits::{BlockSource, ReceiptProvider},
-    BlockHashReader, BlockNumReader, BlockReader, ChainSpecProvider, DatabaseProviderFactory,
-    EvmEnvProvider, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, ProviderError,
-    PruneCheckpointReader, RequestsProvider, StageCheckpointReader, StateProviderBox,
-    StaticFileProviderFactory, TransactionVariant, TransactionsProvider, WithdrawalsProvider,
+    AddressAppearancesReader, BlockHashReader, BlockNumReader, BlockReader, ChainSpecProvider,
+    DatabaseProviderFactory, EvmEnvProvider, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider,
+    ProviderError, PruneCheckpointReader, RequestsProvider, StageCheckpointReader,
+    StateProviderBox, StaticFileProviderFactory, TransactionVariant, TransactionsProvider,
+    WithdrawalsProvider,
 };
 use reth_chainspec::{ChainInfo, ChainSpec};
 use reth_db::{init_db, mdbx::DatabaseArguments, DatabaseEnv};
@@ -504,6 +505,38 @@ where
     }
 }
 
+impl<DB: Database> AddressAppearancesReader for ProviderFactory<DB> {
+    fn address_appearances_checkpoint(&self) -> ProviderResult<Option<BlockNumber>> {
+        self.provider()?.address_appearances_checkpoint()
+    }
+
+    fn address_appearances_before(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.provider()?.address_appearances_before(address, block_number, limit)
+    }
+
+    fn address_appearances_after(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        self.provider()?.address_appearances_after(address, block_number, limit)
+    }
+
+    fn address_appearances_in_block(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<usize>> {
+        self.provider()?.address_appearances_in_block(address, block_number)
+    }
+}
+
 impl<DB: Database> StageCheckpointReader for ProviderFactory<DB> {
     fn get_stage_checkpoint(&self, id: StageId) -> ProviderResult<Option<StageCheckpoint>> {
         self.provider()?.get_stage_checkpoint(id)
@@ -605,9 +638,10 @@ impl<DB> Clone for ProviderFactory<DB> {
 mod tests {
     use super::*;
     use crate::{
-        providers::{StaticFileProvider, StaticFileWriter},
-        test_utils::create_test_provider_factory,
-        BlockHashReader, BlockNumReader, BlockWriter, HeaderSyncGapProvider, TransactionsProvider,
+        providers::{HistoricalStateProviderRef, StaticFileProvider, StaticFileWriter},
+        test_utils::{create_test_provider_factory, create_test_provider_factory_with_mem_db},
+        AccountReader, BlockHashReader, BlockNumReader, BlockWriter, ChangeSetReader,
+        HeaderSyncGapProvider, PruneCheckpointWriter, TransactionsProvider,
     };
     use alloy_rlp::Decodable;
     use assert_matches::assert_matches;
@@ -617,9 +651,20 @@ mod tests {
         mdbx::DatabaseArguments,
         tables,
         test_utils::{create_test_static_files_dir, ERROR_TEMPDIR},
+        BlockNumberList,
     };
-    use reth_primitives::{hex_literal::hex, SealedBlock, StaticFileSegment, TxNumber, B256, U256};
-    use reth_prune_types::{PruneMode, PruneModes};
+    use reth_db_api::{
+        models::{
+            AccountBeforeTx, BlockNumberAddress, ShardedKey, StorageBeforeTx,
+            StoredAccountChangeSet, StoredStorageChangeSet,
+        },
+        transaction::DbTxMut,
+    };
+    use reth_primitives::{
+        hex_literal::hex, Account, Address, SealedBlock, StaticFileSegment, StorageEntry, TxNumber,
+        B256, U256,
+    };
+    use reth_prune_types::PruneMode;
     use reth_storage_errors::provider::ProviderError;
     use reth_testing_utils::{
         generators,
@@ -709,6 +754,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn insert_block_with_mem_db() {
+        let factory = create_test_provider_factory_with_mem_db();
+
+        let mut rng = generators::rng();
+        let block = random_block(&mut rng, 0, None, Some(3), None);
+
+        let provider = factory.provider_rw().unwrap();
+        assert_matches!(
+            provider.insert_block(block.clone().try_seal_with_senders().unwrap()),
+            Ok(_)
+        );
+        provider.commit().unwrap();
+
+        let provider = factory.provider().unwrap();
+        assert_eq!(provider.block(0.into()), Ok(Some(block.clone().unseal())));
+        assert_matches!(provider.transaction_id(block.body[2].hash), Ok(Some(2)));
+    }
+
     #[test]
     fn take_block_transaction_range_recover_senders() {
         let factory = create_test_provider_factory();
@@ -782,4 +846,142 @@ mod tests {
         assert_eq!(gap.local_head, head);
         assert_eq!(gap.target.tip(), consensus_tip.into());
     }
+
+    #[test]
+    fn changed_addresses_with_range() {
+        let factory = create_test_provider_factory();
+        let provider = factory.provider_rw().unwrap();
+
+        let (alice, bob, carol) = (Address::random(), Address::random(), Address::random());
+        let tx = provider.tx_ref();
+        tx.put::<tables::AccountChangeSets>(1, AccountBeforeTx { address: alice, info: None })
+            .unwrap();
+        tx.put::<tables::AccountChangeSets>(2, AccountBeforeTx { address: bob, info: None })
+            .unwrap();
+        tx.put::<tables::StorageChangeSets>((2, carol).into(), StorageEntry::default()).unwrap();
+        tx.put::<tables::AccountChangeSets>(3, AccountBeforeTx { address: alice, info: None })
+            .unwrap();
+
+        assert_eq!(provider.changed_addresses_with_range(1..=1).unwrap(), [alice].into());
+        assert_eq!(
+            provider.changed_addresses_with_range(2..=3).unwrap(),
+            [alice, bob, carol].into()
+        );
+        assert!(provider.changed_addresses_with_range(4..=10).unwrap().is_empty());
+
+        provider
+            .save_prune_checkpoint(
+                PruneSegment::AccountHistory,
+                PruneCheckpoint {
+                    block_number: Some(1),
+                    tx_number: None,
+                    prune_mode: PruneMode::Before(2),
+                },
+            )
+            .unwrap();
+        assert_matches!(
+            provider.changed_addresses_with_range(1..=3),
+            Err(ProviderError::ChangeSetsPruned(1))
+        );
+        assert_eq!(provider.changed_addresses_with_range(2..=2).unwrap(), [bob, carol].into());
+    }
+
+    #[test]
+    fn changesets_in_static_files() {
+        let factory = create_test_provider_factory();
+        let (alice, bob, carol) = (Address::random(), Address::random(), Address::random());
+        let bob_account = Account { nonce: 1, ..Default::default() };
+        let slot = B256::random();
+
+        // Blocks 0..=2 are in static files, block 3 is in the database
+        let static_file_provider = factory.static_file_provider();
+        let mut writer =
+            static_file_provider.latest_writer(StaticFileSegment::AccountChangeSets).unwrap();
+        for (block, changes) in [
+            vec![],
+            vec![AccountBeforeTx { address: alice, info: None }],
+            vec![AccountBeforeTx { address: bob, info: Some(bob_account) }],
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            writer
+                .append_account_changeset(block as u64, &StoredAccountChangeSet { changes })
+                .unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+        let mut writer =
+            static_file_provider.latest_writer(StaticFileSegment::StorageChangeSets).unwrap();
+        for (block, changes) in [
+            vec![],
+            vec![],
+            vec![StorageBeforeTx { address: carol, key: slot, value: U256::from(1) }],
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            writer
+                .append_storage_changeset(block as u64, &StoredStorageChangeSet { changes })
+                .unwrap();
+        }
+        writer.commit().unwrap();
+        drop(writer);
+
+        let provider = factory.provider_rw().unwrap();
+        let tx = provider.tx_ref();
+        tx.put::<tables::AccountChangeSets>(3, AccountBeforeTx { address: alice, info: None })
+            .unwrap();
+        tx.put::<tables::StorageChangeSets>(
+            (3, carol).into(),
+            StorageEntry { key: slot, value: U256::from(2) },
+        )
+        .unwrap();
+        tx.put::<tables::PlainAccountState>(bob, Account::default()).unwrap();
+        tx.put::<tables::AccountsHistory>(
+            ShardedKey::new(bob, u64::MAX),
+            BlockNumberList::new([0, 2]).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(
+            provider.account_changesets_range(1..=3).unwrap(),
+            vec![
+                (1, AccountBeforeTx { address: alice, info: None }),
+                (2, AccountBeforeTx { address: bob, info: Some(bob_account) }),
+                (3, AccountBeforeTx { address: alice, info: None }),
+            ]
+        );
+        assert_eq!(
+            provider.storage_changesets_range(2..=3).unwrap(),
+            vec![
+                (BlockNumberAddress((2, carol)), StorageEntry { key: slot, value: U256::from(1) }),
+                (BlockNumberAddress((3, carol)), StorageEntry { key: slot, value: U256::from(2) }),
+            ]
+        );
+        assert_eq!(
+            provider.changed_addresses_with_range(2..=3).unwrap(),
+            [alice, bob, carol].into()
+        );
+
+        // Single entries are binary searched in the changeset of the block
+        assert_eq!(
+            static_file_provider.account_changeset_entry(2, bob).unwrap(),
+            Some(AccountBeforeTx { address: bob, info: Some(bob_account) })
+        );
+        assert_eq!(static_file_provider.account_changeset_entry(2, alice).unwrap(), None);
+        assert_eq!(
+            static_file_provider.storage_changeset_entry(2, carol, slot).unwrap(),
+            Some(StorageEntry { key: slot, value: U256::from(1) })
+        );
+        assert_eq!(static_file_provider.storage_changeset_entry(1, carol, slot).unwrap(), None);
+
+        // The account state before block 2 is read from the static file changeset
+        let historical =
+            HistoricalStateProviderRef::new(provider.tx_ref(), 1, static_file_provider.clone());
+        assert_eq!(historical.basic_account(bob).unwrap(), Some(bob_account));
+        let historical =
+            HistoricalStateProviderRef::new(provider.tx_ref(), 3, static_file_provider);
+        assert_eq!(historical.basic_account(bob).unwrap(), Some(Account::default()));
+    }
 }
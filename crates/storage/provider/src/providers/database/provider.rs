@@ -1,25 +1,28 @@
 use crate::{
     bundle_state::{BundleStateInit, RevertsInit},
-    providers::{database::metrics, static_file::StaticFileWriter, StaticFileProvider},
+    providers::{
+        database::metrics, static_file::StaticFileWriter, LowestAvailableBlocks, StaticFileProvider,
+    },
     to_range,
     traits::{
-        AccountExtReader, BlockSource, ChangeSetReader, ReceiptProvider, StageCheckpointWriter,
+        AccountExtReader, AddressAppearancesReader, BlockSource, ChangeSetReader, ReceiptProvider,
+        StageCheckpointWriter,
     },
     writer::StorageWriter,
     AccountReader, BlockExecutionReader, BlockExecutionWriter, BlockHashReader, BlockNumReader,
     BlockReader, BlockWriter, EvmEnvProvider, FinalizedBlockReader, FinalizedBlockWriter,
     HashingWriter, HeaderProvider, HeaderSyncGap, HeaderSyncGapProvider, HistoricalStateProvider,
-    HistoryWriter, LatestStateProvider, OriginalValuesKnown, ProviderError, PruneCheckpointReader,
-    PruneCheckpointWriter, RequestsProvider, StageCheckpointReader, StateProviderBox, StateWriter,
-    StatsReader, StorageReader, TransactionVariant, TransactionsProvider, TransactionsProviderExt,
-    WithdrawalsProvider,
+    HistoricalStateProviderRef, HistoryWriter, LatestStateProvider, OriginalValuesKnown,
+    ProviderError, PruneCheckpointReader, PruneCheckpointWriter, RequestsProvider,
+    StageCheckpointReader, StateProviderBox, StateWriter, StatsReader, StorageReader,
+    TransactionVariant, TransactionsProvider, TransactionsProviderExt, WithdrawalsProvider,
 };
 use itertools::{izip, Itertools};
 use reth_chainspec::{ChainInfo, ChainSpec, EthereumHardforks};
 use reth_db::{tables, BlockNumberList, PlainAccountState, PlainStorageState};
 use reth_db_api::{
     common::KeyValue,
-    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, RangeWalker},
+    cursor::{DbCursorRO, DbCursorRW, DbDupCursorRO, DbDupCursorRW, RangeWalker},
     database::Database,
     models::{
         sharded_key, storage_sharded_key::StorageShardedKey, AccountBeforeTx, BlockNumberAddress,
@@ -45,7 +48,7 @@ use reth_storage_errors::provider::{ProviderResult, RootMismatch};
 use reth_trie::{
     prefix_set::{PrefixSet, PrefixSetMut, TriePrefixSets},
     updates::TrieUpdates,
-    HashedPostStateSorted, Nibbles, StateRoot,
+    HashedPostState, HashedPostStateSorted, Nibbles, StateRoot,
 };
 use reth_trie_db::DatabaseStateRoot;
 use revm::primitives::{BlockEnv, CfgEnvWithHandlerCfg};
@@ -176,6 +179,35 @@ impl<TX: DbTx + 'static> DatabaseProvider<TX> {
 
         Ok(Box::new(state_provider))
     }
+
+    /// Returns the reverts of the hashed state from the latest state to the state after the given
+    /// block.
+    ///
+    /// Returns [`ProviderError::StateAtBlockPruned`] if the history of the block was pruned.
+    pub fn hashed_state_reverts(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<HashedPostState> {
+        let lowest_available_block = |segment| -> ProviderResult<Option<BlockNumber>> {
+            Ok(self
+                .get_prune_checkpoint(segment)?
+                .and_then(|checkpoint| checkpoint.block_number)
+                .map(|block_number| block_number + 1))
+        };
+        let lowest_available_blocks = LowestAvailableBlocks {
+            account_history_block_number: lowest_available_block(PruneSegment::AccountHistory)?,
+            storage_history_block_number: lowest_available_block(PruneSegment::StorageHistory)?,
+        };
+
+        // +1 as the changeset that we want is the one that was applied after this block.
+        HistoricalStateProviderRef::new_with_lowest_available_blocks(
+            &self.tx,
+            block_number + 1,
+            lowest_available_blocks,
+            self.static_file_provider.clone(),
+        )
+        .compute_revert_state()
+    }
 }
 
 impl<DB: Database> DatabaseProviderRW<DB> {
@@ -322,6 +354,88 @@ impl<TX: DbTx> DatabaseProvider<TX> {
         self.tx.cursor_read::<T>()?.walk_range(range)?.collect::<Result<Vec<_>, _>>()
     }
 
+    /// Returns the account changesets of the given range of blocks, in the same order and layout
+    /// as they are stored in [`tables::AccountChangeSets`].
+    ///
+    /// Changesets that were moved to static files are read from there.
+    pub fn account_changesets_range(
+        &self,
+        range: impl RangeBounds<BlockNumber>,
+    ) -> ProviderResult<Vec<(BlockNumber, AccountBeforeTx)>> {
+        self.static_file_provider.get_range_with_static_file_or_database(
+            StaticFileSegment::AccountChangeSets,
+            to_range(range),
+            |static_file, range, _| static_file.account_changesets_range(range),
+            |range, _| Ok(self.get::<tables::AccountChangeSets>(range)?),
+            |_| true,
+        )
+    }
+
+    /// Returns the storage changesets of the given range of blocks, in the same order and layout
+    /// as they are stored in [`tables::StorageChangeSets`].
+    ///
+    /// Changesets that were moved to static files are read from there.
+    pub fn storage_changesets_range(
+        &self,
+        range: impl RangeBounds<BlockNumber>,
+    ) -> ProviderResult<Vec<(BlockNumberAddress, StorageEntry)>> {
+        self.static_file_provider.get_range_with_static_file_or_database(
+            StaticFileSegment::StorageChangeSets,
+            to_range(range),
+            |static_file, range, _| static_file.storage_changesets_range(range),
+            |range, _| {
+                Ok(self.get::<tables::StorageChangeSets>(
+                    BlockNumberAddress((range.start, Address::ZERO))..
+                        BlockNumberAddress((range.end, Address::ZERO)),
+                )?)
+            },
+            |_| true,
+        )
+    }
+
+    /// Loads the trie prefix sets of all account and storage changes in the given range of blocks.
+    ///
+    /// Same as [`PrefixSetLoader`](reth_trie::prefix_set::PrefixSetLoader), only changesets that
+    /// were moved to static files are read from there.
+    pub fn changeset_prefix_sets(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<TriePrefixSets> {
+        let mut account_prefix_set = PrefixSetMut::default();
+        let mut storage_prefix_sets = HashMap::<B256, PrefixSetMut>::default();
+        let mut destroyed_accounts = HashSet::default();
+
+        let mut account_plain_state_cursor = self.tx.cursor_read::<tables::PlainAccountState>()?;
+        for (_, AccountBeforeTx { address, .. }) in self.account_changesets_range(range.clone())? {
+            let hashed_address = keccak256(address);
+            account_prefix_set.insert(Nibbles::unpack(hashed_address));
+
+            if account_plain_state_cursor.seek_exact(address)?.is_none() {
+                destroyed_accounts.insert(hashed_address);
+            }
+        }
+
+        for (BlockNumberAddress((_, address)), StorageEntry { key, .. }) in
+            self.storage_changesets_range(range)?
+        {
+            let hashed_address = keccak256(address);
+            account_prefix_set.insert(Nibbles::unpack(hashed_address));
+            storage_prefix_sets
+                .entry(hashed_address)
+                .or_default()
+                .insert(Nibbles::unpack(keccak256(key)));
+        }
+
+        Ok(TriePrefixSets {
+            account_prefix_set: account_prefix_set.freeze(),
+            storage_prefix_sets: storage_prefix_sets
+                .into_iter()
+                .map(|(k, v)| (k, v.freeze()))
+                .collect(),
+            destroyed_accounts,
+        })
+    }
+
     /// Iterates over read only values in the given table and collects them into a vector.
     ///
     /// Early-returns if the range is empty, without opening a cursor transaction.
@@ -789,10 +903,8 @@ impl<TX: DbTx> DatabaseProvider<TX> {
         let to_transaction_num =
             block_bodies.last().expect("already checked if there are blocks").1.last_tx_num();
 
-        let storage_range = BlockNumberAddress::range(range.clone());
-
-        let storage_changeset = self.get::<tables::StorageChangeSets>(storage_range)?;
-        let account_changeset = self.get::<tables::AccountChangeSets>(range)?;
+        let storage_changeset = self.storage_changesets_range(range.clone())?;
+        let account_changeset = self.account_changesets_range(range)?;
 
         // This is not working for blocks that are not at tip. as plain state is not the last
         // state of end range. We should rename the functions or add support to access
@@ -914,6 +1026,46 @@ impl<TX: DbTx> DatabaseProvider<TX> {
 
         Ok((state, reverts))
     }
+
+    /// Returns all addresses that appear in the given block, as recorded in
+    /// [`tables::TransactionAddressAppearances`] by the `IndexAddressAppearances` stage.
+    ///
+    /// An address appears in a block if it's the sender or the recipient of one of its
+    /// transactions, or if it took part in one of their call frames.
+    pub fn block_address_appearances(
+        &self,
+        block_number: BlockNumber,
+    ) -> ProviderResult<HashSet<Address>> {
+        let mut addresses = HashSet::new();
+        let Some(body) = self.block_body_indices(block_number)? else { return Ok(addresses) };
+        if body.is_empty() {
+            return Ok(addresses)
+        }
+
+        let mut cursor = self.tx.cursor_read::<tables::TransactionAddressAppearances>()?;
+        for entry in cursor.walk_range(body.tx_num_range())? {
+            addresses.insert(entry?.1);
+        }
+
+        Ok(addresses)
+    }
+
+    /// Returns all addresses that appear in the given range of blocks alongside the blocks they
+    /// appear in.
+    ///
+    /// See [`Self::block_address_appearances`].
+    pub fn address_appearances_with_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeMap<Address, Vec<BlockNumber>>> {
+        let mut appearances = BTreeMap::<Address, Vec<BlockNumber>>::new();
+        for block_number in range {
+            for address in self.block_address_appearances(block_number)? {
+                appearances.entry(address).or_default().push(block_number);
+            }
+        }
+        Ok(appearances)
+    }
 }
 
 impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
@@ -957,10 +1109,10 @@ impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
         let to_transaction_num =
             block_bodies.last().expect("already checked if there are blocks").1.last_tx_num();
 
-        let storage_range = BlockNumberAddress::range(range.clone());
-
-        let storage_changeset = self.take::<tables::StorageChangeSets>(storage_range)?;
-        let account_changeset = self.take::<tables::AccountChangeSets>(range)?;
+        let storage_changeset = self.storage_changesets_range(range.clone())?;
+        let account_changeset = self.account_changesets_range(range.clone())?;
+        self.remove::<tables::StorageChangeSets>(BlockNumberAddress::range(range.clone()))?;
+        self.remove::<tables::AccountChangeSets>(range)?;
 
         // This is not working for blocks that are not at tip. as plain state is not the last
         // state of end range. We should rename the functions or add support to access
@@ -1042,6 +1194,7 @@ impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
         if range.is_empty() {
             return Ok(ExecutionOutcome::default())
         }
+
         let start_block_number = *range.start();
 
         // We are not removing block meta as it is used to get block changesets.
@@ -1053,10 +1206,10 @@ impl<TX: DbTxMut + DbTx> DatabaseProvider<TX> {
         let to_transaction_num =
             block_bodies.last().expect("already checked if there are blocks").1.last_tx_num();
 
-        let storage_range = BlockNumberAddress::range(range.clone());
-
-        let storage_changeset = self.take::<tables::StorageChangeSets>(storage_range)?;
-        let account_changeset = self.take::<tables::AccountChangeSets>(range)?;
+        let storage_changeset = self.storage_changesets_range(range.clone())?;
+        let account_changeset = self.account_changesets_range(range.clone())?;
+        self.remove::<tables::StorageChangeSets>(BlockNumberAddress::range(range.clone()))?;
+        self.remove::<tables::AccountChangeSets>(range)?;
 
         // This is not working for blocks that are not at tip. as plain state is not the last
         // state of end range. We should rename the functions or add support to access
@@ -1691,13 +1844,11 @@ impl<TX: DbTx> AccountExtReader for DatabaseProvider<TX> {
         &self,
         range: impl RangeBounds<BlockNumber>,
     ) -> ProviderResult<BTreeSet<Address>> {
-        self.tx
-            .cursor_read::<tables::AccountChangeSets>()?
-            .walk_range(range)?
-            .map(|entry| {
-                entry.map(|(_, account_before)| account_before.address).map_err(Into::into)
-            })
-            .collect()
+        Ok(self
+            .account_changesets_range(range)?
+            .into_iter()
+            .map(|(_, account_before)| account_before.address)
+            .collect())
     }
 
     fn basic_accounts(
@@ -1715,16 +1866,10 @@ impl<TX: DbTx> AccountExtReader for DatabaseProvider<TX> {
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<BTreeMap<Address, Vec<u64>>> {
-        let mut changeset_cursor = self.tx.cursor_read::<tables::AccountChangeSets>()?;
-
-        let account_transitions = changeset_cursor.walk_range(range)?.try_fold(
-            BTreeMap::new(),
-            |mut accounts: BTreeMap<Address, Vec<u64>>, entry| -> ProviderResult<_> {
-                let (index, account) = entry?;
-                accounts.entry(account.address).or_default().push(index);
-                Ok(accounts)
-            },
-        )?;
+        let mut account_transitions = BTreeMap::<Address, Vec<u64>>::new();
+        for (index, account) in self.account_changesets_range(range)? {
+            account_transitions.entry(account.address).or_default().push(index);
+        }
 
         Ok(account_transitions)
     }
@@ -1735,15 +1880,112 @@ impl<TX: DbTx> ChangeSetReader for DatabaseProvider<TX> {
         &self,
         block_number: BlockNumber,
     ) -> ProviderResult<Vec<AccountBeforeTx>> {
-        let range = block_number..=block_number;
-        self.tx
-            .cursor_read::<tables::AccountChangeSets>()?
-            .walk_range(range)?
-            .map(|result| -> ProviderResult<_> {
-                let (_, account_before) = result?;
-                Ok(account_before)
-            })
-            .collect()
+        Ok(self
+            .account_changesets_range(block_number..=block_number)?
+            .into_iter()
+            .map(|(_, account_before)| account_before)
+            .collect())
+    }
+
+    fn changed_addresses_with_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeSet<Address>> {
+        // changesets up to and including the prune checkpoint block are gone
+        for segment in [PruneSegment::AccountHistory, PruneSegment::StorageHistory] {
+            if let Some(pruned) =
+                self.get_prune_checkpoint(segment)?.and_then(|checkpoint| checkpoint.block_number)
+            {
+                if *range.start() <= pruned {
+                    return Err(ProviderError::ChangeSetsPruned(*range.start()))
+                }
+            }
+        }
+
+        let mut addresses = self.changed_accounts_with_range(range.clone())?;
+        addresses.extend(
+            self.storage_changesets_range(range)?.into_iter().map(|(key, _)| key.address()),
+        );
+        Ok(addresses)
+    }
+}
+
+impl<TX: DbTx> AddressAppearancesReader for DatabaseProvider<TX> {
+    fn address_appearances_checkpoint(&self) -> ProviderResult<Option<BlockNumber>> {
+        Ok(self
+            .get_stage_checkpoint(StageId::IndexAddressAppearances)?
+            .map(|checkpoint| checkpoint.block_number))
+    }
+
+    fn address_appearances_before(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        let mut cursor = self.tx.cursor_read::<tables::AddressAppearances>()?;
+        let mut appearances = Vec::new();
+
+        // The shard that contains the block, or the last shard of the address.
+        let mut shard = cursor
+            .seek(ShardedKey::new(address, block_number))?
+            .filter(|(sharded_key, _)| sharded_key.key == address);
+        while let Some((_, list)) = shard {
+            let blocks = list.iter().collect::<Vec<_>>();
+            for block in blocks.into_iter().rev().filter(|block| *block < block_number) {
+                if appearances.len() == limit {
+                    return Ok(appearances)
+                }
+                appearances.push(block);
+            }
+            shard = cursor.prev()?.filter(|(sharded_key, _)| sharded_key.key == address);
+        }
+
+        Ok(appearances)
+    }
+
+    fn address_appearances_after(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        let mut appearances = Vec::new();
+        let Some(start) = block_number.checked_add(1) else { return Ok(appearances) };
+
+        let mut cursor = self.tx.cursor_read::<tables::AddressAppearances>()?;
+        for entry in cursor.walk(Some(ShardedKey::new(address, start)))? {
+            let (sharded_key, list) = entry?;
+            if sharded_key.key != address {
+                break
+            }
+            for block in list.iter().filter(|block| *block > block_number) {
+                if appearances.len() == limit {
+                    return Ok(appearances)
+                }
+                appearances.push(block);
+            }
+        }
+
+        Ok(appearances)
+    }
+
+    fn address_appearances_in_block(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<usize>> {
+        let mut indices = Vec::new();
+        let Some(body) = self.block_body_indices(block_number)? else { return Ok(indices) };
+
+        let mut cursor = self.tx.cursor_dup_read::<tables::TransactionAddressAppearances>()?;
+        for (index, tx_number) in body.tx_num_range().enumerate() {
+            if cursor.seek_by_key_subkey(tx_number, address)? == Some(address) {
+                indices.push(index);
+            }
+        }
+
+        Ok(indices)
     }
 }
 
@@ -2560,6 +2802,15 @@ impl<TX: DbTxMut> StageCheckpointWriter for DatabaseProvider<TX> {
             )?;
         }
 
+        // the optional address appearance index is only built by its stage, it's only unwound
+        if let Some((key, checkpoint)) =
+            cursor.seek_exact(StageId::IndexAddressAppearances.to_string())?
+        {
+            if checkpoint.block_number > block_number {
+                cursor.upsert(key, StageCheckpoint::new(block_number))?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -2592,36 +2843,28 @@ impl<TX: DbTx> StorageReader for DatabaseProvider<TX> {
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<BTreeMap<Address, BTreeSet<B256>>> {
-        self.tx
-            .cursor_read::<tables::StorageChangeSets>()?
-            .walk_range(BlockNumberAddress::range(range))?
-            // fold all storages and save its old state so we can remove it from HashedStorage
-            // it is needed as it is dup table.
-            .try_fold(BTreeMap::new(), |mut accounts: BTreeMap<Address, BTreeSet<B256>>, entry| {
-                let (BlockNumberAddress((_, address)), storage_entry) = entry?;
-                accounts.entry(address).or_default().insert(storage_entry.key);
-                Ok(accounts)
-            })
+        // fold all storages and save its old state so we can remove it from HashedStorage
+        // it is needed as it is dup table.
+        let mut accounts = BTreeMap::<Address, BTreeSet<B256>>::new();
+        for (BlockNumberAddress((_, address)), storage_entry) in
+            self.storage_changesets_range(range)?
+        {
+            accounts.entry(address).or_default().insert(storage_entry.key);
+        }
+        Ok(accounts)
     }
 
     fn changed_storages_and_blocks_with_range(
         &self,
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<BTreeMap<(Address, B256), Vec<u64>>> {
-        let mut changeset_cursor = self.tx.cursor_read::<tables::StorageChangeSets>()?;
-
-        let storage_changeset_lists =
-            changeset_cursor.walk_range(BlockNumberAddress::range(range))?.try_fold(
-                BTreeMap::new(),
-                |mut storages: BTreeMap<(Address, B256), Vec<u64>>, entry| -> ProviderResult<_> {
-                    let (index, storage) = entry?;
-                    storages
-                        .entry((index.address(), storage.key))
-                        .or_default()
-                        .push(index.block_number());
-                    Ok(storages)
-                },
-            )?;
+        let mut storage_changeset_lists = BTreeMap::<(Address, B256), Vec<u64>>::new();
+        for (index, storage) in self.storage_changesets_range(range)? {
+            storage_changeset_lists
+                .entry((index.address(), storage.key))
+                .or_default()
+                .push(index.block_number());
+        }
 
         Ok(storage_changeset_lists)
     }
@@ -2636,12 +2879,9 @@ impl<TX: DbTxMut + DbTx> HashingWriter for DatabaseProvider<TX> {
         // Note that collecting and then reversing the order is necessary to ensure that the
         // changes are applied in the correct order.
         let hashed_accounts = self
-            .tx
-            .cursor_read::<tables::AccountChangeSets>()?
-            .walk_range(range)?
-            .map(|entry| entry.map(|(_, e)| (keccak256(e.address), e.info)))
-            .collect::<Result<Vec<_>, _>>()?
+            .account_changesets_range(range)?
             .into_iter()
+            .map(|(_, e)| (keccak256(e.address), e.info))
             .rev()
             .collect::<BTreeMap<_, _>>();
 
@@ -2680,15 +2920,13 @@ impl<TX: DbTxMut + DbTx> HashingWriter for DatabaseProvider<TX> {
         range: Range<BlockNumberAddress>,
     ) -> ProviderResult<HashMap<B256, BTreeSet<B256>>> {
         // Aggregate all block changesets and make list of accounts that have been changed.
-        let mut changesets = self.tx.cursor_read::<tables::StorageChangeSets>()?;
-        let mut hashed_storages = changesets
-            .walk_range(range)?
-            .map(|entry| {
-                entry.map(|(BlockNumberAddress((_, address)), storage_entry)| {
-                    (keccak256(address), keccak256(storage_entry.key), storage_entry.value)
-                })
+        let mut hashed_storages = self
+            .storage_changesets_range(range.start.block_number()..range.end.block_number())?
+            .into_iter()
+            .map(|(BlockNumberAddress((_, address)), storage_entry)| {
+                (keccak256(address), keccak256(storage_entry.key), storage_entry.value)
             })
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect::<Vec<_>>();
         hashed_storages.sort_by_key(|(ha, hk, _)| (*ha, *hk));
 
         // Apply values to HashedState, and remove the account if it's None.
@@ -2838,11 +3076,10 @@ impl<TX: DbTxMut + DbTx> HistoryWriter for DatabaseProvider<TX> {
         range: RangeInclusive<BlockNumber>,
     ) -> ProviderResult<usize> {
         let mut last_indices = self
-            .tx
-            .cursor_read::<tables::AccountChangeSets>()?
-            .walk_range(range)?
-            .map(|entry| entry.map(|(index, account)| (account.address, index)))
-            .collect::<Result<Vec<_>, _>>()?;
+            .account_changesets_range(range)?
+            .into_iter()
+            .map(|(index, account)| (account.address, index))
+            .collect::<Vec<_>>();
         last_indices.sort_by_key(|(a, _)| *a);
 
         // Unwind the account history index.
@@ -2884,13 +3121,10 @@ impl<TX: DbTxMut + DbTx> HistoryWriter for DatabaseProvider<TX> {
         range: Range<BlockNumberAddress>,
     ) -> ProviderResult<usize> {
         let mut storage_changesets = self
-            .tx
-            .cursor_read::<tables::StorageChangeSets>()?
-            .walk_range(range)?
-            .map(|entry| {
-                entry.map(|(BlockNumberAddress((bn, address)), storage)| (address, storage.key, bn))
-            })
-            .collect::<Result<Vec<_>, _>>()?;
+            .storage_changesets_range(range.start.block_number()..range.end.block_number())?
+            .into_iter()
+            .map(|(BlockNumberAddress((bn, address)), storage)| (address, storage.key, bn))
+            .collect::<Vec<_>>();
         storage_changesets.sort_by_key(|(address, key, _)| (*address, *key));
 
         let mut cursor = self.tx.cursor_write::<tables::StoragesHistory>()?;
@@ -2931,6 +3165,64 @@ impl<TX: DbTxMut + DbTx> HistoryWriter for DatabaseProvider<TX> {
         )
     }
 
+    fn unwind_address_appearance_indices(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<usize> {
+        let appearances = self.address_appearances_with_range(range.clone())?;
+
+        let mut cursor = self.tx.cursor_write::<tables::AddressAppearances>()?;
+        for (&address, blocks) in &appearances {
+            // Blocks are sorted, so the first one is the lowest block to remove.
+            let partial_shard = unwind_history_shards::<_, tables::AddressAppearances, _>(
+                &mut cursor,
+                ShardedKey::last(address),
+                blocks[0],
+                |sharded_key| sharded_key.key == address,
+            )?;
+
+            // Check the last returned partial shard.
+            // If it's not empty, the shard needs to be reinserted.
+            if !partial_shard.is_empty() {
+                cursor.insert(
+                    ShardedKey::last(address),
+                    BlockNumberList::new_pre_sorted(partial_shard),
+                )?;
+            }
+        }
+
+        // Shards were unwound from the first block of the range onwards, same for the appearances
+        // of its transactions.
+        if let Some(body) = self.block_body_indices(*range.start())? {
+            self.remove::<tables::TransactionAddressAppearances>(body.first_tx_num()..)?;
+        }
+
+        Ok(appearances.len())
+    }
+
+    fn insert_address_appearance_index(
+        &self,
+        address_appearances: BTreeMap<Address, Vec<u64>>,
+    ) -> ProviderResult<()> {
+        self.append_history_index::<_, tables::AddressAppearances>(
+            address_appearances,
+            ShardedKey::new,
+        )
+    }
+
+    fn insert_transaction_address_appearances(
+        &self,
+        transaction_appearances: BTreeMap<TxNumber, BTreeSet<Address>>,
+    ) -> ProviderResult<()> {
+        let mut cursor = self.tx.cursor_dup_write::<tables::TransactionAddressAppearances>()?;
+        for (tx_number, addresses) in transaction_appearances {
+            for address in addresses {
+                cursor.append_dup(tx_number, address)?;
+            }
+        }
+        Ok(())
+    }
+
     fn update_history_indices(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<()> {
         // account history stage
         {
@@ -3000,6 +3292,9 @@ impl<DB: Database> BlockExecutionWriter for DatabaseProviderRW<DB> {
         // Unwind storage history indices.
         self.unwind_storage_history_indices(storage_range)?;
 
+        // Unwind address appearance indices, if any of the blocks were indexed.
+        self.unwind_address_appearance_indices(range.clone())?;
+
         // Calculate the reverted merkle root.
         // This is the same as `StateRoot::incremental_root_with_updates`, only the prefix sets
         // are pre-loaded.
@@ -3088,6 +3383,9 @@ impl<DB: Database> BlockExecutionWriter for DatabaseProviderRW<DB> {
         // Unwind storage history indices.
         self.unwind_storage_history_indices(storage_range)?;
 
+        // Unwind address appearance indices, if any of the blocks were indexed.
+        self.unwind_address_appearance_indices(range.clone())?;
+
         // Calculate the reverted merkle root.
         // This is the same as `StateRoot::incremental_root_with_updates`, only the prefix sets
         // are pre-loaded.
@@ -0,0 +1,297 @@
+use crate::{
+    providers::{BlockchainProvider, ProviderFactory},
+    BlockExecutionReader, BlockHashReader, DatabaseProviderRO, FinalizedBlockReader,
+    HeaderProvider, StageCheckpointReader, StaticFileProviderFactory,
+};
+use reth_chain_state::{CanonStateNotification, CanonStateNotificationSender, ChainInfoTracker};
+use reth_db_api::database::Database;
+use reth_execution_types::{Chain, ExecutionOutcome};
+use reth_primitives::{BlockHash, BlockNumber, SealedHeader};
+use reth_stages_types::StageId;
+use reth_storage_errors::provider::{ProviderError, ProviderResult};
+use std::{collections::BTreeMap, sync::Arc};
+
+/// Maximum number of blocks included in a single canonical state notification.
+///
+/// If the followed node advanced further since the last poll, only the most recent blocks are
+/// notified.
+const MAX_NOTIFIED_BLOCKS: u64 = 64;
+
+/// Keeps read-only providers in step with a node that writes to the same datadir from another
+/// process.
+///
+/// Every call to [`DatabaseFollower::poll`] checks the database for a new canonical head or
+/// finalized block. If either changed, the static file index is reloaded and the shared
+/// [`ChainInfoTracker`] is updated, so that a [`BlockchainProvider`] created from the same parts
+/// serves the new chain. Optionally, newly canonical blocks are sent as
+/// [`CanonStateNotification`]s.
+#[allow(missing_debug_implementations)]
+pub struct DatabaseFollower<DB> {
+    /// Provider factory over the followed database and static files.
+    factory: ProviderFactory<DB>,
+    /// Tracks the canonical head and finalized block of the followed node.
+    chain_info: ChainInfoTracker,
+    /// Sender for notifications about newly canonical blocks.
+    canon_state_notification_sender: Option<CanonStateNotificationSender>,
+    /// The most recently notified blocks, used to find where the chain diverged after a reorg and
+    /// to notify the reverted blocks.
+    recent_blocks: BTreeMap<BlockNumber, NotifiedBlock>,
+}
+
+/// A canonical block that was notified by the [`DatabaseFollower`].
+#[derive(Debug)]
+struct NotifiedBlock {
+    /// Hash of the block.
+    hash: BlockHash,
+    /// The block with its execution outcome. It's not available for the block that was the
+    /// canonical head when the follower was created.
+    chain: Option<Chain>,
+}
+
+impl<DB> DatabaseFollower<DB> {
+    /// Creates a new follower that updates the given [`ChainInfoTracker`].
+    pub fn new(factory: ProviderFactory<DB>, chain_info: ChainInfoTracker) -> Self {
+        let head = chain_info.get_canonical_num_hash();
+        Self {
+            factory,
+            chain_info,
+            canon_state_notification_sender: None,
+            recent_blocks: BTreeMap::from([(
+                head.number,
+                NotifiedBlock { hash: head.hash, chain: None },
+            )]),
+        }
+    }
+
+    /// Sends a [`CanonStateNotification::Commit`] with the newly canonical blocks whenever the
+    /// canonical head advances.
+    ///
+    /// Blocks reverted by the followed node are no longer available in the database, so the
+    /// recently notified blocks are kept in memory to send reorgs as
+    /// [`CanonStateNotification::Reorg`]. Reverted blocks that weren't notified before are not
+    /// part of the old chain.
+    pub fn with_canon_state_notifications(mut self, sender: CanonStateNotificationSender) -> Self {
+        self.canon_state_notification_sender = Some(sender);
+        self
+    }
+}
+
+impl<DB: Database> DatabaseFollower<DB> {
+    /// Checks the database for changes made by the followed node and applies them.
+    ///
+    /// Returns the new canonical head if it changed.
+    pub fn poll(&mut self) -> ProviderResult<Option<SealedHeader>> {
+        let head = self.chain_info.get_canonical_num_hash();
+        let finalized = self.chain_info.get_finalized_num_hash().map(|finalized| finalized.number);
+
+        let provider = self.factory.provider()?;
+        let best_number =
+            provider.get_stage_checkpoint(StageId::Finish)?.unwrap_or_default().block_number;
+        let finalized_number = provider.last_finalized_block_number()?;
+        let head_changed =
+            best_number != head.number || provider.block_hash(best_number)? != Some(head.hash);
+        let finalized_changed = finalized_number > 0 && Some(finalized_number) != finalized;
+        drop(provider);
+
+        if !head_changed && !finalized_changed {
+            return Ok(None)
+        }
+
+        // The followed node may have appended to, truncated or created static files since they
+        // were last loaded.
+        self.factory.static_file_provider().reload_index()?;
+        let provider = self.factory.provider()?;
+
+        if finalized_changed {
+            if let Some(header) = provider.sealed_header(finalized_number)? {
+                self.chain_info.set_finalized(header);
+            }
+        }
+
+        if !head_changed {
+            return Ok(None)
+        }
+
+        let header = provider
+            .sealed_header(best_number)?
+            .ok_or_else(|| ProviderError::HeaderNotFound(best_number.into()))?;
+        self.notify(&provider, best_number)?;
+        self.chain_info.set_canonical_head(header.clone());
+
+        Ok(Some(header))
+    }
+
+    /// Sends a notification with the canonical blocks up to `head` that weren't notified yet.
+    fn notify(
+        &mut self,
+        provider: &DatabaseProviderRO<DB>,
+        head: BlockNumber,
+    ) -> ProviderResult<()> {
+        let Some(sender) = &self.canon_state_notification_sender else { return Ok(()) };
+
+        // Drop the blocks that are no longer canonical, the remaining highest one is where the
+        // new chain starts from.
+        let mut reverted = Vec::new();
+        while let Some(entry) = self.recent_blocks.last_entry() {
+            if *entry.key() <= head && provider.block_hash(*entry.key())? == Some(entry.get().hash)
+            {
+                break
+            }
+            reverted.push(entry.remove());
+        }
+
+        let first = self
+            .recent_blocks
+            .last_key_value()
+            .map_or(0, |(number, _)| number + 1)
+            .max(head.saturating_sub(MAX_NOTIFIED_BLOCKS - 1));
+        if first > head {
+            return Ok(())
+        }
+
+        // Load the blocks one by one, so each of them can be notified as reverted later on.
+        let mut new_blocks = Vec::with_capacity((head - first + 1) as usize);
+        for number in first..=head {
+            let chain = provider.get_block_and_execution_range(number..=number)?;
+            self.recent_blocks.insert(
+                number,
+                NotifiedBlock { hash: chain.tip().hash(), chain: Some(chain.clone()) },
+            );
+            new_blocks.push(chain);
+        }
+        while self.recent_blocks.len() > MAX_NOTIFIED_BLOCKS as usize {
+            self.recent_blocks.pop_first();
+        }
+
+        let new = Arc::new(join_chains(new_blocks).expect("range is not empty"));
+        let notification =
+            match join_chains(reverted.into_iter().rev().filter_map(|block| block.chain)) {
+                Some(old) => CanonStateNotification::Reorg { old: Arc::new(old), new },
+                None => CanonStateNotification::Commit { new },
+            };
+
+        // Ignore the error, there may be no subscribers.
+        let _ = sender.send(notification);
+
+        Ok(())
+    }
+}
+
+/// Joins consecutive chains into one, returns [`None`] if there are none.
+fn join_chains(chains: impl IntoIterator<Item = Chain>) -> Option<Chain> {
+    let mut blocks = Vec::new();
+    let mut execution_outcome: Option<ExecutionOutcome> = None;
+    for chain in chains {
+        let (chain_blocks, chain_execution_outcome, _) = chain.into_inner();
+        blocks.extend(chain_blocks.into_blocks());
+        match &mut execution_outcome {
+            Some(execution_outcome) => execution_outcome.extend(chain_execution_outcome),
+            None => execution_outcome = Some(chain_execution_outcome),
+        }
+    }
+    execution_outcome.map(|execution_outcome| Chain::new(blocks, execution_outcome, None))
+}
+
+impl<DB> BlockchainProvider<DB> {
+    /// Returns a [`DatabaseFollower`] that keeps this provider in step with a node writing to the
+    /// same datadir.
+    pub fn follower(&self) -> DatabaseFollower<DB> {
+        DatabaseFollower::new(self.database.clone(), self.chain_info.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        providers::{StaticFileProvider, StaticFileWriter},
+        test_utils::create_test_provider_factory_with_mem_db,
+        BlockWriter, StageCheckpointWriter,
+    };
+    use reth_chainspec::MAINNET;
+    use reth_primitives::{StaticFileSegment, U256};
+    use reth_stages_types::StageCheckpoint;
+    use reth_testing_utils::generators::{self, random_block, random_header};
+    use tokio::sync::broadcast;
+
+    #[test]
+    fn follows_canonical_blocks() {
+        let factory = create_test_provider_factory_with_mem_db();
+        let mut rng = generators::rng();
+
+        let mut blocks = vec![random_block(&mut rng, 0, None, Some(0), Some(0))];
+        let provider = factory.provider_rw().unwrap();
+        provider.insert_block(blocks[0].clone().try_seal_with_senders().unwrap()).unwrap();
+        provider.commit().unwrap();
+
+        let chain_info = ChainInfoTracker::new(blocks[0].header.clone());
+        let (sender, mut notifications) = broadcast::channel(16);
+        let mut follower = DatabaseFollower::new(factory.clone(), chain_info.clone())
+            .with_canon_state_notifications(sender);
+        assert_eq!(follower.poll(), Ok(None));
+
+        let provider = factory.provider_rw().unwrap();
+        for number in 1..=2 {
+            let parent = blocks.last().unwrap().hash();
+            let block = random_block(&mut rng, number, Some(parent), Some(1), Some(0));
+            provider.insert_block(block.clone().try_seal_with_senders().unwrap()).unwrap();
+            blocks.push(block);
+        }
+        provider.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(2)).unwrap();
+        provider.commit().unwrap();
+
+        assert_eq!(follower.poll(), Ok(Some(blocks[2].header.clone())));
+        assert_eq!(chain_info.get_canonical_head(), blocks[2].header);
+        let CanonStateNotification::Commit { new } = notifications.try_recv().unwrap() else {
+            panic!("expected a commit")
+        };
+        assert_eq!(new.range(), 1..=2);
+        assert_eq!(follower.poll(), Ok(None));
+
+        // Replace the head with a sibling, it's notified as a reorg of the previous head.
+        let sibling = random_block(&mut rng, 2, Some(blocks[1].hash()), Some(0), Some(0));
+        let provider = factory.provider_rw().unwrap();
+        provider.insert_block(sibling.clone().try_seal_with_senders().unwrap()).unwrap();
+        provider.commit().unwrap();
+
+        assert_eq!(follower.poll(), Ok(Some(sibling.header.clone())));
+        let CanonStateNotification::Reorg { old, new } = notifications.try_recv().unwrap() else {
+            panic!("expected a reorg")
+        };
+        assert_eq!(old.range(), 2..=2);
+        assert_eq!(old.tip().hash(), blocks[2].hash());
+        assert_eq!(new.range(), 2..=2);
+        assert_eq!(new.tip().hash(), sibling.hash());
+    }
+
+    #[test]
+    fn reloads_static_files() {
+        let writer = create_test_provider_factory_with_mem_db();
+        let static_file_provider = writer.static_file_provider();
+        let mut rng = generators::rng();
+
+        let genesis = random_header(&mut rng, 0, None);
+        let mut headers = static_file_provider.latest_writer(StaticFileSegment::Headers).unwrap();
+        headers.append_header(&genesis, U256::ZERO, &genesis.hash()).unwrap();
+        headers.commit().unwrap();
+
+        let factory = ProviderFactory::new(
+            writer.db_ref().clone(),
+            MAINNET.clone(),
+            StaticFileProvider::read_only(static_file_provider.path()).unwrap(),
+        );
+        let chain_info = ChainInfoTracker::new(genesis.clone());
+        let mut follower = DatabaseFollower::new(factory, chain_info.clone());
+
+        let header = random_header(&mut rng, 1, Some(genesis.hash()));
+        headers.append_header(&header, U256::ZERO, &header.hash()).unwrap();
+        headers.commit().unwrap();
+        drop(headers);
+        let provider = writer.provider_rw().unwrap();
+        provider.save_stage_checkpoint(StageId::Finish, StageCheckpoint::new(1)).unwrap();
+        provider.commit().unwrap();
+
+        assert_eq!(follower.poll(), Ok(Some(header.clone())));
+        assert_eq!(chain_info.get_canonical_head(), header);
+    }
+}
@@ -1,23 +1,33 @@
 use crate::{
-    providers::{state::macros::delegate_provider_impls, StaticFileProvider},
+    providers::{
+        state::{macros::delegate_provider_impls, snap::HashedStateFallback},
+        StaticFileProvider,
+    },
     AccountReader, BlockHashReader, ProviderError, StateProvider, StateRootProvider,
 };
 use reth_db::{tables, BlockNumberList};
 use reth_db_api::{
     cursor::{DbCursorRO, DbDupCursorRO},
-    models::{storage_sharded_key::StorageShardedKey, ShardedKey},
+    models::{storage_sharded_key::StorageShardedKey, BlockNumberAddress, ShardedKey},
     table::Table,
     transaction::DbTx,
 };
 use reth_primitives::{
     constants::EPOCH_SLOTS, Account, Address, BlockNumber, Bytecode, StaticFileSegment, StorageKey,
-    StorageValue, B256,
+    StorageValue, B256, U256,
 };
-use reth_storage_api::StateProofProvider;
+use reth_storage_api::{HashedStateRangeProvider, StateProofProvider};
 use reth_storage_errors::provider::ProviderResult;
-use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState, StateRoot};
+use reth_trie::{
+    updates::TrieUpdates, AccountProof, HashedPostState, HashedPostStateSorted, HashedRange,
+    StateRoot,
+};
 use reth_trie_db::DatabaseStateRoot;
-use std::fmt::Debug;
+use std::{
+    borrow::Cow,
+    fmt::Debug,
+    sync::{Arc, OnceLock},
+};
 
 /// State provider for a given block number which takes a tx reference.
 ///
@@ -30,6 +40,9 @@ use std::fmt::Debug;
 /// - [`tables::StoragesHistory`]
 /// - [`tables::AccountChangeSets`]
 /// - [`tables::StorageChangeSets`]
+///
+/// Changesets that were already moved to static files are read from there. If the state was
+/// downloaded over `snap`, the state missing from the plain state is read from the hashed state.
 #[derive(Debug)]
 pub struct HistoricalStateProviderRef<'b, TX: DbTx> {
     /// Transaction
@@ -40,6 +53,18 @@ pub struct HistoricalStateProviderRef<'b, TX: DbTx> {
     lowest_available_blocks: LowestAvailableBlocks,
     /// Static File provider
     static_file_provider: StaticFileProvider,
+    /// Reads the state missing from the plain state of snap synced nodes.
+    hashed_state_fallback: HashedStateFallback,
+    /// The reverts of the state after the block, computed once by the first state root, proof or
+    /// range request.
+    revert_state: Arc<RevertStateCache>,
+}
+
+/// The reverts from the latest state to the state of a historical block.
+#[derive(Debug, Default)]
+struct RevertStateCache {
+    state: OnceLock<HashedPostState>,
+    sorted: OnceLock<HashedPostStateSorted>,
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -57,18 +82,32 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         block_number: BlockNumber,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks: Default::default(), static_file_provider }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks: Default::default(),
+            static_file_provider,
+            hashed_state_fallback: HashedStateFallback::new(),
+            revert_state: Default::default(),
+        }
     }
 
     /// Create new `StateProvider` for historical block number and lowest block numbers at which
     /// account & storage histories are available.
-    pub const fn new_with_lowest_available_blocks(
+    pub fn new_with_lowest_available_blocks(
         tx: &'b TX,
         block_number: BlockNumber,
         lowest_available_blocks: LowestAvailableBlocks,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks, static_file_provider }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks,
+            static_file_provider,
+            hashed_state_fallback: HashedStateFallback::new(),
+            revert_state: Default::default(),
+        }
     }
 
     /// Lookup an account in the `AccountsHistory` table
@@ -105,8 +144,41 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
         )
     }
 
-    /// Retrieve revert hashed state for this history provider.
-    fn revert_state(&self) -> ProviderResult<HashedPostState> {
+    /// Returns the revert hashed state for this history provider, computed on first use.
+    fn revert_state(&self) -> ProviderResult<&HashedPostState> {
+        if let Some(state) = self.revert_state.state.get() {
+            return Ok(state)
+        }
+        let state = self.compute_revert_state()?;
+        Ok(self.revert_state.state.get_or_init(|| state))
+    }
+
+    /// Returns the revert hashed state with the given state on top of it.
+    fn revert_state_with(&self, hashed_state: &HashedPostState) -> ProviderResult<HashedPostState> {
+        let mut revert_state = self.revert_state()?.clone();
+        revert_state.extend(hashed_state.clone());
+        Ok(revert_state)
+    }
+
+    /// Returns the sorted revert hashed state with the given state on top of it.
+    ///
+    /// The sorted reverts are cached, so they are only cloned if the given state isn't empty.
+    fn sorted_revert_state_with(
+        &self,
+        hashed_state: &HashedPostState,
+    ) -> ProviderResult<Cow<'_, HashedPostStateSorted>> {
+        if !hashed_state.accounts.is_empty() || !hashed_state.storages.is_empty() {
+            return Ok(Cow::Owned(self.revert_state_with(hashed_state)?.into_sorted()))
+        }
+        if let Some(sorted) = self.revert_state.sorted.get() {
+            return Ok(Cow::Borrowed(sorted))
+        }
+        let sorted = self.revert_state()?.clone().into_sorted();
+        Ok(Cow::Borrowed(self.revert_state.sorted.get_or_init(|| sorted)))
+    }
+
+    /// Computes the revert hashed state for this history provider.
+    pub(crate) fn compute_revert_state(&self) -> ProviderResult<HashedPostState> {
         if !self.lowest_available_blocks.is_account_history_available(self.block_number) ||
             !self.lowest_available_blocks.is_storage_history_available(self.block_number)
         {
@@ -131,7 +203,52 @@ impl<'b, TX: DbTx> HistoricalStateProviderRef<'b, TX> {
             );
         }
 
-        Ok(HashedPostState::from_reverts(self.tx, self.block_number)?)
+        let in_static_files = |segment| {
+            self.static_file_provider
+                .get_highest_static_file_block(segment)
+                .is_some_and(|highest| highest >= self.block_number)
+        };
+        if !in_static_files(StaticFileSegment::AccountChangeSets) &&
+            !in_static_files(StaticFileSegment::StorageChangeSets)
+        {
+            return Ok(HashedPostState::from_reverts(self.tx, self.block_number)?)
+        }
+
+        // Some of the changesets were already moved to static files, the rest is read from the
+        // database.
+        let account_changesets = self.static_file_provider.get_range_with_static_file_or_database(
+            StaticFileSegment::AccountChangeSets,
+            self.block_number..BlockNumber::MAX,
+            |static_file, range, _| static_file.account_changesets_range(range),
+            |range, _| {
+                self.tx
+                    .cursor_read::<tables::AccountChangeSets>()?
+                    .walk_range(range)?
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(Into::into)
+            },
+            |_| true,
+        )?;
+        let storage_changesets = self.static_file_provider.get_range_with_static_file_or_database(
+            StaticFileSegment::StorageChangeSets,
+            self.block_number..BlockNumber::MAX,
+            |static_file, range, _| static_file.storage_changesets_range(range),
+            |range, _| {
+                self.tx
+                    .cursor_read::<tables::StorageChangeSets>()?
+                    .walk_range(BlockNumberAddress((range.start, Address::ZERO))..)?
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(Into::into)
+            },
+            |_| true,
+        )?;
+
+        HashedPostState::from_changesets(
+            account_changesets.into_iter().map(|(_, account)| Ok(account)),
+            storage_changesets
+                .into_iter()
+                .map(|(BlockNumberAddress((_, address)), storage)| Ok((address, storage))),
+        )
     }
 
     fn history_info<T, K>(
@@ -204,17 +321,31 @@ impl<'b, TX: DbTx> AccountReader for HistoricalStateProviderRef<'b, TX> {
         match self.account_history_lookup(address)? {
             HistoryInfo::NotYetWritten => Ok(None),
             HistoryInfo::InChangeset(changeset_block_number) => Ok(self
-                .tx
-                .cursor_dup_read::<tables::AccountChangeSets>()?
-                .seek_by_key_subkey(changeset_block_number, address)?
-                .filter(|acc| acc.address == address)
+                .static_file_provider
+                .get_with_static_file_or_database(
+                    StaticFileSegment::AccountChangeSets,
+                    changeset_block_number,
+                    |static_file| {
+                        static_file.account_changeset_entry(changeset_block_number, address)
+                    },
+                    || {
+                        Ok(self
+                            .tx
+                            .cursor_dup_read::<tables::AccountChangeSets>()?
+                            .seek_by_key_subkey(changeset_block_number, address)?
+                            .filter(|acc| acc.address == address))
+                    },
+                )?
                 .ok_or(ProviderError::AccountChangesetNotFound {
                     block_number: changeset_block_number,
                     address,
                 })?
                 .info),
             HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => {
-                Ok(self.tx.get::<tables::PlainAccountState>(address)?)
+                if let Some(account) = self.tx.get::<tables::PlainAccountState>(address)? {
+                    return Ok(Some(account))
+                }
+                self.hashed_state_fallback.basic_account(self.tx, address)
             }
         }
     }
@@ -258,9 +389,7 @@ impl<'b, TX: DbTx> BlockHashReader for HistoricalStateProviderRef<'b, TX> {
 
 impl<'b, TX: DbTx> StateRootProvider for HistoricalStateProviderRef<'b, TX> {
     fn hashed_state_root(&self, hashed_state: &HashedPostState) -> ProviderResult<B256> {
-        let mut revert_state = self.revert_state()?;
-        revert_state.extend(hashed_state.clone());
-        StateRoot::overlay_root(self.tx, revert_state)
+        StateRoot::overlay_root(self.tx, self.revert_state_with(hashed_state)?)
             .map_err(|err| ProviderError::Database(err.into()))
     }
 
@@ -268,9 +397,7 @@ impl<'b, TX: DbTx> StateRootProvider for HistoricalStateProviderRef<'b, TX> {
         &self,
         hashed_state: &HashedPostState,
     ) -> ProviderResult<(B256, TrieUpdates)> {
-        let mut revert_state = self.revert_state()?;
-        revert_state.extend(hashed_state.clone());
-        StateRoot::overlay_root_with_updates(self.tx, revert_state)
+        StateRoot::overlay_root_with_updates(self.tx, self.revert_state_with(hashed_state)?)
             .map_err(|err| ProviderError::Database(err.into()))
     }
 }
@@ -283,14 +410,38 @@ impl<'b, TX: DbTx> StateProofProvider for HistoricalStateProviderRef<'b, TX> {
         address: Address,
         slots: &[B256],
     ) -> ProviderResult<AccountProof> {
-        let mut revert_state = self.revert_state()?;
-        revert_state.extend(hashed_state.clone());
-        revert_state
+        self.revert_state_with(hashed_state)?
             .account_proof(self.tx, address, slots)
             .map_err(|err| ProviderError::Database(err.into()))
     }
 }
 
+impl<'b, TX: DbTx> HashedStateRangeProvider for HistoricalStateProviderRef<'b, TX> {
+    fn hashed_account_range(
+        &self,
+        hashed_state: &HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedRange<Account>> {
+        Ok(self.sorted_revert_state_with(hashed_state)?.account_range(self.tx, start, limit)?)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_state: &HashedPostState,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedRange<U256>> {
+        Ok(self.sorted_revert_state_with(hashed_state)?.storage_range(
+            self.tx,
+            hashed_address,
+            start,
+            limit,
+        )?)
+    }
+}
+
 impl<'b, TX: DbTx> StateProvider for HistoricalStateProviderRef<'b, TX> {
     /// Get storage.
     fn storage(
@@ -301,10 +452,28 @@ impl<'b, TX: DbTx> StateProvider for HistoricalStateProviderRef<'b, TX> {
         match self.storage_history_lookup(address, storage_key)? {
             HistoryInfo::NotYetWritten => Ok(None),
             HistoryInfo::InChangeset(changeset_block_number) => Ok(Some(
-                self.tx
-                    .cursor_dup_read::<tables::StorageChangeSets>()?
-                    .seek_by_key_subkey((changeset_block_number, address).into(), storage_key)?
-                    .filter(|entry| entry.key == storage_key)
+                self.static_file_provider
+                    .get_with_static_file_or_database(
+                        StaticFileSegment::StorageChangeSets,
+                        changeset_block_number,
+                        |static_file| {
+                            static_file.storage_changeset_entry(
+                                changeset_block_number,
+                                address,
+                                storage_key,
+                            )
+                        },
+                        || {
+                            Ok(self
+                                .tx
+                                .cursor_dup_read::<tables::StorageChangeSets>()?
+                                .seek_by_key_subkey(
+                                    (changeset_block_number, address).into(),
+                                    storage_key,
+                                )?
+                                .filter(|entry| entry.key == storage_key))
+                        },
+                    )?
                     .ok_or_else(|| ProviderError::StorageChangesetNotFound {
                         block_number: changeset_block_number,
                         address,
@@ -312,13 +481,21 @@ impl<'b, TX: DbTx> StateProvider for HistoricalStateProviderRef<'b, TX> {
                     })?
                     .value,
             )),
-            HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => Ok(self
-                .tx
-                .cursor_dup_read::<tables::PlainStorageState>()?
-                .seek_by_key_subkey(address, storage_key)?
-                .filter(|entry| entry.key == storage_key)
-                .map(|entry| entry.value)
-                .or(Some(StorageValue::ZERO))),
+            HistoryInfo::InPlainState | HistoryInfo::MaybeInPlainState => {
+                if let Some(entry) = self
+                    .tx
+                    .cursor_dup_read::<tables::PlainStorageState>()?
+                    .seek_by_key_subkey(address, storage_key)?
+                    .filter(|entry| entry.key == storage_key)
+                {
+                    return Ok(Some(entry.value))
+                }
+                Ok(Some(
+                    self.hashed_state_fallback
+                        .storage(self.tx, address, storage_key)?
+                        .unwrap_or(StorageValue::ZERO),
+                ))
+            }
         }
     }
 
@@ -340,6 +517,8 @@ pub struct HistoricalStateProvider<TX: DbTx> {
     lowest_available_blocks: LowestAvailableBlocks,
     /// Static File provider
     static_file_provider: StaticFileProvider,
+    /// The reverts of the state after the block, shared by all requests to this provider.
+    revert_state: Arc<RevertStateCache>,
 }
 
 impl<TX: DbTx> HistoricalStateProvider<TX> {
@@ -349,7 +528,13 @@ impl<TX: DbTx> HistoricalStateProvider<TX> {
         block_number: BlockNumber,
         static_file_provider: StaticFileProvider,
     ) -> Self {
-        Self { tx, block_number, lowest_available_blocks: Default::default(), static_file_provider }
+        Self {
+            tx,
+            block_number,
+            lowest_available_blocks: Default::default(),
+            static_file_provider,
+            revert_state: Default::default(),
+        }
     }
 
     /// Set the lowest block number at which the account history is available.
@@ -373,12 +558,15 @@ impl<TX: DbTx> HistoricalStateProvider<TX> {
     /// Returns a new provider that takes the `TX` as reference
     #[inline(always)]
     fn as_ref(&self) -> HistoricalStateProviderRef<'_, TX> {
-        HistoricalStateProviderRef::new_with_lowest_available_blocks(
-            &self.tx,
-            self.block_number,
-            self.lowest_available_blocks,
-            self.static_file_provider.clone(),
-        )
+        HistoricalStateProviderRef {
+            revert_state: Arc::clone(&self.revert_state),
+            ..HistoricalStateProviderRef::new_with_lowest_available_blocks(
+                &self.tx,
+                self.block_number,
+                self.lowest_available_blocks,
+                self.static_file_provider.clone(),
+            )
+        }
     }
 }
 
@@ -1,5 +1,8 @@
 use crate::{
-    providers::{state::macros::delegate_provider_impls, StaticFileProvider},
+    providers::{
+        state::{macros::delegate_provider_impls, snap::HashedStateFallback},
+        StaticFileProvider,
+    },
     AccountReader, BlockHashReader, StateProvider, StateRootProvider,
 };
 use reth_db::tables;
@@ -9,32 +12,41 @@ use reth_db_api::{
 };
 use reth_primitives::{
     Account, Address, BlockNumber, Bytecode, StaticFileSegment, StorageKey, StorageValue, B256,
+    U256,
 };
-use reth_storage_api::StateProofProvider;
+use reth_storage_api::{HashedStateRangeProvider, StateProofProvider};
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
-use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState, StateRoot};
+use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState, HashedRange, StateRoot};
 use reth_trie_db::DatabaseStateRoot;
 
 /// State provider over latest state that takes tx reference.
+///
+/// If the state was downloaded over `snap`, accounts and storage slots missing from the plain
+/// state are read from the hashed state.
 #[derive(Debug)]
 pub struct LatestStateProviderRef<'b, TX: DbTx> {
     /// database transaction
     tx: &'b TX,
     /// Static File provider
     static_file_provider: StaticFileProvider,
+    /// Reads the state missing from the plain state of snap synced nodes.
+    hashed_state_fallback: HashedStateFallback,
 }
 
 impl<'b, TX: DbTx> LatestStateProviderRef<'b, TX> {
     /// Create new state provider
     pub const fn new(tx: &'b TX, static_file_provider: StaticFileProvider) -> Self {
-        Self { tx, static_file_provider }
+        Self { tx, static_file_provider, hashed_state_fallback: HashedStateFallback::new() }
     }
 }
 
 impl<'b, TX: DbTx> AccountReader for LatestStateProviderRef<'b, TX> {
     /// Get basic account information.
     fn basic_account(&self, address: Address) -> ProviderResult<Option<Account>> {
-        self.tx.get::<tables::PlainAccountState>(address).map_err(Into::into)
+        if let Some(account) = self.tx.get::<tables::PlainAccountState>(address)? {
+            return Ok(Some(account))
+        }
+        self.hashed_state_fallback.basic_account(self.tx, address)
     }
 }
 
@@ -102,6 +114,27 @@ impl<'b, TX: DbTx> StateProofProvider for LatestStateProviderRef<'b, TX> {
     }
 }
 
+impl<'b, TX: DbTx> HashedStateRangeProvider for LatestStateProviderRef<'b, TX> {
+    fn hashed_account_range(
+        &self,
+        hashed_state: &HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedRange<Account>> {
+        Ok(hashed_state.account_range(self.tx, start, limit)?)
+    }
+
+    fn hashed_storage_range(
+        &self,
+        hashed_state: &HashedPostState,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedRange<U256>> {
+        Ok(hashed_state.storage_range(self.tx, hashed_address, start, limit)?)
+    }
+}
+
 impl<'b, TX: DbTx> StateProvider for LatestStateProviderRef<'b, TX> {
     /// Get storage.
     fn storage(
@@ -115,7 +148,7 @@ impl<'b, TX: DbTx> StateProvider for LatestStateProviderRef<'b, TX> {
                 return Ok(Some(entry.value))
             }
         }
-        Ok(None)
+        self.hashed_state_fallback.storage(self.tx, account, storage_key)
     }
 
     /// Get account code by its hash
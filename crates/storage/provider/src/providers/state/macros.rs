@@ -26,6 +26,9 @@ pub(crate) use delegate_impls_to_as_ref;
 /// [`AccountReader`](crate::AccountReader)
 /// [`BlockHashReader`](crate::BlockHashReader)
 /// [`StateProvider`](crate::StateProvider)
+/// [`StateRootProvider`](crate::StateRootProvider)
+/// [`StateProofProvider`](crate::StateProofProvider)
+/// [`HashedStateRangeProvider`](crate::HashedStateRangeProvider)
 macro_rules! delegate_provider_impls {
     ($target:ty $(where [$($generics:tt)*])?) => {
         $crate::providers::state::macros::delegate_impls_to_as_ref!(
@@ -51,6 +54,10 @@ macro_rules! delegate_provider_impls {
                 fn proof(&self, state: &revm::db::BundleState, address: reth_primitives::Address, slots: &[reth_primitives::B256]) -> reth_storage_errors::provider::ProviderResult<reth_trie::AccountProof>;
                 fn hashed_proof(&self, state: &reth_trie::HashedPostState, address: reth_primitives::Address, slots: &[reth_primitives::B256]) -> reth_storage_errors::provider::ProviderResult<reth_trie::AccountProof>;
             }
+            HashedStateRangeProvider $(where [$($generics)*])? {
+                fn hashed_account_range(&self, state: &reth_trie::HashedPostState, start: reth_primitives::B256, limit: usize) -> reth_storage_errors::provider::ProviderResult<reth_trie::HashedRange<reth_primitives::Account>>;
+                fn hashed_storage_range(&self, state: &reth_trie::HashedPostState, hashed_address: reth_primitives::B256, start: reth_primitives::B256, limit: usize) -> reth_storage_errors::provider::ProviderResult<reth_trie::HashedRange<reth_primitives::U256>>;
+            }
         );
     }
 }
@@ -0,0 +1,86 @@
+use reth_codecs::Compact;
+use reth_db::tables;
+use reth_db_api::{cursor::DbDupCursorRO, transaction::DbTx};
+use reth_primitives::{keccak256, Account, Address, BlockNumber, StorageKey, StorageValue};
+use reth_stages_types::{SnapSyncCheckpoint, SnapSyncPhase, StageId};
+use reth_storage_errors::provider::ProviderResult;
+use std::sync::OnceLock;
+
+/// Returns `true` if the state was downloaded over `snap`, instead of executing all blocks.
+///
+/// The plain state of such nodes only has the accounts and storage slots that changed after the
+/// pivot block of the `SnapSync` stage, the rest of the state is only in the hashed state tables.
+///
+/// The state is only complete once the snap sync finished, see [`decode_snap_sync_pivot`].
+pub fn is_snap_synced<TX: DbTx>(tx: &TX) -> ProviderResult<bool> {
+    Ok(tx
+        .get::<tables::StageCheckpointProgresses>(StageId::SnapSync.to_string())?
+        .is_some_and(|progress| decode_snap_sync_pivot(&progress).is_some()))
+}
+
+/// Decodes the pivot block from the checkpoint progress of the `SnapSync` stage.
+///
+/// Returns `None` if the snap sync didn't start, or didn't finish yet.
+pub fn decode_snap_sync_pivot(progress: &[u8]) -> Option<BlockNumber> {
+    if progress.is_empty() {
+        return None
+    }
+
+    let (checkpoint, _) = SnapSyncCheckpoint::from_compact(progress, progress.len());
+    (checkpoint.phase == SnapSyncPhase::Done).then_some(checkpoint.pivot)
+}
+
+/// Reads the accounts and storage slots missing from the plain state from the hashed state, if the
+/// state was downloaded over `snap`.
+///
+/// See [`is_snap_synced`].
+#[derive(Debug, Default)]
+pub(crate) struct HashedStateFallback {
+    /// Whether the state was downloaded over `snap`, looked up on the first miss.
+    snap_synced: OnceLock<bool>,
+}
+
+impl HashedStateFallback {
+    /// Creates a new instance.
+    pub(crate) const fn new() -> Self {
+        Self { snap_synced: OnceLock::new() }
+    }
+
+    fn is_enabled<TX: DbTx>(&self, tx: &TX) -> ProviderResult<bool> {
+        if let Some(enabled) = self.snap_synced.get() {
+            return Ok(*enabled)
+        }
+        let enabled = is_snap_synced(tx)?;
+        Ok(*self.snap_synced.get_or_init(|| enabled))
+    }
+
+    /// Returns the account from the hashed state, if enabled.
+    pub(crate) fn basic_account<TX: DbTx>(
+        &self,
+        tx: &TX,
+        address: Address,
+    ) -> ProviderResult<Option<Account>> {
+        if !self.is_enabled(tx)? {
+            return Ok(None)
+        }
+        Ok(tx.get::<tables::HashedAccounts>(keccak256(address))?)
+    }
+
+    /// Returns the storage slot from the hashed state, if enabled.
+    pub(crate) fn storage<TX: DbTx>(
+        &self,
+        tx: &TX,
+        address: Address,
+        storage_key: StorageKey,
+    ) -> ProviderResult<Option<StorageValue>> {
+        if !self.is_enabled(tx)? {
+            return Ok(None)
+        }
+        let hashed_key = keccak256(storage_key);
+        Ok(tx
+            .cursor_dup_read::<tables::HashedStorages>()?
+            .seek_by_key_subkey(keccak256(address), hashed_key)?
+            .filter(|entry| entry.key == hashed_key)
+            .map(|entry| entry.value))
+    }
+}
@@ -12,12 +12,18 @@ use parking_lot::RwLock;
 use reth_chainspec::ChainInfo;
 use reth_db::{
     lockfile::StorageLock,
-    static_file::{iter_static_files, HeaderMask, ReceiptMask, StaticFileCursor, TransactionMask},
+    static_file::{
+        iter_static_files, AccountChangeSetMask, HeaderMask, ReceiptMask, StaticFileCursor,
+        StorageChangeSetMask, TransactionMask,
+    },
     tables,
 };
 use reth_db_api::{
     cursor::DbCursorRO,
-    models::{CompactU256, StoredBlockBodyIndices},
+    models::{
+        AccountBeforeTx, BlockNumberAddress, CompactU256, StorageBeforeTx, StoredAccountChangeSet,
+        StoredBlockBodyIndices, StoredStorageChangeSet,
+    },
     table::Table,
     transaction::DbTx,
 };
@@ -26,9 +32,9 @@ use reth_primitives::{
     keccak256,
     static_file::{find_fixed_range, HighestStaticFiles, SegmentHeader, SegmentRangeInclusive},
     Address, Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithSenders, Header, Receipt,
-    SealedBlock, SealedBlockWithSenders, SealedHeader, StaticFileSegment, TransactionMeta,
-    TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal, Withdrawals, B256,
-    U256,
+    SealedBlock, SealedBlockWithSenders, SealedHeader, StaticFileSegment, StorageEntry,
+    TransactionMeta, TransactionSigned, TransactionSignedNoHash, TxHash, TxNumber, Withdrawal,
+    Withdrawals, B256, U256,
 };
 use reth_stages_types::{PipelineTarget, StageId};
 use reth_storage_errors::provider::{ProviderError, ProviderResult};
@@ -105,6 +111,8 @@ pub struct StaticFileProviderInner {
     /// Maintains a map which allows for concurrent access to different `NippyJars`, over different
     /// segments and ranges.
     map: DashMap<(BlockNumber, StaticFileSegment), LoadedJar>,
+    /// Min static file block for each segment
+    static_files_min_block: RwLock<HashMap<StaticFileSegment, u64>>,
     /// Max static file block for each segment
     static_files_max_block: RwLock<HashMap<StaticFileSegment, u64>>,
     /// Available static file block ranges on disk indexed by max transactions.
@@ -135,6 +143,7 @@ impl StaticFileProviderInner {
         let provider = Self {
             map: Default::default(),
             writers: Default::default(),
+            static_files_min_block: Default::default(),
             static_files_max_block: Default::default(),
             static_files_tx_index: Default::default(),
             path: path.as_ref().to_path_buf(),
@@ -318,6 +327,49 @@ impl StaticFileProvider {
         Ok(())
     }
 
+    /// Deletes all static files of a block based segment which only contain blocks below `block`.
+    /// The static file of the highest block is never deleted, since it's the one being written to.
+    ///
+    /// Returns the fixed block ranges of the deleted static files.
+    ///
+    /// CAUTION: destructive. Deletes files on disk.
+    pub fn delete_jars_below(
+        &self,
+        segment: StaticFileSegment,
+        block: BlockNumber,
+    ) -> ProviderResult<Vec<SegmentRangeInclusive>> {
+        debug_assert!(segment.is_block_based());
+
+        let (Some(lowest), Some(highest)) = (
+            self.get_lowest_static_file_block(segment),
+            self.get_highest_static_file_block(segment),
+        ) else {
+            return Ok(Vec::new())
+        };
+        let highest_start = find_fixed_range(highest).start();
+
+        let mut deleted = Vec::new();
+        let mut fixed_range = find_fixed_range(lowest);
+        while fixed_range.end() < block && fixed_range.start() < highest_start {
+            let jar = if let Some((_, jar)) = self.map.remove(&(fixed_range.end(), segment)) {
+                jar.jar
+            } else {
+                NippyJar::<SegmentHeader>::load(&self.path.join(segment.filename(&fixed_range)))
+                    .map_err(|e| ProviderError::NippyJar(e.to_string()))?
+            };
+            jar.delete().map_err(|e| ProviderError::NippyJar(e.to_string()))?;
+
+            // Update the index right away, so a failure to delete the next static file leaves it
+            // consistent with the files on disk.
+            self.static_files_min_block.write().insert(segment, fixed_range.end() + 1);
+
+            deleted.push(fixed_range);
+            fixed_range = find_fixed_range(fixed_range.end() + 1);
+        }
+
+        Ok(deleted)
+    }
+
     /// Given a segment and block range it returns a cached
     /// [`StaticFileJarProvider`]. TODO(joshie): we should check the size and pop N if there's too
     /// many.
@@ -400,6 +452,7 @@ impl StaticFileProvider {
         segment: StaticFileSegment,
         segment_max_block: Option<BlockNumber>,
     ) -> ProviderResult<()> {
+        let mut min_block = self.static_files_min_block.write();
         let mut max_block = self.static_files_max_block.write();
         let mut tx_index = self.static_files_tx_index.write();
 
@@ -409,6 +462,10 @@ impl StaticFileProvider {
                 max_block.insert(segment, segment_max_block);
                 let fixed_range = find_fixed_range(segment_max_block);
 
+                // If there was no static file for the segment, this is its first one
+                let fixed_range_start = fixed_range.start();
+                min_block.entry(segment).or_insert(fixed_range_start);
+
                 let jar = NippyJar::<SegmentHeader>::load(
                     &self.path.join(segment.filename(&fixed_range)),
                 )
@@ -443,12 +500,7 @@ impl StaticFileProvider {
                 } else if tx_index.get(&segment).map(|index| index.len()) == Some(1) {
                     // Only happens if we unwind all the txs/receipts from the first static file.
                     // Should only happen in test scenarios.
-                    if jar.user_header().expected_block_start() == 0 &&
-                        matches!(
-                            segment,
-                            StaticFileSegment::Receipts | StaticFileSegment::Transactions
-                        )
-                    {
+                    if jar.user_header().expected_block_start() == 0 && !segment.is_block_based() {
                         tx_index.remove(&segment);
                     }
                 }
@@ -461,6 +513,7 @@ impl StaticFileProvider {
             }
             None => {
                 tx_index.remove(&segment);
+                min_block.remove(&segment);
                 max_block.remove(&segment);
             }
         };
@@ -470,14 +523,22 @@ impl StaticFileProvider {
 
     /// Initializes the inner transaction and block index
     pub fn initialize_index(&self) -> ProviderResult<()> {
+        let mut min_block = self.static_files_min_block.write();
         let mut max_block = self.static_files_max_block.write();
         let mut tx_index = self.static_files_tx_index.write();
 
+        min_block.clear();
+        max_block.clear();
         tx_index.clear();
 
         for (segment, ranges) in
             iter_static_files(&self.path).map_err(|e| ProviderError::NippyJar(e.to_string()))?
         {
+            // Update first block for each segment
+            if let Some((block_range, _)) = ranges.first() {
+                min_block.insert(segment, find_fixed_range(block_range.start()).start());
+            }
+
             // Update last block for each segment
             if let Some((block_range, _)) = ranges.last() {
                 max_block.insert(segment, block_range.end());
@@ -503,6 +564,23 @@ impl StaticFileProvider {
         Ok(())
     }
 
+    /// Re-reads the index from disk and drops the cached providers of static files that may have
+    /// changed since they were loaded.
+    ///
+    /// Only the highest static file of each segment is appended to or truncated, so only its
+    /// provider and the providers of deleted static files are dropped. This is meant for read-only
+    /// providers of a directory that another process writes to.
+    pub fn reload_index(&self) -> ProviderResult<()> {
+        self.initialize_index()?;
+
+        let max_block = self.static_files_max_block.read();
+        self.map.retain(|(end, segment), _| {
+            max_block.get(segment).is_some_and(|max| *end < find_fixed_range(*max).end())
+        });
+
+        Ok(())
+    }
+
     /// Ensures that any broken invariants which cannot be healed on the spot return a pipeline
     /// target to unwind to.
     ///
@@ -560,6 +638,11 @@ impl StaticFileProvider {
             }
 
             let initial_highest_block = self.get_highest_static_file_block(segment);
+            if segment.is_change_sets() && initial_highest_block.is_none() {
+                // Changesets are only moved to static files on nodes which keep the full account
+                // and storage history, and the static files are only created once they are.
+                continue
+            }
 
             //  File consistency is broken if:
             //
@@ -643,6 +726,10 @@ impl StaticFileProvider {
                     highest_tx,
                     highest_block,
                 )?,
+                StaticFileSegment::AccountChangeSets | StaticFileSegment::StorageChangeSets => {
+                    self.ensure_changeset_invariants(provider, segment, highest_block)?;
+                    None
+                }
             } {
                 update_unwind_target(unwind);
             }
@@ -707,7 +794,9 @@ impl StaticFileProvider {
             .get_stage_checkpoint(match segment {
                 StaticFileSegment::Headers => StageId::Headers,
                 StaticFileSegment::Transactions => StageId::Bodies,
-                StaticFileSegment::Receipts => StageId::Execution,
+                StaticFileSegment::Receipts |
+                StaticFileSegment::AccountChangeSets |
+                StaticFileSegment::StorageChangeSets => StageId::Execution,
             })?
             .unwrap_or_default()
             .block_number;
@@ -752,6 +841,51 @@ impl StaticFileProvider {
         Ok(None)
     }
 
+    /// Check invariants for the changeset static file segments:
+    ///
+    /// * its highest block should not be higher than the [`StageId::Execution`] checkpoint. If it
+    ///   is, then we failed to do a database commit **but committed** to static files on unwinding
+    ///   the stage, and the extra static file rows are removed.
+    ///
+    /// Unlike the other segments, changesets are only moved to static files once their blocks are
+    /// finalized, so the checkpoint being ahead of the static files is expected. Since blocks might
+    /// not change any state, the database table keys are not expected to be contiguous either.
+    fn ensure_changeset_invariants<TX: DbTx>(
+        &self,
+        provider: &DatabaseProvider<TX>,
+        segment: StaticFileSegment,
+        highest_static_file_block: Option<BlockNumber>,
+    ) -> ProviderResult<()> {
+        let highest_static_file_block = highest_static_file_block.unwrap_or_default();
+        let checkpoint_block_number =
+            provider.get_stage_checkpoint(StageId::Execution)?.unwrap_or_default().block_number;
+
+        if checkpoint_block_number < highest_static_file_block {
+            info!(
+                target: "reth::providers",
+                ?segment,
+                from = highest_static_file_block,
+                to = checkpoint_block_number,
+                "Unwinding static file segment."
+            );
+            let mut writer = self.latest_writer(segment)?;
+            writer.prune_changesets(highest_static_file_block - checkpoint_block_number)?;
+            writer.commit()?;
+        }
+
+        Ok(())
+    }
+
+    /// Gets the lowest static file block if it exists for a static file segment.
+    ///
+    /// It's the first block of the lowest static file on disk, which is only higher than zero if
+    /// lower static files were deleted by [`Self::delete_jars_below`].
+    ///
+    /// If there is nothing on disk for the given segment, this will return [`None`].
+    pub fn get_lowest_static_file_block(&self, segment: StaticFileSegment) -> Option<BlockNumber> {
+        self.static_files_min_block.read().get(&segment).copied()
+    }
+
     /// Gets the highest static file block if it exists for a static file segment.
     ///
     /// If there is nothing on disk for the given segment, this will return [`None`].
@@ -775,6 +909,10 @@ impl StaticFileProvider {
             headers: self.get_highest_static_file_block(StaticFileSegment::Headers),
             receipts: self.get_highest_static_file_block(StaticFileSegment::Receipts),
             transactions: self.get_highest_static_file_block(StaticFileSegment::Transactions),
+            account_changesets: self
+                .get_highest_static_file_block(StaticFileSegment::AccountChangeSets),
+            storage_changesets: self
+                .get_highest_static_file_block(StaticFileSegment::StorageChangeSets),
         }
     }
 
@@ -817,11 +955,10 @@ impl StaticFileProvider {
         F: FnMut(&mut StaticFileCursor<'_>, u64) -> ProviderResult<Option<T>>,
         P: FnMut(&T) -> bool,
     {
-        let get_provider = |start: u64| match segment {
-            StaticFileSegment::Headers => {
+        let get_provider = |start: u64| {
+            if segment.is_block_based() {
                 self.get_segment_provider_from_block(segment, start, None)
-            }
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            } else {
                 self.get_segment_provider_from_transaction(segment, start, None)
             }
         };
@@ -856,7 +993,7 @@ impl StaticFileProvider {
                                 "Could not find block or tx number on a range request"
                             );
 
-                            let err = if segment.is_headers() {
+                            let err = if segment.is_block_based() {
                                 ProviderError::MissingStaticFileBlock(segment, number)
                             } else {
                                 ProviderError::MissingStaticFileTx(segment, number)
@@ -887,11 +1024,10 @@ impl StaticFileProvider {
         F: Fn(&mut StaticFileCursor<'_>, u64) -> ProviderResult<Option<T>> + 'a,
         T: std::fmt::Debug,
     {
-        let get_provider = move |start: u64| match segment {
-            StaticFileSegment::Headers => {
+        let get_provider = move |start: u64| {
+            if segment.is_block_based() {
                 self.get_segment_provider_from_block(segment, start, None)
-            }
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
+            } else {
                 self.get_segment_provider_from_transaction(segment, start, None)
             }
         };
@@ -934,11 +1070,10 @@ impl StaticFileProvider {
         FD: Fn() -> ProviderResult<Option<T>>,
     {
         // If there is, check the maximum block or transaction number of the segment.
-        let static_file_upper_bound = match segment {
-            StaticFileSegment::Headers => self.get_highest_static_file_block(segment),
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
-                self.get_highest_static_file_tx(segment)
-            }
+        let static_file_upper_bound = if segment.is_block_based() {
+            self.get_highest_static_file_block(segment)
+        } else {
+            self.get_highest_static_file_tx(segment)
         };
 
         if static_file_upper_bound
@@ -976,11 +1111,10 @@ impl StaticFileProvider {
         let mut data = Vec::new();
 
         // If there is, check the maximum block or transaction number of the segment.
-        if let Some(static_file_upper_bound) = match segment {
-            StaticFileSegment::Headers => self.get_highest_static_file_block(segment),
-            StaticFileSegment::Transactions | StaticFileSegment::Receipts => {
-                self.get_highest_static_file_tx(segment)
-            }
+        if let Some(static_file_upper_bound) = if segment.is_block_based() {
+            self.get_highest_static_file_block(segment)
+        } else {
+            self.get_highest_static_file_tx(segment)
         } {
             if block_or_tx_range.start <= static_file_upper_bound {
                 let end = block_or_tx_range.end.min(static_file_upper_bound + 1);
@@ -1000,6 +1134,149 @@ impl StaticFileProvider {
         Ok(data)
     }
 
+    /// Returns the account changesets of the given range of blocks, in the same order and layout
+    /// as they are stored in [`tables::AccountChangeSets`].
+    pub fn account_changesets_range(
+        &self,
+        range: impl RangeBounds<BlockNumber>,
+    ) -> ProviderResult<Vec<(BlockNumber, AccountBeforeTx)>> {
+        let changesets = self.fetch_range_with_predicate(
+            StaticFileSegment::AccountChangeSets,
+            self.available_changesets_range(StaticFileSegment::AccountChangeSets, range),
+            |cursor, number| {
+                Ok(cursor
+                    .get_one::<AccountChangeSetMask<StoredAccountChangeSet>>(number.into())?
+                    .map(|changeset| (number, changeset)))
+            },
+            |_| true,
+        )?;
+
+        Ok(changesets
+            .into_iter()
+            .flat_map(|(block_number, changeset)| {
+                changeset.changes.into_iter().map(move |account| (block_number, account))
+            })
+            .collect())
+    }
+
+    /// Returns the storage changesets of the given range of blocks, in the same order and layout
+    /// as they are stored in [`tables::StorageChangeSets`].
+    pub fn storage_changesets_range(
+        &self,
+        range: impl RangeBounds<BlockNumber>,
+    ) -> ProviderResult<Vec<(BlockNumberAddress, StorageEntry)>> {
+        let changesets = self.fetch_range_with_predicate(
+            StaticFileSegment::StorageChangeSets,
+            self.available_changesets_range(StaticFileSegment::StorageChangeSets, range),
+            |cursor, number| {
+                Ok(cursor
+                    .get_one::<StorageChangeSetMask<StoredStorageChangeSet>>(number.into())?
+                    .map(|changeset| (number, changeset)))
+            },
+            |_| true,
+        )?;
+
+        Ok(changesets
+            .into_iter()
+            .flat_map(|(block_number, changeset)| {
+                changeset.changes.into_iter().map(move |StorageBeforeTx { address, key, value }| {
+                    (BlockNumberAddress((block_number, address)), StorageEntry { key, value })
+                })
+            })
+            .collect())
+    }
+
+    /// Returns the entry of the account in the account changeset of the given block.
+    ///
+    /// The entries of a changeset are ordered by address, so only the changeset of the block is
+    /// decoded and the account is binary searched in it.
+    pub fn account_changeset_entry(
+        &self,
+        block_number: BlockNumber,
+        address: Address,
+    ) -> ProviderResult<Option<AccountBeforeTx>> {
+        let Some(mut changeset) = self
+            .changeset_provider(StaticFileSegment::AccountChangeSets, block_number)?
+            .map(|provider| {
+                provider
+                    .cursor()?
+                    .get_one::<AccountChangeSetMask<StoredAccountChangeSet>>(block_number.into())
+            })
+            .transpose()?
+            .flatten()
+        else {
+            return Ok(None)
+        };
+
+        Ok(changeset
+            .changes
+            .binary_search_by_key(&address, |account| account.address)
+            .ok()
+            .map(|index| changeset.changes.swap_remove(index)))
+    }
+
+    /// Returns the entry of the storage slot in the storage changeset of the given block.
+    ///
+    /// The entries of a changeset are ordered by address and storage key, so only the changeset
+    /// of the block is decoded and the slot is binary searched in it.
+    pub fn storage_changeset_entry(
+        &self,
+        block_number: BlockNumber,
+        address: Address,
+        storage_key: B256,
+    ) -> ProviderResult<Option<StorageEntry>> {
+        let Some(mut changeset) = self
+            .changeset_provider(StaticFileSegment::StorageChangeSets, block_number)?
+            .map(|provider| {
+                provider
+                    .cursor()?
+                    .get_one::<StorageChangeSetMask<StoredStorageChangeSet>>(block_number.into())
+            })
+            .transpose()?
+            .flatten()
+        else {
+            return Ok(None)
+        };
+
+        Ok(changeset
+            .changes
+            .binary_search_by(|entry| (entry.address, entry.key).cmp(&(address, storage_key)))
+            .ok()
+            .map(|index| {
+                let StorageBeforeTx { key, value, .. } = changeset.changes.swap_remove(index);
+                StorageEntry { key, value }
+            }))
+    }
+
+    /// Returns the provider of the changeset segment static file containing the block, or `None`
+    /// if the changeset of the block was pruned.
+    fn changeset_provider(
+        &self,
+        segment: StaticFileSegment,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Option<StaticFileJarProvider<'_>>> {
+        if self.available_changesets_range(segment, block_number..=block_number).is_empty() {
+            return Ok(None)
+        }
+        match self.get_segment_provider_from_block(segment, block_number, None) {
+            Ok(provider) => Ok(Some(provider)),
+            Err(ProviderError::MissingStaticFileBlock(_, _)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Returns the part of the block range that wasn't deleted from the static files of the
+    /// changeset segment, because its changesets were pruned.
+    fn available_changesets_range(
+        &self,
+        segment: StaticFileSegment,
+        range: impl RangeBounds<BlockNumber>,
+    ) -> Range<BlockNumber> {
+        let range = to_range(range);
+        let lowest = self.get_lowest_static_file_block(segment).unwrap_or_default();
+        range.start.max(lowest)..range.end.max(lowest)
+    }
+
     #[cfg(any(test, feature = "test-utils"))]
     /// Returns `static_files` directory
     pub fn path(&self) -> &Path {
@@ -1,7 +1,10 @@
 use crate::{providers::StaticFileProvider, ProviderFactory};
 use reth_chainspec::{ChainSpec, MAINNET};
 use reth_db::{
-    test_utils::{create_test_rw_db, create_test_static_files_dir, TempDatabase},
+    mem::MemDatabase,
+    test_utils::{
+        create_test_mem_db, create_test_rw_db, create_test_static_files_dir, TempDatabase,
+    },
     DatabaseEnv,
 };
 use std::sync::Arc;
@@ -31,3 +34,13 @@ pub fn create_test_provider_factory_with_chain_spec(
         StaticFileProvider::read_write(static_dir.into_path()).expect("static file provider"),
     )
 }
+
+/// Creates test provider factory with mainnet chain spec, backed by an in-memory database.
+pub fn create_test_provider_factory_with_mem_db() -> ProviderFactory<Arc<MemDatabase>> {
+    let (static_dir, _) = create_test_static_files_dir();
+    ProviderFactory::new(
+        create_test_mem_db(),
+        MAINNET.clone(),
+        StaticFileProvider::read_write(static_dir.into_path()).expect("static file provider"),
+    )
+}
@@ -1,4 +1,5 @@
 use std::{
+    collections::BTreeSet,
     ops::{RangeBounds, RangeInclusive},
     sync::Arc,
 };
@@ -15,20 +16,21 @@ use reth_primitives::{
 };
 use reth_prune_types::{PruneCheckpoint, PruneSegment};
 use reth_stages_types::{StageCheckpoint, StageId};
-use reth_storage_api::StateProofProvider;
+use reth_storage_api::{HashedStateRangeProvider, StateProofProvider};
 use reth_storage_errors::provider::ProviderResult;
-use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState};
+use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState, HashedRange};
 use revm::primitives::{BlockEnv, CfgEnvWithHandlerCfg};
 use tokio::sync::broadcast;
 
 use crate::{
     providers::StaticFileProvider,
     traits::{BlockSource, ReceiptProvider},
-    AccountReader, BlockHashReader, BlockIdReader, BlockNumReader, BlockReader, BlockReaderIdExt,
-    ChainSpecProvider, ChangeSetReader, EvmEnvProvider, HeaderProvider, PruneCheckpointReader,
-    ReceiptProviderIdExt, RequestsProvider, StageCheckpointReader, StateProvider, StateProviderBox,
-    StateProviderFactory, StateRootProvider, StaticFileProviderFactory, TransactionVariant,
-    TransactionsProvider, WithdrawalsProvider,
+    AccountReader, AddressAppearancesReader, BlockHashReader, BlockIdReader, BlockNumReader,
+    BlockReader, BlockReaderIdExt, ChainSpecProvider, ChangeSetReader, EvmEnvProvider,
+    HeaderProvider, PruneCheckpointReader, ReceiptProviderIdExt, RequestsProvider,
+    StageCheckpointReader, StateProvider, StateProviderBox, StateProviderFactory,
+    StateRootProvider, StaticFileProviderFactory, TransactionVariant, TransactionsProvider,
+    WithdrawalsProvider,
 };
 
 /// Supports various api interfaces for testing purposes.
@@ -309,6 +311,13 @@ impl ChangeSetReader for NoopProvider {
     ) -> ProviderResult<Vec<AccountBeforeTx>> {
         Ok(Vec::default())
     }
+
+    fn changed_addresses_with_range(
+        &self,
+        _range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeSet<Address>> {
+        Ok(BTreeSet::new())
+    }
 }
 
 impl StateRootProvider for NoopProvider {
@@ -335,6 +344,27 @@ impl StateProofProvider for NoopProvider {
     }
 }
 
+impl HashedStateRangeProvider for NoopProvider {
+    fn hashed_account_range(
+        &self,
+        _hashed_state: &HashedPostState,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<HashedRange<Account>> {
+        Ok(HashedRange::default())
+    }
+
+    fn hashed_storage_range(
+        &self,
+        _hashed_state: &HashedPostState,
+        _hashed_address: B256,
+        _start: B256,
+        _limit: usize,
+    ) -> ProviderResult<HashedRange<U256>> {
+        Ok(HashedRange::default())
+    }
+}
+
 impl StateProvider for NoopProvider {
     fn storage(
         &self,
@@ -434,6 +464,38 @@ impl StateProviderFactory for NoopProvider {
     }
 }
 
+impl AddressAppearancesReader for NoopProvider {
+    fn address_appearances_checkpoint(&self) -> ProviderResult<Option<BlockNumber>> {
+        Ok(None)
+    }
+
+    fn address_appearances_before(
+        &self,
+        _address: Address,
+        _block_number: BlockNumber,
+        _limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        Ok(Vec::new())
+    }
+
+    fn address_appearances_after(
+        &self,
+        _address: Address,
+        _block_number: BlockNumber,
+        _limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>> {
+        Ok(Vec::new())
+    }
+
+    fn address_appearances_in_block(
+        &self,
+        _address: Address,
+        _block_number: BlockNumber,
+    ) -> ProviderResult<Vec<usize>> {
+        Ok(Vec::new())
+    }
+}
+
 impl StageCheckpointReader for NoopProvider {
     fn get_stage_checkpoint(&self, _id: StageId) -> ProviderResult<Option<StageCheckpoint>> {
         Ok(None)
@@ -1,9 +1,9 @@
 //! Helper provider traits to encapsulate all provider traits for simplicity.
 
 use crate::{
-    AccountReader, BlockReaderIdExt, ChainSpecProvider, ChangeSetReader, DatabaseProviderFactory,
-    EvmEnvProvider, HeaderProvider, StageCheckpointReader, StateProviderFactory,
-    StaticFileProviderFactory, TransactionsProvider,
+    AccountReader, AddressAppearancesReader, BlockReaderIdExt, ChainSpecProvider, ChangeSetReader,
+    DatabaseProviderFactory, EvmEnvProvider, HeaderProvider, StageCheckpointReader,
+    StateProviderFactory, StaticFileProviderFactory, TransactionsProvider,
 };
 use reth_chain_state::CanonStateSubscriptions;
 use reth_db_api::database::Database;
@@ -18,6 +18,7 @@ pub trait FullProvider<DB: Database>:
     + EvmEnvProvider
     + ChainSpecProvider
     + ChangeSetReader
+    + AddressAppearancesReader
     + CanonStateSubscriptions
     + StageCheckpointReader
     + Clone
@@ -35,6 +36,7 @@ impl<T, DB: Database> FullProvider<DB> for T where
         + EvmEnvProvider
         + ChainSpecProvider
         + ChangeSetReader
+        + AddressAppearancesReader
         + CanonStateSubscriptions
         + StageCheckpointReader
         + Clone
@@ -52,6 +54,7 @@ pub trait FullRpcProvider:
     + BlockReaderIdExt
     + HeaderProvider
     + TransactionsProvider
+    + AddressAppearancesReader
     + Clone
     + Unpin
     + 'static
@@ -65,6 +68,7 @@ impl<T> FullRpcProvider for T where
         + BlockReaderIdExt
         + HeaderProvider
         + TransactionsProvider
+        + AddressAppearancesReader
         + Clone
         + Unpin
         + 'static
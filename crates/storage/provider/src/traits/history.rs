@@ -1,9 +1,9 @@
 use auto_impl::auto_impl;
 use reth_db_api::models::BlockNumberAddress;
-use reth_primitives::{Address, BlockNumber, B256};
+use reth_primitives::{Address, BlockNumber, TxNumber, B256};
 use reth_storage_errors::provider::ProviderResult;
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     ops::{Range, RangeInclusive},
 };
 
@@ -38,6 +38,27 @@ pub trait HistoryWriter: Send + Sync {
         storage_transitions: BTreeMap<(Address, B256), Vec<u64>>,
     ) -> ProviderResult<()>;
 
+    /// Unwind and clear address appearance indices.
+    ///
+    /// Returns number of addresses unwound.
+    fn unwind_address_appearance_indices(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<usize>;
+
+    /// Insert address appearance index to database. Used inside `IndexAddressAppearances` stage
+    fn insert_address_appearance_index(
+        &self,
+        address_appearances: BTreeMap<Address, Vec<u64>>,
+    ) -> ProviderResult<()>;
+
+    /// Insert the addresses that appear in each transaction to database. Used inside
+    /// `IndexAddressAppearances` stage
+    fn insert_transaction_address_appearances(
+        &self,
+        transaction_appearances: BTreeMap<TxNumber, BTreeSet<Address>>,
+    ) -> ProviderResult<()>;
+
     /// Read account/storage changesets and update account/storage history indices.
     fn update_history_indices(&self, range: RangeInclusive<BlockNumber>) -> ProviderResult<()>;
 }
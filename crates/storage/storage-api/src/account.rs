@@ -52,4 +52,49 @@ pub trait ChangeSetReader: Send + Sync {
         &self,
         block_number: BlockNumber,
     ) -> ProviderResult<Vec<AccountBeforeTx>>;
+
+    /// Returns the addresses of all accounts whose info or storage changed in the given range of
+    /// blocks.
+    ///
+    /// NOTE: Get inclusive range of blocks.
+    fn changed_addresses_with_range(
+        &self,
+        range: RangeInclusive<BlockNumber>,
+    ) -> ProviderResult<BTreeSet<Address>>;
+}
+
+/// Reader for the index of blocks in which an address appears, see the `AddressAppearances`
+/// table.
+#[auto_impl(&, Arc, Box)]
+pub trait AddressAppearancesReader: Send + Sync {
+    /// Returns the highest block covered by the index.
+    ///
+    /// Returns `None` if the index is not maintained.
+    fn address_appearances_checkpoint(&self) -> ProviderResult<Option<BlockNumber>>;
+
+    /// Returns up to `limit` blocks lower than `block_number` in which the address appears, in
+    /// descending order.
+    fn address_appearances_before(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>>;
+
+    /// Returns up to `limit` blocks higher than `block_number` in which the address appears, in
+    /// ascending order.
+    fn address_appearances_after(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+        limit: usize,
+    ) -> ProviderResult<Vec<BlockNumber>>;
+
+    /// Returns the indices of the transactions of the block in which the address appears, in
+    /// ascending order.
+    fn address_appearances_in_block(
+        &self,
+        address: Address,
+        block_number: BlockNumber,
+    ) -> ProviderResult<Vec<usize>>;
 }
@@ -1,6 +1,6 @@
-use reth_primitives::{Address, B256};
+use reth_primitives::{Account, Address, B256, U256};
 use reth_storage_errors::provider::ProviderResult;
-use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState};
+use reth_trie::{updates::TrieUpdates, AccountProof, HashedPostState, HashedRange};
 use revm::db::BundleState;
 
 /// A type that can compute the state root of a given post state.
@@ -63,3 +63,27 @@ pub trait StateProofProvider: Send + Sync {
         slots: &[B256],
     ) -> ProviderResult<AccountProof>;
 }
+
+/// A type that can iterate over the hashed state on top of a given post state.
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait HashedStateRangeProvider: Send + Sync {
+    /// Returns up to `limit` accounts of the `HashedPostState` on top of the current state,
+    /// ordered by their hashed address and starting at the given hashed address.
+    fn hashed_account_range(
+        &self,
+        hashed_state: &HashedPostState,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedRange<Account>>;
+
+    /// Returns up to `limit` storage slots of the account with the given hashed address in the
+    /// `HashedPostState` on top of the current state, ordered by their hashed slot and starting at
+    /// the given hashed slot.
+    fn hashed_storage_range(
+        &self,
+        hashed_state: &HashedPostState,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> ProviderResult<HashedRange<U256>>;
+}
@@ -0,0 +1,567 @@
+//! Storage for MEV bundles.
+//!
+//! Bundles are submitted out of band (e.g. via `mev_sendBundle`) and target a range of blocks.
+//! They are kept in the [`BundlePool`] until they are included or expire and are offered to the
+//! payload builder via the [`BundleProvider`] trait whenever a block within their inclusion range
+//! is built.
+
+use futures_util::{Stream, StreamExt};
+use parking_lot::RwLock;
+use reth_primitives::{keccak256, Address, TransactionSignedEcRecovered, TxHash, B256};
+use reth_provider::CanonStateNotification;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fmt,
+    ops::RangeInclusive,
+    sync::Arc,
+};
+use tracing::trace;
+
+/// The maximum number of blocks a bundle's inclusion range may span.
+pub const MAX_BUNDLE_BLOCK_RANGE: u64 = 30;
+
+/// The default maximum number of bundles kept in the [`BundlePool`].
+pub const DEFAULT_MAX_BUNDLES: usize = 10_000;
+
+/// A transaction that is part of a [`MevBundle`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BundleTransaction {
+    /// The signed transaction with its recovered signer.
+    pub transaction: TransactionSignedEcRecovered,
+    /// Whether the transaction is allowed to revert without invalidating the bundle.
+    pub can_revert: bool,
+}
+
+/// A refund requirement of a [`MevBundle`].
+///
+/// `percent` of the bundle's refundable value must be paid to the refund recipients of the
+/// transaction at `body_idx`, see [`MevBundle::refund_recipients`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BundleRefund {
+    /// The index of the transaction in the bundle.
+    pub body_idx: usize,
+    /// The percent of the refundable value that must be paid.
+    pub percent: u64,
+}
+
+/// A recipient of the refunds of a [`MevBundle`] and its share of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BundleRefundConfig {
+    /// The address the refund is paid to.
+    pub address: Address,
+    /// The percent of the refund the address receives.
+    pub percent: u64,
+}
+
+/// A fully matched bundle of transactions that must be included atomically and in order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MevBundle {
+    /// The hash of the bundle.
+    hash: B256,
+    /// The first block the bundle is valid for.
+    block: u64,
+    /// The last block the bundle is valid for.
+    max_block: u64,
+    /// The transactions of the bundle.
+    transactions: Vec<BundleTransaction>,
+    /// The refund requirements of the bundle.
+    refunds: Vec<BundleRefund>,
+    /// The recipients of the refunds, if they are not paid to the transaction signers.
+    refund_config: Vec<BundleRefundConfig>,
+}
+
+// === impl MevBundle ===
+
+impl MevBundle {
+    /// Creates a new bundle that is valid for blocks `block..=max_block`.
+    pub fn new(
+        block: u64,
+        max_block: u64,
+        transactions: Vec<BundleTransaction>,
+        refunds: Vec<BundleRefund>,
+    ) -> Self {
+        let mut bundle = Self {
+            hash: B256::ZERO,
+            block,
+            max_block,
+            transactions,
+            refunds,
+            refund_config: Vec::new(),
+        };
+        bundle.hash = bundle.compute_hash();
+        bundle
+    }
+
+    /// Sets the recipients of the refunds of the bundle.
+    pub fn with_refund_config(mut self, refund_config: Vec<BundleRefundConfig>) -> Self {
+        self.refund_config = refund_config;
+        self.hash = self.compute_hash();
+        self
+    }
+
+    /// Computes the hash of the bundle.
+    ///
+    /// This is the keccak256 hash of the inclusion range, the transaction hashes and the refund
+    /// rules, so that the same transactions submitted for different blocks or with different
+    /// refunds are different bundles.
+    fn compute_hash(&self) -> B256 {
+        let mut hash_bytes = Vec::with_capacity(
+            16 + 32 * self.transactions.len() +
+                16 * self.refunds.len() +
+                28 * self.refund_config.len(),
+        );
+        hash_bytes.extend_from_slice(&self.block.to_be_bytes());
+        hash_bytes.extend_from_slice(&self.max_block.to_be_bytes());
+        for tx in &self.transactions {
+            hash_bytes.extend_from_slice(tx.transaction.hash().as_slice());
+        }
+        for refund in &self.refunds {
+            hash_bytes.extend_from_slice(&(refund.body_idx as u64).to_be_bytes());
+            hash_bytes.extend_from_slice(&refund.percent.to_be_bytes());
+        }
+        for config in &self.refund_config {
+            hash_bytes.extend_from_slice(config.address.as_slice());
+            hash_bytes.extend_from_slice(&config.percent.to_be_bytes());
+        }
+        keccak256(&hash_bytes)
+    }
+
+    /// Returns the hash of the bundle.
+    pub const fn hash(&self) -> B256 {
+        self.hash
+    }
+
+    /// Returns the range of blocks the bundle is valid for.
+    pub const fn block_range(&self) -> RangeInclusive<u64> {
+        self.block..=self.max_block
+    }
+
+    /// Returns true if the bundle can be included in the given block.
+    pub const fn is_valid_for_block(&self, block_number: u64) -> bool {
+        self.block <= block_number && block_number <= self.max_block
+    }
+
+    /// Returns the transactions of the bundle.
+    pub fn transactions(&self) -> &[BundleTransaction] {
+        &self.transactions
+    }
+
+    /// Returns the refund requirements of the bundle.
+    pub fn refunds(&self) -> &[BundleRefund] {
+        &self.refunds
+    }
+
+    /// Returns the recipients of the refunds of the bundle.
+    pub fn refund_config(&self) -> &[BundleRefundConfig] {
+        &self.refund_config
+    }
+
+    /// Returns true if including the bundle requires paying refunds.
+    pub fn requires_refund(&self) -> bool {
+        !self.refunds.is_empty()
+    }
+
+    /// Returns the recipients of the given refund and their share of it.
+    ///
+    /// The refund is split according to the refund config of the bundle, or paid to the signer of
+    /// the refunded transaction in full if the bundle has no refund config.
+    pub fn refund_recipients(&self, refund: &BundleRefund) -> Vec<BundleRefundConfig> {
+        if !self.refund_config.is_empty() {
+            return self.refund_config.clone()
+        }
+        self.transactions
+            .get(refund.body_idx)
+            .map(|tx| BundleRefundConfig { address: tx.transaction.signer(), percent: 100 })
+            .into_iter()
+            .collect()
+    }
+
+    /// Returns true if any of the given transactions is part of the bundle.
+    fn contains_any(&self, hashes: &HashSet<TxHash>) -> bool {
+        self.transactions.iter().any(|tx| hashes.contains(&tx.transaction.hash()))
+    }
+
+    /// Returns the sum of the gas limits of all transactions in the bundle.
+    pub fn gas_limit(&self) -> u64 {
+        self.transactions.iter().map(|tx| tx.transaction.gas_limit()).sum()
+    }
+}
+
+/// Provides the bundles that are eligible for inclusion in a block.
+///
+/// This is the hook the payload builder uses to pull bundles.
+#[auto_impl::auto_impl(&, Arc)]
+pub trait BundleProvider: fmt::Debug + Send + Sync + 'static {
+    /// Returns all bundles that can be included in the block with the given number.
+    fn bundles_for_block(&self, block_number: u64) -> Vec<Arc<MevBundle>>;
+}
+
+/// A [`BundleProvider`] that never returns any bundles.
+#[derive(Debug, Clone, Copy, Default)]
+#[non_exhaustive]
+pub struct NoopBundleProvider;
+
+impl BundleProvider for NoopBundleProvider {
+    fn bundles_for_block(&self, _block_number: u64) -> Vec<Arc<MevBundle>> {
+        Vec::new()
+    }
+}
+
+/// Configuration for the [`BundlePool`].
+#[derive(Debug, Clone, Copy)]
+pub struct BundlePoolConfig {
+    /// The maximum number of bundles the pool keeps.
+    pub max_bundles: usize,
+    /// The maximum number of blocks a bundle's inclusion range may span.
+    pub max_block_range: u64,
+}
+
+impl Default for BundlePoolConfig {
+    fn default() -> Self {
+        Self { max_bundles: DEFAULT_MAX_BUNDLES, max_block_range: MAX_BUNDLE_BLOCK_RANGE }
+    }
+}
+
+/// A pool of [`MevBundle`]s keyed by the range of blocks they target.
+///
+/// Bundles are removed once one of their transactions is included in a canonical block, or the
+/// chain has moved past the last block they are valid for, see [`maintain_bundle_pool`].
+///
+/// Note: this type is cheap to clone, all clones share the same bundles.
+#[derive(Debug, Clone, Default)]
+pub struct BundlePool {
+    /// The configuration of the pool.
+    config: BundlePoolConfig,
+    /// The bundles of the pool.
+    inner: Arc<RwLock<BundlePoolInner>>,
+}
+
+// === impl BundlePool ===
+
+impl BundlePool {
+    /// Creates a new, empty pool with the given config.
+    pub fn new(config: BundlePoolConfig) -> Self {
+        Self { config, inner: Default::default() }
+    }
+
+    /// Returns the config of the pool.
+    pub const fn config(&self) -> &BundlePoolConfig {
+        &self.config
+    }
+
+    /// Adds a bundle to the pool and returns its hash.
+    ///
+    /// Adding a bundle that is already in the pool is a no-op.
+    pub fn add_bundle(&self, bundle: MevBundle) -> Result<B256, BundlePoolError> {
+        if bundle.transactions.is_empty() {
+            return Err(BundlePoolError::EmptyBundle)
+        }
+        if bundle.max_block < bundle.block {
+            return Err(BundlePoolError::InvalidBlockRange {
+                block: bundle.block,
+                max_block: bundle.max_block,
+            })
+        }
+        if bundle.max_block - bundle.block >= self.config.max_block_range {
+            return Err(BundlePoolError::BlockRangeTooLarge(self.config.max_block_range))
+        }
+
+        let hash = bundle.hash;
+        let mut inner = self.inner.write();
+        if inner.by_hash.contains_key(&hash) {
+            return Ok(hash)
+        }
+        if inner.by_hash.len() >= self.config.max_bundles {
+            return Err(BundlePoolError::PoolFull(self.config.max_bundles))
+        }
+        inner.by_max_block.entry(bundle.max_block).or_default().insert(hash);
+        inner.by_hash.insert(hash, Arc::new(bundle));
+        Ok(hash)
+    }
+
+    /// Returns the bundle with the given hash, if it is in the pool.
+    pub fn get(&self, hash: &B256) -> Option<Arc<MevBundle>> {
+        self.inner.read().by_hash.get(hash).cloned()
+    }
+
+    /// Removes the bundle with the given hash from the pool.
+    pub fn remove_bundle(&self, hash: &B256) -> Option<Arc<MevBundle>> {
+        self.inner.write().remove(hash)
+    }
+
+    /// Updates the pool with a new canonical block.
+    ///
+    /// Removes all bundles that contain one of the block's transactions, since they can't be
+    /// included again, and all bundles that are only valid for this or earlier blocks.
+    ///
+    /// Returns the number of removed bundles.
+    pub fn on_canonical_block(&self, block_number: u64, transactions: &HashSet<TxHash>) -> usize {
+        let mut removed = self.remove_expired(block_number + 1);
+
+        let mut inner = self.inner.write();
+        let included = inner
+            .by_hash
+            .values()
+            .filter(|bundle| bundle.contains_any(transactions))
+            .map(|bundle| bundle.hash)
+            .collect::<Vec<_>>();
+        for hash in included {
+            inner.remove(&hash);
+            removed += 1;
+        }
+        removed
+    }
+
+    /// Removes all bundles that can no longer be included in a block with the given number or
+    /// any later block.
+    ///
+    /// Returns the number of removed bundles.
+    pub fn remove_expired(&self, block_number: u64) -> usize {
+        let mut inner = self.inner.write();
+        let retained = inner.by_max_block.split_off(&block_number);
+        let expired = std::mem::replace(&mut inner.by_max_block, retained);
+        let mut removed = 0;
+        for hash in expired.into_values().flatten() {
+            inner.by_hash.remove(&hash);
+            removed += 1;
+        }
+        removed
+    }
+
+    /// Returns the number of bundles in the pool.
+    pub fn len(&self) -> usize {
+        self.inner.read().by_hash.len()
+    }
+
+    /// Returns true if the pool is empty.
+    pub fn is_empty(&self) -> bool {
+        self.inner.read().by_hash.is_empty()
+    }
+}
+
+impl BundleProvider for BundlePool {
+    fn bundles_for_block(&self, block_number: u64) -> Vec<Arc<MevBundle>> {
+        // bundles that target older blocks can never be included again
+        self.remove_expired(block_number);
+
+        let inner = self.inner.read();
+        inner
+            .by_max_block
+            .values()
+            .flatten()
+            .filter_map(|hash| inner.by_hash.get(hash))
+            .filter(|bundle| bundle.is_valid_for_block(block_number))
+            .cloned()
+            .collect()
+    }
+}
+
+/// The bundles of the [`BundlePool`].
+#[derive(Debug, Default)]
+struct BundlePoolInner {
+    /// All bundles by their hash.
+    by_hash: HashMap<B256, Arc<MevBundle>>,
+    /// The hashes of all bundles by the last block they are valid for.
+    by_max_block: BTreeMap<u64, HashSet<B256>>,
+}
+
+impl BundlePoolInner {
+    /// Removes the bundle with the given hash.
+    fn remove(&mut self, hash: &B256) -> Option<Arc<MevBundle>> {
+        let bundle = self.by_hash.remove(hash)?;
+        if let Some(hashes) = self.by_max_block.get_mut(&bundle.max_block) {
+            hashes.remove(hash);
+            if hashes.is_empty() {
+                self.by_max_block.remove(&bundle.max_block);
+            }
+        }
+        Some(bundle)
+    }
+}
+
+/// Keeps the [`BundlePool`] in sync with the canonical chain.
+///
+/// Every committed block removes the bundles that were included in it or expired, see
+/// [`BundlePool::on_canonical_block`].
+pub async fn maintain_bundle_pool<St>(pool: BundlePool, mut events: St)
+where
+    St: Stream<Item = CanonStateNotification> + Send + Unpin + 'static,
+{
+    while let Some(event) = events.next().await {
+        for block in event.committed().blocks_iter() {
+            let transactions = block.body.iter().map(|tx| tx.hash()).collect::<HashSet<_>>();
+            let removed = pool.on_canonical_block(block.number, &transactions);
+            if removed > 0 {
+                trace!(target: "txpool::bundle", block=block.number, removed, "removed included and expired bundles");
+            }
+        }
+    }
+}
+
+/// Error variants that can occur when adding a bundle to the [`BundlePool`].
+#[derive(Debug, thiserror::Error)]
+pub enum BundlePoolError {
+    /// Thrown if the bundle does not contain any transactions.
+    #[error("bundle contains no transactions")]
+    EmptyBundle,
+    /// Thrown if the last block of the bundle is before its first block.
+    #[error("invalid bundle block range {block}..={max_block}")]
+    InvalidBlockRange {
+        /// The first block the bundle is valid for.
+        block: u64,
+        /// The last block the bundle is valid for.
+        max_block: u64,
+    },
+    /// Thrown if the bundle targets more blocks than allowed.
+    #[error("bundle block range exceeds the limit of {0} blocks")]
+    BlockRangeTooLarge(u64),
+    /// Thrown if the pool reached its maximum number of bundles.
+    #[error("bundle pool is full, max {0} bundles")]
+    PoolFull(usize),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_primitives::{Address, Signature, Transaction, TransactionSigned, TxLegacy};
+
+    fn bundle(nonce: u64, block: u64, max_block: u64) -> MevBundle {
+        let tx = TransactionSigned::from_transaction_and_signature(
+            Transaction::Legacy(TxLegacy { nonce, ..Default::default() }),
+            Signature::default(),
+        );
+        let transaction =
+            TransactionSignedEcRecovered::from_signed_transaction(tx, Address::random());
+        MevBundle::new(
+            block,
+            max_block,
+            vec![BundleTransaction { transaction, can_revert: false }],
+            vec![],
+        )
+    }
+
+    #[test]
+    fn bundles_for_block_range() {
+        let pool = BundlePool::default();
+        let first = pool.add_bundle(bundle(0, 10, 12)).unwrap();
+        let second = pool.add_bundle(bundle(1, 12, 15)).unwrap();
+        assert_eq!(pool.len(), 2);
+
+        let hashes = |number| {
+            let mut hashes =
+                pool.bundles_for_block(number).iter().map(|b| b.hash()).collect::<Vec<_>>();
+            hashes.sort();
+            hashes
+        };
+        assert!(hashes(9).is_empty());
+        assert_eq!(hashes(10), vec![first]);
+        let mut both = vec![first, second];
+        both.sort();
+        assert_eq!(hashes(12), both);
+        assert_eq!(hashes(13), vec![second]);
+
+        // the first bundle expired
+        assert_eq!(pool.len(), 1);
+        assert!(pool.get(&first).is_none());
+    }
+
+    #[test]
+    fn remove_expired() {
+        let pool = BundlePool::default();
+        pool.add_bundle(bundle(0, 1, 1)).unwrap();
+        pool.add_bundle(bundle(1, 1, 2)).unwrap();
+        let last = pool.add_bundle(bundle(2, 1, 3)).unwrap();
+
+        assert_eq!(pool.remove_expired(3), 2);
+        assert_eq!(pool.len(), 1);
+        assert!(pool.remove_bundle(&last).is_some());
+        assert!(pool.is_empty());
+    }
+
+    #[test]
+    fn hash_includes_block_range_and_refunds() {
+        let first = bundle(0, 1, 1);
+        let later = MevBundle::new(2, 2, first.transactions().to_vec(), vec![]);
+        assert_ne!(first.hash(), later.hash());
+
+        let refund = MevBundle::new(
+            1,
+            1,
+            first.transactions().to_vec(),
+            vec![BundleRefund { body_idx: 0, percent: 10 }],
+        );
+        assert_ne!(first.hash(), refund.hash());
+        let with_config = refund.clone().with_refund_config(vec![BundleRefundConfig {
+            address: Address::with_last_byte(1),
+            percent: 100,
+        }]);
+        assert_ne!(refund.hash(), with_config.hash());
+
+        let pool = BundlePool::default();
+        pool.add_bundle(first).unwrap();
+        pool.add_bundle(later).unwrap();
+        assert_eq!(pool.len(), 2);
+    }
+
+    #[test]
+    fn refund_recipients() {
+        let bundle = MevBundle::new(
+            1,
+            1,
+            bundle(0, 1, 1).transactions().to_vec(),
+            vec![BundleRefund { body_idx: 0, percent: 10 }],
+        );
+        let refund = bundle.refunds()[0];
+        assert_eq!(
+            bundle.refund_recipients(&refund),
+            vec![BundleRefundConfig {
+                address: bundle.transactions()[0].transaction.signer(),
+                percent: 100
+            }]
+        );
+
+        let config = vec![
+            BundleRefundConfig { address: Address::with_last_byte(1), percent: 60 },
+            BundleRefundConfig { address: Address::with_last_byte(2), percent: 40 },
+        ];
+        let bundle = bundle.with_refund_config(config.clone());
+        assert_eq!(bundle.refund_recipients(&refund), config);
+    }
+
+    #[test]
+    fn remove_included_bundles() {
+        let pool = BundlePool::default();
+        let included = bundle(0, 1, 3);
+        let tx_hash = included.transactions()[0].transaction.hash();
+        let included = pool.add_bundle(included).unwrap();
+        let expired = pool.add_bundle(bundle(1, 1, 1)).unwrap();
+        let pending = pool.add_bundle(bundle(2, 1, 3)).unwrap();
+
+        assert_eq!(pool.on_canonical_block(1, &HashSet::from([tx_hash])), 2);
+        assert!(pool.get(&included).is_none());
+        assert!(pool.get(&expired).is_none());
+        assert!(pool.get(&pending).is_some());
+    }
+
+    #[test]
+    fn reject_invalid_bundles() {
+        let pool = BundlePool::new(BundlePoolConfig { max_bundles: 1, max_block_range: 5 });
+        assert!(matches!(
+            pool.add_bundle(MevBundle::new(1, 1, vec![], vec![])),
+            Err(BundlePoolError::EmptyBundle)
+        ));
+        assert!(matches!(
+            pool.add_bundle(bundle(0, 2, 1)),
+            Err(BundlePoolError::InvalidBlockRange { block: 2, max_block: 1 })
+        ));
+        assert!(matches!(
+            pool.add_bundle(bundle(0, 1, 6)),
+            Err(BundlePoolError::BlockRangeTooLarge(5))
+        ));
+
+        let hash = pool.add_bundle(bundle(0, 1, 5)).unwrap();
+        // re-adding the same bundle is a no-op
+        assert_eq!(pool.add_bundle(bundle(0, 1, 5)).unwrap(), hash);
+        assert!(matches!(pool.add_bundle(bundle(1, 1, 5)), Err(BundlePoolError::PoolFull(1))));
+    }
+}
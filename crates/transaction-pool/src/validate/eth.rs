@@ -5,7 +5,7 @@ use crate::{
     blobstore::BlobStore,
     error::{Eip4844PoolTransactionError, InvalidPoolTransactionError},
     traits::TransactionOrigin,
-    validate::{ValidTransaction, ValidationTask, MAX_INIT_CODE_BYTE_SIZE},
+    validate::{ImpersonatedAccounts, ValidTransaction, ValidationTask, MAX_INIT_CODE_BYTE_SIZE},
     EthBlobTransactionSidecar, EthPoolTransaction, LocalTransactionConfig, PoolTransaction,
     TransactionValidationOutcome, TransactionValidationTaskExecutor, TransactionValidator,
 };
@@ -131,6 +131,8 @@ pub(crate) struct EthTransactionValidatorInner<Client, T> {
     local_transactions_config: LocalTransactionConfig,
     /// Maximum size in bytes a single transaction can have in order to be accepted into the pool.
     max_tx_input_bytes: usize,
+    /// Accounts that skip the signer checks.
+    impersonated_accounts: ImpersonatedAccounts,
     /// Marker for the transaction type
     _marker: PhantomData<T>,
 }
@@ -327,8 +329,10 @@ where
         };
 
         // Signer account shouldn't have bytecode. Presence of bytecode means this is a
-        // smartcontract.
-        if account.has_bytecode() {
+        // smartcontract, unless the account is impersonated.
+        if account.has_bytecode() &&
+            !self.impersonated_accounts.is_impersonated(&transaction.sender())
+        {
             return TransactionValidationOutcome::Invalid(
                 transaction,
                 InvalidTransactionError::SignerAccountHasBytecode.into(),
@@ -466,6 +470,8 @@ pub struct EthTransactionValidatorBuilder {
     local_transactions_config: LocalTransactionConfig,
     /// Max size in bytes of a single transaction allowed
     max_tx_input_bytes: usize,
+    /// Accounts that skip the signer checks
+    impersonated_accounts: ImpersonatedAccounts,
 }
 
 impl EthTransactionValidatorBuilder {
@@ -486,6 +492,7 @@ impl EthTransactionValidatorBuilder {
             kzg_settings: EnvKzgSettings::Default,
             local_transactions_config: Default::default(),
             max_tx_input_bytes: DEFAULT_MAX_TX_INPUT_BYTES,
+            impersonated_accounts: Default::default(),
 
             // by default all transaction types are allowed
             eip2718: true,
@@ -612,6 +619,17 @@ impl EthTransactionValidatorBuilder {
         self
     }
 
+    /// Sets the [`ImpersonatedAccounts`] whose transactions skip the signer checks.
+    ///
+    /// This is intended for development chains only.
+    pub fn with_impersonated_accounts(
+        mut self,
+        impersonated_accounts: ImpersonatedAccounts,
+    ) -> Self {
+        self.impersonated_accounts = impersonated_accounts;
+        self
+    }
+
     /// Sets the block gas limit
     ///
     /// Transactions with a gas limit greater than this will be rejected.
@@ -643,6 +661,7 @@ impl EthTransactionValidatorBuilder {
             kzg_settings,
             local_transactions_config,
             max_tx_input_bytes,
+            impersonated_accounts,
             ..
         } = self;
 
@@ -666,6 +685,7 @@ impl EthTransactionValidatorBuilder {
             kzg_settings,
             local_transactions_config,
             max_tx_input_bytes,
+            impersonated_accounts,
             _marker: Default::default(),
         };
 
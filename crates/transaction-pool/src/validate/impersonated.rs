@@ -0,0 +1,78 @@
+//! Accounts that are impersonated on development chains.
+
+use parking_lot::RwLock;
+use reth_primitives::Address;
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// A shared set of accounts whose transactions skip the signer checks of the pool validation.
+///
+/// This is intended for development chains, where cheatcodes like `anvil_impersonateAccount`
+/// allow sending transactions on behalf of any account, including contracts.
+#[derive(Debug, Clone, Default)]
+pub struct ImpersonatedAccounts {
+    inner: Arc<ImpersonatedAccountsInner>,
+}
+
+#[derive(Debug, Default)]
+struct ImpersonatedAccountsInner {
+    /// The accounts that are impersonated.
+    accounts: RwLock<HashSet<Address>>,
+    /// Whether all accounts are impersonated.
+    impersonate_all: AtomicBool,
+}
+
+impl ImpersonatedAccounts {
+    /// Starts impersonating the given account.
+    ///
+    /// Returns `true` if the account was not impersonated before.
+    pub fn impersonate(&self, address: Address) -> bool {
+        self.inner.accounts.write().insert(address)
+    }
+
+    /// Stops impersonating the given account.
+    ///
+    /// Returns `true` if the account was impersonated.
+    pub fn stop_impersonating(&self, address: &Address) -> bool {
+        self.inner.accounts.write().remove(address)
+    }
+
+    /// Enables or disables the impersonation of all accounts.
+    pub fn set_impersonate_all(&self, enabled: bool) {
+        self.inner.impersonate_all.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the given account is impersonated.
+    pub fn is_impersonated(&self, address: &Address) -> bool {
+        self.inner.impersonate_all.load(Ordering::Relaxed) ||
+            self.inner.accounts.read().contains(address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn impersonate_accounts() {
+        let accounts = ImpersonatedAccounts::default();
+        let shared = accounts.clone();
+        let address = Address::random();
+        assert!(!accounts.is_impersonated(&address));
+
+        assert!(accounts.impersonate(address));
+        assert!(!accounts.impersonate(address));
+        assert!(shared.is_impersonated(&address));
+
+        assert!(accounts.stop_impersonating(&address));
+        assert!(!accounts.is_impersonated(&address));
+
+        accounts.set_impersonate_all(true);
+        assert!(accounts.is_impersonated(&Address::random()));
+    }
+}
@@ -13,11 +13,15 @@ use std::{fmt, future::Future, time::Instant};
 
 mod constants;
 mod eth;
+mod impersonated;
 mod task;
 
 /// A `TransactionValidator` implementation that validates ethereum transaction.
 pub use eth::*;
 
+/// Accounts that skip the signer checks on development chains.
+pub use impersonated::ImpersonatedAccounts;
+
 /// A spawnable task that performs transaction validation.
 pub use task::{TransactionValidationTaskExecutor, ValidationTask};
 
@@ -2,12 +2,14 @@
 
 use crate::{Nibbles, TrieAccount};
 use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
-use alloy_rlp::encode_fixed_size;
+use alloy_rlp::{encode_fixed_size, Decodable};
 use alloy_trie::{
+    nodes::{word_rlp, TrieNode},
     proof::{verify_proof, ProofVerificationError},
-    EMPTY_ROOT_HASH,
+    HashBuilder, EMPTY_ROOT_HASH,
 };
 use reth_primitives_traits::Account;
+use std::collections::HashMap;
 
 /// The merkle proof with the relevant account info.
 #[derive(PartialEq, Eq, Debug)]
@@ -124,6 +126,144 @@ impl StorageProof {
     }
 }
 
+/// Verifies that the leaves are all the leaves of the trie with the given root from the origin up
+/// to the last leaf, or up to the end of the trie if there are no leaves.
+///
+/// The proof consists of the nodes on the paths to the origin and to the last leaf. Without a
+/// proof, the leaves have to be all the leaves of the trie. The leaves have to be sorted by their
+/// keys, starting at the origin.
+pub fn verify_range_proof<V: AsRef<[u8]>>(
+    root: B256,
+    origin: B256,
+    leaves: &[(B256, V)],
+    proof: &[Bytes],
+) -> Result<(), ProofVerificationError> {
+    let mut hash_builder = HashBuilder::default();
+    if proof.is_empty() {
+        for (key, value) in leaves {
+            hash_builder.add_leaf(Nibbles::unpack(key), value.as_ref());
+        }
+    } else {
+        let last = leaves.last().map_or(B256::repeat_byte(0xff), |(key, _)| *key);
+        let mut range = RangeProof {
+            nodes: proof.iter().map(|node| (keccak256(node), node)).collect(),
+            first: Nibbles::unpack(origin),
+            last: Nibbles::unpack(last),
+            left: Vec::new(),
+            right: Vec::new(),
+        };
+        range.visit(Nibbles::default(), &word_rlp(&root))?;
+
+        // The parts of the trie outside of the range are known from the proof
+        let leaves = leaves.iter().map(|(key, value)| {
+            (Nibbles::unpack(key), RangeProofElement::Leaf(value.as_ref().to_vec()))
+        });
+        for (path, element) in range.left.into_iter().chain(leaves).chain(range.right) {
+            match element {
+                RangeProofElement::Branch(hash) => hash_builder.add_branch(path, hash, false),
+                RangeProofElement::Leaf(value) => hash_builder.add_leaf(path, &value),
+            }
+        }
+    }
+
+    let got = hash_builder.root();
+    if got != root {
+        return Err(ProofVerificationError::RootMismatch { got, expected: root })
+    }
+    Ok(())
+}
+
+/// A part of the trie outside of the proven range.
+#[derive(Debug)]
+enum RangeProofElement {
+    /// A subtrie referenced by its hash.
+    Branch(B256),
+    /// A leaf with its value.
+    Leaf(Vec<u8>),
+}
+
+/// Collects the parts of the trie outside of a proven range from the nodes of the proof.
+#[derive(Debug)]
+struct RangeProof<'a> {
+    /// The nodes of the proof, by their hash.
+    nodes: HashMap<B256, &'a Bytes>,
+    /// The first key of the range.
+    first: Nibbles,
+    /// The last key of the range.
+    last: Nibbles,
+    /// The parts of the trie before the range, in order.
+    left: Vec<(Nibbles, RangeProofElement)>,
+    /// The parts of the trie after the range, in order.
+    right: Vec<(Nibbles, RangeProofElement)>,
+}
+
+impl RangeProof<'_> {
+    /// Visits the node at the path, which is either referenced by its hash, or embedded.
+    fn visit(&mut self, path: Nibbles, child: &[u8]) -> Result<(), ProofVerificationError> {
+        let len = path.len().min(self.first.len());
+        let before = path < self.first.slice(..len);
+        let after = path > self.last.slice(..len.min(self.last.len()));
+        // The leaves in the range are provided separately
+        if !before && !after && !self.first.has_prefix(&path) && !self.last.has_prefix(&path) {
+            return Ok(())
+        }
+
+        // A hash is encoded as a 32 byte string
+        let node = if child.len() == B256::len_bytes() + 1 {
+            let hash = B256::from_slice(&child[1..]);
+            if before || after {
+                let elements = if before { &mut self.left } else { &mut self.right };
+                elements.push((path, RangeProofElement::Branch(hash)));
+                return Ok(())
+            }
+
+            // The nodes on the paths to the first and the last key have to be proven
+            let Some(node) = self.nodes.get(&hash) else {
+                return Err(ProofVerificationError::ValueMismatch {
+                    path,
+                    got: None,
+                    expected: Some(Bytes::copy_from_slice(hash.as_slice())),
+                })
+            };
+            TrieNode::decode(&mut &node[..])?
+        } else {
+            // Nodes shorter than a hash are embedded in their parent
+            TrieNode::decode(&mut &child[..])?
+        };
+
+        match node {
+            TrieNode::Branch(branch) => {
+                let mut stack = branch.stack.iter();
+                for nibble in 0..16 {
+                    if branch.state_mask.is_bit_set(nibble) {
+                        let Some(child) = stack.next() else {
+                            return Err(alloy_rlp::Error::Custom("missing branch child").into())
+                        };
+                        let mut child_path = path.clone();
+                        child_path.push(nibble);
+                        self.visit(child_path, child)?;
+                    }
+                }
+            }
+            TrieNode::Extension(extension) => {
+                let mut child_path = path;
+                child_path.extend_from_slice(&extension.key);
+                self.visit(child_path, &extension.child)?;
+            }
+            TrieNode::Leaf(leaf) => {
+                let mut key = path;
+                key.extend_from_slice(&leaf.key);
+                if key < self.first {
+                    self.left.push((key, RangeProofElement::Leaf(leaf.value)));
+                } else if key > self.last {
+                    self.right.push((key, RangeProofElement::Leaf(leaf.value)));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 /// Implementation of hasher using our keccak256 hashing function
 /// for compatibility with `triehash` crate.
 #[cfg(any(test, feature = "test-utils"))]
@@ -284,3 +284,35 @@ fn holesky_deposit_contract_proof() {
     similar_asserts::assert_eq!(account_proof, expected);
     assert_eq!(account_proof.verify(root), Ok(()));
 }
+
+#[test]
+fn holesky_deposit_contract_trie_nodes() {
+    // Create test database and insert genesis accounts.
+    let factory = create_test_provider_factory();
+    let root = insert_genesis(&factory, HOLESKY.clone()).unwrap();
+
+    let target = Address::from_str("0x4242424242424242424242424242424242424242").unwrap();
+    let hashed_target = keccak256(target);
+    let slot = B256::with_last_byte(0x22);
+
+    let provider = factory.provider().unwrap();
+    let proof = Proof::from_tx(provider.tx_ref());
+    let account_proof = proof.account_proof(target, &[slot]).unwrap();
+
+    // The root node hashes to the state root.
+    let nodes = proof.account_trie_nodes(vec![Nibbles::default()]).unwrap();
+    assert_eq!(nodes.keys().collect::<Vec<_>>(), vec![&Nibbles::default()]);
+    assert_eq!(keccak256(&nodes[&Nibbles::default()]), root);
+
+    // The nodes on the path to an account are its proof.
+    let nodes = proof.account_trie_nodes(vec![Nibbles::unpack(hashed_target)]).unwrap();
+    assert_eq!(nodes.into_values().collect::<Vec<_>>(), account_proof.proof);
+
+    let nodes =
+        proof.storage_trie_nodes(hashed_target, vec![Nibbles::unpack(keccak256(slot))]).unwrap();
+    assert_eq!(nodes.into_values().collect::<Vec<_>>(), account_proof.storage_proofs[0].proof);
+    assert!(proof
+        .storage_trie_nodes(keccak256(Address::ZERO), vec![Nibbles::default()])
+        .unwrap()
+        .is_empty());
+}
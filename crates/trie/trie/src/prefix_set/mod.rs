@@ -9,7 +9,7 @@ mod loader;
 pub use loader::PrefixSetLoader;
 
 /// Collection of mutable prefix sets.
-#[derive(Default, Debug)]
+#[derive(Clone, Default, Debug)]
 pub struct TriePrefixSetsMut {
     /// A set of account prefixes that have changed.
     pub account_prefix_set: PrefixSetMut,
@@ -1,5 +1,8 @@
 use crate::{
-    hashed_cursor::{DatabaseHashedCursorFactory, HashedPostStateCursorFactory},
+    hashed_cursor::{
+        DatabaseHashedCursorFactory, HashedCursor, HashedCursorFactory,
+        HashedPostStateCursorFactory,
+    },
     prefix_set::{PrefixSetMut, TriePrefixSetsMut},
     proof::Proof,
     Nibbles,
@@ -13,7 +16,7 @@ use reth_db_api::{
     transaction::DbTx,
 };
 use reth_execution_errors::StateRootError;
-use reth_primitives::{keccak256, Account, Address, BlockNumber, B256, U256};
+use reth_primitives::{keccak256, Account, Address, BlockNumber, StorageEntry, B256, U256};
 use reth_trie_common::AccountProof;
 use revm::db::BundleAccount;
 use std::collections::{hash_map, HashMap, HashSet};
@@ -61,11 +64,29 @@ impl HashedPostState {
     /// Initializes [`HashedPostState`] from reverts. Iterates over state reverts from the specified
     /// block up to the current tip and aggregates them into hashed state in reverse.
     pub fn from_reverts<TX: DbTx>(tx: &TX, from: BlockNumber) -> Result<Self, DatabaseError> {
+        let mut account_changesets_cursor = tx.cursor_read::<tables::AccountChangeSets>()?;
+        let mut storage_changesets_cursor = tx.cursor_read::<tables::StorageChangeSets>()?;
+        Self::from_changesets(
+            account_changesets_cursor
+                .walk_range(from..)?
+                .map(|entry| entry.map(|(_, account)| account)),
+            storage_changesets_cursor.walk_range(BlockNumberAddress((from, Address::ZERO))..)?.map(
+                |entry| entry.map(|(BlockNumberAddress((_, address)), storage)| (address, storage)),
+            ),
+        )
+    }
+
+    /// Initializes [`HashedPostState`] from account and storage changesets ordered by block
+    /// number, recording the values before the first occurring change of every account and storage
+    /// slot.
+    pub fn from_changesets<E>(
+        account_changesets: impl IntoIterator<Item = Result<AccountBeforeTx, E>>,
+        storage_changesets: impl IntoIterator<Item = Result<(Address, StorageEntry), E>>,
+    ) -> Result<Self, E> {
         // Iterate over account changesets and record value before first occurring account change.
         let mut accounts = HashMap::<Address, Option<Account>>::default();
-        let mut account_changesets_cursor = tx.cursor_read::<tables::AccountChangeSets>()?;
-        for entry in account_changesets_cursor.walk_range(from..)? {
-            let (_, AccountBeforeTx { address, info }) = entry?;
+        for entry in account_changesets {
+            let AccountBeforeTx { address, info } = entry?;
             if let hash_map::Entry::Vacant(entry) = accounts.entry(address) {
                 entry.insert(info);
             }
@@ -73,11 +94,8 @@ impl HashedPostState {
 
         // Iterate over storage changesets and record value before first occurring storage change.
         let mut storages = HashMap::<Address, HashMap<B256, U256>>::default();
-        let mut storage_changesets_cursor = tx.cursor_read::<tables::StorageChangeSets>()?;
-        for entry in
-            storage_changesets_cursor.walk_range(BlockNumberAddress((from, Address::ZERO))..)?
-        {
-            let (BlockNumberAddress((_, address)), storage) = entry?;
+        for entry in storage_changesets {
+            let (address, storage) = entry?;
             let account_storage = storages.entry(address).or_default();
             if let hash_map::Entry::Vacant(entry) = account_storage.entry(storage.key) {
                 entry.insert(storage.value);
@@ -93,8 +111,8 @@ impl HashedPostState {
                 keccak256(address),
                 HashedStorage::from_iter(
                     // The `wiped` flag indicates only whether previous storage entries
-                    // should be looked up in db or not. For reverts it's a noop since all
-                    // wiped changes had been written as storage reverts.
+                    // should be looked up in db or not. For reverts it's a noop since all
+                    // wiped changes had been written as storage reverts.
                     false,
                     storage.into_iter().map(|(slot, value)| (keccak256(slot), value)),
                 ),
@@ -210,6 +228,64 @@ impl HashedPostState {
             .with_prefix_sets_mut(prefix_sets)
             .account_proof(address, slots)
     }
+
+    /// Returns up to `limit` accounts of the state on top of this [`HashedPostState`], starting at
+    /// the given hashed address.
+    pub fn account_range<TX: DbTx>(
+        &self,
+        tx: &TX,
+        start: B256,
+        limit: usize,
+    ) -> Result<HashedRange<Account>, DatabaseError> {
+        self.clone().into_sorted().account_range(tx, start, limit)
+    }
+
+    /// Returns up to `limit` storage slots of the given account on top of this
+    /// [`HashedPostState`], starting at the given hashed slot.
+    pub fn storage_range<TX: DbTx>(
+        &self,
+        tx: &TX,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> Result<HashedRange<U256>, DatabaseError> {
+        self.clone().into_sorted().storage_range(tx, hashed_address, start, limit)
+    }
+}
+
+/// A page of hashed state entries, ordered by their hashed keys.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct HashedRange<T> {
+    /// The hashed keys and values of the entries in this page.
+    pub entries: Vec<(B256, T)>,
+    /// The hashed key of the first entry of the next page, `None` if this is the last page.
+    pub next_key: Option<B256>,
+}
+
+impl<T> Default for HashedRange<T> {
+    fn default() -> Self {
+        Self { entries: Vec::new(), next_key: None }
+    }
+}
+
+impl<T: std::fmt::Debug> HashedRange<T> {
+    /// Collects up to `limit` entries of the cursor, starting at the given hashed key.
+    pub fn from_cursor<C: HashedCursor<Value = T>>(
+        mut cursor: C,
+        start: B256,
+        limit: usize,
+    ) -> Result<Self, DatabaseError> {
+        let mut entries = Vec::new();
+        let mut entry = cursor.seek(start)?;
+        while let Some((key, value)) = entry {
+            if entries.len() == limit {
+                return Ok(Self { entries, next_key: Some(key) })
+            }
+            entries.push((key, value));
+            entry = cursor.next()?;
+        }
+        Ok(Self { entries, next_key: None })
+    }
 }
 
 /// Representation of in-memory hashed storage.
@@ -280,6 +356,37 @@ impl HashedPostStateSorted {
     pub const fn account_storages(&self) -> &HashMap<B256, HashedStorageSorted> {
         &self.storages
     }
+
+    /// Returns up to `limit` accounts of the state on top of this [`HashedPostStateSorted`],
+    /// starting at the given hashed address.
+    pub fn account_range<TX: DbTx>(
+        &self,
+        tx: &TX,
+        start: B256,
+        limit: usize,
+    ) -> Result<HashedRange<Account>, DatabaseError> {
+        let hashed_cursor_factory =
+            HashedPostStateCursorFactory::new(DatabaseHashedCursorFactory::new(tx), self);
+        HashedRange::from_cursor(hashed_cursor_factory.hashed_account_cursor()?, start, limit)
+    }
+
+    /// Returns up to `limit` storage slots of the given account on top of this
+    /// [`HashedPostStateSorted`], starting at the given hashed slot.
+    pub fn storage_range<TX: DbTx>(
+        &self,
+        tx: &TX,
+        hashed_address: B256,
+        start: B256,
+        limit: usize,
+    ) -> Result<HashedRange<U256>, DatabaseError> {
+        let hashed_cursor_factory =
+            HashedPostStateCursorFactory::new(DatabaseHashedCursorFactory::new(tx), self);
+        HashedRange::from_cursor(
+            hashed_cursor_factory.hashed_storage_cursor(hashed_address)?,
+            start,
+            limit,
+        )
+    }
 }
 
 /// Sorted account state optimized for iterating during state trie calculation.
@@ -332,6 +439,8 @@ impl HashedStorageSorted {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use reth_db::test_utils::create_test_rw_db;
+    use reth_db_api::{database::Database, transaction::DbTxMut};
 
     #[test]
     fn hashed_state_wiped_extension() {
@@ -406,4 +515,40 @@ mod tests {
         );
         assert_eq!(account_storage.map(|st| st.wiped), Some(true));
     }
+
+    #[test]
+    fn hashed_range_pages_over_post_state() {
+        let db = create_test_rw_db();
+        db.update(|tx| {
+            for key in 1..=4 {
+                tx.put::<tables::HashedAccounts>(B256::with_last_byte(key), Account::default())
+                    .unwrap();
+            }
+        })
+        .unwrap();
+
+        // destroy an account of the database and add a new one
+        let hashed_state = HashedPostState::default().with_accounts([
+            (B256::with_last_byte(2), None),
+            (B256::with_last_byte(5), Some(Account { nonce: 1, ..Default::default() })),
+        ]);
+
+        let tx = db.tx().unwrap();
+        let page = hashed_state.account_range(&tx, B256::ZERO, 2).unwrap();
+        assert_eq!(
+            page.entries.iter().map(|(key, _)| *key).collect::<Vec<_>>(),
+            vec![B256::with_last_byte(1), B256::with_last_byte(3)]
+        );
+        assert_eq!(page.next_key, Some(B256::with_last_byte(4)));
+
+        let page = hashed_state.account_range(&tx, page.next_key.unwrap(), 2).unwrap();
+        assert_eq!(
+            page.entries,
+            vec![
+                (B256::with_last_byte(4), Account::default()),
+                (B256::with_last_byte(5), Account { nonce: 1, ..Default::default() })
+            ]
+        );
+        assert_eq!(page.next_key, None);
+    }
 }
@@ -284,7 +284,8 @@ where
         &self,
         args: BuildArguments<Pool, Client, Self::Attributes, Self::BuiltPayload>,
     ) -> Result<BuildOutcome<Self::BuiltPayload>, PayloadBuilderError> {
-        let BuildArguments { client, pool, cached_reads, config, cancel, best_payload } = args;
+        let BuildArguments { client, pool, cached_reads, config, cancel, best_payload, bundles } =
+            args;
         let PayloadConfig {
             initialized_block_env,
             initialized_cfg,
@@ -310,6 +311,7 @@ where
             },
             cancel,
             best_payload,
+            bundles,
         })
     }
 
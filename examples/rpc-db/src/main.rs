@@ -15,6 +15,7 @@
 use std::{path::Path, sync::Arc};
 
 use reth::{
+    beacon_consensus::EthBeaconConsensus,
     providers::{
         providers::{BlockchainProvider, StaticFileProvider},
         ProviderFactory,
@@ -33,7 +34,7 @@ use reth::rpc::builder::{
 // Configuring the network parts, ideally also wouldn't need to think about this.
 use myrpc_ext::{MyRpcExt, MyRpcExtApiServer};
 use reth::{blockchain_tree::noop::NoopBlockchainTree, tasks::TokioTaskExecutor};
-use reth_node_ethereum::EthEvmConfig;
+use reth_node_ethereum::{EthEvmConfig, EthExecutorProvider};
 use reth_provider::test_utils::TestCanonStateSubscriptions;
 
 // Custom rpc extension
@@ -67,7 +68,9 @@ async fn main() -> eyre::Result<()> {
         .with_noop_network()
         .with_executor(TokioTaskExecutor::default())
         .with_evm_config(EthEvmConfig::default())
-        .with_events(TestCanonStateSubscriptions::default());
+        .with_events(TestCanonStateSubscriptions::default())
+        .with_block_executor(EthExecutorProvider::ethereum(spec.clone()))
+        .with_consensus(Arc::new(EthBeaconConsensus::new(spec)));
 
     // Pick which namespaces to expose.
     let config = TransportRpcModuleConfig::default().with_http([RethRpcModule::Eth]);